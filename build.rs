@@ -0,0 +1,5 @@
+// Rebuild when a schema migration is added or edited, since `sqlx::migrate!`
+// embeds the `migrations` directory into the binary
+fn main() {
+    println!("cargo:rerun-if-changed=migrations");
+}
@@ -0,0 +1,292 @@
+-- Initial schema, the PostgreSQL version of migrations/sqlite.
+--
+-- Differences from SQLite:
+-- * Discord IDs and counters are BIGINT (SQLite's INT is already 64-bit).
+-- * Timestamps are TIMESTAMP(0) in UTC; the bot sets each connection's time
+--   zone to UTC, as SQLite's CURRENT_TIMESTAMP is.
+-- * Case-insensitive names use a nondeterministic ICU collation named
+--   `nocase`, standing in for SQLite's COLLATE NOCASE.  It needs a UTF-8
+--   database.
+
+CREATE COLLATION IF NOT EXISTS nocase (provider = icu, locale = 'und-u-ks-level2', deterministic = false);
+
+-- Create the shard_stats table for single-process deployments
+CREATE TABLE IF NOT EXISTS shard_stats (
+    shard_id BIGINT PRIMARY KEY,
+    server_count BIGINT,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    mem DOUBLE PRECISION
+);
+
+-- Create the process_stats table for multi-process tracking
+CREATE TABLE IF NOT EXISTS process_stats (
+    process_id TEXT PRIMARY KEY,
+    shard_start BIGINT,
+    shard_count BIGINT,
+    total_shards BIGINT,
+    server_count BIGINT,
+    memory_mb DOUBLE PRECISION,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the channel_tallies table for running roll totals
+CREATE TABLE IF NOT EXISTS channel_tallies (
+    channel_id BIGINT NOT NULL,
+    guild_id BIGINT,
+    name TEXT NOT NULL COLLATE nocase,
+    total BIGINT NOT NULL DEFAULT 0,
+    message_id BIGINT,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (channel_id, name)
+);
+
+-- Create the channel_clocks table for progress clocks
+CREATE TABLE IF NOT EXISTS channel_clocks (
+    channel_id BIGINT NOT NULL,
+    guild_id BIGINT,
+    name TEXT NOT NULL COLLATE nocase,
+    size BIGINT NOT NULL,
+    filled BIGINT NOT NULL DEFAULT 0,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (channel_id, name)
+);
+
+-- Create the initiative tables for /init
+CREATE TABLE IF NOT EXISTS initiative_entries (
+    channel_id BIGINT NOT NULL,
+    guild_id BIGINT,
+    name TEXT NOT NULL COLLATE nocase,
+    expression TEXT NOT NULL,
+    initiative BIGINT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (channel_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS initiative_trackers (
+    channel_id BIGINT PRIMARY KEY,
+    guild_id BIGINT,
+    round BIGINT NOT NULL DEFAULT 0,
+    current TEXT,
+    message_id BIGINT,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the data_erasure_log table for auditing data deletion requests
+CREATE TABLE IF NOT EXISTS data_erasure_log (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    scope TEXT NOT NULL,
+    target_id BIGINT NOT NULL,
+    requested_by BIGINT NOT NULL,
+    rows_deleted BIGINT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the roll_history table; what gets stored depends on the privacy level
+CREATE TABLE IF NOT EXISTS roll_history (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    guild_id BIGINT,
+    channel_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    expression TEXT,
+    result TEXT,
+    total BIGINT NOT NULL,
+    outcome TEXT,
+    round BIGINT,
+    turn TEXT,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_roll_history_user ON roll_history (user_id, timestamp);
+
+CREATE INDEX IF NOT EXISTS idx_roll_history_channel ON roll_history (channel_id, round);
+
+-- Create the privacy_settings table for per-guild and per-user history levels
+CREATE TABLE IF NOT EXISTS privacy_settings (
+    scope TEXT NOT NULL,
+    target_id BIGINT NOT NULL,
+    level TEXT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (scope, target_id)
+);
+
+-- Create the guild_config table for server settings
+CREATE TABLE IF NOT EXISTS guild_config (
+    guild_id BIGINT PRIMARY KEY,
+    default_system TEXT,
+    ephemeral_default BIGINT NOT NULL DEFAULT 0,
+    gm_role_id BIGINT,
+    restricted_channels TEXT,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the alias_usage table for anonymous alias counters
+CREATE TABLE IF NOT EXISTS alias_usage (
+    alias TEXT PRIMARY KEY,
+    uses BIGINT NOT NULL DEFAULT 0,
+    first_used TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    last_used TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the presence_messages table for the bot status rotation
+CREATE TABLE IF NOT EXISTS presence_messages (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    message TEXT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the guild_aliases table for /alias and macro packs
+CREATE TABLE IF NOT EXISTS guild_aliases (
+    guild_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    expression TEXT NOT NULL,
+    pack TEXT,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, name)
+);
+
+-- Create the character_stats table for /sheet
+CREATE TABLE IF NOT EXISTS character_stats (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    value BIGINT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, user_id, name)
+);
+
+-- Create the game_sessions table for per-channel seeded roll streams
+CREATE TABLE IF NOT EXISTS game_sessions (
+    channel_id BIGINT PRIMARY KEY,
+    guild_id BIGINT,
+    seed BIGINT NOT NULL,
+    started_by BIGINT NOT NULL,
+    rolls BIGINT NOT NULL DEFAULT 0,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the fair_seeds table for provably-fair rolls
+CREATE TABLE IF NOT EXISTS fair_seeds (
+    id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    guild_id BIGINT NOT NULL,
+    seed TEXT NOT NULL,
+    seed_hash TEXT NOT NULL,
+    rolls BIGINT NOT NULL DEFAULT 0,
+    revealed BIGINT NOT NULL DEFAULT 0,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_fair_seeds_active ON fair_seeds (guild_id) WHERE revealed = 0;
+
+-- Create the comment_keywords table for /keyword rules
+CREATE TABLE IF NOT EXISTS comment_keywords (
+    guild_id BIGINT NOT NULL,
+    keyword TEXT NOT NULL,
+    color TEXT,
+    tally TEXT,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, keyword)
+);
+
+-- Create the follow_ups table for /followup quick actions
+CREATE TABLE IF NOT EXISTS follow_ups (
+    guild_id BIGINT NOT NULL,
+    keyword TEXT NOT NULL,
+    label TEXT NOT NULL COLLATE nocase,
+    expression TEXT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, keyword, label)
+);
+
+-- Create the log_drains table for /config log-drain
+CREATE TABLE IF NOT EXISTS log_drains (
+    guild_id BIGINT PRIMARY KEY,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the user_macros table for /macro
+CREATE TABLE IF NOT EXISTS user_macros (
+    user_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    expression TEXT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, name)
+);
+
+-- Create the roll_tips and tip_opt_outs tables for onboarding tips
+CREATE TABLE IF NOT EXISTS roll_tips (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    rolls BIGINT NOT NULL DEFAULT 0,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS tip_opt_outs (
+    user_id BIGINT PRIMARY KEY,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the guild_tables table for /table
+CREATE TABLE IF NOT EXISTS guild_tables (
+    guild_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    entries TEXT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, name)
+);
+
+-- Create the deck tables for /draw
+CREATE TABLE IF NOT EXISTS guild_decks (
+    guild_id BIGINT NOT NULL,
+    name TEXT NOT NULL,
+    cards TEXT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS channel_decks (
+    channel_id BIGINT NOT NULL,
+    guild_id BIGINT,
+    name TEXT NOT NULL,
+    draw_pile TEXT NOT NULL,
+    discard_pile TEXT NOT NULL,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (channel_id, name)
+);
+
+-- Create the roll_stats and stats_opt_outs tables for /stats
+CREATE TABLE IF NOT EXISTS roll_stats (
+    guild_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    rolls BIGINT NOT NULL DEFAULT 0,
+    total BIGINT NOT NULL DEFAULT 0,
+    nat20s BIGINT NOT NULL DEFAULT 0,
+    nat1s BIGINT NOT NULL DEFAULT 0,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS stats_opt_outs (
+    user_id BIGINT PRIMARY KEY,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the guild_settings table for /settings
+CREATE TABLE IF NOT EXISTS guild_settings (
+    guild_id BIGINT PRIMARY KEY,
+    verbosity TEXT NOT NULL DEFAULT 'full',
+    max_dice BIGINT,
+    disabled_systems TEXT,
+    bands TEXT,
+    feature_roles TEXT,
+    autocorrect BIGINT NOT NULL DEFAULT 0,
+    fun_mode BIGINT NOT NULL DEFAULT 0,
+    version_footer BIGINT NOT NULL DEFAULT 0,
+    fair_rolls BIGINT NOT NULL DEFAULT 0,
+    embed_output BIGINT NOT NULL DEFAULT 0,
+    crit_table TEXT,
+    fumble_table TEXT,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
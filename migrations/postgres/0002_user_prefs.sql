@@ -0,0 +1,9 @@
+-- Create the user_prefs table for /prefs
+CREATE TABLE IF NOT EXISTS user_prefs (
+    user_id BIGINT PRIMARY KEY,
+    unsorted BIGINT NOT NULL DEFAULT 0,
+    simple BIGINT NOT NULL DEFAULT 0,
+    dice_skin TEXT NOT NULL DEFAULT 'classic',
+    utc_offset BIGINT,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+-- Add the /settings text-commands switch for legacy `!roll` messages
+ALTER TABLE guild_settings ADD COLUMN IF NOT EXISTS text_commands BIGINT NOT NULL DEFAULT 0;
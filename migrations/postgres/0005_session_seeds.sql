@@ -0,0 +1,11 @@
+-- Session seeds become 32 secret bytes stored as hex.  Old 64-bit seeds can't
+-- drive the new session stream, so sessions running at upgrade time end here
+DROP TABLE IF EXISTS game_sessions;
+CREATE TABLE game_sessions (
+    channel_id BIGINT PRIMARY KEY,
+    guild_id BIGINT,
+    seed TEXT NOT NULL,
+    started_by BIGINT NOT NULL,
+    rolls BIGINT NOT NULL DEFAULT 0,
+    timestamp TIMESTAMP(0) DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,285 @@
+-- Initial schema: every table the bot used before migrations were added.
+-- `IF NOT EXISTS` lets this run over a database created by older versions.
+--
+-- Keep migrations/postgres in step with this directory.
+
+-- Create the shard_stats table for single-process deployments
+CREATE TABLE IF NOT EXISTS shard_stats (
+    shard_id INT PRIMARY KEY,
+    server_count INT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    mem REAL
+);
+
+-- Create the process_stats table for multi-process tracking
+CREATE TABLE IF NOT EXISTS process_stats (
+    process_id TEXT PRIMARY KEY,
+    shard_start INT,
+    shard_count INT,
+    total_shards INT,
+    server_count INT,
+    memory_mb REAL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the channel_tallies table for running roll totals
+CREATE TABLE IF NOT EXISTS channel_tallies (
+    channel_id INT NOT NULL,
+    guild_id INT,
+    name TEXT NOT NULL COLLATE NOCASE,
+    total INT NOT NULL DEFAULT 0,
+    message_id INT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (channel_id, name)
+);
+
+-- Create the channel_clocks table for progress clocks
+CREATE TABLE IF NOT EXISTS channel_clocks (
+    channel_id INT NOT NULL,
+    guild_id INT,
+    name TEXT NOT NULL COLLATE NOCASE,
+    size INT NOT NULL,
+    filled INT NOT NULL DEFAULT 0,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (channel_id, name)
+);
+
+-- Create the initiative tables for /init
+CREATE TABLE IF NOT EXISTS initiative_entries (
+    channel_id INT NOT NULL,
+    guild_id INT,
+    name TEXT NOT NULL COLLATE NOCASE,
+    expression TEXT NOT NULL,
+    initiative INT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (channel_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS initiative_trackers (
+    channel_id INT PRIMARY KEY,
+    guild_id INT,
+    round INT NOT NULL DEFAULT 0,
+    current TEXT,
+    message_id INT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the data_erasure_log table for auditing data deletion requests
+CREATE TABLE IF NOT EXISTS data_erasure_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scope TEXT NOT NULL,
+    target_id INT NOT NULL,
+    requested_by INT NOT NULL,
+    rows_deleted INT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the roll_history table; what gets stored depends on the privacy level
+CREATE TABLE IF NOT EXISTS roll_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id INT,
+    channel_id INT NOT NULL,
+    user_id INT NOT NULL,
+    expression TEXT,
+    result TEXT,
+    total INT NOT NULL,
+    outcome TEXT,
+    round INT,
+    turn TEXT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_roll_history_user ON roll_history (user_id, timestamp);
+
+CREATE INDEX IF NOT EXISTS idx_roll_history_channel ON roll_history (channel_id, round);
+
+-- Create the privacy_settings table for per-guild and per-user history levels
+CREATE TABLE IF NOT EXISTS privacy_settings (
+    scope TEXT NOT NULL,
+    target_id INT NOT NULL,
+    level TEXT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (scope, target_id)
+);
+
+-- Create the guild_config table for server settings
+CREATE TABLE IF NOT EXISTS guild_config (
+    guild_id INT PRIMARY KEY,
+    default_system TEXT,
+    ephemeral_default INT NOT NULL DEFAULT 0,
+    gm_role_id INT,
+    restricted_channels TEXT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the alias_usage table for anonymous alias counters
+CREATE TABLE IF NOT EXISTS alias_usage (
+    alias TEXT PRIMARY KEY,
+    uses INT NOT NULL DEFAULT 0,
+    first_used DATETIME DEFAULT CURRENT_TIMESTAMP,
+    last_used DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the presence_messages table for the bot status rotation
+CREATE TABLE IF NOT EXISTS presence_messages (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    message TEXT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the guild_aliases table for /alias and macro packs
+CREATE TABLE IF NOT EXISTS guild_aliases (
+    guild_id INT NOT NULL,
+    name TEXT NOT NULL,
+    expression TEXT NOT NULL,
+    pack TEXT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, name)
+);
+
+-- Create the character_stats table for /sheet
+CREATE TABLE IF NOT EXISTS character_stats (
+    guild_id INT NOT NULL,
+    user_id INT NOT NULL,
+    name TEXT NOT NULL,
+    value INT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, user_id, name)
+);
+
+-- Create the game_sessions table for per-channel seeded roll streams
+CREATE TABLE IF NOT EXISTS game_sessions (
+    channel_id INT PRIMARY KEY,
+    guild_id INT,
+    seed INT NOT NULL,
+    started_by INT NOT NULL,
+    rolls INT NOT NULL DEFAULT 0,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the fair_seeds table for provably-fair rolls
+CREATE TABLE IF NOT EXISTS fair_seeds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    guild_id INT NOT NULL,
+    seed TEXT NOT NULL,
+    seed_hash TEXT NOT NULL,
+    rolls INT NOT NULL DEFAULT 0,
+    revealed INT NOT NULL DEFAULT 0,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_fair_seeds_active ON fair_seeds (guild_id) WHERE revealed = 0;
+
+-- Create the comment_keywords table for /keyword rules
+CREATE TABLE IF NOT EXISTS comment_keywords (
+    guild_id INT NOT NULL,
+    keyword TEXT NOT NULL,
+    color TEXT,
+    tally TEXT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, keyword)
+);
+
+-- Create the follow_ups table for /followup quick actions
+CREATE TABLE IF NOT EXISTS follow_ups (
+    guild_id INT NOT NULL,
+    keyword TEXT NOT NULL,
+    label TEXT NOT NULL COLLATE NOCASE,
+    expression TEXT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, keyword, label)
+);
+
+-- Create the log_drains table for /config log-drain
+CREATE TABLE IF NOT EXISTS log_drains (
+    guild_id INT PRIMARY KEY,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the user_macros table for /macro
+CREATE TABLE IF NOT EXISTS user_macros (
+    user_id INT NOT NULL,
+    name TEXT NOT NULL,
+    expression TEXT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, name)
+);
+
+-- Create the roll_tips and tip_opt_outs tables for onboarding tips
+CREATE TABLE IF NOT EXISTS roll_tips (
+    guild_id INT NOT NULL,
+    user_id INT NOT NULL,
+    rolls INT NOT NULL DEFAULT 0,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS tip_opt_outs (
+    user_id INT PRIMARY KEY,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the guild_tables table for /table
+CREATE TABLE IF NOT EXISTS guild_tables (
+    guild_id INT NOT NULL,
+    name TEXT NOT NULL,
+    entries TEXT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, name)
+);
+
+-- Create the deck tables for /draw
+CREATE TABLE IF NOT EXISTS guild_decks (
+    guild_id INT NOT NULL,
+    name TEXT NOT NULL,
+    cards TEXT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS channel_decks (
+    channel_id INT NOT NULL,
+    guild_id INT,
+    name TEXT NOT NULL,
+    draw_pile TEXT NOT NULL,
+    discard_pile TEXT NOT NULL,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (channel_id, name)
+);
+
+-- Create the roll_stats and stats_opt_outs tables for /stats
+CREATE TABLE IF NOT EXISTS roll_stats (
+    guild_id INT NOT NULL,
+    user_id INT NOT NULL,
+    rolls INT NOT NULL DEFAULT 0,
+    total INT NOT NULL DEFAULT 0,
+    nat20s INT NOT NULL DEFAULT 0,
+    nat1s INT NOT NULL DEFAULT 0,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (guild_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS stats_opt_outs (
+    user_id INT PRIMARY KEY,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Create the guild_settings table for /settings
+CREATE TABLE IF NOT EXISTS guild_settings (
+    guild_id INT PRIMARY KEY,
+    verbosity TEXT NOT NULL DEFAULT 'full',
+    max_dice INT,
+    disabled_systems TEXT,
+    bands TEXT,
+    feature_roles TEXT,
+    autocorrect INT NOT NULL DEFAULT 0,
+    fun_mode INT NOT NULL DEFAULT 0,
+    version_footer INT NOT NULL DEFAULT 0,
+    fair_rolls INT NOT NULL DEFAULT 0,
+    embed_output INT NOT NULL DEFAULT 0,
+    crit_table TEXT,
+    fumble_table TEXT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,9 @@
+-- Create the user_prefs table for /prefs
+CREATE TABLE IF NOT EXISTS user_prefs (
+    user_id INT PRIMARY KEY,
+    unsorted INT NOT NULL DEFAULT 0,
+    simple INT NOT NULL DEFAULT 0,
+    dice_skin TEXT NOT NULL DEFAULT 'classic',
+    utc_offset INT,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,2 @@
+-- Add the /settings text-commands switch for legacy `!roll` messages
+ALTER TABLE guild_settings ADD COLUMN text_commands INT NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- Add the /settings threads switch for posting long results in a thread
+ALTER TABLE guild_settings ADD COLUMN threaded_output INT NOT NULL DEFAULT 0;
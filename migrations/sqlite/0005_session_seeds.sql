@@ -0,0 +1,11 @@
+-- Session seeds become 32 secret bytes stored as hex.  Old 64-bit seeds can't
+-- drive the new session stream, so sessions running at upgrade time end here
+DROP TABLE IF EXISTS game_sessions;
+CREATE TABLE game_sessions (
+    channel_id INT PRIMARY KEY,
+    guild_id INT,
+    seed TEXT NOT NULL,
+    started_by INT NOT NULL,
+    rolls INT NOT NULL DEFAULT 0,
+    timestamp DATETIME DEFAULT CURRENT_TIMESTAMP
+);
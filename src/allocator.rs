@@ -0,0 +1,39 @@
+//! Global memory allocator, chosen at build time.
+//!
+//! | Build                             | Allocator                         |
+//! |-----------------------------------|-----------------------------------|
+//! | default                           | The system allocator (glibc, …)   |
+//! | `cargo build --features mimalloc` | [mimalloc](https://github.com/microsoft/mimalloc) |
+//! | `cargo build --features jemalloc` | [jemalloc](https://jemalloc.net) (not on Windows MSVC) |
+//!
+//! With many shards in one process the bot spends most of its memory on
+//! short-lived gateway payloads and roll output, which glibc's per-thread
+//! arenas tend to hold on to; mimalloc and jemalloc usually return more of it.
+//! The choice is logged at startup and next to the memory figure of every
+//! stats collection (the `memory_mb` / `mem` columns of `process_stats` and
+//! `shard_stats`), so two builds can be compared on the same shards.
+//! `tests/performance_tests.rs` has a roll-formatting workload that prints its
+//! timing with the allocator in use.
+//!
+//! The allocator is set here, in the library, so the test suites built with
+//! the same feature measure the same allocator as the bot.  If both features
+//! are enabled (as `--all-features` does), mimalloc is used.
+
+#[cfg(feature = "mimalloc")]
+#[global_allocator]
+static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[cfg(all(feature = "jemalloc", not(feature = "mimalloc")))]
+#[global_allocator]
+static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
+/// Name of the allocator this build uses: `system`, `mimalloc` or `jemalloc`
+pub const fn name() -> &'static str {
+    if cfg!(feature = "mimalloc") {
+        "mimalloc"
+    } else if cfg!(feature = "jemalloc") {
+        "jemalloc"
+    } else {
+        "system"
+    }
+}
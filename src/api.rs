@@ -0,0 +1,120 @@
+//! HTTP roll API (`api` feature).
+//!
+//! When the bot is built with `--features api` and `API_ADDR` is set (e.g.
+//! `127.0.0.1:8080`), `main.rs` serves this router next to the Discord
+//! client so VTTs and other tools can roll with exactly the bot's parser and
+//! roller.
+//!
+//! | Route          | Body                            | Response                          |
+//! |----------------|---------------------------------|-----------------------------------|
+//! | `POST /roll`   | `{"expression": "4d6 k3 + 2"}`  | `{"expression", "results", "text", "engine"}` |
+//! | `GET /version` |                                 | `{"crate_version", "grammar_revision"}` |
+//!
+//! `results` holds one serialized [`RollResult`] per roll (several for roll
+//! sets and `;`-separated expressions), `text` the Discord-formatted output
+//! and `engine` the dice engine version that rolled it (see `version.rs`).  An expression the engine rejects gives `400` with
+//! `{"error": "...", "kind": "parse_error"}`, `kind` being
+//! [`DiceError::kind`].
+//!
+//! The API has no authentication or rate limiting; bind it to a private
+//! address or put it behind a proxy that does.
+
+use crate::dice::{self, DiceError, RollResult};
+use crate::version::{self, EngineVersion};
+use anyhow::Result;
+use axum::{
+    Json, Router,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use serde::{Deserialize, Serialize};
+use tokio::{net::TcpListener, sync::broadcast};
+use tracing::info;
+
+/// Longest expression accepted, the same as a Discord command option
+pub const MAX_EXPRESSION_LENGTH: usize = 6000;
+
+#[derive(Debug, Deserialize)]
+pub struct RollRequest {
+    pub expression: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RollResponse {
+    pub expression: String,
+    pub results: Vec<RollResult>,
+    pub text: String,
+    /// Engine version, e.g. `1.5.2+g1`
+    pub engine: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    error: String,
+    kind: &'static str,
+}
+
+/// The API's routes
+pub fn router() -> Router {
+    Router::new()
+        .route("/roll", post(roll))
+        .route("/version", get(engine_version))
+}
+
+/// Serve the API on `addr` until `shutdown` fires
+pub async fn serve(addr: &str, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    info!("Roll API listening on {}", listener.local_addr()?);
+
+    axum::serve(listener, router())
+        .with_graceful_shutdown(async move {
+            let _ = shutdown.recv().await;
+        })
+        .await?;
+
+    info!("Roll API stopped");
+    Ok(())
+}
+
+/// `POST /roll`
+pub async fn roll(Json(request): Json<RollRequest>) -> Response {
+    match roll_expression(&request.expression) {
+        Ok(response) => Json(response).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+                kind: e.kind(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// `GET /version`
+pub async fn engine_version() -> Json<EngineVersion> {
+    Json(version::engine_version())
+}
+
+/// Roll an expression the way `/roll` does
+pub fn roll_expression(expression: &str) -> dice::Result<RollResponse> {
+    let expression = expression.trim();
+    if expression.is_empty() {
+        return Err(DiceError::ParseError("Expression is empty".into()));
+    }
+    if expression.len() > MAX_EXPRESSION_LENGTH {
+        return Err(DiceError::InputTooLong {
+            length: expression.len(),
+            max: MAX_EXPRESSION_LENGTH,
+        });
+    }
+
+    let results = dice::parse_and_roll(expression)?;
+    Ok(RollResponse {
+        expression: expression.to_string(),
+        text: dice::format_multiple_results_with_limit(&results),
+        results,
+        engine: version::engine_version().to_string(),
+    })
+}
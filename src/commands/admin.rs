@@ -0,0 +1,521 @@
+//! `/admin` owner-only operational commands.
+//!
+//! | Subcommand        | Effect                                                   |
+//! |-------------------|----------------------------------------------------------|
+//! | `resync-commands` | Delete and re-register the slash commands of a scope     |
+//! | `selftest`        | Check the dice engine against recorded seeded outputs    |
+//! | `stats export`    | Attach CSVs of every user's rolls per day and alias use  |
+//! | `presence add`    | Add a message to the bot status rotation                 |
+//! | `presence remove` | Remove a rotation message by its number                  |
+//! | `presence list`   | Show the rotation and where it comes from                |
+//!
+//! `scope:global` replaces the global commands; `scope:guild` replaces the
+//! commands registered to the server it is used in (as with `GUILD_ID`
+//! testing; elsewhere that duplicates the global commands).  Shard 0
+//! overwrites the commands at startup, but commands removed or renamed since
+//! are only cleared by deleting them, which this does one at a time before
+//! registering [`all_commands`].
+//!
+//! Progress is edited into the response as the resync runs.  Serenity's HTTP
+//! client waits out Discord's per-route rate limits and each request goes
+//! through [`outbound::send_with_retry`](crate::outbound::send_with_retry);
+//! because Discord also caps how many commands can be created a day, a scope
+//! can only be resynced once every [`RESYNC_COOLDOWN`].
+//!
+//! `selftest` runs the checks in [`crate::selftest`] and reports which
+//! expressions no longer roll exactly as recorded, so an upgrade can be
+//! verified without shell access to the host.
+//!
+//! `stats export` sends the files described in [`crate::stats_export`]: the
+//! same daily counts as `/mystats export` across all users, and the anonymous
+//! alias counters `/usage` reports.
+//!
+//! `presence` edits the `presence_messages` list described in
+//! [`crate::presence`]; while it is empty the rotation uses
+//! `PRESENCE_MESSAGES` or the built-in messages.  Changes show from the next
+//! rotation step.
+//!
+//! Like `/usage`, the command is restricted to the application owner (or team
+//! members), read from [`OwnersContainer`](crate::OwnersContainer).
+
+use crate::DatabaseContainer;
+use crate::commands::{CommandResponse, all_commands, mystats, require_owner};
+use crate::outbound;
+use crate::presence::{self, PresenceMessage};
+use crate::selftest;
+use crate::stats_export;
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use serenity::{
+    all::{Command, CommandDataOptionValue, CommandInteraction, CommandOptionType, GuildId},
+    builder::{CreateCommand, CreateCommandOption},
+    http::Http,
+    model::prelude::Permissions,
+    prelude::Context,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+/// Shortest time between two resyncs of the same scope
+pub const RESYNC_COOLDOWN: Duration = Duration::from_secs(10 * 60);
+
+// Deletions between progress edits, to keep edits under Discord's limits
+const PROGRESS_EVERY: usize = 5;
+
+// Last resync per scope: `None` for global, otherwise the guild ID
+static LAST_RESYNC: Lazy<Mutex<HashMap<Option<u64>, Instant>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("admin")
+        .description("Bot maintenance (bot owner only)")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "resync-commands",
+                "Delete and re-register slash commands",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "scope", "Commands to resync")
+                    .required(true)
+                    .add_string_choice("Global", "global")
+                    .add_string_choice("This server", "guild"),
+            ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "selftest",
+            "Check the dice engine against recorded results",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommandGroup,
+                "stats",
+                "Bot roll statistics",
+            )
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "export",
+                "Download roll statistics for all users as CSV files",
+            )),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommandGroup,
+                "presence",
+                "Bot status rotation",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::SubCommand,
+                    "add",
+                    "Add a status message",
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "message",
+                        "e.g. `watching: {servers} servers` or `[10-24..10-31] playing: 🎃 Spooky rolls`",
+                    )
+                    .required(true)
+                    .max_length(160),
+                ),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::SubCommand,
+                    "remove",
+                    "Remove a status message",
+                )
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::Integer,
+                        "number",
+                        "Number shown by /admin presence list",
+                    )
+                    .required(true)
+                    .min_int_value(1),
+                ),
+            )
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "list",
+                "Show the status rotation",
+            )),
+        )
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    if let Some(denied) = require_owner(ctx, command).await {
+        return Ok(denied);
+    }
+
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing admin subcommand"));
+    };
+    match subcommand.name.as_str() {
+        "resync-commands" => {}
+        "selftest" => return Ok(CommandResponse::private(run_selftest())),
+        "stats" => return export_stats(ctx, &subcommand.value).await,
+        "presence" => return edit_presence(ctx, &subcommand.value).await,
+        other => return Err(anyhow!("Unknown admin subcommand: {}", other)),
+    }
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid admin subcommand"));
+    };
+
+    let scope = options
+        .iter()
+        .find(|opt| opt.name == "scope")
+        .and_then(|opt| opt.value.as_str());
+    let guild_id = match scope {
+        Some("guild") => match command.guild_id {
+            Some(guild_id) => Some(guild_id),
+            None => {
+                return Ok(CommandResponse::private(
+                    "❌ Use `scope:guild` in the server whose commands should be resynced."
+                        .to_string(),
+                ));
+            }
+        },
+        _ => None,
+    };
+
+    if let Some(remaining) = claim_resync(guild_id.map(GuildId::get), Instant::now()) {
+        return Ok(CommandResponse::private(format!(
+            "⏳ These commands were resynced recently. Try again in {} min.",
+            remaining.as_secs().div_ceil(60)
+        )));
+    }
+
+    let (sender, receiver) = mpsc::channel(32);
+    tokio::spawn(resync(ctx.http.clone(), guild_id, sender));
+
+    Ok(
+        CommandResponse::private(format!("🔄 Resyncing {} commands…", scope_label(guild_id)))
+            .with_progress(receiver),
+    )
+}
+
+// `stats export`: every user's daily rolls and the alias counters as CSVs
+async fn export_stats(ctx: &Context, value: &CommandDataOptionValue) -> Result<CommandResponse> {
+    let CommandDataOptionValue::SubCommandGroup(options) = value else {
+        return Err(anyhow!("Invalid admin stats subcommand"));
+    };
+    if options.first().map(|opt| opt.name.as_str()) != Some("export") {
+        return Err(anyhow!("Unknown admin stats subcommand"));
+    }
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let export = stats_export::export_bot_stats(&db).await?;
+    info!("Exported bot stats: {} rolls", export.rolls);
+    Ok(mystats::export_response("Bot roll statistics", export))
+}
+
+// `presence add|remove|list`: the stored status rotation
+async fn edit_presence(ctx: &Context, value: &CommandDataOptionValue) -> Result<CommandResponse> {
+    let CommandDataOptionValue::SubCommandGroup(options) = value else {
+        return Err(anyhow!("Invalid admin presence subcommand"));
+    };
+    let Some(subcommand) = options.first() else {
+        return Err(anyhow!("Missing admin presence subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid admin presence subcommand"));
+    };
+    let option = |name: &str| options.iter().find(|opt| opt.name == name);
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let stored = db.get_presence_messages().await?;
+
+    let content = match subcommand.name.as_str() {
+        "add" => {
+            let line = option("message")
+                .and_then(|opt| opt.value.as_str())
+                .unwrap_or_default();
+            let message = match PresenceMessage::parse(line) {
+                Ok(message) => message,
+                Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+            };
+            if stored.len() >= presence::MAX_MESSAGES {
+                return Ok(CommandResponse::private(format!(
+                    "❌ The rotation already has {} messages. Remove one first.",
+                    presence::MAX_MESSAGES
+                )));
+            }
+            db.add_presence_message(&message.to_string()).await?;
+            info!("Presence message added: {}", message);
+            format!(
+                "✅ Added `{message}` as message {} of the status rotation.",
+                stored.len() + 1
+            )
+        }
+        "remove" => {
+            let number = option("number")
+                .and_then(|opt| opt.value.as_i64())
+                .unwrap_or_default();
+            let Some((id, line)) = usize::try_from(number)
+                .ok()
+                .and_then(|number| stored.get(number.checked_sub(1)?))
+            else {
+                return Ok(CommandResponse::private(format!(
+                    "❌ There is no stored message {number}. See `/admin presence list`."
+                )));
+            };
+            db.remove_presence_message(*id).await?;
+            info!("Presence message removed: {}", line);
+            if stored.len() == 1 {
+                format!(
+                    "🗑️ Removed `{line}`. The rotation now uses `PRESENCE_MESSAGES` or the built-in messages."
+                )
+            } else {
+                format!("🗑️ Removed `{line}` from the status rotation.")
+            }
+        }
+        "list" => format_presence_list(&stored, &presence::load_messages(&db).await),
+        other => return Err(anyhow!("Unknown admin presence subcommand: {}", other)),
+    };
+    Ok(CommandResponse::private(content))
+}
+
+/// `/admin presence list`: the stored messages, or the list used instead
+pub fn format_presence_list(stored: &[(i64, String)], active: &[PresenceMessage]) -> String {
+    let mut content = if stored.is_empty() {
+        "🎭 **Status rotation** (no stored messages; using `PRESENCE_MESSAGES` or the built-in list):"
+            .to_string()
+    } else {
+        "🎭 **Status rotation**:".to_string()
+    };
+    if stored.is_empty() {
+        for message in active {
+            content.push_str(&format!("\n• `{message}`"));
+        }
+    } else {
+        for (number, (_, line)) in stored.iter().enumerate() {
+            content.push_str(&format!("\n{}. `{line}`", number + 1));
+        }
+    }
+    content.push_str(&format!(
+        "\n-# Placeholders: `{{rolls}}`, `{{servers}}`, `{{shard}}`, `{{shards}}`, `{{version}}`. Messages change every {}s.",
+        presence::rotation_interval().as_secs()
+    ));
+    content
+}
+
+// Run the engine checks, logging the outcome for the operator
+fn run_selftest() -> String {
+    let failures = selftest::run_checks();
+    if failures.is_empty() {
+        info!("Self-test passed: {} checks", selftest::CHECKS.len());
+    } else {
+        for failure in &failures {
+            warn!(
+                "Self-test check {} failed: expected {:?}, got {:?}",
+                failure.expression, failure.expected, failure.actual
+            );
+        }
+    }
+    selftest::format_report(&failures)
+}
+
+/// Record a resync of `scope` starting at `now`, or return how long until
+/// the scope may be resynced again
+pub fn claim_resync(scope: Option<u64>, now: Instant) -> Option<Duration> {
+    let mut last = LAST_RESYNC.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(previous) = last.get(&scope) {
+        let elapsed = now.saturating_duration_since(*previous);
+        if elapsed < RESYNC_COOLDOWN {
+            return Some(RESYNC_COOLDOWN - elapsed);
+        }
+    }
+    last.insert(scope, now);
+    None
+}
+
+/// A step of a resync, reported as a progress edit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResyncStage {
+    Deleting {
+        deleted: usize,
+        total: usize,
+    },
+    Registering {
+        count: usize,
+    },
+    Done {
+        deleted: usize,
+        registered: usize,
+    },
+    Failed {
+        deleted: usize,
+        total: usize,
+        error: String,
+    },
+}
+
+impl ResyncStage {
+    /// Progress message, e.g. `🔄 Resyncing global commands: deleted 5/21…`
+    pub fn message(&self, scope: &str) -> String {
+        match self {
+            Self::Deleting { deleted, total } => {
+                format!("🔄 Resyncing {scope} commands: deleted {deleted}/{total}…")
+            }
+            Self::Registering { count } => {
+                format!("🔄 Resyncing {scope} commands: registering {count}…")
+            }
+            Self::Done {
+                deleted,
+                registered,
+            } => format!(
+                "✅ Resynced {scope} commands: deleted {deleted}, registered {registered}. \
+                 Discord may take a few minutes to show the changes."
+            ),
+            Self::Failed {
+                deleted,
+                total,
+                error,
+            } => format!(
+                "❌ Resync of {scope} commands stopped after deleting {deleted}/{total}: {error}\n\
+                 Commands may be missing until a resync succeeds or the bot restarts."
+            ),
+        }
+    }
+}
+
+fn scope_label(guild_id: Option<GuildId>) -> &'static str {
+    if guild_id.is_some() {
+        "this server's"
+    } else {
+        "global"
+    }
+}
+
+// Delete every command in the scope, then register the current set
+async fn resync(http: Arc<Http>, guild_id: Option<GuildId>, progress: mpsc::Sender<String>) {
+    let scope = scope_label(guild_id);
+    // The response may already be gone; the resync carries on regardless
+    let report = async |stage: ResyncStage| {
+        progress.send(stage.message(scope)).await.ok();
+    };
+
+    let existing = match guild_id {
+        Some(guild_id) => {
+            outbound::send_with_retry("list guild commands", || guild_id.get_commands(&http)).await
+        }
+        None => {
+            outbound::send_with_retry("list global commands", || {
+                Command::get_global_commands(&http)
+            })
+            .await
+        }
+    };
+    let existing = match existing {
+        Ok(existing) => existing,
+        Err(e) => {
+            error!("Cannot list {} commands: {}", scope, e);
+            report(ResyncStage::Failed {
+                deleted: 0,
+                total: 0,
+                error: e.to_string(),
+            })
+            .await;
+            return;
+        }
+    };
+
+    let total = existing.len();
+    for (index, command) in existing.iter().enumerate() {
+        let deleted = match guild_id {
+            Some(guild_id) => {
+                outbound::send_with_retry("delete guild command", || {
+                    guild_id.delete_command(&http, command.id)
+                })
+                .await
+            }
+            None => {
+                outbound::send_with_retry("delete global command", || {
+                    Command::delete_global_command(&http, command.id)
+                })
+                .await
+            }
+        };
+        if let Err(e) = deleted {
+            error!("Cannot delete {} command {}: {}", scope, command.name, e);
+            report(ResyncStage::Failed {
+                deleted: index,
+                total,
+                error: e.to_string(),
+            })
+            .await;
+            return;
+        }
+        if (index + 1) % PROGRESS_EVERY == 0 && index + 1 < total {
+            report(ResyncStage::Deleting {
+                deleted: index + 1,
+                total,
+            })
+            .await;
+        }
+    }
+
+    // Cloned per attempt: the register calls take the list by value
+    let commands = all_commands();
+    report(ResyncStage::Registering {
+        count: commands.len(),
+    })
+    .await;
+    let registered = match guild_id {
+        Some(guild_id) => {
+            outbound::send_with_retry("register guild commands", || {
+                guild_id.set_commands(&http, commands.clone())
+            })
+            .await
+        }
+        None => {
+            outbound::send_with_retry("register global commands", || {
+                Command::set_global_commands(&http, commands.clone())
+            })
+            .await
+        }
+    };
+    match registered {
+        Ok(registered) => {
+            info!(
+                "Resynced {} commands: deleted {}, registered {}",
+                scope,
+                total,
+                registered.len()
+            );
+            report(ResyncStage::Done {
+                deleted: total,
+                registered: registered.len(),
+            })
+            .await;
+        }
+        Err(e) => {
+            error!("Cannot register {} commands: {}", scope, e);
+            report(ResyncStage::Failed {
+                deleted: total,
+                total,
+                error: e.to_string(),
+            })
+            .await;
+        }
+    }
+}
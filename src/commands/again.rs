@@ -0,0 +1,195 @@
+//! `/again` slash-command handler and the per-user last-roll cache.
+//!
+//! Every successful `/roll` remembers the expression as typed, per user and
+//! per server (DMs count as one place).  `/again` (or `/roll last`) rolls it
+//! again, optionally with a delta added to every roll in it:
+//!
+//! | Typed             | Last roll           | Rolls                    |
+//! |-------------------|---------------------|--------------------------|
+//! | `/again`          | `1d20+5 ! Attack`   | `1d20+5 ! Attack`        |
+//! | `/again +2`       | `1d20+5 ! Attack`   | `1d20+5 +2 ! Attack`     |
+//! | `/roll last -1d4` | `2d6 ; 1d8`         | `2d6 -1d4 ; 1d8 -1d4`    |
+//!
+//! Because the expression is stored as typed, server aliases, `/sheet` stats
+//! and trackers are looked up fresh on each replay.  The cache lives in
+//! memory only ([`LastRollContainer`](crate::LastRollContainer)), is lost on
+//! restart and is cleared for a user by `/forgetme`.
+
+use crate::LastRollContainer;
+use crate::commands::{CommandResponse, RollOrigin, roll};
+use crate::sync::lock;
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::{
+    all::{CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Users (per server) remembered before the oldest entry is evicted
+pub const LAST_ROLL_CAPACITY: usize = 10_000;
+
+// One or more `+N`, `-NdS`, `*N`, `/N` terms
+static DELTA_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[+\-*/]\s*\d*d?\d+(\s*[+\-*/]\s*\d*d?\d+)*$")
+        .expect("Failed to compile DELTA_REGEX")
+});
+
+/// The most recent roll of each user in each server
+#[derive(Debug, Default)]
+pub struct LastRolls {
+    rolls: Mutex<HashMap<(u64, u64), (String, Instant)>>,
+}
+
+impl LastRolls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember `expression` as the user's last roll in `guild_id`
+    pub fn remember(&self, guild_id: Option<u64>, user_id: u64, expression: &str) {
+        let key = (guild_id.unwrap_or(0), user_id);
+        let mut rolls = lock(&self.rolls);
+        if rolls.len() >= LAST_ROLL_CAPACITY
+            && !rolls.contains_key(&key)
+            && let Some(oldest) = rolls
+                .iter()
+                .min_by_key(|(_, (_, at))| *at)
+                .map(|(key, _)| *key)
+        {
+            rolls.remove(&oldest);
+        }
+        rolls.insert(key, (expression.to_string(), Instant::now()));
+    }
+
+    /// The user's last roll in `guild_id`, if any
+    pub fn last(&self, guild_id: Option<u64>, user_id: u64) -> Option<String> {
+        lock(&self.rolls)
+            .get(&(guild_id.unwrap_or(0), user_id))
+            .map(|(expression, _)| expression.clone())
+    }
+
+    /// Forget the user's last rolls in every server
+    pub fn forget_user(&self, user_id: u64) {
+        lock(&self.rolls).retain(|(_, user), _| *user != user_id);
+    }
+}
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("again")
+        .description("Roll your last roll in this server again")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "modifier",
+                "Added to the roll (e.g. +2, -1, +1d4)",
+            )
+            .required(false),
+        )
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let delta = command
+        .data
+        .options
+        .iter()
+        .find(|opt| opt.name == "modifier")
+        .and_then(|opt| opt.value.as_str())
+        .unwrap_or_default();
+    reroll(ctx, &RollOrigin::from(command), delta).await
+}
+
+/// The delta after a `last` keyword (`""` for a bare `last`), or `None` if
+/// `expression` isn't one
+pub fn strip_last_keyword(expression: &str) -> Option<&str> {
+    let expression = expression.trim();
+    let keyword = expression.get(..4)?;
+    if !keyword.eq_ignore_ascii_case("last") {
+        return None;
+    }
+    let rest = &expression[4..];
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+/// Add `delta` to each roll of `expression`, before its comment.  An empty
+/// delta returns the expression unchanged.
+pub fn apply_delta(expression: &str, delta: &str) -> Result<String> {
+    let delta = delta.trim();
+    if delta.is_empty() {
+        return Ok(expression.to_string());
+    }
+    if !DELTA_REGEX.is_match(delta) {
+        return Err(anyhow!(
+            "The modifier must be like `+2`, `-1` or `+1d4`, not `{delta}`"
+        ));
+    }
+
+    let (roll_part, comment_part) = match expression.find('!') {
+        Some(pos) => expression.split_at(pos),
+        None => (expression, ""),
+    };
+    let rolls = roll_part
+        .split(';')
+        .map(|part| format!("{} {delta}", part.trim()))
+        .collect::<Vec<_>>()
+        .join(" ; ");
+
+    if comment_part.is_empty() {
+        Ok(rolls)
+    } else {
+        Ok(format!("{rolls} {}", comment_part.trim()))
+    }
+}
+
+/// Remember a successful roll for `/again`
+pub async fn remember(ctx: &Context, origin: &RollOrigin<'_>, expression: &str) {
+    if let Some(last_rolls) = ctx.data.read().await.get::<LastRollContainer>().cloned() {
+        last_rolls.remember(
+            origin.guild_id.map(|id| id.get()),
+            origin.user.id.get(),
+            expression,
+        );
+    }
+}
+
+/// Forget a user's last rolls, for `/forgetme`
+pub async fn forget_user(ctx: &Context, user_id: u64) {
+    if let Some(last_rolls) = ctx.data.read().await.get::<LastRollContainer>().cloned() {
+        last_rolls.forget_user(user_id);
+    }
+}
+
+/// Roll the user's last roll again with `delta` added
+pub async fn reroll(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    delta: &str,
+) -> Result<CommandResponse> {
+    let last = ctx
+        .data
+        .read()
+        .await
+        .get::<LastRollContainer>()
+        .cloned()
+        .and_then(|last_rolls| {
+            last_rolls.last(origin.guild_id.map(|id| id.get()), origin.user.id.get())
+        });
+    let Some(last) = last else {
+        return Ok(CommandResponse::private(
+            "❌ You haven't rolled anything here yet. Roll with `/roll` first.".to_string(),
+        ));
+    };
+
+    match apply_delta(&last, delta) {
+        Ok(expression) => roll::roll_expression(ctx, origin, &expression).await,
+        Err(e) => Ok(CommandResponse::private(format!("❌ {e}"))),
+    }
+}
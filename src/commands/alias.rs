@@ -0,0 +1,230 @@
+//! `/alias` slash-command handler for a server's own shorthand.
+//!
+//! | Subcommand | Effect                                                        |
+//! |------------|---------------------------------------------------------------|
+//! | `add`      | Add or replace an alias, e.g. `/alias add name:zap dice:8d6 ie6 t5` |
+//! | `remove`   | Remove an alias                                               |
+//! | `list`     | Show the server's aliases                                     |
+//!
+//! Aliases are stored in `guild_aliases` next to the ones `/macropack`
+//! registers, and `/roll` expands them with `aliases::expand_user_aliases`
+//! before the built-in game system aliases: `/roll zap + 2` rolls
+//! `8d6 ie6 t5 + 2`.  A member's own `/macro` of the same name wins.  Names
+//! that already roll (`d20`, `4cod`) can't be used, so built-in syntax always
+//! means the same thing in every server.  Changing aliases requires the
+//! **Administrator** permission, enforced both via
+//! `default_member_permissions` and at runtime by `middleware::Permissions`.
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::database::GuildAlias;
+use crate::dice::aliases;
+use crate::templates::{self, RollTemplate};
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    model::prelude::Permissions,
+    prelude::Context,
+};
+use std::collections::HashMap;
+use tracing::info;
+
+/// Aliases a server may define, not counting macro packs
+pub const MAX_GUILD_ALIASES: usize = 50;
+
+// Aliases expand as whole words, like macros
+static ALIAS_NAME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[a-z][a-z0-9_]{0,31}$").expect("Failed to compile ALIAS_NAME_REGEX")
+});
+
+pub fn register() -> CreateCommand {
+    let name_option = || {
+        CreateCommandOption::new(CommandOptionType::String, "name", "Alias name (e.g. zap)")
+            .required(true)
+            .max_length(32)
+    };
+
+    CreateCommand::new("alias")
+        .description("Define shorthand rolls for this server (administrators only)")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "add",
+                "Add or replace an alias",
+            )
+            .add_sub_option(name_option())
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "dice",
+                    "Expression it rolls (e.g. 8d6 ie6 t5)",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "remove", "Remove an alias")
+                .add_sub_option(name_option()),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "Show this server's aliases",
+        ))
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ This command can only be used in servers.".to_string(),
+        ));
+    };
+
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing alias subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid alias subcommand"));
+    };
+    let option_str = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| opt.value.as_str())
+            .map(str::trim)
+    };
+    let name = option_str("name").unwrap_or_default().to_lowercase();
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let guild_id = guild_id.get() as i64;
+    let custom = db.get_custom_guild_aliases(guild_id).await?;
+
+    let content = match subcommand.name.as_str() {
+        "list" => {
+            let packs = db.get_enabled_macro_packs(guild_id).await?;
+            format_alias_list(&custom, &packs)
+        }
+        "add" => {
+            let expression = option_str("dice").unwrap_or_default();
+            let all = db.get_guild_aliases(guild_id).await?;
+            let alias = match validate_alias(&name, expression, &custom, &all) {
+                Ok(alias) => alias,
+                Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+            };
+            db.set_guild_alias(guild_id, &alias).await?;
+            info!(
+                "Saved alias {} for guild {} (by {})",
+                alias.name, guild_id, command.user.id
+            );
+            format!(
+                "✅ Saved `{}` → `{}`. Anyone here can roll it with `/roll {}`.",
+                alias.name, alias.expression, alias.name
+            )
+        }
+        "remove" => {
+            if db.remove_guild_alias(guild_id, &name).await? {
+                info!(
+                    "Removed alias {} for guild {} (by {})",
+                    name, guild_id, command.user.id
+                );
+                format!("🗑️ Removed `{name}`.")
+            } else {
+                format!(
+                    "❌ This server has no alias named `{name}`. See `/alias list`; macro pack aliases are removed with `/macropack disable`."
+                )
+            }
+        }
+        other => return Err(anyhow!("Unknown alias subcommand: {}", other)),
+    };
+
+    Ok(CommandResponse::private(content))
+}
+
+/// Check a new alias against the server's own aliases (`custom`) and every
+/// alias it has including macro packs (`all`), returning it normalized
+pub fn validate_alias(
+    name: &str,
+    expression: &str,
+    custom: &[GuildAlias],
+    all: &HashMap<String, String>,
+) -> Result<GuildAlias> {
+    let template = RollTemplate::new(name, expression)?;
+    if !ALIAS_NAME_REGEX.is_match(&template.name) {
+        return Err(anyhow!(
+            "Invalid alias name `{}`: use up to 32 letters, digits or `_`, starting with a letter",
+            template.name
+        ));
+    }
+    if templates::is_reserved_roll_word(&template.name) {
+        return Err(anyhow!(
+            "`{}` already means something to `/roll`, pick another name",
+            template.name
+        ));
+    }
+
+    let replacing = custom.iter().any(|alias| alias.name == template.name);
+    if !replacing && custom.len() >= MAX_GUILD_ALIASES {
+        return Err(anyhow!(
+            "This server already has {MAX_GUILD_ALIASES} aliases. Remove one first."
+        ));
+    }
+
+    // Catch aliases that would refer to themselves through other aliases
+    let mut updated = all.clone();
+    updated.insert(template.name.clone(), template.expression.clone());
+    let parameters = template.placeholders();
+    let call = if parameters.is_empty() {
+        template.name.clone()
+    } else {
+        format!(
+            "{}({})",
+            template.name,
+            vec!["1"; parameters.len()].join(", ")
+        )
+    };
+    aliases::expand_user_aliases(&call, |name| updated.get(name).cloned())?;
+
+    Ok(GuildAlias {
+        name: template.name,
+        expression: template.expression,
+    })
+}
+
+/// The `/alias list` message
+pub fn format_alias_list(custom: &[GuildAlias], packs: &[String]) -> String {
+    let mut output = if custom.is_empty() {
+        "📜 This server has no aliases yet. Add one with `/alias add`.".to_string()
+    } else {
+        let mut output = format!(
+            "📜 **Server aliases** ({}/{MAX_GUILD_ALIASES})",
+            custom.len()
+        );
+        for alias in custom {
+            output.push_str(&format!("\n`{}` → `{}`", alias.name, alias.expression));
+        }
+        output
+    };
+    if !packs.is_empty() {
+        output.push_str(&format!(
+            "\n*Also enabled: the {} macro pack{} (see `/macropack`)*",
+            packs
+                .iter()
+                .map(|pack| format!("`{pack}`"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            if packs.len() == 1 { "" } else { "s" }
+        ));
+    }
+    output
+}
@@ -0,0 +1,62 @@
+//! `/calc` slash-command handler for arithmetic without dice.
+//!
+//! `/calc 4*7+2` is the same as `/roll 4*7+2`: whole numbers with `+`, `-`,
+//! `*` and `/`, evaluated left to right like the math after a dice roll, with
+//! an optional `(label)` and `! comment`.  Unlike `/roll` it refuses anything
+//! with dice in it, and a bare number is never expanded to the server's
+//! default system.
+
+use crate::commands::{CommandResponse, RollOrigin, roll};
+use crate::dice::{RollResult, parser, roller};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("calc")
+        .description("Do some quick math without rolling dice")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "expression",
+                "Arithmetic to work out (e.g. 4*7+2)",
+            )
+            .required(true),
+        )
+}
+
+pub async fn run(_ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let expression = command
+        .data
+        .options
+        .first()
+        .and_then(|opt| opt.value.as_str())
+        .unwrap_or_default();
+
+    let display_name = roll::get_display_name(&RollOrigin::from(command));
+    let clean_expr = roll::strip_label_and_comment_from_expression(expression);
+    match calculate(expression) {
+        Ok(result) => Ok(CommandResponse::new(
+            format!("🧮 **{display_name}** Request: `{clean_expr}` {result}"),
+            result.private,
+        )),
+        Err(e) => Ok(CommandResponse::private(format!(
+            "🧮 `{clean_expr}` - ❌ **Error**: {e}"
+        ))),
+    }
+}
+
+/// Evaluate a math-only expression
+pub fn calculate(expression: &str) -> Result<RollResult> {
+    let mut rolls = parser::parse_dice_string(expression)?;
+    let math_only = rolls.len() == 1 && rolls[0].count == 0 && rolls[0].sides == 0;
+    match rolls.pop() {
+        Some(dice) if math_only => Ok(roller::roll_dice(dice)?),
+        _ => Err(anyhow!(
+            "`/calc` only does arithmetic, like `4*7+2`. Use `/roll` for dice"
+        )),
+    }
+}
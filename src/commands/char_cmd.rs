@@ -0,0 +1,133 @@
+//! `/char` slash-command handler for stat modifiers rolled with `@name`.
+//!
+//! | Subcommand | Effect                                                       |
+//! |------------|--------------------------------------------------------------|
+//! | `set`      | Save a modifier, e.g. `/char set stat:str value:3`           |
+//! | `roll`     | Roll `1d20 + @str` (or other dice) for a stat                |
+//!
+//! The modifiers are the `/sheet` stats, per user and per server, so
+//! `/sheet show` lists them and `/sheet remove` deletes them.  Any roll can
+//! refer to one as `@name` (`/roll 2d6 + @str`); `/roll` substitutes the
+//! values with `parser::substitute_variables` before parsing.
+
+use crate::DatabaseContainer;
+use crate::commands::sheet::{self, MAX_SHEET_STATS};
+use crate::commands::{CommandResponse, RollOrigin, roll};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+
+/// Dice `/char roll` adds the modifier to when none are given
+pub const DEFAULT_CHAR_DICE: &str = "1d20";
+
+pub fn register() -> CreateCommand {
+    let stat_option = || {
+        CreateCommandOption::new(CommandOptionType::String, "stat", "Stat name (e.g. str)")
+            .required(true)
+            .max_length(32)
+    };
+
+    CreateCommand::new("char")
+        .description("Save stat modifiers and roll them, or use @name in /roll")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "set", "Save a stat modifier")
+                .add_sub_option(stat_option())
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::Integer,
+                        "value",
+                        "Modifier (e.g. 3 or -1)",
+                    )
+                    .required(true)
+                    // min_int_value only takes a u64, so negative minimums are checked in run()
+                    .max_int_value(sheet::STAT_VALUE_RANGE.1 as u64),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "roll",
+                "Roll with a stat modifier",
+            )
+            .add_sub_option(stat_option())
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "dice",
+                    "Dice to add it to (default 1d20)",
+                )
+                .required(false)
+                .max_length(100),
+            ),
+        )
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing char subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid char subcommand"));
+    };
+    let option = |name: &str| options.iter().find(|opt| opt.name == name);
+    let stat = option("stat")
+        .and_then(|opt| opt.value.as_str())
+        .map(sheet::normalize_stat_name)
+        .ok_or_else(|| anyhow!("Missing stat name"))?;
+
+    match subcommand.name.as_str() {
+        "roll" => {
+            let dice = option("dice").and_then(|opt| opt.value.as_str());
+            let expression = char_roll_expression(dice, &stat);
+            roll::roll_expression(ctx, &RollOrigin::from(command), &expression).await
+        }
+        "set" => {
+            let Some(value) = option("value").and_then(|opt| opt.value.as_i64()) else {
+                return Err(anyhow!("Missing stat value"));
+            };
+            if let Err(e) = sheet::validate_stat(&stat, value) {
+                return Ok(CommandResponse::private(format!("❌ {e}")));
+            }
+
+            let db = ctx
+                .data
+                .read()
+                .await
+                .get::<DatabaseContainer>()
+                .cloned()
+                .ok_or_else(|| anyhow!("Database not available"))?;
+            let guild_id = command.guild_id.map_or(0, |id| id.get() as i64);
+            let user_id = command.user.id.get() as i64;
+            let stats = db.get_character_stats(guild_id, user_id).await?;
+            if !stats.contains_key(&stat) && stats.len() >= MAX_SHEET_STATS {
+                return Ok(CommandResponse::private(format!(
+                    "❌ Your sheet already has {MAX_SHEET_STATS} stats. Remove one with `/sheet remove` first."
+                )));
+            }
+
+            db.set_character_stat(guild_id, user_id, &stat, value)
+                .await?;
+            Ok(CommandResponse::private(format_stat_saved(&stat, value)))
+        }
+        other => Err(anyhow!("Unknown char subcommand: {}", other)),
+    }
+}
+
+/// The roll `/char roll` makes, e.g. `1d20 + @str ! str`
+pub fn char_roll_expression(dice: Option<&str>, stat: &str) -> String {
+    let dice = dice
+        .map(str::trim)
+        .filter(|dice| !dice.is_empty())
+        .unwrap_or(DEFAULT_CHAR_DICE);
+    format!("{dice} + @{stat} ! {stat}")
+}
+
+/// The `/char set` confirmation
+pub fn format_stat_saved(stat: &str, value: i64) -> String {
+    format!(
+        "📝 Set `@{stat}` to **{value:+}**. Roll it with `/char roll stat:{stat}` or `/roll 1d20 + @{stat}`."
+    )
+}
@@ -0,0 +1,264 @@
+//! `/clock` slash-command handler and `clock:<name>` roll integration.
+//!
+//! Progress clocks (as used by Forged in the Dark games) are segmented
+//! counters owned by a channel.  They can be ticked by hand with `/clock tick`
+//! or automatically by flagging a roll with `clock:<name>`, in which case the
+//! roll's outcome decides how many segments fill:
+//!
+//! | Outcome                     | Segments filled |
+//! |-----------------------------|-----------------|
+//! | Failure                     | 2               |
+//! | Partial success             | 1               |
+//! | Success / critical success  | 0               |
+//!
+//! FitD and `bands` rolls use their `fitd_outcome`; any other roll is read the
+//! PbtA way from its total (10+ success, 7–9 partial, 6- failure).
+//!
+//! Clock state lives in the `channel_clocks` table (see `database.rs`).
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::commands::tally::{extract_named_flag, is_valid_tally_name};
+use crate::database::Clock;
+use crate::dice::RollResult;
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::{
+    all::{ChannelId, CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use tracing::warn;
+
+static CLOCK_FLAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|\s)clock:([a-z0-9_-]+)(?:\s|$)")
+        .expect("Failed to compile CLOCK_FLAG_REGEX")
+});
+
+const MIN_CLOCK_SIZE: i64 = 2;
+const MAX_CLOCK_SIZE: i64 = 12;
+
+pub fn register() -> CreateCommand {
+    let name_option = || {
+        CreateCommandOption::new(CommandOptionType::String, "name", "Clock name (e.g. Alarm)")
+            .required(true)
+            .max_length(32)
+    };
+
+    CreateCommand::new("clock")
+        .description("Track progress clocks in this channel")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "create", "Create a clock")
+                .add_sub_option(name_option())
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::Integer,
+                        "size",
+                        "Number of segments (2-12)",
+                    )
+                    .required(true)
+                    .min_int_value(MIN_CLOCK_SIZE as u64)
+                    .max_int_value(MAX_CLOCK_SIZE as u64),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "show", "Show a clock")
+                .add_sub_option(name_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "tick",
+                "Fill or clear segments",
+            )
+            .add_sub_option(name_option())
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "amount",
+                    "Segments to fill (negative clears; default 1)",
+                )
+                .required(false)
+                .max_int_value(MAX_CLOCK_SIZE as u64),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "delete", "Delete a clock")
+                .add_sub_option(name_option()),
+        )
+}
+
+/// Remove a `clock:<name>` flag from a roll expression.
+///
+/// Returns the clock name (if any) and the remaining expression.
+pub fn extract_clock_flag(expr: &str) -> (Option<String>, String) {
+    extract_named_flag(expr, &CLOCK_FLAG_REGEX)
+}
+
+/// Classify a roll for clock purposes, returning the segments to fill and the outcome
+pub fn clock_ticks(result: &RollResult) -> (i64, &'static str) {
+    let outcome = match result.fitd_outcome.as_deref() {
+        Some("CRITICAL SUCCESS") => "critical success",
+        Some("SUCCESS") => "success",
+        Some("PARTIAL SUCCESS") => "partial success",
+        Some(_) => "failure",
+        None => match result.total {
+            10.. => "success",
+            7..=9 => "partial success",
+            _ => "failure",
+        },
+    };
+
+    let ticks = match outcome {
+        "failure" => 2,
+        "partial success" => 1,
+        _ => 0,
+    };
+
+    (ticks, outcome)
+}
+
+/// Render a clock as a segmented emoji bar, e.g. `🕒 **Alarm** 🟥🟥⬜⬜⬜⬜ 2/6`
+pub fn format_clock(clock: &Clock) -> String {
+    let filled = clock.filled.clamp(0, clock.size) as usize;
+    let empty = (clock.size as usize).saturating_sub(filled);
+    let mut output = format!(
+        "🕒 **{}** {}{} `{}/{}`",
+        clock.name,
+        "🟥".repeat(filled),
+        "⬜".repeat(empty),
+        clock.filled,
+        clock.size
+    );
+
+    if clock.filled >= clock.size {
+        output.push_str(" — **FILLED!**");
+    }
+
+    output
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing clock subcommand"));
+    };
+
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid clock subcommand"));
+    };
+
+    let name = options
+        .iter()
+        .find(|opt| opt.name == "name")
+        .and_then(|opt| opt.value.as_str())
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let integer_option = |option_name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == option_name)
+            .and_then(|opt| opt.value.as_i64())
+    };
+
+    if !is_valid_tally_name(&name) {
+        return Ok(CommandResponse::private(
+            "❌ Clock names must be 1-32 letters, numbers, `-` or `_`.".to_string(),
+        ));
+    }
+
+    let data = ctx.data.read().await;
+    let db = data
+        .get::<DatabaseContainer>()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let channel_id = command.channel_id.get() as i64;
+
+    match subcommand.name.as_str() {
+        "create" => {
+            let size = integer_option("size").unwrap_or(4);
+            if !(MIN_CLOCK_SIZE..=MAX_CLOCK_SIZE).contains(&size) {
+                return Ok(CommandResponse::private(format!(
+                    "❌ Clock size must be between {MIN_CLOCK_SIZE} and {MAX_CLOCK_SIZE}."
+                )));
+            }
+
+            let guild_id = command.guild_id.map(|id| id.get() as i64);
+            if !db.create_clock(guild_id, channel_id, &name, size).await? {
+                return Ok(CommandResponse::private(format!(
+                    "❌ A clock named **{name}** already exists in this channel."
+                )));
+            }
+
+            match db.get_clock(channel_id, &name).await? {
+                Some(clock) => Ok(CommandResponse::public(format_clock(&clock))),
+                None => Err(anyhow!("Clock {} missing after creation", name)),
+            }
+        }
+        "show" => match db.get_clock(channel_id, &name).await? {
+            Some(clock) => Ok(CommandResponse::public(format_clock(&clock))),
+            None => Ok(CommandResponse::private(not_found_message(&name))),
+        },
+        "tick" => {
+            let amount = integer_option("amount").unwrap_or(1);
+            if !(-MAX_CLOCK_SIZE..=MAX_CLOCK_SIZE).contains(&amount) {
+                return Ok(CommandResponse::private(format!(
+                    "❌ Tick amount must be between -{MAX_CLOCK_SIZE} and {MAX_CLOCK_SIZE}."
+                )));
+            }
+            match db.tick_clock(channel_id, &name, amount).await? {
+                Some(clock) => Ok(CommandResponse::public(format_clock(&clock))),
+                None => Ok(CommandResponse::private(not_found_message(&name))),
+            }
+        }
+        "delete" => {
+            if db.delete_clock(channel_id, &name).await? {
+                Ok(CommandResponse::public(format!(
+                    "🗑️ Deleted clock **{name}**"
+                )))
+            } else {
+                Ok(CommandResponse::private(not_found_message(&name)))
+            }
+        }
+        other => Err(anyhow!("Unknown clock subcommand: {}", other)),
+    }
+}
+
+/// Tick a clock based on a roll's outcome.
+///
+/// Returns the line appended to the roll output; failures are reported in
+/// that line rather than failing the roll itself.
+pub async fn record_roll(
+    ctx: &Context,
+    channel_id: ChannelId,
+    name: &str,
+    results: &[RollResult],
+) -> String {
+    let data = ctx.data.read().await;
+    let Some(db) = data.get::<DatabaseContainer>() else {
+        return "\n🕒 Clock unavailable: database not connected".to_string();
+    };
+
+    let outcomes: Vec<(i64, &str)> = results.iter().map(clock_ticks).collect();
+    let ticks: i64 = outcomes.iter().map(|(ticks, _)| ticks).sum();
+    let outcome_text = outcomes
+        .iter()
+        .map(|(_, outcome)| *outcome)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    match db.tick_clock(channel_id.get() as i64, name, ticks).await {
+        Ok(Some(clock)) => format!("\n{} (+{ticks}, {outcome_text})", format_clock(&clock)),
+        Ok(None) => format!("\n🕒 {}", not_found_message(name)),
+        Err(e) => {
+            warn!("Failed to tick clock {}: {}", name, e);
+            "\n🕒 Clock update failed".to_string()
+        }
+    }
+}
+
+fn not_found_message(name: &str) -> String {
+    format!(
+        "No clock named **{name}** in this channel. Create it with `/clock create name:{name} size:4`"
+    )
+}
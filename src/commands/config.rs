@@ -0,0 +1,316 @@
+//! `/config` slash-command handler for server-level settings.
+//!
+//! | Subcommand   | Effect                                                   |
+//! |--------------|----------------------------------------------------------|
+//! | `erase-data` | Delete everything Dice Maiden stores for this server     |
+//! | `history`    | View or set the server's roll history level              |
+//! | `log-drain`  | View, set (`url:https://…`) or remove (`url:off`) the server's log drain |
+//!
+//! All subcommands require the **Administrator** permission, enforced both via
+//! `default_member_permissions` and at runtime (by `middleware::Permissions`
+//! for the command, and again here for the confirmation buttons).
+//! Destructive subcommands reply with a private `commands::confirm` prompt;
+//! the buttons are handled by [`handle_component`] using custom IDs prefixed
+//! with `config:`.
+//!
+//! Setting a log drain generates a new signing secret, shown once in the
+//! private reply; setting the same URL again rotates it.  See `log_drain` for
+//! what is posted and how.
+
+use crate::commands::confirm::{self, Choice};
+use crate::commands::{CommandResponse, privacy};
+use crate::database::{HistoryLevel, LogDrainConfig};
+use crate::log_drain::{self, LogDrains};
+use crate::write_queue::{self, DbWrite};
+use crate::{DatabaseContainer, LogDrainContainer};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType, ComponentInteraction},
+    builder::{CreateCommand, CreateCommandOption},
+    model::prelude::Permissions,
+    prelude::Context,
+};
+use tracing::info;
+
+const ERASE_PREFIX: &str = "config:erase-data";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("config")
+        .description("Configure Dice Maiden for this server (administrators only)")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "erase-data",
+            "Permanently delete all data Dice Maiden stores for this server",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "history",
+                "View or set how much roll history is kept for this server",
+            )
+            .add_sub_option(privacy::history_level_option(
+                "level",
+                "Record rolls here: off, totals only, or full (omit to view)",
+            )),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "log-drain",
+                "View, set or remove the HTTPS endpoint sent a copy of every roll",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "url",
+                    "https:// endpoint, or off to remove (omit to view)",
+                )
+                .required(false)
+                .max_length(log_drain::MAX_URL_LENGTH as u16),
+            ),
+        )
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ This command can only be used in servers.".to_string(),
+        ));
+    };
+
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing config subcommand"));
+    };
+
+    match subcommand.name.as_str() {
+        "erase-data" => Ok(confirm::prompt(
+            ERASE_PREFIX,
+            "⚠️ This will permanently delete **all** data Dice Maiden stores for this server \
+             (tallies, clocks, roll history, character sheets, log drain and server settings). This cannot be undone.",
+            "Erase all server data",
+            None,
+        )),
+        "history" => {
+            let requested = match &subcommand.value {
+                CommandDataOptionValue::SubCommand(options) => options
+                    .iter()
+                    .find(|opt| opt.name == "level")
+                    .and_then(|opt| opt.value.as_str()),
+                _ => return Err(anyhow!("Invalid config subcommand")),
+            };
+
+            let db = ctx
+                .data
+                .read()
+                .await
+                .get::<DatabaseContainer>()
+                .cloned()
+                .ok_or_else(|| anyhow!("Database not available"))?;
+            let guild_id = guild_id.get() as i64;
+
+            match requested {
+                Some(value) => {
+                    let Some(level) = HistoryLevel::parse(value) else {
+                        return Ok(CommandResponse::private(format!(
+                            "❌ Unknown history level `{value}`. Use off, totals or full."
+                        )));
+                    };
+                    db.set_privacy_level("guild", guild_id, level).await?;
+                    info!(
+                        "Set history level for guild {} to {} (by {})",
+                        guild_id,
+                        level.as_str(),
+                        command.user.id
+                    );
+                    Ok(CommandResponse::private(format!(
+                        "🔒 Roll history for this server is now **{}**. \
+                         Members can still choose a stricter level with `/privacy`.",
+                        level.description()
+                    )))
+                }
+                None => {
+                    let level = db
+                        .get_privacy_level("guild", guild_id)
+                        .await?
+                        .unwrap_or_default();
+                    Ok(CommandResponse::private(format!(
+                        "🔒 Roll history for this server: **{}**.",
+                        level.description()
+                    )))
+                }
+            }
+        }
+        "log-drain" => {
+            let requested = match &subcommand.value {
+                CommandDataOptionValue::SubCommand(options) => options
+                    .iter()
+                    .find(|opt| opt.name == "url")
+                    .and_then(|opt| opt.value.as_str())
+                    .map(str::trim),
+                _ => return Err(anyhow!("Invalid config subcommand")),
+            };
+
+            let (db, drains) = {
+                let data = ctx.data.read().await;
+                (
+                    data.get::<DatabaseContainer>()
+                        .cloned()
+                        .ok_or_else(|| anyhow!("Database not available"))?,
+                    data.get::<LogDrainContainer>()
+                        .cloned()
+                        .ok_or_else(|| anyhow!("Log drains not available"))?,
+                )
+            };
+            let guild_id = guild_id.get() as i64;
+
+            let content = match requested {
+                None => format_log_drain(
+                    db.get_log_drain(guild_id).await?.as_ref(),
+                    &drains,
+                    guild_id,
+                ),
+                Some(value) if value.eq_ignore_ascii_case("off") => {
+                    if db.remove_log_drain(guild_id).await? {
+                        drains.update_config(guild_id, None);
+                        info!(
+                            "Removed log drain for guild {} (by {})",
+                            guild_id, command.user.id
+                        );
+                        "🗑️ Removed this server's log drain. Rolls waiting to be sent were discarded."
+                            .to_string()
+                    } else {
+                        "This server has no log drain.".to_string()
+                    }
+                }
+                Some(value) => {
+                    let url = match log_drain::validate_url(value) {
+                        Ok(url) => url,
+                        Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+                    };
+                    let config = LogDrainConfig {
+                        url,
+                        secret: log_drain::generate_secret()?,
+                    };
+                    db.set_log_drain(guild_id, &config).await?;
+                    drains.update_config(guild_id, Some(config.clone()));
+                    info!(
+                        "Set log drain for guild {} (by {})",
+                        guild_id, command.user.id
+                    );
+                    format!(
+                        "📤 Rolls in this server will be posted to <{}> in batches.\n\
+                         Signing secret (shown only now, setting the URL again replaces it): ||`{}`||\n\
+                         Check each request's `{}` header against it; see the README for the format.",
+                        config.url,
+                        config.secret,
+                        log_drain::SIGNATURE_HEADER
+                    )
+                }
+            };
+            Ok(CommandResponse::private(content))
+        }
+        other => Err(anyhow!("Unknown config subcommand: {}", other)),
+    }
+}
+
+/// The `/config log-drain` status message
+pub fn format_log_drain(
+    config: Option<&LogDrainConfig>,
+    drains: &LogDrains,
+    guild_id: i64,
+) -> String {
+    let Some(config) = config else {
+        return "📤 This server has no log drain. Set one with `/config log-drain url:https://…`."
+            .to_string();
+    };
+    let mut output = format!("📤 Rolls are posted to <{}>.", config.url);
+    if let Some(status) = drains.status(guild_id) {
+        output.push_str(&format!("\nWaiting to be sent: {}", status.pending));
+        if status.failures > 0 {
+            output.push_str(&format!(
+                "\n⚠️ The last {} attempt{} failed",
+                status.failures,
+                if status.failures == 1 { "" } else { "s" }
+            ));
+            if let Some(retry_in) = status.retry_in {
+                output.push_str(&format!("; retrying in {}s", retry_in.as_secs().max(1)));
+            }
+        }
+        if status.dropped > 0 {
+            output.push_str(&format!(
+                "\n⚠️ {} roll{} dropped while the endpoint was unreachable",
+                status.dropped,
+                if status.dropped == 1 {
+                    " was"
+                } else {
+                    "s were"
+                }
+            ));
+        }
+    }
+    output
+}
+
+/// Handle the `config:` confirmation buttons, returning the replacement message
+pub async fn handle_component(ctx: &Context, component: &ComponentInteraction) -> Result<String> {
+    match confirm::parse_now(ERASE_PREFIX, &component.data.custom_id) {
+        Some(Choice::Cancelled) => Ok("Data erasure cancelled. Nothing was deleted.".to_string()),
+        Some(Choice::Expired) => Ok(confirm::expired_message("/config erase-data")),
+        Some(Choice::Confirmed(_)) => {
+            let Some(guild_id) = component.guild_id else {
+                return Ok("❌ This can only be used in servers.".to_string());
+            };
+
+            // Permissions may have changed since the prompt was shown
+            if !is_administrator(component.member.as_ref().and_then(|m| m.permissions)) {
+                return Ok(
+                    "❌ You need the 'Administrator' permission to erase server data.".to_string(),
+                );
+            }
+
+            let db = ctx
+                .data
+                .read()
+                .await
+                .get::<DatabaseContainer>()
+                .cloned()
+                .ok_or_else(|| anyhow!("Database not available"))?;
+
+            let guild_id = guild_id.get() as i64;
+            let requested_by = component.user.id.get() as i64;
+            let deleted = db.erase_guild_data(guild_id).await?;
+            if let Some(drains) = ctx.data.read().await.get::<LogDrainContainer>() {
+                drains.update_config(guild_id, None);
+            }
+            write_queue::enqueue(
+                ctx,
+                DbWrite::DataErasure {
+                    scope: "guild",
+                    target_id: guild_id,
+                    requested_by,
+                    rows_deleted: deleted,
+                },
+            )
+            .await;
+
+            info!(
+                "Erased {} rows of guild data for {} (requested by {})",
+                deleted, guild_id, requested_by
+            );
+
+            Ok(format!(
+                "🗑️ Erased all Dice Maiden data for this server ({deleted} records)."
+            ))
+        }
+        None => Err(anyhow!(
+            "Unknown config button: {}",
+            component.data.custom_id
+        )),
+    }
+}
+
+pub(crate) fn is_administrator(permissions: Option<Permissions>) -> bool {
+    permissions.is_some_and(|perms| perms.administrator())
+}
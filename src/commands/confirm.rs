@@ -0,0 +1,116 @@
+//! Two-stage confirmation for destructive commands.
+//!
+//! A destructive command replies with [`prompt`]: a private warning with a red
+//! confirm button and a Cancel button.  Nothing is changed until the button
+//! is pressed; the command's `handle_component` passes the clicked custom ID
+//! to [`parse`] and only acts on [`Choice::Confirmed`].
+//!
+//! Custom IDs carry the time the prompt was shown and an optional payload,
+//! such as how many messages `/purge` deletes:
+//!
+//! ```text
+//! <prefix>:confirm:<unix seconds>[:<payload>]
+//! <prefix>:cancel
+//! ```
+//!
+//! so a confirmation pressed more than [`CONFIRM_TIMEOUT`] later is refused
+//! without keeping any state between the two steps.  Prompts from before
+//! the timestamp was added count as expired.
+//!
+//! | Command              | Prefix              | Payload       |
+//! |----------------------|---------------------|---------------|
+//! | `/purge`             | `purge`             | Message count |
+//! | `/config erase-data` | `config:erase-data` |               |
+//! | `/forgetme`          | `forgetme`          |               |
+
+use crate::commands::CommandResponse;
+use chrono::Utc;
+use serenity::{
+    all::ButtonStyle,
+    builder::{CreateActionRow, CreateButton},
+};
+use std::time::Duration;
+
+/// How long a prompt's confirm button works
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// What a pressed confirmation button means
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice<'a> {
+    /// Go ahead, with the prompt's payload if it had one
+    Confirmed(Option<&'a str>),
+    Cancelled,
+    /// Pressed after [`CONFIRM_TIMEOUT`]; nothing should happen
+    Expired,
+}
+
+/// A private `warning` with a `confirm_label` danger button and Cancel
+pub fn prompt(
+    prefix: &str,
+    warning: &str,
+    confirm_label: &str,
+    payload: Option<&str>,
+) -> CommandResponse {
+    let issued = Utc::now().timestamp();
+    CommandResponse::private(format!(
+        "{warning}\n-# Confirm within {} minutes.",
+        CONFIRM_TIMEOUT.as_secs() / 60
+    ))
+    .with_components(vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(confirm_id(prefix, issued, payload))
+            .label(confirm_label)
+            .style(ButtonStyle::Danger),
+        CreateButton::new(cancel_id(prefix))
+            .label("Cancel")
+            .style(ButtonStyle::Secondary),
+    ])])
+}
+
+/// Custom ID of the confirm button of a prompt shown at `issued`
+pub fn confirm_id(prefix: &str, issued: i64, payload: Option<&str>) -> String {
+    match payload {
+        Some(payload) => format!("{prefix}:confirm:{issued}:{payload}"),
+        None => format!("{prefix}:confirm:{issued}"),
+    }
+}
+
+pub fn cancel_id(prefix: &str) -> String {
+    format!("{prefix}:cancel")
+}
+
+/// Read a button of a `prefix` prompt pressed at `now` (unix seconds),
+/// or `None` if the custom ID isn't one
+pub fn parse<'a>(prefix: &str, custom_id: &'a str, now: i64) -> Option<Choice<'a>> {
+    let action = custom_id.strip_prefix(prefix)?.strip_prefix(':')?;
+    if action == "cancel" {
+        return Some(Choice::Cancelled);
+    }
+
+    let rest = action.strip_prefix("confirm")?;
+    if rest.is_empty() {
+        return Some(Choice::Expired);
+    }
+    let rest = rest.strip_prefix(':')?;
+    let (issued, payload) = match rest.split_once(':') {
+        Some((issued, payload)) => (issued, Some(payload)),
+        None => (rest, None),
+    };
+    let issued: i64 = issued.parse().ok()?;
+    // A prompt from a process whose clock runs slightly ahead is still fresh
+    if now.saturating_sub(issued) > CONFIRM_TIMEOUT.as_secs() as i64 {
+        return Some(Choice::Expired);
+    }
+    Some(Choice::Confirmed(payload))
+}
+
+/// [`parse`] at the current time
+pub fn parse_now<'a>(prefix: &str, custom_id: &'a str) -> Option<Choice<'a>> {
+    parse(prefix, custom_id, Utc::now().timestamp())
+}
+
+/// The reply to an expired confirmation of `command`
+pub fn expired_message(command: &str) -> String {
+    format!(
+        "⌛ This confirmation expired, so nothing was changed. Run `{command}` again if you still want to."
+    )
+}
@@ -0,0 +1,98 @@
+//! `/debugparse` owner-only dump of how an expression is parsed.
+//!
+//! Shows the expansion steps (server aliases and macros, game-system
+//! aliases, roll sets, …) and the resulting [`DiceRoll`](crate::dice::DiceRoll)
+//! list with every modifier, as JSON, without rolling anything.  Dumps too
+//! long for a message are attached as `parse.json`.
+//!
+//! The command is restricted to the application owner (or team members),
+//! read from [`OwnersContainer`](crate::OwnersContainer).
+
+use crate::commands::roll::expand_guild_aliases;
+use crate::commands::{CommandResponse, RollOrigin, require_owner};
+use crate::dice::DISCORD_MESSAGE_LIMIT;
+use crate::dice::parser::{self, ParseStep, ParseTrace};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandInteraction, CommandOptionType},
+    builder::{CreateAttachment, CreateCommand, CreateCommandOption},
+    model::prelude::Permissions,
+    prelude::Context,
+};
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("debugparse")
+        .description("Show how a dice expression is parsed (bot owner only)")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "expr", "Expression to parse")
+                .required(true)
+                .max_length(1000),
+        )
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    if let Some(denied) = require_owner(ctx, command).await {
+        return Ok(denied);
+    }
+
+    let expr = command
+        .data
+        .options
+        .iter()
+        .find(|opt| opt.name == "expr")
+        .and_then(|opt| opt.value.as_str())
+        .ok_or_else(|| anyhow!("Missing expression"))?
+        .trim();
+
+    let origin = RollOrigin::from(command);
+    let trace = match expand_guild_aliases(ctx, &origin, expr).await {
+        Ok(expanded) if expanded != expr => {
+            let mut trace = parser::trace_dice_string(&expanded);
+            trace.input = expr.to_string();
+            trace.steps.insert(
+                0,
+                ParseStep {
+                    stage: "server aliases and macros",
+                    expression: expanded,
+                },
+            );
+            trace
+        }
+        Ok(_) => parser::trace_dice_string(expr),
+        Err(e) => ParseTrace {
+            input: expr.to_string(),
+            steps: Vec::new(),
+            rolls: Vec::new(),
+            error: Some(e.to_string()),
+        },
+    };
+
+    format_trace(&trace)
+}
+
+/// The trace as a private JSON reply, attached as `parse.json` if it doesn't
+/// fit in a message
+pub fn format_trace(trace: &ParseTrace) -> Result<CommandResponse> {
+    let json = serde_json::to_string_pretty(trace)?;
+    let summary = match &trace.error {
+        Some(e) => format!("❌ `{}` doesn't parse: {e}", trace.input),
+        None => format!(
+            "🔍 `{}` parses to {} roll(s) in {} step(s)",
+            trace.input,
+            trace.rolls.len(),
+            trace.steps.len()
+        ),
+    };
+
+    let content = format!("{summary}\n```json\n{json}\n```");
+    if content.chars().count() <= DISCORD_MESSAGE_LIMIT {
+        return Ok(CommandResponse::private(content));
+    }
+    Ok(
+        CommandResponse::private(summary).with_attachments(vec![CreateAttachment::bytes(
+            json.into_bytes(),
+            "parse.json",
+        )]),
+    )
+}
@@ -0,0 +1,394 @@
+//! `/draw` slash-command handler for card decks.
+//!
+//! | Subcommand | Effect                                                       |
+//! |------------|--------------------------------------------------------------|
+//! | `cards`    | Draw cards from a deck (default `standard`)                  |
+//! | `init`     | Deal initiative cards from the `jokers` deck, e.g. `Alice, Bob` |
+//! | `shuffle`  | Return every card of a deck and shuffle it                   |
+//! | `discard`  | Move the cards in play to the discard pile                   |
+//! | `create`   | Save (or replace) a custom deck, e.g. `3x Goblin; Ogre`      |
+//! | `list`     | Show the built-in and server decks                           |
+//! | `delete`   | Delete a custom deck                                         |
+//!
+//! Decks, piles and initiative ordering are described in [`crate::deck`].
+//! Every channel draws from its own copy of a deck, kept in the
+//! `channel_decks` table.  Creating and deleting custom decks needs the
+//! **Administrator** permission or the GM role chosen in `/setup`.
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::commands::session::is_game_master;
+use crate::commands::tally::is_valid_tally_name;
+use crate::database::{ChannelDeck, Database};
+use crate::deck::{
+    self, Deck, DeckState, Draw, InitiativeDeal, JOKERS_DECK, MAX_DRAW, MAX_GUILD_DECKS,
+    STANDARD_DECK,
+};
+use crate::dice::rng;
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+pub fn register() -> CreateCommand {
+    let deck_option = || {
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "deck",
+            "Deck name (default standard)",
+        )
+        .required(false)
+        .max_length(32)
+    };
+    let name_option = || {
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "name",
+            "Deck name (e.g. encounters)",
+        )
+        .required(true)
+        .max_length(32)
+    };
+
+    CreateCommand::new("draw")
+        .description("Draw cards from this channel's decks")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "cards", "Draw cards")
+                .add_sub_option(deck_option())
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::Integer,
+                        "count",
+                        "Cards to draw (default 1)",
+                    )
+                    .required(false)
+                    .min_int_value(1)
+                    .max_int_value(MAX_DRAW as u64),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "init",
+                "Deal initiative cards (Deadlands / Savage Worlds)",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "names",
+                    "Combatants separated by commas (e.g. Alice, Bob, Goblins)",
+                )
+                .required(true)
+                .max_length(1000),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "shuffle",
+                "Gather every card and shuffle the deck",
+            )
+            .add_sub_option(deck_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "discard",
+                "Discard the cards in play",
+            )
+            .add_sub_option(deck_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "create",
+                "Save a custom deck (admins or GM role)",
+            )
+            .add_sub_option(name_option())
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "cards",
+                    "Cards separated by ; (e.g. 3x Goblin; 2x Wolf; Ogre)",
+                )
+                .required(true)
+                .max_length(4000),
+            ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "Show the decks you can draw from",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "delete",
+                "Delete a custom deck (admins or GM role)",
+            )
+            .add_sub_option(name_option()),
+        )
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing draw subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid draw subcommand"));
+    };
+    let option_str = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| opt.value.as_str())
+            .map(str::trim)
+    };
+    let name = option_str("name")
+        .or(option_str("deck"))
+        .unwrap_or(STANDARD_DECK)
+        .to_lowercase();
+
+    if !is_valid_tally_name(&name) {
+        return Ok(CommandResponse::private(
+            "❌ Deck names must be 1-32 letters, numbers, `-` or `_`.".to_string(),
+        ));
+    }
+    let guild_id = command.guild_id.map(|id| id.get() as i64);
+    if matches!(subcommand.name.as_str(), "create" | "delete") {
+        if guild_id.is_none() {
+            return Ok(CommandResponse::private(
+                "❌ Custom decks can only be made in servers.".to_string(),
+            ));
+        }
+        if !is_game_master(ctx, command).await {
+            return Ok(CommandResponse::private(
+                "❌ You need the 'Administrator' permission or the server's GM role to do this."
+                    .to_string(),
+            ));
+        }
+    }
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let channel_id = command.channel_id.get() as i64;
+    let decks = match guild_id {
+        Some(guild_id) => db.get_guild_decks(guild_id).await?,
+        None => BTreeMap::new(),
+    };
+
+    match subcommand.name.as_str() {
+        "cards" | "shuffle" | "discard" => {
+            let deck = match find_deck(&decks, &name) {
+                Ok(deck) => deck,
+                Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+            };
+            let mut state = load_state(&db, channel_id, &deck).await?;
+            let content = match subcommand.name.as_str() {
+                "cards" => {
+                    let count = options
+                        .iter()
+                        .find(|opt| opt.name == "count")
+                        .and_then(|opt| opt.value.as_i64())
+                        .unwrap_or(1)
+                        .clamp(1, MAX_DRAW as i64) as usize;
+                    match state.draw(count, &mut rng::get_dice_rng()) {
+                        Ok(draw) => format_draw(&deck, &draw, &state),
+                        Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+                    }
+                }
+                "shuffle" => {
+                    state = DeckState::shuffled(deck.cards.len(), &mut rng::get_dice_rng());
+                    format!(
+                        "🔀 Shuffled **{}** ({} cards).",
+                        deck.name,
+                        deck.cards.len()
+                    )
+                }
+                _ => {
+                    let discarded = state.discard_in_play(deck.cards.len());
+                    format!(
+                        "🗑️ Discarded {discarded} {} from **{}** · {} left to draw",
+                        if discarded == 1 { "card" } else { "cards" },
+                        deck.name,
+                        state.draw_pile.len()
+                    )
+                }
+            };
+            save_state(&db, guild_id, channel_id, &deck, &state).await?;
+            Ok(CommandResponse::public(content))
+        }
+        "init" => {
+            let names = match deck::parse_names(option_str("names").unwrap_or_default()) {
+                Ok(names) => names,
+                Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+            };
+            let deck = Deck::playing_cards(true);
+            let mut state = load_state(&db, channel_id, &deck).await?;
+            match deck::deal_initiative(&mut state, &names, &mut rng::get_dice_rng()) {
+                Ok(deal) => {
+                    save_state(&db, guild_id, channel_id, &deck, &state).await?;
+                    Ok(CommandResponse::public(format_initiative(&deck, &deal)))
+                }
+                Err(e) => Ok(CommandResponse::private(format!("❌ {e}"))),
+            }
+        }
+        "create" => {
+            let guild_id = guild_id.ok_or_else(|| anyhow!("Custom decks need a server"))?;
+            if !decks.contains_key(&name) && decks.len() >= MAX_GUILD_DECKS {
+                return Ok(CommandResponse::private(format!(
+                    "❌ This server already has {MAX_GUILD_DECKS} decks. Delete one first."
+                )));
+            }
+            let deck = match Deck::parse(&name, option_str("cards").unwrap_or_default()) {
+                Ok(deck) => deck,
+                Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+            };
+            db.set_guild_deck(guild_id, &deck.name, &deck.definition())
+                .await?;
+            info!("Saved deck {} for guild {}", deck.name, guild_id);
+            Ok(CommandResponse::private(format!(
+                "✅ Saved deck **{}** ({} cards). Draw from it with `/draw cards deck:{}`.",
+                deck.name,
+                deck.cards.len(),
+                deck.name
+            )))
+        }
+        "list" => Ok(CommandResponse::private(format_deck_list(&decks))),
+        "delete" => {
+            let guild_id = guild_id.ok_or_else(|| anyhow!("Custom decks need a server"))?;
+            if db.remove_guild_deck(guild_id, &name).await? {
+                Ok(CommandResponse::private(format!(
+                    "🗑️ Deleted deck **{name}**."
+                )))
+            } else {
+                Ok(CommandResponse::private(format!(
+                    "❌ {}",
+                    not_found_message(&name)
+                )))
+            }
+        }
+        other => Err(anyhow!("Unknown draw subcommand: {}", other)),
+    }
+}
+
+/// A built-in deck or one of the server's custom decks
+pub fn find_deck(decks: &BTreeMap<String, String>, name: &str) -> Result<Deck> {
+    if let Some(deck) = Deck::builtin(name) {
+        return Ok(deck);
+    }
+    let definition = decks
+        .get(name)
+        .ok_or_else(|| anyhow!("{}", not_found_message(name)))?;
+    Deck::parse(name, definition).map_err(|e| anyhow!("Deck **{name}** can't be drawn: {e}"))
+}
+
+// The channel's piles for a deck, or a fresh shuffle if it has none (or they
+// no longer fit the deck)
+async fn load_state(db: &Database, channel_id: i64, deck: &Deck) -> Result<DeckState> {
+    let size = deck.cards.len();
+    let stored = db.get_channel_deck(channel_id, &deck.name).await?;
+    Ok(stored
+        .and_then(|stored| {
+            let state = DeckState::parse(&stored.draw_pile, &stored.discard_pile, size);
+            if state.is_none() {
+                warn!(
+                    "Stored deck {} in channel {} is invalid, reshuffling",
+                    deck.name, channel_id
+                );
+            }
+            state
+        })
+        .unwrap_or_else(|| DeckState::shuffled(size, &mut rng::get_dice_rng())))
+}
+
+async fn save_state(
+    db: &Database,
+    guild_id: Option<i64>,
+    channel_id: i64,
+    deck: &Deck,
+    state: &DeckState,
+) -> Result<()> {
+    let stored = ChannelDeck {
+        draw_pile: DeckState::encode(&state.draw_pile),
+        discard_pile: DeckState::encode(&state.discard_pile),
+    };
+    db.set_channel_deck(guild_id, channel_id, &deck.name, &stored)
+        .await
+}
+
+/// A draw, e.g. `🃏 **standard** → A♠, 10♥ · 50 left`
+pub fn format_draw(deck: &Deck, draw: &Draw, state: &DeckState) -> String {
+    let cards = draw
+        .cards
+        .iter()
+        .map(|&card| format!("**{}**", deck.card(card)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut output = format!(
+        "🃏 **{}** → {cards} · {} left",
+        deck.name,
+        state.draw_pile.len()
+    );
+    if draw.reshuffled {
+        output.push_str("\n-# The discard pile was shuffled back in.");
+    }
+    output
+}
+
+/// The `/draw init` message: combatants in turn order with their cards
+pub fn format_initiative(deck: &Deck, deal: &InitiativeDeal) -> String {
+    let mut output = String::from("🃏 **Initiative**");
+    if deal.shuffled {
+        output.push_str("\n-# A Joker came up last round, so the deck was shuffled.");
+    }
+    for (position, (name, card)) in deal.order.iter().enumerate() {
+        output.push_str(&format!(
+            "\n{}. **{}** {name}",
+            position + 1,
+            deck.card(*card)
+        ));
+    }
+    if deal.joker() {
+        output.push_str(
+            "\n🃏 **Joker!** Act whenever you like this round, with +2 to Trait and damage rolls. \
+             The deck is shuffled before the next deal.",
+        );
+    }
+    output
+}
+
+/// The `/draw list` message
+pub fn format_deck_list(decks: &BTreeMap<String, String>) -> String {
+    let mut output = format!(
+        "🃏 **Decks**\n`{STANDARD_DECK}` — 52 playing cards\n`{JOKERS_DECK}` — 52 playing cards and 2 Jokers (used by `/draw init`)"
+    );
+    if decks.is_empty() {
+        output.push_str("\nNo custom decks yet. Admins and GMs can add one with `/draw create`.");
+        return output;
+    }
+    output.push_str(&format!(
+        "\n**Server decks** ({}/{MAX_GUILD_DECKS})",
+        decks.len()
+    ));
+    for (name, definition) in decks {
+        match Deck::parse(name, definition) {
+            Ok(deck) => output.push_str(&format!("\n`{name}` — {} cards", deck.cards.len())),
+            Err(_) => output.push_str(&format!("\n`{name}` — invalid")),
+        }
+    }
+    output
+}
+
+fn not_found_message(name: &str) -> String {
+    format!("There's no deck named `{name}`. See `/draw list`.")
+}
@@ -0,0 +1,394 @@
+//! `/followup` slash-command handler and the quick-action buttons it adds to
+//! rolls.
+//!
+//! | Subcommand | Effect                                                   |
+//! |------------|----------------------------------------------------------|
+//! | `add`      | Add (or replace) a button for rolls with a keyword       |
+//! | `remove`   | Remove one button of a keyword, or all of them           |
+//! | `list`     | Show the server's follow-ups                             |
+//!
+//! A follow-up links a word in roll comments to a button and the expression
+//! it rolls.  With `attack` → "Roll damage" `2d6+3 ! Damage`, every
+//! `/roll 1d20+5 ! Attack` shows a ⚔ **Roll damage** button.  Keywords match
+//! like `/keyword` rules: whole words, ignoring case.  Clicking a button rolls
+//! its expression as a new message, for the original roller only, so macros,
+//! server aliases and `/sheet` stats are those of the roller.
+//!
+//! The expressions behind a message's buttons are kept in memory
+//! ([`QuickActionContainer`](crate::QuickActionContainer)) under a token in
+//! each button's custom ID (`action:<token>:<index>`).  That keeps them clear
+//! of Discord's 100-character custom IDs and makes a button roll what it was
+//! created with even after its follow-up changes.  The store holds the
+//! newest [`QUICK_ACTION_CAPACITY`] messages and is lost on restart; older
+//! buttons then say they have expired.
+//!
+//! Follow-ups live in the `follow_ups` table (see `database.rs`).  Changing
+//! them requires the **Administrator** permission, enforced both via
+//! `default_member_permissions` and at runtime by `middleware::Permissions`.
+
+use crate::commands::token_store::TokenStore;
+use crate::commands::{CommandResponse, RollOrigin, keyword, roll};
+use crate::database::FollowUp;
+use crate::dice::RollResult;
+use crate::{DatabaseContainer, QuickActionContainer};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{
+        ButtonStyle, CommandDataOptionValue, CommandInteraction, CommandOptionType,
+        ComponentInteraction,
+    },
+    builder::{CreateActionRow, CreateButton, CreateCommand, CreateCommandOption},
+    model::prelude::Permissions,
+    prelude::Context,
+};
+use tracing::{info, warn};
+
+/// Custom ID prefix of quick-action buttons: `action:<token>:<index>`
+pub const ACTION_PREFIX: &str = "action:";
+
+/// Follow-ups a server may define
+pub const MAX_FOLLOW_UPS: usize = 25;
+
+/// Buttons shown under one roll (one row of a Discord message)
+pub const MAX_ACTION_BUTTONS: usize = 5;
+
+/// Messages whose buttons are remembered before the oldest are dropped
+pub const QUICK_ACTION_CAPACITY: usize = 10_000;
+
+// Longest button label and expression
+const MAX_LABEL_LEN: usize = 32;
+const MAX_EXPRESSION_LEN: usize = 200;
+
+/// The buttons of one roll message: who may use them and what they roll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingActions {
+    pub user_id: u64,
+    pub actions: Vec<FollowUp>,
+}
+
+/// Quick-action buttons of recent roll messages, by token
+pub type QuickActions = TokenStore<PendingActions, QUICK_ACTION_CAPACITY>;
+
+pub fn register() -> CreateCommand {
+    let keyword_option = || {
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "keyword",
+            "Word to look for in roll comments (e.g. attack)",
+        )
+        .required(true)
+        .max_length(32)
+    };
+    let label_option = || {
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "label",
+            "Button label (e.g. Roll damage)",
+        )
+        .max_length(MAX_LABEL_LEN as u16)
+    };
+
+    CreateCommand::new("followup")
+        .description(
+            "Add quick-action buttons to rolls by words in their comments (administrators only)",
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "add",
+                "Add or replace a follow-up button",
+            )
+            .add_sub_option(keyword_option())
+            .add_sub_option(label_option().required(true))
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "dice",
+                    "Expression the button rolls (e.g. 2d6+3 ! Damage)",
+                )
+                .required(true)
+                .max_length(MAX_EXPRESSION_LEN as u16),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "remove",
+                "Remove a follow-up button, or every button of a keyword",
+            )
+            .add_sub_option(keyword_option())
+            .add_sub_option(label_option()),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "Show this server's follow-up buttons",
+        ))
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ This command can only be used in servers.".to_string(),
+        ));
+    };
+
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing followup subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid followup subcommand"));
+    };
+    let option_str = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| opt.value.as_str())
+            .map(str::trim)
+    };
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let guild_id = guild_id.get() as i64;
+    let follow_ups = db.get_follow_ups(guild_id).await?;
+
+    if subcommand.name == "list" {
+        return Ok(CommandResponse::private(format_follow_up_list(&follow_ups)));
+    }
+
+    let keyword = option_str("keyword").unwrap_or_default().to_lowercase();
+    if !keyword::is_valid_keyword(&keyword) {
+        return Ok(CommandResponse::private(
+            "❌ Keywords must be 1-32 letters, numbers, `-` or `_`.".to_string(),
+        ));
+    }
+
+    match subcommand.name.as_str() {
+        "add" => {
+            let follow_up = FollowUp {
+                keyword,
+                label: option_str("label").unwrap_or_default().to_string(),
+                expression: option_str("dice").unwrap_or_default().to_string(),
+            };
+            if let Err(e) = validate_follow_up(&follow_up, &follow_ups) {
+                return Ok(CommandResponse::private(format!("❌ {e}")));
+            }
+            db.set_follow_up(guild_id, &follow_up).await?;
+            info!(
+                "Set follow-up {}/{} for guild {} (by {})",
+                follow_up.keyword, follow_up.label, guild_id, command.user.id
+            );
+            Ok(CommandResponse::private(format!(
+                "✅ Rolls with `{}` in their comment get a **{}** button that rolls `{}`.",
+                follow_up.keyword, follow_up.label, follow_up.expression
+            )))
+        }
+        "remove" => {
+            let label = option_str("label");
+            match db.remove_follow_ups(guild_id, &keyword, label).await? {
+                0 => Ok(CommandResponse::private(format!(
+                    "❌ No follow-up for `{keyword}`{}. See `/followup list`.",
+                    label.map_or(String::new(), |label| format!(" labelled **{label}**"))
+                ))),
+                1 => Ok(CommandResponse::private(format!(
+                    "🗑️ Removed 1 follow-up for `{keyword}`."
+                ))),
+                removed => Ok(CommandResponse::private(format!(
+                    "🗑️ Removed {removed} follow-ups for `{keyword}`."
+                ))),
+            }
+        }
+        other => Err(anyhow!("Unknown followup subcommand: {}", other)),
+    }
+}
+
+/// Check a new follow-up against the server's existing ones
+pub fn validate_follow_up(follow_up: &FollowUp, follow_ups: &[FollowUp]) -> Result<()> {
+    if !keyword::is_valid_keyword(&follow_up.keyword) {
+        return Err(anyhow!(
+            "Keywords must be 1-32 letters, numbers, `-` or `_`."
+        ));
+    }
+    if follow_up.label.is_empty() || follow_up.label.chars().count() > MAX_LABEL_LEN {
+        return Err(anyhow!("Labels must be 1-{MAX_LABEL_LEN} characters."));
+    }
+    if follow_up.expression.is_empty() || follow_up.expression.len() > MAX_EXPRESSION_LEN {
+        return Err(anyhow!(
+            "The expression must be 1-{MAX_EXPRESSION_LEN} characters."
+        ));
+    }
+    let replacing = follow_ups
+        .iter()
+        .any(|existing| same_button(existing, follow_up));
+    if !replacing {
+        if follow_ups.len() >= MAX_FOLLOW_UPS {
+            return Err(anyhow!(
+                "This server already has {MAX_FOLLOW_UPS} follow-ups. Remove one first."
+            ));
+        }
+        let for_keyword = follow_ups
+            .iter()
+            .filter(|existing| existing.keyword == follow_up.keyword)
+            .count();
+        if for_keyword >= MAX_ACTION_BUTTONS {
+            return Err(anyhow!(
+                "`{}` already has {MAX_ACTION_BUTTONS} buttons. Remove one first.",
+                follow_up.keyword
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Labels are compared ignoring case, like the `follow_ups` key
+fn same_button(a: &FollowUp, b: &FollowUp) -> bool {
+    a.keyword == b.keyword && a.label.eq_ignore_ascii_case(&b.label)
+}
+
+/// The follow-ups whose keyword is in a comment of the roll's results, in
+/// list order, without repeated labels and at most [`MAX_ACTION_BUTTONS`]
+pub fn match_follow_ups(follow_ups: &[FollowUp], results: &[RollResult]) -> Vec<FollowUp> {
+    let comments: Vec<&str> = results
+        .iter()
+        .filter_map(|result| result.comment.as_deref())
+        .collect();
+
+    let mut matched: Vec<FollowUp> = Vec::new();
+    for follow_up in follow_ups.iter().filter(|follow_up| {
+        comments
+            .iter()
+            .any(|comment| keyword::comment_has_keyword(comment, &follow_up.keyword))
+    }) {
+        if matched.len() == MAX_ACTION_BUTTONS {
+            break;
+        }
+        if !matched
+            .iter()
+            .any(|existing| existing.label.eq_ignore_ascii_case(&follow_up.label))
+        {
+            matched.push(follow_up.clone());
+        }
+    }
+    matched
+}
+
+/// A row of buttons for `actions`, stored under `token`
+pub fn action_buttons(token: u64, actions: &[FollowUp]) -> CreateActionRow {
+    CreateActionRow::Buttons(
+        actions
+            .iter()
+            .enumerate()
+            .map(|(index, action)| {
+                CreateButton::new(format!("{ACTION_PREFIX}{token}:{index}"))
+                    .label(&action.label)
+                    .emoji('⚔')
+                    .style(ButtonStyle::Primary)
+            })
+            .collect(),
+    )
+}
+
+/// The token and button index of a quick-action button's custom ID
+pub fn parse_action_id(custom_id: &str) -> Option<(u64, usize)> {
+    let (token, index) = custom_id.strip_prefix(ACTION_PREFIX)?.split_once(':')?;
+    Some((token.parse().ok()?, index.parse().ok()?))
+}
+
+/// Quick-action buttons for a finished roll, from the server's follow-ups.
+/// Failures to load them are logged and the roll gets no buttons.
+pub async fn buttons_for_roll(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    results: &[RollResult],
+) -> Option<CreateActionRow> {
+    let guild_id = origin.guild_id?;
+    if results.iter().all(|result| result.comment.is_none()) {
+        return None;
+    }
+    let (db, quick_actions) = {
+        let data = ctx.data.read().await;
+        (
+            data.get::<DatabaseContainer>().cloned()?,
+            data.get::<QuickActionContainer>().cloned()?,
+        )
+    };
+
+    let follow_ups = match db.get_follow_ups(guild_id.get() as i64).await {
+        Ok(follow_ups) => follow_ups,
+        Err(e) => {
+            warn!("Failed to load follow-ups for guild {}: {}", guild_id, e);
+            return None;
+        }
+    };
+    let actions = match_follow_ups(&follow_ups, results);
+    if actions.is_empty() {
+        return None;
+    }
+
+    let token = quick_actions.store(PendingActions {
+        user_id: origin.user.id.get(),
+        actions: actions.clone(),
+    });
+    Some(action_buttons(token, &actions))
+}
+
+/// Handle a quick-action button click: roll its expression as a new message.
+/// Only the original roller may use the buttons.
+pub async fn handle_action(
+    ctx: &Context,
+    component: &ComponentInteraction,
+) -> Result<CommandResponse> {
+    let Some((token, index)) = parse_action_id(&component.data.custom_id) else {
+        return Err(anyhow!("Invalid quick-action button"));
+    };
+    let pending = ctx
+        .data
+        .read()
+        .await
+        .get::<QuickActionContainer>()
+        .cloned()
+        .and_then(|quick_actions| quick_actions.get(token));
+    let expired = || {
+        CommandResponse::private(
+            "⌛ This button has expired. Roll again to get new buttons.".to_string(),
+        )
+    };
+    let Some(pending) = pending else {
+        return Ok(expired());
+    };
+    if pending.user_id != component.user.id.get() {
+        return Ok(CommandResponse::private(
+            "🎲 Only the original roller can use this button. Roll your own with `/roll`!"
+                .to_string(),
+        ));
+    }
+    let Some(action) = pending.actions.get(index) else {
+        return Ok(expired());
+    };
+    roll::roll_expression(ctx, &RollOrigin::from(component), &action.expression).await
+}
+
+/// The `/followup list` message
+pub fn format_follow_up_list(follow_ups: &[FollowUp]) -> String {
+    if follow_ups.is_empty() {
+        return "⚔ No follow-up buttons yet. Add one with `/followup add`.".to_string();
+    }
+    let mut output = format!(
+        "⚔ **Follow-up buttons** ({}/{MAX_FOLLOW_UPS})",
+        follow_ups.len()
+    );
+    for follow_up in follow_ups {
+        output.push_str(&format!(
+            "\n`{}`: **{}** → `{}`",
+            follow_up.keyword, follow_up.label, follow_up.expression
+        ));
+    }
+    output
+}
@@ -0,0 +1,76 @@
+//! `/forgetme` slash-command handler.
+//!
+//! Lets any user delete everything Dice Maiden stores about them, across all
+//! servers.  Replies with a private `commands::confirm` prompt; the buttons are
+//! handled by [`handle_component`] using custom IDs prefixed with `forgetme:`.  Each
+//! erasure is recorded in the `data_erasure_log` audit table.
+
+use crate::DatabaseContainer;
+use crate::commands::confirm::{self, Choice};
+use crate::commands::{CommandResponse, again};
+use crate::write_queue::{self, DbWrite};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandInteraction, ComponentInteraction},
+    builder::CreateCommand,
+    prelude::Context,
+};
+use tracing::info;
+
+const PREFIX: &str = "forgetme";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("forgetme")
+        .description("Permanently delete all data Dice Maiden stores about you")
+}
+
+pub async fn run(_ctx: &Context, _command: &CommandInteraction) -> Result<CommandResponse> {
+    Ok(confirm::prompt(
+        PREFIX,
+        "⚠️ This will permanently delete **all** data Dice Maiden stores about you, \
+         in every server. This cannot be undone.",
+        "Delete my data",
+        None,
+    ))
+}
+
+/// Handle the `forgetme:` confirmation buttons, returning the replacement message
+pub async fn handle_component(ctx: &Context, component: &ComponentInteraction) -> Result<String> {
+    match confirm::parse_now(PREFIX, &component.data.custom_id) {
+        Some(Choice::Cancelled) => Ok("Cancelled. Nothing was deleted.".to_string()),
+        Some(Choice::Expired) => Ok(confirm::expired_message("/forgetme")),
+        Some(Choice::Confirmed(_)) => {
+            let db = ctx
+                .data
+                .read()
+                .await
+                .get::<DatabaseContainer>()
+                .cloned()
+                .ok_or_else(|| anyhow!("Database not available"))?;
+
+            let user_id = component.user.id.get() as i64;
+            let deleted = db.erase_user_data(user_id).await?;
+            write_queue::enqueue(
+                ctx,
+                DbWrite::DataErasure {
+                    scope: "user",
+                    target_id: user_id,
+                    requested_by: user_id,
+                    rows_deleted: deleted,
+                },
+            )
+            .await;
+            again::forget_user(ctx, component.user.id.get()).await;
+
+            info!("Erased {} rows of user data for {}", deleted, user_id);
+
+            Ok(format!(
+                "🗑️ Deleted all data Dice Maiden stored about you ({deleted} records)."
+            ))
+        }
+        None => Err(anyhow!(
+            "Unknown forgetme button: {}",
+            component.data.custom_id
+        )),
+    }
+}
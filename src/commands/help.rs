@@ -11,12 +11,21 @@
 //! | `a5e`        | A5E (Level Up: Advanced 5e) specific syntax  |
 //! | `aliens`     | Alien RPG stress / panic mechanics           |
 //! | `mothership` | Mothership RPG stat checks                   |
+//! | `privacy`    | Stored data and how to erase it              |
+//! | `about`      | Dice engine version for bug reports          |
+//!
+//! `modifier:<token>` (e.g. `ie`, `km2`, `t ds`) shows a single modifier's
+//! entry from `dice::modifier_docs` instead, and takes precedence over `topic`.
+//!
+//! The basic page is in the language of the user's Discord client when it's
+//! translated (see [`locale`]); the other topics are English.
 //!
 //! Message content is generated by `help_text.rs`; this module only handles
 //! command registration and option dispatch.
 
 use crate::commands::CommandResponse; // Import CommandResponse
 use crate::help_text; // Import the shared help text module from src root
+use crate::locale;
 use anyhow::Result;
 use serenity::{
     all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
@@ -39,20 +48,41 @@ pub fn register() -> CreateCommand {
             .add_string_choice("system", "system")
             .add_string_choice("a5e", "a5e")
             .add_string_choice("aliens", "aliens")
-            .add_string_choice("mothership", "mothership"),
+            .add_string_choice("mothership", "mothership")
+            .add_string_choice("privacy", "privacy")
+            .add_string_choice("about", "about"),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "modifier",
+                "Explain one modifier (e.g. ie, km, t ds)",
+            )
+            .required(false)
+            .max_length(32),
         )
 }
 
 pub async fn run(_ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
-    let topic = command
-        .data
-        .options
-        .first()
-        .and_then(|opt| match &opt.value {
-            CommandDataOptionValue::String(s) => Some(s.as_str()),
-            _ => None,
-        })
-        .unwrap_or("basic");
+    let option_str = |name: &str| {
+        command
+            .data
+            .options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| match &opt.value {
+                CommandDataOptionValue::String(s) => Some(s.as_str()),
+                _ => None,
+            })
+    };
+
+    if let Some(modifier) = option_str("modifier") {
+        return Ok(CommandResponse::private(help_text::generate_modifier_help(
+            modifier,
+        )));
+    }
+
+    let topic = option_str("topic").unwrap_or("basic");
 
     let help_text = match topic {
         "alias" => help_text::generate_alias_help(),
@@ -60,7 +90,12 @@ pub async fn run(_ctx: &Context, command: &CommandInteraction) -> Result<Command
         "a5e" => help_text::generate_a5e_help(),
         "aliens" => help_text::generate_aliens_help(),
         "mothership" => help_text::generate_mothership_help(),
-        _ => help_text::generate_basic_help(),
+        "privacy" => help_text::generate_privacy_help(),
+        "about" => help_text::generate_about_help(),
+        _ => help_text::generate_basic_help_in(locale::language_for(
+            &command.locale,
+            command.guild_locale.as_deref(),
+        )),
     };
 
     // Return as private response
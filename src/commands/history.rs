@@ -0,0 +1,439 @@
+//! `/history` slash-command handler: a channel's recorded rolls for the GM,
+//! and a download of your own.
+//!
+//! | Subcommand | Effect                                                        |
+//! |------------|---------------------------------------------------------------|
+//! | `channel`  | List the channel's recent rolls, or those of one `/init` round |
+//! | `export`   | Attach your rolls in this server as a CSV or JSON file        |
+//!
+//! Rolls made while the channel's `/init` tracker is running are recorded
+//! with its round and whose turn it was (see `privacy::record_roll`), so
+//! `/history channel round:3` shows what happened in round 3.  Without a
+//! round the channel's most recent rolls are shown.  Rounds restart with
+//! `/init roll`, so a round can hold rolls from earlier combats too; the
+//! newest [`HISTORY_LIMIT`] are shown, oldest first.  Each roll shows the
+//! time it was made, in the timezone the viewer chose with `/prefs timezone`
+//! (UTC by default).  Listing a channel needs the **Administrator**
+//! permission or the GM role chosen in `/setup`.
+//!
+//! `export` is open to everyone, for their own rolls.  The rows are read
+//! [`EXPORT_PAGE_SIZE`] at a time, oldest first, and written into the file as
+//! they come; the file stops growing at [`MAX_EXPORT_BYTES`], and the reply
+//! says so.  Times in the file are UTC.
+//!
+//! Only rolls kept by the players' and server's history levels appear, and
+//! totals-only rolls have just their total.  Replies are private.
+
+use crate::DatabaseContainer;
+use crate::commands::session::is_game_master;
+use crate::commands::{CommandResponse, prefs};
+use crate::database::{Database, RollHistoryEntry};
+use crate::stats_export::{CsvWriter, MAX_EXPORT_BYTES};
+use anyhow::{Result, anyhow};
+use serde_json::json;
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateAttachment, CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use tracing::info;
+
+/// Most rolls one `/history channel` reply lists
+pub const HISTORY_LIMIT: i64 = 25;
+
+/// Rows read from the database at a time by `/history export`
+pub const EXPORT_PAGE_SIZE: i64 = 500;
+
+/// Room left in the 2000-character message for the rolls
+const MAX_REPLY_LENGTH: usize = 1900;
+
+/// Expressions longer than this are cut short in the list
+const MAX_EXPRESSION_LENGTH: usize = 60;
+
+/// Columns of a CSV export, and the keys of a JSON one
+const EXPORT_COLUMNS: [&str; 8] = [
+    "time",
+    "channel_id",
+    "expression",
+    "result",
+    "total",
+    "outcome",
+    "round",
+    "turn",
+];
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("history")
+        .description("Show this channel's recent rolls, or download your own")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "channel",
+                "Show this channel's recent rolls, e.g. from one initiative round",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "round",
+                    "Only rolls made in this /init round",
+                )
+                .required(false)
+                .min_int_value(1),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "export",
+                "Download your rolls in this server as a file",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "format", "File format")
+                    .required(false)
+                    .add_string_choice("CSV (spreadsheets)", "csv")
+                    .add_string_choice("JSON", "json"),
+            ),
+        )
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing history subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid history subcommand"));
+    };
+    let option = |name: &str| options.iter().find(|opt| opt.name == name);
+
+    match subcommand.name.as_str() {
+        "channel" => {
+            let round = option("round").and_then(|opt| opt.value.as_i64());
+            show_channel(ctx, command, round).await
+        }
+        "export" => {
+            let format = option("format")
+                .and_then(|opt| opt.value.as_str())
+                .and_then(ExportFormat::parse)
+                .unwrap_or(ExportFormat::Csv);
+            export(ctx, command, format).await
+        }
+        other => Err(anyhow!("Unknown history subcommand: {other}")),
+    }
+}
+
+async fn show_channel(
+    ctx: &Context,
+    command: &CommandInteraction,
+    round: Option<i64>,
+) -> Result<CommandResponse> {
+    if !is_game_master(ctx, command).await {
+        return Ok(CommandResponse::private(
+            "❌ You need the 'Administrator' permission or the server's GM role to do this."
+                .to_string(),
+        ));
+    }
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let (entries, times): (Vec<_>, Vec<_>) = db
+        .get_timed_channel_roll_history(command.channel_id.get() as i64, round, HISTORY_LIMIT)
+        .await?
+        .into_iter()
+        .unzip();
+    let utc_offset = db
+        .get_user_prefs(command.user.id.get() as i64)
+        .await?
+        .utc_offset;
+    let times: Vec<String> = times
+        .iter()
+        .map(|time| prefs::local_time(time, utc_offset).unwrap_or_default())
+        .collect();
+
+    Ok(CommandResponse::private(format_timed_history(
+        &entries, &times, round,
+    )))
+}
+
+async fn export(
+    ctx: &Context,
+    command: &CommandInteraction,
+    format: ExportFormat,
+) -> Result<CommandResponse> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ `/history export` only works in servers.".to_string(),
+        ));
+    };
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let file = export_user_history(
+        &db,
+        guild_id.get() as i64,
+        command.user.id.get() as i64,
+        format,
+        MAX_EXPORT_BYTES,
+    )
+    .await?;
+    if file.rows == 0 {
+        return Ok(CommandResponse::private(
+            "📜 No rolls of yours are recorded in this server. History is kept for 30 days; \
+             check your level with `/privacy`."
+                .to_string(),
+        ));
+    }
+
+    info!(
+        "Exported {} history rows for {} in guild {}",
+        file.rows, command.user.id, guild_id
+    );
+    let mut content = format!(
+        "📜 **Your rolls in this server**: {} roll{} (times in UTC).",
+        file.rows,
+        if file.rows == 1 { "" } else { "s" }
+    );
+    if file.truncated {
+        content.push_str(&format!(
+            "\n*Note: the file stops after {} rolls to stay under {} KB*",
+            file.rows,
+            MAX_EXPORT_BYTES / 1024
+        ));
+    }
+    let attachment = CreateAttachment::bytes(file.contents.into_bytes(), file.name);
+    Ok(CommandResponse::private(content).with_attachments(vec![attachment]))
+}
+
+/// File formats `/history export` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::Csv => "history.csv",
+            Self::Json => "history.json",
+        }
+    }
+}
+
+/// A finished `/history export` file
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryFile {
+    pub name: String,
+    pub contents: String,
+    /// Rolls written
+    pub rows: usize,
+    /// Rolls were left out to keep the file under its limit
+    pub truncated: bool,
+}
+
+/// A `/history export` file built one roll at a time, refusing rolls past
+/// its size limit
+#[derive(Debug, Clone)]
+pub struct HistoryWriter {
+    format: ExportFormat,
+    csv: CsvWriter,
+    json: String,
+    limit: usize,
+    rows: usize,
+    truncated: bool,
+}
+
+impl HistoryWriter {
+    pub fn new(format: ExportFormat, limit: usize) -> Self {
+        Self {
+            format,
+            csv: CsvWriter::new(format.file_name(), &EXPORT_COLUMNS, limit),
+            json: String::new(),
+            limit,
+            rows: 0,
+            truncated: false,
+        }
+    }
+
+    /// Append a roll recorded at `time` (UTC), or mark the file truncated and
+    /// return false when it would pass the limit
+    pub fn write(&mut self, entry: &RollHistoryEntry, time: &str) -> bool {
+        let written = match self.format {
+            ExportFormat::Csv => self.csv.write_row(&[
+                time.to_string(),
+                entry.channel_id.to_string(),
+                entry.expression.clone().unwrap_or_default(),
+                entry.result.clone().unwrap_or_default(),
+                entry.total.to_string(),
+                entry
+                    .outcome
+                    .map(|o| o.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                entry.round.map(|r| r.to_string()).unwrap_or_default(),
+                entry.turn.clone().unwrap_or_default(),
+            ]),
+            ExportFormat::Json => {
+                // IDs are strings, as Discord's are, so they survive JavaScript
+                let row = json!({
+                    "time": time,
+                    "channel_id": entry.channel_id.to_string(),
+                    "expression": entry.expression,
+                    "result": entry.result,
+                    "total": entry.total,
+                    "outcome": entry.outcome.map(|o| o.as_str()),
+                    "round": entry.round,
+                    "turn": entry.turn,
+                })
+                .to_string();
+                // Room for the separator now and the closing "\n]\n" later
+                let fits = self.json.len() + row.len() + 4 + 3 <= self.limit;
+                if fits && !self.truncated {
+                    self.json
+                        .push_str(if self.rows == 0 { "[\n  " } else { ",\n  " });
+                    self.json.push_str(&row);
+                }
+                fits && !self.truncated
+            }
+        };
+        if written {
+            self.rows += 1;
+        } else {
+            self.truncated = true;
+        }
+        written
+    }
+
+    pub fn finish(self) -> HistoryFile {
+        let contents = match self.format {
+            ExportFormat::Csv => self.csv.finish().contents,
+            ExportFormat::Json if self.rows == 0 => "[]\n".to_string(),
+            ExportFormat::Json => format!("{}\n]\n", self.json),
+        };
+        HistoryFile {
+            name: self.format.file_name().to_string(),
+            contents,
+            rows: self.rows,
+            truncated: self.truncated,
+        }
+    }
+}
+
+/// Export a user's recorded rolls in a guild, oldest first, reading the
+/// database a page at a time until the rolls or the file's room run out
+pub async fn export_user_history(
+    db: &Database,
+    guild_id: i64,
+    user_id: i64,
+    format: ExportFormat,
+    limit: usize,
+) -> Result<HistoryFile> {
+    let mut writer = HistoryWriter::new(format, limit);
+    let mut after_id = 0;
+    loop {
+        let page = db
+            .get_user_roll_history_page(guild_id, user_id, after_id, EXPORT_PAGE_SIZE)
+            .await?;
+        let full_page = page.len() as i64 == EXPORT_PAGE_SIZE;
+        for (id, entry, time) in &page {
+            if !writer.write(entry, time) {
+                return Ok(writer.finish());
+            }
+            after_id = *id;
+        }
+        if !full_page {
+            return Ok(writer.finish());
+        }
+    }
+}
+
+/// The `/history` reply for `entries` (newest first, as stored)
+pub fn format_history(entries: &[RollHistoryEntry], round: Option<i64>) -> String {
+    format_timed_history(entries, &[], round)
+}
+
+/// [`format_history`] with the time of each roll, in the viewer's timezone
+/// (`times[i]` is `HH:MM` for `entries[i]`; missing or empty times are left out)
+pub fn format_timed_history(
+    entries: &[RollHistoryEntry],
+    times: &[String],
+    round: Option<i64>,
+) -> String {
+    if entries.is_empty() {
+        return match round {
+            Some(round) => format!("📜 No recorded rolls from round {round} in this channel."),
+            None => "📜 No recorded rolls in this channel.".to_string(),
+        };
+    }
+
+    let mut content = match round {
+        Some(round) => format!("📜 **Round {round}** in this channel:"),
+        None => "📜 **Recent rolls** in this channel:".to_string(),
+    };
+    // Keep the newest rolls that fit in one message
+    let mut length = content.len();
+    let lines: Vec<String> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let line = format_entry(entry, round.is_none());
+            match times.get(i).filter(|time| !time.is_empty()) {
+                Some(time) => format!("• `{time}` {}", &line["• ".len()..]),
+                None => line,
+            }
+        })
+        .take_while(|line| {
+            length += line.len() + 1;
+            length <= MAX_REPLY_LENGTH
+        })
+        .collect();
+    if lines.len() < entries.len() {
+        content.push_str(&format!(
+            "\n-# {} older rolls left out",
+            entries.len() - lines.len()
+        ));
+    }
+    for line in lines.iter().rev() {
+        content.push('\n');
+        content.push_str(line);
+    }
+    content
+}
+
+fn format_entry(entry: &RollHistoryEntry, show_round: bool) -> String {
+    let turn = match (entry.round, &entry.turn) {
+        (Some(round), Some(turn)) if show_round => format!("R{round} · {turn}'s turn · "),
+        (Some(round), None) if show_round => format!("R{round} · "),
+        (_, Some(turn)) => format!("{turn}'s turn · "),
+        _ => String::new(),
+    };
+    let expression = match &entry.expression {
+        Some(expression) if expression.chars().count() > MAX_EXPRESSION_LENGTH => {
+            let cut: String = expression.chars().take(MAX_EXPRESSION_LENGTH).collect();
+            format!("`{cut}…` ")
+        }
+        Some(expression) => format!("`{expression}` "),
+        None => String::new(),
+    };
+    format!(
+        "• {turn}<@{}> {expression}= **{}**",
+        entry.user_id, entry.total
+    )
+}
@@ -0,0 +1,402 @@
+//! `/init` slash-command handler for a channel's initiative order.
+//!
+//! | Subcommand | Effect                                                       |
+//! |------------|--------------------------------------------------------------|
+//! | `add`      | Roll initiative for a combatant (replacing one with the same name) |
+//! | `roll`     | Re-roll everyone's initiative and restart from round 0       |
+//! | `next`     | Advance to the next turn and update the pinned tracker       |
+//! | `show`     | Show the turn order                                          |
+//! | `clear`    | End the combat (the pinned tracker message is kept)          |
+//!
+//! Initiative expressions go through the normal dice engine, so anything that
+//! produces a single roll works (`1d20+2`, `2d6`, `1d20 + 1d4`).  The order
+//! is highest initiative first, ties broken by name.
+//!
+//! The first `/init next` posts a tracker message and pins it; later turns,
+//! additions and re-rolls edit it.  Like tally messages it adapts to the
+//! bot's channel permissions (see `outbound::ChannelAccess`).
+//!
+//! State lives in the `initiative_entries` and `initiative_trackers` tables
+//! (see `database.rs`).  Once the first turn starts, rolls in the channel
+//! are recorded with the round and turn, for `/history channel round:<n>`.
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::commands::tally::is_valid_tally_name;
+use crate::database::{Database, InitiativeEntry, InitiativeTracker};
+use crate::dice::{self, rng};
+use crate::outbound::{self, ChannelAccess};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{
+        ChannelId, CommandDataOptionValue, CommandInteraction, CommandOptionType, Message,
+        MessageId,
+    },
+    builder::{CreateCommand, CreateCommandOption, CreateMessage, EditMessage},
+    http::Http,
+    prelude::Context,
+};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Combatants allowed in one channel's initiative
+pub const MAX_COMBATANTS: usize = 25;
+
+const DEFAULT_EXPRESSION: &str = "1d20";
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("init")
+        .description("Track initiative order in this channel")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "add",
+                "Roll initiative for a combatant",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "name",
+                    "Combatant name (e.g. Goblin_2)",
+                )
+                .required(true)
+                .max_length(32),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "dice",
+                    "Initiative roll (default 1d20)",
+                )
+                .required(false)
+                .max_length(100),
+            ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "roll",
+            "Re-roll everyone's initiative",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "next",
+            "Advance to the next turn",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "show",
+            "Show the turn order",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "clear",
+            "End the combat",
+        ))
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing init subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid init subcommand"));
+    };
+    let option_str = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| opt.value.as_str())
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let guild_id = command.guild_id.map(|id| id.get() as i64);
+    let channel_id = command.channel_id.get() as i64;
+    let entries = db.get_initiative_entries(channel_id).await?;
+    let tracker = db.get_initiative_tracker(channel_id).await?;
+
+    match subcommand.name.as_str() {
+        "add" => {
+            let name = option_str("name").unwrap_or_default().to_string();
+            if !is_valid_tally_name(&name) {
+                return Ok(CommandResponse::private(
+                    "❌ Combatant names must be 1-32 letters, numbers, `-` or `_`.".to_string(),
+                ));
+            }
+            let replacing = entries.iter().any(|e| e.name.eq_ignore_ascii_case(&name));
+            if !replacing && entries.len() >= MAX_COMBATANTS {
+                return Ok(CommandResponse::private(format!(
+                    "❌ This channel already has {MAX_COMBATANTS} combatants. Clear the combat with `/init clear` first."
+                )));
+            }
+
+            let expression = option_str("dice").unwrap_or(DEFAULT_EXPRESSION);
+            let initiative = match roll_initiative(expression, &mut rng::get_dice_rng()) {
+                Ok(initiative) => initiative,
+                Err(e) => {
+                    return Ok(CommandResponse::private(format!(
+                        "❌ `{expression}` can't be rolled for initiative: {e}"
+                    )));
+                }
+            };
+            db.set_initiative_entry(guild_id, channel_id, &name, expression, initiative)
+                .await?;
+
+            let mut content =
+                format!("⚔️ **{name}** rolls initiative `{expression}`: **{initiative}**");
+            if let Some(notice) = refresh_tracker(ctx, &db, command, false).await? {
+                content.push_str(&notice);
+            }
+            Ok(CommandResponse::public(content))
+        }
+        "roll" => {
+            if entries.is_empty() {
+                return Ok(CommandResponse::private(empty_message()));
+            }
+
+            let mut dice_rng = rng::get_dice_rng();
+            for entry in &entries {
+                // Stored expressions rolled when they were added; a failure here
+                // means the engine changed, so keep the old result
+                match roll_initiative(&entry.expression, &mut dice_rng) {
+                    Ok(initiative) => {
+                        db.set_initiative_entry(
+                            guild_id,
+                            channel_id,
+                            &entry.name,
+                            &entry.expression,
+                            initiative,
+                        )
+                        .await?
+                    }
+                    Err(e) => warn!(
+                        "Failed to re-roll initiative {} for {}: {}",
+                        entry.expression, entry.name, e
+                    ),
+                }
+            }
+            db.set_initiative_turn(guild_id, channel_id, 0, None)
+                .await?;
+
+            let entries = db.get_initiative_entries(channel_id).await?;
+            let tracker = db.get_initiative_tracker(channel_id).await?;
+            let mut content = format_initiative(&entries, &tracker.unwrap_or_default());
+            if let Some(notice) = refresh_tracker(ctx, &db, command, false).await? {
+                content.push_str(&notice);
+            }
+            Ok(CommandResponse::public(content))
+        }
+        "next" => {
+            let tracker = tracker.unwrap_or_default();
+            let Some((round, current)) = advance_turn(&entries, &tracker) else {
+                return Ok(CommandResponse::private(empty_message()));
+            };
+            db.set_initiative_turn(guild_id, channel_id, round, Some(&current))
+                .await?;
+
+            let mut content = format!("▶️ **Round {round}**: it's **{current}**'s turn");
+            if let Some(notice) = refresh_tracker(ctx, &db, command, true).await? {
+                content.push_str(&notice);
+            }
+            Ok(CommandResponse::public(content))
+        }
+        "show" => {
+            if entries.is_empty() {
+                return Ok(CommandResponse::private(empty_message()));
+            }
+            Ok(CommandResponse::public(format_initiative(
+                &entries,
+                &tracker.unwrap_or_default(),
+            )))
+        }
+        "clear" => {
+            let removed = db.clear_initiative(channel_id).await?;
+            if removed == 0 {
+                return Ok(CommandResponse::private(empty_message()));
+            }
+            Ok(CommandResponse::public(format!(
+                "🗑️ Combat over: cleared {removed} combatant{}",
+                if removed == 1 { "" } else { "s" }
+            )))
+        }
+        other => Err(anyhow!("Unknown init subcommand: {}", other)),
+    }
+}
+
+/// Roll an initiative expression, which must produce a single roll
+pub fn roll_initiative(expression: &str, rng: &mut impl rand::Rng) -> Result<i64> {
+    let results = dice::parse_and_roll_with_rng(expression, rng)?;
+    match results.as_slice() {
+        [result] => Ok(i64::from(result.total)),
+        _ => Err(anyhow!("initiative must be a single roll")),
+    }
+}
+
+/// The round and combatant after the tracker's current turn, wrapping to a
+/// new round after the last combatant.  A combatant who has left the order
+/// hands the turn to the top of the next round.  None without combatants.
+pub fn advance_turn(
+    entries: &[InitiativeEntry],
+    tracker: &InitiativeTracker,
+) -> Option<(i64, String)> {
+    let first = entries.first()?;
+    let position = tracker.current.as_deref().and_then(|current| {
+        entries
+            .iter()
+            .position(|entry| entry.name.eq_ignore_ascii_case(current))
+    });
+
+    Some(match position.and_then(|i| entries.get(i + 1)) {
+        Some(next) => (tracker.round, next.name.clone()),
+        None => (tracker.round + 1, first.name.clone()),
+    })
+}
+
+/// The turn order, marking whose turn it is, e.g.
+///
+/// ```text
+/// ⚔️ **Initiative** — Round 2
+/// ▶️ `18` **Goblin**
+/// ▫️ `12` **Aria**
+/// ```
+pub fn format_initiative(entries: &[InitiativeEntry], tracker: &InitiativeTracker) -> String {
+    let mut output = if tracker.current.is_some() {
+        format!("⚔️ **Initiative** — Round {}", tracker.round)
+    } else {
+        "⚔️ **Initiative** — not started (`/init next` to begin)".to_string()
+    };
+
+    for entry in entries {
+        let marker = if tracker
+            .current
+            .as_deref()
+            .is_some_and(|current| entry.name.eq_ignore_ascii_case(current))
+        {
+            "▶️"
+        } else {
+            "▫️"
+        };
+        output.push_str(&format!(
+            "\n{marker} `{}` **{}**",
+            entry.initiative, entry.name
+        ));
+    }
+
+    if entries.is_empty() {
+        output.push_str("\nNo combatants.");
+    }
+
+    output
+}
+
+fn empty_message() -> String {
+    "⚔️ No combatants in this channel. Add them with `/init add name:<name> dice:1d20+2`"
+        .to_string()
+}
+
+// Queue an update of the tracker message.  With `post` a missing message is
+// posted (and pinned), otherwise only an existing one is edited.  Returns a
+// notice for the response when the bot can't post in the channel at all.
+async fn refresh_tracker(
+    ctx: &Context,
+    db: &Arc<Database>,
+    command: &CommandInteraction,
+    post: bool,
+) -> Result<Option<String>> {
+    let channel_id = command.channel_id.get() as i64;
+    let tracker = db.get_initiative_tracker(channel_id).await?;
+    let Some(tracker) = tracker.filter(|t| post || t.message_id.is_some()) else {
+        return Ok(None);
+    };
+    let entries = db.get_initiative_entries(channel_id).await?;
+
+    let access = ChannelAccess::from_permissions(command.app_permissions);
+    let notice = (!access.send_messages && tracker.message_id.is_none()).then(|| {
+        " (I can't post the tracker message here: I need the Send Messages permission)".to_string()
+    });
+
+    outbound::enqueue_job(
+        ctx,
+        format!("initiative tracker {channel_id}"),
+        refresh_tracker_message(
+            ctx.http.clone(),
+            db.clone(),
+            command.channel_id,
+            format_initiative(&entries, &tracker),
+            tracker.message_id,
+            access,
+        ),
+    )
+    .await;
+    Ok(notice)
+}
+
+// Edit the pinned tracker message, posting a fresh one if it was deleted
+async fn refresh_tracker_message(
+    http: Arc<Http>,
+    db: Arc<Database>,
+    channel_id: ChannelId,
+    content: String,
+    message_id: Option<i64>,
+    access: ChannelAccess,
+) {
+    let content = access.adapt_content(&content);
+    if let Some(message_id) = message_id {
+        let edited = outbound::send_with_retry("initiative tracker edit", || {
+            channel_id.edit_message(
+                &http,
+                MessageId::new(message_id as u64),
+                EditMessage::new().content(content.clone()),
+            )
+        })
+        .await;
+        if edited.is_ok() {
+            return;
+        }
+    }
+
+    if !access.send_messages {
+        return;
+    }
+    match outbound::send_with_retry("initiative tracker post", || {
+        channel_id.send_message(&http, CreateMessage::new().content(content.clone()))
+    })
+    .await
+    {
+        Ok(message) => store_tracker_message(&http, &db, channel_id, &message, access).await,
+        Err(e) => warn!("Failed to post initiative tracker in {}: {}", channel_id, e),
+    }
+}
+
+// Pin a posted tracker message (when allowed) and remember it for later edits
+async fn store_tracker_message(
+    http: &Http,
+    db: &Database,
+    channel_id: ChannelId,
+    message: &Message,
+    access: ChannelAccess,
+) {
+    if access.pin_messages
+        && let Err(e) =
+            outbound::send_with_retry("initiative tracker pin", || message.pin(http)).await
+    {
+        warn!("Failed to pin initiative tracker in {}: {}", channel_id, e);
+    }
+
+    if let Err(e) = db
+        .set_initiative_message(channel_id.get() as i64, message.id.get() as i64)
+        .await
+    {
+        warn!(
+            "Failed to store initiative tracker in {}: {}",
+            channel_id, e
+        );
+    }
+}
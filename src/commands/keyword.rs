@@ -0,0 +1,366 @@
+//! `/keyword` slash-command handler and comment keyword rules.
+//!
+//! | Subcommand | Effect                                                  |
+//! |------------|---------------------------------------------------------|
+//! | `add`      | Add (or replace) a rule for a word in roll comments     |
+//! | `remove`   | Remove a rule                                           |
+//! | `list`     | Show the server's rules                                 |
+//!
+//! A rule matches rolls whose comment contains the keyword as a whole word
+//! (case-insensitive, so `heal` matches `! Heal the fighter` but not
+//! `! healthy`).  After the roll, a matching rule can:
+//!
+//! * mark the result with a colour (`🟢`, `🔴`, …), and
+//! * add the roll's value to a channel tally, as `tally:<name>` would.
+//!   Rules apply in every channel, so channels without that tally are
+//!   skipped silently.
+//!
+//! Rules live in the `comment_keywords` table (see `database.rs`).  Changing
+//! them requires the **Administrator** permission, enforced both via
+//! `default_member_permissions` and at runtime by `middleware::Permissions`.
+
+use crate::DatabaseContainer;
+use crate::commands::{CommandResponse, RollOrigin, tally};
+use crate::database::CommentKeyword;
+use crate::dice::{self, RollResult};
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    model::prelude::Permissions,
+    prelude::Context,
+};
+use tracing::{info, warn};
+
+/// Rules a server may define
+pub const MAX_KEYWORDS: usize = 25;
+
+/// Colour names and the marker shown on matching rolls
+pub const KEYWORD_COLORS: [(&str, &str); 6] = [
+    ("green", "🟢"),
+    ("red", "🔴"),
+    ("blue", "🔵"),
+    ("yellow", "🟡"),
+    ("purple", "🟣"),
+    ("orange", "🟠"),
+];
+
+static KEYWORD_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[a-z0-9_-]{1,32}$").expect("Failed to compile KEYWORD_REGEX"));
+
+static COMMENT_WORD_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[A-Za-z0-9_-]+").expect("Failed to compile COMMENT_WORD_REGEX"));
+
+pub fn register() -> CreateCommand {
+    let keyword_option = || {
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "keyword",
+            "Word to look for in roll comments (e.g. heal)",
+        )
+        .required(true)
+        .max_length(32)
+    };
+    let color_option = KEYWORD_COLORS.iter().fold(
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "color",
+            "Colour marker for matching rolls",
+        ),
+        |option, (name, marker)| option.add_string_choice(format!("{marker} {name}"), *name),
+    );
+
+    CreateCommand::new("keyword")
+        .description("Tag rolls by words in their comments (administrators only)")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "add",
+                "Add or replace a keyword rule",
+            )
+            .add_sub_option(keyword_option())
+            .add_sub_option(color_option)
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "tally",
+                    "Channel tally matching rolls are added to (e.g. Healing)",
+                )
+                .max_length(32),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "remove", "Remove a rule")
+                .add_sub_option(keyword_option()),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "Show this server's keyword rules",
+        ))
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ This command can only be used in servers.".to_string(),
+        ));
+    };
+
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing keyword subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid keyword subcommand"));
+    };
+    let option_str = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| opt.value.as_str())
+            .map(str::trim)
+    };
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let guild_id = guild_id.get() as i64;
+    let rules = db.get_comment_keywords(guild_id).await?;
+
+    if subcommand.name == "list" {
+        return Ok(CommandResponse::private(format_rule_list(&rules)));
+    }
+
+    let keyword = option_str("keyword").unwrap_or_default().to_lowercase();
+    if !is_valid_keyword(&keyword) {
+        return Ok(CommandResponse::private(
+            "❌ Keywords must be 1-32 letters, numbers, `-` or `_`.".to_string(),
+        ));
+    }
+
+    match subcommand.name.as_str() {
+        "add" => {
+            let rule = CommentKeyword {
+                keyword,
+                color: option_str("color").map(str::to_string),
+                tally: option_str("tally").map(str::to_string),
+            };
+            if let Err(e) = validate_rule(&rule, &rules) {
+                return Ok(CommandResponse::private(format!("❌ {e}")));
+            }
+            db.set_comment_keyword(guild_id, &rule).await?;
+            info!(
+                "Set keyword rule {} for guild {} (by {})",
+                rule.keyword, guild_id, command.user.id
+            );
+            Ok(CommandResponse::private(format!(
+                "✅ Rolls with `{}` in their comment will be {}.",
+                rule.keyword,
+                describe_rule(&rule)
+            )))
+        }
+        "remove" => {
+            if db.remove_comment_keyword(guild_id, &keyword).await? {
+                Ok(CommandResponse::private(format!(
+                    "🗑️ Removed the rule for `{keyword}`."
+                )))
+            } else {
+                Ok(CommandResponse::private(format!(
+                    "❌ No rule for `{keyword}`. See `/keyword list`."
+                )))
+            }
+        }
+        other => Err(anyhow!("Unknown keyword subcommand: {}", other)),
+    }
+}
+
+pub fn is_valid_keyword(keyword: &str) -> bool {
+    KEYWORD_REGEX.is_match(keyword)
+}
+
+/// Marker emoji for a colour name
+pub fn color_marker(color: &str) -> Option<&'static str> {
+    KEYWORD_COLORS
+        .iter()
+        .find(|(name, _)| *name == color)
+        .map(|(_, marker)| *marker)
+}
+
+/// Check a new rule against the server's existing `rules`
+pub fn validate_rule(rule: &CommentKeyword, rules: &[CommentKeyword]) -> Result<()> {
+    if !is_valid_keyword(&rule.keyword) {
+        return Err(anyhow!(
+            "Keywords must be 1-32 letters, numbers, `-` or `_`."
+        ));
+    }
+    if rule.color.is_none() && rule.tally.is_none() {
+        return Err(anyhow!("Give the rule a colour, a tally, or both."));
+    }
+    if let Some(color) = &rule.color
+        && color_marker(color).is_none()
+    {
+        return Err(anyhow!("Unknown colour `{color}`."));
+    }
+    if let Some(name) = &rule.tally
+        && !tally::is_valid_tally_name(name)
+    {
+        return Err(anyhow!(
+            "Tally names must be 1-32 letters, numbers, `-` or `_`."
+        ));
+    }
+    let replacing = rules.iter().any(|r| r.keyword == rule.keyword);
+    if !replacing && rules.len() >= MAX_KEYWORDS {
+        return Err(anyhow!(
+            "This server already has {MAX_KEYWORDS} keyword rules. Remove one first."
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `comment` contains `keyword` as a whole word, ignoring case
+pub fn comment_has_keyword(comment: &str, keyword: &str) -> bool {
+    COMMENT_WORD_REGEX
+        .find_iter(comment)
+        .any(|word| word.as_str().eq_ignore_ascii_case(keyword))
+}
+
+/// What the rules do to one roll
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct KeywordEffects {
+    /// Colour markers to show, in rule order, without repeats
+    pub markers: Vec<&'static str>,
+    /// Tallies to add to, with the amount for each
+    pub tallies: Vec<(String, i32)>,
+}
+
+/// Apply `rules` to the comments of a roll's results.  Each matching result
+/// adds its value (total or successes) to the rules' tallies, once per tally
+/// even when several of its rules name the same one.
+pub fn match_keywords(rules: &[CommentKeyword], results: &[RollResult]) -> KeywordEffects {
+    let mut effects = KeywordEffects::default();
+
+    for result in results {
+        let Some(comment) = result.comment.as_deref() else {
+            continue;
+        };
+        let mut counted: Vec<&str> = Vec::new();
+
+        for rule in rules
+            .iter()
+            .filter(|rule| comment_has_keyword(comment, &rule.keyword))
+        {
+            if let Some(marker) = rule.color.as_deref().and_then(color_marker)
+                && !effects.markers.contains(&marker)
+            {
+                effects.markers.push(marker);
+            }
+
+            let Some(name) = rule.tally.as_deref() else {
+                continue;
+            };
+            if counted.iter().any(|seen| seen.eq_ignore_ascii_case(name)) {
+                continue;
+            }
+            counted.push(name);
+
+            let amount = dice::calculate_result_value(result);
+            match effects
+                .tallies
+                .iter_mut()
+                .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            {
+                Some((_, total)) => *total += amount,
+                None => effects.tallies.push((name.to_string(), amount)),
+            }
+        }
+    }
+
+    effects
+}
+
+/// Run the server's keyword rules on a finished roll.
+///
+/// Returns the colour markers to put in front of the result and the tally
+/// lines to append after it.  `flagged_tally` is the roll's own `tally:<name>`
+/// flag, which already counted the roll.  Failures to load the rules are
+/// logged and the roll is shown unchanged.
+pub async fn process_roll(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    results: &[RollResult],
+    flagged_tally: Option<&str>,
+) -> (String, String) {
+    let Some(guild_id) = origin.guild_id else {
+        return (String::new(), String::new());
+    };
+    if results.iter().all(|result| result.comment.is_none()) {
+        return (String::new(), String::new());
+    }
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return (String::new(), String::new());
+    };
+
+    let rules = match db.get_comment_keywords(guild_id.get() as i64).await {
+        Ok(rules) => rules,
+        Err(e) => {
+            warn!("Failed to load keyword rules for guild {}: {}", guild_id, e);
+            return (String::new(), String::new());
+        }
+    };
+
+    let effects = match_keywords(&rules, results);
+    let markers = effects
+        .markers
+        .iter()
+        .map(|marker| format!("{marker} "))
+        .collect::<String>();
+
+    let mut lines = String::new();
+    for (name, amount) in &effects.tallies {
+        if flagged_tally.is_some_and(|flagged| flagged.eq_ignore_ascii_case(name)) {
+            continue;
+        }
+        lines.push_str(&tally::record_keyword_roll(ctx, origin, name, *amount).await);
+    }
+
+    (markers, lines)
+}
+
+/// The `/keyword list` message
+pub fn format_rule_list(rules: &[CommentKeyword]) -> String {
+    if rules.is_empty() {
+        return "🏷️ No keyword rules yet. Add one with `/keyword add`.".to_string();
+    }
+    let mut output = format!("🏷️ **Keyword rules** ({}/{MAX_KEYWORDS})", rules.len());
+    for rule in rules {
+        output.push_str(&format!("\n`{}`: {}", rule.keyword, describe_rule(rule)));
+    }
+    output
+}
+
+// e.g. "marked 🟢 and added to the **Healing** tally"
+fn describe_rule(rule: &CommentKeyword) -> String {
+    let marker = rule
+        .color
+        .as_deref()
+        .and_then(color_marker)
+        .map(|marker| format!("marked {marker}"));
+    let tally = rule
+        .tally
+        .as_ref()
+        .map(|name| format!("added to the **{name}** tally"));
+    match (marker, tally) {
+        (Some(marker), Some(tally)) => format!("{marker} and {tally}"),
+        (Some(marker), None) => marker,
+        (None, Some(tally)) => tally,
+        (None, None) => "left unchanged".to_string(),
+    }
+}
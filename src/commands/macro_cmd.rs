@@ -0,0 +1,204 @@
+//! `/macro` slash-command handler for personal macros.
+//!
+//! | Subcommand | Effect                                                      |
+//! |------------|-------------------------------------------------------------|
+//! | `add`      | Save a macro, e.g. `/macro add name:smite dice:2d8 + 1d6 + 5` |
+//! | `remove`   | Remove a macro                                              |
+//! | `list`     | Show your macros                                            |
+//! | `roll`     | Roll a macro, the same as `/roll smite`                     |
+//!
+//! Macros belong to a user and work in every server and in DMs.  They expand
+//! like guild aliases (see `aliases::expand_user_aliases`), so they can be
+//! combined with other dice (`/roll smite + 1d6`) and refer to each other.
+//! When a macro and a server alias share a name, the macro wins.  Macros with
+//! `{placeholder}` slots take arguments: `atk` → `1d20+{x} + 1d8+{x}` rolls as
+//! `/roll atk(5)`.
+
+use crate::DatabaseContainer;
+use crate::commands::{CommandResponse, RollOrigin, roll};
+use crate::dice::aliases;
+use crate::templates::{self, RollTemplate};
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use std::collections::BTreeMap;
+use tracing::info;
+
+/// Most macros a user may save
+pub const MAX_USER_MACROS: usize = 50;
+
+// Macros expand as whole words, so no `-` unlike template names
+static MACRO_NAME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[a-z][a-z0-9_]{0,31}$").expect("Failed to compile MACRO_NAME_REGEX")
+});
+
+pub fn register() -> CreateCommand {
+    let name_option = || {
+        CreateCommandOption::new(CommandOptionType::String, "name", "Macro name (e.g. smite)")
+            .required(true)
+            .max_length(32)
+    };
+
+    CreateCommand::new("macro")
+        .description("Save your own named dice expressions")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "add", "Save a macro")
+                .add_sub_option(name_option())
+                .add_sub_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "dice",
+                        "Expression it rolls (e.g. 2d8 + 1d6 + 5)",
+                    )
+                    .required(true),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "remove", "Remove a macro")
+                .add_sub_option(name_option()),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "Show your macros",
+        ))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "roll", "Roll a macro")
+                .add_sub_option(name_option()),
+        )
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing macro subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid macro subcommand"));
+    };
+    let option_str = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| opt.value.as_str())
+            .map(str::trim)
+    };
+    let name = option_str("name").unwrap_or_default().to_lowercase();
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let user_id = command.user.id.get() as i64;
+    let macros = db.get_user_macros(user_id).await?;
+
+    let content = match subcommand.name.as_str() {
+        // The roll goes through /roll, which expands the macro itself
+        "roll" if macros.contains_key(&name) => {
+            return roll::roll_expression(ctx, &RollOrigin::from(command), &name).await;
+        }
+        "roll" => format!("❌ You have no macro named `{name}`. See `/macro list`."),
+        "list" => format_macro_list(&macros),
+        "add" => {
+            let expression = option_str("dice").unwrap_or_default();
+            let template = match validate_macro(&name, expression, &macros) {
+                Ok(template) => template,
+                Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+            };
+            db.set_user_macro(user_id, &template.name, &template.expression)
+                .await?;
+            info!("Saved macro {} for user {}", template.name, user_id);
+            format!(
+                "✅ Saved `{}` → `{}`. Roll it with `/roll {}`.",
+                template.name,
+                template.expression,
+                macro_usage(&template)
+            )
+        }
+        "remove" => {
+            if db.remove_user_macro(user_id, &name).await? {
+                format!("🗑️ Removed `{name}`.")
+            } else {
+                format!("❌ You have no macro named `{name}`. See `/macro list`.")
+            }
+        }
+        other => return Err(anyhow!("Unknown macro subcommand: {}", other)),
+    };
+
+    Ok(CommandResponse::private(content))
+}
+
+/// Check a new macro against the user's existing `macros`, returning it
+/// normalized
+pub fn validate_macro(
+    name: &str,
+    expression: &str,
+    macros: &BTreeMap<String, String>,
+) -> Result<RollTemplate> {
+    let template = RollTemplate::new(name, expression)?;
+    if !MACRO_NAME_REGEX.is_match(&template.name) {
+        return Err(anyhow!(
+            "Invalid macro name `{}`: use up to 32 letters, digits or `_`, starting with a letter",
+            template.name
+        ));
+    }
+    if templates::is_reserved_roll_word(&template.name) {
+        return Err(anyhow!(
+            "`{}` already means something to `/roll`, pick another name",
+            template.name
+        ));
+    }
+
+    let replacing = macros.contains_key(&template.name);
+    if !replacing && macros.len() >= MAX_USER_MACROS {
+        return Err(anyhow!(
+            "You already have {MAX_USER_MACROS} macros. Remove one first."
+        ));
+    }
+
+    // Catch macros that would refer to themselves through other macros
+    let mut updated = macros.clone(); // small map; only for the loop check
+    updated.insert(template.name.clone(), template.expression.clone());
+    let parameters = template.placeholders();
+    let call = if parameters.is_empty() {
+        template.name.clone()
+    } else {
+        format!(
+            "{}({})",
+            template.name,
+            vec!["1"; parameters.len()].join(", ")
+        )
+    };
+    aliases::expand_user_aliases(&call, |name| updated.get(name).cloned())?;
+
+    Ok(template)
+}
+
+// How to call a macro, e.g. `atk(x)` for `1d20+{x}`
+fn macro_usage(template: &RollTemplate) -> String {
+    let parameters = template.placeholders();
+    if parameters.is_empty() {
+        template.name.clone()
+    } else {
+        format!("{}({})", template.name, parameters.join(", "))
+    }
+}
+
+/// The `/macro list` message
+pub fn format_macro_list(macros: &BTreeMap<String, String>) -> String {
+    if macros.is_empty() {
+        return "📜 You have no macros yet. Save one with `/macro add`.".to_string();
+    }
+    let mut output = format!("📜 **Your macros** ({}/{MAX_USER_MACROS})", macros.len());
+    for (name, expression) in macros {
+        output.push_str(&format!("\n`{name}` → `{expression}`"));
+    }
+    output
+}
@@ -0,0 +1,177 @@
+//! `/macropack` slash-command handler for built-in macro libraries.
+//!
+//! | Subcommand | Effect                                                     |
+//! |------------|------------------------------------------------------------|
+//! | `list`     | Show the available packs and which are enabled             |
+//! | `enable`   | Register every macro in a pack as a guild alias            |
+//! | `disable`  | Remove the aliases a pack registered                       |
+//!
+//! Packs are defined in [`templates::MACRO_PACKS`]; enabling one lets members
+//! roll e.g. `/roll stealth + 5`.  Macros whose name is already taken by
+//! another alias are skipped, never overwritten.  Changing packs requires the
+//! **Administrator** permission, enforced both via `default_member_permissions`
+//! and at runtime by the `middleware::Permissions` stage.
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::templates::{self, MACRO_PACKS, MacroPack};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    model::prelude::Permissions,
+    prelude::Context,
+};
+use tracing::info;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("macropack")
+        .description("Enable built-in macro packs for this server (administrators only)")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "Show the available macro packs",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "enable",
+                "Add a pack's macros to this server",
+            )
+            .add_sub_option(pack_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "disable",
+                "Remove a pack's macros from this server",
+            )
+            .add_sub_option(pack_option()),
+        )
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
+
+fn pack_option() -> CreateCommandOption {
+    MACRO_PACKS.iter().fold(
+        CreateCommandOption::new(CommandOptionType::String, "pack", "Macro pack").required(true),
+        |option, pack| option.add_string_choice(pack.name, pack.key),
+    )
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ This command can only be used in servers.".to_string(),
+        ));
+    };
+
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing macropack subcommand"));
+    };
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let guild_id = guild_id.get() as i64;
+
+    if subcommand.name == "list" {
+        let enabled = db.get_enabled_macro_packs(guild_id).await?;
+        return Ok(CommandResponse::private(format_pack_list(&enabled)));
+    }
+
+    let pack = match &subcommand.value {
+        CommandDataOptionValue::SubCommand(options) => options
+            .iter()
+            .find(|opt| opt.name == "pack")
+            .and_then(|opt| opt.value.as_str())
+            .and_then(templates::find_macro_pack),
+        _ => return Err(anyhow!("Invalid macropack subcommand")),
+    };
+    let Some(pack) = pack else {
+        return Ok(CommandResponse::private(
+            "❌ Unknown macro pack. Use `/macropack list` to see the available packs.".to_string(),
+        ));
+    };
+
+    match subcommand.name.as_str() {
+        "enable" => {
+            let skipped = db
+                .enable_macro_pack(guild_id, pack.key, pack.macros)
+                .await?;
+            info!(
+                "Enabled macro pack {} for guild {} (by {})",
+                pack.key, guild_id, command.user.id
+            );
+            Ok(CommandResponse::private(format_enabled(pack, &skipped)))
+        }
+        "disable" => {
+            let removed = db.disable_macro_pack(guild_id, pack.key).await?;
+            info!(
+                "Disabled macro pack {} for guild {} (by {})",
+                pack.key, guild_id, command.user.id
+            );
+            Ok(CommandResponse::private(if removed == 0 {
+                format!("ℹ️ **{}** was not enabled.", pack.name)
+            } else {
+                format!("🗑️ Disabled **{}** ({removed} macros removed).", pack.name)
+            }))
+        }
+        other => Err(anyhow!("Unknown macropack subcommand: {}", other)),
+    }
+}
+
+/// The available packs, marking the enabled ones
+pub fn format_pack_list(enabled: &[String]) -> String {
+    let mut output = "📚 **Macro packs**".to_string();
+    for pack in &MACRO_PACKS {
+        let status = if enabled.iter().any(|key| key == pack.key) {
+            "✅ enabled"
+        } else {
+            "not enabled"
+        };
+        output.push_str(&format!(
+            "\n**{}** (`{}`, {}): {}",
+            pack.name,
+            pack.key,
+            status,
+            macro_names(pack)
+        ));
+    }
+    output.push_str("\nEnable one with `/macropack enable`.");
+    output
+}
+
+/// Confirmation after enabling a pack, listing any macros that were skipped
+pub fn format_enabled(pack: &MacroPack, skipped: &[String]) -> String {
+    let added = pack.macros.len() - skipped.len();
+    let mut output = format!(
+        "✅ Enabled **{}** ({added} macros): {}\nTry `/roll {}`.",
+        pack.name,
+        macro_names(pack),
+        pack.macros.first().map_or("", |(name, _)| name)
+    );
+    if !skipped.is_empty() {
+        output.push_str(&format!(
+            "\n⚠️ Skipped because the name is already in use: {}",
+            skipped
+                .iter()
+                .map(|name| format!("`{name}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    output
+}
+
+fn macro_names(pack: &MacroPack) -> String {
+    pack.macros
+        .iter()
+        .map(|(name, _)| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,6 +1,104 @@
+pub mod admin;
+pub mod again;
+pub mod alias;
+pub mod calc;
+pub mod char_cmd;
+pub mod clock;
+pub mod config;
+pub mod confirm;
+pub mod debugparse;
+pub mod draw;
+pub mod followup;
+pub mod forgetme;
 pub mod help;
+pub mod history;
+pub mod init;
+pub mod keyword;
+pub mod macro_cmd;
+pub mod macropack;
+pub mod mystats;
+pub mod odds;
+pub mod prefs;
+pub mod privacy;
 pub mod purge;
+pub mod reroll_select;
 pub mod roll;
+pub mod session;
+pub mod settings;
+pub mod setup;
+pub mod sheet;
+pub mod stats;
+pub mod streak;
+pub mod table;
+pub mod tally;
+pub mod text_prefix;
+pub mod tips;
+pub mod token_store;
+pub mod usage;
+pub mod verify;
 
 // Re-export CommandResponse for use in main.rs
-pub use roll::CommandResponse;
+pub use roll::{CommandResponse, RollOrigin};
+
+/// Every slash command the bot registers, in registration order
+pub fn all_commands() -> Vec<serenity::builder::CreateCommand> {
+    vec![
+        roll::register(),
+        roll::register_r_alias(),
+        again::register(),
+        calc::register(),
+        odds::register(),
+        help::register(),
+        purge::register(),
+        tally::register(),
+        clock::register(),
+        config::register(),
+        forgetme::register(),
+        privacy::register(),
+        prefs::register(),
+        mystats::register(),
+        stats::register(),
+        usage::register(),
+        admin::register(),
+        debugparse::register(),
+        setup::register(),
+        macropack::register(),
+        alias::register(),
+        macro_cmd::register(),
+        keyword::register(),
+        followup::register(),
+        settings::register(),
+        sheet::register(),
+        char_cmd::register(),
+        session::register(),
+        verify::register(),
+        table::register(),
+        init::register(),
+        history::register(),
+        tips::register(),
+        draw::register(),
+    ]
+    .into_iter()
+    .map(crate::locale::localize_command)
+    .collect()
+}
+
+/// The refusal for users who aren't the application owner (or a team
+/// member), or `None` when `command` may run an owner-only command
+pub async fn require_owner(
+    ctx: &serenity::prelude::Context,
+    command: &serenity::all::CommandInteraction,
+) -> Option<CommandResponse> {
+    let owners = ctx
+        .data
+        .read()
+        .await
+        .get::<crate::OwnersContainer>()
+        .cloned();
+    if owners.is_some_and(|owners| owners.contains(&command.user.id)) {
+        return None;
+    }
+    Some(CommandResponse::private(
+        "❌ This command is only available to the bot owner.".to_string(),
+    ))
+}
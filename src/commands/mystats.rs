@@ -0,0 +1,104 @@
+//! `/mystats` slash-command handler.
+//!
+//! | Subcommand | Effect                                                     |
+//! |------------|------------------------------------------------------------|
+//! | `export`   | Attach CSVs of your rolls: counts per day and per system   |
+//!
+//! The files are built by [`stats_export`](crate::stats_export) from the
+//! user's `roll_history` rows in every server, so they cover what `/privacy`
+//! lets the bot keep: nothing with history off, and no systems for rolls
+//! recorded as totals only.  The reply is private.  `/admin stats export`
+//! sends the same files for every user through [`export_response`].
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::stats_export::{self, MAX_EXPORT_BYTES, StatsExport};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandInteraction, CommandOptionType},
+    builder::{CreateAttachment, CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use tracing::info;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("mystats")
+        .description("Your roll statistics")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "export",
+            "Download your roll statistics as CSV files",
+        ))
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing mystats subcommand"));
+    };
+    if subcommand.name != "export" {
+        return Err(anyhow!("Unknown mystats subcommand: {}", subcommand.name));
+    }
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+
+    let export = stats_export::export_user_stats(&db, command.user.id.get() as i64).await?;
+    if export.rolls == 0 {
+        return Ok(CommandResponse::private(
+            "📊 No rolls of yours are recorded yet. History is kept for 30 days; \
+             check your level with `/privacy`."
+                .to_string(),
+        ));
+    }
+
+    info!(
+        "Exported stats for {}: {} rolls",
+        command.user.id, export.rolls
+    );
+    Ok(export_response("Your roll statistics", export))
+}
+
+/// The private reply carrying an export's CSV files
+pub fn export_response(title: &str, export: StatsExport) -> CommandResponse {
+    let content = format_export_summary(title, &export);
+    let attachments = export
+        .files
+        .into_iter()
+        .map(|file| CreateAttachment::bytes(file.contents.into_bytes(), file.name))
+        .collect();
+    CommandResponse::private(content).with_attachments(attachments)
+}
+
+/// The message sent with an export, e.g. "📊 **Your roll statistics**: 42
+/// rolls over 3 days, average total 11.50"
+pub fn format_export_summary(title: &str, export: &StatsExport) -> String {
+    let mut output = format!(
+        "📊 **{title}**: {} roll{} over {} day{}",
+        export.rolls,
+        if export.rolls == 1 { "" } else { "s" },
+        export.days,
+        if export.days == 1 { "" } else { "s" },
+    );
+    if let Some(average) = export.average_total {
+        output.push_str(&format!(
+            ", average total {}",
+            stats_export::format_average(average)
+        ));
+    }
+    output.push_str(" (UTC days).");
+
+    for file in export.files.iter().filter(|file| file.truncated) {
+        output.push_str(&format!(
+            "\n*Note: `{}` stops after {} rows to stay under {} KB*",
+            file.name,
+            file.rows,
+            MAX_EXPORT_BYTES / 1024
+        ));
+    }
+    output
+}
@@ -0,0 +1,168 @@
+//! `/odds` slash-command handler: the chances of a roll without rolling it.
+//!
+//! `/odds 3d6` shows the mean, lowest and highest result, the 10th, 50th and
+//! 90th percentiles, a one-line chart of the whole distribution and a
+//! histogram of every result from [`probability`](crate::dice::probability),
+//! worked out exactly where it can be and from simulated rolls otherwise.
+//! When the reply would be too long for a Discord message, the chart and
+//! histogram merge more values into each column and line.  With
+//! `target:15` it adds the chance of rolling 15 or more and 15 or less; for a
+//! roll that counts successes the target is a number of successes.
+//!
+//! Only single rolls are supported.  The reply is public unless the
+//! expression has the `p` flag, as in `/calc`.
+
+use crate::commands::{CommandResponse, RollOrigin, roll};
+use crate::dice::DISCORD_MESSAGE_LIMIT;
+use crate::dice::probability::{self, Distribution};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+
+/// Most lines in the histogram
+pub const HISTOGRAM_ROWS: usize = 20;
+
+/// Most columns in the distribution chart
+pub const CHART_COLUMNS: usize = 40;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("odds")
+        .description("Show the chances of a roll without rolling it")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "expression",
+                "The roll to work out (e.g. 3d6, 4d6 k3, 1d20+5)",
+            )
+            .required(true),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::Integer,
+            "target",
+            "Also show the chance of rolling at least and at most this",
+        ))
+}
+
+pub async fn run(_ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let option = |name: &str| {
+        command
+            .data
+            .options
+            .iter()
+            .find(|opt| opt.name == name)
+            .map(|opt| &opt.value)
+    };
+    let expression = option("expression")
+        .and_then(|value| value.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let target = option("target").and_then(|value| value.as_i64());
+
+    let display_name = roll::get_display_name(&RollOrigin::from(command));
+    let clean_expr = roll::strip_label_and_comment_from_expression(&expression);
+    let dice = match probability::parse_single_roll(&expression) {
+        Ok(dice) => dice,
+        Err(e) => {
+            return Ok(CommandResponse::private(format!(
+                "📊 `{clean_expr}` - ❌ **Error**: {e}"
+            )));
+        }
+    };
+    let private = dice.private;
+
+    // Simulating can take a couple of seconds, so keep it off the async runtime
+    let distribution = tokio::task::spawn_blocking(move || {
+        probability::roll_distribution(&dice, &mut crate::dice::create_fast_rng())
+    })
+    .await
+    .map_err(|e| anyhow!("Odds calculation failed: {e}"))?;
+
+    match distribution {
+        Ok(distribution) => {
+            let heading = format!("📊 **{display_name}** Odds: `{clean_expr}` ");
+            let budget = DISCORD_MESSAGE_LIMIT.saturating_sub(heading.chars().count());
+            Ok(CommandResponse::new(
+                format!(
+                    "{heading}{}",
+                    format_odds_within(&distribution, target, budget)
+                ),
+                private,
+            ))
+        }
+        Err(e) => Ok(CommandResponse::private(format!(
+            "📊 `{clean_expr}` - ❌ **Error**: {e}"
+        ))),
+    }
+}
+
+/// The reply after the expression: how the odds were found, the mean and
+/// range, the target chances, the percentiles, the chart and the histogram
+pub fn format_odds(distribution: &Distribution, target: Option<i64>) -> String {
+    format_odds_within(distribution, target, DISCORD_MESSAGE_LIMIT)
+}
+
+/// [`format_odds`] in at most `limit` characters: the chart and histogram
+/// are halved, merging neighbouring values, until the reply fits
+pub fn format_odds_within(
+    distribution: &Distribution,
+    target: Option<i64>,
+    limit: usize,
+) -> String {
+    let (mut columns, mut rows) = (CHART_COLUMNS, HISTOGRAM_ROWS);
+    loop {
+        let output = format_odds_sized(distribution, target, columns, rows);
+        if output.chars().count() <= limit || rows == 1 {
+            return output;
+        }
+        columns = (columns / 2).max(1);
+        rows = (rows / 2).max(1);
+    }
+}
+
+fn format_odds_sized(
+    distribution: &Distribution,
+    target: Option<i64>,
+    columns: usize,
+    rows: usize,
+) -> String {
+    let method = match distribution.samples {
+        None => "exact".to_string(),
+        Some(samples) => format!("simulated from {samples} rolls"),
+    };
+    let unit = if distribution.successes {
+        " successes"
+    } else {
+        ""
+    };
+    let mut output = format!(
+        "({method})\nMean **{:.2}**{unit} · Min **{}** · Max **{}**",
+        distribution.mean(),
+        distribution.min(),
+        distribution.max()
+    );
+    if let Some(target) = target {
+        output.push_str(&format!(
+            "\n{target}+{unit}: **{}** · {target} or less: **{}**",
+            probability::format_percent(distribution.chance_at_least(target)),
+            probability::format_percent(distribution.chance_at_most(target))
+        ));
+    }
+    let percentiles: Vec<String> = probability::CHART_PERCENTILES
+        .iter()
+        .zip(["10th", "50th", "90th"])
+        .map(|(&fraction, name)| format!("{name} **{}**", distribution.percentile(fraction)))
+        .collect();
+    output.push_str(&format!("\nPercentiles: {}", percentiles.join(" · ")));
+    output.push_str(&format!(
+        "\n```\n{}\n```",
+        probability::format_chart(distribution, columns)
+    ));
+    output.push_str(&format!(
+        "```\n{}\n```",
+        probability::format_histogram(distribution, rows)
+    ));
+    output
+}
@@ -0,0 +1,310 @@
+//! `/prefs` slash-command handler for a user's own roll defaults.
+//!
+//! | Subcommand | Effect                                                          |
+//! |------------|-----------------------------------------------------------------|
+//! | `view`     | Show your preferences                                           |
+//! | `unsorted` | Leave your dice in the order rolled, as the `ul` flag does      |
+//! | `simple`   | Show only your results, as the `s` flag does                    |
+//! | `skin`     | The emoji heading your rolls, e.g. 🐉 instead of 🎲             |
+//! | `timezone` | UTC offset `/history` times are shown in, e.g. `UTC-5`          |
+//! | `reset`    | Go back to the defaults                                         |
+//!
+//! Preferences belong to the user and apply in every server and in DMs.
+//! They are merged with the server's `/settings` when `commands::roll` rolls:
+//! [`with_default_flags`] adds the `ul` and `s` flags to each expression
+//! before parsing, so a server's `simple` output mode still applies to a
+//! user who didn't ask for it, and [`apply_skin`] swaps the 🎲 heading the
+//! finished message.  Timezones are fixed offsets, so daylight saving time
+//! isn't followed.  Preferences live in the `user_prefs` table and are
+//! removed by `/forgetme`.
+
+use crate::DatabaseContainer;
+use crate::commands::{CommandResponse, RollOrigin};
+use crate::database::{DiceSkin, UserPrefs};
+use anyhow::{Result, anyhow};
+use chrono::{Duration, NaiveDateTime};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use tracing::warn;
+
+/// Furthest a timezone may be from UTC, in minutes (UTC-12 to UTC+14)
+pub const MIN_UTC_OFFSET: i32 = -12 * 60;
+pub const MAX_UTC_OFFSET: i32 = 14 * 60;
+
+// `UTC+2`, `GMT-5:30`, `+0330` or `-3`
+static UTC_OFFSET_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:utc|gmt)?\s*(?:([+-])\s*(\d{1,2})(?::?(\d{2}))?)?$")
+        .expect("Failed to compile UTC_OFFSET_REGEX")
+});
+
+pub fn register() -> CreateCommand {
+    let enabled_option = |description: &str| {
+        CreateCommandOption::new(CommandOptionType::Boolean, "enabled", description).required(true)
+    };
+    let skin_option = DiceSkin::ALL.into_iter().fold(
+        CreateCommandOption::new(CommandOptionType::String, "skin", "Dice emoji").required(true),
+        |option, skin| {
+            option.add_string_choice(format!("{} {}", skin.emoji(), skin.as_str()), skin.as_str())
+        },
+    );
+
+    CreateCommand::new("prefs")
+        .description("Set your own roll defaults, in every server")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "view",
+            "Show your roll preferences",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "unsorted",
+                "Always leave your dice in the order rolled (like ul)",
+            )
+            .add_sub_option(enabled_option("Whether your dice stay unsorted")),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "simple",
+                "Always show only your results, not the dice (like s)",
+            )
+            .add_sub_option(enabled_option("Whether your rolls show only results")),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "skin",
+                "Choose the emoji heading your rolls",
+            )
+            .add_sub_option(skin_option),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "timezone",
+                "Show /history times in your timezone",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "offset",
+                    "UTC offset, e.g. UTC+2 or UTC-5:30 (omit for UTC)",
+                )
+                .max_length(16),
+            ),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "reset",
+            "Go back to the default preferences",
+        ))
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing prefs subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid prefs subcommand"));
+    };
+    let option = |name: &str| options.iter().find(|opt| opt.name == name);
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let user_id = command.user.id.get() as i64;
+    let mut prefs = db.get_user_prefs(user_id).await?;
+
+    let content = match subcommand.name.as_str() {
+        "view" => return Ok(CommandResponse::private(format_prefs(&prefs))),
+        "reset" => {
+            db.delete_user_prefs(user_id).await?;
+            return Ok(CommandResponse::private(
+                "⚙️ Your roll preferences are back to the defaults.".to_string(),
+            ));
+        }
+        "unsorted" => {
+            prefs.unsorted = option("enabled")
+                .and_then(|opt| opt.value.as_bool())
+                .unwrap_or_default();
+            if prefs.unsorted {
+                "⚙️ Your dice now stay in the order they were rolled.".to_string()
+            } else {
+                "⚙️ Your dice are now sorted high to low.".to_string()
+            }
+        }
+        "simple" => {
+            prefs.simple = option("enabled")
+                .and_then(|opt| opt.value.as_bool())
+                .unwrap_or_default();
+            if prefs.simple {
+                "⚙️ Your rolls now show only their results.".to_string()
+            } else {
+                "⚙️ Your rolls now show their dice, unless the server's `/settings` hide them."
+                    .to_string()
+            }
+        }
+        "skin" => {
+            let value = option("skin")
+                .and_then(|opt| opt.value.as_str())
+                .unwrap_or_default();
+            let Some(skin) = DiceSkin::parse(value) else {
+                return Ok(CommandResponse::private(format!(
+                    "❌ Unknown dice skin `{value}`."
+                )));
+            };
+            prefs.dice_skin = skin;
+            format!(
+                "{} Your dice skin is now **{}**.",
+                skin.emoji(),
+                skin.as_str()
+            )
+        }
+        "timezone" => {
+            let value = option("offset").and_then(|opt| opt.value.as_str());
+            let offset = match value.map(parse_utc_offset) {
+                None => None,
+                Some(Some(offset)) => Some(offset).filter(|&offset| offset != 0),
+                Some(None) => {
+                    return Ok(CommandResponse::private(format!(
+                        "❌ `{}` isn't a UTC offset. Use one like `UTC+2`, `UTC-5:30` or `+0100`, between UTC-12 and UTC+14.",
+                        value.unwrap_or_default()
+                    )));
+                }
+            };
+            prefs.utc_offset = offset;
+            format!(
+                "🕒 `/history` times are now shown in **{}**.",
+                format_utc_offset(offset)
+            )
+        }
+        other => return Err(anyhow!("Unknown prefs subcommand: {other}")),
+    };
+
+    db.save_user_prefs(&prefs).await?;
+    Ok(CommandResponse::private(content))
+}
+
+/// The `/prefs view` message
+pub fn format_prefs(prefs: &UserPrefs) -> String {
+    let on_off = |enabled: bool| if enabled { "on" } else { "off" };
+    format!(
+        "⚙️ **Your roll preferences** (in every server)\n\
+         • Unsorted dice (`ul`): **{}**\n\
+         • Simple output (`s`): **{}**\n\
+         • Dice skin: {} **{}**\n\
+         • Timezone for `/history`: **{}**\n\
+         Change them with `/prefs`, or `/prefs reset` to clear them.",
+        on_off(prefs.unsorted),
+        on_off(prefs.simple),
+        prefs.dice_skin.emoji(),
+        prefs.dice_skin.as_str(),
+        format_utc_offset(prefs.utc_offset)
+    )
+}
+
+/// Minutes east of UTC for an offset such as `UTC+2`, `UTC-5:30` or `+0100`;
+/// a bare `UTC` is 0.  `None` when it isn't an offset or is out of range.
+pub fn parse_utc_offset(value: &str) -> Option<i32> {
+    let captures = UTC_OFFSET_REGEX.captures(value.trim())?;
+    let Some(sign) = captures.get(1) else {
+        // An empty string isn't a timezone
+        return (!value.trim().is_empty()).then_some(0);
+    };
+    let hours: i32 = captures[2].parse().ok()?;
+    let minutes: i32 = captures
+        .get(3)
+        .map_or(Some(0), |m| m.as_str().parse().ok())?;
+    if minutes >= 60 {
+        return None;
+    }
+    let offset = hours * 60 + minutes;
+    let offset = if sign.as_str() == "-" {
+        -offset
+    } else {
+        offset
+    };
+    (MIN_UTC_OFFSET..=MAX_UTC_OFFSET)
+        .contains(&offset)
+        .then_some(offset)
+}
+
+/// `UTC`, `UTC+2` or `UTC-5:30`
+pub fn format_utc_offset(offset: Option<i32>) -> String {
+    match offset {
+        None | Some(0) => "UTC".to_string(),
+        Some(offset) => {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let (hours, minutes) = (offset.abs() / 60, offset.abs() % 60);
+            if minutes == 0 {
+                format!("UTC{sign}{hours}")
+            } else {
+                format!("UTC{sign}{hours}:{minutes:02}")
+            }
+        }
+    }
+}
+
+/// A UTC database timestamp (`2026-10-16 21:05:00`) as `HH:MM` at `offset`
+pub fn local_time(timestamp: &str, offset: Option<i32>) -> Option<String> {
+    let utc = NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()?;
+    let local = utc + Duration::minutes(i64::from(offset.unwrap_or(0)));
+    Some(local.format("%H:%M").to_string())
+}
+
+/// `expression` with the flags the user's preferences turn on added in
+/// front of each `;`-separated roll.  Sentences with `[[inline]]` rolls get
+/// the flags on each inline expression instead, by the caller.
+pub fn with_default_flags(prefs: &UserPrefs, expression: &str) -> String {
+    let flags: String = [(prefs.unsorted, "ul "), (prefs.simple, "s ")]
+        .into_iter()
+        .filter_map(|(enabled, flag)| enabled.then_some(flag))
+        .collect();
+    if flags.is_empty() {
+        return expression.to_string();
+    }
+    expression
+        .split(';')
+        .map(|part| format!("{flags}{}", part.trim()))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Swap the 🎲 heading the response (and its suspense frames) for the
+/// user's dice skin
+pub fn apply_skin(prefs: &UserPrefs, response: &mut CommandResponse) {
+    if prefs.dice_skin == DiceSkin::Classic {
+        return;
+    }
+    let skin = |text: &mut String| {
+        if let Some(rest) = text.strip_prefix(DiceSkin::Classic.emoji()) {
+            *text = format!("{}{rest}", prefs.dice_skin.emoji());
+        }
+    };
+    skin(&mut response.content);
+    for frame in response.reveal.iter_mut().flatten() {
+        skin(frame);
+    }
+}
+
+/// The roller's preferences; the defaults when there's no database
+pub async fn load(ctx: &Context, origin: &RollOrigin<'_>) -> UserPrefs {
+    let user_id = origin.user.id.get() as i64;
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return UserPrefs::new(user_id);
+    };
+    db.get_user_prefs(user_id).await.unwrap_or_else(|e| {
+        warn!("Failed to load roll preferences: {}", e);
+        UserPrefs::new(user_id)
+    })
+}
@@ -0,0 +1,209 @@
+//! `/privacy` slash-command handler and roll-history recording.
+//!
+//! Rolls are written to the `roll_history` table at one of three levels:
+//!
+//! | Level    | Recorded                                   |
+//! |----------|--------------------------------------------|
+//! | `off`    | Nothing                                    |
+//! | `totals` | Total only — no expression or dice         |
+//! | `full`   | Expression, formatted result and total     |
+//!
+//! Users pick their own level with `/privacy history:<level>`; server
+//! administrators set a server-wide level with `/config history`.  The stricter
+//! of the two wins, and private (`p`) and secret `gm` rolls are never recorded,
+//! so they stay out of `/history channel` and the server's log drain.
+//! The level is applied by [`record_roll`] at write time, so data above the
+//! chosen level never reaches the database.  Both levels keep whether a check
+//! succeeded, which `commands::streak` reads for streak titles.
+
+use crate::DatabaseContainer;
+use crate::commands::{CommandResponse, RollOrigin, streak};
+use crate::database::{HistoryLevel, RollHistoryEntry};
+use crate::dice::{self, RollResult};
+use crate::log_drain;
+use crate::write_queue::{self, DbWrite};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use tracing::warn;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("privacy")
+        .description("View or change how much of your roll history Dice Maiden keeps")
+        .add_option(history_level_option(
+            "history",
+            "Record your rolls: off, totals only, or full (omit to view)",
+        ))
+}
+
+/// The history level choice option shared by `/privacy` and `/config history`
+pub fn history_level_option(name: &str, description: &str) -> CreateCommandOption {
+    CreateCommandOption::new(CommandOptionType::String, name, description)
+        .required(false)
+        .add_string_choice("Off", HistoryLevel::Off.as_str())
+        .add_string_choice("Totals only", HistoryLevel::TotalsOnly.as_str())
+        .add_string_choice("Full", HistoryLevel::Full.as_str())
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let user_id = command.user.id.get() as i64;
+
+    let requested = command
+        .data
+        .options
+        .iter()
+        .find(|opt| opt.name == "history")
+        .and_then(|opt| opt.value.as_str());
+
+    match requested {
+        Some(value) => {
+            let Some(level) = HistoryLevel::parse(value) else {
+                return Ok(CommandResponse::private(format!(
+                    "❌ Unknown history level `{value}`. Use off, totals or full."
+                )));
+            };
+            db.set_privacy_level("user", user_id, level).await?;
+            Ok(CommandResponse::private(format!(
+                "🔒 Your roll history is now **{}**.",
+                level.description()
+            )))
+        }
+        None => {
+            let level = db
+                .get_privacy_level("user", user_id)
+                .await?
+                .unwrap_or_default();
+            let mut content = format!("🔒 Your roll history: **{}**.", level.description());
+
+            if let Some(guild_id) = command.guild_id {
+                let effective = db
+                    .history_level(Some(guild_id.get() as i64), user_id)
+                    .await?;
+                if effective != level {
+                    content.push_str(&format!(
+                        "\nThis server's setting is stricter, so your rolls here are recorded as **{}**.",
+                        effective.description()
+                    ));
+                }
+            }
+
+            content.push_str("\nChange it with `/privacy history:<off|totals|full>`.");
+            Ok(CommandResponse::private(content))
+        }
+    }
+}
+
+/// Build the history row for a roll at the given level, or `None` when
+/// nothing should be recorded.  Private and `gm` rolls are never recorded.
+pub fn history_entry(
+    level: HistoryLevel,
+    guild_id: Option<i64>,
+    channel_id: i64,
+    user_id: i64,
+    expression: &str,
+    results: &[RollResult],
+    formatted: &str,
+) -> Option<RollHistoryEntry> {
+    let level = if results.iter().any(|r| r.private || r.gm) {
+        HistoryLevel::Off
+    } else {
+        level
+    };
+    let total = results
+        .iter()
+        .map(|r| i64::from(dice::calculate_result_value(r)))
+        .sum();
+    let outcome = streak::roll_outcome(expression, results);
+
+    match level {
+        HistoryLevel::Off => None,
+        HistoryLevel::TotalsOnly => Some(RollHistoryEntry {
+            guild_id,
+            channel_id,
+            user_id,
+            expression: None,
+            result: None,
+            total,
+            outcome,
+            round: None,
+            turn: None,
+        }),
+        HistoryLevel::Full => Some(RollHistoryEntry {
+            guild_id,
+            channel_id,
+            user_id,
+            expression: Some(expression.to_string()),
+            result: Some(formatted.to_string()),
+            total,
+            outcome,
+            round: None,
+            turn: None,
+        }),
+    }
+}
+
+/// Record a roll in history according to the guild's and user's privacy levels.
+///
+/// Failures are logged and never affect the roll itself.
+pub async fn record_roll(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    expression: &str,
+    results: &[RollResult],
+    formatted: &str,
+) {
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return;
+    };
+
+    let guild_id = origin.guild_id.map(|id| id.get() as i64);
+    let user_id = origin.user.id.get() as i64;
+
+    let level = match db.history_level(guild_id, user_id).await {
+        Ok(level) => level,
+        Err(e) => {
+            // Fail closed: without a known level, record nothing
+            warn!("Failed to read history level for {}: {}", user_id, e);
+            return;
+        }
+    };
+
+    let Some(mut entry) = history_entry(
+        level,
+        guild_id,
+        origin.channel_id.get() as i64,
+        user_id,
+        expression,
+        results,
+        formatted,
+    ) else {
+        return;
+    };
+
+    // Rolls during a channel's combat are tagged with its round and turn
+    match db.get_initiative_tracker(entry.channel_id).await {
+        Ok(Some(tracker)) if tracker.round > 0 => {
+            entry.round = Some(tracker.round);
+            entry.turn = tracker.current;
+        }
+        Ok(_) => {}
+        Err(e) => warn!(
+            "Failed to read initiative in channel {}: {}",
+            origin.channel_id, e
+        ),
+    }
+
+    // The server's log drain gets exactly what history keeps
+    log_drain::forward(ctx, &entry).await;
+    write_queue::enqueue(ctx, DbWrite::RollHistory(entry)).await;
+}
@@ -2,17 +2,28 @@
 //!
 //! Deletes 2–100 recent messages from the current channel. Requires the
 //! **Manage Messages** or **Administrator** permission, enforced both via
-//! `default_member_permissions` and at runtime. Falls back to sequential
-//! single-message deletion if Discord's bulk-delete endpoint fails.
+//! `default_member_permissions` and at runtime, again when the deletion is
+//! confirmed.  The command only shows a private `commands::confirm` prompt;
+//! messages are deleted by [`handle_component`] once its button is pressed.
+//! Falls back to sequential single-message deletion if Discord's bulk-delete
+//! endpoint fails.
 
-use anyhow::Result;
+use crate::commands::CommandResponse;
+use crate::commands::confirm::{self, Choice};
+use crate::outbound;
+use anyhow::{Result, anyhow};
 use serenity::{
-    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType, GetMessages},
+    all::{
+        ChannelId, CommandDataOptionValue, CommandInteraction, CommandOptionType,
+        ComponentInteraction, GetMessages,
+    },
     builder::{CreateCommand, CreateCommandOption},
     model::prelude::Permissions,
     prelude::Context,
 };
 
+const PREFIX: &str = "purge";
+
 pub fn register() -> CreateCommand {
     CreateCommand::new("purge")
         .description("Purge recent messages (requires manage messages permission)")
@@ -29,27 +40,12 @@ pub fn register() -> CreateCommand {
         .default_member_permissions(Permissions::MANAGE_MESSAGES)
 }
 
-pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<String> {
-    // Check if user has manage messages permission
-    let has_permission = match command.member.as_ref() {
-        Some(member) => {
-            let _guild_id = command
-                .guild_id
-                .ok_or_else(|| anyhow::anyhow!("Purge command can only be used in servers"))?;
-
-            // Check if user has admin or manage messages permission
-            member
-                .permissions
-                .is_some_and(|perms| perms.administrator() || perms.manage_messages())
-        }
-        None => return Err(anyhow::anyhow!("This command can only be used in servers")),
-    };
-
-    if !has_permission {
-        return Ok(
-            "❌ You need the 'Manage Messages' or 'Administrator' permission to use this command."
-                .to_string(),
-        );
+pub async fn run(_ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    if command.guild_id.is_none() {
+        return Err(anyhow!("This command can only be used in servers"));
+    }
+    if !can_purge(command.member.as_ref().and_then(|m| m.permissions)) {
+        return Ok(CommandResponse::public(NO_PERMISSION.to_string()));
     }
 
     let count = command
@@ -63,43 +59,89 @@ pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<String>
         .unwrap_or(10);
 
     if !(2..=100).contains(&count) {
-        return Ok("❌ Message count must be between 2 and 100.".to_string());
+        return Ok(CommandResponse::public(
+            "❌ Message count must be between 2 and 100.".to_string(),
+        ));
+    }
+
+    Ok(confirm::prompt(
+        PREFIX,
+        &format!("⚠️ This will permanently delete the last **{count}** messages in this channel."),
+        &format!("Delete {count} messages"),
+        Some(&count.to_string()),
+    ))
+}
+
+/// Handle the `purge:` confirmation buttons, returning the replacement message
+pub async fn handle_component(ctx: &Context, component: &ComponentInteraction) -> Result<String> {
+    let count = match confirm::parse_now(PREFIX, &component.data.custom_id) {
+        Some(Choice::Cancelled) => return Ok("Purge cancelled. Nothing was deleted.".to_string()),
+        Some(Choice::Expired) => return Ok(confirm::expired_message("/purge")),
+        Some(Choice::Confirmed(count)) => count
+            .and_then(|count| count.parse::<u8>().ok())
+            .filter(|count| (2..=100).contains(count))
+            .ok_or_else(|| anyhow!("Invalid purge button: {}", component.data.custom_id))?,
+        None => {
+            return Err(anyhow!(
+                "Unknown purge button: {}",
+                component.data.custom_id
+            ));
+        }
+    };
+
+    // Permissions may have changed since the prompt was shown
+    if !can_purge(component.member.as_ref().and_then(|m| m.permissions)) {
+        return Ok(NO_PERMISSION.to_string());
+    }
+
+    let deleted_count = delete_recent(ctx, component.channel_id, count).await?;
+    if deleted_count == 0 {
+        return Ok("❌ No messages found to delete.".to_string());
     }
+    Ok(format!(
+        "🗑️ Successfully deleted {deleted_count} message(s)."
+    ))
+}
+
+const NO_PERMISSION: &str =
+    "❌ You need the 'Manage Messages' or 'Administrator' permission to use this command.";
 
+fn can_purge(permissions: Option<Permissions>) -> bool {
+    permissions.is_some_and(|perms| perms.administrator() || perms.manage_messages())
+}
+
+// Delete the channel's last `count` messages, returning how many went
+async fn delete_recent(ctx: &Context, channel_id: ChannelId, count: u8) -> Result<usize> {
     // Get recent messages
-    let messages = command
-        .channel_id
-        .messages(&ctx.http, GetMessages::new().limit(count))
-        .await?;
+    let messages = outbound::send_with_retry("purge fetch", || {
+        channel_id.messages(&ctx.http, GetMessages::new().limit(count))
+    })
+    .await?;
 
     if messages.is_empty() {
-        return Ok("❌ No messages found to delete.".to_string());
+        return Ok(0);
     }
 
     // Delete messages
     let deleted_count = if messages.len() == 1 {
         // Delete single message
-        command
-            .channel_id
-            .delete_message(&ctx.http, messages[0].id)
-            .await?;
+        channel_id.delete_message(&ctx.http, messages[0].id).await?;
         1
     } else {
         // Bulk delete (Discord API limitation: messages must be less than 2 weeks old)
         let message_ids: Vec<_> = messages.iter().map(|m| m.id).collect();
 
-        match command
-            .channel_id
-            .delete_messages(&ctx.http, &message_ids)
-            .await
+        match outbound::send_with_retry("purge bulk delete", || {
+            channel_id.delete_messages(&ctx.http, &message_ids)
+        })
+        .await
         {
             Ok(_) => message_ids.len(),
             Err(_) => {
                 // Fallback to individual deletion if bulk delete fails
                 let mut deleted = 0;
                 for message in &messages {
-                    if command
-                        .channel_id
+                    if channel_id
                         .delete_message(&ctx.http, message.id)
                         .await
                         .is_ok()
@@ -112,7 +154,5 @@ pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<String>
         }
     };
 
-    Ok(format!(
-        "🗑️ Successfully deleted {deleted_count} message(s)."
-    ))
+    Ok(deleted_count)
 }
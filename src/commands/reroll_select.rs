@@ -0,0 +1,217 @@
+//! "Reroll selected" menu under rolls: pick some of a roll's dice and roll
+//! just those again, for abilities such as Great Weapon Fighting or a
+//! willpower reroll.
+//!
+//! A roll gets the menu when it is one standard roll shown with its dice
+//! (see `roller::supports_selected_reroll`): its only random draws are its
+//! main dice, so every die left alone keeps its value.  Each option is one
+//! of those dice, kept dice first.  Picking some edits the roll's message in
+//! place with the new dice and total; a note lists the old values struck
+//! through.  Only the original roller may use the menu, and only once, so
+//! a roll can't be fished for a better result.  Session and provably-fair
+//! rolls get no menu, since their dice come from a seeded stream, and
+//! stats, history and tallies keep the original roll.
+//!
+//! The roll behind a menu is kept in memory
+//! ([`SelectedRerollContainer`](crate::SelectedRerollContainer)) under a
+//! token in the menu's custom ID (`rerollpick:<token>`), like `/followup`
+//! buttons.  The store holds the newest [`SELECTED_REROLL_CAPACITY`] menus
+//! and is lost on restart; older menus then say they have expired.
+
+use crate::SelectedRerollContainer;
+use crate::commands::token_store::TokenStore;
+use crate::commands::{CommandResponse, RollOrigin};
+use crate::dice::{DISCORD_MESSAGE_LIMIT, RollResult, parser, rng, roller};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{ComponentInteraction, ComponentInteractionDataKind},
+    builder::{CreateActionRow, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption},
+    prelude::Context,
+};
+
+/// Custom ID prefix of Reroll selected menus: `rerollpick:<token>`
+pub const PICK_PREFIX: &str = "rerollpick:";
+
+/// Menus remembered before the oldest are dropped
+pub const SELECTED_REROLL_CAPACITY: usize = 10_000;
+
+/// Most dice offered in one menu (Discord's limit on select options)
+pub const MAX_SELECTABLE_DICE: usize = 25;
+
+/// A roll whose dice can be picked to reroll
+#[derive(Debug, Clone)]
+pub struct PendingReroll {
+    pub user_id: u64,
+    /// The expression as rolled, after aliases and stats expanded
+    pub expression: String,
+    /// The roll's main dice, as `roller::selectable_dice` orders them
+    pub rolls: Vec<i32>,
+    /// How many of `rolls` were kept; the rest were dropped
+    pub kept: usize,
+    /// The message around the result, which the reroll keeps
+    pub before: String,
+    pub after: String,
+    /// The message's other components, kept when the menu is used
+    pub rows: Vec<CreateActionRow>,
+}
+
+/// Rolls behind recent Reroll selected menus, by token
+pub type SelectedRerolls = TokenStore<PendingReroll, SELECTED_REROLL_CAPACITY>;
+
+/// The dice of `results` that can be picked to reroll and how many of them
+/// were kept, or `None` if the roll can't be partly rerolled
+pub fn selectable_roll(expression: &str, results: &[RollResult]) -> Option<(Vec<i32>, usize)> {
+    let [result] = results else {
+        return None;
+    };
+    if result.simple || result.no_results {
+        return None;
+    }
+    let dice = parser::parse_dice_string(expression).ok()?;
+    let [dice] = dice.as_slice() else {
+        return None;
+    };
+    if !roller::supports_selected_reroll(dice) {
+        return None;
+    }
+    let rolls = roller::selectable_dice(result);
+    if rolls.is_empty() || rolls.len() > MAX_SELECTABLE_DICE || rolls.len() != dice.count as usize {
+        return None;
+    }
+    Some((rolls, result.kept_rolls.len()))
+}
+
+/// The menu listing `rolls`, of which the first `kept` were kept
+pub fn select_menu(token: u64, rolls: &[i32], kept: usize) -> CreateActionRow {
+    let options = rolls
+        .iter()
+        .enumerate()
+        .map(|(index, roll)| {
+            let label = if index < kept {
+                format!("Die {}: {roll}", index + 1)
+            } else {
+                format!("Die {}: {roll} (dropped)", index + 1)
+            };
+            CreateSelectMenuOption::new(label, index.to_string())
+        })
+        .collect();
+    CreateActionRow::SelectMenu(
+        CreateSelectMenu::new(
+            format!("{PICK_PREFIX}{token}"),
+            CreateSelectMenuKind::String { options },
+        )
+        .placeholder("Reroll selected")
+        .min_values(1)
+        .max_values(rolls.len() as u8),
+    )
+}
+
+/// The token of a Reroll selected menu's custom ID
+pub fn parse_pick_id(custom_id: &str) -> Option<u64> {
+    custom_id.strip_prefix(PICK_PREFIX)?.parse().ok()
+}
+
+/// `rows` with a Reroll selected menu added when the roll can be partly
+/// rerolled.  `message` is the roll's content, which must end with the
+/// result's text followed by `after`.
+pub async fn add_select_menu(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    expression: &str,
+    results: &[RollResult],
+    message: &str,
+    after: &str,
+    mut rows: Vec<CreateActionRow>,
+) -> Vec<CreateActionRow> {
+    let Some((rolls, kept)) = selectable_roll(expression, results) else {
+        return rows;
+    };
+    let result = results[0].to_string();
+    let Some(before) = message
+        .strip_suffix(after)
+        .and_then(|rest| rest.strip_suffix(result.as_str()))
+    else {
+        return rows;
+    };
+    let Some(store) = ctx
+        .data
+        .read()
+        .await
+        .get::<SelectedRerollContainer>()
+        .cloned()
+    else {
+        return rows;
+    };
+
+    let token = store.store(PendingReroll {
+        user_id: origin.user.id.get(),
+        expression: expression.to_string(),
+        rolls: rolls.clone(),
+        kept,
+        before: before.to_string(),
+        after: after.to_string(),
+        rows: rows.clone(),
+    });
+    rows.push(select_menu(token, &rolls, kept));
+    rows
+}
+
+/// Reroll the dice at `selected` and rebuild the roll's message
+pub fn reroll_message(pending: &PendingReroll, selected: &[usize]) -> Result<String> {
+    let dice = parser::parse_dice_string(&pending.expression)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("Nothing to reroll"))?;
+    let result = roller::reroll_selected(dice, &pending.rolls, selected, &mut rng::get_dice_rng())?;
+    Ok(format!("{}{result}{}", pending.before, pending.after))
+}
+
+/// Handle a Reroll selected pick: edit the roll's message with the picked
+/// dice rolled again.  Refusals are private (and sent as new messages).
+pub async fn handle_select(
+    ctx: &Context,
+    component: &ComponentInteraction,
+) -> Result<CommandResponse> {
+    let Some(token) = parse_pick_id(&component.data.custom_id) else {
+        return Err(anyhow!("Invalid reroll menu"));
+    };
+    let ComponentInteractionDataKind::StringSelect { values } = &component.data.kind else {
+        return Err(anyhow!("Invalid reroll menu"));
+    };
+    let store = ctx
+        .data
+        .read()
+        .await
+        .get::<SelectedRerollContainer>()
+        .cloned();
+    let expired = || {
+        CommandResponse::private(
+            "⌛ This menu has expired. Roll again to reroll selected dice.".to_string(),
+        )
+    };
+    let Some(pending) = store.as_ref().and_then(|store| store.get(token)) else {
+        return Ok(expired());
+    };
+    if pending.user_id != component.user.id.get() {
+        return Ok(CommandResponse::private(
+            "🎲 Only the original roller can reroll these dice. Roll your own with `/roll`!"
+                .to_string(),
+        ));
+    }
+
+    let selected = values
+        .iter()
+        .map(|value| value.parse())
+        .collect::<Result<Vec<usize>, _>>()?;
+    let content = reroll_message(&pending, &selected)?;
+    if content.len() > DISCORD_MESSAGE_LIMIT {
+        return Ok(CommandResponse::private(
+            "❌ The rerolled result is too long to show.".to_string(),
+        ));
+    }
+    // Only one reroll per roll; a second pick of the same menu has expired
+    if store.and_then(|store| store.remove(token)).is_none() {
+        return Ok(expired());
+    }
+    Ok(CommandResponse::public(content).with_components(pending.rows))
+}
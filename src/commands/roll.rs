@@ -3,40 +3,223 @@
 //! Registers the commands, accepts the user's `dice` option string, drives the
 //! dice engine, and formats the result into a Discord message.  Handles the `p`
 //! (private/ephemeral) flag and enforces Discord's 2 000-character message limit.
+//! Server settings from `/setup` (roll channels, default system, private by
+//! default) and `/settings` (output mode, dice limit, disabled systems), `/macro`s, guild aliases from `/macropack`, and `/sheet` stat pools and
+//! trackers are applied before the expression is parsed; `/keyword` rules
+//! mark and tally the result afterwards, `/settings fun` adds streak
+//! titles and `/settings version` the dice engine version.  In a channel with
+//! a running `/session` the dice come from the session's seeded stream, and
+//! with `/settings fair` from the server's provably-fair seed (`/verify`).
+//! The `e` flag or `/settings embed` shows the results as an embed
+//! ([`format_embed`]) instead of text, and a user's first rolls in a server
+//! end with a tip (`commands::tips`).  Results too long for one message are
+//! shortened to fit, or with `/settings threads` posted in full in a thread
+//! under a one-line summary ([`results_thread`]); a `full` roll is sent whole
+//! as the response and follow-up messages instead.
+//! Successful rolls are remembered for `/again` and `/roll last`, and carry a
+//! Reroll button that rolls the same expression again for the original roller,
+//! plus any `/followup` buttons matching the roll's comment and, for a
+//! standard roll, a Reroll selected menu (`commands::reroll_select`).
+//! A `gm` roll posts only a placeholder in the channel and DMs the result to
+//! the members of the server's GM role (`/setup` or `/settings gm-role`).
+//! A roll that fails because of a mistyped alias suggests the close match, or
+//! rolls it with a note when the server turned on `/settings autocorrect`.
+//! While the expression is typed, [`autocomplete`] suggests the user's
+//! macros, the server's aliases and matching built-in game-system aliases.
+//! Sentences with `[[dice]]` inline rolls roll each bracket and show its
+//! value in place ([`split_inline_rolls`], `dice::format_inline_results`);
+//! a secret `gm` roll is refused there ([`inline_refusal`]).
 //!
 //! # Data flow
 //!
 //! ```text
 //! /roll <dice>
 //!   └─ dice::parse_and_roll(input)
-//!         ├─ parser::parse_dice_string  →  Vec<DiceRoll>
+//!         ├─ cache::parse → parser::parse_dice_string  →  Vec<DiceRoll>
 //!         └─ roller::roll_dice (×N)     →  Vec<RollResult>
 //!              └─ format_multiple_results_with_limit  →  String
 //!                   └─ CommandResponse { content, ephemeral }
 //! ```
 
 use crate::DatabaseContainer;
-use crate::dice;
+use crate::commands::{
+    again, clock, followup, keyword, prefs, privacy, reroll_select, session, settings, sheet,
+    stats, streak, table, tally, tips, usage, verify,
+};
+use crate::database::{FairSeed, GuildConfig, GuildSettings, RollOutcome, UserPrefs};
+use crate::dice::parser::{self, AliasCorrection};
+use crate::dice::trackers::{self, TrackerUpdate};
+use crate::dice::{self, DISCORD_MESSAGE_LIMIT, DiceError, aliases};
 use crate::help_text; // Import the shared help text module from src root
-use anyhow::Result;
+use crate::i18n::Language;
+use crate::{locale, outbound, version};
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
 use regex::Regex;
 use serenity::{
-    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
-    builder::{CreateCommand, CreateCommandOption},
+    all::{
+        ButtonStyle, ChannelId, Colour, CommandDataOptionValue, CommandInteraction,
+        CommandOptionType, ComponentInteraction, GuildId, Member, Permissions, RoleId, User,
+        UserId,
+    },
+    builder::{
+        CreateActionRow, CreateAllowedMentions, CreateAttachment, CreateAutocompleteResponse,
+        CreateButton, CreateCommand, CreateCommandOption, CreateEmbed, CreateInteractionResponse,
+        CreateMessage,
+    },
     prelude::Context,
 };
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
 use sysinfo::{Pid, System};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Custom ID prefix of Reroll buttons: `reroll:<user id>:<expression>`
+pub const REROLL_PREFIX: &str = "reroll:";
+
+// Discord's limit on a component's custom ID
+const MAX_CUSTOM_ID_LEN: usize = 100;
+
+// Close aliases suggested for a failed roll
+const MAX_ALIAS_SUGGESTIONS: usize = 3;
+
+// Discord's limits on autocomplete choices: how many, and the length of each
+// choice's name and value
+const MAX_AUTOCOMPLETE_CHOICES: usize = 25;
+const MAX_AUTOCOMPLETE_CHOICE_LEN: usize = 100;
+
+/// Most results shown as one embed (three fields each, within Discord's 25);
+/// larger roll sets are shown as text
+pub const MAX_EMBED_RESULTS: usize = 8;
+
+// Discord's limit on an embed field's value
+const MAX_EMBED_FIELD_LEN: usize = 1024;
+
+/// Embed side bar of a natural 20 or critical success
+pub const CRIT_COLOUR: Colour = Colour::DARK_GREEN;
+
+/// Embed side bar of a natural 1 or botch
+pub const FUMBLE_COLOUR: Colour = Colour::RED;
+
+/// Most GMs a `gm` roll is sent to
+pub const MAX_GM_RECIPIENTS: usize = 10;
+
+// Members listed per request while looking for the GMs, and the most
+// requests made for one roll
+const GM_MEMBER_PAGE_SIZE: u64 = 1000;
+const MAX_GM_MEMBER_PAGES: usize = 10;
+
+/// Most messages a `full` roll is sent in (the response and its follow-ups);
+/// longer output is shortened as usual
+pub const MAX_FULL_MESSAGES: usize = 5;
+
+// Discord's limit on a thread's name
+const MAX_THREAD_NAME_LEN: usize = 100;
+
+/// Dice in one expression (counting each roll set) from which `/roll` is
+/// deferred before rolling
+pub const HEAVY_ROLL_DICE: u64 = 1000;
+
+/// Roll sets from which `/roll` is deferred before rolling
+pub const HEAVY_ROLL_SETS: u64 = 15;
+
+// The dice count of each `NdM` in an expression
+static DICE_COUNT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(\d+)\s*d\s*[\d%{]").expect("Failed to compile DICE_COUNT_REGEX")
+});
+
+// The set count of a roll set: `20 4d6`, after any flags
+static ROLL_SETS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|\s)(\d{1,2})\s+\d*d").expect("Failed to compile ROLL_SETS_REGEX")
+});
+
+/// Most `[[dice]]` inline rolls in one sentence
+pub const MAX_INLINE_ROLLS: usize = 10;
 
 // Custom response type to include privacy information
 #[derive(Debug)]
 pub struct CommandResponse {
     pub content: String,
     pub ephemeral: bool,
+    // Intermediate messages shown after "Rolling…" and before the final content.
+    // `None` sends the content straight away.
+    pub reveal: Option<Vec<String>>,
+    // Buttons attached below the content (e.g. confirmation prompts)
+    pub components: Vec<CreateActionRow>,
+    // Files attached to the message (e.g. `/mystats export` CSVs)
+    pub attachments: Vec<CreateAttachment>,
+    // Embeds shown below the content (rolls in embed mode)
+    pub embeds: Vec<CreateEmbed>,
+    // Updates edited into the response after it is sent, until the sender is
+    // dropped (for slow operations such as `/admin resync-commands`)
+    pub progress: Option<mpsc::Receiver<String>>,
+    // Full results posted in a thread started from the message, when they
+    // don't fit in it (`/settings threads`)
+    pub thread: Option<ThreadedOutput>,
+    // Messages sent after the content, in order, for `full` rolls too long
+    // for one message
+    pub followups: Vec<String>,
+}
+
+/// Results too long for one message, posted in a thread started from the
+/// roll's summary message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadedOutput {
+    /// Thread name, within Discord's 100 characters
+    pub name: String,
+    /// Messages posted in the thread, each within Discord's message limit
+    pub messages: Vec<String>,
 }
 
 impl CommandResponse {
     pub fn new(content: String, ephemeral: bool) -> Self {
-        Self { content, ephemeral }
+        Self {
+            content,
+            ephemeral,
+            reveal: None,
+            components: Vec::new(),
+            attachments: Vec::new(),
+            embeds: Vec::new(),
+            progress: None,
+            thread: None,
+            followups: Vec::new(),
+        }
+    }
+
+    pub fn with_reveal(mut self, frames: Vec<String>) -> Self {
+        self.reveal = Some(frames);
+        self
+    }
+
+    pub fn with_components(mut self, components: Vec<CreateActionRow>) -> Self {
+        self.components = components;
+        self
+    }
+
+    pub fn with_attachments(mut self, attachments: Vec<CreateAttachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+
+    pub fn with_embeds(mut self, embeds: Vec<CreateEmbed>) -> Self {
+        self.embeds = embeds;
+        self
+    }
+
+    pub fn with_progress(mut self, progress: mpsc::Receiver<String>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    pub fn with_thread(mut self, thread: ThreadedOutput) -> Self {
+        self.thread = Some(thread);
+        self
+    }
+
+    pub fn with_followups(mut self, followups: Vec<String>) -> Self {
+        self.followups = followups;
+        self
     }
 
     pub fn public(content: String) -> Self {
@@ -57,7 +240,8 @@ pub fn register() -> CreateCommand {
                 "dice",
                 "Dice expression (e.g., 2d6+3, 4d6 k3, 3d10 t7)",
             )
-            .required(true),
+            .required(true)
+            .set_autocomplete(true),
         )
 }
 
@@ -70,30 +254,66 @@ pub fn register_r_alias() -> CreateCommand {
                 "dice",
                 "Dice expression (e.g., 2d6+3, 4d6 k3, 3d10 t7)",
             )
-            .required(true),
+            .required(true)
+            .set_autocomplete(true),
         )
 }
 
+/// Who is rolling and where: the parts of an interaction a roll uses, so a
+/// roll can come from a slash command or a button
+#[derive(Debug, Clone, Copy)]
+pub struct RollOrigin<'a> {
+    pub user: &'a User,
+    pub member: Option<&'a Member>,
+    pub guild_id: Option<GuildId>,
+    pub channel_id: ChannelId,
+    pub app_permissions: Option<Permissions>,
+}
+
+impl<'a> From<&'a CommandInteraction> for RollOrigin<'a> {
+    fn from(command: &'a CommandInteraction) -> Self {
+        Self {
+            user: &command.user,
+            member: command.member.as_deref(),
+            guild_id: command.guild_id,
+            channel_id: command.channel_id,
+            app_permissions: command.app_permissions,
+        }
+    }
+}
+
+impl<'a> From<&'a ComponentInteraction> for RollOrigin<'a> {
+    fn from(component: &'a ComponentInteraction) -> Self {
+        Self {
+            user: &component.user,
+            member: component.member.as_ref(),
+            guild_id: component.guild_id,
+            channel_id: component.channel_id,
+            app_permissions: component.app_permissions,
+        }
+    }
+}
+
 // Helper function to get the display name (nickname or username)
-fn get_display_name(command: &CommandInteraction) -> String {
+pub(crate) fn get_display_name(origin: &RollOrigin<'_>) -> String {
     // Try to get the nickname from the member info (only available in guilds)
-    if let Some(member) = &command.member
+    if let Some(member) = origin.member
         && let Some(nick) = &member.nick
     {
         return nick.clone();
     }
 
     // Fall back to the user's global display name or username
-    if !command
+    if !origin
         .user
         .global_name
         .as_ref()
         .unwrap_or(&String::new())
         .is_empty()
     {
-        command.user.global_name.as_ref().unwrap().clone()
+        origin.user.global_name.as_ref().unwrap().clone()
     } else {
-        command.user.name.clone()
+        origin.user.name.clone()
     }
 }
 
@@ -116,23 +336,71 @@ fn get_server_and_user_counts(ctx: &Context) -> (usize, usize) {
 }
 
 pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
-    let options = &command.data.options;
+    let dice_expr = dice_option(command);
+    let language = locale::language_for(&command.locale, command.guild_locale.as_deref());
 
-    let dice_expr = options
+    run_expression(ctx, &RollOrigin::from(command), dice_expr, language).await
+}
+
+// The `dice` option of `/roll` and `/r`
+fn dice_option(command: &CommandInteraction) -> &str {
+    command
+        .data
+        .options
         .first()
         .and_then(|opt| match &opt.value {
             CommandDataOptionValue::String(s) => Some(s.as_str()),
             _ => None,
         })
-        .unwrap_or("1d6");
+        .unwrap_or("1d6")
+}
 
+/// Whether a `/roll` or `/r` command should be deferred before it starts
+/// rolling ([`is_heavy_expression`]).  A roll runs on the interaction's own
+/// task, so a slow one would also hold up the usual deferral after
+/// `outbound::DEFER_AFTER` until Discord's 3 seconds had passed.
+pub fn is_heavy_command(command: &CommandInteraction) -> bool {
+    matches!(command.data.name.as_str(), "roll" | "r") && is_heavy_expression(dice_option(command))
+}
+
+/// Whether an expression rolls enough dice to be slow: at least
+/// [`HEAVY_ROLL_DICE`] in all, counting each roll set, or at least
+/// [`HEAVY_ROLL_SETS`] roll sets.  A rough estimate from the text as typed,
+/// before aliases and macros are expanded.
+pub fn is_heavy_expression(expression: &str) -> bool {
+    let sets = ROLL_SETS_REGEX
+        .captures(expression)
+        .and_then(|captures| captures[1].parse::<u64>().ok())
+        .unwrap_or(1);
+    let dice: u64 = DICE_COUNT_REGEX
+        .captures_iter(expression)
+        .filter_map(|captures| captures[1].parse::<u64>().ok())
+        .sum();
+    sets >= HEAVY_ROLL_SETS || sets.saturating_mul(dice) >= HEAVY_ROLL_DICE
+}
+
+/// Answer a `/roll` expression: the `help` topics, `donate`, `bot-info` and
+/// `last` keywords, or a roll.  Shared by `/roll` and `!roll` text commands
+/// (`commands::text_prefix`); `language` is the requester's Discord locale,
+/// for the help text.
+pub async fn run_expression(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    dice_expr: &str,
+    language: Language,
+) -> Result<CommandResponse> {
     // Handle special commands using shared help text
     match dice_expr.trim().to_lowercase().as_str() {
-        "help" => return Ok(CommandResponse::private(help_text::generate_basic_help())),
+        "help" => {
+            return Ok(CommandResponse::private(help_text::generate_basic_help_in(
+                language,
+            )));
+        }
         "help alias" => return Ok(CommandResponse::private(help_text::generate_alias_help())),
         "help system" => return Ok(CommandResponse::private(help_text::generate_system_help())),
         "help a5e" => return Ok(CommandResponse::private(help_text::generate_a5e_help())),
         "help aliens" => return Ok(CommandResponse::private(help_text::generate_aliens_help())),
+        "help privacy" => return Ok(CommandResponse::private(help_text::generate_privacy_help())),
         "donate" => return Ok(CommandResponse::public(generate_donate_text())),
         "bot-info" => {
             let bot_info = generate_bot_info(ctx).await?;
@@ -141,23 +409,286 @@ pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandR
         _ => {} // Continue with normal dice parsing
     }
 
+    // `last` rolls the user's previous roll again, like /again
+    if let Some(delta) = again::strip_last_keyword(dice_expr) {
+        return again::reroll(ctx, origin, delta).await;
+    }
+
+    roll_expression(ctx, origin, dice_expr).await
+}
+
+/// Roll a dice expression for the user and format the response.  Shared by
+/// `/roll`, `/again`, `/macro roll` and the Reroll button.
+pub async fn roll_expression(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    dice_expr: &str,
+) -> Result<CommandResponse> {
+    let typed_expr = dice_expr;
+
+    // Server settings from /setup: where rolls are allowed, what a bare number
+    // rolls as, and whether results are private by default
+    let guild_config = load_guild_config(ctx, origin).await;
+    if let Some(config) = &guild_config
+        && !config.allows_channel(origin.channel_id.get() as i64)
+    {
+        let channels = config
+            .restricted_channels
+            .iter()
+            .map(|id| format!("<#{id}>"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Ok(CommandResponse::private(format!(
+            "❌ Dice rolling is limited to {channels} in this server."
+        )));
+    }
+    let ephemeral_default = guild_config.as_ref().is_some_and(|c| c.ephemeral_default);
+    // The roller's own defaults from /prefs, merged with the server's settings
+    let user_prefs = prefs::load(ctx, origin).await;
+
+    // `I hit [[1d20+5]] for [[2d6]]` rolls each bracket inside the sentence
+    if let Some((pieces, expressions)) = split_inline_rolls(dice_expr) {
+        let mut response =
+            roll_inline(ctx, origin, typed_expr, &pieces, &expressions, &user_prefs).await?;
+        response.ephemeral |= ephemeral_default;
+        prefs::apply_skin(&user_prefs, &mut response);
+        return Ok(response);
+    }
+
+    // Pull out `tally:<name>` and `clock:<name>` flags; the roll's value is added
+    // to that channel tally and its outcome ticks that clock
+    let (tally_name, dice_expr) = tally::extract_tally_flag(dice_expr);
+    let (clock_name, dice_expr) = clock::extract_clock_flag(&dice_expr);
+    let dice_expr = guild_config
+        .as_ref()
+        .and_then(|config| config.default_system.as_deref())
+        .and_then(|system| aliases::expand_default_system(system, &dice_expr))
+        .unwrap_or(dice_expr);
+
     // Get the display name (nickname if available, otherwise username)
-    let display_name = get_display_name(command);
+    let display_name = get_display_name(origin);
 
-    // Parse and roll dice
-    match dice::parse_and_roll(dice_expr) {
-        Ok(results) => {
-            let formatted = dice::format_multiple_results_with_limit(&results);
+    // Personal macros, guild aliases (from macro packs), `@str` stat references,
+    // character sheet pools such as `vtm str + brawl` and sheet trackers expand
+    // before parsing
+    let expanded = match expand_guild_aliases(ctx, origin, &dice_expr).await {
+        Ok(expr) => substitute_stats(ctx, origin, expr).await,
+        Err(e) => Err(e),
+    };
+    let expanded = match expanded {
+        Ok(expr) => expand_stat_pool(ctx, origin, expr).await,
+        Err(e) => Err(e),
+    };
+    let expanded = match expanded {
+        Ok(expr) => apply_trackers(ctx, origin, expr).await,
+        Err(e) => Err(e),
+    };
+    let (dice_expr, tracker_updates) = match expanded {
+        Ok(tracked) => tracked,
+        Err(e) => {
+            let clean_expr = strip_label_and_comment_from_expression(&dice_expr);
+            let mut response = CommandResponse::public(format!(
+                "🎲 **{display_name}** used `{clean_expr}` - ❌ **Error**: {e}"
+            ));
+            response.ephemeral |= ephemeral_default;
+            return Ok(response);
+        }
+    };
+    let dice_expr = dice_expr.as_str();
+
+    // Roll defaults from /settings: disabled systems, the dice limit, restricted
+    // features and outcome bands
+    let guild_settings = load_guild_settings(ctx, origin).await;
+    if let Some(refusal) = guild_settings
+        .as_ref()
+        .and_then(|settings| settings::check_roll(settings, dice_expr))
+    {
+        return Ok(CommandResponse::private(format!("❌ {refusal}")));
+    }
+    if let Some(settings) = guild_settings
+        .as_ref()
+        .filter(|settings| !settings.feature_roles.is_empty())
+        && let Some(denial) = settings::roll_features(dice_expr)
+            .into_iter()
+            .find_map(|feature| settings::check_member_feature(settings, feature, origin.member))
+    {
+        return Ok(CommandResponse::private(format!("❌ {denial}")));
+    }
+    let banded = guild_settings
+        .as_ref()
+        .and_then(|settings| settings::apply_default_bands(settings, dice_expr));
+    let dice_expr = banded.as_deref().unwrap_or(dice_expr);
+
+    // Parse and roll dice, from the channel's seeded stream while a /session
+    // runs, else from the server's fair seed with `/settings fair`
+    let source = RollSource::claim(ctx, origin, guild_settings.as_ref()).await;
+    let (session_roll, fair_roll) = (source.session, &source.fair);
+    let roll_dice =
+        |expression: &str| source.roll(&prefs::with_default_flags(&user_prefs, expression));
+    let started = Instant::now();
+    let mut rolled = roll_dice(dice_expr);
+
+    // A mistyped alias (`4cof`) is rolled as its one close match when the
+    // server turned on autocorrect, as long as its settings allow the fix
+    let corrections = if rolled.is_err() {
+        parser::alias_corrections(dice_expr)
+    } else {
+        Vec::new()
+    };
+    let mut correction = None;
+    if let [fix] = corrections.as_slice()
+        && let Some(settings) = guild_settings.as_ref().filter(|s| s.autocorrect)
+        && settings::check_roll(settings, &fix.expression).is_none()
+        && settings::roll_features(&fix.expression)
+            .into_iter()
+            .all(|feature| {
+                settings::check_member_feature(settings, feature, origin.member).is_none()
+            })
+    {
+        rolled = roll_dice(&fix.expression);
+        correction = Some(fix);
+    }
+    let elapsed = started.elapsed();
+    let dice_expr = correction.map_or(dice_expr, |fix| fix.expression.as_str());
+    let response = match rolled {
+        Ok(mut results) => {
+            // A `gm` roll goes only to the server's GMs, so it needs a GM role
+            let is_gm = results.iter().any(|r| r.gm);
+            let gm_role = guild_config.as_ref().and_then(|config| config.gm_role_id);
+            if is_gm && gm_role.is_none() {
+                return Ok(CommandResponse::private(
+                    "❌ Secret `gm` rolls need a GM role: a server administrator can set one with `/setup` or `/settings gm-role`."
+                        .to_string(),
+                ));
+            }
+            if let Some(settings) = &guild_settings {
+                settings::apply_output(settings, &mut results);
+            }
+            let formatted_results = dice::format_multiple_results_with_limit(&results);
+            // Lines shown after the results; embed mode keeps them in the message
+            let mut extras = String::new();
+
+            if let Some(fix) = correction {
+                extras.push_str(&format!(
+                    "\n*Note: Read `{}` as `{}`*",
+                    fix.typed, fix.alias
+                ));
+            }
+
+            if let Some((_, roll)) = session_roll {
+                extras.push_str(&format!(
+                    "\n🎞️ Session roll #{roll}: `{}`",
+                    strip_label_and_comment_from_expression(dice_expr)
+                ));
+            }
+
+            // A secret `gm` roll leaves nothing the channel can read: no
+            // tally, clock, keyword, streak or stats update
+            if !is_gm && let Some(name) = &tally_name {
+                extras.push_str(&tally::record_roll(ctx, origin, name, &results).await);
+            }
+
+            if !is_gm && let Some(name) = &clock_name {
+                extras.push_str(&clock::record_roll(ctx, origin.channel_id, name, &results).await);
+            }
+
+            // The server's critical and fumble tables under a natural 20 or 1
+            extras.push_str(
+                &table::critical_roll(ctx, guild_settings.as_ref(), dice_expr, &results).await,
+            );
+
+            // Server keyword rules: colour markers and tallies from the comment
+            let (markers, keyword_lines) = if is_gm {
+                (String::new(), String::new())
+            } else {
+                keyword::process_roll(ctx, origin, &results, tally_name.as_deref()).await
+            };
+            extras.push_str(&keyword_lines);
+
+            if !tracker_updates.is_empty() {
+                extras
+                    .push_str(&sheet::record_tracker_updates(ctx, origin, &tracker_updates).await);
+            }
+
+            // Streak titles count this roll on top of the recorded history
+            let titles = if is_gm {
+                String::new()
+            } else {
+                streak::process_roll(ctx, origin, guild_settings.as_ref(), dice_expr, &results)
+                    .await
+            };
+            let recorded = format!("{markers}{formatted_results}{extras}");
+            privacy::record_roll(ctx, origin, dice_expr, &results, &recorded).await;
+            stats::record_roll(ctx, origin, dice_expr, &results).await;
+            extras.push_str(&titles);
+            if guild_settings
+                .as_ref()
+                .is_some_and(|settings| settings.version_footer)
+            {
+                extras.push_str(&version::footer());
+            }
+            if let Some((_, fair)) = fair_roll {
+                extras.push_str(&verify::footer(fair));
+            }
+            extras.push_str(&tips::record_roll(ctx, origin, dice_expr).await);
+            let formatted = format!("{markers}{formatted_results}{extras}");
+            again::remember(ctx, origin, typed_expr).await;
+            usage::record_roll(ctx, dice_expr, elapsed).await;
 
             // Check if any roll was marked as private
             let is_private = results.iter().any(|r| r.private);
+            // "Request" in the roll's language (`fr 2d6`)
+            let request = results
+                .first()
+                .map_or(Language::default(), |r| r.language)
+                .keywords()
+                .request;
 
-            if is_private {
-                // For private rolls, strip comment and label from request display
+            // Embed mode (`e` flag or `/settings embed`) shows the results as an
+            // embed; the message keeps the request and the lines that follow them
+            let embed = (results.iter().any(|r| r.embed)
+                || guild_settings
+                    .as_ref()
+                    .is_some_and(|settings| settings.embed_output))
+            .then(|| format_embed(dice_expr, &results))
+            .flatten();
+            let is_embed = embed.is_some();
+
+            let response = if is_gm && let (Some(guild_id), Some(role)) = (origin.guild_id, gm_role)
+            {
                 let clean_expr = strip_label_and_comment_from_expression(dice_expr);
-                Ok(CommandResponse::private(format!(
-                    "🎲 **Private Roll** `{clean_expr}` {formatted}"
+                let content =
+                    format_gm_roll(&display_name, origin.channel_id, &clean_expr, &formatted);
+                let reached = send_gm_roll(ctx, guild_id, RoleId::new(role as u64), &content).await;
+                Ok(CommandResponse::public(gm_placeholder(
+                    &display_name,
+                    reached,
                 )))
+            } else if let Some(embed) = embed {
+                let clean_expr = strip_label_and_comment_from_expression(dice_expr);
+                let prefix = if is_private {
+                    format!("🎲 **Private Roll** `{clean_expr}` ")
+                } else {
+                    format!("🎲 **{display_name}** {request}: `{clean_expr}` ")
+                };
+                let response =
+                    CommandResponse::new(format!("{prefix}{markers}{extras}"), is_private)
+                        .with_embeds(vec![embed]);
+                Ok(with_suspense(response, &results, &prefix))
+            } else if is_private {
+                // For private rolls, strip comment and label from request display
+                let clean_expr = strip_label_and_comment_from_expression(dice_expr);
+                let prefix = format!("🎲 **Private Roll** `{clean_expr}` ");
+                let full_output = format!(
+                    "{markers}{}{extras}",
+                    dice::format_multiple_results(&results)
+                );
+                if let Some(response) = full_response(&results, &prefix, &full_output, true) {
+                    Ok(response)
+                } else {
+                    let response = CommandResponse::private(format!("{prefix}{formatted}"));
+                    Ok(with_suspense(response, &results, &prefix))
+                }
             } else {
                 // Check if this has multiple results that are semicolon-separated
                 let is_semicolon_separated = results.len() > 1
@@ -166,21 +697,48 @@ pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandR
                         .iter()
                         .all(|r| r.label.as_ref().is_some_and(|l| l.starts_with("Set ")));
 
-                let content = if is_semicolon_separated {
+                let prefix = if is_semicolon_separated {
                     // For semicolon-separated rolls, the formatted string already contains individual requests
-                    format!("🎲 **{display_name}** {formatted}")
+                    format!("🎲 **{display_name}** ")
                 } else if results.len() > 1 {
                     // For roll sets, strip comment and label from request display
                     let clean_expr = strip_label_and_comment_from_expression(dice_expr);
-                    format!("🎲 **{display_name}** Request: `{clean_expr}`\n{formatted}")
+                    format!("🎲 **{display_name}** {request}: `{clean_expr}`\n")
                 } else {
                     // Single result, strip comment and label from request display
                     let clean_expr = strip_label_and_comment_from_expression(dice_expr);
-                    format!("🎲 **{display_name}** Request: `{clean_expr}` {formatted}")
+                    format!("🎲 **{display_name}** {request}: `{clean_expr}` ")
                 };
+                let content = format!("{prefix}{formatted}");
+                // With `/settings threads`, results that don't fit in full go
+                // in a thread under a short summary instead of being cut down
+                let full_output = format!(
+                    "{markers}{}{extras}",
+                    dice::format_multiple_results(&results)
+                );
+                let thread = (prefix.len() + full_output.len() > DISCORD_MESSAGE_LIMIT
+                    && guild_settings
+                        .as_ref()
+                        .is_some_and(|settings| settings.threaded_output)
+                    && origin.guild_id.is_some()
+                    && outbound::ChannelAccess::from_permissions(origin.app_permissions)
+                        .create_threads)
+                    .then(|| {
+                        let clean_expr = strip_label_and_comment_from_expression(dice_expr);
+                        results_thread(&display_name, &clean_expr, &full_output)
+                    });
 
-                // Check if final content exceeds Discord limit
-                if content.len() > 2000 {
+                if let Some(thread) = thread {
+                    let clean_expr = strip_label_and_comment_from_expression(dice_expr);
+                    let summary = format!(
+                        "🎲 **{display_name}** {request}: `{clean_expr}` {}\n🧵 Full results in the thread",
+                        summarize_results(&results)
+                    );
+                    Ok(CommandResponse::public(summary).with_thread(thread))
+                } else if let Some(response) = full_response(&results, &prefix, &full_output, false)
+                {
+                    Ok(response)
+                } else if content.len() > DISCORD_MESSAGE_LIMIT {
                     // Final fallback - just show the simplified result
                     let clean_expr = strip_label_and_comment_from_expression(dice_expr);
                     let simplified_result = if results.len() == 1 {
@@ -195,32 +753,800 @@ pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandR
                     };
 
                     let simplified_content = format!(
-                        "🎲 **{display_name}** Request: `{clean_expr}` {simplified_result}"
+                        "🎲 **{display_name}** {request}: `{clean_expr}` {simplified_result}"
                     );
                     Ok(CommandResponse::public(simplified_content))
                 } else {
-                    Ok(CommandResponse::public(content))
+                    let response = CommandResponse::public(content);
+                    Ok(with_suspense(response, &results, &prefix))
                 }
-            }
+            };
+            // Reroll, then the server's follow-up buttons for rolls like `! Attack`;
+            // a secret roll's placeholder has none
+            let components = if is_gm {
+                Vec::new()
+            } else {
+                reroll_button(origin.user.id, typed_expr)
+                    .into_iter()
+                    .chain(followup::buttons_for_roll(ctx, origin, &results).await)
+                    .collect()
+            };
+            // A Reroll selected menu for rolls shown as text; seeded session
+            // and fair rolls keep their dice
+            let components = match &response {
+                Ok(response)
+                    if !is_gm && !is_embed && session_roll.is_none() && fair_roll.is_none() =>
+                {
+                    reroll_select::add_select_menu(
+                        ctx,
+                        origin,
+                        dice_expr,
+                        &results,
+                        &response.content,
+                        &extras,
+                        components,
+                    )
+                    .await
+                }
+                _ => components,
+            };
+            response.map(|response| response.with_components(components))
         }
+        // For input length errors, don't show the request to avoid spam
+        Err(e @ DiceError::InputTooLong { .. }) => Ok(CommandResponse::public(format!(
+            "🎲 **{display_name}** ❌ **Error**: {e}"
+        ))),
         Err(e) => {
-            // Check if this is an input length error to avoid spamming Discord
-            let error_message = e.to_string();
-            if error_message.contains("Input too long") {
-                // For input length errors, don't show the request to avoid spam
-                let content = format!("🎲 **{display_name}** ❌ **Error**: {e}");
-                Ok(CommandResponse::public(content))
-            } else {
-                // For other errors, show the cleaned request as before
-                let clean_expr = strip_label_and_comment_from_expression(dice_expr);
-                let content =
-                    format!("🎲 **{display_name}** used `{clean_expr}` - ❌ **Error**: {e}");
-                Ok(CommandResponse::public(content))
+            // For other errors, show the cleaned request as before
+            let clean_expr = strip_label_and_comment_from_expression(dice_expr);
+            let content = format!(
+                "🎲 **{display_name}** used `{clean_expr}` - ❌ **Error**: {e}{}",
+                format_alias_suggestions(&corrections)
+            );
+            Ok(CommandResponse::public(content))
+        }
+    };
+
+    response.map(|mut response| {
+        response.ephemeral |= ephemeral_default;
+        prefs::apply_skin(&user_prefs, &mut response);
+        response
+    })
+}
+
+// Where a roll's dice come from: the channel's seeded stream while a
+// /session runs, else the server's fair seed with `/settings fair`, else the
+// normal entropy-seeded RNG.  Each roll claims its own, so every `/session`
+// and fair roll number can be replayed on its own.
+struct RollSource {
+    // Session seed and the roll's number in the session
+    session: Option<([u8; 32], u64)>,
+    // Fair seed bytes and the server's fair roll count
+    fair: Option<(Vec<u8>, FairSeed)>,
+}
+
+impl RollSource {
+    async fn claim(
+        ctx: &Context,
+        origin: &RollOrigin<'_>,
+        guild_settings: Option<&GuildSettings>,
+    ) -> Self {
+        let session = session::claim_roll(ctx, origin.channel_id).await;
+        let fair = match (session, origin.guild_id) {
+            (None, Some(guild_id)) if guild_settings.is_some_and(|s| s.fair_rolls) => {
+                verify::claim_roll(ctx, guild_id).await
             }
+            _ => None,
+        }
+        .and_then(|fair| Some((hex::decode(&fair.seed).ok()?, fair)));
+        Self { session, fair }
+    }
+
+    fn roll(&self, expression: &str) -> dice::Result<Vec<dice::RollResult>> {
+        match (self.session, &self.fair) {
+            (Some((seed, roll)), _) => {
+                dice::parse_and_roll_with_rng(expression, &mut dice::rng::session_rng(&seed, roll))
+            }
+            (None, Some((seed, fair))) => dice::parse_and_roll_with_rng(
+                expression,
+                &mut dice::rng::fair_rng(seed, fair.rolls as u64),
+            ),
+            (None, None) => dice::parse_and_roll(expression),
         }
     }
 }
 
+/// Split a sentence with `[[dice]]` inline rolls into the text around them
+/// and their expressions: `I hit [[1d20+5]]!` is `["I hit ", "!"]` and
+/// `["1d20+5"]`.  `None` when there are no inline rolls.
+pub fn split_inline_rolls(expr: &str) -> Option<(Vec<String>, Vec<String>)> {
+    let mut pieces = Vec::new();
+    let mut expressions = Vec::new();
+    let mut last = 0;
+    for captures in INLINE_ROLL_REGEX.captures_iter(expr) {
+        let (Some(whole), Some(dice)) = (captures.get(0), captures.get(1)) else {
+            continue;
+        };
+        pieces.push(expr[last..whole.start()].to_string());
+        expressions.push(dice.as_str().trim().to_string());
+        last = whole.end();
+    }
+    if expressions.is_empty() {
+        return None;
+    }
+    pieces.push(expr[last..].to_string());
+    Some((pieces, expressions))
+}
+
+// Roll every inline expression like a `/roll` of its own (server aliases,
+// macros, `@stat`s, the server's roll settings, a running `/session` and
+// `/settings fair` apply) and put the values into the sentence
+async fn roll_inline(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    typed_expr: &str,
+    pieces: &[String],
+    expressions: &[String],
+    user_prefs: &UserPrefs,
+) -> Result<CommandResponse> {
+    let display_name = get_display_name(origin);
+    if expressions.len() > MAX_INLINE_ROLLS {
+        return Ok(CommandResponse::private(format!(
+            "❌ A sentence can have at most {MAX_INLINE_ROLLS} inline rolls."
+        )));
+    }
+
+    let guild_settings = load_guild_settings(ctx, origin).await;
+    let mut rolls = Vec::new();
+    // Session roll lines and fair roll footers shown after the sentence's dice
+    let mut extras = String::new();
+    for expression in expressions {
+        let rolled = match expand_guild_aliases(ctx, origin, expression).await {
+            Ok(expanded) => substitute_stats(ctx, origin, expanded).await,
+            Err(e) => Err(e),
+        }
+        .and_then(|expanded| {
+            let refusal = guild_settings.as_ref().and_then(|settings| {
+                settings::check_roll(settings, &expanded).or_else(|| {
+                    settings::roll_features(&expanded)
+                        .into_iter()
+                        .find_map(|feature| {
+                            settings::check_member_feature(settings, feature, origin.member)
+                        })
+                })
+            });
+            match refusal {
+                Some(refusal) => Err(anyhow!(refusal)),
+                None => Ok(expanded),
+            }
+        });
+        // Each bracket takes its own session (or fair) roll, like a roll of its own
+        let rolled = match rolled {
+            Ok(expanded) => {
+                let source = RollSource::claim(ctx, origin, guild_settings.as_ref()).await;
+                if let Some((_, roll)) = source.session {
+                    extras.push_str(&format!(
+                        "\n🎞️ Session roll #{roll}: `{}`",
+                        strip_label_and_comment_from_expression(&expanded)
+                    ));
+                }
+                if let Some((_, fair)) = &source.fair {
+                    extras.push_str(&verify::footer(fair));
+                }
+                source
+                    .roll(&prefs::with_default_flags(user_prefs, &expanded))
+                    .map_err(anyhow::Error::from)
+            }
+            Err(e) => Err(e),
+        };
+
+        match rolled {
+            Ok(mut results) => {
+                if let Some(refusal) = inline_refusal(&results) {
+                    return Ok(refusal);
+                }
+                if let Some(settings) = &guild_settings {
+                    settings::apply_output(settings, &mut results);
+                }
+                rolls.push(dice::InlineRoll {
+                    expression: expression.clone(),
+                    results,
+                });
+            }
+            Err(e) => {
+                return Ok(CommandResponse::public(format!(
+                    "🎲 **{display_name}** used `[[{expression}]]` - ❌ **Error**: {e}"
+                )));
+            }
+        }
+    }
+
+    let results: Vec<dice::RollResult> = rolls
+        .iter()
+        .flat_map(|roll| roll.results.iter().cloned())
+        .collect();
+    let is_private = results.iter().any(|r| r.private);
+    let prefix = if is_private {
+        "🎲 **Private Roll** ".to_string()
+    } else {
+        format!("🎲 **{display_name}**: ")
+    };
+
+    // Without room for the dice, only the sentence is shown
+    let mut content = format!(
+        "{prefix}{}{extras}",
+        dice::format_inline_results(pieces, &rolls)
+    );
+    if content.len() > DISCORD_MESSAGE_LIMIT {
+        content = format!(
+            "{prefix}{}{extras}",
+            dice::format_inline_sentence(pieces, &rolls)
+        );
+    }
+    if content.len() > DISCORD_MESSAGE_LIMIT {
+        return Ok(CommandResponse::private(
+            "❌ The sentence is too long to show with its rolls.".to_string(),
+        ));
+    }
+
+    privacy::record_roll(ctx, origin, typed_expr, &results, &content).await;
+    again::remember(ctx, origin, typed_expr).await;
+
+    let components = reroll_button(origin.user.id, typed_expr)
+        .into_iter()
+        .collect();
+    Ok(CommandResponse::new(content, is_private).with_components(components))
+}
+
+/// The private reply refusing a `[[dice]]` inline roll that can't be shown
+/// in the sentence: a secret `gm` roll goes only to the GMs, never into the
+/// channel.  `None` when the roll can be shown.
+pub fn inline_refusal(results: &[dice::RollResult]) -> Option<CommandResponse> {
+    results.iter().any(|r| r.gm).then(|| {
+        CommandResponse::private(
+            "❌ Secret `gm` rolls can't go inside a sentence: roll them on their own with `/roll gm`."
+                .to_string(),
+        )
+    })
+}
+
+// "Did you mean `4cod`?" for a failed roll's close aliases, or nothing
+fn format_alias_suggestions(corrections: &[AliasCorrection]) -> String {
+    let aliases: Vec<String> = corrections
+        .iter()
+        .take(MAX_ALIAS_SUGGESTIONS)
+        .map(|fix| format!("`{}`", fix.alias))
+        .collect();
+    match aliases.split_last() {
+        None => String::new(),
+        Some((last, [])) => format!("\n💡 Did you mean {last}?"),
+        Some((last, rest)) => format!("\n💡 Did you mean {} or {last}?", rest.join(", ")),
+    }
+}
+
+/// Autocomplete choices for the `dice` option as `typed` so far, as
+/// (shown name, value) pairs: the user's `/macro`s, then the server's
+/// aliases, then built-in game-system aliases whose name starts with it,
+/// ignoring case.  Names and values too long for Discord are left out.
+pub fn roll_suggestions(
+    typed: &str,
+    user_macros: &BTreeMap<String, String>,
+    guild_aliases: &HashMap<String, String>,
+) -> Vec<(String, String)> {
+    let prefix = typed.trim().to_lowercase();
+    let mut sorted_aliases: Vec<_> = guild_aliases.iter().collect();
+    sorted_aliases.sort();
+
+    let saved = user_macros
+        .iter()
+        .map(|entry| (entry, "macro"))
+        .chain(sorted_aliases.into_iter().map(|entry| (entry, "alias")))
+        .filter(|((name, _), _)| name.to_lowercase().starts_with(&prefix))
+        .map(|((name, expression), kind)| {
+            (format!("{name} → {expression} ({kind})"), name.clone())
+        });
+    let built_in = aliases::alias_examples_with_prefix(&prefix)
+        .iter()
+        .map(|(example, description)| (format!("{example} — {description}"), example.to_string()));
+
+    let mut suggestions: Vec<(String, String)> = Vec::new();
+    for (name, value) in saved.chain(built_in) {
+        if value.chars().count() > MAX_AUTOCOMPLETE_CHOICE_LEN
+            || suggestions.iter().any(|(_, taken)| *taken == value)
+        {
+            continue;
+        }
+        let name = if name.chars().count() > MAX_AUTOCOMPLETE_CHOICE_LEN {
+            let cut: String = name.chars().take(MAX_AUTOCOMPLETE_CHOICE_LEN - 1).collect();
+            format!("{cut}…")
+        } else {
+            name
+        };
+        suggestions.push((name, value));
+        if suggestions.len() == MAX_AUTOCOMPLETE_CHOICES {
+            break;
+        }
+    }
+    suggestions
+}
+
+/// Answer an autocomplete request for `/roll` or `/r` with
+/// [`roll_suggestions`] for what has been typed.  Macros and aliases that
+/// can't be loaded are left out.
+pub async fn autocomplete(ctx: &Context, command: &CommandInteraction) -> Result<()> {
+    let typed = command
+        .data
+        .autocomplete()
+        .map(|option| option.value)
+        .unwrap_or_default();
+
+    let db = ctx.data.read().await.get::<DatabaseContainer>().cloned();
+    let (user_macros, guild_aliases) = match db {
+        Some(db) => {
+            let user_macros = db
+                .get_user_macros(command.user.id.get() as i64)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("Failed to load macros for {}: {}", command.user.id, e);
+                    Default::default()
+                });
+            let guild_aliases = match command.guild_id {
+                Some(guild_id) => db
+                    .get_guild_aliases(guild_id.get() as i64)
+                    .await
+                    .unwrap_or_else(|e| {
+                        warn!("Failed to load aliases for guild {}: {}", guild_id, e);
+                        Default::default()
+                    }),
+                None => Default::default(),
+            };
+            (user_macros, guild_aliases)
+        }
+        None => Default::default(),
+    };
+
+    let response = roll_suggestions(typed, &user_macros, &guild_aliases)
+        .into_iter()
+        .fold(
+            CreateAutocompleteResponse::new(),
+            |response, (name, value)| response.add_string_choice(name, value),
+        );
+    command
+        .create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+        .await?;
+    Ok(())
+}
+
+/// The DM a GM gets for a `gm` roll: who rolled, where and the full result,
+/// shortened to fit a message
+pub fn format_gm_roll(
+    display_name: &str,
+    channel_id: ChannelId,
+    expression: &str,
+    formatted: &str,
+) -> String {
+    let content = format!(
+        "🤫 **{display_name}** rolled secretly in <#{channel_id}>: `{expression}` {formatted}"
+    );
+    if content.chars().count() <= DISCORD_MESSAGE_LIMIT {
+        return content;
+    }
+    let shortened: String = content.chars().take(DISCORD_MESSAGE_LIMIT - 1).collect();
+    format!("{shortened}…")
+}
+
+/// What the channel sees of a `gm` roll, given how many GMs were sent it
+pub fn gm_placeholder(display_name: &str, reached: usize) -> String {
+    if reached == 0 {
+        format!(
+            "🎲 **{display_name}** rolled secretly for the GM, but no GM could be reached by DM."
+        )
+    } else {
+        format!("🎲 **{display_name}** rolled secretly for the GM 🤫")
+    }
+}
+
+/// DM a `gm` roll to the members with the GM role (at most
+/// [`MAX_GM_RECIPIENTS`]), returning how many were sent it.  The bot keeps no
+/// member cache, so the members are listed page by page; that needs the
+/// Server Members intent turned on for the application.
+async fn send_gm_roll(ctx: &Context, guild_id: GuildId, role: RoleId, content: &str) -> usize {
+    let mut gms: Vec<UserId> = Vec::new();
+    let mut after = None;
+    for _ in 0..MAX_GM_MEMBER_PAGES {
+        let members = match guild_id
+            .members(&ctx.http, Some(GM_MEMBER_PAGE_SIZE), after)
+            .await
+        {
+            Ok(members) => members,
+            Err(e) => {
+                warn!(
+                    "Failed to list members of guild {} for a GM roll: {}",
+                    guild_id, e
+                );
+                break;
+            }
+        };
+        after = members.last().map(|member| member.user.id);
+        gms.extend(
+            members
+                .iter()
+                .filter(|member| !member.user.bot && member.roles.contains(&role))
+                .map(|member| member.user.id),
+        );
+        if members.len() < GM_MEMBER_PAGE_SIZE as usize || gms.len() >= MAX_GM_RECIPIENTS {
+            break;
+        }
+    }
+    gms.truncate(MAX_GM_RECIPIENTS);
+
+    let message = CreateMessage::new()
+        .content(content)
+        .allowed_mentions(CreateAllowedMentions::new());
+    let mut reached = 0;
+    for gm in gms {
+        match outbound::send_with_retry("GM roll DM", || {
+            gm.direct_message(&ctx.http, message.clone())
+        })
+        .await
+        {
+            Ok(_) => reached += 1,
+            Err(e) => warn!("Failed to DM GM roll to {}: {}", gm, e),
+        }
+    }
+    reached
+}
+
+/// A Reroll button that rolls `expression` again for the user, or `None` if
+/// the expression is too long to fit in the button's custom ID
+pub fn reroll_button(user_id: UserId, expression: &str) -> Option<CreateActionRow> {
+    let custom_id = format!("{REROLL_PREFIX}{user_id}:{expression}");
+    if custom_id.len() > MAX_CUSTOM_ID_LEN {
+        return None;
+    }
+    Some(CreateActionRow::Buttons(vec![
+        CreateButton::new(custom_id)
+            .label("Reroll")
+            .emoji('🎲')
+            .style(ButtonStyle::Secondary),
+    ]))
+}
+
+/// The roller and expression of a Reroll button's custom ID
+pub fn parse_reroll_id(custom_id: &str) -> Option<(u64, &str)> {
+    let (user_id, expression) = custom_id.strip_prefix(REROLL_PREFIX)?.split_once(':')?;
+    Some((user_id.parse().ok()?, expression))
+}
+
+/// Handle a Reroll button click: roll the same expression again as a new
+/// message.  Only the original roller may use the button.
+pub async fn handle_reroll(
+    ctx: &Context,
+    component: &ComponentInteraction,
+) -> Result<CommandResponse> {
+    let Some((user_id, expression)) = parse_reroll_id(&component.data.custom_id) else {
+        return Err(anyhow!("Invalid reroll button"));
+    };
+    if user_id != component.user.id.get() {
+        return Ok(CommandResponse::private(
+            "🎲 Only the original roller can use this button. Roll your own with `/roll`!"
+                .to_string(),
+        ));
+    }
+    roll_expression(ctx, &RollOrigin::from(component), expression).await
+}
+
+async fn load_guild_config(ctx: &Context, origin: &RollOrigin<'_>) -> Option<GuildConfig> {
+    let guild_id = origin.guild_id?;
+    let db = ctx.data.read().await.get::<DatabaseContainer>().cloned()?;
+    match db.get_guild_config(guild_id.get() as i64).await {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("Failed to load config for guild {}: {}", guild_id, e);
+            None
+        }
+    }
+}
+
+async fn load_guild_settings(ctx: &Context, origin: &RollOrigin<'_>) -> Option<GuildSettings> {
+    let guild_id = origin.guild_id?;
+    let db = ctx.data.read().await.get::<DatabaseContainer>().cloned()?;
+    match db.get_guild_settings(guild_id.get() as i64).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!("Failed to load settings for guild {}: {}", guild_id, e);
+            None
+        }
+    }
+}
+
+/// Expand the user's `/macro`s and the guild's aliases in an expression, with
+/// macros taking precedence.  Only alias errors (such as a loop) are returned;
+/// if either can't be loaded the roll goes ahead without it.
+pub async fn expand_guild_aliases(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    expr: &str,
+) -> Result<String> {
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return Ok(expr.to_string());
+    };
+
+    let user_macros = db
+        .get_user_macros(origin.user.id.get() as i64)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("Failed to load macros for {}: {}", origin.user.id, e);
+            Default::default()
+        });
+    let guild_aliases = match origin.guild_id {
+        Some(guild_id) => db
+            .get_guild_aliases(guild_id.get() as i64)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to load aliases for guild {}: {}", guild_id, e);
+                Default::default()
+            }),
+        None => Default::default(),
+    };
+
+    if user_macros.is_empty() && guild_aliases.is_empty() {
+        return Ok(expr.to_string());
+    }
+    Ok(aliases::expand_user_aliases(expr, |name| {
+        user_macros
+            .get(name)
+            .or_else(|| guild_aliases.get(name))
+            .cloned()
+    })?)
+}
+
+// The user's /sheet (and /char) stats in this server, or `None` without a database
+async fn load_sheet(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+) -> Result<Option<BTreeMap<String, i64>>> {
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return Ok(None);
+    };
+
+    let guild_id = origin.guild_id.map_or(0, |id| id.get() as i64);
+    let stats = db
+        .get_character_stats(guild_id, origin.user.id.get() as i64)
+        .await
+        .map_err(|e| {
+            warn!("Failed to load sheet for {}: {}", origin.user.id, e);
+            anyhow!("Couldn't load your character sheet, please try again")
+        })?;
+    Ok(Some(stats))
+}
+
+// Replace `@str` references with the user's stats, e.g. `1d20 + @str`
+async fn substitute_stats(ctx: &Context, origin: &RollOrigin<'_>, expr: String) -> Result<String> {
+    if !parser::has_variables(&expr) {
+        return Ok(expr);
+    }
+    let stats = load_sheet(ctx, origin).await?.unwrap_or_default();
+
+    Ok(parser::substitute_variables(&expr, |name| {
+        stats.get(name).copied()
+    })?)
+}
+
+// Fill a pool roll like `vtm str + brawl` from the user's /sheet stats
+async fn expand_stat_pool(ctx: &Context, origin: &RollOrigin<'_>, expr: String) -> Result<String> {
+    if !aliases::is_stat_pool(&expr) {
+        return Ok(expr);
+    }
+    let Some(stats) = load_sheet(ctx, origin).await? else {
+        return Ok(expr);
+    };
+
+    Ok(aliases::expand_stat_pool(&expr, |name| stats.get(name).copied()).unwrap_or(Ok(expr))?)
+}
+
+// Fill hunger / stress / momentum from the user's /sheet into system rolls,
+// returning the tracker changes to save once the roll succeeds
+async fn apply_trackers(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    expr: String,
+) -> Result<(String, Vec<TrackerUpdate>)> {
+    if !trackers::uses_trackers(&expr) {
+        return Ok((expr, Vec::new()));
+    }
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return Ok((expr, Vec::new()));
+    };
+
+    let guild_id = origin.guild_id.map_or(0, |id| id.get() as i64);
+    let stats = db
+        .get_character_stats(guild_id, origin.user.id.get() as i64)
+        .await
+        .map_err(|e| {
+            warn!("Failed to load sheet for {}: {}", origin.user.id, e);
+            anyhow!("Couldn't load your character sheet, please try again")
+        })?;
+
+    match trackers::apply_trackers(&expr, |name| stats.get(name).copied()) {
+        Some(tracked) => {
+            let tracked = tracked?;
+            Ok((tracked.expression, tracked.updates))
+        }
+        None => Ok((expr, Vec::new())),
+    }
+}
+
+/// The thread for results too long for one message: named after the roller
+/// and expression, with the full output split between lines
+/// (`dice::split_results_output`) into messages that each fit
+pub fn results_thread(display_name: &str, expression: &str, output: &str) -> ThreadedOutput {
+    let name: String = format!("{display_name}: {expression}")
+        .chars()
+        .take(MAX_THREAD_NAME_LEN)
+        .collect();
+    ThreadedOutput {
+        name,
+        messages: dice::split_results_output(output.trim_start(), DISCORD_MESSAGE_LIMIT),
+    }
+}
+
+/// A `full` roll too long for one message, sent whole instead of shortened:
+/// the output split between lines (`dice::split_results_output`), the first
+/// part as the response and the rest as follow-ups.  `None` when it isn't a
+/// `full` roll, fits in one message, or needs more than [`MAX_FULL_MESSAGES`].
+pub fn full_response(
+    results: &[dice::RollResult],
+    prefix: &str,
+    output: &str,
+    ephemeral: bool,
+) -> Option<CommandResponse> {
+    if !results.iter().any(|r| r.full) || prefix.len() + output.len() <= DISCORD_MESSAGE_LIMIT {
+        return None;
+    }
+    let mut messages =
+        dice::split_results_output(&format!("{prefix}{output}"), DISCORD_MESSAGE_LIMIT);
+    if messages.len() > MAX_FULL_MESSAGES {
+        return None;
+    }
+    let first = messages.remove(0);
+    Some(CommandResponse::new(first, ephemeral).with_followups(messages))
+}
+
+/// The in-channel line for results posted in a thread: a single result's
+/// total, or how many results there are
+fn summarize_results(results: &[dice::RollResult]) -> String {
+    if let [result] = results {
+        let mut simplified = result.create_simplified();
+        simplified.simple = true;
+        simplified.to_string()
+    } else {
+        format!("**{}** results", results.len())
+    }
+}
+
+// Attach a suspense reveal to a roll response. `drama` rolls reveal their dice
+// one group at a time; SUSPENSE_MODE=true adds a plain "Rolling…" pause to every roll.
+fn with_suspense(
+    response: CommandResponse,
+    results: &[dice::RollResult],
+    prefix: &str,
+) -> CommandResponse {
+    if results.iter().any(|r| r.drama) {
+        let frames = dice::format_reveal_frames(results)
+            .into_iter()
+            .map(|frame| format!("{prefix}{frame}"))
+            .filter(|frame| frame.len() <= DISCORD_MESSAGE_LIMIT)
+            .collect();
+        response.with_reveal(frames)
+    } else if std::env::var("SUSPENSE_MODE")
+        .map(|s| s.to_lowercase() == "true")
+        .unwrap_or(false)
+    {
+        response.with_reveal(Vec::new())
+    } else {
+        response
+    }
+}
+
+/// The embed-mode rendering of a roll, parallel to
+/// `dice::format_multiple_results_with_limit`: fields for each result's dice,
+/// total and notes, with a side bar coloured by [`embed_colour`].  `None`
+/// when the results don't fit one embed, so the roll is shown as text.
+pub fn format_embed(expression: &str, results: &[dice::RollResult]) -> Option<CreateEmbed> {
+    if results.len() > MAX_EMBED_RESULTS {
+        return None;
+    }
+
+    let mut embed = CreateEmbed::new();
+    let mut fields = 0;
+    for (index, result) in results.iter().enumerate() {
+        let keywords = result.language.keywords();
+        // Roll sets and `;`-separated rolls name each result's fields
+        let heading = match (&result.label, &result.original_expression) {
+            (Some(label), _) => format!("{label} · "),
+            (None, Some(expression)) if results.len() > 1 => format!("{expression} · "),
+            (None, _) if results.len() > 1 => format!("#{} · ", index + 1),
+            _ => String::new(),
+        };
+
+        let dice = result.dice_breakdown();
+        if !result.simple && !dice.is_empty() {
+            embed = embed.field(
+                format!("{heading}{}", keywords.roll),
+                embed_field_value(&dice),
+                true,
+            );
+            fields += 1;
+        }
+        if !result.no_results {
+            embed = embed.field(
+                format!("{heading}{}", keywords.total),
+                embed_field_value(&result.result_value()),
+                true,
+            );
+            fields += 1;
+        }
+
+        let mut notes = Vec::new();
+        if !result.suppress_comment
+            && let Some(comment) = result.comment.as_deref().filter(|c| !c.is_empty())
+        {
+            notes.push(format!("{}: `{comment}`", keywords.reason));
+        }
+        notes.extend(result.notes.iter().map(|note| format!("*{note}*")));
+        if !notes.is_empty() {
+            embed = embed.field(
+                format!("{heading}Notes"),
+                embed_field_value(&notes.join("\n")),
+                false,
+            );
+            fields += 1;
+        }
+    }
+    if fields == 0 {
+        return None;
+    }
+
+    Some(match embed_colour(expression, results) {
+        Some(colour) => embed.colour(colour),
+        None => embed,
+    })
+}
+
+/// Side bar of a roll embed: green for a critical (natural 20, critical
+/// success), red for a fumble (natural 1, botches without successes)
+pub fn embed_colour(expression: &str, results: &[dice::RollResult]) -> Option<Colour> {
+    match streak::roll_outcome(expression, results) {
+        Some(RollOutcome::NaturalTwenty) => return Some(CRIT_COLOUR),
+        Some(RollOutcome::NaturalOne) => return Some(FUMBLE_COLOUR),
+        _ => {}
+    }
+    if results
+        .iter()
+        .any(|r| r.fitd_outcome.as_deref() == Some("CRITICAL SUCCESS"))
+    {
+        Some(CRIT_COLOUR)
+    } else if results.iter().any(|r| {
+        r.botches.is_some_and(|botches| botches > 0)
+            && r.successes.is_some_and(|successes| successes <= 0)
+    }) {
+        Some(FUMBLE_COLOUR)
+    } else {
+        None
+    }
+}
+
+// An embed field's value, cut to Discord's limit
+fn embed_field_value(value: &str) -> String {
+    if value.chars().count() <= MAX_EMBED_FIELD_LEN {
+        value.to_string()
+    } else {
+        let cut: String = value.chars().take(MAX_EMBED_FIELD_LEN - 1).collect();
+        format!("{cut}…")
+    }
+}
+
 fn generate_donate_text() -> String {
     "Care to support the bot? You can donate via Patreon https://www.patreon.com/dicemaiden \n Another option is join the Dice Maiden Discord server and subscribe! https://discord.gg/4T3R5Cb".to_string()
 }
@@ -406,16 +1732,22 @@ async fn get_database_stats(ctx: &Context) -> String {
 /// - "(roll to hit) 1d20+2" -> "1d20+2"
 /// - "2d6 ! fire damage" -> "2d6"
 /// - "(attack) 1d20+5 ! with sword" -> "1d20+5"
-fn strip_label_and_comment_from_expression(expr: &str) -> String {
-    let mut cleaned = expr.to_string();
-
+pub(crate) fn strip_label_and_comment_from_expression(expr: &str) -> String {
     // Remove labels in parentheses at the beginning: (label) dice_expression
-    let label_regex = Regex::new(r"^\s*\([^)]*\)\s*").unwrap();
-    cleaned = label_regex.replace(&cleaned, "").to_string();
+    let cleaned = LABEL_REGEX.replace(expr, "");
 
     // Remove comments (everything after ! including the !)
-    let comment_regex = Regex::new(r"\s*!\s*.*$").unwrap();
-    cleaned = comment_regex.replace(&cleaned, "").to_string();
+    let cleaned = COMMENT_REGEX.replace(&cleaned, "");
 
     cleaned.trim().to_string()
 }
+
+// `[[dice]]` inside a sentence
+static INLINE_ROLL_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[\[([^\[\]]*)\]\]").expect("Failed to compile INLINE_ROLL_REGEX"));
+
+static LABEL_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*\([^)]*\)\s*").expect("Failed to compile LABEL_REGEX"));
+
+static COMMENT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\s*!\s*.*$").expect("Failed to compile COMMENT_REGEX"));
@@ -0,0 +1,267 @@
+//! `/session` slash-command handler for reproducible game sessions.
+//!
+//! | Subcommand | Effect                                                       |
+//! |------------|--------------------------------------------------------------|
+//! | `start`    | Start a session in this channel with a fresh random seed     |
+//! | `status`   | Show whether a session is running and how many rolls it has  |
+//! | `end`      | End the session and reveal its seed                          |
+//! | `replay`   | Re-roll a numbered session roll from the revealed seed       |
+//!
+//! While a session runs, every roll in the channel takes its dice from
+//! `rng::session_rng(seed, n)`, where `seed` is 32 secret bytes and `n` is
+//! the roll's number in the session (shown under the result).  Each roll has its own stream, so once the seed
+//! is revealed any roll can be checked on its own with `/session replay`.
+//! The seed stays hidden until the session ends, otherwise rolls could be
+//! predicted.  Rolls outside sessions use the normal entropy-seeded RNG.
+//!
+//! Starting and ending sessions in a server needs the **Administrator**
+//! permission or the GM role chosen in `/setup`.
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::commands::config::is_administrator;
+use crate::database::GameSession;
+use crate::dice::{self, RollResult, rng};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{ChannelId, CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use tracing::{info, warn};
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("session")
+        .description("Run a game session with reproducible rolls in this channel")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "start",
+            "Start a session with a fresh seed (admins or GM role)",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "status",
+            "Show the current session",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "end",
+            "End the session and reveal its seed (admins or GM role)",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "replay",
+                "Re-roll a session roll from its seed",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "seed",
+                    "Seed shown when the session ended",
+                )
+                .required(true)
+                .max_length(64),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::Integer, "roll", "Session roll number")
+                    .required(true)
+                    .min_int_value(1),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "dice",
+                    "Expression shown with the roll",
+                )
+                .required(true),
+            ),
+        )
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing session subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid session subcommand"));
+    };
+
+    if subcommand.name == "replay" {
+        let option_str = |name: &str| {
+            options
+                .iter()
+                .find(|opt| opt.name == name)
+                .and_then(|opt| opt.value.as_str())
+        };
+        let (Some(seed), Some(expr), Some(roll)) = (
+            option_str("seed"),
+            option_str("dice"),
+            options
+                .iter()
+                .find(|opt| opt.name == "roll")
+                .and_then(|opt| opt.value.as_i64()),
+        ) else {
+            return Err(anyhow!("Missing replay options"));
+        };
+
+        let content = match parse_seed(seed).and_then(|seed| replay(&seed, roll, expr)) {
+            Ok(results) => format!(
+                "🎞️ Replay of session roll #{roll} `{expr}`: {}",
+                dice::format_multiple_results_with_limit(&results)
+            ),
+            Err(e) => format!("❌ {e}"),
+        };
+        return Ok(CommandResponse::private(content));
+    }
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let channel_id = command.channel_id.get() as i64;
+
+    match subcommand.name.as_str() {
+        "status" => Ok(CommandResponse::private(
+            match db.get_session(channel_id).await? {
+                Some(session) => format!(
+                    "🎞️ A session started by <@{}> is running here ({} rolls so far). \
+                     The seed is revealed when it ends.",
+                    session.started_by, session.rolls
+                ),
+                None => {
+                    "🎞️ No session is running in this channel. Start one with `/session start`."
+                        .to_string()
+                }
+            },
+        )),
+        "start" | "end" if !is_game_master(ctx, command).await => Ok(CommandResponse::private(
+            "❌ You need the 'Administrator' permission or the server's GM role to do this."
+                .to_string(),
+        )),
+        "start" => {
+            let guild_id = command.guild_id.map(|id| id.get() as i64);
+            let user_id = command.user.id.get() as i64;
+            let Ok(seed) = rng::new_session_seed()
+                .inspect_err(|e| warn!("Failed to create session seed: {}", e))
+            else {
+                return Ok(CommandResponse::private(
+                    "❌ Couldn't generate a secure session seed, please try again.".to_string(),
+                ));
+            };
+            if !db
+                .start_session(guild_id, channel_id, &format_seed(&seed), user_id)
+                .await?
+            {
+                return Ok(CommandResponse::private(
+                    "❌ A session is already running in this channel. End it with `/session end` first."
+                        .to_string(),
+                ));
+            }
+            info!(
+                "Session started in channel {} by {}",
+                channel_id, command.user.id
+            );
+            Ok(CommandResponse::public(
+                "🎞️ **Session started.** Rolls in this channel are now numbered and can be \
+                 reproduced from the session seed, which is revealed with `/session end`."
+                    .to_string(),
+            ))
+        }
+        "end" => Ok(match db.end_session(channel_id).await? {
+            Some(session) => {
+                info!(
+                    "Session ended in channel {} by {}",
+                    channel_id, command.user.id
+                );
+                CommandResponse::public(format_session_end(&session))
+            }
+            None => {
+                CommandResponse::private("❌ No session is running in this channel.".to_string())
+            }
+        }),
+        other => Err(anyhow!("Unknown session subcommand: {}", other)),
+    }
+}
+
+/// Whether the user may run the game: administrators and the `/setup` GM
+/// role qualify in servers, and anyone does in DMs
+pub(crate) async fn is_game_master(ctx: &Context, command: &CommandInteraction) -> bool {
+    let Some(guild_id) = command.guild_id else {
+        return true;
+    };
+    let Some(member) = command.member.as_deref() else {
+        return false;
+    };
+    if is_administrator(member.permissions) {
+        return true;
+    }
+
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return false;
+    };
+    match db.get_guild_config(guild_id.get() as i64).await {
+        Ok(config) => config
+            .and_then(|config| config.gm_role_id)
+            .is_some_and(|role| member.roles.iter().any(|id| id.get() as i64 == role)),
+        Err(e) => {
+            warn!("Failed to load config for guild {}: {}", guild_id, e);
+            false
+        }
+    }
+}
+
+/// Claim the next roll number if a session is running in the channel,
+/// returning the session seed and the roll's number.  Lookup failures are
+/// logged and the roll falls back to the normal RNG.
+pub async fn claim_roll(ctx: &Context, channel_id: ChannelId) -> Option<([u8; 32], u64)> {
+    let db = ctx.data.read().await.get::<DatabaseContainer>().cloned()?;
+    match db.next_session_roll(channel_id.get() as i64).await {
+        Ok(session) => {
+            let session = session?;
+            parse_seed(&session.seed)
+                .inspect_err(|e| warn!("Bad session seed in channel {}: {}", channel_id, e))
+                .ok()
+                .map(|seed| (seed, session.rolls as u64))
+        }
+        Err(e) => {
+            warn!("Failed to check session in channel {}: {}", channel_id, e);
+            None
+        }
+    }
+}
+
+/// Roll `expr` exactly as session roll number `roll` with `seed` did
+pub fn replay(seed: &[u8; 32], roll: i64, expr: &str) -> Result<Vec<RollResult>> {
+    let roll = u64::try_from(roll)
+        .ok()
+        .filter(|roll| *roll > 0)
+        .ok_or_else(|| anyhow!("Roll numbers start at 1"))?;
+    Ok(dice::parse_and_roll_with_rng(
+        expr,
+        &mut rng::session_rng(seed, roll),
+    )?)
+}
+
+/// Seeds are shown (and stored) as 64 hex digits
+pub fn format_seed(seed: &[u8; 32]) -> String {
+    hex::encode(seed)
+}
+
+pub fn parse_seed(seed: &str) -> Result<[u8; 32]> {
+    hex::decode(seed.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow!("Invalid seed `{}`: expected 64 hex digits", seed.trim()))
+}
+
+pub fn format_session_end(session: &GameSession) -> String {
+    format!(
+        "🎞️ **Session ended** after {} rolls. Seed: `{}`\n\
+         Check any roll with `/session replay seed:{} roll:<number> dice:<expression>`.",
+        session.rolls, session.seed, session.seed
+    )
+}
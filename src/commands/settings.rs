@@ -0,0 +1,840 @@
+//! `/settings` slash-command handler for server roll defaults.
+//!
+//! | Subcommand    | Effect                                                        |
+//! |---------------|---------------------------------------------------------------|
+//! | `view`        | Show the current settings                                     |
+//! | `output`      | `full` shows the dice, `simple` only the result (like `s`)    |
+//! | `private`     | Make every roll private (ephemeral) by default                |
+//! | `max-dice`    | Limit the dice in one roll, or clear the limit                |
+//! | `disable`     | Turn off a game system, e.g. `cod` or `fitd`                  |
+//! | `enable`      | Turn a game system back on                                    |
+//! | `bands`       | Thresholds a bare `bands` uses, e.g. `5,9`, or the default    |
+//! | `restrict`    | Limit a heavy feature to one role, or lift the limit          |
+//! | `autocorrect` | Roll a mistyped alias as its close match, not just suggest it |
+//! | `fun`         | Show playful streak titles on rolls (see `commands::streak`)  |
+//! | `version`     | Show the dice engine version under rolls, for bug reports     |
+//! | `fair`        | Roll from a provably-fair seed checked with `/verify`         |
+//! | `embed`       | Show rolls as embeds with a coloured side bar (like `e`)      |
+//! | `crit-table`  | Roll on a `/table` under every natural 20 or natural 1        |
+//! | `gm-role`     | Set the GM role, who are DMed `gm` secret rolls               |
+//! | `text-commands` | Answer `!roll 2d6` chat messages like `/roll`               |
+//! | `threads`     | Post results too long for a message in a thread               |
+//!
+//! Systems are named by their alias key, as `/usage` counts them: `4cod` and
+//! `cod8` are both `cod`, `+d20` is `+d`.  Plain dice (`dice`) can't be
+//! disabled.  The settings live in the `guild_settings` table, except
+//! `private` and `gm-role`, which are the `/setup` settings stored in
+//! `guild_config`.
+//! `commands::roll` checks them before rolling ([`check_roll`]), fills in
+//! the server's bands ([`apply_default_bands`]) and applies the output mode
+//! before formatting ([`apply_output`]).
+//!
+//! Restricted features ([`GatedFeature`]) stay open to administrators; other
+//! members need the chosen role.  `/roll` checks `drama` rolls and roll sets
+//! with [`roll_features`], and `/table roll` checks table rolls, both through
+//! [`check_feature`] before anything is rolled.
+//!
+//! Critical tables are rolled by `commands::table::critical_roll` for d20
+//! checks (see `commands::streak::roll_outcome`); a table deleted after being
+//! chosen is skipped.
+//!
+//! A `gm` roll (`/roll gm 2d6+4`) only shows a placeholder in the channel;
+//! `commands::roll` DMs the result to the members of the GM role, and refuses
+//! the roll while no role is set.
+//!
+//! A roll that fails to parse because of a mistyped alias always gets a
+//! "did you mean" suggestion; with `autocorrect` on, `/roll` rolls the fix
+//! instead when there is only one, noting the change.
+//!
+//! Changing settings requires the **Administrator** permission, enforced both
+//! via `default_member_permissions` and at runtime by `middleware::Permissions`.
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::commands::config::is_administrator;
+use crate::commands::text_prefix;
+use crate::database::{GatedFeature, GuildSettings, OutputVerbosity};
+use crate::dice::{RollResult, parser};
+use crate::version;
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType, Member},
+    builder::{CreateCommand, CreateCommandOption},
+    model::prelude::Permissions,
+    prelude::Context,
+};
+use tracing::info;
+
+/// Systems a server may disable
+pub const MAX_DISABLED_SYSTEMS: usize = 25;
+
+// A bare `bands` modifier (no threshold list) outside the label and comment
+static BARE_BANDS_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bbands\b(\[)?").expect("Failed to compile BARE_BANDS_REGEX"));
+
+// Alias keys: an optional sign, then letters, digits or `%` (`+d`, `d6s`, `d%`)
+static SYSTEM_KEY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[+-]?[a-z][a-z0-9%]{0,15}$").expect("Failed to compile SYSTEM_KEY_REGEX")
+});
+
+pub fn register() -> CreateCommand {
+    let system_option = |description: &str| {
+        CreateCommandOption::new(CommandOptionType::String, "system", description)
+            .required(true)
+            .max_length(16)
+    };
+
+    CreateCommand::new("settings")
+        .description("Set roll defaults for this server (administrators only)")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "view",
+            "Show this server's roll settings",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "output",
+                "Show the dice with each result, or only the result",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "mode", "Output mode")
+                    .required(true)
+                    .add_string_choice("Full (dice and result)", "full")
+                    .add_string_choice("Simple (result only)", "simple"),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "private",
+                "Make rolls private by default",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "Whether rolls are private by default",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "max-dice",
+                "Limit the dice in one roll",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Integer,
+                    "limit",
+                    "Most dice in one roll (omit to remove the limit)",
+                )
+                .min_int_value(1)
+                .max_int_value(10_000),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "disable",
+                "Turn off a game system in this server",
+            )
+            .add_sub_option(system_option("System alias to turn off (e.g. cod, fitd)")),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "enable",
+                "Turn a game system back on",
+            )
+            .add_sub_option(system_option("System alias to turn back on")),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "bands",
+                "Set the outcome bands a plain `bands` roll uses",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "thresholds",
+                    "Highest total of each band, e.g. 5,9 (omit for 6,9)",
+                )
+                .max_length(32),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "restrict",
+                "Limit a heavy feature to members with a role",
+            )
+            .add_sub_option(
+                GatedFeature::ALL.into_iter().fold(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "feature",
+                        "Feature to limit",
+                    )
+                    .required(true),
+                    |option, feature| {
+                        option.add_string_choice(feature.description(), feature.as_str())
+                    },
+                ),
+            )
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::Role,
+                "role",
+                "Role that may use it (omit to open it to everyone)",
+            )),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "autocorrect",
+                "Roll a mistyped alias as its close match instead of suggesting it",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "Whether mistyped aliases are corrected",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "fun",
+                "Show playful titles for success and failure streaks",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "Whether rolls show streak titles",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "version",
+                "Show the dice engine version under every roll",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "Whether rolls show the engine version",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "fair",
+                "Roll from a provably-fair seed that /verify reveals",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "Whether rolls are provably fair",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "embed",
+                "Show rolls as embeds, green for crits and red for fumbles",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "Whether rolls are shown as embeds",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "text-commands",
+                "Answer !roll and !r chat messages like /roll",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "Whether !roll messages are answered",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "threads",
+                "Post results too long for one message in a thread",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "Whether long results get a summary and a thread",
+                )
+                .required(true),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "crit-table",
+                "Roll on a table under every natural 20 or natural 1 of a d20",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(CommandOptionType::String, "on", "Which result")
+                    .required(true)
+                    .add_string_choice("Natural 20", "nat20")
+                    .add_string_choice("Natural 1", "nat1"),
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "table",
+                    "Table from /table (omit to stop rolling one)",
+                )
+                .max_length(32),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "gm-role",
+                "Role whose members are sent gm secret rolls by DM",
+            )
+            .add_sub_option(CreateCommandOption::new(
+                CommandOptionType::Role,
+                "role",
+                "GM role (omit to turn secret rolls off)",
+            )),
+        )
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ This command can only be used in servers.".to_string(),
+        ));
+    };
+
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing settings subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid settings subcommand"));
+    };
+    let option = |name: &str| options.iter().find(|opt| opt.name == name);
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let guild_id = guild_id.get() as i64;
+    let mut settings = db
+        .get_guild_settings(guild_id)
+        .await?
+        .unwrap_or_else(|| GuildSettings::new(guild_id));
+
+    let content = match subcommand.name.as_str() {
+        "view" => {
+            let ephemeral = db
+                .get_guild_config(guild_id)
+                .await?
+                .is_some_and(|config| config.ephemeral_default);
+            return Ok(CommandResponse::private(format_settings(
+                &settings, ephemeral,
+            )));
+        }
+        "private" => {
+            let enabled = option("enabled")
+                .and_then(|opt| opt.value.as_bool())
+                .unwrap_or_default();
+            db.set_ephemeral_default(guild_id, enabled).await?;
+            info!(
+                "Set private rolls for guild {} to {} (by {})",
+                guild_id, enabled, command.user.id
+            );
+            return Ok(CommandResponse::private(if enabled {
+                "🔒 Rolls in this server are now private by default.".to_string()
+            } else {
+                "🔓 Rolls in this server are now public by default.".to_string()
+            }));
+        }
+        "gm-role" => {
+            let role = option("role")
+                .and_then(|opt| opt.value.as_role_id())
+                .map(|role| role.get() as i64);
+            db.set_gm_role(guild_id, role).await?;
+            info!(
+                "Set GM role for guild {} to {:?} (by {})",
+                guild_id, role, command.user.id
+            );
+            return Ok(CommandResponse::private(match role {
+                Some(role) => format!(
+                    "🤫 <@&{role}> is now the GM role: `gm` rolls post a placeholder and DM them the result."
+                ),
+                None => "🤫 This server has no GM role; `gm` rolls are turned off.".to_string(),
+            }));
+        }
+        "output" => {
+            let mode = option("mode")
+                .and_then(|opt| opt.value.as_str())
+                .unwrap_or_default();
+            let Some(verbosity) = OutputVerbosity::parse(mode) else {
+                return Ok(CommandResponse::private(format!(
+                    "❌ Unknown output mode `{mode}`. Use full or simple."
+                )));
+            };
+            settings.verbosity = verbosity;
+            match verbosity {
+                OutputVerbosity::Full => "🎲 Rolls now show the dice and the result.".to_string(),
+                OutputVerbosity::Simple => "🎲 Rolls now show only the result.".to_string(),
+            }
+        }
+        "max-dice" => {
+            settings.max_dice = option("limit")
+                .and_then(|opt| opt.value.as_i64())
+                .and_then(|limit| u32::try_from(limit).ok());
+            match settings.max_dice {
+                Some(limit) => format!("🎲 Rolls are now limited to {limit} dice."),
+                None => "🎲 Rolls are no longer limited beyond the bot's own limits.".to_string(),
+            }
+        }
+        name @ ("disable" | "enable") => {
+            let system = option("system")
+                .and_then(|opt| opt.value.as_str())
+                .unwrap_or_default();
+            let disable = name == "disable";
+            match set_system_disabled(&mut settings, system, disable) {
+                Ok(system) if disable => format!("🚫 `{system}` rolls are now turned off."),
+                Ok(system) => format!("✅ `{system}` rolls are turned on again."),
+                Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+            }
+        }
+        "bands" => {
+            let thresholds = option("thresholds").and_then(|opt| opt.value.as_str());
+            match set_default_bands(&mut settings, thresholds) {
+                Ok(bands) => format!("🎯 `bands` rolls now use `bands[{bands}]`."),
+                Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+            }
+        }
+        "restrict" => {
+            let value = option("feature")
+                .and_then(|opt| opt.value.as_str())
+                .unwrap_or_default();
+            let Some(feature) = GatedFeature::parse(value) else {
+                return Ok(CommandResponse::private(format!(
+                    "❌ Unknown feature `{value}`."
+                )));
+            };
+            let role = option("role").and_then(|opt| opt.value.as_role_id());
+            match role {
+                Some(role) => {
+                    settings.feature_roles.insert(feature, role.get() as i64);
+                    format!(
+                        "🔐 {} are now limited to <@&{role}>.",
+                        feature.description()
+                    )
+                }
+                None => {
+                    settings.feature_roles.remove(&feature);
+                    format!("🔓 {} are open to everyone again.", feature.description())
+                }
+            }
+        }
+        "autocorrect" => {
+            settings.autocorrect = option("enabled")
+                .and_then(|opt| opt.value.as_bool())
+                .unwrap_or_default();
+            if settings.autocorrect {
+                "✏️ Mistyped aliases are now rolled as their close match, e.g. `4cof` as `4cod`."
+                    .to_string()
+            } else {
+                "✏️ Mistyped aliases are now only suggested.".to_string()
+            }
+        }
+        "fun" => {
+            settings.fun_mode = option("enabled")
+                .and_then(|opt| opt.value.as_bool())
+                .unwrap_or_default();
+            if settings.fun_mode {
+                "🎉 Rolls now show streak titles, e.g. *3rd nat 1 today — Cursed*.".to_string()
+            } else {
+                "🎉 Streak titles are turned off.".to_string()
+            }
+        }
+        "version" => {
+            settings.version_footer = option("enabled")
+                .and_then(|opt| opt.value.as_bool())
+                .unwrap_or_default();
+            if settings.version_footer {
+                format!(
+                    "🔖 Rolls now end with the dice engine version (`{}`).",
+                    version::engine_version()
+                )
+            } else {
+                "🔖 Rolls no longer show the dice engine version.".to_string()
+            }
+        }
+        "fair" => {
+            settings.fair_rolls = option("enabled")
+                .and_then(|opt| opt.value.as_bool())
+                .unwrap_or_default();
+            if settings.fair_rolls {
+                "🔏 Rolls are now provably fair: each shows its seed hash and number, \
+                 and `/verify` reveals the seed to recompute it."
+                    .to_string()
+            } else {
+                "🔏 Rolls no longer use the provably-fair seed.".to_string()
+            }
+        }
+        "embed" => {
+            settings.embed_output = option("enabled")
+                .and_then(|opt| opt.value.as_bool())
+                .unwrap_or_default();
+            if settings.embed_output {
+                "🖼️ Rolls are now shown as embeds: green for crits, red for fumbles.".to_string()
+            } else {
+                "🖼️ Rolls are shown as text again; the `e` flag still shows one as an embed."
+                    .to_string()
+            }
+        }
+        "text-commands" => {
+            settings.text_commands = option("enabled")
+                .and_then(|opt| opt.value.as_bool())
+                .unwrap_or_default();
+            match (settings.text_commands, text_prefix::intent_enabled()) {
+                (true, true) => {
+                    "💬 `!roll 2d6` and `!r 2d6` messages are now answered like `/roll`.".to_string()
+                }
+                (true, false) => "💬 `!roll` messages are turned on for this server, but this bot isn't reading messages: its host needs to set `MESSAGE_COMMANDS=true`.".to_string(),
+                (false, _) => "💬 `!roll` messages are no longer answered; use `/roll`.".to_string(),
+            }
+        }
+        "threads" => {
+            settings.threaded_output = option("enabled")
+                .and_then(|opt| opt.value.as_bool())
+                .unwrap_or_default();
+            if settings.threaded_output {
+                "🧵 Results too long for one message now get a short summary, with the full results in a thread. The bot needs the Create Public Threads permission.".to_string()
+            } else {
+                "🧵 Results too long for one message are shortened to fit again.".to_string()
+            }
+        }
+        "crit-table" => {
+            let on = option("on")
+                .and_then(|opt| opt.value.as_str())
+                .unwrap_or_default();
+            let table = option("table")
+                .and_then(|opt| opt.value.as_str())
+                .map(|name| name.trim().to_lowercase())
+                .filter(|name| !name.is_empty());
+            if let Some(name) = &table
+                && !db.get_guild_tables(guild_id).await?.contains_key(name)
+            {
+                return Ok(CommandResponse::private(format!(
+                    "❌ This server has no table named `{name}`. Create it with `/table create` first."
+                )));
+            }
+            match set_critical_table(&mut settings, on, table) {
+                Ok(content) => content,
+                Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+            }
+        }
+        other => return Err(anyhow!("Unknown settings subcommand: {}", other)),
+    };
+
+    db.save_guild_settings(&settings).await?;
+    info!(
+        "Updated settings for guild {} with {} (by {})",
+        guild_id, subcommand.name, command.user.id
+    );
+    Ok(CommandResponse::private(content))
+}
+
+/// Add `system` to (or remove it from) the disabled systems, returning the
+/// normalized alias key
+pub fn set_system_disabled(
+    settings: &mut GuildSettings,
+    system: &str,
+    disable: bool,
+) -> Result<String> {
+    let system = system.trim().to_lowercase();
+    if system == "dice" || !SYSTEM_KEY_REGEX.is_match(&system) {
+        return Err(anyhow!(
+            "`{system}` isn't a game system alias. Use the alias name, like `cod` or `fitd`."
+        ));
+    }
+
+    let disabled = &mut settings.disabled_systems;
+    if disable {
+        if disabled.contains(&system) {
+            return Err(anyhow!("`{system}` is already turned off"));
+        }
+        if disabled.len() >= MAX_DISABLED_SYSTEMS {
+            return Err(anyhow!(
+                "You can turn off at most {MAX_DISABLED_SYSTEMS} systems"
+            ));
+        }
+        disabled.push(system.clone());
+        disabled.sort();
+    } else {
+        let Some(pos) = disabled.iter().position(|key| *key == system) else {
+            return Err(anyhow!("`{system}` isn't turned off"));
+        };
+        disabled.remove(pos);
+    }
+    Ok(system)
+}
+
+/// Set the thresholds a bare `bands` uses, or restore the default when
+/// `thresholds` is `None`, returning them as a list like `6,9`
+pub fn set_default_bands(settings: &mut GuildSettings, thresholds: Option<&str>) -> Result<String> {
+    settings.bands = match thresholds.map(str::trim) {
+        Some(list) if !list.is_empty() => parser::parse_band_thresholds(list)?,
+        _ => Vec::new(),
+    };
+    Ok(format_bands(&settings.bands))
+}
+
+/// Set (or clear, when `table` is `None`) the table rolled under a natural
+/// 20 (`nat20`) or natural 1 (`nat1`), returning the confirmation
+pub fn set_critical_table(
+    settings: &mut GuildSettings,
+    on: &str,
+    table: Option<String>,
+) -> Result<String> {
+    let (slot, result) = match on {
+        "nat20" => (&mut settings.crit_table, "natural 20"),
+        "nat1" => (&mut settings.fumble_table, "natural 1"),
+        _ => return Err(anyhow!("Unknown result `{on}`. Use nat20 or nat1.")),
+    };
+    *slot = table;
+    Ok(match slot {
+        Some(name) => format!("📜 Every {result} of a d20 now rolls on **{name}**."),
+        None => format!("📜 A {result} no longer rolls on a table."),
+    })
+}
+
+/// Give each bare `bands` in the expression the server's thresholds, so
+/// `2d6 bands` rolls as `2d6 bands[5,9]`.  Returns `None` when nothing changes.
+pub fn apply_default_bands(settings: &GuildSettings, expression: &str) -> Option<String> {
+    if settings.bands.is_empty() {
+        return None;
+    }
+
+    // Leave a leading `(label)` and the `! comment` alone
+    let (body, comment) = expression.split_at(expression.find('!').unwrap_or(expression.len()));
+    let label_end = if body.trim_start().starts_with('(') {
+        body.find(')').map_or(0, |i| i + 1)
+    } else {
+        0
+    };
+    let (label, body) = body.split_at(label_end);
+
+    let replacement = format!("bands[{}]", format_bands(&settings.bands));
+    let mut changed = false;
+    let body = BARE_BANDS_REGEX.replace_all(body, |captures: &regex::Captures| {
+        if captures.get(1).is_some() {
+            captures[0].to_string()
+        } else {
+            changed = true;
+            replacement.clone() // one short string per bare `bands`
+        }
+    });
+    changed.then(|| format!("{label}{body}{comment}"))
+}
+
+// `6,9`, or the default thresholds when none are set
+fn format_bands(bands: &[i32]) -> String {
+    let bands = if bands.is_empty() {
+        &parser::DEFAULT_BANDS[..]
+    } else {
+        bands
+    };
+    bands
+        .iter()
+        .map(i32::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Why the server's settings refuse a roll, or `None` if it may go ahead
+pub fn check_roll(settings: &GuildSettings, expression: &str) -> Option<String> {
+    if let Some(system) = parser::usage_keys(expression)
+        .into_iter()
+        .find(|key| settings.disabled_systems.contains(key))
+    {
+        return Some(format!("`{system}` rolls are turned off in this server."));
+    }
+
+    let max_dice = settings.max_dice?;
+    // Unparseable rolls are left for the roller to report
+    let rolls = parser::parse_dice_string(expression).ok()?;
+    let dice: u32 = rolls
+        .iter()
+        .map(|roll| roll.dice_count())
+        .fold(0, u32::saturating_add);
+    (dice > max_dice).then(|| format!("This server allows at most {max_dice} dice per roll."))
+}
+
+/// Restricted features a roll uses: `drama` rolls and roll sets
+pub fn roll_features(expression: &str) -> Vec<GatedFeature> {
+    // Unparseable rolls are left for the roller to report
+    let Ok(rolls) = parser::parse_dice_string(expression) else {
+        return Vec::new();
+    };
+
+    let mut features = Vec::new();
+    if rolls.iter().any(|roll| roll.drama) {
+        features.push(GatedFeature::Drama);
+    }
+    let is_roll_set = rolls.len() > 1
+        && rolls
+            .iter()
+            .enumerate()
+            .all(|(i, roll)| roll.label.as_deref() == Some(format!("Set {}", i + 1).as_str()));
+    if is_roll_set {
+        features.push(GatedFeature::RollSets);
+    }
+    features
+}
+
+/// Why a member can't use `feature`, or `None` if they may.  Administrators
+/// are never limited.
+pub fn check_feature(
+    settings: &GuildSettings,
+    feature: GatedFeature,
+    roles: &[i64],
+    administrator: bool,
+) -> Option<String> {
+    let role = settings.feature_roles.get(&feature)?;
+    (!administrator && !roles.contains(role)).then(|| {
+        format!(
+            "{} are limited to the <@&{role}> role in this server.",
+            feature.description()
+        )
+    })
+}
+
+/// [`check_feature`] for the member making a request
+pub fn check_member_feature(
+    settings: &GuildSettings,
+    feature: GatedFeature,
+    member: Option<&Member>,
+) -> Option<String> {
+    let roles: Vec<i64> = member
+        .map(|member| member.roles.iter().map(|role| role.get() as i64).collect())
+        .unwrap_or_default();
+    let administrator = is_administrator(member.and_then(|member| member.permissions));
+    check_feature(settings, feature, &roles, administrator)
+}
+
+/// Apply the server's output mode to rolled results
+pub fn apply_output(settings: &GuildSettings, results: &mut [RollResult]) {
+    if settings.verbosity == OutputVerbosity::Simple {
+        for result in results {
+            result.simple = true;
+        }
+    }
+}
+
+/// The `/settings view` message
+pub fn format_settings(settings: &GuildSettings, ephemeral: bool) -> String {
+    let output = match settings.verbosity {
+        OutputVerbosity::Full => "full (dice and result)",
+        OutputVerbosity::Simple => "simple (result only)",
+    };
+    let max_dice = settings
+        .max_dice
+        .map_or("no limit".to_string(), |limit| limit.to_string());
+    let restricted = if settings.feature_roles.is_empty() {
+        "none".to_string()
+    } else {
+        settings
+            .feature_roles
+            .iter()
+            .map(|(feature, role)| format!("`{}` → <@&{role}>", feature.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let critical_tables = match (&settings.crit_table, &settings.fumble_table) {
+        (None, None) => "none".to_string(),
+        (crit, fumble) => {
+            let table = |name: &Option<String>| {
+                name.as_ref()
+                    .map_or("none".to_string(), |name| format!("`{name}`"))
+            };
+            format!("nat 20 → {}, nat 1 → {}", table(crit), table(fumble))
+        }
+    };
+    let disabled = if settings.disabled_systems.is_empty() {
+        "none".to_string()
+    } else {
+        settings
+            .disabled_systems
+            .iter()
+            .map(|system| format!("`{system}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    format!(
+        "⚙️ **Roll settings**\n\
+         Output: {output}\n\
+         Private by default: {}\n\
+         Max dice per roll: {max_dice}\n\
+         Disabled systems: {disabled}\n\
+         Outcome bands: `bands[{}]`\n\
+         Restricted features: {restricted}\n\
+         Autocorrect aliases: {}\n\
+         Streak titles: {}\n\
+         Engine version footer: {}\n\
+         Provably fair rolls: {}\n\
+         Embed output: {}\n\
+         Critical tables: {critical_tables}\n\
+         `!roll` text commands: {}\n\
+         Long results in threads: {}",
+        if ephemeral { "yes" } else { "no" },
+        format_bands(&settings.bands),
+        if settings.autocorrect { "yes" } else { "no" },
+        if settings.fun_mode { "yes" } else { "no" },
+        if settings.version_footer { "yes" } else { "no" },
+        if settings.fair_rolls { "yes" } else { "no" },
+        if settings.embed_output { "yes" } else { "no" },
+        if settings.text_commands { "yes" } else { "no" },
+        if settings.threaded_output {
+            "yes"
+        } else {
+            "no"
+        }
+    )
+}
@@ -0,0 +1,410 @@
+//! `/setup` interactive onboarding for server administrators.
+//!
+//! Walks through the key server settings one select menu at a time:
+//!
+//! | Step | Setting            | Component                              |
+//! |------|--------------------|----------------------------------------|
+//! | 1    | Default system     | String select (`aliases::DEFAULT_SYSTEMS`) |
+//! | 2    | Roll visibility    | String select (public / private)       |
+//! | 3    | GM role            | Role select, or "No GM role" button    |
+//! | 4    | Roll channels      | Channel select, or "All channels" button |
+//! | 5    | Roll history       | String select (full / totals / off)    |
+//! | 6    | Review             | Save / Cancel buttons                  |
+//!
+//! Choices are kept in an in-memory draft (keyed by guild and user) until
+//! **Save**, which writes the `guild_config` row and the server's history
+//! level in one transaction.  Drafts expire with the interaction token after
+//! 15 minutes.  Components use custom IDs prefixed with `setup:` and are
+//! handled by [`handle_component`].
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::commands::config::is_administrator;
+use crate::database::{GuildConfig, HistoryLevel};
+use crate::dice::aliases::DEFAULT_SYSTEMS;
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use serenity::{
+    all::{
+        ButtonStyle, ChannelType, CommandInteraction, ComponentInteraction,
+        ComponentInteractionDataKind,
+    },
+    builder::{
+        CreateActionRow, CreateButton, CreateCommand, CreateSelectMenu, CreateSelectMenuKind,
+        CreateSelectMenuOption,
+    },
+    model::prelude::Permissions,
+    prelude::Context,
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+const SYSTEM_ID: &str = "setup:system";
+const VISIBILITY_ID: &str = "setup:visibility";
+const GM_ROLE_ID: &str = "setup:gm-role";
+const NO_GM_ROLE_ID: &str = "setup:gm-role:none";
+const CHANNELS_ID: &str = "setup:channels";
+const ALL_CHANNELS_ID: &str = "setup:channels:all";
+const HISTORY_ID: &str = "setup:history";
+const SAVE_ID: &str = "setup:save";
+const CANCEL_ID: &str = "setup:cancel";
+
+const NO_SYSTEM: &str = "none";
+
+// Interaction tokens are valid for 15 minutes, so drafts can't outlive them
+const DRAFT_TTL: Duration = Duration::from_secs(15 * 60);
+
+struct SetupDraft {
+    config: GuildConfig,
+    history: HistoryLevel,
+    started: Instant,
+}
+
+// In-progress /setup sessions keyed by (guild_id, user_id)
+static DRAFTS: Lazy<Mutex<HashMap<(u64, u64), SetupDraft>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("setup")
+        .description("Set up Dice Maiden for this server (administrators only)")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .dm_permission(false)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ This command can only be used in servers.".to_string(),
+        ));
+    };
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+
+    // Start from the current settings so re-running /setup only changes what's picked
+    let gid = guild_id.get() as i64;
+    let config = db
+        .get_guild_config(gid)
+        .await?
+        .unwrap_or_else(|| GuildConfig::new(gid));
+    let history = db
+        .get_privacy_level("guild", gid)
+        .await?
+        .unwrap_or_default();
+
+    let mut drafts = lock_drafts();
+    drafts.retain(|_, draft| draft.started.elapsed() < DRAFT_TTL);
+    drafts.insert(
+        (guild_id.get(), command.user.id.get()),
+        SetupDraft {
+            config,
+            history,
+            started: Instant::now(),
+        },
+    );
+
+    Ok(system_step())
+}
+
+/// Handle the `setup:` select menus and buttons, returning the next step
+pub async fn handle_component(
+    ctx: &Context,
+    component: &ComponentInteraction,
+) -> Result<CommandResponse> {
+    let Some(guild_id) = component.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ This can only be used in servers.".to_string(),
+        ));
+    };
+
+    if !is_administrator(component.member.as_ref().and_then(|m| m.permissions)) {
+        return Ok(CommandResponse::private(
+            "❌ You need the 'Administrator' permission to change server settings.".to_string(),
+        ));
+    }
+
+    let key = (guild_id.get(), component.user.id.get());
+    let custom_id = component.data.custom_id.as_str();
+
+    if custom_id == CANCEL_ID {
+        lock_drafts().remove(&key);
+        return Ok(CommandResponse::private(
+            "Setup cancelled. No settings were changed.".to_string(),
+        ));
+    }
+
+    if custom_id == SAVE_ID {
+        let Some(draft) = lock_drafts().remove(&key) else {
+            return Ok(expired());
+        };
+
+        let db = ctx
+            .data
+            .read()
+            .await
+            .get::<DatabaseContainer>()
+            .cloned()
+            .ok_or_else(|| anyhow!("Database not available"))?;
+        db.save_guild_config(&draft.config, draft.history).await?;
+
+        info!(
+            "Saved setup for guild {} (by {})",
+            draft.config.guild_id, component.user.id
+        );
+
+        return Ok(CommandResponse::private(format!(
+            "✅ Settings saved.\n{}",
+            format_setup_summary(&draft.config, draft.history)
+        )));
+    }
+
+    let mut drafts = lock_drafts();
+    let Some(draft) = drafts
+        .get_mut(&key)
+        .filter(|draft| draft.started.elapsed() < DRAFT_TTL)
+    else {
+        return Ok(expired());
+    };
+
+    let selected_string = || match &component.data.kind {
+        ComponentInteractionDataKind::StringSelect { values } => values.first().cloned(),
+        _ => None,
+    };
+
+    let next = match custom_id {
+        SYSTEM_ID => {
+            draft.config.default_system = selected_string().filter(|value| value != NO_SYSTEM);
+            visibility_step()
+        }
+        VISIBILITY_ID => {
+            draft.config.ephemeral_default = selected_string().as_deref() == Some("private");
+            gm_role_step()
+        }
+        GM_ROLE_ID => {
+            if let ComponentInteractionDataKind::RoleSelect { values } = &component.data.kind {
+                draft.config.gm_role_id = values.first().map(|role| role.get() as i64);
+            }
+            channels_step()
+        }
+        NO_GM_ROLE_ID => {
+            draft.config.gm_role_id = None;
+            channels_step()
+        }
+        CHANNELS_ID => {
+            if let ComponentInteractionDataKind::ChannelSelect { values } = &component.data.kind {
+                draft.config.restricted_channels =
+                    values.iter().map(|channel| channel.get() as i64).collect();
+            }
+            history_step()
+        }
+        ALL_CHANNELS_ID => {
+            draft.config.restricted_channels.clear();
+            history_step()
+        }
+        HISTORY_ID => {
+            if let Some(level) = selected_string().as_deref().and_then(HistoryLevel::parse) {
+                draft.history = level;
+            }
+            review_step(&draft.config, draft.history)
+        }
+        other => return Err(anyhow!("Unknown setup component: {}", other)),
+    };
+
+    Ok(next)
+}
+
+/// Summary of a server's settings, as shown on the review step and after saving
+pub fn format_setup_summary(config: &GuildConfig, history: HistoryLevel) -> String {
+    let system = config
+        .default_system
+        .as_deref()
+        .and_then(|key| DEFAULT_SYSTEMS.iter().find(|(k, _, _)| *k == key))
+        .map_or("None", |(_, name, _)| name);
+    let visibility = if config.ephemeral_default {
+        "Private (only the roller sees results)"
+    } else {
+        "Public"
+    };
+    let gm_role = config
+        .gm_role_id
+        .map_or("None".to_string(), |id| format!("<@&{id}>"));
+    let channels = if config.restricted_channels.is_empty() {
+        "All channels".to_string()
+    } else {
+        config
+            .restricted_channels
+            .iter()
+            .map(|id| format!("<#{id}>"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    format!(
+        "**Default system:** {system}\n**Roll visibility:** {visibility}\n**GM role:** {gm_role}\n**Roll channels:** {channels}\n**Roll history:** {}",
+        history.description()
+    )
+}
+
+fn lock_drafts() -> std::sync::MutexGuard<'static, HashMap<(u64, u64), SetupDraft>> {
+    DRAFTS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn expired() -> CommandResponse {
+    CommandResponse::private(
+        "⌛ This setup session has expired. Run `/setup` again to start over.".to_string(),
+    )
+}
+
+fn string_select(
+    custom_id: &str,
+    placeholder: &str,
+    options: Vec<(&str, String)>,
+) -> CreateActionRow {
+    let options = options
+        .into_iter()
+        .map(|(value, label)| CreateSelectMenuOption::new(label, value))
+        .collect();
+    CreateActionRow::SelectMenu(
+        CreateSelectMenu::new(custom_id, CreateSelectMenuKind::String { options })
+            .placeholder(placeholder),
+    )
+}
+
+fn cancel_button() -> CreateButton {
+    CreateButton::new(CANCEL_ID)
+        .label("Cancel")
+        .style(ButtonStyle::Secondary)
+}
+
+fn system_step() -> CommandResponse {
+    let mut options = vec![(NO_SYSTEM, "No default system".to_string())];
+    options.extend(
+        DEFAULT_SYSTEMS
+            .iter()
+            .map(|(key, name, _)| (*key, name.to_string())),
+    );
+
+    CommandResponse::private(
+        "⚙️ **Dice Maiden setup — step 1/5**\nPick a default game system. \
+         A bare number like `/roll 5` will roll it."
+            .to_string(),
+    )
+    .with_components(vec![
+        string_select(SYSTEM_ID, "Default system", options),
+        CreateActionRow::Buttons(vec![cancel_button()]),
+    ])
+}
+
+fn visibility_step() -> CommandResponse {
+    CommandResponse::private(
+        "⚙️ **Dice Maiden setup — step 2/5**\nShould rolls be public or private by default?"
+            .to_string(),
+    )
+    .with_components(vec![
+        string_select(
+            VISIBILITY_ID,
+            "Roll visibility",
+            vec![
+                ("public", "Public — everyone sees results".to_string()),
+                (
+                    "private",
+                    "Private — only the roller sees results".to_string(),
+                ),
+            ],
+        ),
+        CreateActionRow::Buttons(vec![cancel_button()]),
+    ])
+}
+
+fn gm_role_step() -> CommandResponse {
+    CommandResponse::private(
+        "⚙️ **Dice Maiden setup — step 3/5**\nWhich role do your game masters have?".to_string(),
+    )
+    .with_components(vec![
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(
+                GM_ROLE_ID,
+                CreateSelectMenuKind::Role {
+                    default_roles: None,
+                },
+            )
+            .placeholder("GM role"),
+        ),
+        CreateActionRow::Buttons(vec![
+            CreateButton::new(NO_GM_ROLE_ID)
+                .label("No GM role")
+                .style(ButtonStyle::Primary),
+            cancel_button(),
+        ]),
+    ])
+}
+
+fn channels_step() -> CommandResponse {
+    CommandResponse::private(
+        "⚙️ **Dice Maiden setup — step 4/5**\nLimit rolling to specific channels, or allow it everywhere."
+            .to_string(),
+    )
+    .with_components(vec![
+        CreateActionRow::SelectMenu(
+            CreateSelectMenu::new(
+                CHANNELS_ID,
+                CreateSelectMenuKind::Channel {
+                    channel_types: Some(vec![ChannelType::Text]),
+                    default_channels: None,
+                },
+            )
+            .placeholder("Roll channels")
+            .min_values(1)
+            .max_values(25),
+        ),
+        CreateActionRow::Buttons(vec![
+            CreateButton::new(ALL_CHANNELS_ID)
+                .label("All channels")
+                .style(ButtonStyle::Primary),
+            cancel_button(),
+        ]),
+    ])
+}
+
+fn history_step() -> CommandResponse {
+    CommandResponse::private(
+        "⚙️ **Dice Maiden setup — step 5/5**\nShould rolls in this server be kept in roll history? \
+         Members can still choose a stricter level with `/privacy`."
+            .to_string(),
+    )
+    .with_components(vec![
+        string_select(
+            HISTORY_ID,
+            "Roll history",
+            vec![
+                (HistoryLevel::Full.as_str(), "On — full rolls".to_string()),
+                (
+                    HistoryLevel::TotalsOnly.as_str(),
+                    "Totals only".to_string(),
+                ),
+                (HistoryLevel::Off.as_str(), "Off".to_string()),
+            ],
+        ),
+        CreateActionRow::Buttons(vec![cancel_button()]),
+    ])
+}
+
+fn review_step(config: &GuildConfig, history: HistoryLevel) -> CommandResponse {
+    CommandResponse::private(format!(
+        "⚙️ **Dice Maiden setup — review**\n{}",
+        format_setup_summary(config, history)
+    ))
+    .with_components(vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(SAVE_ID)
+            .label("Save")
+            .style(ButtonStyle::Success),
+        cancel_button(),
+    ])])
+}
@@ -0,0 +1,225 @@
+//! `/sheet` slash-command handler for character sheet stats.
+//!
+//! | Subcommand | Effect                                              |
+//! |------------|-----------------------------------------------------|
+//! | `show`     | List your stats in this server                      |
+//! | `set`      | Set a stat, e.g. `/sheet set name:str value:3`      |
+//! | `remove`   | Remove one stat                                     |
+//! | `clear`    | Remove every stat                                   |
+//!
+//! Sheets are per user and per server (DMs share one sheet), so each campaign
+//! can have its own character.  Stats feed pool rolls such as
+//! `/roll vtm str + brawl`; see `aliases::expand_stat_pool`, and `@name`
+//! modifiers such as `/roll 1d20 + @str`; see `commands::char_cmd`.  The `hunger`,
+//! `stress` and `momentum` stats are trackers that system rolls read and
+//! update automatically; see `dice::trackers`.
+
+use crate::DatabaseContainer;
+use crate::commands::{CommandResponse, RollOrigin};
+use crate::dice::trackers::{self, TrackerUpdate};
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use std::collections::BTreeMap;
+use tracing::warn;
+
+/// Most stats a single sheet may hold
+pub const MAX_SHEET_STATS: usize = 50;
+
+/// Lowest and highest value a stat may be set to
+pub const STAT_VALUE_RANGE: (i64, i64) = (-20, 50);
+
+static STAT_NAME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[a-z_][a-z0-9_]{0,31}$").expect("Failed to compile STAT_NAME_REGEX")
+});
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("sheet")
+        .description("Manage your character sheet stats for pool rolls")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "show",
+            "Show your stats in this server",
+        ))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "set", "Set a stat")
+                .add_sub_option(name_option())
+                .add_sub_option(
+                    CreateCommandOption::new(CommandOptionType::Integer, "value", "Stat value")
+                        .required(true)
+                        // min_int_value only takes a u64, so negative minimums are checked in run()
+                        .max_int_value(STAT_VALUE_RANGE.1 as u64),
+                ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "remove", "Remove a stat")
+                .add_sub_option(name_option()),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "clear",
+            "Remove all your stats in this server",
+        ))
+}
+
+fn name_option() -> CreateCommandOption {
+    CreateCommandOption::new(
+        CommandOptionType::String,
+        "name",
+        "Stat name (e.g. str, brawl, hunger)",
+    )
+    .required(true)
+    .max_length(32)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing sheet subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid sheet subcommand"));
+    };
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let guild_id = command.guild_id.map_or(0, |id| id.get() as i64);
+    let user_id = command.user.id.get() as i64;
+
+    let name = options
+        .iter()
+        .find(|opt| opt.name == "name")
+        .and_then(|opt| opt.value.as_str())
+        .map(normalize_stat_name);
+
+    let content = match subcommand.name.as_str() {
+        "show" => format_sheet(&db.get_character_stats(guild_id, user_id).await?),
+        "set" => {
+            let Some(name) = name else {
+                return Err(anyhow!("Missing stat name"));
+            };
+            let Some(value) = options
+                .iter()
+                .find(|opt| opt.name == "value")
+                .and_then(|opt| opt.value.as_i64())
+            else {
+                return Err(anyhow!("Missing stat value"));
+            };
+
+            if let Err(e) = validate_stat(&name, value) {
+                return Ok(CommandResponse::private(format!("❌ {e}")));
+            }
+
+            let stats = db.get_character_stats(guild_id, user_id).await?;
+            if !stats.contains_key(&name) && stats.len() >= MAX_SHEET_STATS {
+                return Ok(CommandResponse::private(format!(
+                    "❌ Your sheet already has {MAX_SHEET_STATS} stats. Remove one first."
+                )));
+            }
+
+            db.set_character_stat(guild_id, user_id, &name, value)
+                .await?;
+            format!("📝 Set `{name}` to **{value}**.")
+        }
+        "remove" => {
+            let Some(name) = name else {
+                return Err(anyhow!("Missing stat name"));
+            };
+            if db.remove_character_stat(guild_id, user_id, &name).await? {
+                format!("🗑️ Removed `{name}`.")
+            } else {
+                format!("❌ Your sheet has no stat named `{name}`.")
+            }
+        }
+        "clear" => {
+            let removed = db.clear_character_stats(guild_id, user_id).await?;
+            format!("🗑️ Cleared your sheet ({removed} stats removed).")
+        }
+        other => return Err(anyhow!("Unknown sheet subcommand: {}", other)),
+    };
+
+    Ok(CommandResponse::private(content))
+}
+
+pub fn normalize_stat_name(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Check a (normalized) stat name and value before saving
+pub fn validate_stat(name: &str, value: i64) -> Result<()> {
+    if !STAT_NAME_REGEX.is_match(name) {
+        return Err(anyhow!(
+            "Invalid stat name `{name}`: use up to 32 letters, digits or `_`, not starting with a digit"
+        ));
+    }
+    if let Some(tracker) = trackers::find_tracker(name) {
+        if !(0..=tracker.max).contains(&value) {
+            return Err(anyhow!(
+                "`{name}` ({}) must be between 0 and {}",
+                tracker.system,
+                tracker.max
+            ));
+        }
+        return Ok(());
+    }
+    if !(STAT_VALUE_RANGE.0..=STAT_VALUE_RANGE.1).contains(&value) {
+        return Err(anyhow!(
+            "Stat values must be between {} and {}",
+            STAT_VALUE_RANGE.0,
+            STAT_VALUE_RANGE.1
+        ));
+    }
+    Ok(())
+}
+
+/// Save the tracker changes from a successful roll, returning a note for the
+/// roll message.  Failures are logged and noted but never fail the roll.
+pub async fn record_tracker_updates(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    updates: &[TrackerUpdate],
+) -> String {
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return "\n📝 Sheet unavailable: database not connected".to_string();
+    };
+
+    let guild_id = origin.guild_id.map_or(0, |id| id.get() as i64);
+    let user_id = origin.user.id.get() as i64;
+    for update in updates {
+        if let Err(e) = db
+            .set_character_stat(guild_id, user_id, update.name, update.to)
+            .await
+        {
+            warn!(
+                "Failed to update {} for {}: {}",
+                update.name, origin.user.id, e
+            );
+            return format!("\n📝 Couldn't update your `{}`", update.name);
+        }
+    }
+    format!("\n📝 {}", trackers::format_updates(updates))
+}
+
+pub fn format_sheet(stats: &BTreeMap<String, i64>) -> String {
+    if stats.is_empty() {
+        return "📝 Your sheet is empty. Add stats with `/sheet set name:str value:3`, \
+                then roll pools like `/roll vtm str + brawl`."
+            .to_string();
+    }
+
+    let lines = stats
+        .iter()
+        .map(|(name, value)| format!("`{name}`: **{value}**"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("📝 **Your sheet**\n{lines}")
+}
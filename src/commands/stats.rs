@@ -0,0 +1,215 @@
+//! `/stats` slash-command handler and per-server roll statistics.
+//!
+//! Every public roll in a server counts towards the roller's stats there
+//! (`roll_stats`): how many rolls, the natural 20s and 1s of d20 checks (as
+//! classified by [`streak::roll_outcome`]) and the average total.
+//!
+//! | Subcommand       | Shows                                            |
+//! |------------------|--------------------------------------------------|
+//! | `me`             | Your stats in this server and your place         |
+//! | `server`         | The [`LEADERBOARD_LIMIT`] rollers with the most rolls |
+//! | `tracking`       | Whether your rolls are counted; `enabled:false` stops counting them and deletes your stats in every server (`stats_opt_outs`) |
+//!
+//! Private (`p`) and secret `gm` rolls, and rolls in DMs, are never counted.
+//! Replies are private.  Counts are written through the `write_queue`.
+
+use crate::DatabaseContainer;
+use crate::commands::{CommandResponse, RollOrigin, streak};
+use crate::database::{RollStatsEntry, UserRollStats};
+use crate::dice::{self, RollResult};
+use crate::write_queue::{self, DbWrite};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+
+/// Rollers listed by `/stats server`
+pub const LEADERBOARD_LIMIT: i64 = 10;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("stats")
+        .description("Roll counts, natural 20s and 1s in this server")
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "me",
+            "Show your roll stats in this server",
+        ))
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "server",
+            "Show this server's roll leaderboard",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "tracking",
+                "Turn the counting of your rolls on or off",
+            )
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::Boolean,
+                    "enabled",
+                    "Whether to count your rolls (off deletes your stats; omit to view)",
+                )
+                .required(false),
+            ),
+        )
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing stats subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid stats subcommand"));
+    };
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let user_id = command.user.id.get() as i64;
+
+    if subcommand.name == "tracking" {
+        let enabled = options
+            .iter()
+            .find(|opt| opt.name == "enabled")
+            .and_then(|opt| opt.value.as_bool());
+        let content = match enabled {
+            Some(true) => {
+                db.set_stats_hidden(user_id, false).await?;
+                "📊 Your public rolls in servers are counted in `/stats`.".to_string()
+            }
+            Some(false) => {
+                db.set_stats_hidden(user_id, true).await?;
+                "📊 Your rolls are no longer counted, and your stats were deleted in every server."
+                    .to_string()
+            }
+            None if db.stats_hidden(user_id).await? => {
+                "📊 Your rolls aren't counted. Turn it back on with `/stats tracking enabled:true`."
+                    .to_string()
+            }
+            None => "📊 Your public rolls in servers are counted in `/stats`. \
+                     Stop and delete them with `/stats tracking enabled:false`."
+                .to_string(),
+        };
+        return Ok(CommandResponse::private(content));
+    }
+
+    let Some(guild_id) = command.guild_id.map(|id| id.get() as i64) else {
+        return Ok(CommandResponse::private(
+            "❌ Roll stats are kept per server; use this in a server.".to_string(),
+        ));
+    };
+
+    let content = match subcommand.name.as_str() {
+        "me" => {
+            if db.stats_hidden(user_id).await? {
+                "📊 Your rolls aren't counted. Turn it back on with `/stats tracking enabled:true`."
+                    .to_string()
+            } else {
+                let stats = db.get_user_roll_stats(guild_id, user_id).await?;
+                let rank = db.roll_stats_rank(guild_id, user_id).await?;
+                format_user_stats(stats.as_ref(), rank)
+            }
+        }
+        "server" => {
+            let (leaders, rollers) = db.get_guild_roll_stats(guild_id, LEADERBOARD_LIMIT).await?;
+            format_leaderboard(&leaders, rollers)
+        }
+        other => return Err(anyhow!("Unknown stats subcommand: {}", other)),
+    };
+
+    Ok(CommandResponse::private(content))
+}
+
+/// The stats row for a roll, or `None` when it isn't counted: rolls in DMs,
+/// private rolls and secret `gm` rolls
+pub fn stats_entry(
+    guild_id: Option<i64>,
+    user_id: i64,
+    expression: &str,
+    results: &[RollResult],
+) -> Option<RollStatsEntry> {
+    let guild_id = guild_id?;
+    if results.is_empty() || results.iter().any(|r| r.private || r.gm) {
+        return None;
+    }
+
+    Some(RollStatsEntry {
+        guild_id,
+        user_id,
+        total: results
+            .iter()
+            .map(|r| i64::from(dice::calculate_result_value(r)))
+            .sum(),
+        outcome: streak::roll_outcome(expression, results),
+    })
+}
+
+/// Count a roll towards the user's stats in the server.  The write is
+/// queued; users who turned tracking off are skipped by the database.
+pub async fn record_roll(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    expression: &str,
+    results: &[RollResult],
+) {
+    let guild_id = origin.guild_id.map(|id| id.get() as i64);
+    if let Some(entry) = stats_entry(guild_id, origin.user.id.get() as i64, expression, results) {
+        write_queue::enqueue(ctx, DbWrite::RollStats(entry)).await;
+    }
+}
+
+/// `/stats me` reply
+pub fn format_user_stats(stats: Option<&UserRollStats>, rank: Option<i64>) -> String {
+    let Some(stats) = stats.filter(|stats| stats.rolls > 0) else {
+        return "📊 You haven't rolled in this server yet.".to_string();
+    };
+
+    let mut content = format!("📊 **Your rolls in this server**\n{}", stats_line(stats));
+    if let Some(rank) = rank {
+        content.push_str(&format!("\n🏆 #{rank} on the server leaderboard"));
+    }
+    content
+}
+
+/// `/stats server` reply: the leaders, and how many rollers there are in all
+pub fn format_leaderboard(leaders: &[UserRollStats], rollers: i64) -> String {
+    if leaders.is_empty() {
+        return "📊 Nobody has rolled in this server yet.".to_string();
+    }
+
+    let mut content = "🏆 **Roll leaderboard**".to_string();
+    for (place, stats) in leaders.iter().enumerate() {
+        content.push_str(&format!(
+            "\n{}. <@{}> — {}",
+            place + 1,
+            stats.user_id,
+            stats_line(stats)
+        ));
+    }
+    if rollers > leaders.len() as i64 {
+        content.push_str(&format!("\n-# Top {} of {rollers} rollers", leaders.len()));
+    }
+    content
+}
+
+// "**42 rolls** · 🌟 3 nat 20s · 💀 1 nat 1 · avg 10.5"
+fn stats_line(stats: &UserRollStats) -> String {
+    let plural = |count: i64, one: &str, many: &str| {
+        format!("{count} {}", if count == 1 { one } else { many })
+    };
+    format!(
+        "**{}** · 🌟 {} · 💀 {} · avg {:.1}",
+        plural(stats.rolls, "roll", "rolls"),
+        plural(stats.nat20s, "nat 20", "nat 20s"),
+        plural(stats.nat1s, "nat 1", "nat 1s"),
+        stats.average()
+    )
+}
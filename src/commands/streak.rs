@@ -0,0 +1,249 @@
+//! Success and failure streaks, shown as playful titles in fun mode.
+//!
+//! Each check a user rolls is classified by [`roll_outcome`] and stored with
+//! its `roll_history` row, so streaks are computed from history rather than
+//! kept in memory:
+//!
+//! | Roll                                  | Outcome                              |
+//! |---------------------------------------|--------------------------------------|
+//! | Pool or target roll (`4cod`, `1d20 t15`) | success with 1+ successes, else failure |
+//! | Forged in the Dark (`fitd3`)          | failure on a 1-3, else success       |
+//! | Ironsworn action (`iron +2`)          | hit or miss                          |
+//! | d20 check (`1d20+5`, `+d20`)          | natural 20 or 1, else neutral        |
+//!
+//! A streak counts the user's consecutive successes or failures in the same
+//! server (a neutral d20 check ends both); rolls that aren't checks, such as
+//! damage, don't break it.  When a server turns on `/settings fun`, `/roll`
+//! appends a title from [`fun_titles`] once a streak reaches
+//! [`MIN_STREAK`], or on a second natural 20 or 1 in a day.
+//!
+//! Streaks only see what history keeps: with `/privacy history:off` nothing
+//! is recorded, so every roll starts a new streak.
+
+use crate::DatabaseContainer;
+use crate::commands::RollOrigin;
+use crate::database::{GuildSettings, RollOutcome};
+use crate::dice::{Modifier, RollResult, parser};
+use serenity::prelude::Context;
+use tracing::warn;
+
+/// Consecutive successes or failures that earn a title
+pub const MIN_STREAK: usize = 3;
+
+// Past checks read from history; longer streaks show as this many
+const MAX_STREAK_LOOKBACK: i64 = 20;
+
+/// A roll's streak, counting the roll itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Streak {
+    pub outcome: RollOutcome,
+    /// Consecutive checks on the same side (success or failure); 0 for a
+    /// neutral d20 check
+    pub length: usize,
+    /// Natural 20s (or 1s) rolled today when this roll is one
+    pub naturals_today: i64,
+}
+
+impl Streak {
+    /// The streak of a roll with `outcome`, given the user's earlier
+    /// outcomes (newest first)
+    pub fn new(outcome: RollOutcome, previous: &[RollOutcome], naturals_today: i64) -> Self {
+        let same_side = |other: &RollOutcome| {
+            (outcome.is_success() && other.is_success())
+                || (outcome.is_failure() && other.is_failure())
+        };
+        let length = if outcome == RollOutcome::Neutral {
+            0
+        } else {
+            1 + previous.iter().take_while(|other| same_side(other)).count()
+        };
+        Self {
+            outcome,
+            length,
+            naturals_today,
+        }
+    }
+}
+
+/// Classify a roll for streaks, or `None` if it isn't a check.  Only single
+/// rolls count; roll sets and `;`-separated rolls are skipped.
+pub fn roll_outcome(expression: &str, results: &[RollResult]) -> Option<RollOutcome> {
+    let [result] = results else {
+        return None;
+    };
+    // Labeled dice (coins, `d{yes,no}`) aren't checks
+    if result.face_labels.is_some() {
+        return None;
+    }
+
+    if let Some(outcome) = result.fitd_outcome.as_deref() {
+        return match outcome {
+            "FAILURE" | "PANIC" => Some(RollOutcome::Failure),
+            "UNKNOWN" => None,
+            _ => Some(RollOutcome::Success),
+        };
+    }
+    if let Some(outcome) = result.ironsworn_outcome.as_deref() {
+        return match outcome {
+            "STRONG HIT" | "WEAK HIT" => Some(RollOutcome::Success),
+            "MISS" => Some(RollOutcome::Failure),
+            _ => None, // Oracle answers
+        };
+    }
+    if let Some(successes) = result.successes {
+        return Some(if successes > 0 {
+            RollOutcome::Success
+        } else {
+            RollOutcome::Failure
+        });
+    }
+
+    // A d20 check keeps one d20, which comes first in the kept dice
+    let rolls = parser::parse_dice_string(expression).ok()?;
+    let [roll] = rolls.as_slice() else {
+        return None;
+    };
+    // Dragonbane rolls under the skill: a Dragon (1) succeeds and a Demon (20) fails
+    if let Some(skill) = roll.modifiers.iter().find_map(|m| match m {
+        Modifier::Dragonbane(skill, _, _) => Some(*skill),
+        _ => None,
+    }) {
+        let kept = *result.kept_rolls.first()?;
+        return Some(match (kept, skill) {
+            (1, _) => RollOutcome::Success,
+            (20, _) => RollOutcome::Failure,
+            (kept, Some(skill)) if kept <= skill as i32 => RollOutcome::Success,
+            (_, Some(_)) => RollOutcome::Failure,
+            (_, None) => RollOutcome::Neutral,
+        });
+    }
+    let kept_d20s = (roll.count as usize).checked_sub(result.dropped_rolls.len());
+    if roll.sides != 20 || kept_d20s != Some(1) {
+        return None;
+    }
+    Some(match result.kept_rolls.first()? {
+        20 => RollOutcome::NaturalTwenty,
+        1 => RollOutcome::NaturalOne,
+        _ => RollOutcome::Neutral,
+    })
+}
+
+/// Titles a streak earns, e.g. `💀 3rd nat 1 today — Cursed`
+pub fn fun_titles(streak: &Streak) -> Vec<String> {
+    let mut titles = Vec::new();
+
+    if streak.naturals_today >= 2 {
+        let nth = ordinal(streak.naturals_today);
+        match streak.outcome {
+            RollOutcome::NaturalTwenty => {
+                let title = match streak.naturals_today {
+                    2 => "Blessed",
+                    3 => "Favored by Fate",
+                    _ => "Dice Whisperer",
+                };
+                titles.push(format!("🌟 {nth} nat 20 today — {title}"));
+            }
+            RollOutcome::NaturalOne => {
+                let title = match streak.naturals_today {
+                    2 => "Unlucky",
+                    3 => "Cursed",
+                    _ => "Doomed",
+                };
+                titles.push(format!("💀 {nth} nat 1 today — {title}"));
+            }
+            _ => {}
+        }
+    }
+
+    if streak.length >= MIN_STREAK {
+        let length = streak.length;
+        if streak.outcome.is_success() {
+            let title = match length {
+                ..5 => "On a Roll",
+                5..10 => "Unstoppable",
+                _ => "Legendary",
+            };
+            titles.push(format!("🔥 {length} successes in a row — {title}"));
+        } else {
+            let title = match length {
+                ..5 => "Jinxed",
+                5..10 => "Hexed",
+                _ => "Blame the Dice",
+            };
+            titles.push(format!("🌧️ {length} failures in a row — {title}"));
+        }
+    }
+
+    titles
+}
+
+/// The lines appended to a roll for its titles, or nothing
+pub fn format_titles(titles: &[String]) -> String {
+    titles.iter().map(|title| format!("\n*{title}*")).collect()
+}
+
+/// Look up a roll's streak in history and return its fun-mode titles.
+///
+/// Call before the roll is recorded: the streak counts it on top of the
+/// history.  Returns nothing unless the server turned on fun mode; failures
+/// are logged and never affect the roll itself.
+pub async fn process_roll(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    settings: Option<&GuildSettings>,
+    expression: &str,
+    results: &[RollResult],
+) -> String {
+    if !settings.is_some_and(|settings| settings.fun_mode) {
+        return String::new();
+    }
+    let Some(outcome) = roll_outcome(expression, results) else {
+        return String::new();
+    };
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return String::new();
+    };
+
+    let guild_id = origin.guild_id.map(|id| id.get() as i64);
+    let user_id = origin.user.id.get() as i64;
+    let previous = match db
+        .get_recent_outcomes(guild_id, user_id, MAX_STREAK_LOOKBACK)
+        .await
+    {
+        Ok(previous) => previous,
+        Err(e) => {
+            warn!("Failed to read roll outcomes for {}: {}", user_id, e);
+            return String::new();
+        }
+    };
+    let naturals_today = match outcome {
+        RollOutcome::NaturalTwenty | RollOutcome::NaturalOne => {
+            match db.count_outcomes_today(guild_id, user_id, outcome).await {
+                Ok(count) => count + 1,
+                Err(e) => {
+                    warn!("Failed to count today's rolls for {}: {}", user_id, e);
+                    return String::new();
+                }
+            }
+        }
+        _ => 0,
+    };
+
+    format_titles(&fun_titles(&Streak::new(
+        outcome,
+        &previous,
+        naturals_today,
+    )))
+}
+
+// 1st, 2nd, 3rd, 4th, …, 11th, 12th, 13th, …, 21st
+fn ordinal(n: i64) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
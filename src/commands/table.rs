@@ -0,0 +1,297 @@
+//! `/table` slash-command handler for server random tables.
+//!
+//! | Subcommand | Effect                                                       |
+//! |------------|--------------------------------------------------------------|
+//! | `create`   | Save (or replace) a table, e.g. `1-50: Miss; 51-100: Hit`    |
+//! | `roll`     | Roll on a table                                              |
+//! | `list`     | Show the server's tables                                     |
+//! | `delete`   | Delete a table                                               |
+//!
+//! Entry syntax and inline `[dice]` are described in [`crate::tables`].
+//! Anyone can roll on a server's tables unless `/settings restrict` limits
+//! table rolls to a role; creating and deleting them needs the
+//! **Administrator** permission or the GM role chosen in `/setup`.
+//!
+//! `/settings crit-table` picks a table to roll under every natural 20 or
+//! natural 1 of a d20 check; [`critical_roll`] adds its result to the roll.
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::commands::session::is_game_master;
+use crate::commands::tally::is_valid_tally_name;
+use crate::commands::{settings, streak};
+use crate::database::{GatedFeature, GuildSettings, RollOutcome};
+use crate::dice::{RollResult, rng};
+use crate::tables::{MAX_GUILD_TABLES, RandomTable, TableRoll};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandDataOptionValue, CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+pub fn register() -> CreateCommand {
+    let name_option = || {
+        CreateCommandOption::new(CommandOptionType::String, "name", "Table name (e.g. crits)")
+            .required(true)
+            .max_length(32)
+    };
+
+    CreateCommand::new("table")
+        .description("Roll on this server's random tables")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "create",
+                "Save a table (admins or GM role)",
+            )
+            .add_sub_option(name_option())
+            .add_sub_option(
+                CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "entries",
+                    "Entries separated by ; (e.g. 1-50: Miss; 51-90: Hit for [1d6]; 91-100: Crit)",
+                )
+                .required(true)
+                .max_length(4000),
+            ),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "roll", "Roll on a table")
+                .add_sub_option(name_option()),
+        )
+        .add_option(CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "Show this server's tables",
+        ))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::SubCommand,
+                "delete",
+                "Delete a table (admins or GM role)",
+            )
+            .add_sub_option(name_option()),
+        )
+        .dm_permission(false)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ This command can only be used in servers.".to_string(),
+        ));
+    };
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing table subcommand"));
+    };
+    let CommandDataOptionValue::SubCommand(options) = &subcommand.value else {
+        return Err(anyhow!("Invalid table subcommand"));
+    };
+    let option_str = |name: &str| {
+        options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| opt.value.as_str())
+            .map(str::trim)
+    };
+    let name = option_str("name").unwrap_or_default().to_lowercase();
+
+    if subcommand.name != "list" && !is_valid_tally_name(&name) {
+        return Ok(CommandResponse::private(
+            "❌ Table names must be 1-32 letters, numbers, `-` or `_`.".to_string(),
+        ));
+    }
+    if matches!(subcommand.name.as_str(), "create" | "delete")
+        && !is_game_master(ctx, command).await
+    {
+        return Ok(CommandResponse::private(
+            "❌ You need the 'Administrator' permission or the server's GM role to do this."
+                .to_string(),
+        ));
+    }
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let guild_id = guild_id.get() as i64;
+    let tables = db.get_guild_tables(guild_id).await?;
+
+    match subcommand.name.as_str() {
+        "create" => {
+            if !tables.contains_key(&name) && tables.len() >= MAX_GUILD_TABLES {
+                return Ok(CommandResponse::private(format!(
+                    "❌ This server already has {MAX_GUILD_TABLES} tables. Delete one first."
+                )));
+            }
+            let table = match RandomTable::parse(&name, option_str("entries").unwrap_or_default()) {
+                Ok(table) => table,
+                Err(e) => return Ok(CommandResponse::private(format!("❌ {e}"))),
+            };
+            db.set_guild_table(guild_id, &table.name, &table.definition())
+                .await?;
+            info!("Saved table {} for guild {}", table.name, guild_id);
+            Ok(CommandResponse::private(format!(
+                "✅ Saved table **{}** (d{}, {} entries). Roll it with `/table roll name:{}`.",
+                table.name,
+                table.die(),
+                table.entries.len(),
+                table.name
+            )))
+        }
+        "roll" => {
+            let denial = db.get_guild_settings(guild_id).await?.and_then(|settings| {
+                settings::check_member_feature(
+                    &settings,
+                    GatedFeature::Tables,
+                    command.member.as_deref(),
+                )
+            });
+            if let Some(denial) = denial {
+                return Ok(CommandResponse::private(format!("❌ {denial}")));
+            }
+            let Some(definition) = tables.get(&name) else {
+                return Ok(CommandResponse::private(not_found_message(&name)));
+            };
+            let table = match RandomTable::parse(&name, definition) {
+                Ok(table) => table,
+                Err(e) => {
+                    warn!(
+                        "Stored table {} in guild {} is invalid: {}",
+                        name, guild_id, e
+                    );
+                    return Ok(CommandResponse::private(format!(
+                        "❌ Table **{name}** can't be rolled: {e}"
+                    )));
+                }
+            };
+            match table.roll(&mut rng::get_dice_rng()) {
+                Ok(roll) => Ok(CommandResponse::public(format_table_roll(&table, &roll))),
+                Err(e) => Ok(CommandResponse::private(format!("❌ {e}"))),
+            }
+        }
+        "list" => Ok(CommandResponse::private(format_table_list(&tables))),
+        "delete" => {
+            if db.remove_guild_table(guild_id, &name).await? {
+                Ok(CommandResponse::private(format!(
+                    "🗑️ Deleted table **{name}**."
+                )))
+            } else {
+                Ok(CommandResponse::private(not_found_message(&name)))
+            }
+        }
+        other => Err(anyhow!("Unknown table subcommand: {}", other)),
+    }
+}
+
+/// A roll on a table, e.g. `🎲 **crits** (d100: 73) → Bleeding, **3** damage a turn`
+pub fn format_table_roll(table: &RandomTable, roll: &TableRoll) -> String {
+    format!(
+        "🎲 **{}** (d{}: {}) → {}",
+        table.name,
+        table.die(),
+        roll.roll,
+        roll.text
+    )
+}
+
+/// The `/table list` message
+pub fn format_table_list(tables: &BTreeMap<String, String>) -> String {
+    if tables.is_empty() {
+        return "📜 This server has no tables yet. Create one with `/table create`.".to_string();
+    }
+    let mut output = format!("📜 **Server tables** ({}/{MAX_GUILD_TABLES})", tables.len());
+    for (name, definition) in tables {
+        match RandomTable::parse(name, definition) {
+            Ok(table) => output.push_str(&format!(
+                "\n`{name}` — d{}, {} entries",
+                table.die(),
+                table.entries.len()
+            )),
+            Err(_) => output.push_str(&format!("\n`{name}` — invalid")),
+        }
+    }
+    output
+}
+
+/// The table a roll's outcome calls for: the server's `crit_table` for a
+/// natural 20 and `fumble_table` for a natural 1
+pub fn critical_table(settings: &GuildSettings, outcome: RollOutcome) -> Option<&str> {
+    match outcome {
+        RollOutcome::NaturalTwenty => settings.crit_table.as_deref(),
+        RollOutcome::NaturalOne => settings.fumble_table.as_deref(),
+        _ => None,
+    }
+}
+
+/// The line added under a natural 20 or 1, e.g. `💥 Natural 20! 🎲 **crits** (d100: 73) → Bleeding`
+pub fn format_critical_roll(outcome: RollOutcome, table: &RandomTable, roll: &TableRoll) -> String {
+    let heading = match outcome {
+        RollOutcome::NaturalOne => "💀 Natural 1!",
+        _ => "💥 Natural 20!",
+    };
+    format!("\n{heading} {}", format_table_roll(table, roll))
+}
+
+/// Roll on the server's critical table when a d20 check comes up 20 or 1,
+/// returning the line to add under the roll, or an empty string.  Missing or
+/// broken tables are logged and skipped.
+pub async fn critical_roll(
+    ctx: &Context,
+    settings: Option<&GuildSettings>,
+    expression: &str,
+    results: &[RollResult],
+) -> String {
+    let Some(settings) = settings else {
+        return String::new();
+    };
+    let Some(outcome) = streak::roll_outcome(expression, results) else {
+        return String::new();
+    };
+    let Some(name) = critical_table(settings, outcome) else {
+        return String::new();
+    };
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return String::new();
+    };
+
+    let definition = match db.get_guild_tables(settings.guild_id).await {
+        Ok(mut tables) => tables.remove(name),
+        Err(e) => {
+            warn!(
+                "Failed to load tables for guild {}: {}",
+                settings.guild_id, e
+            );
+            return String::new();
+        }
+    };
+    let Some(definition) = definition else {
+        warn!(
+            "Critical table {} of guild {} no longer exists",
+            name, settings.guild_id
+        );
+        return String::new();
+    };
+    let rolled = RandomTable::parse(name, &definition)
+        .and_then(|table| Ok((table.roll(&mut rng::get_dice_rng())?, table)));
+    match rolled {
+        Ok((roll, table)) => format_critical_roll(outcome, &table, &roll),
+        Err(e) => {
+            warn!(
+                "Critical table {} of guild {} can't be rolled: {}",
+                name, settings.guild_id, e
+            );
+            String::new()
+        }
+    }
+}
+
+fn not_found_message(name: &str) -> String {
+    format!("❌ This server has no table named `{name}`. See `/table list`.")
+}
@@ -0,0 +1,412 @@
+//! `/tally` slash-command handler and `tally:<name>` roll integration.
+//!
+//! A tally is a named running total owned by a channel (e.g. `PartyDamage`).
+//! Rolls flagged with `tally:<name>` add their total (or successes) to it, and
+//! the bot keeps a pinned message in the channel showing the current sum.
+//!
+//! | Subcommand | Effect                                          |
+//! |------------|-------------------------------------------------|
+//! | `create`   | Create the tally and post its pinned message    |
+//! | `show`     | Show the current total                          |
+//! | `reset`    | Set the total back to zero                      |
+//! | `delete`   | Remove the tally (the pinned message is kept)   |
+//!
+//! Tally state lives in the `channel_tallies` table (see `database.rs`).
+//!
+//! The pinned message adapts to what the bot may do in the channel (see
+//! `outbound::ChannelAccess`): without Send Messages the roll output says the
+//! message was skipped, without Manage Messages it is posted unpinned, and a
+//! post refused by slowmode is sent again once the slowmode has passed.
+
+use crate::DatabaseContainer;
+use crate::commands::{CommandResponse, RollOrigin};
+use crate::database::{Database, Tally};
+use crate::dice::{self, RollResult};
+use crate::outbound::{self, ChannelAccess};
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::{
+    all::{
+        ChannelId, CommandDataOptionValue, CommandInteraction, CommandOptionType, Message,
+        MessageId, Permissions,
+    },
+    builder::{CreateCommand, CreateCommandOption, CreateMessage, EditMessage},
+    http::Http,
+    prelude::Context,
+};
+use std::sync::Arc;
+use tracing::warn;
+
+static TALLY_FLAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:^|\s)tally:([a-z0-9_-]+)(?:\s|$)")
+        .expect("Failed to compile TALLY_FLAG_REGEX")
+});
+
+static TALLY_NAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9_-]{1,32}$").expect("Failed to compile TALLY_NAME_REGEX"));
+
+pub fn register() -> CreateCommand {
+    let name_option = || {
+        CreateCommandOption::new(
+            CommandOptionType::String,
+            "name",
+            "Tally name (e.g. PartyDamage)",
+        )
+        .required(true)
+        .max_length(32)
+    };
+
+    CreateCommand::new("tally")
+        .description("Keep a running total of rolls in this channel")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "create", "Create a tally")
+                .add_sub_option(name_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "show", "Show a tally")
+                .add_sub_option(name_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "reset", "Reset a tally to 0")
+                .add_sub_option(name_option()),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::SubCommand, "delete", "Delete a tally")
+                .add_sub_option(name_option()),
+        )
+}
+
+/// Remove a `tally:<name>` flag from a roll expression.
+///
+/// Only the part before a `!` comment is searched, so comments can mention
+/// tallies freely.  Returns the tally name (if any) and the remaining expression.
+pub fn extract_tally_flag(expr: &str) -> (Option<String>, String) {
+    extract_named_flag(expr, &TALLY_FLAG_REGEX)
+}
+
+// Shared by the `tally:` and `clock:` roll flags: remove the first match of
+// `flag_regex` (capturing the name) from the roll part of an expression
+pub(crate) fn extract_named_flag(expr: &str, flag_regex: &Regex) -> (Option<String>, String) {
+    let (roll_part, comment_part) = match expr.find('!') {
+        Some(pos) => expr.split_at(pos),
+        None => (expr, ""),
+    };
+
+    match flag_regex.captures(roll_part) {
+        Some(captures) => {
+            let name = captures[1].to_string();
+            let whole = captures.get(0).map(|m| m.range()).unwrap_or(0..0);
+            let remaining: Vec<&str> = roll_part[..whole.start]
+                .split_whitespace()
+                .chain(roll_part[whole.end..].split_whitespace())
+                .chain(std::iter::once(comment_part.trim()))
+                .filter(|part| !part.is_empty())
+                .collect();
+            (Some(name), remaining.join(" "))
+        }
+        None => (None, expr.to_string()),
+    }
+}
+
+pub fn is_valid_tally_name(name: &str) -> bool {
+    TALLY_NAME_REGEX.is_match(name)
+}
+
+/// Text of the pinned message for a tally
+pub fn format_tally_message(tally: &Tally) -> String {
+    format!("📊 **{}** running tally: **{}**", tally.name, tally.total)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(subcommand) = command.data.options.first() else {
+        return Err(anyhow!("Missing tally subcommand"));
+    };
+
+    let name = match &subcommand.value {
+        CommandDataOptionValue::SubCommand(options) => options
+            .iter()
+            .find(|opt| opt.name == "name")
+            .and_then(|opt| opt.value.as_str())
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        _ => return Err(anyhow!("Invalid tally subcommand")),
+    };
+
+    if !is_valid_tally_name(&name) {
+        return Ok(CommandResponse::private(
+            "❌ Tally names must be 1-32 letters, numbers, `-` or `_`.".to_string(),
+        ));
+    }
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let channel_id = command.channel_id.get() as i64;
+
+    match subcommand.name.as_str() {
+        "create" => {
+            let guild_id = command.guild_id.map(|id| id.get() as i64);
+            if !db.create_tally(guild_id, channel_id, &name).await? {
+                return Ok(CommandResponse::private(format!(
+                    "❌ A tally named **{name}** already exists in this channel."
+                )));
+            }
+
+            let mut created = format!(
+                "📊 Created tally **{name}**. Add rolls to it with `/roll tally:{name} <dice>`"
+            );
+            if let Some(tally) = db.get_tally(channel_id, &name).await?
+                && let Some(notice) = queue_tally_message(
+                    ctx,
+                    db.clone(),
+                    command.channel_id,
+                    command.app_permissions,
+                    tally,
+                )
+                .await
+            {
+                created.push_str(&notice);
+            }
+
+            Ok(CommandResponse::public(created))
+        }
+        "show" => match db.get_tally(channel_id, &name).await? {
+            Some(tally) => Ok(CommandResponse::public(format_tally_message(&tally))),
+            None => Ok(CommandResponse::private(not_found_message(&name))),
+        },
+        "reset" => match db.reset_tally(channel_id, &name).await? {
+            Some(tally) => {
+                let mut reset_message = format!("📊 Reset tally **{}** to **0**", tally.name);
+                if let Some(notice) = queue_tally_message(
+                    ctx,
+                    db.clone(),
+                    command.channel_id,
+                    command.app_permissions,
+                    tally,
+                )
+                .await
+                {
+                    reset_message.push_str(&notice);
+                }
+                Ok(CommandResponse::public(reset_message))
+            }
+            None => Ok(CommandResponse::private(not_found_message(&name))),
+        },
+        "delete" => {
+            if db.delete_tally(channel_id, &name).await? {
+                Ok(CommandResponse::public(format!(
+                    "🗑️ Deleted tally **{name}**"
+                )))
+            } else {
+                Ok(CommandResponse::private(not_found_message(&name)))
+            }
+        }
+        other => Err(anyhow!("Unknown tally subcommand: {}", other)),
+    }
+}
+
+/// Add a roll's value to a tally and queue an update of its pinned message.
+///
+/// Returns the line appended to the roll output; failures are reported in
+/// that line rather than failing the roll itself.
+pub async fn record_roll(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    name: &str,
+    results: &[RollResult],
+) -> String {
+    let Some(amount) = roll_amount(results) else {
+        return String::new();
+    };
+    update_tally(ctx, origin, name, amount)
+        .await
+        .unwrap_or_else(|| format!("\n📊 {}", not_found_message(name)))
+}
+
+/// What a roll adds to a tally: its total (or successes), or `None` for a
+/// secret `gm` roll, whose result must not show on the pinned message
+pub fn roll_amount(results: &[RollResult]) -> Option<i32> {
+    if results.iter().any(|r| r.gm) {
+        return None;
+    }
+    Some(results.iter().map(dice::calculate_result_value).sum())
+}
+
+/// [`record_roll`] for `/keyword` rules, which apply in every channel: a
+/// channel without the tally is skipped instead of reported
+pub async fn record_keyword_roll(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    name: &str,
+    amount: i32,
+) -> String {
+    update_tally(ctx, origin, name, amount)
+        .await
+        .unwrap_or_default()
+}
+
+// The roll output line, or None if the channel has no tally by that name
+async fn update_tally(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    name: &str,
+    amount: i32,
+) -> Option<String> {
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return Some("\n📊 Tally unavailable: database not connected".to_string());
+    };
+
+    match db
+        .add_to_tally(origin.channel_id.get() as i64, name, i64::from(amount))
+        .await
+    {
+        Ok(Some(tally)) => {
+            let mut line = format!(
+                "\n📊 **{}** tally: **{}** ({:+})",
+                tally.name, tally.total, amount
+            );
+            if let Some(notice) =
+                queue_tally_message(ctx, db, origin.channel_id, origin.app_permissions, tally).await
+            {
+                line.push_str(&notice);
+            }
+            Some(line)
+        }
+        Ok(None) => None,
+        Err(e) => {
+            warn!("Failed to update tally {}: {}", name, e);
+            Some("\n📊 Tally update failed".to_string())
+        }
+    }
+}
+
+fn not_found_message(name: &str) -> String {
+    format!("No tally named **{name}** in this channel. Create it with `/tally create name:{name}`")
+}
+
+// Pinned message updates are non-critical, so they go through the outbound
+// queue instead of delaying the roll response.  Returns a notice for the
+// response when the bot can't post in the channel at all.
+async fn queue_tally_message(
+    ctx: &Context,
+    db: Arc<Database>,
+    channel_id: ChannelId,
+    permissions: Option<Permissions>,
+    tally: Tally,
+) -> Option<String> {
+    let access = ChannelAccess::from_permissions(permissions);
+    // Editing our own message needs no Send Messages, posting does
+    let notice = (!access.send_messages && tally.message_id.is_none()).then(|| {
+        " (I can't post the tally message here: I need the Send Messages permission)".to_string()
+    });
+
+    let label = format!("tally message {}", tally.name);
+    outbound::enqueue_job(
+        ctx,
+        label,
+        refresh_tally_message(ctx.http.clone(), db, channel_id, tally, access),
+    )
+    .await;
+    notice
+}
+
+// Post a new tally message.  A post refused by slowmode is tried once more
+// when the slowmode has passed, in the background so the queue keeps moving.
+async fn post_tally_message(
+    http: Arc<Http>,
+    db: Arc<Database>,
+    channel_id: ChannelId,
+    tally: Tally,
+    access: ChannelAccess,
+) {
+    if !access.send_messages {
+        return;
+    }
+
+    match send_tally_message(&http, channel_id, &tally).await {
+        Ok(message) => store_tally_message(&http, &db, &tally, &message, access).await,
+        Err(e) if outbound::is_slowmode_error(&e) => {
+            let delay = outbound::slowmode_delay(&http, channel_id).await;
+            warn!(
+                "Tally message for {} held by slowmode, posting in {}s",
+                tally.name,
+                delay.as_secs()
+            );
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                match send_tally_message(&http, channel_id, &tally).await {
+                    Ok(message) => store_tally_message(&http, &db, &tally, &message, access).await,
+                    Err(e) => warn!("Failed to post tally message for {}: {}", tally.name, e),
+                }
+            });
+        }
+        Err(e) => warn!("Failed to post tally message for {}: {}", tally.name, e),
+    }
+}
+
+async fn send_tally_message(
+    http: &Http,
+    channel_id: ChannelId,
+    tally: &Tally,
+) -> serenity::Result<Message> {
+    outbound::send_with_retry("tally message post", || {
+        channel_id.send_message(
+            http,
+            CreateMessage::new().content(format_tally_message(tally)),
+        )
+    })
+    .await
+}
+
+// Pin a posted tally message (when allowed) and remember it for later edits
+async fn store_tally_message(
+    http: &Http,
+    db: &Database,
+    tally: &Tally,
+    message: &Message,
+    access: ChannelAccess,
+) {
+    if access.pin_messages
+        && let Err(e) = outbound::send_with_retry("tally message pin", || message.pin(http)).await
+    {
+        warn!("Failed to pin tally message for {}: {}", tally.name, e);
+    }
+
+    if let Err(e) = db
+        .set_tally_message(tally.channel_id, &tally.name, message.id.get() as i64)
+        .await
+    {
+        warn!("Failed to store tally message for {}: {}", tally.name, e);
+    }
+}
+
+// Edit the tally's pinned message, posting a fresh one if it was deleted
+async fn refresh_tally_message(
+    http: Arc<Http>,
+    db: Arc<Database>,
+    channel_id: ChannelId,
+    tally: Tally,
+    access: ChannelAccess,
+) {
+    if let Some(message_id) = tally.message_id {
+        let edited = outbound::send_with_retry("tally message edit", || {
+            channel_id.edit_message(
+                &http,
+                MessageId::new(message_id as u64),
+                EditMessage::new().content(format_tally_message(&tally)),
+            )
+        })
+        .await;
+        if edited.is_ok() {
+            return;
+        }
+    }
+
+    post_tally_message(http, db, channel_id, tally, access).await;
+}
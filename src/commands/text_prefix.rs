@@ -0,0 +1,88 @@
+//! Legacy `!roll` text commands.
+//!
+//! Before slash commands, Dice Maiden answered chat messages such as
+//! `!roll 2d6+3`.  Servers moving from the old bot can turn that back on with
+//! `/settings text-commands`, if the host runs the bot with
+//! `MESSAGE_COMMANDS=true`: reading message content needs Discord's
+//! privileged `MESSAGE_CONTENT` intent, so it is off unless asked for.
+//!
+//! | Message          | Same as          |
+//! |------------------|------------------|
+//! | `!roll 2d6+3`    | `/roll 2d6+3`    |
+//! | `!r 4d6 k3`      | `/r 4d6 k3`      |
+//! | `!roll`          | `/roll 1d6`      |
+//!
+//! The expression goes through [`roll::run_expression`], so aliases,
+//! macros, `/settings` and `/prefs` apply as they do to `/roll`.  The result
+//! is posted as a reply to the message; private results (help topics, a
+//! roll the server's settings refuse) are sent by DM.
+
+use crate::DatabaseContainer;
+use crate::commands::{CommandResponse, RollOrigin, roll};
+use crate::i18n::Language;
+use crate::locale;
+use serenity::{model::id::GuildId, prelude::Context};
+use std::env;
+use tracing::{error, warn};
+
+/// Prefixes a text command starts with; longest first, so `!roll` isn't
+/// read as `!r` followed by `oll`
+pub const TEXT_COMMANDS: [&str; 2] = ["!roll", "!r"];
+
+/// What a bare `!roll` rolls
+pub const DEFAULT_EXPRESSION: &str = "1d6";
+
+/// Whether the host turned on text commands (`MESSAGE_COMMANDS=true`),
+/// which also requests the `MESSAGE_CONTENT` intent
+pub fn intent_enabled() -> bool {
+    env::var("MESSAGE_COMMANDS").is_ok_and(|value| value.eq_ignore_ascii_case("true"))
+}
+
+/// The expression in a text command, or `None` if `content` isn't one.
+/// The prefix is case-insensitive and must be followed by a space or the end
+/// of the message; a bare prefix gives an empty expression.
+pub fn parse(content: &str) -> Option<&str> {
+    let content = content.trim_start();
+    TEXT_COMMANDS.iter().find_map(|prefix| {
+        let head = content.get(..prefix.len())?;
+        if !head.eq_ignore_ascii_case(prefix) {
+            return None;
+        }
+        let rest = &content[prefix.len()..];
+        (rest.is_empty() || rest.starts_with(char::is_whitespace)).then(|| rest.trim())
+    })
+}
+
+/// Whether the guild turned on text commands in `/settings`
+pub async fn enabled_in(ctx: &Context, guild_id: GuildId) -> bool {
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return false;
+    };
+    match db.get_guild_settings(guild_id.get() as i64).await {
+        Ok(settings) => settings.is_some_and(|settings| settings.text_commands),
+        Err(e) => {
+            warn!("Failed to load settings for guild {}: {}", guild_id, e);
+            false
+        }
+    }
+}
+
+/// Roll a text command's expression as `/roll` would
+pub async fn run(
+    ctx: &Context,
+    origin: &RollOrigin<'_>,
+    expression: &str,
+    language: Language,
+) -> CommandResponse {
+    let expression = if expression.is_empty() {
+        DEFAULT_EXPRESSION
+    } else {
+        expression
+    };
+    roll::run_expression(ctx, origin, expression, language)
+        .await
+        .unwrap_or_else(|e| {
+            error!("Error executing text command: {}", e);
+            CommandResponse::public(locale::messages(language).command_failed.to_string())
+        })
+}
@@ -0,0 +1,170 @@
+//! `/tips` slash-command handler and onboarding tips for new rollers.
+//!
+//! A user's first [`TIP_ROLLS`] rolls in each server end with a one-line tip
+//! about something the roll didn't use:
+//!
+//! ```text
+//! -# 💡 Tip: add `! reason` to label your roll, e.g. `/roll 1d20+5 ! Attack` · `/tips` to hide
+//! ```
+//!
+//! Each roll moves on to the next tip in [`TIPS`], starting from a place
+//! picked by the user's ID so that a table of new players doesn't see the
+//! same tip at once; tips whose feature the roll already uses are skipped.
+//! Rolls are counted in the `roll_tips` table and stop being counted after
+//! the last tip.  `/tips enabled:false` hides tips in every server
+//! (`tip_opt_outs`) and `/tips enabled:true` brings them back.  Rolls in DMs
+//! never show tips.
+
+use crate::DatabaseContainer;
+use crate::commands::{CommandResponse, RollOrigin};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use tracing::warn;
+
+/// Rolls in a server that end with a tip
+pub const TIP_ROLLS: i64 = 5;
+
+/// An onboarding tip, shown when `applies` accepts the rolled expression
+#[derive(Debug, Clone, Copy)]
+pub struct Tip {
+    pub text: &'static str,
+    pub applies: fn(&str) -> bool,
+}
+
+/// Tips in rotation order
+pub const TIPS: [Tip; 8] = [
+    Tip {
+        text: "add `! reason` to label your roll, e.g. `/roll 1d20+5 ! Attack`",
+        applies: |expr| !expr.contains('!'),
+    },
+    Tip {
+        text: "start with `p` to roll privately, e.g. `/roll p 1d20`",
+        applies: |expr| !has_flag(expr, "p"),
+    },
+    Tip {
+        text: "`/roll 4d6 k3` keeps the 3 highest dice",
+        applies: |expr| !expr.contains(" k") && !expr.contains(" d"),
+    },
+    Tip {
+        text: "`/roll 6 4d6 k3` rolls six ability scores at once",
+        applies: |_| true,
+    },
+    Tip {
+        text: "`/roll last` rolls your previous roll again",
+        applies: |_| true,
+    },
+    Tip {
+        text: "the 🎲 Reroll button rolls the same dice again",
+        applies: |_| true,
+    },
+    Tip {
+        text: "`/macro add` saves an expression you roll often",
+        applies: |_| true,
+    },
+    Tip {
+        text: "`/roll help` lists every modifier, and `/roll help system` the game systems",
+        applies: |_| true,
+    },
+];
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("tips")
+        .description("Turn the tips shown under your first rolls in a server on or off")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "enabled",
+                "Whether to show tips (omit to view)",
+            )
+            .required(false),
+        )
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let user_id = command.user.id.get() as i64;
+
+    let enabled = command
+        .data
+        .options
+        .iter()
+        .find(|opt| opt.name == "enabled")
+        .and_then(|opt| opt.value.as_bool());
+    let content = match enabled {
+        Some(enabled) => {
+            db.set_tips_hidden(user_id, !enabled).await?;
+            if enabled {
+                format!("💡 Tips are on: your first {TIP_ROLLS} rolls in each server end with one.")
+            } else {
+                "💡 Tips are off in every server.".to_string()
+            }
+        }
+        None if db.tips_hidden(user_id).await? => {
+            "💡 Tips are off. Turn them back on with `/tips enabled:true`.".to_string()
+        }
+        None => format!(
+            "💡 Tips are on: your first {TIP_ROLLS} rolls in each server end with one. \
+             Hide them with `/tips enabled:false`."
+        ),
+    };
+
+    Ok(CommandResponse::private(content))
+}
+
+/// The tip for a user's roll number `roll` (from 1) of `expression`, or
+/// `None` once no tip applies
+pub fn pick_tip(expression: &str, user_id: u64, roll: i64) -> Option<&'static str> {
+    let start = (user_id as usize).wrapping_add(roll.max(1) as usize - 1);
+    (0..TIPS.len())
+        .map(|offset| &TIPS[(start + offset) % TIPS.len()])
+        .find(|tip| (tip.applies)(expression))
+        .map(|tip| tip.text)
+}
+
+/// The line added under a roll with a tip
+pub fn footer(tip: &str) -> String {
+    format!("\n-# 💡 Tip: {tip} · `/tips` to hide")
+}
+
+/// Count the roll towards the user's tips in the server, returning the tip
+/// line to add, or an empty string
+pub async fn record_roll(ctx: &Context, origin: &RollOrigin<'_>, expression: &str) -> String {
+    let Some(guild_id) = origin.guild_id else {
+        return String::new();
+    };
+    let Some(db) = ctx.data.read().await.get::<DatabaseContainer>().cloned() else {
+        return String::new();
+    };
+    let user_id = origin.user.id.get();
+    match db
+        .next_tip_roll(guild_id.get() as i64, user_id as i64, TIP_ROLLS)
+        .await
+    {
+        Ok(Some(roll)) => pick_tip(expression, user_id, roll)
+            .map(footer)
+            .unwrap_or_default(),
+        Ok(None) => String::new(),
+        Err(e) => {
+            warn!("Failed to count roll for tips: {}", e);
+            String::new()
+        }
+    }
+}
+
+// Whether `flag` is one of the expression's leading flags (`p 1d20`)
+fn has_flag(expression: &str, flag: &str) -> bool {
+    expression
+        .split_whitespace()
+        .take_while(|word| !word.chars().next().is_some_and(|c| c.is_ascii_digit()))
+        .any(|word| word.eq_ignore_ascii_case(flag))
+}
@@ -0,0 +1,54 @@
+//! In-memory store behind message components whose state doesn't fit in a
+//! custom ID: `/followup` quick-action buttons and Reroll selected menus.
+//!
+//! Each stored value gets a token, which the component carries in its custom
+//! ID (`action:<token>:<index>`, `rerollpick:<token>`).  The store keeps the
+//! newest `CAPACITY` values and is lost on restart; components whose value
+//! has gone say they have expired.
+
+use crate::sync::lock;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Values behind recent components, by token, keeping the newest `CAPACITY`
+#[derive(Debug)]
+pub struct TokenStore<T, const CAPACITY: usize> {
+    // Tokens only grow, so the first entry is always the oldest
+    entries: Mutex<(u64, BTreeMap<u64, T>)>,
+}
+
+impl<T, const CAPACITY: usize> Default for TokenStore<T, CAPACITY> {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new((0, BTreeMap::new())),
+        }
+    }
+}
+
+impl<T: Clone, const CAPACITY: usize> TokenStore<T, CAPACITY> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember a value, returning the token for its custom ID
+    pub fn store(&self, value: T) -> u64 {
+        let mut guard = lock(&self.entries);
+        let (next_token, entries) = &mut *guard;
+        *next_token += 1;
+        if entries.len() >= CAPACITY {
+            entries.pop_first();
+        }
+        entries.insert(*next_token, value);
+        *next_token
+    }
+
+    /// The value stored under `token`, if it hasn't expired
+    pub fn get(&self, token: u64) -> Option<T> {
+        lock(&self.entries).1.get(&token).cloned()
+    }
+
+    /// Forget the value under `token`, e.g. once a single-use menu is used
+    pub fn remove(&self, token: u64) -> Option<T> {
+        lock(&self.entries).1.remove(&token)
+    }
+}
@@ -0,0 +1,238 @@
+//! `/usage` owner-only report and anonymous alias usage counters.
+//!
+//! Every successful roll increments a counter for each game-system alias it
+//! used (see `parser::usage_keys`); plain dice expressions count as `dice`.
+//! Counters are stored in the `alias_usage` table without any guild, channel
+//! or user IDs, so maintainers can see whether an alias such as `snm` or
+//! `bnw` is actually used before investing in it.
+//!
+//! Each roll's parse-and-roll time is also recorded under the same keys in
+//! the in-memory [`metrics`](crate::metrics) histograms; `/usage
+//! report:timings` lists every system's p50 / p95 / max, slowest first, and
+//! `report:commands` does the same for whole commands.  `report:writes`
+//! counts the background database writes (see `write_queue`) and how many
+//! were dropped.
+//!
+//! The report is restricted to the application owner (or team members),
+//! read from [`OwnersContainer`](crate::OwnersContainer).
+
+use crate::commands::{CommandResponse, require_owner};
+use crate::database::AliasUsage;
+use crate::dice::parser;
+use crate::metrics::{SystemTiming, WriteStats};
+use crate::write_queue::{self, DbWrite};
+use crate::{DatabaseContainer, MetricsContainer, WriteQueueContainer};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandInteraction, CommandOptionType},
+    builder::{CreateCommand, CreateCommandOption},
+    model::prelude::Permissions,
+    prelude::Context,
+};
+use std::time::Duration;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("usage")
+        .description("Show alias usage and roll timings (bot owner only)")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "alias",
+                "Look up a single alias (e.g. snm)",
+            )
+            .required(false)
+            .max_length(32),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "report", "Report to show")
+                .required(false)
+                .add_string_choice("Alias usage", "aliases")
+                .add_string_choice("Roll timings", "timings")
+                .add_string_choice("Command timings", "commands")
+                .add_string_choice("Database writes", "writes"),
+        )
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    if let Some(denied) = require_owner(ctx, command).await {
+        return Ok(denied);
+    }
+    let (db, metrics) = {
+        let data = ctx.data.read().await;
+        (
+            data.get::<DatabaseContainer>().cloned(),
+            data.get::<MetricsContainer>().cloned(),
+        )
+    };
+
+    let option = |name: &str| {
+        command
+            .data
+            .options
+            .iter()
+            .find(|opt| opt.name == name)
+            .and_then(|opt| opt.value.as_str())
+    };
+    let alias = option("alias");
+
+    if option("report") == Some("writes") {
+        let pending = ctx
+            .data
+            .read()
+            .await
+            .get::<WriteQueueContainer>()
+            .map(|queue| queue.pending());
+        let stats = metrics.map(|m| m.writes.snapshot()).unwrap_or_default();
+        return Ok(CommandResponse::private(format_write_report(
+            &stats, pending,
+        )));
+    }
+
+    let timings = match option("report") {
+        Some("timings") => Some(("Roll timings", metrics.map(|m| m.rolls.snapshot()))),
+        Some("commands") => Some(("Command timings", metrics.map(|m| m.commands.snapshot()))),
+        _ => None,
+    };
+    if let Some((title, timings)) = timings {
+        return Ok(CommandResponse::private(format_timing_report(
+            title,
+            &timings.unwrap_or_default(),
+            alias,
+        )));
+    }
+
+    let db = db.ok_or_else(|| anyhow!("Database not available"))?;
+
+    let usage = db.get_alias_usage().await?;
+    Ok(CommandResponse::private(format_usage_report(&usage, alias)))
+}
+
+/// Render the usage report, or the line for a single alias when `alias` is given.
+///
+/// The full report lists as many aliases as fit in one Discord message.
+pub fn format_usage_report(usage: &[AliasUsage], alias: Option<&str>) -> String {
+    if let Some(alias) = alias {
+        let alias = alias.trim().to_lowercase();
+        return match usage.iter().find(|entry| entry.alias == alias) {
+            Some(entry) => format!("📈 {}", format_entry(entry)),
+            None => format!("📈 `{alias}` has never been used"),
+        };
+    }
+
+    if usage.is_empty() {
+        return "📈 No alias usage recorded yet".to_string();
+    }
+
+    let total: i64 = usage.iter().map(|entry| entry.uses).sum();
+    let mut output = format!("📈 **Alias usage** ({total} rolls counted)");
+
+    for (shown, entry) in usage.iter().enumerate() {
+        let line = format!("\n{}", format_entry(entry));
+        let remaining = usage.len() - shown;
+        // Leave room for the "… and N more" footer
+        if output.len() + line.len() > 1950 {
+            output.push_str(&format!("\n… and {remaining} more"));
+            break;
+        }
+        output.push_str(&line);
+    }
+
+    output
+}
+
+fn format_entry(entry: &AliasUsage) -> String {
+    format!(
+        "`{}` — {} use{} (since {}, last {})",
+        entry.alias,
+        entry.uses,
+        if entry.uses == 1 { "" } else { "s" },
+        entry.first_used,
+        entry.last_used
+    )
+}
+
+/// Render a timing report (per system or per command), or the line for a
+/// single key.
+///
+/// Percentiles are bucket upper bounds, so `≤ 250µs` means the roll landed
+/// in the 100–250µs bucket.
+pub fn format_timing_report(title: &str, timings: &[SystemTiming], alias: Option<&str>) -> String {
+    if let Some(alias) = alias {
+        let alias = alias.trim().to_lowercase();
+        return match timings.iter().find(|timing| timing.system == alias) {
+            Some(timing) => format!("⏱️ {}", format_timing(timing)),
+            None => format!("⏱️ No timings recorded for `{alias}` since the bot started"),
+        };
+    }
+
+    if timings.is_empty() {
+        return format!(
+            "⏱️ No {} recorded since the bot started",
+            title.to_lowercase()
+        );
+    }
+
+    let mut output = format!("⏱️ **{title}** since the bot started (slowest p95 first)");
+    for (shown, timing) in timings.iter().enumerate() {
+        let line = format!("\n{}", format_timing(timing));
+        if output.len() + line.len() > 1950 {
+            output.push_str(&format!("\n… and {} more", timings.len() - shown));
+            break;
+        }
+        output.push_str(&line);
+    }
+    output
+}
+
+fn format_timing(timing: &SystemTiming) -> String {
+    format!(
+        "`{}` — p50 ≤ {}, p95 ≤ {}, max {} ({} roll{})",
+        timing.system,
+        format_micros(timing.p50_us),
+        format_micros(timing.p95_us),
+        format_micros(timing.max_us),
+        timing.count,
+        if timing.count == 1 { "" } else { "s" }
+    )
+}
+
+/// Render the background database write counters; `pending` is `None`
+/// when writes aren't queued
+pub fn format_write_report(stats: &WriteStats, pending: Option<usize>) -> String {
+    let mut output = format!(
+        "💾 **Database writes** since the bot started\n\
+         Written: {}\n\
+         Retried attempts: {}\n\
+         Dropped (queue full): {}\n\
+         Dropped (failed): {}",
+        stats.written, stats.retried, stats.dropped_full, stats.dropped_failed
+    );
+    match pending {
+        Some(pending) => output.push_str(&format!("\nWaiting: {pending}")),
+        None => output.push_str("\n-# Writes are made directly; no queue is running"),
+    }
+    output
+}
+
+fn format_micros(micros: u64) -> String {
+    if micros >= 1_000 {
+        format!("{:.1}ms", micros as f64 / 1_000.0)
+    } else {
+        format!("{micros}µs")
+    }
+}
+
+/// Count the aliases used by a successful roll and record how long it took.
+///
+/// The counters are written through the `write_queue`, so a busy database
+/// never delays the roll.
+pub async fn record_roll(ctx: &Context, expression: &str, elapsed: Duration) {
+    let metrics = ctx.data.read().await.get::<MetricsContainer>().cloned();
+
+    let keys = parser::usage_keys(expression);
+    if let Some(metrics) = metrics {
+        metrics.rolls.record(&keys, elapsed);
+    }
+    write_queue::enqueue(ctx, DbWrite::AliasUsage(keys)).await;
+}
@@ -0,0 +1,208 @@
+//! `/verify` slash-command handler and provably-fair rolls.
+//!
+//! With `/settings fair` on, a server's rolls take their dice from
+//! `rng::fair_rng(seed, n)`: a secret 32-byte server seed and the roll's
+//! number.  Each roll ends with its number and the start of the seed's
+//! SHA-256 hash:
+//!
+//! ```text
+//! -# 🔏 Fair roll #12 · seed a1b2c3d4e5f60718 · check with /verify
+//! ```
+//!
+//! The hash commits the bot to the seed before anyone rolls with it.
+//! `/verify seed:<hash> roll:<n> dice:<expression>` reveals the seed and its
+//! full hash and rolls the expression again from it, so the roll can be
+//! recomputed independently (`rng::fair_rng` documents the key).  Revealing
+//! a seed retires it: the next roll starts a new seed with a new hash, so a
+//! revealed seed never predicts a roll.
+//!
+//! Seeds live in the `fair_seeds` table.  Rolls in a running `/session` use
+//! the session seed instead, and rolls in DMs are never fair rolls.
+
+use crate::DatabaseContainer;
+use crate::commands::CommandResponse;
+use crate::database::FairSeed;
+use crate::dice::{self, RollResult, rng};
+use anyhow::{Result, anyhow};
+use serenity::{
+    all::{CommandInteraction, CommandOptionType, GuildId},
+    builder::{CreateCommand, CreateCommandOption},
+    prelude::Context,
+};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+/// Hash digits shown under fair rolls
+pub const SHOWN_HASH_LENGTH: usize = 16;
+
+/// Fewest hash digits `/verify` accepts to pick a seed
+pub const MIN_HASH_PREFIX: usize = 8;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("verify")
+        .description("Reveal a fair-roll seed and recompute a roll made with it")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "seed",
+                "Seed hash shown under the roll",
+            )
+            .required(true)
+            .min_length(MIN_HASH_PREFIX as u16)
+            .max_length(64),
+        )
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::Integer, "roll", "Fair roll number")
+                .required(true)
+                .min_int_value(1),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "dice",
+                "Expression shown with the roll",
+            )
+            .required(true),
+        )
+        .dm_permission(false)
+}
+
+pub async fn run(ctx: &Context, command: &CommandInteraction) -> Result<CommandResponse> {
+    let Some(guild_id) = command.guild_id else {
+        return Ok(CommandResponse::private(
+            "❌ Fair rolls are only made in servers.".to_string(),
+        ));
+    };
+    let option = |name: &str| command.data.options.iter().find(|opt| opt.name == name);
+    let (Some(hash), Some(roll), Some(expr)) = (
+        option("seed").and_then(|opt| opt.value.as_str()),
+        option("roll").and_then(|opt| opt.value.as_i64()),
+        option("dice").and_then(|opt| opt.value.as_str()),
+    ) else {
+        return Err(anyhow!("Missing verify options"));
+    };
+
+    let hash = hash.trim().to_lowercase();
+    if hash.len() < MIN_HASH_PREFIX || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Ok(CommandResponse::private(format!(
+            "❌ Invalid seed hash `{hash}`: expected at least {MIN_HASH_PREFIX} hex digits."
+        )));
+    }
+
+    let db = ctx
+        .data
+        .read()
+        .await
+        .get::<DatabaseContainer>()
+        .cloned()
+        .ok_or_else(|| anyhow!("Database not available"))?;
+    let Some(seed) = db.reveal_fair_seed(guild_id.get() as i64, &hash).await? else {
+        return Ok(CommandResponse::private(format!(
+            "❌ No fair-roll seed in this server has a hash starting with `{hash}`."
+        )));
+    };
+    if !seed.revealed {
+        info!(
+            "Fair seed {} revealed in guild {} by {}",
+            short_hash(&seed.seed_hash),
+            guild_id,
+            command.user.id
+        );
+    }
+
+    Ok(CommandResponse::public(format_verification(
+        &seed, roll, expr,
+    )))
+}
+
+/// Claim the next fair roll number when the server has fair rolls on,
+/// returning its seed (with `rolls` as this roll's number).  Failures are
+/// logged and the roll falls back to the normal RNG.
+pub async fn claim_roll(ctx: &Context, guild_id: GuildId) -> Option<FairSeed> {
+    let db = ctx.data.read().await.get::<DatabaseContainer>().cloned()?;
+    let seed = new_seed()
+        .inspect_err(|e| warn!("Failed to create fair seed: {}", e))
+        .ok()?;
+    match db
+        .next_fair_roll(guild_id.get() as i64, &seed, &seed_hash(&seed)?)
+        .await
+    {
+        Ok(seed) => Some(seed),
+        Err(e) => {
+            warn!("Failed to claim fair roll in guild {}: {}", guild_id, e);
+            None
+        }
+    }
+}
+
+/// A new random server seed: 32 bytes as hex
+pub fn new_seed() -> Result<String> {
+    let seed = rng::new_fair_seed().map_err(|e| anyhow!("Couldn't generate a seed: {e}"))?;
+    Ok(hex::encode(seed))
+}
+
+/// SHA-256 of a hex seed's bytes, as hex, or `None` if it isn't hex
+pub fn seed_hash(seed: &str) -> Option<String> {
+    let bytes = hex::decode(seed).ok()?;
+    Some(hex::encode(Sha256::digest(bytes)))
+}
+
+/// The start of a seed hash, as shown under rolls
+pub fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(SHOWN_HASH_LENGTH)]
+}
+
+/// Roll `expr` exactly as fair roll number `roll` with the hex `seed` did
+pub fn replay(seed: &str, roll: i64, expr: &str) -> Result<Vec<RollResult>> {
+    let roll = u64::try_from(roll)
+        .ok()
+        .filter(|roll| *roll > 0)
+        .ok_or_else(|| anyhow!("Roll numbers start at 1"))?;
+    let seed = hex::decode(seed).map_err(|_| anyhow!("Invalid seed `{seed}`"))?;
+    Ok(dice::parse_and_roll_with_rng(
+        expr,
+        &mut rng::fair_rng(&seed, roll),
+    )?)
+}
+
+/// The line added under a fair roll
+pub fn footer(seed: &FairSeed) -> String {
+    format!(
+        "\n-# 🔏 Fair roll #{} · seed `{}` · check with `/verify`",
+        seed.rolls,
+        short_hash(&seed.seed_hash)
+    )
+}
+
+/// The `/verify` reply: the revealed seed and roll `roll` of `expr` recomputed
+pub fn format_verification(seed: &FairSeed, roll: i64, expr: &str) -> String {
+    let hash_check = if seed_hash(&seed.seed).as_deref() == Some(seed.seed_hash.as_str()) {
+        "✅ SHA-256 of the seed matches"
+    } else {
+        "⚠️ SHA-256 of the seed does not match"
+    };
+    let result = if roll > seed.rolls {
+        format!(
+            "❌ This seed only made {} rolls, so there is no roll #{roll}.",
+            seed.rolls
+        )
+    } else {
+        match replay(&seed.seed, roll, expr) {
+            Ok(results) => format!(
+                "Fair roll #{roll} `{expr}`: {}",
+                dice::format_multiple_results_with_limit(&results)
+            ),
+            Err(e) => format!("❌ {e}"),
+        }
+    };
+    let retired = if seed.revealed {
+        ""
+    } else {
+        "\n-# This seed is now retired; new fair rolls use a new seed."
+    };
+
+    format!(
+        "🔏 **Seed** `{}`\nHash `{}` · {hash_check}\n{result}{retired}",
+        seed.seed, seed.seed_hash
+    )
+}
@@ -0,0 +1,332 @@
+//! Card decks drawn from with `/draw`.
+//!
+//! | Deck       | Cards                                                  |
+//! |------------|--------------------------------------------------------|
+//! | `standard` | 52 playing cards                                       |
+//! | `jokers`   | 52 playing cards and a red and a black Joker (54)      |
+//! | custom     | A server's own cards, e.g. `3x Goblin; Ogre; Dragon`   |
+//!
+//! A custom deck is written as cards separated by `;` or new lines; `3x Card`
+//! adds three copies.  Custom decks belong to a server and are stored in their
+//! normalized form (see [`Deck::definition`]) in the `guild_decks` table.
+//!
+//! Each channel has its own copy of every deck ([`DeckState`], stored in the
+//! `channel_decks` table): a shuffled draw pile and a discard pile.  Cards that
+//! are in neither have been drawn and are still in play until discarded.  A
+//! draw that runs out of cards shuffles the discard pile back in, and a
+//! shuffle returns every card to the draw pile.
+//!
+//! Initiative draws ([`deal_initiative`]) follow Deadlands and Savage Worlds:
+//! each combatant is dealt a card from the `jokers` deck and acts from the
+//! highest card down (Aces high, ties broken ♠ ♥ ♦ ♣, Jokers first).  The
+//! previous round's cards are discarded before dealing, and the whole deck is
+//! shuffled instead if a Joker came up.
+
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use rand::seq::SliceRandom;
+use regex::Regex;
+
+/// Most cards in a custom deck, copies included
+pub const MAX_DECK_CARDS: usize = 200;
+
+/// Most characters on one custom card
+pub const MAX_CARD_LENGTH: usize = 100;
+
+/// Most custom decks a server may keep
+pub const MAX_GUILD_DECKS: usize = 25;
+
+/// Most cards drawn at once
+pub const MAX_DRAW: usize = 20;
+
+/// Most combatants dealt into one initiative draw
+pub const MAX_INITIATIVE_NAMES: usize = 20;
+
+/// Deck of 52 playing cards
+pub const STANDARD_DECK: &str = "standard";
+
+/// Deck of 52 playing cards and two Jokers, used for initiative
+pub const JOKERS_DECK: &str = "jokers";
+
+const RANKS: [&str; 13] = [
+    "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K", "A",
+];
+
+// Lowest suit first, so a card's index is also its initiative order
+const SUITS: [&str; 4] = ["♣", "♦", "♥", "♠"];
+
+// Index of the first Joker in the `jokers` deck
+const FIRST_JOKER: usize = 52;
+
+// `3x Goblin` or `3 x Goblin`
+static CARD_COPIES_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?i)(\d+)\s*x\s+(.+)$").expect("Failed to compile CARD_COPIES_REGEX")
+});
+
+/// A deck's cards in their unshuffled order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deck {
+    pub name: String,
+    pub cards: Vec<String>,
+}
+
+impl Deck {
+    /// The `standard` or `jokers` deck of playing cards
+    pub fn playing_cards(jokers: bool) -> Self {
+        let mut cards: Vec<String> = SUITS
+            .iter()
+            .flat_map(|suit| RANKS.iter().map(move |rank| format!("{rank}{suit}")))
+            .collect();
+        let name = if jokers {
+            cards.push("🃏 Black Joker".to_string());
+            cards.push("🃏 Red Joker".to_string());
+            JOKERS_DECK
+        } else {
+            STANDARD_DECK
+        };
+        Self {
+            name: name.to_string(),
+            cards,
+        }
+    }
+
+    /// A built-in deck by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            STANDARD_DECK => Some(Self::playing_cards(false)),
+            JOKERS_DECK => Some(Self::playing_cards(true)),
+            _ => None,
+        }
+    }
+
+    /// Parse a custom deck definition
+    pub fn parse(name: &str, definition: &str) -> Result<Self> {
+        let name = name.trim().to_lowercase();
+        if Self::builtin(&name).is_some() {
+            return Err(anyhow!("`{name}` is a built-in deck; pick another name"));
+        }
+
+        let mut cards = Vec::new();
+        for part in definition.split([';', '\n']).map(str::trim) {
+            if part.is_empty() {
+                continue;
+            }
+            let (copies, card) = match CARD_COPIES_REGEX.captures(part) {
+                Some(captures) => {
+                    let copies: usize = captures[1]
+                        .parse()
+                        .map_err(|_| anyhow!("Too many copies in `{}`", part))?;
+                    (copies, captures[2].trim().to_string())
+                }
+                None => (1, part.to_string()),
+            };
+            if copies == 0 {
+                return Err(anyhow!("`{}` adds no cards", part));
+            }
+            if card.chars().count() > MAX_CARD_LENGTH {
+                return Err(anyhow!(
+                    "Cards can be at most {MAX_CARD_LENGTH} characters (`{}…`)",
+                    card.chars().take(20).collect::<String>()
+                ));
+            }
+            if cards.len() + copies > MAX_DECK_CARDS {
+                return Err(anyhow!("Decks can have at most {MAX_DECK_CARDS} cards"));
+            }
+            cards.extend(std::iter::repeat_n(card, copies));
+        }
+        if cards.is_empty() {
+            return Err(anyhow!("Deck has no cards"));
+        }
+
+        Ok(Self { name, cards })
+    }
+
+    /// Normalized definition, e.g. `3x Goblin; Ogre`
+    pub fn definition(&self) -> String {
+        let mut parts: Vec<(usize, &str)> = Vec::new();
+        for card in &self.cards {
+            match parts.last_mut() {
+                Some((copies, last)) if last == card => *copies += 1,
+                _ => parts.push((1, card)),
+            }
+        }
+        parts
+            .iter()
+            .map(|&(copies, card)| {
+                if copies == 1 {
+                    card.to_string()
+                } else {
+                    format!("{copies}x {card}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// The face of the card at `index`
+    pub fn card(&self, index: usize) -> &str {
+        self.cards.get(index).map_or("?", String::as_str)
+    }
+}
+
+/// Whether a card of the `jokers` deck is a Joker
+pub fn is_joker(index: usize) -> bool {
+    index >= FIRST_JOKER
+}
+
+/// A channel's copy of a deck, as indices into [`Deck::cards`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeckState {
+    /// Cards left to draw; the last is on top
+    pub draw_pile: Vec<usize>,
+    pub discard_pile: Vec<usize>,
+}
+
+/// Cards taken by one draw
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Draw {
+    pub cards: Vec<usize>,
+    /// The discard pile was shuffled back in to finish the draw
+    pub reshuffled: bool,
+}
+
+impl DeckState {
+    /// Every card of a deck of `size`, shuffled into the draw pile
+    pub fn shuffled(size: usize, rng: &mut impl rand::Rng) -> Self {
+        let mut draw_pile: Vec<usize> = (0..size).collect();
+        draw_pile.shuffle(rng);
+        Self {
+            draw_pile,
+            discard_pile: Vec::new(),
+        }
+    }
+
+    /// Read stored piles, or `None` if they don't fit a deck of `size` (such
+    /// as after a custom deck was replaced)
+    pub fn parse(draw_pile: &str, discard_pile: &str, size: usize) -> Option<Self> {
+        let pile = |text: &str| {
+            text.split_whitespace()
+                .map(|card| card.parse::<usize>().ok().filter(|&card| card < size))
+                .collect::<Option<Vec<_>>>()
+        };
+        let state = Self {
+            draw_pile: pile(draw_pile)?,
+            discard_pile: pile(discard_pile)?,
+        };
+        let mut seen = vec![false; size];
+        for &card in state.draw_pile.iter().chain(&state.discard_pile) {
+            if std::mem::replace(&mut seen[card], true) {
+                return None;
+            }
+        }
+        Some(state)
+    }
+
+    /// A pile as stored, e.g. `12 3 40`
+    pub fn encode(pile: &[usize]) -> String {
+        pile.iter()
+            .map(|card| card.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Cards drawn and not yet discarded, in deck order
+    pub fn in_play(&self, size: usize) -> Vec<usize> {
+        let mut in_play = vec![true; size];
+        for &card in self.draw_pile.iter().chain(&self.discard_pile) {
+            if let Some(slot) = in_play.get_mut(card) {
+                *slot = false;
+            }
+        }
+        (0..size).filter(|&card| in_play[card]).collect()
+    }
+
+    /// Draw `count` cards, shuffling the discard pile back in if the draw
+    /// pile runs out
+    pub fn draw(&mut self, count: usize, rng: &mut impl rand::Rng) -> Result<Draw> {
+        if count > self.draw_pile.len() + self.discard_pile.len() {
+            return Err(anyhow!(
+                "Only {} cards are left to draw. Use `/draw shuffle` to gather the deck.",
+                self.draw_pile.len() + self.discard_pile.len()
+            ));
+        }
+
+        let mut draw = Draw {
+            cards: Vec::with_capacity(count),
+            reshuffled: false,
+        };
+        while draw.cards.len() < count {
+            if self.draw_pile.is_empty() {
+                self.draw_pile = std::mem::take(&mut self.discard_pile);
+                self.draw_pile.shuffle(rng);
+                draw.reshuffled = true;
+            }
+            if let Some(card) = self.draw_pile.pop() {
+                draw.cards.push(card);
+            }
+        }
+        Ok(draw)
+    }
+
+    /// Move every card in play to the discard pile, returning how many
+    pub fn discard_in_play(&mut self, size: usize) -> usize {
+        let in_play = self.in_play(size);
+        self.discard_pile.extend(&in_play);
+        in_play.len()
+    }
+}
+
+/// One round of initiative cards
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitiativeDeal {
+    /// Combatants and their cards, in turn order
+    pub order: Vec<(String, usize)>,
+    /// The deck was shuffled first because a Joker came up last round
+    pub shuffled: bool,
+}
+
+impl InitiativeDeal {
+    /// Whether a Joker was dealt this round
+    pub fn joker(&self) -> bool {
+        self.order.iter().any(|&(_, card)| is_joker(card))
+    }
+}
+
+/// Deal a card from the `jokers` deck to each combatant
+pub fn deal_initiative(
+    state: &mut DeckState,
+    names: &[String],
+    rng: &mut impl rand::Rng,
+) -> Result<InitiativeDeal> {
+    let size = FIRST_JOKER + 2;
+    let shuffled = state.in_play(size).into_iter().any(is_joker);
+    if shuffled {
+        *state = DeckState::shuffled(size, rng);
+    } else {
+        state.discard_in_play(size);
+    }
+
+    let draw = state.draw(names.len(), rng)?;
+    let mut order: Vec<(String, usize)> = names.iter().cloned().zip(draw.cards).collect();
+    order.sort_by_key(|&(_, card)| std::cmp::Reverse(card));
+    Ok(InitiativeDeal { order, shuffled })
+}
+
+/// Split `/draw init` names, e.g. `Alice, Bob, Goblins`
+pub fn parse_names(names: &str) -> Result<Vec<String>> {
+    let names: Vec<String> = names
+        .split([',', ';', '\n'])
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+    if names.is_empty() {
+        return Err(anyhow!("List the combatants, e.g. `Alice, Bob, Goblins`"));
+    }
+    if names.len() > MAX_INITIATIVE_NAMES {
+        return Err(anyhow!(
+            "At most {MAX_INITIATIVE_NAMES} combatants can be dealt in"
+        ));
+    }
+    Ok(names)
+}
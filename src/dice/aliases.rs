@@ -25,7 +25,36 @@
 //! | `hs`             | Hero System                          |
 //! | `ex`             | Exalted                              |
 //! | `ms` / `ms2`     | Mothership RPG                       |
+//! | `msh`            | Mothership stat checks and panic     |
 //! | `ola` / `old`    | Open Legend RPG                      |
+//! | `gen`            | Genesys / Star Wars FFG narrative dice |
+//! | `coc`            | Call of Cthulhu 7e percentile rolls  |
+//! | `wfrp`           | Warhammer Fantasy Roleplay 4e tests  |
+//! | `db`             | Dragonbane d20 rolls                 |
+//! | `gurps`          | GURPS 3d6 success rolls              |
+//! | `l5r`            | Legend of the Five Rings 5e          |
+//! | `fbl`            | Forbidden Lands (Year Zero) pools    |
+//! | `bw`             | Burning Wheel / Mouse Guard tests    |
+//! | `ore`            | One-Roll Engine d10 pools (sets)     |
+//! | `cortex`         | Cortex Prime mixed dice pools        |
+//! | `dcc`            | Dungeon Crawl Classics dice chain    |
+//! | `iron`           | Ironsworn / Starforged action rolls  |
+//! | `oracle`         | Ironsworn / Starforged oracle (d100) |
+//! | `coin` / `Ncoin` | Coin flips (`Nd{Heads,Tails}`)       |
+//! | `sta` / `dune` / `fallout` | 2d20 checks (also `conan tn#`) |
+//!
+//! Servers can also pick a default system with `/setup`; a bare number is then
+//! rolled as that system (see [`expand_default_system`]).
+//!
+//! User-defined aliases (a server's `/alias` and `/macropack` aliases, and
+//! `/macro`s) are expanded separately by [`expand_user_aliases`] before this
+//! runs, so they are looked up first; it guards against alias loops and
+//! runaway nesting.
+//!
+//! When a roll fails to parse, [`similar_aliases`] looks for a built-in alias
+//! one typo away, which `/roll` suggests or (with `/settings autocorrect`)
+//! rolls instead.  [`alias_examples_with_prefix`] looks up the built-in
+//! examples `/roll` autocomplete suggests as the expression is typed.
 //!
 //! See `roll_syntax.md` for the full syntax reference.  All regex patterns are
 //! compiled once at startup via `once_cell::Lazy`.
@@ -37,8 +66,13 @@
 //!    and hard to debug).
 //! 2. Add a `Lazy<Regex>` constant near the top of this file.
 //! 3. Add a match arm in `expand_alias`.
-//! 4. Document the syntax in `roll_syntax.md`.
+//! 4. Add an example to [`ALIAS_EXAMPLES`] for `/roll` autocomplete.
+//! 5. Document the syntax in `roll_syntax.md`.
 
+use super::DCC_DICE_CHAIN;
+use super::error::{DiceError, Result};
+use super::parser::MAX_INPUT_LENGTH;
+use crate::templates;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
@@ -137,11 +171,10 @@ static EX_DS_FULL_REGEX: Lazy<Regex> =
 static EX_DS_SIMPLE_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^ex(\d+)ds(\d+)$").expect("Failed to compile EX_DS_SIMPLE_REGEX"));
 
-static ED_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^ed(\d+)$").expect("Failed to compile ED_REGEX"));
-
-static ED4E_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^ed4e(\d+)$").expect("Failed to compile ED4E_REGEX"));
+// Earthdawn step, with a karma die: ed15, ed4e15 k8, ed15 k (a d6)
+static EARTHDAWN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^ed(4e)?(\d+)(?:\s*k(\d*))?$").expect("Failed to compile EARTHDAWN_REGEX")
+});
 
 static DND_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"^(attack|skill|save)(\s*[+-]\s*\d+)?$").expect("Failed to compile DND_REGEX")
@@ -161,9 +194,20 @@ static CS_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"^cs\s+(\d+)(?:\s*([+-]\s*\d+))?$").expect("Failed to compile CS_REGEX")
 });
 
+// Dungeon Crawl Classics: dcc, dcc d16, dcc +1 (a step up the dice chain),
+// dcc -1d +2 (a step down, then a +2 modifier)
+static DCC_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^dcc(?:\s+(\d{0,2})d(\d+))?(?:\s+([+-]\d+)d?)?(?:\s*([+-]\s*\d+))?$")
+        .expect("Failed to compile DCC_REGEX")
+});
+
 static BNW_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^bnw(\d+)$").expect("Failed to compile BNW_REGEX"));
 
+// One-Roll Engine: ore 7 rolls 7d10 and groups the matching dice into sets
+static ORE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^ore\s*(\d+)$").expect("Failed to compile ORE_REGEX"));
+
 static CONAN_SKILL_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^conan([345])$").expect("Failed to compile CONAN_SKILL_REGEX"));
 
@@ -175,6 +219,18 @@ static CONAN_COMBINED_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"^conan([2-5])cd(\d+)$").expect("Failed to compile CONAN_COMBINED_REGEX")
 });
 
+// Conan skill rolls as 2d20 checks: conan3 tn12 focus2 diff1
+static CONAN_CHECK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^conan([2-5])?((?:\s+(?:tn|focus|f|diff|comp)\d+)+)$")
+        .expect("Failed to compile CONAN_CHECK_REGEX")
+});
+
+// Other 2d20 games: sta 3d20 tn15 f2, dune 2d20 tn12 diff2, fallout 2d20 tn10 comp2
+static TWO_D20_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:sta|dune|fallout)\s*(\d+)d20((?:\s+(?:tn|focus|f|diff|comp)\d+)+)$")
+        .expect("Failed to compile TWO_D20_REGEX")
+});
+
 static SIL_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^sil(\d+)$").expect("Failed to compile SIL_REGEX"));
 
@@ -233,6 +289,85 @@ static MNM_REGEX: Lazy<Regex> =
 static MS_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^([+-])?ms(\d+)?$").expect("Failed to compile MS_REGEX"));
 
+// Mothership stat check: msh 45, msh 45 adv, -msh 45
+static MSH_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([+-])?msh\s*(\d+)(?:\s+(adv|dis))?$").expect("Failed to compile MSH_REGEX")
+});
+
+// Mothership panic check against the current stress: msh panic 5
+static MSH_PANIC_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^msh\s*panic\s*(\d+)$").expect("Failed to compile MSH_PANIC_REGEX"));
+
+// Genesys / Star Wars FFG narrative pool: gen 2p 1a 2d
+static GENESYS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^gen\s+(\d+[bapsdc](?:\s*\d+[bapsdc])*)$")
+        .expect("Failed to compile GENESYS_REGEX")
+});
+
+static GENESYS_DIE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(\d+)([bapsdc])").expect("Failed to compile GENESYS_DIE_REGEX"));
+
+// Cortex Prime pool of mixed dice: cortex d8 d8 d6 d10, or cortex 2d8 d6
+static CORTEX_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^cortex\s+((?:\d{0,2}d\d{1,2}\s*)+)$").expect("Failed to compile CORTEX_REGEX")
+});
+
+static CORTEX_DIE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\d*d\d+").expect("Failed to compile CORTEX_DIE_REGEX"));
+
+static COC_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^coc(?:\s*(\d+))?(?:\s*(bb|b|pp|p))?$").expect("Failed to compile COC_REGEX")
+});
+
+// Warhammer Fantasy Roleplay 4e: wfrp 45 tests a skill of 45
+static WFRP_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^wfrp\s*(\d+)$").expect("Failed to compile WFRP_REGEX"));
+
+// Dragonbane: db 12 boon push tests a skill of 12 with a boon, pushed
+static DRAGONBANE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^db(?:\s*(\d+))?(?:\s*(boon|bane))?(?:\s*(push))?$")
+        .expect("Failed to compile DRAGONBANE_REGEX")
+});
+
+// Forbidden Lands: fbl b5 s3 g2 a10 push rolls base, skill, gear and
+// artifact dice, pushed
+static FORBIDDEN_LANDS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^fbl((?:\s*[bsga]\d+)+)(?:\s*(push))?$")
+        .expect("Failed to compile FORBIDDEN_LANDS_REGEX")
+});
+
+// Burning Wheel: bw 5 ob3 h1 open luck tests an exponent of 5 against
+// obstacle 3 with a helping die, open-ended, spending Fate for luck
+static BURNING_WHEEL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^bw\s*(\d+)(?:\s*ob\s*(\d+))?(?:\s*h\s*(\d+))?(?:\s*(open))?(?:\s*(luck))?$")
+        .expect("Failed to compile BURNING_WHEEL_REGEX")
+});
+
+// GURPS success roll: gurps 12, or gurps 14 -2 for an effective skill of 12
+static GURPS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^gurps\s*(\d+)(?:\s*([+-])\s*(\d+))?$").expect("Failed to compile GURPS_REGEX")
+});
+
+// Legend of the Five Rings 5e: l5r 5k3 rolls 5 dice and keeps 3
+static L5R_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^l5r\s*(\d+)k(\d+)$").expect("Failed to compile L5R_REGEX"));
+
+// Ironsworn / Starforged action roll: iron +2, iron +3 +1
+static IRON_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^iron((?:\s*[+-]\s*\d+)*)$").expect("Failed to compile IRON_REGEX"));
+
+// Ironsworn oracle with optional odds: oracle, oracle likely
+static ORACLE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"^oracle(?:\s+(almost certain|certain|likely|50/50|even|unlikely|small chance|small))?$",
+    )
+    .expect("Failed to compile ORACLE_REGEX")
+});
+
+// Coin flips: coin, 3coin, 3coins
+static COIN_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\d*)coins?$").expect("Failed to compile COIN_REGEX"));
+
 static DP_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^(\d+)dp$").expect("Failed to compile DP_REGEX"));
 
@@ -284,6 +419,72 @@ static STATIC_ALIASES: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(||
     aliases
 });
 
+/// Systems a server can pick as its default with `/setup`: key, display name,
+/// and the expression a bare number rolls as (`{n}` is replaced by the number).
+pub const DEFAULT_SYSTEMS: [(&str, &str, &str); 11] = [
+    ("d20", "D&D / d20 (1d20 + N)", "1d20 + {n}"),
+    ("pbta", "Powered by the Apocalypse (2d6 + N)", "2d6 + {n}"),
+    ("cod", "Chronicles of Darkness (Ncod)", "{n}cod"),
+    ("wod", "World of Darkness (Nwod8)", "{n}wod8"),
+    ("sr", "Shadowrun (srN)", "sr{n}"),
+    ("fitd", "Forged in the Dark (fitdN)", "fitd{n}"),
+    ("yz", "Year Zero Engine (Nyz)", "{n}yz"),
+    ("alien", "Alien RPG (alienN)", "alien{n}"),
+    ("ex", "Exalted (exN)", "ex{n}"),
+    ("df", "Fate (4df + N)", "4df + {n}"),
+    ("snm", "Stars Without Number (snmN)", "snm{n}"),
+];
+
+/// Expand a bare number (e.g. `5`) into the given default system's roll.
+///
+/// Returns `None` for unknown systems or when the input is anything other
+/// than a number, so ordinary expressions are never rewritten.
+pub fn expand_default_system(system: &str, input: &str) -> Option<String> {
+    let input = input.trim();
+    if input.is_empty() || input.len() > 3 || !input.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    DEFAULT_SYSTEMS
+        .iter()
+        .find(|(key, _, _)| *key == system)
+        .map(|(_, _, template)| template.replace("{n}", input))
+}
+
+// Combine the pool's dice by type into one `gen` modifier, in the order
+// boost, ability, proficiency, setback, difficulty, challenge
+fn expand_genesys_pool(pool: &str) -> Option<String> {
+    let mut counts = [0u32; 6];
+    for captures in GENESYS_DIE_REGEX.captures_iter(pool) {
+        let count: u32 = captures[1].parse().ok()?;
+        let index = "bapsdc".find(&captures[2])?;
+        counts[index] = counts[index].checked_add(count)?;
+    }
+    let modifier: String = counts
+        .iter()
+        .zip("bapsdc".chars())
+        .filter(|(count, _)| **count > 0)
+        .map(|(count, die)| format!("{count}{die}"))
+        .collect();
+    if modifier.is_empty() {
+        return None;
+    }
+    Some(format!("1d1 gen{modifier}"))
+}
+
+// Roll the pool's dice as one mixed pool, e.g. "2d8 d6" -> "{2d8+d6} cortex";
+// the roller checks the die sizes and the pool size
+fn expand_cortex_pool(pool: &str) -> Option<String> {
+    let terms: Vec<&str> = CORTEX_DIE_REGEX
+        .find_iter(pool)
+        .map(|term| term.as_str())
+        .collect();
+    if terms.is_empty() {
+        return None;
+    }
+    Some(format!("{{{}}} cortex", terms.join("+")))
+}
+
 pub fn expand_alias(input: &str) -> Option<String> {
     let input = input.trim().to_lowercase();
 
@@ -306,6 +507,462 @@ pub fn expand_alias(input: &str) -> Option<String> {
     None
 }
 
+// Longest input checked for a mistyped alias; aliases are short, and each
+// candidate spelling runs the whole alias table
+const MAX_SIMILAR_ALIAS_INPUT: usize = 24;
+
+/// Built-in aliases one typo away from `input`: a letter changed, added or
+/// dropped, or two neighbouring characters swapped (`4cof` → `4cod`,
+/// `wtm5h2` → `vtm5h2`).  Only letters are edited, so the numbers typed stay
+/// as they are.  Closer-looking fixes (a changed or swapped letter) come first.
+pub fn similar_aliases(input: &str) -> Vec<String> {
+    let input = input.trim().to_lowercase();
+    if input.len() > MAX_SIMILAR_ALIAS_INPUT || !input.is_ascii() || expand_alias(&input).is_some()
+    {
+        return Vec::new();
+    }
+
+    let chars: Vec<char> = input.chars().collect();
+    let letters = 'a'..='z';
+    let mut candidates: Vec<Vec<char>> = Vec::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_alphabetic() {
+            for letter in letters.clone().filter(|&letter| letter != c) {
+                let mut changed = chars.clone();
+                changed[i] = letter;
+                candidates.push(changed);
+            }
+        }
+    }
+    for i in 1..chars.len() {
+        if chars[i - 1] != chars[i]
+            && (chars[i - 1].is_ascii_alphabetic() || chars[i].is_ascii_alphabetic())
+        {
+            let mut swapped = chars.clone();
+            swapped.swap(i - 1, i);
+            candidates.push(swapped);
+        }
+    }
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_alphabetic() {
+            let mut dropped = chars.clone();
+            dropped.remove(i);
+            candidates.push(dropped);
+        }
+    }
+    for i in 0..=chars.len() {
+        for letter in letters.clone() {
+            let mut added = chars.clone();
+            added.insert(i, letter);
+            candidates.push(added);
+        }
+    }
+
+    let mut similar: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let candidate: String = candidate.into_iter().collect();
+        if !similar.contains(&candidate) && expand_alias(&candidate).is_some() {
+            similar.push(candidate);
+        }
+    }
+    similar
+}
+
+/// Built-in aliases offered by `/roll` autocomplete: an example of each, with
+/// what it rolls.  Kept sorted by example so [`alias_examples_with_prefix`]
+/// can binary-search it.
+pub const ALIAS_EXAMPLES: &[(&str, &str)] = &[
+    ("+d%", "Percentile with a bonus die"),
+    ("+d20", "Advantage: roll 2d20, keep the highest"),
+    ("-d%", "Percentile with a penalty die"),
+    ("-d20", "Disadvantage: roll 2d20, keep the lowest"),
+    ("3coin", "Flip three coins"),
+    ("4cod", "Chronicles of Darkness pool, 10-again"),
+    ("4codr", "Chronicles of Darkness pool, rote action"),
+    ("4df", "Fate / Fudge dice"),
+    ("5wod8", "World of Darkness pool, difficulty 8"),
+    (
+        "a5e +5 ex4",
+        "Level Up: Advanced 5e roll with expertise die",
+    ),
+    ("age", "AGE system 2d6 + stunt die"),
+    ("alien3", "Alien RPG base dice"),
+    ("alien3s2", "Alien RPG base and stress dice"),
+    ("attack +5", "d20 attack roll"),
+    ("bnw3", "Brave New World pool"),
+    ("bw 4 ob3", "Burning Wheel test against an obstacle"),
+    ("coc 50", "Call of Cthulhu 7e percentile roll"),
+    ("coin", "Flip a coin"),
+    ("cortex d8 d8 d6", "Cortex Prime pool with effect die"),
+    ("cpr", "Cyberpunk Red d10 check"),
+    ("cs 3", "Cypher System level 3 task"),
+    ("d6s4", "D6 System pool with wild die"),
+    ("db 12", "Dragonbane d20 roll"),
+    (
+        "dcc +1",
+        "Dungeon Crawl Classics d24, one step up the dice chain",
+    ),
+    ("dd34", "Double-digit d3 and d4 (d66-style)"),
+    ("dh", "Dark Heresy d100 test"),
+    ("dheart", "Daggerheart hope and fear dice"),
+    ("dndstats", "D&D ability scores: 4d6 keep 3, six times"),
+    ("dp", "Plot die"),
+    ("ed15", "Earthdawn step 15"),
+    ("ex5", "Exalted pool, 10s count double"),
+    ("fbl b5 s3", "Forbidden Lands base and skill dice"),
+    ("fitd3", "Forged in the Dark action roll"),
+    ("gb", "Godbound d20 damage"),
+    ("gbs 2d8", "Godbound straight damage"),
+    ("gen 2a 1p", "Genesys narrative dice"),
+    ("gurps 12", "GURPS 3d6 success roll"),
+    ("hsh", "Hero System to-hit roll"),
+    ("iron +2", "Ironsworn action roll"),
+    ("l5r 5k3", "Legend of the Five Rings roll and keep"),
+    ("mm", "Marvel Multiverse 3d6 roll"),
+    ("mnm", "Mutants & Masterminds d20 check"),
+    ("ms45", "Mothership d100 check"),
+    ("msh panic 5", "Mothership panic check"),
+    ("oracle", "Ironsworn oracle d100"),
+    ("ore 7", "One-Roll Engine pool, matched into sets"),
+    ("sil3", "Silhouette skilled roll"),
+    ("snm5", "Sunsails: New Millennium pool"),
+    ("sp4", "Splittermond pool"),
+    ("sr6", "Shadowrun 6e pool"),
+    ("sta 2d20 tn12", "2d20 check against a target number"),
+    ("sw8", "Savage Worlds trait and wild die"),
+    ("vtm5h2", "Vampire: The Masquerade 5e pool with hunger dice"),
+    ("wfrp 45", "Warhammer Fantasy Roleplay 4e test"),
+    ("wit", "Witcher d10 check"),
+    ("wng 4d6", "Wrath & Glory pool with wrath die"),
+];
+
+/// The [`ALIAS_EXAMPLES`] starting with `prefix`, ignoring case
+pub fn alias_examples_with_prefix(prefix: &str) -> &'static [(&'static str, &'static str)] {
+    let prefix = prefix.trim_start().to_lowercase();
+    let start = ALIAS_EXAMPLES.partition_point(|(example, _)| *example < prefix.as_str());
+    let len = ALIAS_EXAMPLES[start..].partition_point(|(example, _)| example.starts_with(&prefix));
+    &ALIAS_EXAMPLES[start..start + len]
+}
+
+// Alias identifiers containing digits, checked before the letters-only rule
+const DIGIT_IDENTIFIERS: [&str; 6] = ["d6s", "a5e", "ed4e", "d6l", "d%", "l5r"];
+
+/// Usage-counter key for a single roll expression: the alias identifier with
+/// its parameters removed (`4cod` → `cod`, `+d20` → `+d`, `fitd3` → `fitd`),
+/// or `dice` when the expression is not an alias.
+pub fn alias_usage_key(input: &str) -> String {
+    let input = input.trim().to_lowercase();
+    let first_token = input.split_whitespace().next().unwrap_or_default();
+
+    if expand_alias(&input).is_none() && expand_alias(first_token).is_none() {
+        return "dice".to_string();
+    }
+
+    let sign = first_token
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '+' | '-'))
+        .map(String::from)
+        .unwrap_or_default();
+    let rest = first_token
+        .trim_start_matches(['+', '-'])
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+    let identifier = match DIGIT_IDENTIFIERS.iter().find(|id| rest.starts_with(*id)) {
+        Some(id) => id.to_string(),
+        None => rest
+            .chars()
+            .take_while(|c| c.is_ascii_alphabetic())
+            .collect(),
+    };
+
+    format!("{sign}{identifier}")
+}
+
+/// Maximum nesting depth when user-defined aliases reference other aliases
+pub const MAX_USER_ALIAS_DEPTH: usize = 8;
+
+// Candidate names for user-defined aliases: whole words, not starting with a digit
+static USER_ALIAS_TOKEN_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b[A-Za-z_][A-Za-z0-9_]*\b").expect("Failed to compile USER_ALIAS_TOKEN_REGEX")
+});
+
+// Values accepted for alias parameters: a number or a plain dice term
+static USER_ALIAS_ARGUMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\d+|\d*d\d+)$").expect("Failed to compile USER_ALIAS_ARGUMENT_REGEX")
+});
+
+/// Expand user-defined aliases (guild aliases, macros) in an expression.
+///
+/// `lookup` returns the definition for a lowercase name, or `None` if the name
+/// is not defined.  Definitions may reference other aliases; expansion stops
+/// with an error on a cycle (`alias loop: a → b → a`), when nesting exceeds
+/// [`MAX_USER_ALIAS_DEPTH`], or when the result would exceed the parser's input
+/// limit.  A leading `(label)` and anything after `!` are left untouched.
+///
+/// Definitions with `{placeholder}` slots take arguments: with
+/// `atk = 1d20+{x} + 1d8+{x}`, `atk(5)` expands to `1d20+5 + 1d8+5`.
+/// Arguments fill the placeholders in order of first use and must be numbers
+/// or dice terms (`2`, `d6`, `2d8`).
+pub fn expand_user_aliases<F>(input: &str, lookup: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let (expression, comment) = input.split_at(input.find('!').unwrap_or(input.len()));
+    let label_end = if expression.trim_start().starts_with('(') {
+        expression.find(')').map_or(0, |i| i + 1)
+    } else {
+        0
+    };
+    let (label, expression) = expression.split_at(label_end);
+
+    let mut chain = Vec::new();
+    let expanded = expand_user_alias_tokens(expression, &lookup, &mut chain)?;
+
+    let output = format!("{label}{expanded}{comment}");
+    if output.len() > MAX_INPUT_LENGTH {
+        return Err(alias_too_long());
+    }
+    Ok(output)
+}
+
+fn expand_user_alias_tokens<F>(text: &str, lookup: &F, chain: &mut Vec<String>) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for token in USER_ALIAS_TOKEN_REGEX.find_iter(text) {
+        // Skip words inside an argument list that was already consumed
+        if token.start() < last {
+            continue;
+        }
+        // Face names of labeled dice (`d{hit,miss}`) are text, not aliases
+        let before = &text[..token.start()];
+        if before.matches('{').count() > before.matches('}').count() {
+            continue;
+        }
+        let name = token.as_str().to_lowercase();
+        let Some(definition) = lookup(&name) else {
+            continue;
+        };
+        let (definition, end) = bind_user_alias_arguments(&name, definition, text, token.end())?;
+
+        if let Some(start) = chain.iter().position(|seen| *seen == name) {
+            return Err(DiceError::AliasError(format!(
+                "alias loop: {} → {}",
+                chain[start..].join(" → "),
+                name
+            )));
+        }
+        if chain.len() >= MAX_USER_ALIAS_DEPTH {
+            return Err(DiceError::AliasError(format!(
+                "alias nesting too deep (max {}): {} → {}",
+                MAX_USER_ALIAS_DEPTH,
+                chain.join(" → "),
+                name
+            )));
+        }
+
+        output.push_str(&text[last..token.start()]);
+        chain.push(name);
+        let expanded = expand_user_alias_tokens(&definition, lookup, chain)?;
+        chain.pop();
+        output.push_str(&expanded);
+        last = end;
+
+        // Bail out early so self-doubling definitions can't blow up exponentially
+        if output.len() > MAX_INPUT_LENGTH {
+            return Err(alias_too_long());
+        }
+    }
+
+    output.push_str(&text[last..]);
+    Ok(output)
+}
+
+/// Largest dice pool a character sheet roll may build
+pub const MAX_STAT_POOL: i64 = 100;
+
+// `[flags] [(label)] <system> <pool> [! comment]`, e.g. `p vtm str + brawl`
+static STAT_POOL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)^((?:(?:p|s|nr|ul|drama)\s+)*)(\([^)]*\)\s*)?(vtm|cod|sr|ex)\s+([a-z_][a-z0-9_]*(?:\s*[+-]\s*(?:[a-z_][a-z0-9_]*|\d+))*|\d+(?:\s*[+-]\s*(?:[a-z_][a-z0-9_]*|\d+))*)\s*$",
+    )
+    .expect("Failed to compile STAT_POOL_REGEX")
+});
+
+static STAT_POOL_TERM_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)([+-]?)\s*([a-z_][a-z0-9_]*|\d+)")
+        .expect("Failed to compile STAT_POOL_TERM_REGEX")
+});
+
+/// Whether `input` is a character sheet pool roll such as `vtm str+brawl`.
+///
+/// Only pools naming at least one stat qualify, so expressions that already
+/// roll (`5cod`, `vtm7h2`) are never rewritten.
+pub fn is_stat_pool(input: &str) -> bool {
+    let expression = input.split('!').next().unwrap_or_default();
+    STAT_POOL_REGEX
+        .captures(expression.trim())
+        .and_then(|captures| captures.get(4))
+        .is_some_and(|pool| pool.as_str().contains(|c: char| c.is_ascii_alphabetic()))
+}
+
+/// Build a pool-system roll from character sheet stats.
+///
+/// `vtm str + brawl` becomes `(str + brawl) vtm7h2` when `str` is 3, `brawl`
+/// is 4 and `hunger` is 2.  `lookup` returns a stat's value by lowercase name.
+/// Supported systems:
+///
+/// | System | Rolls as     | Notes                                         |
+/// |--------|--------------|-----------------------------------------------|
+/// | `vtm`  | `vtmNhH`     | Hunger dice from the `hunger` stat (max N)    |
+/// | `cod`  | `Ncod`       | A pool of 0 or less rolls a chance die        |
+/// | `sr`   | `srN`        |                                               |
+/// | `ex`   | `exN`        |                                               |
+///
+/// Returns `None` when the input is not a stat pool (see [`is_stat_pool`]).
+pub fn expand_stat_pool<F>(input: &str, lookup: F) -> Option<Result<String>>
+where
+    F: Fn(&str) -> Option<i64>,
+{
+    if !is_stat_pool(input) {
+        return None;
+    }
+
+    let (expression, comment) = input.split_at(input.find('!').unwrap_or(input.len()));
+    let captures = STAT_POOL_REGEX.captures(expression.trim())?;
+    let flags = captures
+        .get(1)
+        .map_or(String::new(), |m| m.as_str().to_lowercase());
+    let label = captures.get(2).map(|m| m.as_str().trim());
+    let system = captures.get(3)?.as_str().to_lowercase();
+    let pool_terms = captures.get(4)?.as_str();
+
+    let mut pool = 0i64;
+    let mut description = String::new();
+    for term in STAT_POOL_TERM_REGEX.captures_iter(pool_terms) {
+        let negative = term.get(1).is_some_and(|sign| sign.as_str() == "-");
+        let name = term
+            .get(2)
+            .map_or(String::new(), |m| m.as_str().to_lowercase());
+        let value = match name.parse::<i64>() {
+            Ok(number) => number,
+            Err(_) => match lookup(&name) {
+                Some(value) => value,
+                None => {
+                    return Some(Err(DiceError::ValidationError(format!(
+                        "Unknown stat `{name}`. Set it with `/sheet set`"
+                    ))));
+                }
+            },
+        };
+        pool = if negative {
+            pool.saturating_sub(value)
+        } else {
+            pool.saturating_add(value)
+        };
+
+        if !description.is_empty() {
+            description.push_str(if negative { " - " } else { " + " });
+        } else if negative {
+            description.push('-');
+        }
+        description.push_str(&name);
+    }
+
+    if pool > MAX_STAT_POOL {
+        return Some(Err(DiceError::RollLimitExceeded(format!(
+            "Dice pool of {pool} is too large (max {MAX_STAT_POOL})"
+        ))));
+    }
+
+    let roll = match system.as_str() {
+        "vtm" if pool < 1 => {
+            return Some(Err(DiceError::ValidationError(format!(
+                "Dice pool of {pool} is empty"
+            ))));
+        }
+        "vtm" => {
+            let hunger = lookup("hunger").unwrap_or(0).clamp(0, pool);
+            format!("vtm{pool}h{hunger}")
+        }
+        "cod" if pool < 1 => {
+            description.push_str(", chance die");
+            "1d10 t10 f1".to_string()
+        }
+        "cod" => format!("{pool}cod"),
+        _ if pool < 1 => {
+            return Some(Err(DiceError::ValidationError(format!(
+                "Dice pool of {pool} is empty"
+            ))));
+        }
+        _ => format!("{system}{pool}"),
+    };
+
+    let label = label.map_or_else(|| format!("({description})"), str::to_string);
+    let comment = comment.trim();
+    let expanded = format!("{flags}{label} {roll} {comment}");
+    Some(Ok(expanded.trim_end().to_string()))
+}
+
+// Fill a parameterised definition from the `(args)` following the alias name
+// at `text[start..]`, returning the definition and where the call ends
+fn bind_user_alias_arguments(
+    name: &str,
+    definition: String,
+    text: &str,
+    start: usize,
+) -> Result<(String, usize)> {
+    let parameters = templates::placeholders(&definition);
+    if parameters.is_empty() {
+        return Ok((definition, start));
+    }
+
+    let signature = format!("{}({})", name, parameters.join(", "));
+    let call = text[start..]
+        .strip_prefix('(')
+        .and_then(|rest| rest.find(')').map(|close| &rest[..close]));
+    let Some(call) = call else {
+        return Err(DiceError::AliasError(format!(
+            "`{name}` needs arguments: `{signature}`"
+        )));
+    };
+
+    let arguments: Vec<&str> = call.split(',').map(str::trim).collect();
+    if arguments.len() != parameters.len() {
+        return Err(DiceError::AliasError(format!(
+            "`{}` takes {} argument{}, got {}",
+            signature,
+            parameters.len(),
+            if parameters.len() == 1 { "" } else { "s" },
+            arguments.len()
+        )));
+    }
+    if let Some(invalid) = arguments
+        .iter()
+        .find(|argument| !USER_ALIAS_ARGUMENT_REGEX.is_match(&argument.to_lowercase()))
+    {
+        return Err(DiceError::AliasError(format!(
+            "Invalid argument `{invalid}` for `{signature}`: use a number or dice such as `2` or `1d6`"
+        )));
+    }
+
+    let values: Vec<(&str, &str)> = parameters.into_iter().zip(arguments).collect();
+    let filled = templates::fill_placeholders(&definition, &values);
+    // Name, `(`, the arguments and `)`
+    Ok((filled, start + call.len() + 2))
+}
+
+fn alias_too_long() -> DiceError {
+    DiceError::AliasError(format!(
+        "alias expansion too long (max {} characters)",
+        MAX_INPUT_LENGTH
+    ))
+}
+
 // Helper function to reduce duplication in Hero System dice processing
 fn process_hero_system_dice(
     dice_count_str: &str,
@@ -604,6 +1261,113 @@ fn expand_parameterized_alias(input: &str) -> Option<String> {
         });
     }
 
+    // Genesys / Star Wars FFG narrative dice (gen 2p 1a 2d -> 1d1 gen1a2p2d)
+    if let Some(captures) = GENESYS_REGEX.captures(input) {
+        return expand_genesys_pool(&captures[1]);
+    }
+
+    // Cortex Prime mixed pool (cortex 2d8 d6 -> {2d8+d6} cortex)
+    if let Some(captures) = CORTEX_REGEX.captures(input) {
+        return expand_cortex_pool(&captures[1]);
+    }
+
+    // Call of Cthulhu 7e (coc 65 bb -> 1d100 coc65b2)
+    if let Some(captures) = COC_REGEX.captures(input) {
+        let skill = captures.get(1).map_or("", |m| m.as_str());
+        let dice = captures.get(2).map_or(String::new(), |m| {
+            let kind = &m.as_str()[..1];
+            format!("{kind}{}", m.as_str().len())
+        });
+        return Some(format!("1d100 coc{skill}{dice}"));
+    }
+
+    // Warhammer Fantasy Roleplay 4e (wfrp 45 -> 1d100 wfrp45)
+    if let Some(captures) = WFRP_REGEX.captures(input) {
+        return Some(format!("1d100 wfrp{}", &captures[1]));
+    }
+
+    // Dragonbane (db 12 bane push -> 1d20 db12banepush)
+    if let Some(captures) = DRAGONBANE_REGEX.captures(input) {
+        let part = |i: usize| captures.get(i).map_or("", |m| m.as_str());
+        return Some(format!("1d20 db{}{}{}", part(1), part(2), part(3)));
+    }
+
+    // Forbidden Lands (fbl b5 s3 g2 a10 push -> 1d1 fblb5s3g2a10push)
+    if let Some(captures) = FORBIDDEN_LANDS_REGEX.captures(input) {
+        let dice: String = captures[1].split_whitespace().collect();
+        let push = captures.get(2).map_or("", |m| m.as_str());
+        return Some(format!("1d1 fbl{dice}{push}"));
+    }
+
+    // Burning Wheel (bw 5 ob3 h1 open luck -> 1d1 bw5ob3h1openluck)
+    if let Some(captures) = BURNING_WHEEL_REGEX.captures(input) {
+        let part = |i: usize, prefix: &str| {
+            captures
+                .get(i)
+                .map_or(String::new(), |m| format!("{prefix}{}", m.as_str()))
+        };
+        return Some(format!(
+            "1d1 bw{}{}{}{}{}",
+            &captures[1],
+            part(2, "ob"),
+            part(3, "h"),
+            part(4, ""),
+            part(5, "")
+        ));
+    }
+
+    // GURPS (gurps 14 -2 -> 3d6 gurps12); a skill modified below 0 is left
+    // for the parser to refuse
+    if let Some(captures) = GURPS_REGEX.captures(input) {
+        let skill: i64 = captures[1].parse().ok()?;
+        let modifier: i64 = match (captures.get(2), captures.get(3)) {
+            (Some(sign), Some(amount)) => {
+                let amount: i64 = amount.as_str().parse().ok()?;
+                if sign.as_str() == "-" {
+                    -amount
+                } else {
+                    amount
+                }
+            }
+            _ => 0,
+        };
+        return Some(format!("3d6 gurps{}", skill + modifier));
+    }
+
+    // Legend of the Five Rings 5e (l5r 5k3 -> 1d1 l5r5k3)
+    if let Some(captures) = L5R_REGEX.captures(input) {
+        return Some(format!("1d1 l5r{}k{}", &captures[1], &captures[2]));
+    }
+
+    // Ironsworn / Starforged action roll (iron +2 -> 1d6+2 iron)
+    if let Some(captures) = IRON_REGEX.captures(input) {
+        let adds = captures[1].replace(' ', "");
+        return Some(format!("1d6{adds} iron"));
+    }
+
+    // Coin flips, shown as the face that came up (3coin -> 3d{Heads,Tails})
+    if let Some(captures) = COIN_REGEX.captures(input) {
+        let count = if captures[1].is_empty() {
+            "1"
+        } else {
+            &captures[1]
+        };
+        return Some(format!("{count}d{{Heads,Tails}}"));
+    }
+
+    // Ironsworn oracle, yes on the odds' number or higher (oracle likely -> 1d100 oracle26)
+    if let Some(captures) = ORACLE_REGEX.captures(input) {
+        let odds = match captures.get(1).map(|m| m.as_str()) {
+            None => return Some("1d100 oracle".to_string()),
+            Some("almost certain" | "certain") => 11,
+            Some("likely") => 26,
+            Some("50/50" | "even") => 51,
+            Some("unlikely") => 76,
+            Some(_) => 91, // small chance
+        };
+        return Some(format!("1d100 oracle{odds}"));
+    }
+
     // Plot dice (3dp -> 3d6 plot)
     if let Some(captures) = DP_REGEX.captures(input) {
         let count = &captures[1];
@@ -817,20 +1581,19 @@ fn expand_parameterized_alias(input: &str) -> Option<String> {
         return Some(format!("{count}d10 t{target} t10"));
     }
 
-    // Earthdawn system (ed1 through ed50)
-    if let Some(captures) = ED_REGEX.captures(input) {
-        let step: u32 = captures[1].parse().ok()?;
-        if (1..=50).contains(&step) {
-            return Some(get_earthdawn_step(step));
-        }
-    }
-
-    // Earthdawn 4th edition (ed4e1 through ed4e100)
-    if let Some(captures) = ED4E_REGEX.captures(input) {
-        let step: u32 = captures[1].parse().ok()?;
-        if (1..=100).contains(&step) {
-            return Some(get_earthdawn_4e_step(step));
+    // Earthdawn (ed1 through ed50, ed4e1 and up), with an optional karma
+    // die that explodes like the step dice (ed15 k8 -> ... + 1d8 ie)
+    if let Some(captures) = EARTHDAWN_REGEX.captures(input) {
+        let step: u32 = captures[2].parse().ok()?;
+        let mut expansion = if captures.get(1).is_some() {
+            get_earthdawn_4e_step(step)?
+        } else {
+            get_earthdawn_step(step)?
+        };
+        if let Some(karma) = captures.get(3) {
+            expansion.push_str(&format!(" + 1d{} ie", earthdawn_karma_die(karma.as_str())?));
         }
+        return Some(expansion);
     }
 
     // DnD style rolls with modifiers (attack +10, skill -4, save +2)
@@ -886,12 +1649,32 @@ fn expand_parameterized_alias(input: &str) -> Option<String> {
         }
     }
 
+    // Dungeon Crawl Classics (dcc +1 +2 -> 1d24 dcc +2)
+    if let Some(captures) = DCC_REGEX.captures(input) {
+        let size = dcc_die(&captures).ok()?;
+        let count = captures.get(1).map_or("", |m| m.as_str());
+        let count = if count.is_empty() { "1" } else { count };
+        let modifier = captures.get(4).map(|m| m.as_str().trim()).unwrap_or("");
+
+        if modifier.is_empty() {
+            return Some(format!("{count}d{size} dcc"));
+        } else {
+            return Some(format!("{count}d{size} dcc {modifier}"));
+        }
+    }
+
     // Brave New World (bnw3 -> 3d6 bnw)
     if let Some(captures) = BNW_REGEX.captures(input) {
         let pool_size = &captures[1];
         return Some(format!("{pool_size}d6 bnw"));
     }
 
+    // One-Roll Engine (ore 7 -> 7d10 m)
+    if let Some(captures) = ORE_REGEX.captures(input) {
+        let pool_size = &captures[1];
+        return Some(format!("{pool_size}d10 m"));
+    }
+
     // Handle Conan combined patterns first (most specific)
     if let Some(captures) = CONAN_COMBINED_REGEX.captures(input) {
         let skill_dice = &captures[1];
@@ -901,6 +1684,23 @@ fn expand_parameterized_alias(input: &str) -> Option<String> {
         ));
     }
 
+    // Conan and other 2d20 checks with a target number, focus, difficulty or
+    // complication range
+    if let Some(captures) = CONAN_CHECK_REGEX.captures(input) {
+        let dice_count = captures.get(1).map_or("2", |m| m.as_str());
+        return Some(format!(
+            "{dice_count}d20 conan{dice_count}{}",
+            expand_2d20_options(&captures[2])
+        ));
+    }
+    if let Some(captures) = TWO_D20_REGEX.captures(input) {
+        let dice_count = &captures[1];
+        return Some(format!(
+            "{dice_count}d20{}",
+            expand_2d20_options(&captures[2])
+        ));
+    }
+
     // Handle Conan skill dice patterns
     if let Some(captures) = CONAN_SKILL_REGEX.captures(input) {
         let dice_count = &captures[1];
@@ -1068,120 +1868,134 @@ static EARTHDAWN_1E_EXTENDED_STEPS: Lazy<HashMap<u32, &'static str>> = Lazy::new
     steps
 });
 
-// Earthdawn 4th Edition specific steps (19-100, different progression from 1E)
-static EARTHDAWN_4E_EXTENDED_STEPS: Lazy<HashMap<u32, &'static str>> = Lazy::new(|| {
-    let mut steps = HashMap::new();
-    steps.insert(19, "1d20 ie + 2d6 ie");
-    steps.insert(20, "1d20 ie + 1d8 ie + 1d6 ie");
-    steps.insert(21, "1d20 ie + 2d8 ie");
-    steps.insert(22, "1d20 ie + 1d10 ie + 1d8 ie");
-    steps.insert(23, "1d20 ie + 2d10 ie");
-    steps.insert(24, "1d20 ie + 1d12 ie + 1d10 ie");
-    steps.insert(25, "1d20 ie + 2d12 ie");
-    steps.insert(26, "1d20 ie + 1d12 ie + 2d6 ie");
-    steps.insert(27, "1d20 ie + 1d12 ie + 1d8 ie + 1d6 ie");
-    steps.insert(28, "1d20 ie + 1d12 ie + 2d8 ie");
-    steps.insert(29, "1d20 ie + 1d12 ie + 1d10 ie + 1d8 ie");
-    steps.insert(30, "2d20 ie + 2d6 ie");
-    steps.insert(31, "2d20 ie + 1d8 ie + 1d6 ie");
-    steps.insert(32, "2d20 ie + 2d8 ie");
-    steps.insert(33, "2d20 ie + 1d10 ie + 1d8 ie");
-    steps.insert(34, "2d20 ie + 2d10 ie");
-    steps.insert(35, "2d20 ie + 1d12 ie + 1d10 ie");
-    steps.insert(36, "2d20 ie + 2d12 ie");
-    steps.insert(37, "2d20 ie + 1d12 ie + 2d6 ie");
-    steps.insert(38, "2d20 ie + 1d12 ie + 1d8 ie + 1d6 ie");
-    steps.insert(39, "2d20 ie + 1d12 ie + 2d8 ie");
-    steps.insert(40, "2d20 ie + 1d12 ie + 1d10 ie + 1d8 ie");
-    steps.insert(41, "3d20 ie + 2d6 ie");
-    steps.insert(42, "3d20 ie + 1d8 ie + 1d6 ie");
-    steps.insert(43, "3d20 ie + 2d8 ie");
-    steps.insert(44, "3d20 ie + 1d10 ie + 1d8 ie");
-    steps.insert(45, "3d20 ie + 2d10 ie");
-    steps.insert(46, "3d20 ie + 1d12 ie + 1d10 ie");
-    steps.insert(47, "3d20 ie + 2d12 ie");
-    steps.insert(48, "3d20 ie + 1d12 ie + 2d6 ie");
-    steps.insert(49, "3d20 ie + 1d12 ie + 1d8 ie + 1d6 ie");
-    steps.insert(50, "3d20 ie + 1d12 ie + 2d8 ie");
-    steps.insert(51, "3d20 ie + 1d12 ie + 1d10 ie + 1d8 ie");
-    steps.insert(52, "4d20 ie + 2d6 ie");
-    steps.insert(53, "4d20 ie + 1d8 ie + 1d6 ie");
-    steps.insert(54, "4d20 ie + 2d8 ie");
-    steps.insert(55, "4d20 ie + 1d10 ie + 1d8 ie");
-    steps.insert(56, "4d20 ie + 2d10 ie");
-    steps.insert(57, "4d20 ie + 1d12 ie + 1d10 ie");
-    steps.insert(58, "4d20 ie + 2d12 ie");
-    steps.insert(59, "4d20 ie + 1d12 ie + 2d6 ie");
-    steps.insert(60, "4d20 ie + 1d12 ie + 1d8 ie + 1d6 ie");
-    steps.insert(61, "4d20 ie + 1d12 ie + 2d8 ie");
-    steps.insert(62, "4d20 ie + 1d12 ie + 1d10 ie + 1d8 ie");
-    steps.insert(63, "5d20 ie + 2d6 ie");
-    steps.insert(64, "5d20 ie + 1d8 ie + 1d6 ie");
-    steps.insert(65, "5d20 ie + 2d8 ie");
-    steps.insert(66, "5d20 ie + 1d10 ie + 1d8 ie");
-    steps.insert(67, "5d20 ie + 2d10 ie");
-    steps.insert(68, "5d20 ie + 1d12 ie + 1d10 ie");
-    steps.insert(69, "5d20 ie + 2d12 ie");
-    steps.insert(70, "5d20 ie + 1d12 ie + 2d6 ie");
-    steps.insert(71, "5d20 ie + 1d12 ie + 1d8 ie + 1d6 ie");
-    steps.insert(72, "5d20 ie + 1d12 ie + 2d8 ie");
-    steps.insert(73, "5d20 ie + 1d12 ie + 1d10 ie + 1d8 ie");
-    steps.insert(74, "6d20 ie + 2d6 ie");
-    steps.insert(75, "6d20 ie + 1d8 ie + 1d6 ie");
-    steps.insert(76, "6d20 ie + 2d8 ie");
-    steps.insert(77, "6d20 ie + 1d10 ie + 1d8 ie");
-    steps.insert(78, "6d20 ie + 2d10 ie");
-    steps.insert(79, "6d20 ie + 1d12 ie + 1d10 ie");
-    steps.insert(80, "6d20 ie + 2d12 ie");
-    steps.insert(81, "6d20 ie + 1d12 ie + 2d6 ie");
-    steps.insert(82, "6d20 ie + 1d12 ie + 1d8 ie + 1d6 ie");
-    steps.insert(83, "6d20 ie + 1d12 ie + 2d8 ie");
-    steps.insert(84, "6d20 ie + 1d12 ie + 1d10 ie + 1d8 ie");
-    steps.insert(85, "7d20 ie + 2d6 ie");
-    steps.insert(86, "7d20 ie + 1d8 ie + 1d6 ie");
-    steps.insert(87, "7d20 ie + 2d8 ie");
-    steps.insert(88, "7d20 ie + 1d10 ie + 1d8 ie");
-    steps.insert(89, "7d20 ie + 2d10 ie");
-    steps.insert(90, "7d20 ie + 1d12 ie + 1d10 ie");
-    steps.insert(91, "7d20 ie + 2d12 ie");
-    steps.insert(92, "7d20 ie + 1d12 ie + 2d6 ie");
-    steps.insert(93, "7d20 ie + 1d12 ie + 1d8 ie + 1d6 ie");
-    steps.insert(94, "7d20 ie + 1d12 ie + 2d8 ie");
-    steps.insert(95, "7d20 ie + 1d12 ie + 1d10 ie + 1d8 ie");
-    steps.insert(96, "8d20 ie + 2d6 ie");
-    steps.insert(97, "8d20 ie + 1d8 ie + 1d6 ie");
-    steps.insert(98, "8d20 ie + 2d8 ie");
-    steps.insert(99, "8d20 ie + 1d10 ie + 1d8 ie");
-    steps.insert(100, "8d20 ie + 2d10 ie");
-    steps
-});
+/// Highest Earthdawn 4e step `ed4e` rolls (22d20 and change)
+pub const MAX_EARTHDAWN_4E_STEP: u32 = 250;
+
+/// Karma dice sizes Earthdawn allows
+pub const EARTHDAWN_KARMA_DICE: [u32; 5] = [4, 6, 8, 10, 12];
+
+// Earthdawn 4th Edition steps from 19 cycle through these dice every 11
+// steps, adding a d20 each time round (19 is 1d20 + 2d6, 30 is 2d20 + 2d6)
+const EARTHDAWN_4E_STEP_CYCLE: [&str; 11] = [
+    "2d6 ie",
+    "1d8 ie + 1d6 ie",
+    "2d8 ie",
+    "1d10 ie + 1d8 ie",
+    "2d10 ie",
+    "1d12 ie + 1d10 ie",
+    "2d12 ie",
+    "1d12 ie + 2d6 ie",
+    "1d12 ie + 1d8 ie + 1d6 ie",
+    "1d12 ie + 2d8 ie",
+    "1d12 ie + 1d10 ie + 1d8 ie",
+];
+
+// 2d20 options with the `f#` shorthand written out as `focus#`
+fn expand_2d20_options(options: &str) -> String {
+    options
+        .split_whitespace()
+        .map(|option| match option.strip_prefix('f') {
+            Some(focus) if focus.starts_with(|c: char| c.is_ascii_digit()) => {
+                format!(" focus{focus}")
+            }
+            _ => format!(" {option}"),
+        })
+        .collect()
+}
+
+// Dice for an Earthdawn 1st Edition step, 1-50
+fn get_earthdawn_step(step: u32) -> Option<String> {
+    EARTHDAWN_BASE_STEPS
+        .get(&step)
+        .or_else(|| EARTHDAWN_1E_EXTENDED_STEPS.get(&step))
+        .map(|step_str| step_str.to_string())
+}
 
-fn get_earthdawn_step(step: u32) -> String {
-    // First check base steps (1-18)
+// Dice for an Earthdawn 4th Edition step, 1 to MAX_EARTHDAWN_4E_STEP
+fn get_earthdawn_4e_step(step: u32) -> Option<String> {
     if let Some(&step_str) = EARTHDAWN_BASE_STEPS.get(&step) {
-        return step_str.to_string();
+        return Some(step_str.to_string());
     }
+    if !(19..=MAX_EARTHDAWN_4E_STEP).contains(&step) {
+        return None;
+    }
+    let round = (step - 19) / 11;
+    let rest = EARTHDAWN_4E_STEP_CYCLE[((step - 19) % 11) as usize];
+    Some(format!("{}d20 ie + {rest}", round + 1))
+}
 
-    // Then check 1E extended steps (19-50)
-    if let Some(&step_str) = EARTHDAWN_1E_EXTENDED_STEPS.get(&step) {
-        return step_str.to_string();
+// Sides of a karma die: `k` alone is the usual d6
+fn earthdawn_karma_die(sides: &str) -> Option<u32> {
+    if sides.is_empty() {
+        return Some(6);
     }
+    sides
+        .parse()
+        .ok()
+        .filter(|sides| EARTHDAWN_KARMA_DICE.contains(sides))
+}
 
-    "1d6".to_string() // fallback
+// The die a `dcc` roll shifts along the dice chain: the named die (d20 by
+// default) moved up or down by the step count
+fn dcc_die(captures: &regex::Captures) -> Result<u32> {
+    let base: u32 = captures
+        .get(2)
+        .map_or(Ok(20), |sides| sides.as_str().parse())
+        .unwrap_or(u32::MAX);
+    let Some(position) = DCC_DICE_CHAIN.iter().position(|&sides| sides == base) else {
+        return Err(DiceError::ValidationError(format!(
+            "d{} isn't on the DCC dice chain (d3, d4, d5, d6, d7, d8, d10, d12, d14, d16, d20, d24, d30)",
+            captures.get(2).map_or("20", |m| m.as_str())
+        )));
+    };
+    let Some(steps) = captures.get(3) else {
+        return Ok(base);
+    };
+    let step: i64 = steps.as_str().parse().unwrap_or(i64::MAX);
+    position
+        .checked_add_signed(step.try_into().unwrap_or(isize::MAX))
+        .and_then(|position| DCC_DICE_CHAIN.get(position).copied())
+        .ok_or_else(|| {
+            DiceError::ValidationError(format!(
+                "d{base} {}d is off the dice chain, which runs from d3 to d30",
+                steps.as_str()
+            ))
+        })
 }
 
-fn get_earthdawn_4e_step(step: u32) -> String {
-    // First check base steps (1-18)
-    if let Some(&step_str) = EARTHDAWN_BASE_STEPS.get(&step) {
-        return step_str.to_string();
+/// Why a roll that names a built-in alias can't be expanded: an Earthdawn
+/// step out of range or a karma die of the wrong size (`ed15 k7`), or a DCC
+/// die off the dice chain (`dcc +3`).  `None` when the input isn't such an
+/// alias, or is a valid one.
+pub fn alias_parameter_error(input: &str) -> Option<DiceError> {
+    let input = input.trim().to_lowercase();
+    if let Some(captures) = DCC_REGEX.captures(&input) {
+        return dcc_die(&captures).err();
     }
-
-    // Then check 4E extended steps (19-100)
-    if let Some(&step_str) = EARTHDAWN_4E_EXTENDED_STEPS.get(&step) {
-        return step_str.to_string();
+    let captures = EARTHDAWN_REGEX.captures(&input)?;
+    let fourth_edition = captures.get(1).is_some();
+    let max_step = if fourth_edition {
+        MAX_EARTHDAWN_4E_STEP
+    } else {
+        50
+    };
+    let step = captures[2].parse::<u32>().unwrap_or(u32::MAX);
+    if !(1..=max_step).contains(&step) {
+        return Some(DiceError::ValidationError(format!(
+            "Earthdawn {}steps go from 1 to {max_step}, got {}",
+            if fourth_edition { "4e " } else { "" },
+            &captures[2]
+        )));
+    }
+    match captures.get(3) {
+        Some(karma) if earthdawn_karma_die(karma.as_str()).is_none() => {
+            Some(DiceError::ValidationError(format!(
+                "Karma dice are d4, d6, d8, d10 or d12, got d{}",
+                karma.as_str()
+            )))
+        }
+        _ => None,
     }
-
-    "1d6".to_string() // fallback
 }
 
 fn expand_marvel_multiverse_alias(input: &str) -> Option<String> {
@@ -1452,6 +2266,23 @@ fn expand_wild_worlds_alias(input: &str) -> Option<String> {
 }
 
 fn expand_mothership_alias(input: &str) -> Option<String> {
+    // msh 45 is ms45; adv / dis (or a +/- in front) roll twice
+    if let Some(captures) = MSH_REGEX.captures(input) {
+        let stat = &captures[2];
+        let mode = captures
+            .get(1)
+            .map(|m| m.as_str())
+            .or(captures.get(3).map(|m| m.as_str()));
+        return Some(match mode {
+            Some("+" | "adv") => format!("2d100 ms{stat}a"),
+            Some("-" | "dis") => format!("2d100 ms{stat}d"),
+            _ => format!("1d100 ms{stat}"),
+        });
+    }
+    if let Some(captures) = MSH_PANIC_REGEX.captures(input) {
+        return Some(format!("1d20 mspanic{}", &captures[1]));
+    }
+
     if let Some(captures) = MS_REGEX.captures(input) {
         let advantage_sign = captures.get(1).map(|m| m.as_str());
         let stat_value = captures.get(2).map(|m| m.as_str());
@@ -0,0 +1,118 @@
+//! Cache of parsed dice expressions.
+//!
+//! Popular expressions (`1d20+5`, `4cod`) are rolled over and over, and
+//! parsing one runs through dozens of regexes.  [`parse`] keeps the parsed
+//! `Vec<DiceRoll>` of the last [`PARSE_CACHE_CAPACITY`] distinct expressions,
+//! evicting the least recently used, so a repeat only rolls the dice.
+//! Expressions that fail to parse aren't cached.  Entries are also ordered by
+//! when they were last used, so a lookup or an eviction takes `O(log n)`
+//! under the lock rather than a scan of the whole cache.
+//!
+//! The key is the trimmed expression as the parser receives it, after
+//! `commands::roll` has expanded `/macro`s, guild aliases, `/sheet` stats and
+//! the server's default system.  The parser depends on nothing but that
+//! string, so an entry can't go stale: once a server changes an alias, rolls
+//! using it expand to a different expression and miss the cache.
+
+use super::error::Result;
+use super::{DiceRoll, parser};
+use crate::sync::lock;
+use once_cell::sync::Lazy;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// Distinct expressions kept before the least recently used is evicted
+pub const PARSE_CACHE_CAPACITY: usize = 1024;
+
+static PARSE_CACHE: Lazy<ParseCache> = Lazy::new(|| ParseCache::new(PARSE_CACHE_CAPACITY));
+
+/// [`parser::parse_dice_string`] through the shared cache
+pub fn parse(input: &str) -> Result<Vec<DiceRoll>> {
+    PARSE_CACHE.parse(input)
+}
+
+/// Parsed expressions, least recently used evicted first
+#[derive(Debug)]
+pub struct ParseCache {
+    capacity: usize,
+    entries: Mutex<Entries>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    // Each expression's parse and the tick it was last used at
+    parsed: HashMap<String, (Vec<DiceRoll>, u64)>,
+    // Each expression by the tick it was last used at, oldest first
+    recency: BTreeMap<u64, String>,
+    clock: u64,
+}
+
+impl Entries {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    // The parse of `key`, marking it used now
+    fn get(&mut self, key: &str) -> Option<Vec<DiceRoll>> {
+        let now = self.tick();
+        let (dice, used) = self.parsed.get_mut(key)?;
+        let expression = self.recency.remove(used).unwrap_or_else(|| key.to_string());
+        self.recency.insert(now, expression);
+        *used = now;
+        Some(dice.clone())
+    }
+
+    // Cache the parse of `key`, evicting the least recently used expression
+    // when `capacity` are already cached
+    fn insert(&mut self, key: &str, dice: Vec<DiceRoll>, capacity: usize) {
+        let now = self.tick();
+        if let Some((_, used)) = self.parsed.get(key) {
+            // Parsed by another roll meanwhile
+            self.recency.remove(used);
+        } else if self.parsed.len() >= capacity
+            && let Some((_, oldest)) = self.recency.pop_first()
+        {
+            self.parsed.remove(&oldest);
+        }
+        self.recency.insert(now, key.to_string());
+        self.parsed.insert(key.to_string(), (dice, now));
+    }
+}
+
+impl ParseCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::default(),
+        }
+    }
+
+    /// The parse of `input`, from the cache or by parsing it (and caching
+    /// the result if it parsed)
+    pub fn parse(&self, input: &str) -> Result<Vec<DiceRoll>> {
+        let key = input.trim();
+        if let Some(dice) = lock(&self.entries).get(key) {
+            return Ok(dice);
+        }
+
+        // Parse without the lock, so other rolls aren't held up
+        let dice = parser::parse_dice_string(key)?;
+        lock(&self.entries).insert(key, dice.clone(), self.capacity);
+        Ok(dice)
+    }
+
+    /// Whether `input` is cached
+    pub fn contains(&self, input: &str) -> bool {
+        lock(&self.entries).parsed.contains_key(input.trim())
+    }
+
+    /// Number of cached expressions
+    pub fn len(&self) -> usize {
+        lock(&self.entries).parsed.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
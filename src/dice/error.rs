@@ -0,0 +1,54 @@
+//! [`DiceError`], the error type of the dice engine.
+//!
+//! Every fallible function in `parser`, `roller`, `aliases` and
+//! [`parse_and_roll`](super::parse_and_roll) returns one, so callers can tell
+//! what went wrong without matching on message text:
+//!
+//! | Variant               | When                                                   |
+//! |-----------------------|--------------------------------------------------------|
+//! | `InputTooLong`        | The expression is longer than `parser::MAX_INPUT_LENGTH` |
+//! | `ParseError`          | The expression, a modifier or a number can't be read   |
+//! | `ValidationError`     | It reads, but asks for something impossible (`0d6`, `k0`, a Witcher roll on a d6) |
+//! | `RollLimitExceeded`   | Too many dice, sides, rolls, or a result that overflows |
+//! | `DivisionByZero`      | `/ 0`, or dividing by dice that rolled 0               |
+//! | `AliasError`          | A `/macro` or `/alias` doesn't expand (loops, wrong arguments) |
+//!
+//! The `Display` text of each is the message shown to the user, unchanged
+//! from before the variants existed.  [`DiceError::kind`] names the variant
+//! for machine consumers such as the HTTP API.
+
+use thiserror::Error;
+
+/// Result of the dice engine's fallible functions
+pub type Result<T, E = DiceError> = std::result::Result<T, E>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DiceError {
+    #[error("Input too long: {length} characters (max {max})")]
+    InputTooLong { length: usize, max: usize },
+    #[error("{0}")]
+    ParseError(String),
+    #[error("{0}")]
+    ValidationError(String),
+    #[error("{0}")]
+    RollLimitExceeded(String),
+    /// `by_dice` when the divisor was rolled (`1d20 / 1d6`) rather than written
+    #[error("Cannot divide by zero{}", if *by_dice { " (dice result was 0)" } else { "" })]
+    DivisionByZero { by_dice: bool },
+    #[error("{0}")]
+    AliasError(String),
+}
+
+impl DiceError {
+    /// Stable snake_case name of the variant, e.g. `parse_error`
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::InputTooLong { .. } => "input_too_long",
+            Self::ParseError(_) => "parse_error",
+            Self::ValidationError(_) => "validation_error",
+            Self::RollLimitExceeded(_) => "roll_limit_exceeded",
+            Self::DivisionByZero { .. } => "division_by_zero",
+            Self::AliasError(_) => "alias_error",
+        }
+    }
+}
@@ -25,6 +25,7 @@
 //! 5. `roll.rs`               — add display / formatting logic
 //! 6. `game_systems_tests.rs` — add table-driven tests
 //! 7. `roll_syntax.md`        — document the new syntax
+//! 8. `version.rs`            — bump `GRAMMAR_REVISION`
 //!
 //! # Modifier ordering invariant
 //!
@@ -33,17 +34,26 @@
 //! change the ordering.
 
 pub mod aliases;
+pub mod cache;
+pub mod error;
+pub mod modifier_docs;
 pub mod parser;
+pub mod probability;
 pub mod rng;
 pub mod roller;
+pub mod trackers;
 
-use anyhow::Result;
+use crate::i18n::Language;
+use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::Serialize;
+use std::borrow::Cow;
 use std::fmt;
 
+pub use error::{DiceError, Result};
 pub use rng::{create_enhanced_rng, create_fast_rng, get_dice_rng};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DiceRoll {
     pub count: u32,
     pub sides: u32,
@@ -54,17 +64,42 @@ pub struct DiceRoll {
     pub simple: bool,
     pub no_results: bool,
     pub unsorted: bool,
+    pub drama: bool,                // Reveal the result gradually for suspense
+    pub pemdas: bool,               // Math with operator precedence and parentheses
+    pub embed: bool,                // Show the result as a Discord embed
+    pub full: bool,                 // Send output too long for a message as follow-ups
+    pub gm: bool,                   // Secret roll sent only to the server's GMs
+    pub language: Language,         // Language of the output keywords (`fr 2d6`)
+    pub faces: Option<Vec<String>>, // Labeled faces of `2d{yes,no,maybe}`, in order
+    pub pool: Option<Vec<u32>>,     // Die size of each die of a mixed pool `{d8+d6+d4}`
     pub original_expression: Option<String>, // Store the original expression
 }
 
-#[derive(Debug, Clone)]
+impl DiceRoll {
+    /// Dice rolled before any explode or reroll: the main dice plus any
+    /// added with `+`, `-`, `*` or `/`
+    pub fn dice_count(&self) -> u32 {
+        self.modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                Modifier::AddDice(extra)
+                | Modifier::SubtractDice(extra)
+                | Modifier::MultiplyDice(extra)
+                | Modifier::DivideDice(extra) => extra.dice_count(),
+                _ => 0,
+            })
+            .fold(self.count, u32::saturating_add)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub enum HeroSystemType {
     Normal,  // hsn - normal damage
     Killing, // hsk - killing damage
     Hit,     // hsh - to hit roll (3d6 roll-under)
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum LaserFeelingsType {
     Lasers,   // Roll <= target for success
     Feelings, // Roll >= target for success
@@ -79,7 +114,7 @@ impl fmt::Display for LaserFeelingsType {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub enum Modifier {
     Add(i32),
     Subtract(i32),
@@ -87,12 +122,17 @@ pub enum Modifier {
     Divide(i32),
     Explode(Option<u32>),                   // e or e#
     ExplodeIndefinite(Option<u32>),         // ie or ie#
+    ExplodeTotal(u32),                      // te# - roll the pool again while its total is >= #
+    Compound(Option<u32>),                  // ke or ke# - explosions add into the die that exploded
+    Penetrate(Option<u32>),                 // kp or kp# - explode with each extra die one lower
     Drop(u32),                              // d#
     KeepHigh(u32),                          // k#
     KeepLow(u32),                           // kl#
     KeepMiddle(u32),                        // km#
     Reroll(u32),                            // r#
     RerollIndefinite(u32),                  // ir#
+    RerollFailed,                           // rfail - reroll dice that missed the target once
+    MaxSuccesses(u32),                      // maxs# - cap counted successes, noting the overflow
     Target(u32),                            // t#  - count successes >= target
     RerollGreater(u32),                     // rg# - reroll >= threshold once
     RerollGreaterIndefinite(u32),           // irg# - reroll >= threshold indefinitely
@@ -106,6 +146,8 @@ pub enum Modifier {
     SubtractDice(DiceRoll),                 // Subtract dice result
     MultiplyDice(DiceRoll),
     DivideDice(DiceRoll),
+    OpenGroup,                          // `(` in a `pemdas` roll, before the next operand
+    CloseGroup,                         // `)` in a `pemdas` roll
     WrathGlory(Option<u32>, bool, u32), // Wrath & Glory: (difficulty, use_total_instead_of_successes)
     Godbound(bool),                     // gb (false) or gbs (true for straight damage)
     HeroSystem(HeroSystemType),         // Hero System damage/hit calculations
@@ -119,8 +161,12 @@ pub enum Modifier {
     Witcher,
     CypherSystem(u32),
     BraveNewWorld(u32),
-    ConanSkill(u32),  // conan, conan3, conan4, conan5 - d20 skill rolls
-    ConanCombat(u32), // cd, cd4, cd5 - combat dice interpretation
+    ConanSkill(u32),   // conan, conan3, conan4, conan5 - d20 skill rolls
+    ConanCombat(u32),  // cd, cd4, cd5 - combat dice interpretation
+    TargetNumber(u32), // tn# - 2d20 target number: each d20 at or under it is a success
+    Focus(u32),        // focus# - 2d20 focus: d20s at or under it score two successes
+    Difficulty(u32),   // diff# - 2d20 difficulty: successes needed, the rest is momentum
+    Complication(u32), // comp# - 2d20 complication range: the top # faces are complications
     Silhouette(u32),
     VampireMasquerade5(u32, u32),
     LaserFeelings(u32, u32, LaserFeelingsType), // (dice_count, target, roll_type)
@@ -128,22 +174,512 @@ pub enum Modifier {
     AlienStress(u32), // Stress dice (count 6s, track 1s for panic, stress level)
     ForgedDark,
     ForgedDarkZero,
-    Daggerheart,                   // Daggerheart player roll (2d12 Hope/Fear)
-    WildWorlds(Option<u32>),       // Wild Worlds RPG: None=basic, Some(n)=cut n highest dice
+    Daggerheart,                        // Daggerheart player roll (2d12 Hope/Fear)
+    WildWorlds(Option<u32>),            // Wild Worlds RPG: None=basic, Some(n)=cut n highest dice
     Mothership(Option<u32>, bool), // Mothership RPG: (stat_target, is_advantage) - roll-under with doubles as crits
+    MothershipPanic(u32),          // Mothership panic check: d20 against the current stress
     MutantsMasterminds,            // Mutants & Masterminds degree system
     PlotDie,                       // Plotweaver system plot die
+    Genesys(NarrativePool),        // gen - Genesys / Star Wars FFG narrative dice
+    L5r(L5rPool),                  // l5r - Legend of the Five Rings 5e ring and skill dice
+    Ironsworn,                     // iron - Ironsworn / Starforged action die vs two challenge dice
+    Oracle(Option<u32>), // oracle - Ironsworn d100 oracle: (lowest "yes" roll for the odds)
+    CoC(Option<u32>, i32), // coc - Call of Cthulhu 7e: (skill, bonus dice; negative for penalty dice)
+    Wfrp(u32), // wfrp - Warhammer Fantasy Roleplay 4e: d100 vs skill with Success Levels
+    Dragonbane(Option<u32>, i32, bool), // db - Dragonbane: (skill, 1 for a boon / -1 for a bane, pushed)
+    Gurps(u32),             // gurps - GURPS 3d6 success roll against the effective skill
+    YearZero(YearZeroPool), // fbl - Forbidden Lands base, skill, gear and artifact dice
+    BurningWheel(BurningWheelPool), // bw - Burning Wheel / Mouse Guard d6 pool against an obstacle
+    Matches,                // m - One-Roll Engine: group the pool into sets of matching dice
+    Cortex,                 // cortex - Cortex Prime: total the best two, keep an effect die
+    Bands(Vec<i32>), // bands[a,b] - classify the total: <= a failure, <= b partial, above success
+    TargetLadder(Vec<u32>), // t[90,70,50] - the highest tier the total reaches, highest first
+    Dcc,             // dcc - Dungeon Crawl Classics: fumble and spell failure notes on a natural 1
 }
 
-#[derive(Debug, Clone)]
+/// Dungeon Crawl Classics' dice chain, smallest first.  A `+1d` shift moves a
+/// die one step up it (d20 → d24).  A `dcc` roll must use a die on the chain;
+/// any die size still rolls without `dcc`, so `1d7` stays a plain d7.
+pub const DCC_DICE_CHAIN: [u32; 13] = [3, 4, 5, 6, 7, 8, 10, 12, 14, 16, 20, 24, 30];
+
+/// The dice of a Genesys / Star Wars FFG narrative pool, by type
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct NarrativePool {
+    pub boost: u32,       // b - d6, blue
+    pub ability: u32,     // a - d8, green
+    pub proficiency: u32, // p - d12, yellow
+    pub setback: u32,     // s - d6, black
+    pub difficulty: u32,  // d - d8, purple
+    pub challenge: u32,   // c - d12, red
+}
+
+impl NarrativePool {
+    pub fn dice_count(&self) -> u32 {
+        self.boost
+            + self.ability
+            + self.proficiency
+            + self.setback
+            + self.difficulty
+            + self.challenge
+    }
+}
+
+/// The symbols rolled on a narrative pool.  Successes cancel failures and
+/// advantages cancel threats; triumphs and despairs are never cancelled
+/// (though each also counts as a success or failure).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NarrativeResult {
+    pub faces: Vec<String>, // Each die as its colour and face, e.g. "🟩`SA`"
+    pub successes: i32,     // Net successes; negative for net failures
+    pub advantages: i32,    // Net advantages; negative for net threats
+    pub triumphs: u32,
+    pub despairs: u32,
+}
+
+impl NarrativeResult {
+    /// e.g. "**Success**: 2 successes, 1 advantage, 1 triumph"
+    fn summary(&self) -> String {
+        let count = |n: i64, singular: &str, plural: &str| {
+            format!("{n} {}", if n == 1 { singular } else { plural })
+        };
+        let mut parts = Vec::new();
+        match self.successes {
+            n if n > 0 => parts.push(count(n.into(), "success", "successes")),
+            n if n < 0 => parts.push(count((-n).into(), "failure", "failures")),
+            _ => {}
+        }
+        match self.advantages {
+            n if n > 0 => parts.push(count(n.into(), "advantage", "advantages")),
+            n if n < 0 => parts.push(count((-n).into(), "threat", "threats")),
+            _ => {}
+        }
+        if self.triumphs > 0 {
+            parts.push(count(self.triumphs.into(), "triumph", "triumphs"));
+        }
+        if self.despairs > 0 {
+            parts.push(count(self.despairs.into(), "despair", "despairs"));
+        }
+
+        let outcome = if self.successes > 0 {
+            "Success"
+        } else {
+            "Failure"
+        };
+        if parts.is_empty() {
+            format!("**{outcome}**")
+        } else {
+            format!("**{outcome}**: {}", parts.join(", "))
+        }
+    }
+}
+
+/// The dice of a Legend of the Five Rings 5e check: ring dice equal to the
+/// ring, which is also how many dice are kept, plus skill dice
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct L5rPool {
+    pub ring: u32,  // d6, black
+    pub skill: u32, // d12, white
+}
+
+/// The symbols kept from an L5R pool.  Explosive successes count as
+/// successes; each kept one added a die of its type, which is kept too
+/// unless it came up blank.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct L5rResult {
+    pub kept: Vec<String>,    // Each kept die as its type and face, e.g. "⚫`S!`"
+    pub dropped: Vec<String>, // Dice rolled but not kept
+    pub successes: u32,
+    pub opportunities: u32,
+    pub strife: u32,
+    pub explosions: u32, // Extra dice rolled for explosive successes
+}
+
+impl L5rResult {
+    /// e.g. "**3** successes, 1 opportunity, 2 strife"
+    fn summary(&self) -> String {
+        let word = |n: u32, singular: &'static str, plural: &'static str| {
+            if n == 1 { singular } else { plural }
+        };
+        let mut summary = format!(
+            "**{}** {}",
+            self.successes,
+            word(self.successes, "success", "successes")
+        );
+        if self.opportunities > 0 {
+            summary.push_str(&format!(
+                ", {} {}",
+                self.opportunities,
+                word(self.opportunities, "opportunity", "opportunities")
+            ));
+        }
+        if self.strife > 0 {
+            summary.push_str(&format!(", {} strife", self.strife));
+        }
+        summary
+    }
+}
+
+/// The dice of a Forbidden Lands (Year Zero) roll, by type
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct YearZeroPool {
+    pub base: u32,           // d6 for the attribute; 1s are banes
+    pub skill: u32,          // d6; 1s don't count
+    pub gear: u32,           // d6 for the gear bonus; 1s are banes
+    pub artifacts: Vec<u32>, // Sides of each artifact die: d8, d10 or d12, counted as gear
+    pub push: bool,          // Push the roll once, rerolling dice without a success or bane
+}
+
+impl YearZeroPool {
+    pub fn dice_count(&self) -> u32 {
+        self.base + self.skill + self.gear + self.artifacts.len() as u32
+    }
+}
+
+/// The dice rolled for a Forbidden Lands pool, with banes kept apart for
+/// the base and gear dice since a pushed roll hurts each differently
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct YearZeroResult {
+    pub base: Vec<i32>,
+    pub skill: Vec<i32>,
+    pub gear: Vec<i32>,
+    pub artifacts: Vec<(u32, i32)>, // (sides, roll) of each artifact die
+    pub successes: u32,
+    pub base_banes: u32,
+    pub gear_banes: u32, // Artifact dice showing 1 included
+    pub pushed: bool,
+}
+
+impl YearZeroResult {
+    /// Successes on one die: a 6 or more on a d6, and on artifact dice 1 for
+    /// 6-7, 2 for 8-9, 3 for 10-11 and 4 for a 12
+    pub fn die_successes(roll: i32) -> u32 {
+        if roll >= 6 { (roll as u32 - 4) / 2 } else { 0 }
+    }
+
+    /// Count the successes and banes of the dice
+    pub fn tally(&mut self) {
+        let d6_successes = |dice: &[i32]| dice.iter().filter(|&&roll| roll >= 6).count() as u32;
+        let ones = |dice: &[i32]| dice.iter().filter(|&&roll| roll == 1).count() as u32;
+        self.successes = d6_successes(&self.base)
+            + d6_successes(&self.skill)
+            + d6_successes(&self.gear)
+            + self
+                .artifacts
+                .iter()
+                .map(|&(_, roll)| Self::die_successes(roll))
+                .sum::<u32>();
+        self.base_banes = ones(&self.base);
+        self.gear_banes = ones(&self.gear)
+            + self
+                .artifacts
+                .iter()
+                .filter(|&&(_, roll)| roll == 1)
+                .count() as u32;
+    }
+
+    /// e.g. "Base `[6, 3, 1]` Skill `[4]` Gear `[2]` d10 `[8]`"
+    fn dice_display(&self) -> String {
+        let mut groups = Vec::new();
+        for (name, dice) in [
+            ("Base", &self.base),
+            ("Skill", &self.skill),
+            ("Gear", &self.gear),
+        ] {
+            if !dice.is_empty() {
+                let rolls: Vec<String> = dice.iter().map(|roll| roll.to_string()).collect();
+                groups.push(format!("{name} `[{}]`", rolls.join(", ")));
+            }
+        }
+        for (sides, roll) in &self.artifacts {
+            groups.push(format!("d{sides} `[{roll}]`"));
+        }
+        groups.join(" ")
+    }
+
+    /// e.g. "**3** successes, 1 base bane, 2 gear banes"
+    fn summary(&self) -> String {
+        let word = |n: u32, singular: &'static str, plural: &'static str| {
+            if n == 1 { singular } else { plural }
+        };
+        let mut summary = format!(
+            "**{}** {}",
+            self.successes,
+            word(self.successes, "success", "successes")
+        );
+        if self.base_banes > 0 {
+            summary.push_str(&format!(
+                ", {} base {}",
+                self.base_banes,
+                word(self.base_banes, "bane", "banes")
+            ));
+        }
+        if self.gear_banes > 0 {
+            summary.push_str(&format!(
+                ", {} gear {}",
+                self.gear_banes,
+                word(self.gear_banes, "bane", "banes")
+            ));
+        }
+        summary
+    }
+}
+
+/// A Burning Wheel (or Mouse Guard / Torchbearer) test: the exponent and
+/// helping dice, rolled against an optional obstacle
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct BurningWheelPool {
+    pub exponent: u32,         // d6 for the ability or skill
+    pub helping: u32,          // d6 from helpers, rolled with the exponent
+    pub obstacle: Option<u32>, // Successes needed to pass
+    pub open: bool,            // Sixes are open-ended: each rolls another die
+    pub luck: bool, // Fate spent on Luck: sixes become open-ended, or one traitor is rerolled
+}
+
+impl BurningWheelPool {
+    pub fn dice_count(&self) -> u32 {
+        self.exponent + self.helping
+    }
+}
+
+/// The dice rolled for a Burning Wheel test and how it went against the
+/// obstacle
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct BurningWheelResult {
+    pub exponent: Vec<i32>,
+    pub helping: Vec<i32>,
+    pub open_ended: Vec<i32>, // Extra dice rolled for open-ended sixes, in roll order
+    pub successes: u32,       // Dice showing 4 or more
+    pub obstacle: Option<u32>,
+    pub margin: Option<i32>, // Successes over the obstacle; negative when it was failed
+    pub passed: Option<bool>,
+}
+
+impl BurningWheelResult {
+    /// Count the successes and compare them with the obstacle
+    pub fn tally(&mut self) {
+        self.successes = self
+            .exponent
+            .iter()
+            .chain(&self.helping)
+            .chain(&self.open_ended)
+            .filter(|&&roll| roll >= 4)
+            .count() as u32;
+        self.margin = self
+            .obstacle
+            .map(|obstacle| self.successes as i32 - obstacle as i32);
+        self.passed = self.margin.map(|margin| margin >= 0);
+    }
+
+    /// e.g. "`[6, 4, 2]` Help `[5]` Open `[3]`"
+    fn dice_display(&self) -> String {
+        let list = |dice: &[i32]| {
+            let rolls: Vec<String> = dice.iter().map(|roll| roll.to_string()).collect();
+            format!("`[{}]`", rolls.join(", "))
+        };
+        let mut groups = vec![list(&self.exponent)];
+        if !self.helping.is_empty() {
+            groups.push(format!("Help {}", list(&self.helping)));
+        }
+        if !self.open_ended.is_empty() {
+            groups.push(format!("Open {}", list(&self.open_ended)));
+        }
+        groups.join(" ")
+    }
+
+    /// e.g. "**3** successes vs Ob 2 — **PASSED** by 1"
+    fn summary(&self) -> String {
+        let word = if self.successes == 1 {
+            "success"
+        } else {
+            "successes"
+        };
+        let mut summary = format!("**{}** {word}", self.successes);
+        if let (Some(obstacle), Some(margin)) = (self.obstacle, self.margin) {
+            let outcome = if margin >= 0 { "PASSED" } else { "FAILED" };
+            summary.push_str(&format!(
+                " vs Ob {obstacle} — **{outcome}** by {}",
+                margin.abs()
+            ));
+        }
+        summary
+    }
+}
+
+/// One set of matching dice in a One-Roll Engine pool: `width` dice all
+/// showing `height`, written `3x7`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MatchSet {
+    pub width: u32,
+    pub height: i32,
+}
+
+/// A pool grouped into matched sets, widest first, and the unmatched dice
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MatchResult {
+    pub sets: Vec<MatchSet>,
+    pub loose: Vec<i32>, // Dice that matched no other, high to low
+}
+
+impl MatchResult {
+    /// Group `rolls` into sets of two or more equal dice
+    pub fn from_rolls(rolls: &[i32]) -> Self {
+        let mut counts = std::collections::BTreeMap::new();
+        for &roll in rolls {
+            *counts.entry(roll).or_insert(0u32) += 1;
+        }
+
+        let mut result = MatchResult::default();
+        for (&height, &width) in counts.iter().rev() {
+            if width >= 2 {
+                result.sets.push(MatchSet { width, height });
+            } else {
+                result.loose.push(height);
+            }
+        }
+        // Widest first; equal widths keep the higher set first
+        result.sets.sort_by_key(|set| std::cmp::Reverse(set.width));
+        result
+    }
+
+    /// e.g. "**3x7**, **2x4** | Loose: `[9, 1]`" or "**No matches**"
+    fn summary(&self) -> String {
+        let mut summary = if self.sets.is_empty() {
+            "**No matches**".to_string()
+        } else {
+            self.sets
+                .iter()
+                .map(|set| format!("**{}x{}**", set.width, set.height))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        if !self.loose.is_empty() {
+            let loose: Vec<String> = self.loose.iter().map(|roll| roll.to_string()).collect();
+            summary.push_str(&format!(" | Loose: `[{}]`", loose.join(", ")));
+        }
+        summary
+    }
+}
+
+/// One die of a Cortex Prime pool and what it rolled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CortexDie {
+    pub sides: u32,
+    pub roll: i32,
+}
+
+/// A Cortex Prime pool: the best two dice make the total and the largest
+/// die left over is the effect die.  Dice showing 1 are hitches and count
+/// for neither.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CortexResult {
+    pub dice: Vec<CortexDie>, // Highest roll first, so hitches are last
+    pub total_dice: usize,    // How many of `dice` (from the front) make the total
+    pub effect: u32,          // Size of the effect die; d4 when no die is left over
+    pub hitches: u32,
+}
+
+impl CortexResult {
+    /// Pick the total and effect die from `dice`
+    pub fn from_dice(mut dice: Vec<CortexDie>) -> Self {
+        // Highest first, so hitches come last; on equal rolls the smaller die
+        // goes into the total, leaving the larger for the effect
+        dice.sort_by_key(|die| (std::cmp::Reverse(die.roll), die.sides));
+        let hitches = dice.iter().filter(|die| die.roll == 1).count() as u32;
+        let total_dice = (dice.len() - hitches as usize).min(2);
+        let effect = dice[total_dice..]
+            .iter()
+            .filter(|die| die.roll != 1)
+            .map(|die| die.sides)
+            .max()
+            .unwrap_or(4);
+        CortexResult {
+            dice,
+            total_dice,
+            effect,
+            hitches,
+        }
+    }
+
+    pub fn total(&self) -> i32 {
+        self.dice[..self.total_dice]
+            .iter()
+            .map(|die| die.roll)
+            .sum()
+    }
+
+    /// Every die came up a hitch
+    pub fn botched(&self) -> bool {
+        self.total_dice == 0
+    }
+
+    /// e.g. "`[d10:9, d8:7]` `[d8:3]` Hitches `[d6:1]`": the total's dice,
+    /// then the rest
+    fn dice_display(&self) -> String {
+        let list = |dice: &[CortexDie]| {
+            let dice: Vec<String> = dice
+                .iter()
+                .map(|die| format!("d{}:{}", die.sides, die.roll))
+                .collect();
+            format!("`[{}]`", dice.join(", "))
+        };
+        let rest = self.dice.len() - self.hitches as usize;
+        let mut groups = Vec::new();
+        if self.total_dice > 0 {
+            groups.push(list(&self.dice[..self.total_dice]));
+        }
+        if rest > self.total_dice {
+            groups.push(list(&self.dice[self.total_dice..rest]));
+        }
+        if self.hitches > 0 {
+            groups.push(format!("Hitches {}", list(&self.dice[rest..])));
+        }
+        groups.join(" ")
+    }
+
+    /// e.g. "**16** | Effect: **d8** | Hitches: 1" or "**BOTCH** | Hitches: 3"
+    fn summary(&self) -> String {
+        let mut summary = if self.botched() {
+            "**BOTCH**".to_string()
+        } else {
+            format!("**{}** | Effect: **d{}**", self.total(), self.effect)
+        };
+        if self.hitches > 0 {
+            summary.push_str(&format!(" | Hitches: {}", self.hitches));
+        }
+        summary
+    }
+}
+
+/// A Warhammer Fantasy Roleplay 4e test: whether the d100 passed the skill
+/// and by how many Success Levels
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WfrpResult {
+    pub skill: u32,
+    pub success: bool,
+    pub success_levels: i32, // Tens digit of the skill minus tens digit of the roll
+    pub double: bool,        // 11, 22, … 00: a critical on a success, a fumble on a failure
+}
+
+impl WfrpResult {
+    /// Success Levels with their sign; a failure by less than ten is "-0"
+    pub fn sl_text(&self) -> String {
+        match self.success_levels {
+            0 if !self.success => "-0".to_string(),
+            sl => format!("{sl:+}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct DiceGroup {
+    #[serde(skip)]
     pub _description: String, // Currently unused but kept for future debugging
     pub rolls: Vec<i32>,
     pub dropped_rolls: Vec<i32>, // Dropped dice for strikethrough display
     pub modifier_type: String,   // "base", "add", "subtract"
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RollResult {
     pub individual_rolls: Vec<i32>,
     pub kept_rolls: Vec<i32>,
@@ -160,6 +696,11 @@ pub struct RollResult {
     pub simple: bool,                        // Add simple flag to control output formatting
     pub no_results: bool,                    // Add no_results flag
     pub private: bool,                       // Add private flag for ephemeral responses
+    pub drama: bool,                         // Reveal dice one group at a time
+    pub embed: bool,                         // Show as a Discord embed (`e` flag)
+    pub full: bool,                          // Never shortened, sent in parts (`full` flag)
+    pub gm: bool,                            // Secret roll DMed to the GMs (`gm` flag)
+    pub language: Language,                  // Language of the output keywords
     pub godbound_damage: Option<i32>,        // Store converted Godbound damage
     pub fudge_symbols: Option<Vec<String>>,  // Store Fudge dice symbols
     // Wrath & Glory specific fields
@@ -175,6 +716,17 @@ pub struct RollResult {
     pub fitd_result: Option<String>,  // Description of what the outcome means
     pub fitd_highest_die: Option<i32>, // The key die used for the result
     pub plot_symbols: Option<Vec<String>>, // Store Plot dice symbols
+    pub narrative: Option<NarrativeResult>, // Genesys / Star Wars FFG symbols
+    pub l5r: Option<L5rResult>,       // Legend of the Five Rings 5e symbols
+    pub wfrp: Option<WfrpResult>,     // Warhammer Fantasy Roleplay 4e Success Levels
+    pub year_zero: Option<YearZeroResult>, // Forbidden Lands dice by pool, with banes
+    pub burning_wheel: Option<BurningWheelResult>, // Burning Wheel dice, margin and pass/fail
+    pub matches: Option<MatchResult>, // One-Roll Engine sets (width x height) and loose dice
+    pub cortex: Option<CortexResult>, // Cortex Prime dice, total, effect die and hitches
+    pub face_labels: Option<Vec<String>>, // Faces rolled on labeled dice, in roll order
+    pub ironsworn_outcome: Option<String>, // "STRONG HIT", "WEAK HIT", "MISS", or an oracle's "YES" / "NO"
+    pub ironsworn_challenge: Option<Vec<i32>>, // The two challenge dice of an action roll
+    pub preserve_order: bool, // Dice order is meaningful (ul flag, wrath dice, trait/wild die); never sort
 }
 
 impl RollResult {
@@ -185,11 +737,53 @@ impl RollResult {
             return format!("`[{}]`", symbols.join(", "));
         }
 
+        // Narrative dice show their symbols, not numbers
+        if let Some(ref narrative) = self.narrative {
+            return narrative.faces.join(" ");
+        }
+
+        // L5R dice show their symbols, with the unkept dice struck through
+        if let Some(ref l5r) = self.l5r {
+            let kept = l5r.kept.join(" ");
+            if l5r.dropped.is_empty() {
+                return kept;
+            }
+            return format!("{kept} ~~{}~~", l5r.dropped.join(" "));
+        }
+
+        // Forbidden Lands dice are shown by pool
+        if let Some(ref year_zero) = self.year_zero {
+            return year_zero.dice_display();
+        }
+
+        // Burning Wheel helping and open-ended dice are shown apart
+        if let Some(ref burning_wheel) = self.burning_wheel {
+            return burning_wheel.dice_display();
+        }
+
+        // Cortex Prime dice are shown by size, grouped by what they count for
+        if let Some(ref cortex) = self.cortex {
+            return cortex.dice_display();
+        }
+
         // Special handling for a Plot die
         if let Some(ref symbols) = self.plot_symbols {
             return format!("`[{}]`", symbols.join(", "));
         }
 
+        // Labeled dice show the faces that came up
+        if let Some(ref labels) = self.face_labels {
+            return format!("`[{}]`", labels.join(", "));
+        }
+
+        // Math-only expressions ("4*7+2") have no dice to show
+        if self.individual_rolls.is_empty()
+            && self.dropped_rolls.is_empty()
+            && self.dice_groups.iter().all(|group| group.rolls.is_empty())
+        {
+            return String::new();
+        }
+
         // When there are dropped dice, always prioritize showing all original dice
         // This addresses the user's complaint that dice groups only show kept dice
         if !self.dropped_rolls.is_empty() {
@@ -317,28 +911,118 @@ impl RollResult {
             );
         }
 
+        if let Some(narrative) = &self.narrative {
+            return narrative.summary();
+        }
+
+        if let Some(l5r) = &self.l5r {
+            return l5r.summary();
+        }
+
+        if let Some(year_zero) = &self.year_zero {
+            return year_zero.summary();
+        }
+
+        if let Some(burning_wheel) = &self.burning_wheel {
+            return burning_wheel.summary();
+        }
+
+        if let Some(matches) = &self.matches {
+            return matches.summary();
+        }
+
+        if let Some(cortex) = &self.cortex {
+            return cortex.summary();
+        }
+
+        if let Some(wfrp) = &self.wfrp {
+            return format!("**{}** — SL **{}**", self.total, wfrp.sl_text());
+        }
+
+        if self.face_labels.is_some() {
+            return self.format_face_counts();
+        }
+
+        // Ironsworn action score against its challenge dice, or an oracle's answer
+        if let Some(outcome) = &self.ironsworn_outcome {
+            return match &self.ironsworn_challenge {
+                Some(challenge) => format!(
+                    "**{}** vs `[{}]` — **{outcome}**",
+                    self.total,
+                    challenge
+                        .iter()
+                        .map(|die| die.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                None => format!("**{}** — **{outcome}**", self.total),
+            };
+        }
+
         // Forged in the Dark result formatting
         if let (Some(outcome), Some(highest_die)) = (&self.fitd_outcome, self.fitd_highest_die) {
             return format!("**{outcome}** (die: `{highest_die}`)");
         }
 
+        // Outcome bands (bands[a,b]) follow the value they classify
+        match &self.fitd_outcome {
+            Some(outcome) => format!("{} — **{outcome}**", self.format_counted_value()),
+            None => self.format_counted_value(),
+        }
+    }
+
+    /// Faces of labeled dice with how often each came up, in face order:
+    /// `**Heads** ×2, **Tails** ×1`, or just `**Heads**` for a single die
+    fn format_face_counts(&self) -> String {
+        let Some(labels) = &self.face_labels else {
+            return String::new();
+        };
+        if labels.len() == 1 {
+            return format!("**{}**", labels[0]);
+        }
+
+        // Rolls are face numbers, so counting by roll keeps the faces in order
+        let mut counts: Vec<(i32, &str, usize)> = Vec::new();
+        for (&roll, label) in self.individual_rolls.iter().zip(labels) {
+            match counts.iter_mut().find(|(face, _, _)| *face == roll) {
+                Some((_, _, count)) => *count += 1,
+                None => counts.push((roll, label, 1)),
+            }
+        }
+        counts.sort_by_key(|&(face, _, _)| face);
+        counts
+            .iter()
+            .map(|(_, label, count)| format!("**{label}** ×{count}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Format damage, successes or the total
+    fn format_counted_value(&self) -> String {
+        let keywords = self.language.keywords();
         if let Some(gb_damage) = self.godbound_damage {
             format!("**{gb_damage}** damage")
         } else if let Some(successes) = self.successes {
-            let mut result = format!("**{successes}** successes");
+            let mut result = format!("**{successes}** {}", keywords.successes);
             if let Some(failures) = self.failures
                 && failures > 0
             {
-                result.push_str(&format!(" ({failures} failures)"));
+                result.push_str(&format!(" ({failures} {})", keywords.failures));
             }
             if let Some(botches) = self.botches
                 && botches > 0
             {
-                result.push_str(&format!(" ({botches} botches)"));
+                result.push_str(&format!(" ({botches} {})", keywords.botches));
             }
             result
         } else if let Some(botches) = self.botches {
-            format!("**{}** total, **{}** botches", self.total, botches)
+            format!(
+                "**{}** {}, **{}** {}",
+                self.total,
+                keywords.total.to_lowercase(),
+                botches,
+                keywords.botches
+            )
         } else {
             format!("**{}**", self.total)
         }
@@ -355,7 +1039,7 @@ impl RollResult {
         if show_dice {
             let dice_display = self.format_dice_display();
             if !dice_display.is_empty() {
-                output.push_str("Roll: ");
+                output.push_str(&format!("{}: ", self.language.keywords().roll));
                 output.push_str(&dice_display);
                 output.push_str(&self.format_dropped_dice());
             }
@@ -379,7 +1063,10 @@ impl RollResult {
         if !self.suppress_comment
             && let Some(comment) = &self.comment
         {
-            output.push_str(&format!(" Reason: `{comment}`"));
+            output.push_str(&format!(
+                " {}: `{comment}`",
+                self.language.keywords().reason
+            ));
         }
 
         for note in &self.notes {
@@ -387,6 +1074,22 @@ impl RollResult {
         }
     }
 
+    /// The dice shown for the roll (dropped dice struck through), or an
+    /// empty string when there are none to show
+    pub fn dice_breakdown(&self) -> String {
+        let dice_display = self.format_dice_display();
+        if dice_display.is_empty() {
+            dice_display
+        } else {
+            format!("{dice_display}{}", self.format_dropped_dice())
+        }
+    }
+
+    /// The value the roll shows after `=`: total, successes or outcome
+    pub fn result_value(&self) -> String {
+        self.format_result_value()
+    }
+
     /// Create a simplified copy of the roll result with suppressed comment
     pub fn create_simplified(&self) -> RollResult {
         let mut simplified = self.clone();
@@ -418,7 +1121,7 @@ impl fmt::Display for RollResult {
 }
 
 pub fn parse_and_roll(input: &str) -> Result<Vec<RollResult>> {
-    let dice_expressions = crate::dice::parser::parse_dice_string(input)?;
+    let dice_expressions = cache::parse(input)?;
     let mut results = Vec::new();
 
     for dice in dice_expressions {
@@ -429,6 +1132,19 @@ pub fn parse_and_roll(input: &str) -> Result<Vec<RollResult>> {
     Ok(results)
 }
 
+/// [`parse_and_roll`] drawing every die from `rng`, for reproducible session rolls
+pub fn parse_and_roll_with_rng(input: &str, rng: &mut impl rand::Rng) -> Result<Vec<RollResult>> {
+    let dice_expressions = cache::parse(input)?;
+    let mut results = Vec::new();
+
+    for dice in dice_expressions {
+        let result = crate::dice::roller::roll_dice_with_rng(dice, rng)?;
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
 pub fn format_multiple_results(results: &[RollResult]) -> String {
     if results.is_empty() {
         return "No dice to roll!".to_string();
@@ -465,6 +1181,10 @@ fn format_roll_set_results(results: &[RollResult]) -> String {
 
     // Extract the comment from the first roll to show once for the entire set
     let set_comment = results.first().and_then(|r| r.comment.as_ref());
+    let keywords = results
+        .first()
+        .map_or(Language::default(), |r| r.language)
+        .keywords();
 
     for (i, result) in results.iter().enumerate() {
         if i > 0 {
@@ -481,18 +1201,21 @@ fn format_roll_set_results(results: &[RollResult]) -> String {
         total_sum += calculate_result_value(result);
     }
 
-    output.push_str(&format!("\n**Total: {total_sum}**"));
+    // Labeled dice have no total to add up
+    if !results.iter().all(|r| r.face_labels.is_some()) {
+        output.push_str(&format!("\n**{}: {total_sum}**", keywords.total));
+    }
 
     // Add the comment once for the entire set
     if let Some(comment) = set_comment {
-        output.push_str(&format!(" Reason: `{comment}`"));
+        output.push_str(&format!(" {}: `{comment}`", keywords.reason));
     }
 
     output
 }
 
 /// Helper function to calculate the appropriate value for a result (reducing duplication)
-fn calculate_result_value(result: &RollResult) -> i32 {
+pub fn calculate_result_value(result: &RollResult) -> i32 {
     if let Some(gb_damage) = result.godbound_damage {
         gb_damage
     } else if let Some(successes) = result.successes {
@@ -523,7 +1246,10 @@ fn format_semicolon_separated_results(results: &[RollResult]) -> String {
         // Show the request for each individual roll (without /roll prefix and without comment)
         if let Some(expr) = &result.original_expression {
             let clean_expr = strip_comment_from_expression(expr);
-            format!("Request: `{clean_expr}` {result}")
+            format!(
+                "{}: `{clean_expr}` {result}",
+                result.language.keywords().request
+            )
         } else {
             result.to_string()
         }
@@ -535,14 +1261,126 @@ fn format_standard_multiple_results(results: &[RollResult]) -> String {
     format_results_with_separator(results, |result| result.to_string())
 }
 
+/// One `[[dice]]` roll inside a sentence, e.g. `I hit for [[2d6]] damage`
+#[derive(Debug, Clone)]
+pub struct InlineRoll {
+    pub expression: String,
+    pub results: Vec<RollResult>,
+}
+
+impl InlineRoll {
+    /// What replaces the brackets: the value each result shows after `=`
+    pub fn value(&self) -> String {
+        self.results
+            .iter()
+            .map(RollResult::result_value)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// The sentence with every inline roll replaced by its value.  `pieces` is
+/// the text around the rolls, one more piece than there are rolls.
+pub fn format_inline_sentence(pieces: &[String], rolls: &[InlineRoll]) -> String {
+    let mut sentence = String::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        sentence.push_str(piece);
+        if let Some(roll) = rolls.get(i) {
+            sentence.push_str(&roll.value());
+        }
+    }
+    sentence.trim().to_string()
+}
+
+/// The sentence followed by one line per inline roll with its dice:
+///
+/// ```text
+/// I attack the orc **17** for **7** damage
+/// `1d20+5` Roll: `[12]` = **17**
+/// `2d6` Roll: `[3, 4]` = **7**
+/// ```
+pub fn format_inline_results(pieces: &[String], rolls: &[InlineRoll]) -> String {
+    let mut output = format_inline_sentence(pieces, rolls);
+    for roll in rolls {
+        let mut results = roll.results.clone();
+        for result in &mut results {
+            result.suppress_comment = true;
+        }
+        output.push_str(&format!(
+            "\n`{}` {}",
+            roll.expression,
+            format_multiple_results(&results).replace('\n', " · ")
+        ));
+    }
+    output
+}
+
+// Everything after ! including the !
+static COMMENT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\s*!\s*.*$").expect("Failed to compile COMMENT_REGEX"));
+
 /// Extract the dice expression without the comment portion
 fn strip_comment_from_expression(expr: &str) -> String {
-    // Use regex to remove comment (everything after ! including the !)
-    let comment_regex = Regex::new(r"\s*!\s*.*$").unwrap();
-    comment_regex.replace(expr, "").trim().to_string()
+    COMMENT_REGEX.replace(expr, "").trim().to_string()
 }
 
-const DISCORD_MESSAGE_LIMIT: usize = 2000;
+/// Discord's limit on a message's content, in characters
+pub const DISCORD_MESSAGE_LIMIT: usize = 2000;
+
+/// Maximum number of intermediate frames shown for a `drama` roll
+const MAX_REVEAL_FRAMES: usize = 4;
+
+/// Build the intermediate frames for a `drama` roll.
+///
+/// A single roll is revealed one dice group at a time with the total held
+/// back; multiple rolls are revealed one result at a time.  The final,
+/// complete output is not included.
+pub fn format_reveal_frames(results: &[RollResult]) -> Vec<String> {
+    let mut frames = Vec::new();
+
+    if results.len() == 1 {
+        let result = &results[0];
+        if result.simple {
+            return frames;
+        }
+
+        let group_count = result.dice_groups.len().max(1);
+        for shown in 1..=group_count {
+            let mut partial = result.clone();
+            partial.dice_groups.truncate(shown);
+            partial.notes.clear();
+            partial.suppress_comment = true;
+            partial.no_results = true;
+
+            let dice_display = partial.to_string();
+            if !dice_display.is_empty() {
+                frames.push(format!("{dice_display} = …"));
+            }
+        }
+    } else {
+        let is_semicolon_separated = results.iter().any(|r| r.original_expression.is_some())
+            && !results
+                .iter()
+                .all(|r| r.label.as_ref().is_some_and(|l| l.starts_with("Set ")));
+
+        for shown in 1..results.len() {
+            let revealed = if is_semicolon_separated {
+                format_semicolon_separated_results(&results[..shown])
+            } else {
+                format_results_with_separator(&results[..shown], |result| {
+                    let mut display_result = result.clone();
+                    display_result.suppress_comment = true;
+                    display_result.to_string()
+                })
+            };
+            frames.push(format!("{revealed}\n…"));
+        }
+    }
+
+    frames.retain(|frame| frame.len() <= DISCORD_MESSAGE_LIMIT);
+    frames.truncate(MAX_REVEAL_FRAMES);
+    frames
+}
 
 pub fn format_multiple_results_with_limit(results: &[RollResult]) -> String {
     let full_output = format_multiple_results(results);
@@ -601,6 +1439,37 @@ pub fn format_multiple_results_with_limit(results: &[RollResult]) -> String {
     emergency_output
 }
 
+/// Split `output` (from [`format_multiple_results`]) into messages of at
+/// most `limit` bytes for posting one after another.  Messages break only
+/// between lines, so each roll set or `;` roll stays whole; a single line
+/// longer than `limit` is cut short.
+pub fn split_results_output(output: &str, limit: usize) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut current = String::new();
+    for line in output.lines() {
+        let line = if line.len() > limit {
+            let mut end = limit.saturating_sub(3);
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            Cow::Owned(format!("{}...", &line[..end]))
+        } else {
+            Cow::Borrowed(line)
+        };
+        if !current.is_empty() && current.len() + 1 + line.len() > limit {
+            messages.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(&line);
+    }
+    if !current.is_empty() {
+        messages.push(current);
+    }
+    messages
+}
+
 /// Format ultra-compact results (totals only, minimal text)
 fn format_ultra_compact_results(results: &[RollResult]) -> String {
     if results.is_empty() {
@@ -612,7 +1481,7 @@ fn format_ultra_compact_results(results: &[RollResult]) -> String {
         if let Some(gb_damage) = result.godbound_damage {
             format!("**{gb_damage}** damage")
         } else if let Some(successes) = result.successes {
-            format!("**{successes}** successes")
+            format!("**{successes}** {}", result.language.keywords().successes)
         } else {
             format!("**{}**", result.total)
         }
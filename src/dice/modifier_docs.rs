@@ -0,0 +1,290 @@
+//! Reference entries for the generic dice modifiers, shown by
+//! `/help modifier:<token>`.
+//!
+//! Each entry documents one parser token (the prefix matched in
+//! `parser::parse_single_modifier`).  A lookup ignores case, numbers, spaces
+//! and brackets, so `ie`, `ie6`, `IE` and `t7 ds10` all find their entry:
+//! `t7ds10` is looked up as `tds`, `bands[6,9]` as `bands`.
+//!
+//! When a modifier is added to the parser, add its entry here too; the
+//! integration tests roll every example, so an entry whose examples stop
+//! parsing fails them.
+
+/// How one modifier token behaves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModifierDoc {
+    /// The token with its numbers removed, e.g. `ie` for `ie6`
+    pub token: &'static str,
+    /// The written forms, e.g. `ie` or `ie#`
+    pub syntax: &'static str,
+    pub name: &'static str,
+    pub summary: &'static str,
+    /// Example expressions and what they do
+    pub examples: &'static [(&'static str, &'static str)],
+    /// Tokens of related modifiers
+    pub see_also: &'static [&'static str],
+}
+
+pub const MODIFIER_DOCS: &[ModifierDoc] = &[
+    ModifierDoc {
+        token: "e",
+        syntax: "e or e#",
+        name: "Explode",
+        summary: "Each die that rolls # or higher (its highest face without a number) adds one extra die. Extra dice don't explode again.",
+        examples: &[
+            ("3d6 e6", "Roll 3d6, one extra die for each 6"),
+            ("2d10 e", "Explode on 10s"),
+        ],
+        see_also: &["ie", "ke", "kp", "te"],
+    },
+    ModifierDoc {
+        token: "ie",
+        syntax: "ie or ie#",
+        name: "Explode indefinitely",
+        summary: "Like `e`, but extra dice explode again for as long as they keep rolling # or higher.",
+        examples: &[
+            ("4d6 ie6", "Roll 4d6, keep adding dice while 6s come up"),
+            ("4d10 t8 ie10", "Chronicles of Darkness style 10-again"),
+        ],
+        see_also: &["e", "ke", "kp"],
+    },
+    ModifierDoc {
+        token: "ke",
+        syntax: "ke or ke#",
+        name: "Compounding explode",
+        summary: "Explosions add into the die that exploded instead of showing as new dice, so `1d6 ke` can show a 14. Stops after 100 extra dice.",
+        examples: &[("1d6 ke", "A d6 that keeps growing on 6s")],
+        see_also: &["e", "ie", "kp"],
+    },
+    ModifierDoc {
+        token: "kp",
+        syntax: "kp or kp#",
+        name: "Penetrating explode",
+        summary: "HackMaster style: each extra die counts one lower but keeps exploding on its natural roll. Stops after 100 extra dice.",
+        examples: &[("2d6 kp", "Penetrating 2d6")],
+        see_also: &["e", "ke"],
+    },
+    ModifierDoc {
+        token: "te",
+        syntax: "te#",
+        name: "Exploding total",
+        summary: "While the pool totals # or more, roll the whole pool again and add it, up to 20 times. # must be above the number of dice.",
+        examples: &[("2d6 te10", "Roll 2d6 again each time the total reaches 10")],
+        see_also: &["e", "ie"],
+    },
+    ModifierDoc {
+        token: "k",
+        syntax: "k#",
+        name: "Keep highest",
+        summary: "Keep the # highest dice; the rest are shown struck through.",
+        examples: &[
+            ("4d6 k3", "Ability score: 4d6, keep the best 3"),
+            ("2d20 k1", "Advantage"),
+        ],
+        see_also: &["kl", "km", "d"],
+    },
+    ModifierDoc {
+        token: "kl",
+        syntax: "kl#",
+        name: "Keep lowest",
+        summary: "Keep the # lowest dice.",
+        examples: &[("2d20 kl1", "Disadvantage")],
+        see_also: &["k", "km", "d"],
+    },
+    ModifierDoc {
+        token: "km",
+        syntax: "km#",
+        name: "Keep middle",
+        summary: "Keep the # middle dice, dropping evenly from the top and bottom.",
+        examples: &[("3d20 km1", "The middle of three d20s")],
+        see_also: &["k", "kl"],
+    },
+    ModifierDoc {
+        token: "d",
+        syntax: "d#",
+        name: "Drop lowest",
+        summary: "Drop the # lowest dice. Dropped dice are never exploded.",
+        examples: &[("4d6 d1", "Roll 4d6, drop the lowest")],
+        see_also: &["k", "kl"],
+    },
+    ModifierDoc {
+        token: "r",
+        syntax: "r#",
+        name: "Reroll low once",
+        summary: "Reroll each die showing # or less, once; the new roll stands.",
+        examples: &[(
+            "2d6 r2",
+            "Great Weapon Fighting style: reroll 1s and 2s once",
+        )],
+        see_also: &["ir", "rg", "rfail"],
+    },
+    ModifierDoc {
+        token: "ir",
+        syntax: "ir#",
+        name: "Reroll low indefinitely",
+        summary: "Reroll each die showing # or less until it rolls higher.",
+        examples: &[("4d6 ir1", "4d6 that never shows a 1")],
+        see_also: &["r", "irg"],
+    },
+    ModifierDoc {
+        token: "rg",
+        syntax: "rg#",
+        name: "Reroll high once",
+        summary: "Reroll each die showing # or more, once.",
+        examples: &[("3d10 rg9", "Reroll 9s and 10s once")],
+        see_also: &["irg", "r"],
+    },
+    ModifierDoc {
+        token: "irg",
+        syntax: "irg#",
+        name: "Reroll high indefinitely",
+        summary: "Reroll each die showing # or more until it rolls lower.",
+        examples: &[("3d10 irg9", "3d10 that never shows a 9 or 10")],
+        see_also: &["rg", "ir"],
+    },
+    ModifierDoc {
+        token: "rfail",
+        syntax: "rfail",
+        name: "Reroll failures",
+        summary: "Reroll every die that missed the success target, once. Needs a `t#` or `tl#` target; the note shows each failed die and its reroll.",
+        examples: &[("8d10 t8 rfail", "Chronicles of Darkness rote action")],
+        see_also: &["t", "tl", "r"],
+    },
+    ModifierDoc {
+        token: "t",
+        syntax: "t# or t[#,#,#]",
+        name: "Count successes",
+        summary: "Count dice showing # or more as successes instead of adding them up. With several targets in brackets (a ladder), name the highest tier the total reaches instead: critical, special or plain success, or failure.",
+        examples: &[
+            ("10d10 t7", "Successes on 7+"),
+            ("6d6 t5", "Shadowrun style hits"),
+            ("1d100 t[90,70,50]", "Degrees of success"),
+        ],
+        see_also: &["tl", "tds", "f", "b", "maxs"],
+    },
+    ModifierDoc {
+        token: "tl",
+        syntax: "tl#",
+        name: "Count successes under",
+        summary: "Count dice showing # or less as successes.",
+        examples: &[("5d10 tl6", "Successes on 6 or lower")],
+        see_also: &["t", "tlds"],
+    },
+    ModifierDoc {
+        token: "tds",
+        syntax: "t#ds or t#ds#",
+        name: "Double successes",
+        summary: "Count successes on # or more, with dice on the `ds` value or higher counting twice. Without a number after `ds` it is the target itself.",
+        examples: &[
+            ("5d10 t7ds10", "Successes on 7+, 10s count twice"),
+            ("4d6 t4ds6", "Successes on 4+, 6s count twice"),
+        ],
+        see_also: &["t", "tlds"],
+    },
+    ModifierDoc {
+        token: "tlds",
+        syntax: "tl#ds or tl#ds#",
+        name: "Double successes under",
+        summary: "Count successes on # or less, with dice on the `ds` value or lower counting twice.",
+        examples: &[("5d10 tl6ds4", "Successes on 6-, 4s and lower count twice")],
+        see_also: &["tl", "tds"],
+    },
+    ModifierDoc {
+        token: "maxs",
+        syntax: "maxs#",
+        name: "Success cap",
+        summary: "Count at most # successes; the excess is shown as an overflow note. Needs a `t#` or `tl#` target.",
+        examples: &[("10d10 t7 maxs5", "At most 5 successes")],
+        see_also: &["t", "tl"],
+    },
+    ModifierDoc {
+        token: "f",
+        syntax: "f#",
+        name: "Count failures",
+        summary: "Count dice showing # or less as failures, which cancel successes.",
+        examples: &[("4d10 t8 f1", "World of Darkness: 1s take away successes")],
+        see_also: &["t", "b"],
+    },
+    ModifierDoc {
+        token: "b",
+        syntax: "b or b#",
+        name: "Count botches",
+        summary: "Count dice showing # or less (1 without a number) as botches, shown next to the result.",
+        examples: &[("5d10 t7 b1", "Successes on 7+, with 1s reported as botches")],
+        see_also: &["f", "t"],
+    },
+    ModifierDoc {
+        token: "c",
+        syntax: "c",
+        name: "Cancel",
+        summary: "Each 10 cancels a 1 counted as a failure, as in World of Darkness. Needs an `f#` failure count.",
+        examples: &[("4d10 f1 t8 c", "10s cancel 1s")],
+        see_also: &["f"],
+    },
+    ModifierDoc {
+        token: "tn",
+        syntax: "tn#",
+        name: "2d20 target number",
+        summary: "Roll as a 2d20 check (Conan, Star Trek Adventures, Fallout, Dune): each d20 at or under # is a success and a natural 1 two. Takes 1-5 d20s; `+N` adds bonus successes.",
+        examples: &[
+            ("2d20 tn12", "A 2d20 check against target number 12"),
+            ("sta 3d20 tn15 f2", "Star Trek Adventures: 3d20, focus 2"),
+        ],
+        see_also: &["focus", "diff", "comp"],
+    },
+    ModifierDoc {
+        token: "focus",
+        syntax: "focus#",
+        name: "2d20 focus",
+        summary: "Dice at or under # score two successes instead of one. Needs a `tn#` target number at or above it.",
+        examples: &[("2d20 tn14 focus3", "Successes on 14-, two on 3-")],
+        see_also: &["tn", "diff"],
+    },
+    ModifierDoc {
+        token: "diff",
+        syntax: "diff#",
+        name: "2d20 difficulty",
+        summary: "The successes needed, from 0 to 5. The note shows success or failure, and successes beyond # as momentum. Needs a `tn#` target number.",
+        examples: &[("2d20 tn12 diff2", "Difficulty 2, reporting momentum")],
+        see_also: &["tn", "comp"],
+    },
+    ModifierDoc {
+        token: "comp",
+        syntax: "comp#",
+        name: "2d20 complication range",
+        summary: "The top # faces (1-5) are complications instead of only a natural 20, so `comp2` counts 19s and 20s.",
+        examples: &[("2d20 tn12 comp2", "Complications on 19-20")],
+        see_also: &["tn", "diff"],
+    },
+    ModifierDoc {
+        token: "bands",
+        syntax: "bands or bands[a,b]",
+        name: "Outcome bands",
+        summary: "Read the total as a failure up to a, a partial success up to b and a success above. One threshold gives failure/success and three add a critical success. Plain `bands` uses 6,9 or the server's `/settings bands`.",
+        examples: &[("2d6+1 bands[6,9]", "Powered by the Apocalypse style move")],
+        see_also: &["t"],
+    },
+    ModifierDoc {
+        token: "m",
+        syntax: "m",
+        name: "Matched sets",
+        summary: "Group the dice into sets of matching faces instead of adding them up, as in the One-Roll Engine: **3x7** is three dice showing 7. Sets are listed widest first, then the loose dice.",
+        examples: &[("7d10 m", "One-Roll Engine pool (also `ore 7`)")],
+        see_also: &["k"],
+    },
+];
+
+/// Normalize a query to a registry token: `T7 DS10` → `tds`, `bands[6,9]` → `bands`
+pub fn normalize_token(query: &str) -> String {
+    query
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// The entry for a modifier token such as `ie6`
+pub fn lookup(query: &str) -> Option<&'static ModifierDoc> {
+    let token = normalize_token(query);
+    MODIFIER_DOCS.iter().find(|doc| doc.token == token)
+}
@@ -3,19 +3,26 @@
 //! The public entry point is [`parse_dice_string`].  It accepts a free-form
 //! string typed by a Discord user and returns a list of [`DiceRoll`] values
 //! that the roller can execute.
+//! [`trace_dice_string`] parses the same way and also records the expansion
+//! steps, for `/debugparse`.
 //!
 //! # Parsing pipeline
 //!
 //! ```text
 //! raw input
 //!   │
+//!   ├─ variables  "1d20 + @str" → "1d20 + 3"  (substitute_variables, run by the caller)
 //!   ├─ alias expansion (aliases::expand_alias)
 //!   ├─ semicolon split → multiple independent rolls
 //!   ├─ roll-set detection  "N <expr>"  (e.g. "6 4d6 k3")
+//!   ├─ pool split  "split NdS <mods> into a/b"  → one roll per sub-pool
 //!   └─ parse_single_dice_expression
 //!         ├─ label extraction   "(Attack) …"
 //!         ├─ comment extraction "… ! Fire damage"
 //!         ├─ flags (p, s, nr, ul)
+//!         ├─ math only  "4*7+2" → 0d0 with math modifiers
+//!         ├─ labeled faces  "2d{yes,no,maybe}" → 2d3 with face names
+//!         ├─ mixed pool  "{d8+d6+d4} k2" → 3d8 k2 with a die size per die
 //!         ├─ dice core  NdS  or  d%
 //!         └─ split_combined_modifiers → Vec<Modifier>
 //! ```
@@ -33,28 +40,87 @@
 //! | Constraint          | Value   |
 //! |---------------------|---------|
 //! | Max input length    | 1 000 chars |
+//! | Dice per roll       | 1 – 500 |
+//! | Sides per die       | 1 – 1 000 000 |
 //! | Roll-set count      | 2 – 20  |
 //! | Multi-roll segments | up to 4 |
+//! | Split sub-pools     | 2 – 4   |
+//! | Labeled dice        | 1 – 100, with 2 – 50 faces |
 //!
 //! All regex patterns are compiled once at startup via `once_cell::Lazy`.
 
-use super::{DiceRoll, HeroSystemType, LaserFeelingsType, Modifier};
-use anyhow::{Result, anyhow};
+use super::error::{DiceError, Result};
+use super::{
+    BurningWheelPool, DiceRoll, HeroSystemType, L5rPool, LaserFeelingsType, Modifier,
+    NarrativePool, YearZeroPool,
+};
+use crate::i18n::{LANGUAGE_CODES, Language};
 use once_cell::sync::Lazy;
-use regex::Regex;
+use regex::{Regex, RegexSet};
+use serde::Serialize;
 
-const MAX_INPUT_LENGTH: usize = 1000;
+pub(crate) const MAX_INPUT_LENGTH: usize = 1000;
+
+/// Most sides a die may have, enough for lottery-style `1d1000000` rolls.
+/// Even the largest pool (500 dice, plus explosions) totals well inside an
+/// `i32`, and math on the total is overflow-checked by the roller.
+pub const MAX_DICE_SIDES: u32 = 1_000_000;
+
+// Most dice in one Genesys narrative pool
+const MAX_NARRATIVE_DICE: u32 = 50;
+
+// Most ring and skill dice in one L5R check
+const MAX_L5R_DICE: u32 = 20;
+
+// Most dice, faces and characters per face of labeled dice (`2d{yes,no}`)
+const MAX_FACE_DICE: u32 = 100;
+const MAX_FACES: usize = 50;
+const MAX_FACE_LENGTH: usize = 50;
+
+// Highest 2d20 difficulty (`diff5`) and widest complication range (`comp5`, 16-20)
+const MAX_2D20_DIFFICULTY: u32 = 5;
+const MAX_2D20_COMPLICATION_RANGE: u32 = 5;
 
 // Pre-compile all regex patterns at startup to reduce memory allocations
 static SET_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^(\d+)\s+(.+)$").expect("Failed to compile SET_REGEX"));
 
+static SPLIT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^split\s+(\d+)d(\d+)(.*?)\s+into\s+(\d+(?:\s*/\s*\d+)+)$")
+        .expect("Failed to compile SPLIT_REGEX")
+});
+
+static MATH_ONLY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d+(?:\s*[+\-*/]\s*\d+)*$").expect("Failed to compile MATH_ONLY_REGEX")
+});
+
+static MATH_TOKEN_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\d+|[+\-*/]").expect("Failed to compile MATH_TOKEN_REGEX"));
+
 static DICE_ONLY_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^(\d+)?d(\d+|%)$").expect("Failed to compile DICE_ONLY_REGEX"));
 
+static FACE_DICE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(\d*)d\{([^{}]*)\}").expect("Failed to compile FACE_DICE_REGEX"));
+
+// A pool of mixed die sizes, e.g. "{d8+d6+d4}" or "{2d8+d6}"
+static MIXED_POOL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\{\s*(\d*d\d+(?:\s*\+\s*\d*d\d+)*)\s*\}")
+        .expect("Failed to compile MIXED_POOL_REGEX")
+});
+
+static MIXED_POOL_TERM_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(\d*)d(\d+)").expect("Failed to compile MIXED_POOL_TERM_REGEX"));
+
 static LABEL_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^\(([^)]*)\)\s*").expect("Failed to compile LABEL_REGEX"));
 
+// `@str` or `+ @str`; the sign is kept so a negative value can flip it
+static VARIABLE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:([+-])(\s*))?@([a-z_][a-z0-9_]{0,31})\b")
+        .expect("Failed to compile VARIABLE_REGEX")
+});
+
 static COMMENT_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"!\s*(.*)$").expect("Failed to compile COMMENT_REGEX"));
 
@@ -67,16 +133,260 @@ static ADV_WITH_SIMPLE_MOD_REGEX: Lazy<Regex> = Lazy::new(|| {
         .expect("Failed to compile ADV_WITH_SIMPLE_MOD_REGEX")
 });
 
+static WHITESPACE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\s+").expect("Failed to compile WHITESPACE_REGEX"));
+
+static NUMBER_DIV_DICE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d+\s*/\s*(d\d+.*|d%.*|\d*d\d+.*)$")
+        .expect("Failed to compile NUMBER_DIV_DICE_REGEX")
+});
+
+static NUMBER_OP_DICE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d+)\s*([+\-*/])\s+(d\d+.*|d%.*|\d*d\d+.*)$")
+        .expect("Failed to compile NUMBER_OP_DICE_REGEX")
+});
+
+static STANDARD_DICE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d*d\d+|d%|\d*d%)").expect("Failed to compile STANDARD_DICE_REGEX")
+});
+
+static ADVANTAGE_PREFIX_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[+-]d(\d+|%)").expect("Failed to compile ADVANTAGE_PREFIX_REGEX"));
+
+static DICE_WITH_REST_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d*d\d+|d%|d\d+)(.*)$").expect("Failed to compile DICE_WITH_REST_REGEX")
+});
+
+static NUMBER_DIV_PART_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d+)/(\S+)(.*)$").expect("Failed to compile NUMBER_DIV_PART_REGEX")
+});
+
+static BASIC_DICE_WITH_REST_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d*d\d+)(.*)$").expect("Failed to compile BASIC_DICE_WITH_REST_REGEX")
+});
+
+static TARGET_DOUBLE_SUCCESS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^t(\d+)ds(\d+)$").expect("Failed to compile TARGET_DOUBLE_SUCCESS_REGEX")
+});
+
+static TARGET_DOUBLE_DEFAULT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^t(\d+)ds$").expect("Failed to compile TARGET_DOUBLE_DEFAULT_REGEX"));
+
+static TARGET_LOWER_DOUBLE_SUCCESS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^tl(\d+)ds(\d+)$").expect("Failed to compile TARGET_LOWER_DOUBLE_SUCCESS_REGEX")
+});
+
+static TARGET_LOWER_DOUBLE_DEFAULT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^tl(\d+)ds$").expect("Failed to compile TARGET_LOWER_DOUBLE_DEFAULT_REGEX")
+});
+
+static TARGET_LADDER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^t\[(\d+(?:,\d+)*)\]$").expect("Failed to compile TARGET_LADDER_REGEX")
+});
+
+static BANDS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^bands(?:\[(\d+(?:,\d+)*)\])?$").expect("Failed to compile BANDS_REGEX")
+});
+
+// What follows `bw`: 5ob3h1openluck
+static BURNING_WHEEL_SPEC_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d+)(?:ob(\d+))?(?:h(\d+))?(open)?(luck)?$")
+        .expect("Failed to compile BURNING_WHEEL_SPEC_REGEX")
+});
+
+static WILD_WORLDS_CUT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^wwc(\d+)$").expect("Failed to compile WILD_WORLDS_CUT_REGEX"));
+
+static MULTI_DIGIT_COUNT_DICE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{2,})d(\d+)$").expect("Failed to compile MULTI_DIGIT_COUNT_DICE_REGEX")
+});
+
+static MULTI_DIGIT_SIDES_DICE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d)d(\d{2,})$").expect("Failed to compile MULTI_DIGIT_SIDES_DICE_REGEX")
+});
+
+static COMMON_DICE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d+)d([46]|8|10|12|20|100)$").expect("Failed to compile COMMON_DICE_REGEX")
+});
+
+// Dice expressions with modifiers accepted as roll-set bodies, e.g. "4d6 k3"
+// left over from "3 (Stat Roll) 4d6 k3" after label/comment parsing
+static DICE_WITH_MODIFIERS_SET: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([
+        r"^\d*d\d+\s+[kdie]+\d*.*$",    // "4d6 k3", "2d6 e6", "1d20 d1"
+        r"^\d*d\d+\s*[+\-*/]\s*\d+.*$", // "4d6 + 3", "2d6 - 1", "1d20 * 2"
+        r"^\d*d%\s+[kdie]+\d*.*$",      // "d% k1", "2d% e6"
+        r"^\d*d%\s*[+\-*/]\s*\d+.*$",   // "d% + 10", "2d% - 5"
+    ])
+    .expect("Failed to compile DICE_WITH_MODIFIERS_SET")
+});
+
+static STANDALONE_DICE_SET: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([
+        r"^\d*d\d+$", // Basic dice like "3d6", "d20", "1d4"
+        r"^\d*d%$",   // Percentile dice like "d%", "2d%"
+    ])
+    .expect("Failed to compile STANDALONE_DICE_SET")
+});
+
+// Modifier tokens recognised by `split_combined_modifiers`, tried in this order
+// (multi-character prefixes before their single-character counterparts)
+static COMBINED_MODIFIER_REGEXES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    compile_patterns(&[
+        r"^(ie\d*)",                                      // ie, ie6 (BEFORE regular explode)
+        r"^(irg\d+)",                                     // irg5 (BEFORE rg)
+        r"^(ir\d+)",                                      // ir1 (BEFORE regular reroll)
+        r"^(ke\d*)",                                      // ke, ke6 (BEFORE regular keep)
+        r"^(kp\d*)",                                      // kp, kp6 (BEFORE regular keep)
+        r"^(km\d+)",                                      // km3 (BEFORE regular keep)
+        r"^(kl\d+)",                                      // kl2 (BEFORE regular keep)
+        r"^(tl\d+)",                                      // tl5 (BEFORE regular target)
+        r"^(te\d+)",                                      // te12 (BEFORE regular target)
+        r"^(rg\d+)",                                      // rg5
+        r"^(rfail)",                                      // rfail (BEFORE regular reroll)
+        r"^(maxs\d+)",                                    // maxs5
+        r"^(k\d+)",                                       // k3
+        r"^(db\d*(?:boon|bane)?(?:push)?)",               // db12boon - Dragonbane (BEFORE d)
+        r"^(gurps-?\d+)",                                 // gurps12 - GURPS success roll (BEFORE g)
+        r"^(d\d+)",                                       // d1
+        r"^(r\d+)",                                       // r1
+        r"^(t\[[\d,]*\])", // t[90,70,50] - target ladder (BEFORE regular target)
+        r"^(t\d+)",        // t4, t7
+        r"^(f\d+)",        // f1
+        r"^(e\d*)",        // e, e6 (AFTER indefinite explode)
+        r"^(bw\d+(?:ob\d+)?(?:h\d+)?(?:open)?(?:luck)?)", // bw5ob3h2 - Burning Wheel (BEFORE b)
+        r"^(bands(?:\[[\d,]*\])?)", // bands, bands[6,9] (BEFORE b)
+        r"^(b\d*)",        // b, b1
+        r"^(cortex)",      // cortex - Cortex Prime (BEFORE c)
+        r"^(coc\d*(?:[bp][12])?)", // coc65b1 - Call of Cthulhu (BEFORE c)
+        r"^(wfrp\d+)",     // wfrp45 - Warhammer Fantasy Roleplay 4e
+        r"^(c)",           // c
+        r"^(wng\d*t?)",    // wng patterns
+        r"^(gb|gbs)",      // gb, gbs
+        r"^(hs[nkh])",     // hsn, hsk, hsh
+        r"^(dh)",          // dh
+        r"^(fudge|df)",    // fudge, df
+        r"^(d6s\d+(?:\+\d+)?(?:\-\d+)?)", // d6 system — d6s patterns
+        r"^(cpr)",         // cpr
+        r"^(wit)",         // wit
+        r"^(bnw)",         // bnw
+        r"^(alien)",       // alien (exact)
+        r"^(aliens\d+)",   // aliens1, aliens2, etc.
+        r"^(wwc\d+)",      // wwc2, wwc3, etc. (before basic ww)
+        r"^(mspanic\d+)",  // mspanic5 - Mothership panic (BEFORE ms)
+        r"^(ms\d+[ad]?|ms[ad]?|ms)", // mothership
+        r"^(ww)",          // ww (basic)
+        r"^(plot)",        // plot
+        r"^(gen(?:\d+[bapsdc])+)", // gen2a1d - Genesys narrative pool
+        r"^(iron)",        // iron - Ironsworn action roll
+        r"^(oracle\d*)",   // oracle, oracle26 - Ironsworn oracle
+        r"^(l5r\d+k\d+)",  // l5r5k3 - L5R 5e ring and skill dice
+        r"^(fbl(?:[bsga]\d+)*(?:push)?)", // fblb5s3g2a10push - Forbidden Lands (BEFORE f)
+    ])
+});
+
+// First-modifier patterns used by `is_combined_modifier`, tried in this order
+static LEADING_MODIFIER_REGEXES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    compile_patterns(&[
+        r"^(ie\d*)",       // Indefinite explode first (longer pattern)
+        r"^(irg\d+)",      // Indefinite reroll greater
+        r"^(ir\d+)",       // Indefinite reroll
+        r"^(ke\d*)",       // Compounding explode
+        r"^(kp\d*)",       // Penetrating explode
+        r"^(km\d+)",       // Keep middle
+        r"^(kl\d+)",       // Keep low
+        r"^(tl\d+)",       // Target lower (must come before regular target)
+        r"^(te\d+)",       // Exploding total
+        r"^(rg\d+)",       // Reroll greater
+        r"^(rfail)",       // Reroll failed dice
+        r"^(maxs\d+)",     // Success cap
+        r"^(e\d*)",        // Explode
+        r"^(k\d+)",        // Keep high
+        r"^(d\d+)",        // Drop
+        r"^(r\d+)",        // Reroll
+        r"^(t\[[\d,]*\])", // Target ladder
+        r"^(t\d+)",        // Target - KEY FOR D6 LEGENDS
+        r"^(f\d+)",        // Failure - KEY FOR D6 LEGENDS
+        r"^(b\d*)",        // Botch
+    ])
+});
+
+// Anything `is_modifier_start` accepts as the start of another modifier
+static MODIFIER_START_SET: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([
+        r"^ie\d*",   // Indefinite explode: ie, ie6
+        r"^irg\d+",  // Indefinite reroll greater: irg5
+        r"^ir\d+",   // Indefinite reroll: ir1
+        r"^ke\d*",   // Compounding explode: ke, ke6
+        r"^kp\d*",   // Penetrating explode: kp, kp6
+        r"^km\d+",   // Keep middle: km3
+        r"^kl\d+",   // Keep low: kl2
+        r"^tl\d+",   // Target lower: tl5
+        r"^te\d+",   // Exploding total: te12
+        r"^rg\d+",   // Reroll greater: rg5
+        r"^rfail",   // Reroll failed dice: rfail
+        r"^maxs\d+", // Success cap: maxs5
+        r"^k\d+",    // Keep high: k3
+        r"^d\d+",    // Drop: d1
+        r"^r\d+",    // Reroll: r1
+        r"^t\[",     // Target ladder: t[90,70,50]
+        r"^t\d+",    // Target: t4, t7 - CRITICAL FOR D6 LEGENDS
+        r"^f\d+",    // Failure: f1 - CRITICAL FOR D6 LEGENDS
+        r"^e\d*",    // Explode: e, e6
+        r"^bands",   // Outcome bands: bands, bands[6,9]
+        r"^b\d*",    // Botch: b, b1
+        r"^c$",      // Cancel: c (exact match)
+        // System modifiers
+        r"^wng",        // Wrath & Glory patterns
+        r"^gb$",        // Godbound (exact)
+        r"^gbs$",       // Godbound straight (exact)
+        r"^hs[nkh]",    // Hero System
+        r"^dh$",        // Dark Heresy (exact)
+        r"^fudge$",     // Fudge (exact)
+        r"^df$",        // Fudge dice (exact)
+        r"^d6s\d+",     // D6 System
+        r"^cpr$",       // Cyberpunk Red (exact)
+        r"^wit$",       // Witcher (exact)
+        r"^alien$",     // Alien base modifier (exact)
+        r"^aliens\d+",  // Alien stress modifiers: aliens1, aliens2, etc.
+        r"^fitd$",      // Forged in the Dark (exact)
+        r"^fitd0$",     // FitD zero dice (exact)
+        r"^plot$",      // Plotweaver/Cosmere RPG plot die (exact)
+        r"^gen\d",      // Genesys narrative pool: gen2a1d
+        r"^l5r\d",      // L5R 5e: l5r5k3
+        r"^fbl",        // Forbidden Lands: fblb5s3g2a10push
+        r"^bw\d",       // Burning Wheel: bw5ob3h2openluck
+        r"^iron$",      // Ironsworn action roll (exact)
+        r"^oracle\d*$", // Ironsworn oracle: oracle, oracle26
+        r"^coc",        // Call of Cthulhu: coc, coc65b1
+        r"^cortex$",    // Cortex Prime (exact)
+        r"^wfrp\d+$",   // Warhammer Fantasy Roleplay: wfrp45
+        r"^db",         // Dragonbane: db, db12banepush
+        r"^gurps",      // GURPS: gurps12
+        r"^tn\d+",      // 2d20 target number: tn14
+        r"^focus\d+",   // 2d20 focus: focus3
+        r"^diff\d+",    // 2d20 difficulty: diff2
+        r"^comp\d+",    // 2d20 complication range: comp2
+    ])
+    .expect("Failed to compile MODIFIER_START_SET")
+});
+
+fn compile_patterns(patterns: &[&str]) -> Vec<Regex> {
+    patterns
+        .iter()
+        .map(|pattern| Regex::new(pattern).expect("Failed to compile modifier pattern"))
+        .collect()
+}
+
 pub fn parse_dice_string(input: &str) -> Result<Vec<DiceRoll>> {
     let input = input.trim();
 
     // Input length validation for DoS protection
     if input.len() > MAX_INPUT_LENGTH {
-        return Err(anyhow!(
-            "Input too long: {} characters (max {})",
-            input.len(),
-            MAX_INPUT_LENGTH
-        ));
+        return Err(DiceError::InputTooLong {
+            length: input.len(),
+            max: MAX_INPUT_LENGTH,
+        });
     }
 
     // Check for aliases that expand to roll sets
@@ -110,7 +420,9 @@ pub fn parse_dice_string(input: &str) -> Result<Vec<DiceRoll>> {
         {
             // Add validation check for count range
             if !(2..=20).contains(&count) {
-                return Err(anyhow!("Set count must be between 2 and 20"));
+                return Err(DiceError::ValidationError(
+                    "Set count must be between 2 and 20".into(),
+                ));
             }
 
             // Handle advantage/disadvantage patterns with modifiers in roll sets
@@ -160,6 +472,11 @@ pub fn parse_dice_string(input: &str) -> Result<Vec<DiceRoll>> {
     let after_comment = parse_comment(&mut temp_dice, after_label);
     let remaining_input = after_comment.trim();
 
+    // Pool split: "split 8d6 t4 into 3/5"
+    if let Some(captures) = SPLIT_REGEX.captures(remaining_input) {
+        return create_split_pools(&captures, &temp_dice);
+    }
+
     // PRIORITY 2: Check for roll sets AGAIN after processing flags, with same validation
     if let Some(captures) = SET_REGEX.captures(remaining_input) {
         let count_str = &captures[1];
@@ -171,7 +488,9 @@ pub fn parse_dice_string(input: &str) -> Result<Vec<DiceRoll>> {
         {
             // Add validation check for count range
             if !(2..=20).contains(&count) {
-                return Err(anyhow!("Set count must be between 2 and 20"));
+                return Err(DiceError::ValidationError(
+                    "Set count must be between 2 and 20".into(),
+                ));
             }
 
             // Handle advantage/disadvantage patterns with modifiers in roll sets
@@ -310,37 +629,25 @@ fn is_valid_roll_set_expression(expr: &str) -> bool {
     }
 
     // Check for number/dice division patterns (like "20/d6", "100/2d4")
-    let number_div_dice_regex = Regex::new(r"^\d+\s*/\s*(d\d+.*|d%.*|\d*d\d+.*)$").unwrap();
-    if number_div_dice_regex.is_match(expr) {
+    if NUMBER_DIV_DICE_REGEX.is_match(expr) {
         return true;
     }
 
     // Check for standard dice expressions (like "2d6", "d20", "4d6+3")
-    let standard_dice_regex = Regex::new(r"^(\d*d\d+|d%|\d*d%)").unwrap();
-    if standard_dice_regex.is_match(expr) {
+    if STANDARD_DICE_REGEX.is_match(expr) {
         return true;
     }
 
     // Check for advantage/disadvantage patterns explicitly
-    let advantage_regex = Regex::new(r"^[+-]d(\d+|%)").unwrap();
-    if advantage_regex.is_match(expr) {
+    if ADVANTAGE_PREFIX_REGEX.is_match(expr) {
         return true;
     }
 
     // Check for dice expressions with modifiers
     // This fixes "3 (Stat Roll) 4d6 k3" and "6 (Stat) 4d6 k3 ! Character generation"
     // After label/comment parsing, these become "4d6 k3"
-    let dice_with_modifiers_patterns = [
-        r"^\d*d\d+\s+[kdie]+\d*.*$",    // "4d6 k3", "2d6 e6", "1d20 d1"
-        r"^\d*d\d+\s*[+\-*/]\s*\d+.*$", // "4d6 + 3", "2d6 - 1", "1d20 * 2"
-        r"^\d*d%\s+[kdie]+\d*.*$",      // "d% k1", "2d% e6"
-        r"^\d*d%\s*[+\-*/]\s*\d+.*$",   // "d% + 10", "2d% - 5"
-    ];
-
-    for pattern in &dice_with_modifiers_patterns {
-        if Regex::new(pattern).unwrap().is_match(expr) {
-            return true;
-        }
+    if DICE_WITH_MODIFIERS_SET.is_match(expr) {
+        return true;
     }
 
     // Must contain 'd' to be a dice expression (for remaining cases)
@@ -352,6 +659,275 @@ fn is_valid_roll_set_expression(expr: &str) -> bool {
     true
 }
 
+/// Whether an expression refers to stored values such as `@str` (outside
+/// its label and comment; `<@123>` mentions don't count)
+pub fn has_variables(input: &str) -> bool {
+    variable_matches(input).next().is_some()
+}
+
+/// Replace `@name` references with the values `resolve` gives for them, so
+/// `1d20 + @str` rolls as `1d20 + 3`.
+///
+/// Names are looked up in lowercase.  A negative value flips the `+` or `-`
+/// in front of it (`1d20 + @dex` with `dex` at -1 is `1d20 - 1`) and is
+/// refused anywhere else, since the parser has no negative numbers.  Labels
+/// and comments are left alone.
+pub fn substitute_variables<F>(input: &str, resolve: F) -> Result<String>
+where
+    F: Fn(&str) -> Option<i64>,
+{
+    let mut output = String::with_capacity(input.len());
+    let mut last = 0;
+    for captures in variable_matches(input) {
+        let Some(whole) = captures.get(0) else {
+            continue;
+        };
+        let name = captures[3].to_lowercase();
+        let Some(value) = resolve(&name) else {
+            return Err(DiceError::ValidationError(format!(
+                "Unknown stat `@{name}`. Set it with `/char set`"
+            )));
+        };
+
+        let replacement = match captures.get(1).map(|sign| sign.as_str()) {
+            Some(sign) => {
+                let sign = match sign {
+                    "+" if value < 0 => "-",
+                    "-" if value < 0 => "+",
+                    sign => sign,
+                };
+                format!("{sign}{}{}", &captures[2], value.unsigned_abs())
+            }
+            None if value < 0 => {
+                return Err(DiceError::ValidationError(format!(
+                    "`@{name}` is {value}; negative stats can only follow `+` or `-`"
+                )));
+            }
+            None => value.to_string(),
+        };
+        output.push_str(&input[last..whole.start()]);
+        output.push_str(&replacement);
+        last = whole.end();
+    }
+    output.push_str(&input[last..]);
+    Ok(output)
+}
+
+// `@name` references outside parentheses (labels) and the `!` comment
+fn variable_matches(input: &str) -> impl Iterator<Item = regex::Captures<'_>> {
+    let end = input.find('!').unwrap_or(input.len());
+    VARIABLE_REGEX
+        .captures_iter(&input[..end])
+        .filter(move |captures| {
+            let start = captures.get(0).map_or(0, |m| m.start());
+            let before = &input[..start];
+            let in_label = before.matches('(').count() > before.matches(')').count();
+            // `<@123>` mentions and `me@home` aren't references
+            let joined = captures.get(1).is_none()
+                && before
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c == '<' || c.is_alphanumeric() || c == '_');
+            !in_label && !joined
+        })
+}
+
+/// Usage-counter keys for a roll expression, one per distinct system used.
+///
+/// Flags, labels, comments and roll-set counts are stripped from each
+/// `;`-separated roll before [`super::aliases::alias_usage_key`] classifies it.
+pub fn usage_keys(input: &str) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+
+    for part in input.split(';') {
+        let mut scratch = create_default_dice_roll();
+        let normalized = normalize_whitespace(part.trim());
+        let mut remaining = parse_flags(&mut scratch, &normalized);
+        remaining = parse_label(&mut scratch, remaining);
+        remaining = parse_comment(&mut scratch, remaining).trim();
+
+        if remaining.is_empty() {
+            continue;
+        }
+
+        // A roll set like "6 4d6" counts as the expression being repeated
+        if let Some(captures) = SET_REGEX.captures(remaining)
+            && is_valid_roll_set_expression(&captures[2])
+        {
+            remaining = captures.get(2).map_or(remaining, |m| m.as_str());
+        }
+
+        let key = super::aliases::alias_usage_key(remaining);
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    keys
+}
+
+/// A mistyped alias in an expression and its fix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasCorrection {
+    /// The text as typed, e.g. `4cof`
+    pub typed: String,
+    /// The alias it was read as, e.g. `4cod`
+    pub alias: String,
+    /// The whole expression with the alias fixed
+    pub expression: String,
+}
+
+/// Ways to fix an expression that doesn't parse because of a mistyped alias
+/// (`4cof ! Stealth` → `4cod ! Stealth`), closest first.
+///
+/// The first part (of a `;`-separated expression) that fails is looked up
+/// with [`super::aliases::similar_aliases`], keeping its flags, label,
+/// comment and roll-set count; a fix is offered only if the whole expression
+/// then parses.
+pub fn alias_corrections(input: &str) -> Vec<AliasCorrection> {
+    let parts: Vec<&str> = input.split(';').collect();
+    let Some(index) = parts
+        .iter()
+        .position(|part| parse_dice_string(part).is_err())
+    else {
+        return Vec::new();
+    };
+
+    let mut scratch = create_default_dice_roll();
+    let normalized = normalize_whitespace(parts[index].trim());
+    let after_flags = parse_flags(&mut scratch, &normalized);
+    let body = parse_label(&mut scratch, after_flags);
+    // The typed text is a slice of `normalized`: the label and flags come off
+    // the front and the comment off the back
+    let mut start = normalized.len() - body.trim_start().len();
+    let mut typed = parse_comment(&mut scratch, body).trim();
+    if let Some(expression) = SET_REGEX
+        .captures(typed)
+        .and_then(|captures| captures.get(2))
+    {
+        start += expression.start();
+        typed = expression.as_str();
+    }
+    let end = start + typed.len();
+
+    super::aliases::similar_aliases(typed)
+        .into_iter()
+        .filter_map(|alias| {
+            let mut fixed = parts.clone();
+            let fixed_part = format!("{}{alias}{}", &normalized[..start], &normalized[end..]);
+            // Keep the spacing around the part, as in "1d20; 4cof"
+            let part = parts[index].replace(parts[index].trim(), &fixed_part);
+            fixed[index] = &part;
+            let expression = fixed.join(";");
+            parse_dice_string(&expression)
+                .is_ok()
+                .then(|| AliasCorrection {
+                    typed: typed.to_string(),
+                    alias,
+                    expression,
+                })
+        })
+        .collect()
+}
+
+/// One rewrite of the expression on its way to [`DiceRoll`]s
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParseStep {
+    /// What was done, e.g. `alias` or `roll set`
+    pub stage: &'static str,
+    /// The expression afterwards
+    pub expression: String,
+}
+
+/// The expansion steps and parsed rolls of an expression, for `/debugparse`
+#[derive(Debug, Clone, Serialize)]
+pub struct ParseTrace {
+    pub input: String,
+    pub steps: Vec<ParseStep>,
+    pub rolls: Vec<DiceRoll>,
+    /// Why the expression didn't parse; `rolls` is then empty
+    pub error: Option<String>,
+}
+
+/// Parse an expression like [`parse_dice_string`], recording how it was
+/// split and expanded along the way.
+///
+/// The steps follow the pipeline in the module docs as far as the
+/// expression a part is finally read as; the rolls and error are exactly
+/// those of [`parse_dice_string`].
+pub fn trace_dice_string(input: &str) -> ParseTrace {
+    let input = input.trim();
+    let mut steps = Vec::new();
+    let mut step = |stage: &'static str, expression: &str| {
+        steps.push(ParseStep {
+            stage,
+            expression: expression.to_string(),
+        });
+    };
+
+    if let Some(expanded) = super::aliases::expand_alias(input) {
+        step("alias", &expanded);
+    } else {
+        let parts: Vec<&str> = input.split(';').map(str::trim).collect();
+        for part in &parts {
+            if parts.len() > 1 {
+                step("multi-roll part", part);
+            }
+
+            let mut scratch = create_default_dice_roll();
+            let normalized = normalize_whitespace(part);
+            let mut remaining = parse_flags(&mut scratch, &normalized);
+            remaining = parse_label(&mut scratch, remaining);
+            remaining = parse_comment(&mut scratch, remaining).trim();
+            if remaining != normalized {
+                step("flags, label and comment", remaining);
+            }
+
+            if SPLIT_REGEX.is_match(remaining) {
+                step("pool split", remaining);
+                continue;
+            }
+            if let Some(expression) = SET_REGEX
+                .captures(remaining)
+                .filter(|captures| is_valid_roll_set_expression(&captures[2]))
+                .and_then(|captures| captures.get(2))
+            {
+                remaining = expression.as_str();
+                step("roll set", remaining);
+            }
+
+            if is_math_only(remaining) {
+                step("math only", remaining);
+            } else if FACE_DICE_REGEX.is_match(remaining) {
+                step("labeled faces", remaining);
+            } else if MIXED_POOL_REGEX.is_match(remaining) {
+                step("mixed pool", remaining);
+            } else if let Some(expanded) = super::aliases::expand_alias(remaining) {
+                step("alias", &expanded);
+            } else if let Some(captures) = ADV_WITH_SIMPLE_MOD_REGEX.captures(remaining)
+                && let Some(expanded) =
+                    super::aliases::expand_alias(&format!("{}d{}", &captures[1], &captures[2]))
+            {
+                step(
+                    "alias",
+                    &format!("{expanded} {} {}", &captures[3], &captures[4]),
+                );
+            }
+        }
+    }
+
+    let (rolls, error) = match parse_dice_string(input) {
+        Ok(rolls) => (rolls, None),
+        Err(e) => (Vec::new(), Some(e.to_string())),
+    };
+    ParseTrace {
+        input: input.to_string(),
+        steps,
+        rolls,
+        error,
+    }
+}
+
 // Helper function to create roll sets, eliminating duplication
 fn create_roll_set(captures: &regex::Captures) -> Result<Vec<DiceRoll>> {
     create_roll_set_with_metadata(captures, None)
@@ -361,7 +937,9 @@ fn create_roll_set(captures: &regex::Captures) -> Result<Vec<DiceRoll>> {
 fn parse_semicolon_separated_rolls(input: &str) -> Result<Vec<DiceRoll>> {
     let parts: Vec<&str> = input.split(';').collect();
     if parts.len() > 4 {
-        return Err(anyhow!("Maximum of 4 separate rolls allowed"));
+        return Err(DiceError::RollLimitExceeded(
+            "Maximum of 4 separate rolls allowed".into(),
+        ));
     }
 
     let mut results = Vec::with_capacity(parts.len());
@@ -376,6 +954,55 @@ fn parse_semicolon_separated_rolls(input: &str) -> Result<Vec<DiceRoll>> {
     Ok(results)
 }
 
+/// Split one pool into sub-pools that each roll with the same modifiers and
+/// count their own successes, e.g. `split 8d6 t4 into 3/5` rolls `3d6 t4`
+/// and `5d6 t4`.  The sizes must add up to the whole pool.
+fn create_split_pools(captures: &regex::Captures, metadata: &DiceRoll) -> Result<Vec<DiceRoll>> {
+    let count: u32 = captures[1]
+        .parse()
+        .map_err(|_| DiceError::ParseError("Invalid dice count in split".into()))?;
+    let sides = &captures[2];
+    let modifiers = captures[3].trim();
+
+    let sizes = captures[4]
+        .split('/')
+        .map(|size| size.trim().parse::<u32>())
+        .collect::<Result<Vec<u32>, _>>()
+        .map_err(|_| DiceError::ParseError("Invalid pool sizes in split".into()))?;
+    if !(2..=4).contains(&sizes.len()) {
+        return Err(DiceError::ValidationError("Split into 2 to 4 pools".into()));
+    }
+    if sizes.contains(&0) {
+        return Err(DiceError::ValidationError(
+            "Each split pool needs at least 1 die".into(),
+        ));
+    }
+    let allocated: u32 = sizes.iter().sum();
+    if allocated != count {
+        return Err(DiceError::ValidationError(format!(
+            "Split pools use {allocated} dice but the pool has {count}"
+        )));
+    }
+
+    let mut pools = Vec::with_capacity(sizes.len());
+    for (i, size) in sizes.iter().enumerate() {
+        let expression = format!("{size}d{sides} {modifiers}");
+        let mut pool = parse_single_dice_expression(expression.trim())?;
+        transfer_dice_metadata(metadata, &mut pool);
+        let name = format!("Pool {} ({size}d{sides})", i + 1);
+        pool.label = Some(match &metadata.label {
+            Some(label) => format!("{label} {name}"),
+            None => name,
+        });
+        // The reason is shown once, after the last pool
+        if i + 1 < sizes.len() {
+            pool.comment = None;
+        }
+        pools.push(pool);
+    }
+    Ok(pools)
+}
+
 fn parse_single_dice_expression(input: &str) -> Result<DiceRoll> {
     let mut dice = create_default_dice_roll();
 
@@ -389,6 +1016,45 @@ fn parse_single_dice_expression(input: &str) -> Result<DiceRoll> {
     remaining = parse_comment(&mut dice, remaining);
     remaining = remaining.trim();
 
+    // Math with operator precedence and parentheses, e.g. "pemdas (2d6 + 3) * 2"
+    if dice.pemdas && super::aliases::expand_alias(remaining).is_none() {
+        parse_precedence_math(&mut dice, remaining)?;
+        return Ok(dice);
+    }
+
+    // Arithmetic with no dice at all, e.g. "4*7+2"
+    if is_math_only(remaining) {
+        parse_math_only(&mut dice, remaining)?;
+        return Ok(dice);
+    }
+
+    // Dice with labeled faces, e.g. "2d{yes,no,maybe}"
+    if let Some(captures) = FACE_DICE_REGEX.captures(remaining) {
+        if captures[0].len() != remaining.len() {
+            return Err(DiceError::ValidationError(
+                "Labeled dice like `2d{yes,no}` can't be combined with modifiers or math".into(),
+            ));
+        }
+        parse_face_dice(&mut dice, &captures[1], &captures[2])?;
+        return Ok(dice);
+    }
+
+    // A pool of mixed die sizes, e.g. "{d8+d6+d4} k2": parsed as that many
+    // of the largest die, then given each die's size
+    if let Some(captures) = MIXED_POOL_REGEX.captures(remaining) {
+        let sizes = parse_mixed_pool(&captures[1])?;
+        let largest = sizes.iter().max().copied().unwrap_or(1);
+        let rest = &remaining[captures[0].len()..];
+        let mut pool_dice =
+            parse_single_dice_expression(&format!("{}d{largest}{rest}", sizes.len()))?;
+        // A pool of one size is just NdS
+        if sizes.iter().any(|&sides| sides != largest) {
+            pool_dice.pool = Some(sizes);
+        }
+        transfer_dice_metadata(&dice, &mut pool_dice);
+        return Ok(pool_dice);
+    }
+
     // Handle D6 System alias expansion BEFORE general alias expansion
     // This prevents the "d6s5" -> "5d6 + 1d6ie" from being mis-parsed
     if remaining.starts_with("d6s")
@@ -401,6 +1067,12 @@ fn parse_single_dice_expression(input: &str) -> Result<DiceRoll> {
         return Ok(expanded_dice);
     }
 
+    // A built-in alias with parameters out of range (ed15 k7) is refused
+    // rather than read as dice and modifiers
+    if let Some(error) = super::aliases::alias_parameter_error(remaining) {
+        return Err(error);
+    }
+
     // Check for simple advantage/disadvantage patterns (without additional modifiers)
     // Only do alias expansion, don't try to be clever about advantage detection here
     if let Some(expanded) = super::aliases::expand_alias(remaining) {
@@ -414,7 +1086,7 @@ fn parse_single_dice_expression(input: &str) -> Result<DiceRoll> {
     let parts = parse_expression_to_parts(remaining)?;
 
     if parts.is_empty() {
-        return Err(anyhow!("No dice expression found"));
+        return Err(DiceError::ParseError("No dice expression found".into()));
     }
 
     // Check for advantage/disadvantage as the first part of a larger expression
@@ -498,6 +1170,264 @@ fn parse_single_dice_expression(input: &str) -> Result<DiceRoll> {
     Ok(dice)
 }
 
+// `count` and the comma-separated `faces` of `2d{yes,no,maybe}`; each die
+// has as many sides as faces
+fn parse_face_dice(dice: &mut DiceRoll, count: &str, faces: &str) -> Result<()> {
+    let count: u32 = if count.is_empty() {
+        1
+    } else {
+        count.parse().map_err(|_| {
+            DiceError::RollLimitExceeded(format!("Maximum {MAX_FACE_DICE} labeled dice allowed"))
+        })?
+    };
+    if count == 0 {
+        return Err(DiceError::ValidationError("Cannot roll 0 dice".into()));
+    }
+    if count > MAX_FACE_DICE {
+        return Err(DiceError::RollLimitExceeded(format!(
+            "Maximum {MAX_FACE_DICE} labeled dice allowed"
+        )));
+    }
+
+    let faces: Vec<String> = faces
+        .split(',')
+        .map(|face| face.trim().to_string())
+        .collect();
+    if faces.iter().any(String::is_empty) {
+        return Err(DiceError::ValidationError(
+            "Labeled dice can't have an empty face, e.g. `d{yes,no}`".into(),
+        ));
+    }
+    if !(2..=MAX_FACES).contains(&faces.len()) {
+        return Err(DiceError::ValidationError(format!(
+            "Labeled dice need 2-{MAX_FACES} faces, got {}",
+            faces.len()
+        )));
+    }
+    if let Some(face) = faces
+        .iter()
+        .find(|face| face.chars().count() > MAX_FACE_LENGTH)
+    {
+        return Err(DiceError::ValidationError(format!(
+            "Faces can be at most {MAX_FACE_LENGTH} characters (`{}…`)",
+            face.chars().take(20).collect::<String>()
+        )));
+    }
+
+    dice.count = count;
+    dice.sides = faces.len() as u32;
+    dice.faces = Some(faces);
+    Ok(())
+}
+
+/// Whether `input` is plain arithmetic on whole numbers, with no dice
+pub fn is_math_only(input: &str) -> bool {
+    MATH_ONLY_REGEX.is_match(input.trim())
+}
+
+// Math-only expressions roll 0d0: the first number is added to the empty
+// total and the rest become math modifiers, evaluated left to right like the
+// math after a dice roll
+fn parse_math_only(dice: &mut DiceRoll, input: &str) -> Result<()> {
+    dice.count = 0;
+    dice.sides = 0;
+
+    let mut operator = "+";
+    for token in MATH_TOKEN_REGEX.find_iter(input).map(|m| m.as_str()) {
+        if matches!(token, "+" | "-" | "*" | "/") {
+            operator = token;
+            continue;
+        }
+        let number: i32 = token
+            .parse()
+            .map_err(|_| DiceError::RollLimitExceeded(format!("Number too large: {}", token)))?;
+        dice.modifiers.push(match operator {
+            "-" => Modifier::Subtract(number),
+            "*" => Modifier::Multiply(number),
+            "/" => Modifier::Divide(number),
+            _ => Modifier::Add(number),
+        });
+    }
+    Ok(())
+}
+
+// Whether `input` opens with a parenthesised group followed by an operator
+// or nothing, i.e. `pemdas` math rather than a label
+fn is_leading_group(input: &str) -> bool {
+    if !input.starts_with('(') {
+        return false;
+    }
+    let mut depth = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    let after = input[i + 1..].trim_start();
+                    return after.is_empty() || after.starts_with(['+', '-', '*', '/', ')', '!']);
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+// `pemdas` math: numbers and dice terms (with their modifiers, e.g. `4d6 k3`)
+// joined by + - * / and grouped with parentheses.  A dice term that comes
+// first is the roll's own dice; every other operand becomes a math modifier
+// in source order, with `OpenGroup` / `CloseGroup` where the parentheses
+// are.  Input that starts with a number rolls 0d0, like math-only input.
+fn parse_precedence_math(dice: &mut DiceRoll, input: &str) -> Result<()> {
+    let invalid = || DiceError::ParseError(format!("Invalid `pemdas` math: {input}"));
+    let is_symbol = |token: &str| matches!(token, "(" | ")" | "+" | "-" | "*" | "/");
+
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    for c in input.chars() {
+        if c.is_whitespace() || "()+-*/".contains(c) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            if !c.is_whitespace() {
+                tokens.push(c.to_string());
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+
+    let mut base = None;
+    let mut base_at = 0;
+    let mut math = Vec::new();
+    let mut operator = "+";
+    let mut depth = 0usize;
+    let mut expect_operand = true;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i].as_str();
+        match token {
+            "(" if expect_operand => {
+                depth += 1;
+                math.push(Modifier::OpenGroup);
+            }
+            ")" if !expect_operand && depth > 0 => {
+                depth -= 1;
+                math.push(Modifier::CloseGroup);
+            }
+            "+" | "-" | "*" | "/" if !expect_operand => {
+                operator = token;
+                expect_operand = true;
+            }
+            _ if expect_operand => {
+                // A leading `-` negates a number: `2d6 * -1`
+                let negative = token == "-";
+                if negative {
+                    i += 1;
+                }
+                let mut term = tokens.get(i).cloned().ok_or_else(invalid)?;
+                if is_symbol(&term) {
+                    return Err(invalid());
+                }
+                // Modifiers written after a die, e.g. the `k3` of `4d6 k3`
+                while let Some(next) = tokens.get(i + 1).filter(|next| !is_symbol(next)) {
+                    term.push(' ');
+                    term.push_str(next);
+                    i += 1;
+                }
+
+                if let Ok(number) = term.parse::<i32>() {
+                    let number = if negative { -number } else { number };
+                    math.push(match operator {
+                        "-" => Modifier::Subtract(number),
+                        "*" => Modifier::Multiply(number),
+                        "/" if number == 0 => {
+                            return Err(DiceError::DivisionByZero { by_dice: false });
+                        }
+                        "/" => Modifier::Divide(number),
+                        _ => Modifier::Add(number),
+                    });
+                } else if negative {
+                    return Err(invalid());
+                } else {
+                    let term_dice = parse_single_dice_expression(&term)?;
+                    let first = math.iter().all(|m| matches!(m, Modifier::OpenGroup));
+                    if base.is_none() && first {
+                        base = Some(term_dice);
+                        base_at = math.len();
+                    } else {
+                        math.push(match operator {
+                            "-" => Modifier::SubtractDice(term_dice),
+                            "*" => Modifier::MultiplyDice(term_dice),
+                            "/" => Modifier::DivideDice(term_dice),
+                            _ => Modifier::AddDice(term_dice),
+                        });
+                    }
+                }
+                expect_operand = false;
+            }
+            _ => return Err(invalid()),
+        }
+        i += 1;
+    }
+    if expect_operand {
+        return Err(invalid());
+    }
+    if depth > 0 {
+        return Err(DiceError::ParseError(format!(
+            "Unclosed parenthesis in `pemdas` math: {input}"
+        )));
+    }
+
+    // Parentheses before the roll's own dice go first, so they enclose its
+    // total along with its own math
+    let rest = math.split_off(base_at);
+    match base {
+        Some(base) => {
+            dice.count = base.count;
+            dice.sides = base.sides;
+            dice.faces = base.faces;
+            dice.pool = base.pool;
+            math.extend(base.modifiers);
+        }
+        None => {
+            dice.count = 0;
+            dice.sides = 0;
+        }
+    }
+    math.extend(rest);
+    dice.modifiers = math;
+    Ok(())
+}
+
+// The die size of each die in a mixed pool's `2d8+d6`, in order
+fn parse_mixed_pool(terms: &str) -> Result<Vec<u32>> {
+    let too_many = || DiceError::RollLimitExceeded("Maximum 500 dice allowed".into());
+    let mut sizes = Vec::new();
+    for captures in MIXED_POOL_TERM_REGEX.captures_iter(terms) {
+        let count: usize = match &captures[1] {
+            "" => 1,
+            count => count.parse().map_err(|_| too_many())?,
+        };
+        let sides: u32 = captures[2].parse().map_err(|_| {
+            DiceError::RollLimitExceeded(format!("Maximum {MAX_DICE_SIDES} sides allowed"))
+        })?;
+        if count == 0 {
+            return Err(DiceError::ValidationError("Cannot roll 0 dice".into()));
+        }
+        validate_sides(sides)?;
+        if sizes.len() + count > 500 {
+            return Err(too_many());
+        }
+        sizes.extend(std::iter::repeat_n(sides, count));
+    }
+    Ok(sizes)
+}
+
 // Helper function to create default dice roll, eliminating duplication
 fn create_default_dice_roll() -> DiceRoll {
     DiceRoll {
@@ -507,6 +1437,14 @@ fn create_default_dice_roll() -> DiceRoll {
         comment: None,
         label: None,
         private: false,
+        drama: false,
+        pemdas: false,
+        embed: false,
+        full: false,
+        gm: false,
+        language: Language::English,
+        faces: None,
+        pool: None,
         simple: false,
         no_results: false,
         unsorted: false,
@@ -520,13 +1458,18 @@ fn transfer_dice_metadata(source: &DiceRoll, target: &mut DiceRoll) {
     target.simple = source.simple;
     target.no_results = source.no_results;
     target.unsorted = source.unsorted;
+    target.drama = source.drama;
+    target.pemdas = source.pemdas;
+    target.embed = source.embed;
+    target.full = source.full;
+    target.gm = source.gm;
+    target.language = source.language;
     target.comment = source.comment.clone();
     target.label = source.label.clone();
 }
 
 fn normalize_whitespace(input: &str) -> String {
-    let whitespace_regex = Regex::new(r"\s+").unwrap();
-    whitespace_regex.replace_all(input.trim(), " ").to_string()
+    WHITESPACE_REGEX.replace_all(input.trim(), " ").into_owned()
 }
 
 // Handle both spaced and combined expressions
@@ -554,9 +1497,7 @@ fn parse_expression_to_parts(input: &str) -> Result<Vec<String>> {
     // Only handle advantage patterns when they're part of complex expressions (multiple operators)
 
     // Special handling for expressions like "200 / 2d4" or "500 / d%" where number comes before dice
-    let number_op_dice_regex =
-        Regex::new(r"^(\d+)\s*([+\-*/])\s+(d\d+.*|d%.*|\d*d\d+.*)$").unwrap();
-    if let Some(captures) = number_op_dice_regex.captures(&normalized) {
+    if let Some(captures) = NUMBER_OP_DICE_REGEX.captures(&normalized) {
         let number = &captures[1];
         let operator = &captures[2];
         let dice_part = &captures[3];
@@ -655,8 +1596,7 @@ struct DiceAndModifiers {
 // Helper function to parse dice and additional modifiers
 fn parse_dice_and_additional_modifiers(input: &str) -> Result<DiceAndModifiers> {
     // Look for the dice expression first
-    let dice_regex = Regex::new(r"^(\d*d\d+|d%|d\d+)(.*)$").unwrap();
-    if let Some(captures) = dice_regex.captures(input) {
+    if let Some(captures) = DICE_WITH_REST_REGEX.captures(input) {
         let dice_part = captures[1].to_string();
         let remaining = captures[2].trim();
 
@@ -774,8 +1714,7 @@ fn has_attached_modifiers(input: &str) -> bool {
 fn parse_combined_expression(input: &str) -> Result<Vec<String>> {
     // Check for number/dice pattern first (e.g., "200/2d4", "500/d%", "100/2d1+5")
     // But be more careful about capturing only the dice part, not trailing spaces/operators
-    let number_div_dice_regex = Regex::new(r"^(\d+)/(\S+)(.*)$").unwrap();
-    if let Some(captures) = number_div_dice_regex.captures(input) {
+    if let Some(captures) = NUMBER_DIV_PART_REGEX.captures(input) {
         let number = &captures[1];
         let dice_part = &captures[2];
         let trailing_part = captures[3].trim();
@@ -925,8 +1864,7 @@ fn split_dice_and_modifiers(input: &str) -> Option<(String, String)> {
     }
 
     // Match basic dice pattern
-    let dice_regex = Regex::new(r"^(\d*d\d+)(.*)$").unwrap();
-    if let Some(captures) = dice_regex.captures(input) {
+    if let Some(captures) = BASIC_DICE_WITH_REST_REGEX.captures(input) {
         let dice_part = captures[1].to_string();
         let modifiers_part = captures[2].to_string();
 
@@ -970,49 +1908,13 @@ fn split_combined_modifiers(input: &str) -> Result<Vec<String>> {
     const MAX_NO_PROGRESS: usize = 3;
 
     while !remaining.is_empty() {
-        // COMPLETE patterns array in correct order
-        let patterns = [
-            (r"^(ie\d*)", "indefinite explode"), // ie, ie6 (BEFORE regular explode)
-            (r"^(irg\d+)", "indefinite reroll greater"), // irg5 (BEFORE rg)
-            (r"^(ir\d+)", "indefinite reroll"),  // ir1 (BEFORE regular reroll)
-            (r"^(km\d+)", "keep middle"),        // km3 (BEFORE regular keep)
-            (r"^(kl\d+)", "keep low"),           // kl2 (BEFORE regular keep)
-            (r"^(tl\d+)", "target lower"),       // tl5 (BEFORE regular target)
-            (r"^(rg\d+)", "reroll greater"),     // rg5
-            (r"^(k\d+)", "keep high"),           // k3
-            (r"^(d\d+)", "drop"),                // d1
-            (r"^(r\d+)", "reroll"),              // r1
-            (r"^(t\d+)", "target"),              // t4, t7
-            (r"^(f\d+)", "failure"),             // f1
-            (r"^(e\d*)", "explode"),             // e, e6 (AFTER indefinite explode)
-            (r"^(b\d*)", "botch"),               // b, b1
-            (r"^(c)", "cancel"),                 // c
-            (r"^(wng\d*t?)", "wrath & glory"),   // wng patterns
-            (r"^(gb|gbs)", "godbound"),          // gb, gbs
-            (r"^(hs[nkh])", "hero system"),      // hsn, hsk, hsh
-            (r"^(dh)", "dark heresy"),           // dh
-            (r"^(fudge|df)", "fudge"),           // fudge, df
-            (r"^(d6s\d+(?:\+\d+)?(?:\-\d+)?)", "d6 system"), // d6s patterns
-            (r"^(cpr)", "cyberpunk red"),        // cpr
-            (r"^(wit)", "witcher"),              // wit
-            (r"^(bnw)", "brave new world"),      // bnw
-            (r"^(alien)", "alien base"),         // alien (exact)
-            (r"^(aliens\d+)", "alien stress"),   // aliens1, aliens2, etc.
-            (r"^(wwc\d+)", "wild worlds cut"),   // wwc2, wwc3, etc. (before basic ww)
-            (r"^(ms\d+[ad]?|ms[ad]?|ms)", "mothership"),
-            (r"^(ww)", "wild worlds"),  // ww (basic)
-            (r"^(plot)", "plotweaver"), // plot
-        ];
-
         let mut found_match = false;
 
-        for (pattern, _) in &patterns {
-            if let Ok(regex) = Regex::new(pattern)
-                && let Some(captures) = regex.captures(remaining)
+        for regex in COMBINED_MODIFIER_REGEXES.iter() {
+            if let Some(captures) = regex.captures(remaining)
                 && let Some(matched) = captures.get(1)
             {
-                let modifier = matched.as_str().to_string();
-                modifiers.push(modifier.clone());
+                modifiers.push(matched.as_str().to_string());
 
                 // Move past this modifier
                 remaining = &remaining[matched.end()..];
@@ -1023,11 +1925,10 @@ fn split_combined_modifiers(input: &str) -> Result<Vec<String>> {
         }
 
         if !found_match {
-            return Err(anyhow!(
+            return Err(DiceError::ValidationError(format!(
                 "Unable to parse combined modifier: '{}' at position: '{}'. Expected valid modifier pattern.",
-                input,
-                remaining
-            ));
+                input, remaining
+            )));
         }
 
         // Progress tracking
@@ -1035,11 +1936,10 @@ fn split_combined_modifiers(input: &str) -> Result<Vec<String>> {
         if current_length == last_length {
             no_progress_count += 1;
             if no_progress_count > MAX_NO_PROGRESS {
-                return Err(anyhow!(
+                return Err(DiceError::ValidationError(format!(
                     "Infinite loop detected while parsing modifiers '{}' at position: '{}'",
-                    input,
-                    remaining
-                ));
+                    input, remaining
+                )));
             }
         } else {
             no_progress_count = 0;
@@ -1048,22 +1948,39 @@ fn split_combined_modifiers(input: &str) -> Result<Vec<String>> {
     }
 
     if modifiers.is_empty() {
-        return Err(anyhow!("No valid modifiers found in: {}", input));
+        return Err(DiceError::ParseError(format!(
+            "No valid modifiers found in: {}",
+            input
+        )));
     }
 
     Ok(modifiers)
 }
 
 // Better flag parsing with proper whitespace handling
+// Roll flags, in the order `parse_flags` tries them
+const ROLL_FLAGS: [&str; 9] = ["drama", "pemdas", "full", "gm", "p", "s", "nr", "ul", "e"];
+
+/// Whether `word` already means something in a roll on its own: a whole
+/// expression (`d20`, `4cod`), a flag (`p`, `gm`), a language code (`fr`) or
+/// a modifier (`k3`, `e6`, `rfail`).  A macro or alias by that name would
+/// change every roll using it.
+pub fn is_roll_word(word: &str) -> bool {
+    ROLL_FLAGS.contains(&word)
+        || LANGUAGE_CODES.iter().any(|(code, _)| *code == word)
+        || parse_single_modifier(word).is_ok()
+        || parse_dice_string(word).is_ok()
+}
+
 fn parse_flags<'a>(dice: &mut DiceRoll, mut remaining: &'a str) -> &'a str {
-    let flags = ["p", "s", "nr", "ul"];
+    let language_codes = LANGUAGE_CODES.map(|(code, _)| code);
 
     let mut changed = true;
     while changed {
         changed = false;
         remaining = remaining.trim_start();
 
-        for &flag in &flags {
+        for &flag in ROLL_FLAGS.iter().chain(&language_codes) {
             if remaining.starts_with(flag) {
                 // Check if it's a complete flag (followed by space or end of string)
                 let after_flag = &remaining[flag.len()..];
@@ -1076,7 +1993,16 @@ fn parse_flags<'a>(dice: &mut DiceRoll, mut remaining: &'a str) -> &'a str {
                         "s" => dice.simple = true,
                         "nr" => dice.no_results = true,
                         "ul" => dice.unsorted = true,
-                        _ => {}
+                        "drama" => dice.drama = true,
+                        "pemdas" => dice.pemdas = true,
+                        "e" => dice.embed = true,
+                        "full" => dice.full = true,
+                        "gm" => dice.gm = true,
+                        code => {
+                            if let Some(language) = Language::from_code(code) {
+                                dice.language = language;
+                            }
+                        }
                     }
                     remaining = remaining[flag.len()..].trim_start();
                     changed = true;
@@ -1090,6 +2016,12 @@ fn parse_flags<'a>(dice: &mut DiceRoll, mut remaining: &'a str) -> &'a str {
 }
 
 fn parse_label<'a>(dice: &mut DiceRoll, remaining: &'a str) -> &'a str {
+    // In a `pemdas` roll, leading parentheses are a label only when dice
+    // follow them: `(Fireball) 8d6`, but `(2d6 + 3) * 2` groups math
+    if dice.pemdas && is_leading_group(remaining) {
+        return remaining;
+    }
+
     if let Some(captures) = LABEL_REGEX.captures(remaining) {
         let label_content = &captures[1];
         let trimmed = label_content.trim();
@@ -1162,7 +2094,10 @@ fn parse_base_dice(dice: &mut DiceRoll, part: &str) -> Result<()> {
         return Ok(());
     }
 
-    Err(anyhow!("Invalid dice expression: {}", part))
+    Err(DiceError::ParseError(format!(
+        "Invalid dice expression: {}",
+        part
+    )))
 }
 
 // Helper function to parse modifiers from a part, reducing duplication
@@ -1187,28 +2122,26 @@ fn parse_simple_dice_part(dice: &mut DiceRoll, part: &str) -> Result<()> {
         if &captures[2] == "%" {
             dice.sides = 100;
         } else {
-            dice.sides = captures[2]
-                .parse()
-                .map_err(|_| anyhow!("Invalid dice sides"))?;
+            dice.sides = captures[2].parse().map_err(|_| {
+                DiceError::RollLimitExceeded(format!("Maximum {MAX_DICE_SIDES} sides allowed"))
+            })?;
         }
 
         if dice.count == 0 {
-            return Err(anyhow!("Cannot roll 0 dice"));
+            return Err(DiceError::ValidationError("Cannot roll 0 dice".into()));
         }
 
         if dice.count > 500 {
-            return Err(anyhow!("Maximum 500 dice allowed"));
-        }
-        if dice.sides < 1 {
-            return Err(anyhow!("Dice must have at least 1 side"));
-        }
-        if dice.sides > 1000 {
-            return Err(anyhow!("Maximum 1000 sides allowed"));
+            return Err(DiceError::RollLimitExceeded(
+                "Maximum 500 dice allowed".into(),
+            ));
         }
-
-        Ok(())
+        validate_sides(dice.sides)
     } else {
-        Err(anyhow!("Invalid dice expression: {}", part))
+        Err(DiceError::ParseError(format!(
+            "Invalid dice expression: {}",
+            part
+        )))
     }
 }
 
@@ -1296,27 +2229,9 @@ fn is_combined_modifiers_token(input: &str) -> bool {
     }
 
     // Check if it starts with common modifier patterns and has more after the first one
-    let modifier_patterns = [
-        r"^(ie\d*)",  // Indefinite explode first (longer pattern)
-        r"^(irg\d+)", // Indefinite reroll greater
-        r"^(ir\d+)",  // Indefinite reroll
-        r"^(km\d+)",  // Keep middle
-        r"^(kl\d+)",  // Keep low
-        r"^(tl\d+)",  // Target lower (must come before regular target)
-        r"^(rg\d+)",  // Reroll greater
-        r"^(e\d*)",   // Explode
-        r"^(k\d+)",   // Keep high
-        r"^(d\d+)",   // Drop
-        r"^(r\d+)",   // Reroll
-        r"^(t\d+)",   // Target - KEY FOR D6 LEGENDS
-        r"^(f\d+)",   // Failure - KEY FOR D6 LEGENDS
-        r"^(b\d*)",   // Botch
-    ];
-
-    for pattern in &modifier_patterns {
-        if let Ok(regex) = Regex::new(pattern)
-            && let Some(captures) = regex.captures(input)
-        {
+
+    for regex in LEADING_MODIFIER_REGEXES.iter() {
+        if let Some(captures) = regex.captures(input) {
             let first_modifier = &captures[1];
             let match_length = first_modifier.len();
 
@@ -1353,50 +2268,7 @@ fn is_modifier_start(input: &str) -> bool {
     }
 
     // Check if it starts with any known modifier pattern (not requiring it to be complete)
-    let modifier_start_patterns = [
-        r"^ie\d*",  // Indefinite explode: ie, ie6
-        r"^irg\d+", // Indefinite reroll greater: irg5
-        r"^ir\d+",  // Indefinite reroll: ir1
-        r"^km\d+",  // Keep middle: km3
-        r"^kl\d+",  // Keep low: kl2
-        r"^tl\d+",  // Target lower: tl5
-        r"^rg\d+",  // Reroll greater: rg5
-        r"^k\d+",   // Keep high: k3
-        r"^d\d+",   // Drop: d1
-        r"^r\d+",   // Reroll: r1
-        r"^t\d+",   // Target: t4, t7 - CRITICAL FOR D6 LEGENDS
-        r"^f\d+",   // Failure: f1 - CRITICAL FOR D6 LEGENDS
-        r"^e\d*",   // Explode: e, e6
-        r"^b\d*",   // Botch: b, b1
-        r"^c$",     // Cancel: c (exact match)
-        // System modifiers
-        r"^wng",       // Wrath & Glory patterns
-        r"^gb$",       // Godbound (exact)
-        r"^gbs$",      // Godbound straight (exact)
-        r"^hs[nkh]",   // Hero System
-        r"^dh$",       // Dark Heresy (exact)
-        r"^fudge$",    // Fudge (exact)
-        r"^df$",       // Fudge dice (exact)
-        r"^d6s\d+",    // D6 System
-        r"^cpr$",      // Cyberpunk Red (exact)
-        r"^wit$",      // Witcher (exact)
-        r"^alien$",    // Alien base modifier (exact)
-        r"^aliens\d+", // Alien stress modifiers: aliens1, aliens2, etc.
-        r"^fitd$",     // Forged in the Dark (exact)
-        r"^fitd0$",    // FitD zero dice (exact)
-        r"^plot$",     // Plotweaver/Cosmere RPG plot die (exact)
-    ];
-
-    // Check if the input starts with any of these patterns
-    for pattern in &modifier_start_patterns {
-        if let Ok(regex) = Regex::new(pattern)
-            && regex.is_match(input)
-        {
-            return true;
-        }
-    }
-
-    false
+    MODIFIER_START_SET.is_match(input)
 }
 
 fn try_parse_operator_pair(
@@ -1454,7 +2326,7 @@ fn try_parse_operator_pair(
         "/" => {
             if let Ok(num) = second.parse::<i32>() {
                 if num == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 dice.modifiers.push(Modifier::Divide(num));
                 return Ok(Some(2));
@@ -1494,14 +2366,413 @@ fn parse_complex_dice_expression(input: &str) -> Result<DiceRoll> {
         parse_modifiers_from_part(&mut dice, &modifiers_part)?;
         Ok(dice)
     } else {
-        Err(anyhow!("Invalid complex dice expression: {}", input))
+        Err(DiceError::ParseError(format!(
+            "Invalid complex dice expression: {}",
+            input
+        )))
+    }
+}
+
+// The dice of a `gen` modifier, e.g. "2a1p2d" for 2 ability, 1 proficiency
+// and 2 difficulty dice
+fn parse_genesys_pool(spec: &str, part: &str) -> Result<Modifier> {
+    let invalid = || DiceError::ParseError(format!("Invalid Genesys dice pool '{}'", part));
+    let mut pool = NarrativePool::default();
+    let mut rest = spec;
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let count: u32 = rest[..digits].parse().map_err(|_| invalid())?;
+        let die = match rest[digits..].chars().next() {
+            Some('b') => &mut pool.boost,
+            Some('a') => &mut pool.ability,
+            Some('p') => &mut pool.proficiency,
+            Some('s') => &mut pool.setback,
+            Some('d') => &mut pool.difficulty,
+            Some('c') => &mut pool.challenge,
+            _ => return Err(invalid()),
+        };
+        *die = die.checked_add(count).ok_or_else(invalid)?;
+        rest = &rest[digits + 1..];
+    }
+
+    let dice_count = pool.dice_count();
+    if dice_count == 0 {
+        return Err(DiceError::ValidationError(
+            "A Genesys dice pool needs at least 1 die".into(),
+        ));
+    }
+    if dice_count > MAX_NARRATIVE_DICE {
+        return Err(DiceError::RollLimitExceeded(format!(
+            "Maximum {} Genesys dice allowed",
+            MAX_NARRATIVE_DICE
+        )));
+    }
+    Ok(Modifier::Genesys(pool))
+}
+
+// `spec` is what follows `coc`: an optional skill, then `b` or `p` and the
+// number of bonus or penalty dice
+fn parse_coc_roll(spec: &str, part: &str) -> Result<Modifier> {
+    let invalid = || DiceError::ParseError(format!("Invalid Call of Cthulhu roll '{}'", part));
+    let (skill, dice) = match spec.find(['b', 'p']) {
+        Some(pos) => spec.split_at(pos),
+        None => (spec, ""),
+    };
+
+    let skill = if skill.is_empty() {
+        None
+    } else {
+        let skill: u32 = skill.parse().map_err(|_| invalid())?;
+        if !(1..=99).contains(&skill) {
+            return Err(DiceError::ValidationError(format!(
+                "Call of Cthulhu skill must be 1-99, got {}",
+                skill
+            )));
+        }
+        Some(skill)
+    };
+
+    let bonus = match dice {
+        "" => 0,
+        "b1" => 1,
+        "b2" => 2,
+        "p1" => -1,
+        "p2" => -2,
+        _ => return Err(invalid()),
+    };
+    Ok(Modifier::CoC(skill, bonus))
+}
+
+/// Highest skill a WFRP test accepts, difficulty included (+60 on a 99 skill
+/// gives 159)
+pub const MAX_WFRP_SKILL: u32 = 200;
+
+// `spec` is what follows `wfrp`: the skill tested against
+fn parse_wfrp_roll(spec: &str, part: &str) -> Result<Modifier> {
+    let skill: u32 = spec.parse().map_err(|_| {
+        DiceError::ParseError(format!(
+            "Invalid Warhammer Fantasy Roleplay roll '{}'",
+            part
+        ))
+    })?;
+    if !(1..=MAX_WFRP_SKILL).contains(&skill) {
+        return Err(DiceError::ValidationError(format!(
+            "Warhammer Fantasy Roleplay skill must be 1-{}, got {}",
+            MAX_WFRP_SKILL, skill
+        )));
+    }
+    Ok(Modifier::Wfrp(skill))
+}
+
+/// Highest effective skill a GURPS success roll accepts
+pub const MAX_GURPS_SKILL: i64 = 30;
+
+// `spec` is what follows `gurps`: the effective skill, modifiers included
+fn parse_gurps_roll(spec: &str, part: &str) -> Result<Modifier> {
+    let skill: i64 = spec
+        .parse()
+        .map_err(|_| DiceError::ParseError(format!("Invalid GURPS roll '{}'", part)))?;
+    if !(0..=MAX_GURPS_SKILL).contains(&skill) {
+        return Err(DiceError::ValidationError(format!(
+            "GURPS effective skill must be 0-{}, got {}",
+            MAX_GURPS_SKILL, skill
+        )));
+    }
+    Ok(Modifier::Gurps(skill as u32))
+}
+
+/// Highest skill value a Dragonbane roll accepts
+pub const MAX_DRAGONBANE_SKILL: u32 = 20;
+
+// `spec` is what follows `db`: an optional skill, then `boon` or `bane`,
+// then `push` for a pushed roll
+fn parse_dragonbane_roll(spec: &str, part: &str) -> Result<Modifier> {
+    let invalid = || DiceError::ParseError(format!("Invalid Dragonbane roll '{}'", part));
+    let (spec, pushed) = match spec.strip_suffix("push") {
+        Some(spec) => (spec, true),
+        None => (spec, false),
+    };
+    let (skill, boon) = if let Some(skill) = spec.strip_suffix("boon") {
+        (skill, 1)
+    } else if let Some(skill) = spec.strip_suffix("bane") {
+        (skill, -1)
+    } else {
+        (spec, 0)
+    };
+
+    let skill = if skill.is_empty() {
+        None
+    } else {
+        let skill: u32 = skill.parse().map_err(|_| invalid())?;
+        if !(1..=MAX_DRAGONBANE_SKILL).contains(&skill) {
+            return Err(DiceError::ValidationError(format!(
+                "Dragonbane skill must be 1-{}, got {}",
+                MAX_DRAGONBANE_SKILL, skill
+            )));
+        }
+        Some(skill)
+    };
+    Ok(Modifier::Dragonbane(skill, boon, pushed))
+}
+
+/// Most tiers a target ladder (`t[90,70,50]`) takes
+pub const MAX_LADDER_TIERS: usize = 4;
+
+// `t[90,70,50]`: thresholds in any order, kept highest first
+fn parse_target_ladder(part: &str) -> Result<Modifier> {
+    let captures = TARGET_LADDER_REGEX.captures(part).ok_or_else(|| {
+        DiceError::ParseError(format!(
+            "Invalid target ladder '{}': list the tiers in brackets, e.g. t[90,70,50]",
+            part
+        ))
+    })?;
+    let mut thresholds = captures[1]
+        .split(',')
+        .map(|value| {
+            value.parse::<u32>().map_err(|_| {
+                DiceError::ParseError(format!("Invalid ladder tier '{}' in '{}'", value, part))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if thresholds.len() < 2 {
+        return Err(DiceError::ValidationError(format!(
+            "Target ladders take at least 2 tiers, e.g. t[90,70,50] (got '{}')",
+            part
+        )));
+    }
+    if thresholds.len() > MAX_LADDER_TIERS {
+        return Err(DiceError::ValidationError(format!(
+            "Target ladders take at most {} tiers, got {}",
+            MAX_LADDER_TIERS,
+            thresholds.len()
+        )));
+    }
+    if thresholds.contains(&0) {
+        return Err(DiceError::ValidationError(
+            "Ladder tiers must be greater than 0".into(),
+        ));
+    }
+    thresholds.sort_unstable_by(|a, b| b.cmp(a));
+    if thresholds.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(DiceError::ValidationError(format!(
+            "Ladder tiers must all differ (got '{}')",
+            part
+        )));
+    }
+    Ok(Modifier::TargetLadder(thresholds))
+}
+
+/// Thresholds used by a bare `bands`: 6- failure, 7-9 partial, 10+ success (PbtA)
+pub const DEFAULT_BANDS: [i32; 2] = [6, 9];
+
+/// Most thresholds `bands[...]` takes (failure, partial, success, critical)
+pub const MAX_BANDS: usize = 3;
+
+// `bands` or `bands[a,b,…]` with ascending thresholds
+fn parse_bands(part: &str) -> Result<Modifier> {
+    let captures = BANDS_REGEX.captures(part).ok_or_else(|| {
+        DiceError::ParseError(format!(
+            "Invalid bands '{}': list the highest total of each band, e.g. bands[6,9]",
+            part
+        ))
+    })?;
+    match captures.get(1) {
+        Some(list) => parse_band_thresholds(list.as_str()).map(Modifier::Bands),
+        None => Ok(Modifier::Bands(DEFAULT_BANDS.to_vec())),
+    }
+}
+
+/// Parse the thresholds of `bands[...]`, e.g. `6,9`.  Thresholds can't be
+/// negative, since `-` would split the roll expression.
+pub fn parse_band_thresholds(list: &str) -> Result<Vec<i32>> {
+    let thresholds = list
+        .split(',')
+        .map(|value| {
+            value
+                .trim()
+                .parse::<u32>()
+                .ok()
+                .and_then(|threshold| i32::try_from(threshold).ok())
+                .ok_or_else(|| {
+                    DiceError::ParseError(format!(
+                        "Invalid band threshold '{}' in '{}'",
+                        value, list
+                    ))
+                })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if thresholds.len() > MAX_BANDS {
+        return Err(DiceError::ValidationError(format!(
+            "bands takes at most {} thresholds, got {}",
+            MAX_BANDS,
+            thresholds.len()
+        )));
+    }
+    if thresholds.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(DiceError::ValidationError(format!(
+            "Band thresholds must go up, e.g. bands[6,9] (got '{}')",
+            list
+        )));
+    }
+    Ok(thresholds)
+}
+
+// An `l5r` modifier such as "5k3": roll 5 dice and keep 3, so 3 ring dice
+// (the ring is the keep limit) and 2 skill dice
+fn parse_l5r_pool(spec: &str, part: &str) -> Result<Modifier> {
+    let invalid = || {
+        DiceError::ParseError(format!(
+            "Invalid L5R dice pool '{}', expected e.g. l5r5k3",
+            part
+        ))
+    };
+    let (rolled, kept) = spec.split_once('k').ok_or_else(invalid)?;
+    let rolled: u32 = rolled.parse().map_err(|_| invalid())?;
+    let ring: u32 = kept.parse().map_err(|_| invalid())?;
+
+    if ring == 0 {
+        return Err(DiceError::ValidationError(
+            "An L5R check needs a ring of at least 1".into(),
+        ));
     }
+    if ring > rolled {
+        return Err(DiceError::ValidationError(format!(
+            "An L5R check can't keep more dice than it rolls ({}k{})",
+            rolled, ring
+        )));
+    }
+    if rolled > MAX_L5R_DICE {
+        return Err(DiceError::RollLimitExceeded(format!(
+            "Maximum {} L5R dice allowed",
+            MAX_L5R_DICE
+        )));
+    }
+    Ok(Modifier::L5r(L5rPool {
+        ring,
+        skill: rolled - ring,
+    }))
+}
+
+/// Highest stress a Mothership panic check takes
+pub const MAX_MOTHERSHIP_STRESS: u32 = 20;
+
+/// Most dice in a Forbidden Lands roll, artifact dice included
+pub const MAX_YEAR_ZERO_DICE: u32 = 30;
+
+// `spec` is what follows `fbl`: `b`, `s` and `g` with the number of base,
+// skill and gear dice, `a` with the sides of an artifact die (any number of
+// them), then `push`
+fn parse_year_zero_pool(spec: &str, part: &str) -> Result<Modifier> {
+    let invalid = || {
+        DiceError::ParseError(format!(
+            "Invalid Forbidden Lands roll '{}', expected e.g. fblb5s3g2a10",
+            part
+        ))
+    };
+    let (spec, push) = match spec.strip_suffix("push") {
+        Some(spec) => (spec, true),
+        None => (spec, false),
+    };
+
+    let mut pool = YearZeroPool {
+        push,
+        ..Default::default()
+    };
+    let mut rest = spec;
+    while let Some(kind) = rest.chars().next() {
+        let digits = &rest[kind.len_utf8()..];
+        let end = digits
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(digits.len());
+        let count: u32 = digits[..end].parse().map_err(|_| invalid())?;
+        rest = &digits[end..];
+        match kind {
+            'b' if pool.base == 0 => pool.base = count,
+            's' if pool.skill == 0 => pool.skill = count,
+            'g' if pool.gear == 0 => pool.gear = count,
+            'a' if matches!(count, 8 | 10 | 12) => pool.artifacts.push(count),
+            'a' => {
+                return Err(DiceError::ValidationError(format!(
+                    "Artifact dice are d8, d10 or d12, got d{}",
+                    count
+                )));
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    if pool.dice_count() == 0 {
+        return Err(DiceError::ValidationError(
+            "A Forbidden Lands roll needs at least one die".into(),
+        ));
+    }
+    if pool.dice_count() > MAX_YEAR_ZERO_DICE {
+        return Err(DiceError::RollLimitExceeded(format!(
+            "Maximum {} Forbidden Lands dice allowed",
+            MAX_YEAR_ZERO_DICE
+        )));
+    }
+    Ok(Modifier::YearZero(pool))
+}
+
+/// Most dice in a Burning Wheel test, helping dice included
+pub const MAX_BURNING_WHEEL_DICE: u32 = 30;
+
+// `spec` is what follows `bw`: the exponent, then `ob` with the obstacle,
+// `h` with the helping dice, `open` and `luck`
+fn parse_burning_wheel_pool(spec: &str, part: &str) -> Result<Modifier> {
+    let captures = BURNING_WHEEL_SPEC_REGEX.captures(spec).ok_or_else(|| {
+        DiceError::ParseError(format!(
+            "Invalid Burning Wheel roll '{}', expected e.g. bw5ob3h1",
+            part
+        ))
+    })?;
+    let number = |i: usize| -> Result<Option<u32>> {
+        captures
+            .get(i)
+            .map(|m| {
+                m.as_str().parse().map_err(|_| {
+                    DiceError::ParseError(format!(
+                        "Invalid number in Burning Wheel roll '{}'",
+                        part
+                    ))
+                })
+            })
+            .transpose()
+    };
+
+    let pool = BurningWheelPool {
+        exponent: number(1)?.unwrap_or_default(),
+        obstacle: number(2)?,
+        helping: number(3)?.unwrap_or_default(),
+        open: captures.get(4).is_some(),
+        luck: captures.get(5).is_some(),
+    };
+    if pool.exponent == 0 {
+        return Err(DiceError::ValidationError(
+            "A Burning Wheel test needs an exponent of at least 1".into(),
+        ));
+    }
+    if pool.obstacle == Some(0) {
+        return Err(DiceError::ValidationError(
+            "A Burning Wheel obstacle must be at least 1".into(),
+        ));
+    }
+    if pool.dice_count() > MAX_BURNING_WHEEL_DICE {
+        return Err(DiceError::RollLimitExceeded(format!(
+            "Maximum {} Burning Wheel dice allowed",
+            MAX_BURNING_WHEEL_DICE
+        )));
+    }
+    Ok(Modifier::BurningWheel(pool))
 }
 
 fn parse_single_modifier(part: &str) -> Result<Modifier> {
     // Reject standalone 'l' - it should only appear in d6l aliases
     if part == "l" {
-        return Err(anyhow!("Unrecognized modifier pattern: 'l' in 'l'"));
+        return Err(DiceError::ParseError(
+            "Unrecognized modifier pattern: 'l' in 'l'".into(),
+        ));
     }
 
     // Check for Alien RPG modifiers first
@@ -1510,15 +2781,22 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
     }
 
     if let Some(stripped) = part.strip_prefix("aliens") {
-        let stress_level: u32 = stripped
-            .parse()
-            .map_err(|_| anyhow!("Invalid stress level in Alien stress modifier '{}'", part))?;
+        let stress_level: u32 = stripped.parse().map_err(|_| {
+            DiceError::ParseError(format!(
+                "Invalid stress level in Alien stress modifier '{}'",
+                part
+            ))
+        })?;
 
         if stress_level == 0 {
-            return Err(anyhow!("Alien stress level cannot be 0"));
+            return Err(DiceError::ValidationError(
+                "Alien stress level cannot be 0".into(),
+            ));
         }
         if stress_level > 10 {
-            return Err(anyhow!("Alien stress level cannot exceed 10"));
+            return Err(DiceError::ValidationError(
+                "Alien stress level cannot exceed 10".into(),
+            ));
         }
 
         return Ok(Modifier::AlienStress(stress_level));
@@ -1548,21 +2826,82 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
         "bnw" => return Ok(Modifier::BraveNewWorld(0)),
         "mnm" => return Ok(Modifier::MutantsMasterminds),
         "c" => return Ok(Modifier::Cancel),
+        "m" => return Ok(Modifier::Matches),
+        "cortex" => return Ok(Modifier::Cortex),
+        "dcc" => return Ok(Modifier::Dcc),
         "ww" => return Ok(Modifier::WildWorlds(None)),
         "plot" => return Ok(Modifier::PlotDie),
+        "iron" => return Ok(Modifier::Ironsworn),
         _ => {}
     }
 
+    // Genesys / Star Wars FFG narrative pool (gen2a1p2d)
+    if let Some(stripped) = part.strip_prefix("gen") {
+        return parse_genesys_pool(stripped, part);
+    }
+
+    // Ironsworn oracle, answering yes on `odds` or higher (oracle, oracle26)
+    if let Some(stripped) = part.strip_prefix("oracle") {
+        if stripped.is_empty() {
+            return Ok(Modifier::Oracle(None));
+        }
+        let odds = stripped
+            .parse()
+            .map_err(|_| DiceError::ParseError(format!("Invalid oracle odds in '{}'", part)))?;
+        if !(1..=100).contains(&odds) {
+            return Err(DiceError::ValidationError(format!(
+                "Oracle odds must be 1-100, got {}",
+                odds
+            )));
+        }
+        return Ok(Modifier::Oracle(Some(odds)));
+    }
+
+    // Forbidden Lands base, skill, gear and artifact dice (fblb5s3g2a10push)
+    if let Some(stripped) = part.strip_prefix("fbl") {
+        return parse_year_zero_pool(stripped, part);
+    }
+
+    // Burning Wheel exponent against an obstacle (bw5ob3h1openluck)
+    if let Some(stripped) = part.strip_prefix("bw") {
+        return parse_burning_wheel_pool(stripped, part);
+    }
+
+    // Legend of the Five Rings 5e ring and skill dice (l5r5k3)
+    if let Some(stripped) = part.strip_prefix("l5r") {
+        return parse_l5r_pool(stripped, part);
+    }
+
+    // Call of Cthulhu 7e (coc, coc65, coc65b1, cocp2)
+    if let Some(stripped) = part.strip_prefix("coc") {
+        return parse_coc_roll(stripped, part);
+    }
+
+    // Warhammer Fantasy Roleplay 4e (wfrp45)
+    if let Some(stripped) = part.strip_prefix("wfrp") {
+        return parse_wfrp_roll(stripped, part);
+    }
+
+    // GURPS success roll (gurps12)
+    if let Some(stripped) = part.strip_prefix("gurps") {
+        return parse_gurps_roll(stripped, part);
+    }
+
+    // Dragonbane (db, db12, db12boon, db12banepush)
+    if let Some(stripped) = part.strip_prefix("db") {
+        return parse_dragonbane_roll(stripped, part);
+    }
+
     // Cypher System handling (cs1, cs3, cs10, etc.)
     if let Some(stripped) = part.strip_prefix("cs") {
-        let level = stripped
-            .parse()
-            .map_err(|_| anyhow!("Invalid Cypher System level in '{}'", part))?;
+        let level = stripped.parse().map_err(|_| {
+            DiceError::ParseError(format!("Invalid Cypher System level in '{}'", part))
+        })?;
         if !(1..=10).contains(&level) {
-            return Err(anyhow!(
+            return Err(DiceError::ValidationError(format!(
                 "Cypher System difficulty level must be 1-10, got {}",
                 level
-            ));
+            )));
         }
         return Ok(Modifier::CypherSystem(level));
     }
@@ -1577,13 +2916,48 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
         if (2..=5).contains(&dice_count) {
             return Ok(Modifier::ConanSkill(dice_count));
         } else {
-            return Err(anyhow!(
+            return Err(DiceError::ValidationError(format!(
                 "Conan skill rolls support 2-5 dice, got {}",
                 dice_count
-            ));
+            )));
         }
     }
 
+    // 2d20 check options (tn14, focus3, diff2, comp2)
+    for (prefix, name) in [
+        ("tn", "target number"),
+        ("focus", "focus"),
+        ("diff", "difficulty"),
+        ("comp", "complication range"),
+    ] {
+        let Some(value) = part
+            .strip_prefix(prefix)
+            .and_then(|digits| digits.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let range = match prefix {
+            "diff" => 0..=MAX_2D20_DIFFICULTY,
+            "comp" => 1..=MAX_2D20_COMPLICATION_RANGE,
+            _ => 1..=20,
+        };
+        if !range.contains(&value) {
+            return Err(DiceError::ValidationError(format!(
+                "2d20 {} must be {}-{}, got {}",
+                name,
+                range.start(),
+                range.end(),
+                value
+            )));
+        }
+        return Ok(match prefix {
+            "tn" => Modifier::TargetNumber(value),
+            "focus" => Modifier::Focus(value),
+            "diff" => Modifier::Difficulty(value),
+            _ => Modifier::Complication(value),
+        });
+    }
+
     // Conan combat dice handling (cd, cd4, cd10, etc.)
     if part == "cd" {
         return Ok(Modifier::ConanCombat(1)); // Default 1d6
@@ -1595,10 +2969,10 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
             // Reasonable limit
             return Ok(Modifier::ConanCombat(dice_count));
         } else {
-            return Err(anyhow!(
+            return Err(DiceError::ValidationError(format!(
                 "Conan combat dice count must be 1-100, got {}",
                 dice_count
-            ));
+            )));
         }
     }
 
@@ -1608,9 +2982,17 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
         return Ok(Modifier::AddDice(dice_roll));
     }
 
+    // Target ladder (t[90,70,50])
+    if part.starts_with("t[") {
+        return parse_target_ladder(part);
+    }
+
     // Check for invalid characters before parsing numbers
     if part.contains(['+', '-', '*', '/']) {
-        return Err(anyhow!("Invalid modifier '{}' - contains operator", part));
+        return Err(DiceError::ParseError(format!(
+            "Invalid modifier '{}' - contains operator",
+            part
+        )));
     }
 
     // Handle exploding dice
@@ -1618,16 +3000,14 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
         let num = if stripped.is_empty() {
             None
         } else {
-            Some(
-                stripped
-                    .parse()
-                    .map_err(|_| anyhow!("Invalid explode value in '{}'", part))?,
-            )
+            Some(stripped.parse().map_err(|_| {
+                DiceError::ParseError(format!("Invalid explode value in '{}'", part))
+            })?)
         };
         if let Some(val) = num
             && val == 0
         {
-            return Err(anyhow!("Cannot explode on 0"));
+            return Err(DiceError::ValidationError("Cannot explode on 0".into()));
         }
         return Ok(Modifier::ExplodeIndefinite(num));
     }
@@ -1636,20 +3016,39 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
         let num = if stripped.is_empty() {
             None
         } else {
-            Some(
-                stripped
-                    .parse()
-                    .map_err(|_| anyhow!("Invalid explode value in '{}'", part))?,
-            )
+            Some(stripped.parse().map_err(|_| {
+                DiceError::ParseError(format!("Invalid explode value in '{}'", part))
+            })?)
         };
         if let Some(val) = num
             && val == 0
         {
-            return Err(anyhow!("Cannot explode on 0"));
+            return Err(DiceError::ValidationError("Cannot explode on 0".into()));
         }
         return Ok(Modifier::Explode(num));
     }
 
+    // Compounding and penetrating explosions (BEFORE keep, which also starts with 'k')
+    for (prefix, penetrating) in [("ke", false), ("kp", true)] {
+        if let Some(stripped) = part.strip_prefix(prefix) {
+            let num = if stripped.is_empty() {
+                None
+            } else {
+                Some(stripped.parse().map_err(|_| {
+                    DiceError::ParseError(format!("Invalid explode value in '{}'", part))
+                })?)
+            };
+            if num == Some(0) {
+                return Err(DiceError::ValidationError("Cannot explode on 0".into()));
+            }
+            return Ok(if penetrating {
+                Modifier::Penetrate(num)
+            } else {
+                Modifier::Compound(num)
+            });
+        }
+    }
+
     // Handle D6 System BEFORE drop modifier to avoid conflicts
     // D6 System handling (Handle BEFORE drop modifier since both start with 'd')
     if let Some(stripped) = part.strip_prefix("d6s") {
@@ -1665,7 +3064,7 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
 
         let count = count_str
             .parse()
-            .map_err(|_| anyhow!("Invalid D6 System count in '{}'", part))?;
+            .map_err(|_| DiceError::ParseError(format!("Invalid D6 System count in '{}'", part)))?;
 
         // Extract pips part (everything after count)
         let pips = if let Some(pos) = count_and_pips.find(['+', '-']) {
@@ -1681,49 +3080,74 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
     if let Some(stripped) = part.strip_prefix('d') {
         // Make sure this isn't a D6 System expression that somehow got through
         if stripped.starts_with("6s") {
-            return Err(anyhow!(
+            return Err(DiceError::ValidationError(format!(
                 "D6 System expressions should be handled earlier: {}",
                 part
-            ));
+            )));
         }
 
         let num = stripped
             .parse()
-            .map_err(|_| anyhow!("Invalid drop value in '{}'", part))?;
+            .map_err(|_| DiceError::ParseError(format!("Invalid drop value in '{}'", part)))?;
         if num == 0 {
-            return Err(anyhow!("Cannot drop 0 dice"));
+            return Err(DiceError::ValidationError("Cannot drop 0 dice".into()));
         }
         return Ok(Modifier::Drop(num));
     }
 
     // Check indefinite reroll greater (irg) BEFORE regular reroll greater (rg)
     if let Some(stripped) = part.strip_prefix("irg") {
-        let num = stripped
-            .parse()
-            .map_err(|_| anyhow!("Invalid indefinite reroll greater value in '{}'", part))?;
+        let num = stripped.parse().map_err(|_| {
+            DiceError::ParseError(format!(
+                "Invalid indefinite reroll greater value in '{}'",
+                part
+            ))
+        })?;
         if num == 0 {
-            return Err(anyhow!("Cannot reroll on 0 - invalid threshold"));
+            return Err(DiceError::ValidationError(
+                "Cannot reroll on 0 - invalid threshold".into(),
+            ));
         }
         return Ok(Modifier::RerollGreaterIndefinite(num));
     }
 
     if let Some(stripped) = part.strip_prefix("rg") {
+        let num = stripped.parse().map_err(|_| {
+            DiceError::ParseError(format!("Invalid reroll greater value in '{}'", part))
+        })?;
+        if num == 0 {
+            return Err(DiceError::ValidationError(
+                "Cannot reroll on 0 - invalid threshold".into(),
+            ));
+        }
+        return Ok(Modifier::RerollGreater(num));
+    }
+
+    if part == "rfail" {
+        return Ok(Modifier::RerollFailed);
+    }
+
+    if let Some(stripped) = part.strip_prefix("maxs") {
         let num = stripped
             .parse()
-            .map_err(|_| anyhow!("Invalid reroll greater value in '{}'", part))?;
+            .map_err(|_| DiceError::ParseError(format!("Invalid success cap in '{}'", part)))?;
         if num == 0 {
-            return Err(anyhow!("Cannot reroll on 0 - invalid threshold"));
+            return Err(DiceError::ValidationError(
+                "Success cap must be at least 1".into(),
+            ));
         }
-        return Ok(Modifier::RerollGreater(num));
+        return Ok(Modifier::MaxSuccesses(num));
     }
 
     // Continue with other modifiers...
     if let Some(stripped) = part.strip_prefix("ir") {
         let num = stripped
             .parse()
-            .map_err(|_| anyhow!("Invalid reroll value in '{}'", part))?;
+            .map_err(|_| DiceError::ParseError(format!("Invalid reroll value in '{}'", part)))?;
         if num == 0 {
-            return Err(anyhow!("Cannot reroll on 0 - invalid threshold"));
+            return Err(DiceError::ValidationError(
+                "Cannot reroll on 0 - invalid threshold".into(),
+            ));
         }
         return Ok(Modifier::RerollIndefinite(num));
     }
@@ -1731,19 +3155,21 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
     if let Some(stripped) = part.strip_prefix('r') {
         let num = stripped
             .parse()
-            .map_err(|_| anyhow!("Invalid reroll value in '{}'", part))?;
+            .map_err(|_| DiceError::ParseError(format!("Invalid reroll value in '{}'", part)))?;
         if num == 0 {
-            return Err(anyhow!("Cannot reroll on 0 - invalid threshold"));
+            return Err(DiceError::ValidationError(
+                "Cannot reroll on 0 - invalid threshold".into(),
+            ));
         }
         return Ok(Modifier::Reroll(num));
     }
 
     if let Some(stripped) = part.strip_prefix("km") {
-        let num = stripped
-            .parse()
-            .map_err(|_| anyhow!("Invalid keep middle value in '{}'", part))?;
+        let num = stripped.parse().map_err(|_| {
+            DiceError::ParseError(format!("Invalid keep middle value in '{}'", part))
+        })?;
         if num == 0 {
-            return Err(anyhow!("Cannot keep 0 dice"));
+            return Err(DiceError::ValidationError("Cannot keep 0 dice".into()));
         }
         return Ok(Modifier::KeepMiddle(num));
     }
@@ -1751,9 +3177,9 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
     if let Some(stripped) = part.strip_prefix("kl") {
         let num = stripped
             .parse()
-            .map_err(|_| anyhow!("Invalid keep low value in '{}'", part))?;
+            .map_err(|_| DiceError::ParseError(format!("Invalid keep low value in '{}'", part)))?;
         if num == 0 {
-            return Err(anyhow!("Cannot keep 0 dice"));
+            return Err(DiceError::ValidationError("Cannot keep 0 dice".into()));
         }
         return Ok(Modifier::KeepLow(num));
     }
@@ -1761,49 +3187,63 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
     if let Some(stripped) = part.strip_prefix('k') {
         let num = stripped
             .parse()
-            .map_err(|_| anyhow!("Invalid keep value in '{}'", part))?;
+            .map_err(|_| DiceError::ParseError(format!("Invalid keep value in '{}'", part)))?;
         if num == 0 {
-            return Err(anyhow!("Cannot keep 0 dice"));
+            return Err(DiceError::ValidationError("Cannot keep 0 dice".into()));
         }
         return Ok(Modifier::KeepHigh(num));
     }
 
     // Target with doubles t{num}ds{num} must be checked BEFORE other targets
-    if let Some(captures) = Regex::new(r"^t(\d+)ds(\d+)$").unwrap().captures(part) {
-        let target = captures[1]
-            .parse()
-            .map_err(|_| anyhow!("Invalid target value in TargetWithDoubleSuccess '{}'", part))?;
-        let double_value = captures[2]
-            .parse()
-            .map_err(|_| anyhow!("Invalid double value in TargetWithDoubleSuccess '{}'", part))?;
+    if let Some(captures) = TARGET_DOUBLE_SUCCESS_REGEX.captures(part) {
+        let target = captures[1].parse().map_err(|_| {
+            DiceError::ParseError(format!(
+                "Invalid target value in TargetWithDoubleSuccess '{}'",
+                part
+            ))
+        })?;
+        let double_value = captures[2].parse().map_err(|_| {
+            DiceError::ParseError(format!(
+                "Invalid double value in TargetWithDoubleSuccess '{}'",
+                part
+            ))
+        })?;
 
         // Validation for new syntax only
         if target == 0 {
-            return Err(anyhow!("Target value must be greater than 0"));
+            return Err(DiceError::ValidationError(
+                "Target value must be greater than 0".into(),
+            ));
         }
         if double_value == 0 {
-            return Err(anyhow!("Double success must be greater than 0"));
+            return Err(DiceError::ValidationError(
+                "Double success must be greater than 0".into(),
+            ));
         }
         if double_value < target {
-            return Err(anyhow!(
+            return Err(DiceError::ValidationError(format!(
                 "Double success ({}) must be >= target value ({})",
-                double_value,
-                target
-            ));
+                double_value, target
+            )));
         }
 
         return Ok(Modifier::TargetWithDoubleSuccess(target, double_value));
     }
 
     // Check for t{target}ds pattern (default double success = target)
-    if let Some(captures) = Regex::new(r"^t(\d+)ds$").unwrap().captures(part) {
-        let target = captures[1]
-            .parse()
-            .map_err(|_| anyhow!("Invalid target value in TargetWithDoubleSuccess '{}'", part))?;
+    if let Some(captures) = TARGET_DOUBLE_DEFAULT_REGEX.captures(part) {
+        let target = captures[1].parse().map_err(|_| {
+            DiceError::ParseError(format!(
+                "Invalid target value in TargetWithDoubleSuccess '{}'",
+                part
+            ))
+        })?;
 
         // Validation
         if target == 0 {
-            return Err(anyhow!("Target value must be greater than 0"));
+            return Err(DiceError::ValidationError(
+                "Target value must be greater than 0".into(),
+            ));
         }
 
         // Default: double success value = target value
@@ -1811,50 +3251,55 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
     }
 
     // Parse target lower with double success (explicit)
-    if let Some(captures) = Regex::new(r"^tl(\d+)ds(\d+)$").unwrap().captures(part) {
+    if let Some(captures) = TARGET_LOWER_DOUBLE_SUCCESS_REGEX.captures(part) {
         let target = captures[1].parse().map_err(|_| {
-            anyhow!(
+            DiceError::ParseError(format!(
                 "Invalid target value in TargetLowerWithDoubleSuccess '{}'",
                 part
-            )
+            ))
         })?;
         let double_value = captures[2].parse().map_err(|_| {
-            anyhow!(
+            DiceError::ParseError(format!(
                 "Invalid double success value in TargetLowerWithDoubleSuccess '{}'",
                 part
-            )
+            ))
         })?;
 
         // Validation
         if target == 0 {
-            return Err(anyhow!("Target lower value must be greater than 0"));
+            return Err(DiceError::ValidationError(
+                "Target lower value must be greater than 0".into(),
+            ));
         }
         if double_value == 0 {
-            return Err(anyhow!("Double success value must be greater than 0"));
+            return Err(DiceError::ValidationError(
+                "Double success value must be greater than 0".into(),
+            ));
         }
         if double_value > target {
-            return Err(anyhow!(
+            return Err(DiceError::ValidationError(format!(
                 "Double success value ({}) must be <= target lower value ({})",
-                double_value,
-                target
-            ));
+                double_value, target
+            )));
         }
 
         return Ok(Modifier::TargetLowerWithDoubleSuccess(target, double_value));
     }
 
     // Parse target lower with double success (default)
-    if let Some(captures) = Regex::new(r"^tl(\d+)ds$").unwrap().captures(part) {
+    if let Some(captures) = TARGET_LOWER_DOUBLE_DEFAULT_REGEX.captures(part) {
         let target = captures[1].parse().map_err(|_| {
-            anyhow!(
+            DiceError::ParseError(format!(
                 "Invalid target value in TargetLowerWithDoubleSuccess '{}'",
                 part
-            )
+            ))
         })?;
 
         // Validation
         if target == 0 {
-            return Err(anyhow!("Target lower value must be greater than 0"));
+            return Err(DiceError::ValidationError(
+                "Target lower value must be greater than 0".into(),
+            ));
         }
 
         // Default: double success value = target value
@@ -1863,21 +3308,37 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
 
     // Target Lower (tl) must be checked BEFORE Target (t) to avoid conflicts
     if let Some(stripped) = part.strip_prefix("tl") {
+        let num = stripped.parse().map_err(|_| {
+            DiceError::ParseError(format!("Invalid target lower value in '{}'", part))
+        })?;
+        if num == 0 {
+            return Err(DiceError::ValidationError(
+                "Target lower value must be greater than 0".into(),
+            ));
+        }
+        return Ok(Modifier::TargetLower(num));
+    }
+
+    if let Some(stripped) = part.strip_prefix("te") {
         let num = stripped
             .parse()
-            .map_err(|_| anyhow!("Invalid target lower value in '{}'", part))?;
+            .map_err(|_| DiceError::ParseError(format!("Invalid exploding total in '{}'", part)))?;
         if num == 0 {
-            return Err(anyhow!("Target lower value must be greater than 0"));
+            return Err(DiceError::ValidationError(
+                "Cannot explode a total of 0".into(),
+            ));
         }
-        return Ok(Modifier::TargetLower(num));
+        return Ok(Modifier::ExplodeTotal(num));
     }
 
     if let Some(stripped) = part.strip_prefix('t') {
         let num = stripped
             .parse()
-            .map_err(|_| anyhow!("Invalid target value in '{}'", part))?;
+            .map_err(|_| DiceError::ParseError(format!("Invalid target value in '{}'", part)))?;
         if num == 0 {
-            return Err(anyhow!("Target value must be greater than 0"));
+            return Err(DiceError::ValidationError(
+                "Target value must be greater than 0".into(),
+            ));
         }
         return Ok(Modifier::Target(num));
     }
@@ -1885,20 +3346,23 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
     if let Some(stripped) = part.strip_prefix('f') {
         let num = stripped
             .parse()
-            .map_err(|_| anyhow!("Invalid failure value in '{}'", part))?;
+            .map_err(|_| DiceError::ParseError(format!("Invalid failure value in '{}'", part)))?;
         return Ok(Modifier::Failure(num));
     }
 
+    if part.starts_with("bands") {
+        return parse_bands(part);
+    }
+
     if let Some(stripped) = part.strip_prefix('b') {
-        let num = if stripped.is_empty() {
-            None
-        } else {
-            Some(
-                stripped
-                    .parse()
-                    .map_err(|_| anyhow!("Invalid botch value in '{}'", part))?,
-            )
-        };
+        let num =
+            if stripped.is_empty() {
+                None
+            } else {
+                Some(stripped.parse().map_err(|_| {
+                    DiceError::ParseError(format!("Invalid botch value in '{}'", part))
+                })?)
+            };
         return Ok(Modifier::Botch(num));
     }
 
@@ -1931,13 +3395,16 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
             }
 
             if !count_str.is_empty() {
-                wrath_count = count_str
-                    .parse()
-                    .map_err(|_| anyhow!("Invalid wrath dice count in '{}'", part))?;
+                wrath_count = count_str.parse().map_err(|_| {
+                    DiceError::ParseError(format!("Invalid wrath dice count in '{}'", part))
+                })?;
 
                 // Validate reasonable range (1-5 wrath dice max)
                 if !(1..=5).contains(&wrath_count) {
-                    return Err(anyhow!("Wrath dice count must be 1-5, got {}", wrath_count));
+                    return Err(DiceError::ValidationError(format!(
+                        "Wrath dice count must be 1-5, got {}",
+                        wrath_count
+                    )));
                 }
             }
         }
@@ -1947,14 +3414,14 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
             return Ok(Modifier::WrathGlory(None, true, wrath_count));
         } else if let Some(dn_part) = remaining.strip_prefix("dn") {
             if let Some(dn_str) = dn_part.strip_suffix('t') {
-                let dn = dn_str
-                    .parse()
-                    .map_err(|_| anyhow!("Invalid difficulty value in '{}'", part))?;
+                let dn = dn_str.parse().map_err(|_| {
+                    DiceError::ParseError(format!("Invalid difficulty value in '{}'", part))
+                })?;
                 return Ok(Modifier::WrathGlory(Some(dn), true, wrath_count));
             } else {
-                let dn = dn_part
-                    .parse()
-                    .map_err(|_| anyhow!("Invalid difficulty value in '{}'", part))?;
+                let dn = dn_part.parse().map_err(|_| {
+                    DiceError::ParseError(format!("Invalid difficulty value in '{}'", part))
+                })?;
                 return Ok(Modifier::WrathGlory(Some(dn), false, wrath_count));
             }
         } else if let Some(dn_str) = remaining.strip_suffix('t') {
@@ -1967,17 +3434,20 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
             return Ok(Modifier::WrathGlory(None, false, wrath_count));
         }
 
-        return Err(anyhow!("Invalid Wrath & Glory modifier: {}", part));
+        return Err(DiceError::ParseError(format!(
+            "Invalid Wrath & Glory modifier: {}",
+            part
+        )));
     }
 
     // Savage Worlds handling (AFTER D6 System but before other modifiers)
     if let Some(stripped) = part.strip_prefix("sw") {
-        let sides = stripped
-            .parse()
-            .map_err(|_| anyhow!("Invalid Savage Worlds trait die in '{}'", part))?;
+        let sides = stripped.parse().map_err(|_| {
+            DiceError::ParseError(format!("Invalid Savage Worlds trait die in '{}'", part))
+        })?;
         if !(4..=12).contains(&sides) || sides % 2 != 0 {
-            return Err(anyhow!(
-                "Savage Worlds trait die must be d4, d6, d8, d10, or d12"
+            return Err(DiceError::ValidationError(
+                "Savage Worlds trait die must be d4, d6, d8, d10, or d12".into(),
             ));
         }
         return Ok(Modifier::SavageWorlds(sides));
@@ -1985,23 +3455,30 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
 
     // Shadowrun handling
     if let Some(stripped) = part.strip_prefix("shadowrun") {
-        let dice_count = stripped
-            .parse()
-            .map_err(|_| anyhow!("Invalid Shadowrun dice count in '{}'", part))?;
+        let dice_count = stripped.parse().map_err(|_| {
+            DiceError::ParseError(format!("Invalid Shadowrun dice count in '{}'", part))
+        })?;
         if dice_count == 0 {
-            return Err(anyhow!("Shadowrun dice count must be greater than 0"));
+            return Err(DiceError::ValidationError(
+                "Shadowrun dice count must be greater than 0".into(),
+            ));
         }
         return Ok(Modifier::Shadowrun(dice_count));
     }
 
     // Wild Worlds RPG cutting mechanics
     if let Some(stripped) = part.strip_prefix("wwc") {
-        let cut_count: u32 = stripped
-            .parse()
-            .map_err(|_| anyhow!("Invalid cut count in Wild Worlds modifier '{}'", part))?;
+        let cut_count: u32 = stripped.parse().map_err(|_| {
+            DiceError::ParseError(format!(
+                "Invalid cut count in Wild Worlds modifier '{}'",
+                part
+            ))
+        })?;
 
         if cut_count == 0 {
-            return Err(anyhow!("Cannot cut 0 dice in Wild Worlds"));
+            return Err(DiceError::ValidationError(
+                "Cannot cut 0 dice in Wild Worlds".into(),
+            ));
         }
 
         return Ok(Modifier::WildWorlds(Some(cut_count)));
@@ -2009,8 +3486,13 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
 
     // Additional dice modifiers
     if let Some(captures) = DICE_MOD_REGEX.captures(part) {
-        let count: u32 = captures[2].parse()?;
-        let sides: u32 = captures[3].parse()?;
+        let count: u32 = captures[2].parse().map_err(|_| {
+            DiceError::RollLimitExceeded(format!("Number too large: {}", &captures[2]))
+        })?;
+        let sides: u32 = captures[3].parse().map_err(|_| {
+            DiceError::RollLimitExceeded(format!("Maximum {MAX_DICE_SIDES} sides allowed"))
+        })?;
+        validate_sides(sides)?;
         let dice_roll = DiceRoll {
             count,
             sides,
@@ -2018,6 +3500,14 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
             comment: None,
             label: None,
             private: false,
+            drama: false,
+            pemdas: false,
+            embed: false,
+            full: false,
+            gm: false,
+            language: Language::English,
+            faces: None,
+            pool: None,
             simple: false,
             no_results: false,
             unsorted: false,
@@ -2026,7 +3516,7 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
         return match &captures[1] {
             "+" => Ok(Modifier::AddDice(dice_roll)),
             "-" => Ok(Modifier::SubtractDice(dice_roll)),
-            _ => Err(anyhow!("Invalid dice modifier")),
+            _ => Err(DiceError::ParseError("Invalid dice modifier".into())),
         };
     }
 
@@ -2053,19 +3543,33 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
         if stripped.is_empty() {
             return Ok(Modifier::Silhouette(1));
         } else {
-            let dice_count = stripped
-                .parse()
-                .map_err(|_| anyhow!("Invalid Silhouette dice count in '{}'", part))?;
+            let dice_count = stripped.parse().map_err(|_| {
+                DiceError::ParseError(format!("Invalid Silhouette dice count in '{}'", part))
+            })?;
             if dice_count == 0 || dice_count > 10 {
-                return Err(anyhow!(
+                return Err(DiceError::ValidationError(format!(
                     "Silhouette dice count must be 1-10, got {}",
                     dice_count
-                ));
+                )));
             }
             return Ok(Modifier::Silhouette(dice_count));
         }
     }
 
+    // Mothership panic check (mspanic5)
+    if let Some(stripped) = part.strip_prefix("mspanic") {
+        let stress = stripped.parse().map_err(|_| {
+            DiceError::ParseError(format!("Invalid Mothership stress in '{}'", part))
+        })?;
+        if stress > MAX_MOTHERSHIP_STRESS {
+            return Err(DiceError::ValidationError(format!(
+                "Mothership stress must be 0-{}, got {}",
+                MAX_MOTHERSHIP_STRESS, stress
+            )));
+        }
+        return Ok(Modifier::MothershipPanic(stress));
+    }
+
     // Mothership RPG handling
     if part == "ms" {
         return Ok(Modifier::Mothership(None, false));
@@ -2083,29 +3587,38 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
     if let Some(stripped) = part.strip_prefix("ms") {
         if let Some(stat_str) = stripped.strip_suffix('a') {
             // Changed from '+'
-            let stat: u32 = stat_str
-                .parse()
-                .map_err(|_| anyhow!("Invalid Mothership stat value in '{}'", part))?;
+            let stat: u32 = stat_str.parse().map_err(|_| {
+                DiceError::ParseError(format!("Invalid Mothership stat value in '{}'", part))
+            })?;
             if !(1..=99).contains(&stat) {
-                return Err(anyhow!("Mothership stat must be 1-99, got {}", stat));
+                return Err(DiceError::ValidationError(format!(
+                    "Mothership stat must be 1-99, got {}",
+                    stat
+                )));
             }
             return Ok(Modifier::Mothership(Some(stat), true));
         } else if let Some(stat_str) = stripped.strip_suffix('d') {
             // Changed from '-'
-            let stat: u32 = stat_str
-                .parse()
-                .map_err(|_| anyhow!("Invalid Mothership stat value in '{}'", part))?;
+            let stat: u32 = stat_str.parse().map_err(|_| {
+                DiceError::ParseError(format!("Invalid Mothership stat value in '{}'", part))
+            })?;
             if !(1..=99).contains(&stat) {
-                return Err(anyhow!("Mothership stat must be 1-99, got {}", stat));
+                return Err(DiceError::ValidationError(format!(
+                    "Mothership stat must be 1-99, got {}",
+                    stat
+                )));
             }
             return Ok(Modifier::Mothership(Some(stat), false));
         } else if !stripped.is_empty() {
             // Just a stat value
-            let stat: u32 = stripped
-                .parse()
-                .map_err(|_| anyhow!("Invalid Mothership stat value in '{}'", part))?;
+            let stat: u32 = stripped.parse().map_err(|_| {
+                DiceError::ParseError(format!("Invalid Mothership stat value in '{}'", part))
+            })?;
             if !(1..=99).contains(&stat) {
-                return Err(anyhow!("Mothership stat must be 1-99, got {}", stat));
+                return Err(DiceError::ValidationError(format!(
+                    "Mothership stat must be 1-99, got {}",
+                    stat
+                )));
             }
             return Ok(Modifier::Mothership(Some(stat), false));
         }
@@ -2118,29 +3631,31 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
             let pool_str = &stripped[..h_pos];
             let hunger_str = &stripped[h_pos + 1..];
 
-            let pool_size = pool_str
-                .parse::<u32>()
-                .map_err(|_| anyhow!("Invalid VTM5 pool size in '{}'", part))?;
-            let hunger_dice = hunger_str
-                .parse::<u32>()
-                .map_err(|_| anyhow!("Invalid VTM5 hunger dice count in '{}'", part))?;
+            let pool_size = pool_str.parse::<u32>().map_err(|_| {
+                DiceError::ParseError(format!("Invalid VTM5 pool size in '{}'", part))
+            })?;
+            let hunger_dice = hunger_str.parse::<u32>().map_err(|_| {
+                DiceError::ParseError(format!("Invalid VTM5 hunger dice count in '{}'", part))
+            })?;
 
             // Validate ranges
             if pool_size == 0 || pool_size > 30 {
-                return Err(anyhow!("VTM5 pool size must be 1-30, got {}", pool_size));
+                return Err(DiceError::ValidationError(format!(
+                    "VTM5 pool size must be 1-30, got {}",
+                    pool_size
+                )));
             }
             if hunger_dice > pool_size {
-                return Err(anyhow!(
+                return Err(DiceError::ValidationError(format!(
                     "VTM5 hunger dice ({}) cannot exceed pool size ({})",
-                    hunger_dice,
-                    pool_size
-                ));
+                    hunger_dice, pool_size
+                )));
             }
             if hunger_dice > 5 {
-                return Err(anyhow!(
+                return Err(DiceError::ValidationError(format!(
                     "VTM5 hunger dice cannot exceed 5, got {}",
                     hunger_dice
-                ));
+                )));
             }
 
             return Ok(Modifier::VampireMasquerade5(pool_size, hunger_dice));
@@ -2153,7 +3668,10 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
         let chars: Vec<char> = stripped.chars().collect();
 
         if chars.is_empty() {
-            return Err(anyhow!("Invalid Lasers & Feelings format: '{}'", part));
+            return Err(DiceError::ParseError(format!(
+                "Invalid Lasers & Feelings format: '{}'",
+                part
+            )));
         }
 
         // Find where the number ends and type begins
@@ -2167,23 +3685,25 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
         }
 
         if number_end == 0 {
-            return Err(anyhow!("Invalid Lasers & Feelings target in '{}'", part));
+            return Err(DiceError::ParseError(format!(
+                "Invalid Lasers & Feelings target in '{}'",
+                part
+            )));
         }
 
         let target_str: String = chars[..number_end].iter().collect();
         let type_str: String = chars[number_end..].iter().collect();
 
-        let target = target_str
-            .parse()
-            .map_err(|_| anyhow!("Invalid Lasers & Feelings target in '{}'", part))?;
+        let target = target_str.parse().map_err(|_| {
+            DiceError::ParseError(format!("Invalid Lasers & Feelings target in '{}'", part))
+        })?;
 
         // Validate target number (2-5 as per L&F rules)
         if !(2..=5).contains(&target) {
-            return Err(anyhow!(
+            return Err(DiceError::ValidationError(format!(
                 "Lasers & Feelings target must be between 2 and 5, got {} in '{}'",
-                target,
-                part
-            ));
+                target, part
+            )));
         }
 
         // Determine roll type
@@ -2196,11 +3716,10 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
                 LaserFeelingsType::Lasers
             }
             _ => {
-                return Err(anyhow!(
+                return Err(DiceError::ParseError(format!(
                     "Invalid Lasers & Feelings type '{}' in '{}'",
-                    type_str,
-                    part
-                ));
+                    type_str, part
+                )));
             }
         };
 
@@ -2214,19 +3733,21 @@ fn parse_single_modifier(part: &str) -> Result<Modifier> {
         return Ok(Modifier::WildWorlds(None));
     }
 
-    if let Some(cut_match) = Regex::new(r"^wwc(\d+)$").unwrap().captures(part) {
-        let cut_count: u32 = cut_match[1]
-            .parse()
-            .map_err(|_| anyhow!("Invalid cut count in Wild Worlds modifier"))?;
+    if let Some(cut_match) = WILD_WORLDS_CUT_REGEX.captures(part) {
+        let cut_count: u32 = cut_match[1].parse().map_err(|_| {
+            DiceError::ParseError("Invalid cut count in Wild Worlds modifier".into())
+        })?;
 
         if cut_count == 0 {
-            return Err(anyhow!("Cannot cut 0 dice in Wild Worlds"));
+            return Err(DiceError::ValidationError(
+                "Cannot cut 0 dice in Wild Worlds".into(),
+            ));
         }
 
         return Ok(Modifier::WildWorlds(Some(cut_count)));
     }
 
-    Err(anyhow!("Unknown modifier: {}", part))
+    Err(DiceError::ParseError(format!("Unknown modifier: {}", part)))
 }
 
 fn is_dice_expression(input: &str) -> bool {
@@ -2252,6 +3773,22 @@ fn is_dice_expression(input: &str) -> bool {
     false
 }
 
+// Dice sides must fit the roller's i32 rolls, for the main dice and any
+// added with `+`, `-`, `*` or `/`
+fn validate_sides(sides: u32) -> Result<()> {
+    if sides < 1 {
+        return Err(DiceError::ValidationError(
+            "Dice must have at least 1 side".into(),
+        ));
+    }
+    if sides > MAX_DICE_SIDES {
+        return Err(DiceError::RollLimitExceeded(format!(
+            "Maximum {MAX_DICE_SIDES} sides allowed (got d{sides})"
+        )));
+    }
+    Ok(())
+}
+
 fn parse_dice_expression_only(input: &str) -> Result<DiceRoll> {
     // Handle cases like "d10", "d20", "d%"
     if input.starts_with('d') && !input.contains(char::is_alphabetic) {
@@ -2267,8 +3804,11 @@ fn parse_dice_expression_only(input: &str) -> Result<DiceRoll> {
         let sides = if &captures[2] == "%" {
             100
         } else {
-            captures[2].parse()?
+            captures[2].parse().map_err(|_| {
+                DiceError::RollLimitExceeded(format!("Maximum {MAX_DICE_SIDES} sides allowed"))
+            })?
         };
+        validate_sides(sides)?;
 
         Ok(DiceRoll {
             count,
@@ -2277,13 +3817,24 @@ fn parse_dice_expression_only(input: &str) -> Result<DiceRoll> {
             comment: None,
             label: None,
             private: false,
+            drama: false,
+            pemdas: false,
+            embed: false,
+            full: false,
+            gm: false,
+            language: Language::English,
+            faces: None,
+            pool: None,
             simple: false,
             no_results: false,
             unsorted: false,
             original_expression: None,
         })
     } else {
-        Err(anyhow!("Invalid dice expression: {}", input))
+        Err(DiceError::ParseError(format!(
+            "Invalid dice expression: {}",
+            input
+        )))
     }
 }
 
@@ -2292,20 +3843,18 @@ fn is_multi_sided_dice_expression(part: &str) -> bool {
 
     // Pattern 1: Multi-digit count + d + sides (like "3d6", "2d10", "10d6")
     // 2+ digit count is clearly dice, not a drop modifier
-    if Regex::new(r"^(\d{2,})d(\d+)$").unwrap().is_match(part) {
+    if MULTI_DIGIT_COUNT_DICE_REGEX.is_match(part) {
         return true;
     }
 
     // Pattern 2: Single digit count + d + multi-digit sides (like "1d20", "3d100")
     // Multi-digit sides is clearly dice, not a drop modifier
-    if Regex::new(r"^(\d)d(\d{2,})$").unwrap().is_match(part) {
+    if MULTI_DIGIT_SIDES_DICE_REGEX.is_match(part) {
         return true;
     }
 
     // Pattern 3: Common multi-die patterns that aren't drop modifiers
-    if let Some(caps) = Regex::new(r"^(\d+)d([46]|8|10|12|20|100)$")
-        .unwrap()
-        .captures(part)
+    if let Some(caps) = COMMON_DICE_REGEX.captures(part)
         && let (Ok(count), Ok(sides)) = (caps[1].parse::<u32>(), caps[2].parse::<u32>())
     {
         // If count > 1, it's clearly additional dice, not a drop modifier
@@ -2330,19 +3879,7 @@ fn is_multi_sided_dice_expression(part: &str) -> bool {
 fn is_standalone_dice_expression(input: &str) -> bool {
     // Check for patterns that represent standalone dice expressions
     // These should NOT be treated as combined modifiers
-    let standalone_patterns = [
-        r"^\d*d\d+$", // Basic dice like "3d6", "d20", "1d4"
-        r"^\d*d%$",   // Percentile dice like "d%", "2d%"
-    ];
-
-    for pattern in &standalone_patterns {
-        let regex = Regex::new(pattern).unwrap();
-        if regex.is_match(input) {
-            return true;
-        }
-    }
-
-    false
+    STANDALONE_DICE_SET.is_match(input)
 }
 
 fn create_roll_set_with_metadata(
@@ -2351,11 +3888,13 @@ fn create_roll_set_with_metadata(
 ) -> Result<Vec<DiceRoll>> {
     let count: u32 = captures[1]
         .parse()
-        .map_err(|_| anyhow!("Invalid set count"))?;
+        .map_err(|_| DiceError::ParseError("Invalid set count".into()))?;
 
     // VALIDATION: Centralized in one place
     if !(2..=20).contains(&count) {
-        return Err(anyhow!("Set count must be between 2 and 20"));
+        return Err(DiceError::ValidationError(
+            "Set count must be between 2 and 20".into(),
+        ));
     }
 
     let expression = &captures[2];
@@ -0,0 +1,620 @@
+//! Probability distributions of dice expressions, for `/odds`.
+//!
+//! [`distribution`] parses an expression with `parser::parse_dice_string`
+//! and works from the resulting [`DiceRoll`] instead of rolling it:
+//!
+//! | Roll                                          | Method                         |
+//! |-----------------------------------------------|--------------------------------|
+//! | `NdS` with `+ - * /` numbers and plain dice   | Exact, by convolution          |
+//! | … with one `k#`, `kl#` or `d#`                | Exact, over every sorted pool  |
+//! | `NdS t#` or `NdS tl#` alone                   | Exact, successes per die       |
+//! | Anything else (explosions, rerolls, systems)  | Monte Carlo, [`SAMPLES`] rolls |
+//!
+//! Exact methods give up in favour of Monte Carlo when a pool is too big to
+//! work through ([`MAX_EXACT_WORK`], [`MAX_KEEP_POOLS`]).  Simulated rolls go
+//! through `roller::roll_dice_with_rng` with the parsed roll, so every
+//! modifier and system behaves exactly as in `/roll`; the value of a roll is
+//! [`calculate_result_value`], its successes when it counts them.
+//! Simulation stops early after [`MAX_SIMULATION_TIME`], keeping the rolls
+//! made so far.
+
+use super::error::{DiceError, Result};
+use super::{DiceRoll, Modifier, calculate_result_value, parser, roller};
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Rolls simulated when a distribution can't be worked out exactly
+pub const SAMPLES: usize = 20_000;
+
+/// Longest a simulation runs before using the rolls made so far
+pub const MAX_SIMULATION_TIME: Duration = Duration::from_secs(2);
+
+/// Most steps (outcome pairs combined) spent on an exact distribution
+pub const MAX_EXACT_WORK: u64 = 5_000_000;
+
+/// Most sorted pools enumerated for an exact keep or drop
+pub const MAX_KEEP_POOLS: u64 = 200_000;
+
+/// The values a roll can take and how likely each is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    /// Probability of each value, summing to 1
+    pub outcomes: BTreeMap<i64, f64>,
+    /// Rolls simulated, or `None` when worked out exactly
+    pub samples: Option<usize>,
+    /// The value counts successes rather than adding up the dice
+    pub successes: bool,
+}
+
+impl Distribution {
+    fn exact(outcomes: BTreeMap<i64, f64>, successes: bool) -> Self {
+        Self {
+            outcomes,
+            samples: None,
+            successes,
+        }
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.samples.is_none()
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.outcomes
+            .iter()
+            .map(|(&value, &p)| value as f64 * p)
+            .sum()
+    }
+
+    pub fn min(&self) -> i64 {
+        self.outcomes.keys().next().copied().unwrap_or_default()
+    }
+
+    pub fn max(&self) -> i64 {
+        self.outcomes
+            .keys()
+            .next_back()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Chance of rolling `target` or more
+    pub fn chance_at_least(&self, target: i64) -> f64 {
+        self.outcomes.range(target..).map(|(_, p)| p).sum()
+    }
+
+    /// Chance of rolling `target` or less
+    pub fn chance_at_most(&self, target: i64) -> f64 {
+        self.outcomes.range(..=target).map(|(_, p)| p).sum()
+    }
+
+    /// The lowest value rolled at or under `fraction` of the time, e.g. the
+    /// median for 0.5
+    pub fn percentile(&self, fraction: f64) -> i64 {
+        let mut cumulative = 0.0;
+        for (&value, &p) in &self.outcomes {
+            cumulative += p;
+            // Allow for rounding in probabilities that should add up exactly
+            if cumulative >= fraction - 1e-9 {
+                return value;
+            }
+        }
+        self.max()
+    }
+}
+
+/// The distribution of a single roll expression, exact where possible
+pub fn distribution(expression: &str) -> Result<Distribution> {
+    distribution_with_rng(expression, &mut super::create_fast_rng())
+}
+
+/// [`distribution`], simulating with `rng` when it can't be exact
+pub fn distribution_with_rng(expression: &str, rng: &mut impl Rng) -> Result<Distribution> {
+    roll_distribution(&parse_single_roll(expression)?, rng)
+}
+
+/// The distribution of a parsed roll, simulating with `rng` when it can't be
+/// exact
+pub fn roll_distribution(dice: &DiceRoll, rng: &mut impl Rng) -> Result<Distribution> {
+    match exact_distribution(dice) {
+        Some(distribution) => Ok(distribution),
+        None => simulate(dice, SAMPLES, rng),
+    }
+}
+
+/// The one roll in an expression; roll sets and `;`-separated rolls are refused
+pub fn parse_single_roll(expression: &str) -> Result<DiceRoll> {
+    let mut rolls = parser::parse_dice_string(expression)?;
+    if rolls.len() != 1 {
+        return Err(DiceError::ValidationError(
+            "Odds work on one roll at a time, not roll sets or `;`-separated rolls".into(),
+        ));
+    }
+    let dice = rolls.remove(0);
+    if dice.count == 0 || dice.sides == 0 {
+        return Err(DiceError::ValidationError(
+            "There are no dice to work out odds for. Use `/calc` for math".into(),
+        ));
+    }
+    Ok(dice)
+}
+
+/// Roll `dice` `samples` times, stopping early after [`MAX_SIMULATION_TIME`]
+pub fn simulate(dice: &DiceRoll, samples: usize, rng: &mut impl Rng) -> Result<Distribution> {
+    let started = Instant::now();
+    let mut counts: BTreeMap<i64, usize> = BTreeMap::new();
+    let mut rolled = 0;
+    let mut successes = false;
+
+    while rolled < samples {
+        let result = roller::roll_dice_with_rng(dice.clone(), rng)?;
+        successes |= result.successes.is_some();
+        *counts
+            .entry(i64::from(calculate_result_value(&result)))
+            .or_default() += 1;
+        rolled += 1;
+        if started.elapsed() > MAX_SIMULATION_TIME {
+            break;
+        }
+    }
+
+    Ok(Distribution {
+        outcomes: counts
+            .into_iter()
+            .map(|(value, count)| (value, count as f64 / rolled as f64))
+            .collect(),
+        samples: Some(rolled),
+        successes,
+    })
+}
+
+// An operator of the left-to-right math after the dice
+#[derive(Debug, Clone, Copy)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operator {
+    fn apply(self, left: i64, right: i64) -> Option<i64> {
+        match self {
+            Operator::Add => Some(left + right),
+            Operator::Subtract => Some(left - right),
+            Operator::Multiply => Some(left * right),
+            Operator::Divide => left.checked_div(right),
+        }
+    }
+}
+
+// One step of the math: an operator with a number or with plain dice
+enum MathStep<'a> {
+    Number(Operator, i32),
+    Dice(Operator, &'a DiceRoll),
+}
+
+// Dice added with `+ 1d6` and the like that can be worked out exactly
+fn is_plain(dice: &DiceRoll) -> bool {
+    dice.modifiers.is_empty()
+        && dice.count > 0
+        && dice.sides > 0
+        && dice.faces.is_none()
+        && dice.pool.is_none()
+}
+
+/// The exact distribution of `dice`, or `None` when it needs simulating
+pub fn exact_distribution(dice: &DiceRoll) -> Option<Distribution> {
+    // The math below is left to right and for numbered dice of one size;
+    // `pemdas` precedence, mixed pools and custom faces are simulated
+    if dice.pemdas || dice.pool.is_some() || dice.faces.is_some() {
+        return None;
+    }
+
+    // Dice skipped (lowest first) and kept by a `k#`, `kl#` or `d#`
+    let mut keep = None;
+    // Faces of a die that count as a success for a `t#` or `tl#`
+    let mut target = None;
+    let mut math = Vec::new();
+    for modifier in &dice.modifiers {
+        match modifier {
+            Modifier::KeepHigh(n) if keep.is_none() => {
+                keep = Some((dice.count.saturating_sub(*n), *n))
+            }
+            Modifier::KeepLow(n) if keep.is_none() => keep = Some((0, *n)),
+            Modifier::Drop(n) if keep.is_none() => keep = Some((*n, dice.count.saturating_sub(*n))),
+            Modifier::Target(value) if target.is_none() => {
+                target = Some(dice.sides.saturating_sub(value.max(&1) - 1))
+            }
+            Modifier::TargetLower(value) if target.is_none() => {
+                target = Some(*value.min(&dice.sides))
+            }
+            Modifier::Add(value) => math.push(MathStep::Number(Operator::Add, *value)),
+            Modifier::Subtract(value) => math.push(MathStep::Number(Operator::Subtract, *value)),
+            Modifier::Multiply(value) if *value != 0 => {
+                math.push(MathStep::Number(Operator::Multiply, *value))
+            }
+            Modifier::Divide(value) if *value != 0 => {
+                math.push(MathStep::Number(Operator::Divide, *value))
+            }
+            Modifier::AddDice(inner) if is_plain(inner) => {
+                math.push(MathStep::Dice(Operator::Add, inner))
+            }
+            Modifier::SubtractDice(inner) if is_plain(inner) => {
+                math.push(MathStep::Dice(Operator::Subtract, inner))
+            }
+            Modifier::MultiplyDice(inner) if is_plain(inner) => {
+                math.push(MathStep::Dice(Operator::Multiply, inner))
+            }
+            Modifier::DivideDice(inner) if is_plain(inner) => {
+                math.push(MathStep::Dice(Operator::Divide, inner))
+            }
+            // Everything else (and a second keep or target) is simulated
+            Modifier::Explode(_)
+            | Modifier::ExplodeIndefinite(_)
+            | Modifier::ExplodeTotal(_)
+            | Modifier::Compound(_)
+            | Modifier::Penetrate(_)
+            | Modifier::Drop(_)
+            | Modifier::KeepHigh(_)
+            | Modifier::KeepLow(_)
+            | Modifier::KeepMiddle(_)
+            | Modifier::Reroll(_)
+            | Modifier::RerollIndefinite(_)
+            | Modifier::RerollFailed
+            | Modifier::MaxSuccesses(_)
+            | Modifier::Target(_)
+            | Modifier::RerollGreater(_)
+            | Modifier::RerollGreaterIndefinite(_)
+            | Modifier::TargetLower(_)
+            | Modifier::TargetWithDoubleSuccess(_, _)
+            | Modifier::TargetLowerWithDoubleSuccess(_, _)
+            | Modifier::Failure(_)
+            | Modifier::Botch(_)
+            | Modifier::Cancel
+            | Modifier::Multiply(_)
+            | Modifier::Divide(_)
+            | Modifier::AddDice(_)
+            | Modifier::SubtractDice(_)
+            | Modifier::MultiplyDice(_)
+            | Modifier::DivideDice(_)
+            | Modifier::OpenGroup
+            | Modifier::CloseGroup
+            | Modifier::WrathGlory(_, _, _)
+            | Modifier::Godbound(_)
+            | Modifier::HeroSystem(_)
+            | Modifier::Fudge
+            | Modifier::DarkHeresy
+            | Modifier::SavageWorlds(_)
+            | Modifier::D6System(_, _)
+            | Modifier::Shadowrun(_)
+            | Modifier::MarvelMultiverse(_, _)
+            | Modifier::CyberpunkRed
+            | Modifier::Witcher
+            | Modifier::CypherSystem(_)
+            | Modifier::BraveNewWorld(_)
+            | Modifier::ConanSkill(_)
+            | Modifier::ConanCombat(_)
+            | Modifier::TargetNumber(_)
+            | Modifier::Focus(_)
+            | Modifier::Difficulty(_)
+            | Modifier::Complication(_)
+            | Modifier::Silhouette(_)
+            | Modifier::VampireMasquerade5(_, _)
+            | Modifier::LaserFeelings(_, _, _)
+            | Modifier::Alien
+            | Modifier::AlienStress(_)
+            | Modifier::ForgedDark
+            | Modifier::ForgedDarkZero
+            | Modifier::Daggerheart
+            | Modifier::WildWorlds(_)
+            | Modifier::Mothership(_, _)
+            | Modifier::MothershipPanic(_)
+            | Modifier::MutantsMasterminds
+            | Modifier::PlotDie
+            | Modifier::Genesys(_)
+            | Modifier::L5r(_)
+            | Modifier::Ironsworn
+            | Modifier::Oracle(_)
+            | Modifier::CoC(_, _)
+            | Modifier::Wfrp(_)
+            | Modifier::Dragonbane(_, _, _)
+            | Modifier::Gurps(_)
+            | Modifier::YearZero(_)
+            | Modifier::BurningWheel(_)
+            | Modifier::Matches
+            | Modifier::Cortex
+            | Modifier::Bands(_)
+            | Modifier::TargetLadder(_)
+            | Modifier::Dcc => return None,
+        }
+    }
+
+    if let Some(hits) = target {
+        // Success counts with anything else on them are left to simulation
+        if keep.is_some() || !math.is_empty() {
+            return None;
+        }
+        return success_distribution(dice.count, dice.sides, hits);
+    }
+
+    let mut work = 0;
+    let mut total = match keep {
+        Some((skip, take)) => keep_distribution(dice.count, dice.sides, skip, take)?,
+        None => sum_distribution(dice.count, dice.sides, &mut work)?,
+    };
+    for step in math {
+        total = match step {
+            MathStep::Number(operator, value) => {
+                map(&total, |v| operator.apply(v, i64::from(value)))
+            }
+            MathStep::Dice(operator, inner) => {
+                let other = sum_distribution(inner.count, inner.sides, &mut work)?;
+                combine(&total, &other, &mut work, |a, b| operator.apply(a, b))?
+            }
+        };
+    }
+
+    Some(Distribution::exact(total, false))
+}
+
+// The sum of `count` d`sides`, convolving one die at a time
+fn sum_distribution(count: u32, sides: u32, work: &mut u64) -> Option<BTreeMap<i64, f64>> {
+    let die = uniform(sides);
+    let mut total = BTreeMap::from([(0, 1.0)]);
+    for _ in 0..count {
+        total = combine(&total, &die, work, |a, b| Some(a + b))?;
+    }
+    Some(total)
+}
+
+// Successes of `count` dice when `hits` of each die's faces succeed
+fn success_distribution(count: u32, sides: u32, hits: u32) -> Option<Distribution> {
+    let p = f64::from(hits) / f64::from(sides);
+    let die = BTreeMap::from([(0, 1.0 - p), (1, p)]);
+
+    let mut work = 0;
+    let mut successes = BTreeMap::from([(0, 1.0)]);
+    for _ in 0..count {
+        successes = combine(&successes, &die, &mut work, |a, b| Some(a + b))?;
+    }
+    successes.retain(|_, p| *p > 0.0);
+    Some(Distribution::exact(successes, true))
+}
+
+// The kept dice of `count` d`sides` after skipping the lowest `skip` and
+// keeping the next `take` (a `k#`, `kl#` or `d#`), summed over every sorted
+// pool weighted by the orderings that produce it
+fn keep_distribution(count: u32, sides: u32, skip: u32, take: u32) -> Option<BTreeMap<i64, f64>> {
+    let (count, skip, take) = (count as usize, skip as usize, take as usize);
+    if take == 0 || sides == 0 || pool_count(count as u64, u64::from(sides))? > MAX_KEEP_POOLS {
+        return None;
+    }
+
+    let total_orderings = f64::from(sides).powi(count as i32);
+    let mut outcomes = BTreeMap::new();
+    let mut pool = vec![1u32; count];
+    loop {
+        // `pool` is non-decreasing; its orderings are count! / Π(run length!)
+        let mut orderings = factorial(count);
+        let mut run = 1;
+        for i in 1..=count {
+            if i < count && pool[i] == pool[i - 1] {
+                run += 1;
+            } else {
+                orderings /= factorial(run);
+                run = 1;
+            }
+        }
+        let kept: i64 = pool[skip..(skip + take).min(count)]
+            .iter()
+            .map(|&die| i64::from(die))
+            .sum();
+        *outcomes.entry(kept).or_insert(0.0) += orderings / total_orderings;
+
+        // Next non-decreasing pool
+        let Some(i) = pool.iter().rposition(|&die| die < sides) else {
+            break;
+        };
+        let next = pool[i] + 1;
+        pool[i..].fill(next);
+    }
+    Some(outcomes)
+}
+
+// Sorted pools of `count` d`sides`: C(count + sides - 1, count), or `None`
+// once it passes any limit worth computing
+fn pool_count(count: u64, sides: u64) -> Option<u64> {
+    let mut pools: u64 = 1;
+    for i in 1..=count {
+        pools = pools.checked_mul(sides - 1 + i)? / i;
+        if pools > MAX_KEEP_POOLS * 1000 {
+            return None;
+        }
+    }
+    Some(pools)
+}
+
+fn factorial(n: usize) -> f64 {
+    (1..=n).map(|i| i as f64).product()
+}
+
+fn uniform(sides: u32) -> BTreeMap<i64, f64> {
+    let p = 1.0 / f64::from(sides);
+    (1..=i64::from(sides)).map(|face| (face, p)).collect()
+}
+
+fn map(outcomes: &BTreeMap<i64, f64>, f: impl Fn(i64) -> Option<i64>) -> BTreeMap<i64, f64> {
+    let mut mapped = BTreeMap::new();
+    for (&value, &p) in outcomes {
+        if let Some(value) = f(value) {
+            *mapped.entry(value).or_insert(0.0) += p;
+        }
+    }
+    mapped
+}
+
+// Every pair of outcomes combined with `f`, counting the pairs against
+// `MAX_EXACT_WORK`
+fn combine(
+    a: &BTreeMap<i64, f64>,
+    b: &BTreeMap<i64, f64>,
+    work: &mut u64,
+    f: impl Fn(i64, i64) -> Option<i64>,
+) -> Option<BTreeMap<i64, f64>> {
+    *work += (a.len() * b.len()) as u64;
+    if *work > MAX_EXACT_WORK {
+        return None;
+    }
+    let mut combined = BTreeMap::new();
+    for (&x, &px) in a {
+        for (&y, &py) in b {
+            *combined.entry(f(x, y)?).or_insert(0.0) += px * py;
+        }
+    }
+    Some(combined)
+}
+
+/// Percentiles marked under [`format_chart`]: the 10th, the median and the 90th
+pub const CHART_PERCENTILES: [f64; 3] = [0.1, 0.5, 0.9];
+
+// Eighths of a block, for the columns of `format_chart`
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One bucket of a chart: the values `start..=end` and their chance
+struct Bucket {
+    start: i64,
+    end: i64,
+    p: f64,
+}
+
+impl Bucket {
+    fn label(&self) -> String {
+        if self.start == self.end {
+            self.start.to_string()
+        } else {
+            format!("{}-{}", self.start, self.end)
+        }
+    }
+}
+
+// At most `count` buckets: one per value when they fit, otherwise
+// neighbouring values merged into equal ranges
+fn buckets(distribution: &Distribution, count: usize) -> Vec<Bucket> {
+    if distribution.outcomes.len() <= count {
+        return distribution
+            .outcomes
+            .iter()
+            .map(|(&value, &p)| Bucket {
+                start: value,
+                end: value,
+                p,
+            })
+            .collect();
+    }
+
+    let (min, max) = (distribution.min(), distribution.max());
+    let span = (max - min + 1) as usize;
+    let width = span.div_ceil(count.max(1)) as i64;
+    (min..=max)
+        .step_by(width as usize)
+        .map(|start| {
+            let end = (start + width - 1).min(max);
+            let p = distribution
+                .outcomes
+                .range(start..=end)
+                .map(|(_, p)| p)
+                .sum();
+            Bucket { start, end, p }
+        })
+        .collect()
+}
+
+/// A text histogram of at most `rows` lines, grouping neighbouring values
+/// into equal ranges when there are more values than rows:
+///
+/// ```text
+///  3-4  │██                    1.85%
+///  5-6  │██████               6.94%
+/// ```
+pub fn format_histogram(distribution: &Distribution, rows: usize) -> String {
+    const BAR_WIDTH: usize = 20;
+
+    let buckets = buckets(distribution, rows);
+    let labels: Vec<String> = buckets.iter().map(Bucket::label).collect();
+    let label_width = labels.iter().map(String::len).max().unwrap_or(0);
+    let highest = buckets.iter().map(|bucket| bucket.p).fold(0.0, f64::max);
+    buckets
+        .iter()
+        .zip(&labels)
+        .map(|(bucket, label)| {
+            let p = bucket.p;
+            let bar = if highest > 0.0 && p > 0.0 {
+                ((p / highest * BAR_WIDTH as f64).round() as usize).max(1)
+            } else {
+                0
+            };
+            format!(
+                "{label:>label_width$} │{:<BAR_WIDTH$} {}",
+                "█".repeat(bar),
+                format_percent(p)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A chart of the whole distribution in block characters, one column per
+/// value or range of values and at most `columns` wide, with `^` under the
+/// columns holding [`CHART_PERCENTILES`] and the lowest and highest value
+/// below:
+///
+/// ```text
+/// ▁▁▂▃▄▅▆▇██▇▆▅▄▃▂▁▁
+///    ^     ^      ^
+/// 3                18
+/// ```
+pub fn format_chart(distribution: &Distribution, columns: usize) -> String {
+    let buckets = buckets(distribution, columns);
+    let highest = buckets.iter().map(|bucket| bucket.p).fold(0.0, f64::max);
+    let bars: String = buckets
+        .iter()
+        .map(|bucket| {
+            if highest <= 0.0 || bucket.p <= 0.0 {
+                return ' ';
+            }
+            let level = (bucket.p / highest * BLOCKS.len() as f64).round() as usize;
+            BLOCKS[level.clamp(1, BLOCKS.len()) - 1]
+        })
+        .collect();
+
+    let mut markers = vec![' '; buckets.len()];
+    for fraction in CHART_PERCENTILES {
+        let value = distribution.percentile(fraction);
+        if let Some(column) = buckets.iter().position(|bucket| bucket.end >= value) {
+            markers[column] = '^';
+        }
+    }
+    let markers: String = markers.into_iter().collect();
+
+    let (min, max) = (
+        distribution.min().to_string(),
+        distribution.max().to_string(),
+    );
+    let gap = buckets.len().saturating_sub(min.len() + max.len()).max(1);
+    format!(
+        "{bars}\n{}\n{min}{}{max}",
+        markers.trim_end(),
+        " ".repeat(gap)
+    )
+}
+
+/// `12.34%`, or `<0.01%` for a chance too small to show
+pub fn format_percent(p: f64) -> String {
+    if p > 0.0 && p < 0.0001 {
+        "<0.01%".to_string()
+    } else {
+        format!("{:.2}%", p * 100.0)
+    }
+}
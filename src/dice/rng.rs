@@ -1,6 +1,6 @@
 //! Enhanced random-number generation for dice rolling.
 //!
-//! All dice in Dice Maiden use [`get_dice_rng`] (an alias for
+//! Dice rolled with `roller::roll_dice` use [`get_dice_rng`] (an alias for
 //! [`create_enhanced_rng`]).  This returns a [`rand::rngs::StdRng`] (ChaCha20
 //! internally) seeded with multiple independent entropy sources so that rapid
 //! successive rolls — common in a busy Discord server — are highly unlikely to
@@ -20,6 +20,40 @@
 //! [`create_fast_rng`] returns a [`rand::rngs::SmallRng`] for benchmarks and
 //! scenarios where speed matters more than cryptographic quality.
 //!
+//! # Session streams
+//!
+//! Rolls in a channel with an active `/session` use [`session_rng`] instead,
+//! so the GM can reproduce any session roll from the seed alone.  The seed is
+//! 32 secret bytes ([`new_session_seed`]) used as the key of a
+//! `rand_chacha::ChaCha20Rng`, and roll number `n` selects its stream
+//! (`set_stream(n)`); each die is then `rng.random_range(1..=sides)` as for
+//! fair rolls below.  Outside sessions the entropy-seeded RNG above is used
+//! as before.
+//!
+//! # Provably-fair rolls
+//!
+//! With `/settings fair` on, a server's rolls use [`fair_rng`], so once
+//! `/verify` reveals the secret 32-byte server seed ([`new_fair_seed`]) anyone
+//! can recompute a roll and check the seed against the hash shown under it.
+//! Roll number `n` is derived as:
+//!
+//! 1. `key = SHA-256(seed || n as u64 little-endian)`
+//! 2. `rand_chacha::ChaCha20Rng::from_seed(key)` (20 rounds, stream 0)
+//! 3. each die is `rng.random_range(1..=sides)` from `rand` 0.10, in the
+//!    order the roller draws them
+//!
+//! `rand_chacha` is pinned in `Cargo.toml` and golden-value tests fix the
+//! faces of both streams, so an upgrade can't silently change past rolls.  Other secrets,
+//! such as log drain signing keys, come from the same [`secret_bytes`].
+//!
+//! # Seeded rolls
+//!
+//! Every roll function has a `_with_rng` form (`roller::roll_dice_with_rng`,
+//! `dice::parse_and_roll_with_rng`) that draws every die, including rerolls,
+//! explosions and game-system extras, from the RNG it is given.  With
+//! [`seeded_rng`] the same seed and expression always give the same result,
+//! for tests and for checking a reported roll.
+//!
 //! # Usage note
 //!
 //! `rand 0.10` requires importing **both** `rand::Rng` (for trait bounds) and
@@ -27,6 +61,8 @@
 
 use rand::rngs::StdRng; // Use StdRng instead of ChaCha20Rng (it's ChaCha20 internally)
 use rand::{RngExt, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Creates an enhanced RNG with multiple entropy sources for maximum unpredictability
@@ -151,6 +187,53 @@ pub fn get_dice_rng() -> StdRng {
     create_enhanced_rng()
 }
 
+/// A fresh secret seed for a `/session` ([`secret_bytes`]).  Without OS
+/// entropy the session's rolls could be predicted, so it doesn't start.
+pub fn new_session_seed() -> Result<[u8; 32], getrandom::Error> {
+    secret_bytes()
+}
+
+/// Deterministic RNG for roll number `roll` of the session seeded with `seed`.
+///
+/// The seed is the ChaCha20 key and the roll number its stream, so every roll
+/// gets its own independent stream and any single roll can be replayed
+/// without the rest.
+pub fn session_rng(seed: &[u8; 32], roll: u64) -> ChaCha20Rng {
+    let mut rng = ChaCha20Rng::from_seed(*seed);
+    rng.set_stream(roll);
+    rng
+}
+
+/// Deterministic RNG for reproducible rolls: the same seed always gives the
+/// same dice
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+/// 32 secret bytes straight from OS entropy, for seeds and keys.  Unlike the
+/// dice RNG there is no fallback: a guessable secret is worse than none, so
+/// the caller should go without instead.
+pub fn secret_bytes() -> Result<[u8; 32], getrandom::Error> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// A new secret provably-fair server seed ([`secret_bytes`]).  Without OS
+/// entropy fair rolls would be predictable, so the caller skips fair rolling.
+pub fn new_fair_seed() -> Result<[u8; 32], getrandom::Error> {
+    secret_bytes()
+}
+
+/// Deterministic RNG for roll number `nonce` of a provably-fair server seed:
+/// a ChaCha20 stream keyed by `SHA-256(seed || nonce as u64 little-endian)`
+pub fn fair_rng(seed: &[u8], nonce: u64) -> ChaCha20Rng {
+    let mut hasher = Sha256::new();
+    hasher.update(seed);
+    hasher.update(nonce.to_le_bytes());
+    ChaCha20Rng::from_seed(hasher.finalize().into())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,4 +317,41 @@ mod tests {
             "Fallback seed should not be all zeros"
         );
     }
+
+    #[test]
+    fn test_seeded_rng_repeats() {
+        let values = |seed| {
+            let mut rng = seeded_rng(seed);
+            (0..8).map(|_| rng.random::<u32>()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(values(1), values(1));
+        assert_ne!(values(1), values(2));
+    }
+
+    #[test]
+    fn test_fair_rng_keyed_by_seed_and_nonce() {
+        let values = |seed: &[u8], nonce| {
+            let mut rng = fair_rng(seed, nonce);
+            (0..8).map(|_| rng.random::<u32>()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(values(&[7; 32], 1), values(&[7; 32], 1));
+        assert_ne!(values(&[7; 32], 1), values(&[7; 32], 2));
+        assert_ne!(values(&[7; 32], 1), values(&[8; 32], 1));
+    }
+
+    #[test]
+    fn test_fair_rng_golden_faces() {
+        // Published fair rolls must replay forever: if this changes, so did
+        // every revealed roll
+        let results =
+            crate::dice::parse_and_roll_with_rng("8d6", &mut fair_rng(&[7; 32], 1)).unwrap();
+        assert_eq!(results[0].individual_rolls, vec![5, 4, 4, 5, 2, 2, 6, 6]);
+    }
+
+    #[test]
+    fn test_secret_bytes_unique() {
+        assert_ne!(secret_bytes().unwrap(), secret_bytes().unwrap());
+    }
 }
@@ -23,7 +23,7 @@
 //! # Specialised handlers
 //! | Handler function                  | System                        |
 //! |-----------------------------------|-------------------------------|
-//! | `handle_conan_skill_roll`         | Conan 2d20 skill checks       |
+//! | `handle_2d20_roll`                | 2d20 checks (Conan, STA, …)   |
 //! | `handle_conan_combat_roll`        | Conan combat / hit location   |
 //! | `handle_d6_system_roll`           | D6 System (wild die)          |
 //! | `handle_marvel_multiverse_roll`   | Marvel Multiverse RPG         |
@@ -33,34 +33,166 @@
 //! | `handle_vtm5_roll`                | Vampire: the Masquerade 5e    |
 //! | `handle_mutants_masterminds_roll` | Mutants & Masterminds DC 10   |
 //! | `handle_mothership_roll`          | Mothership RPG (1d100 ≤ stat) |
+//! | `handle_mothership_panic_roll`    | Mothership panic check (d20)  |
+//! | `handle_genesys_roll`             | Genesys / Star Wars FFG       |
+//! | `handle_coc_roll`                 | Call of Cthulhu 7e (d100)     |
+//! | `handle_wfrp_roll`                | Warhammer Fantasy 4e (d100)   |
+//! | `handle_dragonbane_roll`          | Dragonbane (d20 ≤ skill)      |
+//! | `handle_gurps_roll`               | GURPS (3d6 ≤ skill)           |
+//! | `handle_l5r_roll`                 | Legend of the Five Rings 5e   |
+//! | `handle_year_zero_roll`           | Forbidden Lands (Year Zero)   |
+//! | `handle_burning_wheel_roll`       | Burning Wheel / Mouse Guard   |
+//! | `handle_cortex_roll`              | Cortex Prime (total + effect) |
+//! | `handle_ironsworn_roll`           | Ironsworn / Starforged action |
+//! | `handle_oracle_roll`              | Ironsworn oracle (d100)       |
+//! | `handle_face_roll`                | Labeled faces (`2d{yes,no}`)  |
 //!
-//! RNG is obtained fresh per call via `rng::get_dice_rng` (ChaCha20 / StdRng
-//! seeded with OS entropy + timestamp + thread/process/ASLR entropy).
+//! [`roll_dice`] obtains a fresh RNG per call via `rng::get_dice_rng` (ChaCha20
+//! / StdRng seeded with OS entropy + timestamp + thread/process/ASLR entropy).
+//! [`roll_dice_with_rng`] takes the RNG from the caller instead; session rolls
+//! use it with a seeded stream from `rng::session_rng`.
 
+use super::error::{DiceError, Result};
 use super::rng::get_dice_rng;
-use super::{DiceGroup, DiceRoll, HeroSystemType, LaserFeelingsType, Modifier, RollResult};
-use anyhow::{Result, anyhow};
+use super::{
+    BurningWheelPool, BurningWheelResult, CortexDie, CortexResult, DCC_DICE_CHAIN, DiceGroup,
+    DiceRoll, HeroSystemType, L5rPool, L5rResult, LaserFeelingsType, MatchResult, Modifier,
+    NarrativePool, NarrativeResult, RollResult, WfrpResult, YearZeroPool, YearZeroResult,
+};
 use rand::{Rng, RngExt};
+use std::cmp::Reverse;
 
 pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
-    // Validation check
-    if dice.sides < 1 {
-        return Err(anyhow!("Cannot roll dice with {} sides", dice.sides));
+    roll_dice_with_rng(dice, &mut get_dice_rng())
+}
+
+/// Roll with a caller-supplied RNG, so a seeded stream (e.g. a `/session`)
+/// produces the same dice every time.  Sub-rolls draw from the same stream.
+pub fn roll_dice_with_rng(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResult> {
+    // Validation check; math-only expressions ("4*7+2") parse to 0d0 and
+    // only run the math modifiers
+    let math_only = dice.count == 0 && dice.sides == 0;
+    if dice.sides < 1 && !math_only {
+        return Err(DiceError::ValidationError(format!(
+            "Cannot roll dice with {} sides",
+            dice.sides
+        )));
+    }
+    if dice.count == 0 && !math_only {
+        return Err(DiceError::ValidationError("Cannot roll 0 dice".into()));
+    }
+    if dice.pool.is_some() && !supports_mixed_pool(&dice) {
+        return Err(DiceError::ValidationError(
+            "Mixed pools like `{d8+d6}` can't explode, reroll or use game-system modifiers".into(),
+        ));
+    }
+
+    // Genesys narrative pools roll symbol dice instead of the NdS
+    if let Some(pool) = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::Genesys(pool) => Some(*pool),
+        _ => None,
+    }) {
+        return handle_genesys_roll(dice, pool, rng);
+    }
+
+    // L5R checks roll ring and skill symbol dice instead of the NdS
+    if let Some(pool) = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::L5r(pool) => Some(*pool),
+        _ => None,
+    }) {
+        return handle_l5r_roll(dice, pool, rng);
+    }
+
+    // Forbidden Lands rolls base, skill, gear and artifact dice instead of the NdS
+    if let Some(pool) = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::YearZero(pool) => Some(pool.clone()),
+        _ => None,
+    }) {
+        return handle_year_zero_roll(dice, pool, rng);
+    }
+
+    // Burning Wheel rolls the exponent and helping dice against an obstacle
+    if let Some(pool) = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::BurningWheel(pool) => Some(*pool),
+        _ => None,
+    }) {
+        return handle_burning_wheel_roll(dice, pool, rng);
+    }
+
+    // Cortex Prime picks a total and an effect die from a (usually mixed) pool
+    if dice.modifiers.iter().any(|m| matches!(m, Modifier::Cortex)) {
+        return handle_cortex_roll(dice, rng);
+    }
+
+    // Call of Cthulhu rolls percentile dice with bonus/penalty tens dice
+    if let Some((skill, bonus)) = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::CoC(skill, bonus) => Some((*skill, *bonus)),
+        _ => None,
+    }) {
+        return handle_coc_roll(dice, skill, bonus, rng);
+    }
+
+    // Warhammer Fantasy Roleplay tests a d100 against a skill for Success Levels
+    if let Some(skill) = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::Wfrp(skill) => Some(*skill),
+        _ => None,
+    }) {
+        return handle_wfrp_roll(dice, skill, rng);
     }
-    if dice.count == 0 {
-        return Err(anyhow!("Cannot roll 0 dice"));
+
+    // GURPS success rolls compare 3d6 with the effective skill
+    if let Some(skill) = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::Gurps(skill) => Some(*skill),
+        _ => None,
+    }) {
+        return handle_gurps_roll(dice, skill, rng);
     }
 
-    let mut rng = get_dice_rng();
+    // Dragonbane rolls a d20 under a skill, with a second d20 for a boon or bane
+    if let Some((skill, boon, pushed)) = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::Dragonbane(skill, boon, pushed) => Some((*skill, *boon, *pushed)),
+        _ => None,
+    }) {
+        return handle_dragonbane_roll(dice, skill, boon, pushed, rng);
+    }
 
-    // Check for Conan system handlers
-    let has_conan_skill = dice
+    // Ironsworn action rolls add two challenge dice to the action die
+    if dice
         .modifiers
         .iter()
-        .any(|m| matches!(m, Modifier::ConanSkill(_)));
+        .any(|m| matches!(m, Modifier::Ironsworn))
+    {
+        return handle_ironsworn_roll(dice, rng);
+    }
+
+    // Ironsworn oracles answer yes or no on a d100
+    if let Some(odds) = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::Oracle(odds) => Some(*odds),
+        _ => None,
+    }) {
+        return handle_oracle_roll(dice, odds, rng);
+    }
+
+    // Labeled dice (coins, `2d{yes,no,maybe}`) show faces instead of a total
+    if let Some(faces) = dice.faces.clone() {
+        return handle_face_roll(dice, faces, rng);
+    }
+
+    // 2d20 checks: Conan skill rolls and rolls with a target number, focus,
+    // difficulty or complication range
+    let is_2d20_check = dice.modifiers.iter().any(|m| {
+        matches!(
+            m,
+            Modifier::ConanSkill(_)
+                | Modifier::TargetNumber(_)
+                | Modifier::Focus(_)
+                | Modifier::Difficulty(_)
+                | Modifier::Complication(_)
+        )
+    });
 
-    if has_conan_skill {
-        return handle_conan_skill_roll(dice, &mut rng);
+    if is_2d20_check {
+        return handle_2d20_roll(dice, rng);
     }
 
     let has_conan_combat = dice
@@ -69,7 +201,7 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
         .any(|m| matches!(m, Modifier::ConanCombat(_)));
 
     if has_conan_combat {
-        return handle_conan_combat_roll(dice, &mut rng);
+        return handle_conan_combat_roll(dice, rng);
     }
 
     // Check if this is a D6 System roll - handle it specially
@@ -79,7 +211,7 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
         .any(|m| matches!(m, Modifier::D6System(_, _)));
 
     if has_d6_system {
-        return handle_d6_system_roll(dice, &mut rng);
+        return handle_d6_system_roll(dice, rng);
     }
 
     let has_marvel_multiverse = dice
@@ -88,7 +220,7 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
         .any(|m| matches!(m, Modifier::MarvelMultiverse(_, _)));
 
     if has_marvel_multiverse {
-        return handle_marvel_multiverse_roll(dice, &mut rng);
+        return handle_marvel_multiverse_roll(dice, rng);
     }
 
     // Check if this is a Savage Worlds roll - handle it specially
@@ -99,7 +231,7 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
 
     if has_savage_worlds {
         // For Savage Worlds, handle it completely differently
-        return handle_savage_worlds_roll(dice, &mut rng);
+        return handle_savage_worlds_roll(dice, rng);
     }
 
     let has_brave_new_world = dice
@@ -108,7 +240,7 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
         .any(|m| matches!(m, Modifier::BraveNewWorld(_)));
 
     if has_brave_new_world {
-        return handle_brave_new_world_roll(dice, &mut rng);
+        return handle_brave_new_world_roll(dice, rng);
     }
 
     // Check if this is a Silhouette roll - handle it specially
@@ -118,7 +250,7 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
         .any(|m| matches!(m, Modifier::Silhouette(_)));
 
     if has_silhouette {
-        return handle_silhouette_roll(dice, &mut rng);
+        return handle_silhouette_roll(dice, rng);
     }
 
     // Check if this is a Mutants & Masterminds roll - handle it specially
@@ -128,7 +260,15 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
         .any(|m| matches!(m, Modifier::MutantsMasterminds));
 
     if has_mutants_masterminds {
-        return handle_mutants_masterminds_roll(dice, &mut rng);
+        return handle_mutants_masterminds_roll(dice, rng);
+    }
+
+    // Mothership panic checks roll a d20 against the stress
+    if let Some(stress) = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::MothershipPanic(stress) => Some(*stress),
+        _ => None,
+    }) {
+        return handle_mothership_panic_roll(dice, stress, rng);
     }
 
     // Check if this is a Mothership roll - handle it specially
@@ -138,11 +278,63 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
         .any(|m| matches!(m, Modifier::Mothership(_, _)));
 
     if has_mothership {
-        return handle_mothership_roll(dice, &mut rng);
+        return handle_mothership_roll(dice, rng);
     }
 
+    // Normal dice rolling flow for non-special systems
+    // Initial dice rolls
+    let rolls = die_sizes(&dice)
+        .into_iter()
+        .map(|sides| rng.random_range(1..=sides as i32))
+        .collect();
+    roll_standard(dice, rolls, rng)
+}
+
+/// The size of each of `dice`'s main dice: those of a mixed pool, or
+/// `count` dice of `sides`
+pub fn die_sizes(dice: &DiceRoll) -> Vec<u32> {
+    dice.pool
+        .clone()
+        .unwrap_or_else(|| vec![dice.sides; dice.count as usize])
+}
+
+/// Whether a mixed pool (`{d8+d6+d4}`) can roll with `dice`'s modifiers.
+/// Explosions and rerolls need each die's size, which the standard
+/// pipeline doesn't track once dice are added or sorted.
+fn supports_mixed_pool(dice: &DiceRoll) -> bool {
+    dice.modifiers.iter().all(|modifier| {
+        matches!(
+            modifier,
+            Modifier::Add(_)
+                | Modifier::Subtract(_)
+                | Modifier::Multiply(_)
+                | Modifier::Divide(_)
+                | Modifier::AddDice(_)
+                | Modifier::SubtractDice(_)
+                | Modifier::Drop(_)
+                | Modifier::KeepHigh(_)
+                | Modifier::KeepLow(_)
+                | Modifier::KeepMiddle(_)
+                | Modifier::MaxSuccesses(_)
+                | Modifier::Target(_)
+                | Modifier::TargetLower(_)
+                | Modifier::TargetWithDoubleSuccess(_, _)
+                | Modifier::TargetLowerWithDoubleSuccess(_, _)
+                | Modifier::Failure(_)
+                | Modifier::Botch(_)
+                | Modifier::Cancel
+                | Modifier::Matches
+                | Modifier::Bands(_)
+                | Modifier::TargetLadder(_)
+                | Modifier::Cortex
+        )
+    })
+}
+
+// The standard pipeline, from the main dice already rolled
+fn roll_standard(dice: DiceRoll, rolls: Vec<i32>, rng: &mut impl Rng) -> Result<RollResult> {
     let mut result = RollResult {
-        individual_rolls: Vec::new(),
+        individual_rolls: rolls,
         kept_rolls: Vec::new(),
         dropped_rolls: Vec::new(),
         total: 0,
@@ -157,6 +349,11 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -171,27 +368,34 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: dice.unsorted,
     };
 
-    // Normal dice rolling flow for non-special systems
-    // Initial dice rolls
-    for _ in 0..dice.count {
-        let roll = rng.random_range(1..=dice.sides as i32);
-        result.individual_rolls.push(roll);
+    // Create initial dice group for the base dice; a `pemdas` roll that starts
+    // with a number has none, and shows only its other dice
+    if dice.count > 0 || !dice.pemdas {
+        let base_group = DiceGroup {
+            _description: format!("{}d{}", dice.count, dice.sides),
+            rolls: result.individual_rolls.clone(),
+            dropped_rolls: Vec::new(),
+            modifier_type: "base".to_string(),
+        };
+        result.dice_groups.push(base_group);
     }
 
-    // Create initial dice group for the base dice
-    let base_group = DiceGroup {
-        _description: format!("{}d{}", dice.count, dice.sides),
-        rolls: result.individual_rolls.clone(),
-        dropped_rolls: Vec::new(),
-        modifier_type: "base".to_string(),
-    };
-    result.dice_groups.push(base_group);
-
     // Apply modifiers in the correct order for mathematical precedence
     // 1. Apply dice-modifying modifiers first (exploding, rerolls, etc.)
-    apply_dice_modifying_modifiers(&mut result, &mut rng, &dice)?;
+    apply_dice_modifying_modifiers(&mut result, rng, &dice)?;
 
     // 2. Apply keep/drop modifiers
     apply_keep_drop_modifiers(&mut result, &dice)?;
@@ -200,19 +404,107 @@ pub fn roll_dice(dice: DiceRoll) -> Result<RollResult> {
     if result.kept_rolls.is_empty() {
         result.kept_rolls = result.individual_rolls.clone();
     }
-    result.total = result.kept_rolls.iter().sum();
+    result.total = sum_rolls(&result.kept_rolls)?;
 
     // 4. Apply mathematical modifiers (add, subtract, multiply, divide)
-    apply_mathematical_modifiers(&mut result, &dice, &mut rng)?;
+    apply_mathematical_modifiers(&mut result, &dice, rng)?;
 
     // 5. Apply special system modifiers (after math modifiers for proper precedence)
-    apply_special_system_modifiers(&mut result, &dice, &mut rng)?;
+    apply_special_system_modifiers(&mut result, &dice, rng)?;
+
+    // 6. Cap successes (maxs#) once everything that adds to them has run
+    apply_success_cap(&mut result, &dice)?;
+
+    // 7. Classify the final total into outcome bands (bands[a,b]) or
+    //    target ladder tiers (t[90,70,50])
+    apply_bands(&mut result, &dice)?;
+    apply_target_ladder(&mut result, &dice)?;
+
+    // 8. Sort rolls unless their order matters (ul flag or the system)
+    sort_result_rolls(&mut result);
+
+    Ok(result)
+}
+
+/// Whether [`reroll_selected`] can reroll some of `dice`'s dice: a standard
+/// roll whose only random draws are its main dice, so the dice left alone
+/// keep their values
+pub fn supports_selected_reroll(dice: &DiceRoll) -> bool {
+    dice.count > 0
+        && dice.faces.is_none()
+        && dice.pool.is_none()
+        && dice.modifiers.iter().all(|modifier| {
+            matches!(
+                modifier,
+                Modifier::Add(_)
+                    | Modifier::Subtract(_)
+                    | Modifier::Multiply(_)
+                    | Modifier::Divide(_)
+                    | Modifier::Drop(_)
+                    | Modifier::KeepHigh(_)
+                    | Modifier::KeepLow(_)
+                    | Modifier::KeepMiddle(_)
+                    | Modifier::MaxSuccesses(_)
+                    | Modifier::Target(_)
+                    | Modifier::TargetLower(_)
+                    | Modifier::TargetWithDoubleSuccess(_, _)
+                    | Modifier::TargetLowerWithDoubleSuccess(_, _)
+                    | Modifier::Failure(_)
+                    | Modifier::Botch(_)
+                    | Modifier::Cancel
+                    | Modifier::Bands(_)
+                    | Modifier::TargetLadder(_)
+            )
+        })
+}
+
+/// The main dice of a standard roll, kept dice first, as [`reroll_selected`]
+/// numbers them
+pub fn selectable_dice(result: &RollResult) -> Vec<i32> {
+    result
+        .kept_rolls
+        .iter()
+        .chain(&result.dropped_rolls)
+        .copied()
+        .collect()
+}
+
+/// Roll `dice` again from `rolls` (its [`selectable_dice`]), with the dice
+/// at `selected` rolled afresh and every other die keeping its value.  A
+/// note lists each rerolled die, its old value struck through.
+pub fn reroll_selected(
+    dice: DiceRoll,
+    rolls: &[i32],
+    selected: &[usize],
+    rng: &mut impl Rng,
+) -> Result<RollResult> {
+    if !supports_selected_reroll(&dice) {
+        return Err(DiceError::ValidationError(
+            "Only standard rolls can reroll selected dice".into(),
+        ));
+    }
+    if rolls.len() != dice.count as usize || selected.iter().any(|&index| index >= rolls.len()) {
+        return Err(DiceError::ValidationError(
+            "The selected dice aren't part of this roll".into(),
+        ));
+    }
 
-    // 6. Sort rolls unless unsorted flag is set
-    if !dice.unsorted {
-        sort_result_rolls(&mut result);
+    let mut new_rolls = rolls.to_vec();
+    let mut changes = Vec::new();
+    for (index, roll) in new_rolls.iter_mut().enumerate() {
+        if selected.contains(&index) {
+            let old = *roll;
+            *roll = rng.random_range(1..=dice.sides as i32);
+            changes.push(format!("~~{old}~~ → {roll}"));
+        }
     }
 
+    let mut result = roll_standard(dice, new_rolls, rng)?;
+    if !changes.is_empty() {
+        result
+            .notes
+            .push(format!("Rerolled {}", changes.join(", ")));
+    }
     Ok(result)
 }
 
@@ -232,6 +524,18 @@ fn apply_dice_modifying_modifiers(
                 explode_dice(result, rng, *threshold, dice.sides, true, dice)?;
                 update_base_group(result);
             }
+            Modifier::ExplodeTotal(threshold) => {
+                explode_total(result, rng, *threshold, dice)?;
+                update_base_group(result);
+            }
+            Modifier::Compound(threshold) => {
+                compound_dice(result, rng, *threshold, dice.sides)?;
+                update_base_group(result);
+            }
+            Modifier::Penetrate(threshold) => {
+                penetrate_dice(result, rng, *threshold, dice.sides)?;
+                update_base_group(result);
+            }
             Modifier::Reroll(threshold) => {
                 reroll_dice(result, rng, *threshold, dice.sides, false)?;
                 update_base_group(result);
@@ -248,6 +552,10 @@ fn apply_dice_modifying_modifiers(
                 reroll_dice_greater(result, rng, *threshold, dice.sides, true)?;
                 update_base_group(result);
             }
+            Modifier::RerollFailed => {
+                reroll_failed_dice(result, rng, dice)?;
+                update_base_group(result);
+            }
             _ => {} // Handle other modifiers later
         }
     }
@@ -267,19 +575,19 @@ fn apply_keep_drop_modifiers(result: &mut RollResult, dice: &DiceRoll) -> Result
             }
             Modifier::KeepHigh(count) => {
                 if *count == 0 {
-                    return Err(anyhow!("Cannot keep 0 dice"));
+                    return Err(DiceError::ValidationError("Cannot keep 0 dice".into()));
                 }
                 keep_dice(result, *count as usize, false)?;
             }
             Modifier::KeepLow(count) => {
                 if *count == 0 {
-                    return Err(anyhow!("Cannot keep 0 dice"));
+                    return Err(DiceError::ValidationError("Cannot keep 0 dice".into()));
                 }
                 keep_dice(result, *count as usize, true)?;
             }
             Modifier::KeepMiddle(count) => {
                 if *count == 0 {
-                    return Err(anyhow!("Cannot keep 0 dice"));
+                    return Err(DiceError::ValidationError("Cannot keep 0 dice".into()));
                 }
                 keep_middle_dice(result, *count as usize)?;
             }
@@ -293,29 +601,30 @@ fn apply_keep_drop_modifiers(result: &mut RollResult, dice: &DiceRoll) -> Result
 fn apply_mathematical_modifiers(
     result: &mut RollResult,
     dice: &DiceRoll,
-    _rng: &mut impl Rng,
+    rng: &mut impl Rng,
 ) -> Result<()> {
     // Check for special division pattern: Multiply(0) followed by Add(number)
-    if dice.modifiers.len() >= 2
+    if !dice.pemdas
+        && dice.modifiers.len() >= 2
         && let (Modifier::Multiply(0), Modifier::Add(number)) =
             (&dice.modifiers[0], &dice.modifiers[1])
     {
         // This is our special "number / dice" case
         if result.total == 0 {
-            return Err(anyhow!("Cannot divide by zero (dice result was 0)"));
+            return Err(DiceError::DivisionByZero { by_dice: true });
         }
         result.total = number / result.total;
 
         // IMPORTANT: Continue processing remaining modifiers starting from index 2
         let remaining_modifiers = &dice.modifiers[2..];
         if !remaining_modifiers.is_empty() {
-            apply_remaining_mathematical_modifiers(result, remaining_modifiers, dice)?;
+            apply_remaining_mathematical_modifiers(result, remaining_modifiers, dice, rng)?;
         }
         return Ok(());
     }
 
     // Standard mathematical modifier processing
-    apply_all_mathematical_modifiers(result, dice)?;
+    apply_all_mathematical_modifiers(result, dice, rng)?;
     Ok(())
 }
 
@@ -324,6 +633,7 @@ fn apply_remaining_mathematical_modifiers(
     result: &mut RollResult,
     modifiers: &[Modifier],
     _dice: &DiceRoll,
+    rng: &mut impl Rng,
 ) -> Result<()> {
     // Build an expression from the remaining modifiers
     let mut expression_parts = Vec::new();
@@ -335,7 +645,7 @@ fn apply_remaining_mathematical_modifiers(
     for modifier in modifiers {
         match modifier {
             Modifier::AddDice(dice_to_add) => {
-                let additional_result = roll_dice(dice_to_add.clone())?;
+                let additional_result = roll_dice_with_rng(dice_to_add.clone(), rng)?;
                 expression_parts.push("+".to_string());
                 expression_parts.push(format!("{}", additional_result.total));
 
@@ -351,7 +661,7 @@ fn apply_remaining_mathematical_modifiers(
                 add_dice_group(result, dice_to_add, &additional_result, "add");
             }
             Modifier::SubtractDice(dice_to_subtract) => {
-                let additional_result = roll_dice(dice_to_subtract.clone())?;
+                let additional_result = roll_dice_with_rng(dice_to_subtract.clone(), rng)?;
                 expression_parts.push("-".to_string());
                 expression_parts.push(format!("{}", additional_result.total));
 
@@ -368,7 +678,7 @@ fn apply_remaining_mathematical_modifiers(
             }
             Modifier::MultiplyDice(dice_to_multiply) => {
                 // Handle dice multiplication in remaining modifiers
-                let additional_result = roll_dice(dice_to_multiply.clone())?;
+                let additional_result = roll_dice_with_rng(dice_to_multiply.clone(), rng)?;
                 expression_parts.push("*".to_string());
                 expression_parts.push(format!("{}", additional_result.total));
 
@@ -383,10 +693,10 @@ fn apply_remaining_mathematical_modifiers(
             }
             Modifier::DivideDice(dice_to_divide) => {
                 // Handle dice division in remaining modifiers
-                let additional_result = roll_dice(dice_to_divide.clone())?;
+                let additional_result = roll_dice_with_rng(dice_to_divide.clone(), rng)?;
 
                 if additional_result.total == 0 {
-                    return Err(anyhow!("Cannot divide by zero (dice result was 0)"));
+                    return Err(DiceError::DivisionByZero { by_dice: true });
                 }
 
                 expression_parts.push("/".to_string());
@@ -415,7 +725,7 @@ fn apply_remaining_mathematical_modifiers(
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 expression_parts.push("/".to_string());
                 expression_parts.push(format!("{value}"));
@@ -426,17 +736,32 @@ fn apply_remaining_mathematical_modifiers(
 
     // Evaluate the expression if we have additional operations
     if expression_parts.len() > 1 {
-        result.total = evaluate_expression(&expression_parts)?;
+        result.total = evaluate_expression(&expression_parts, false)?;
     }
 
     Ok(())
 }
 
 // Function to apply all mathematical modifiers (for standard case)
-fn apply_all_mathematical_modifiers(result: &mut RollResult, dice: &DiceRoll) -> Result<()> {
+fn apply_all_mathematical_modifiers(
+    result: &mut RollResult,
+    dice: &DiceRoll,
+    rng: &mut impl Rng,
+) -> Result<()> {
     // Build an expression from the modifiers and evaluate it properly
     let mut expression_parts = Vec::new();
 
+    // `pemdas` parentheses before the first operator enclose the dice total;
+    // later ones open before the next operand
+    let mut leading_groups = dice
+        .modifiers
+        .iter()
+        .take_while(|m| !is_math_operation(m))
+        .filter(|m| matches!(m, Modifier::OpenGroup))
+        .count();
+    let mut open_groups = 0;
+    expression_parts.extend((0..leading_groups).map(|_| "(".to_string()));
+
     // Start with the dice total
     expression_parts.push(format!("{}", result.total));
 
@@ -445,9 +770,13 @@ fn apply_all_mathematical_modifiers(result: &mut RollResult, dice: &DiceRoll) ->
         match modifier {
             Modifier::AddDice(dice_to_add) => {
                 // Roll the additional dice only once and use that result consistently
-                let additional_result = roll_dice(dice_to_add.clone())?;
-                expression_parts.push("+".to_string());
-                expression_parts.push(format!("{}", additional_result.total));
+                let additional_result = roll_dice_with_rng(dice_to_add.clone(), rng)?;
+                push_operation(
+                    &mut expression_parts,
+                    "+",
+                    format!("{}", additional_result.total),
+                    &mut open_groups,
+                );
 
                 // IMPORTANT: Merge notes from AddDice into main result
                 // This ensures Hero System notes are preserved
@@ -473,9 +802,13 @@ fn apply_all_mathematical_modifiers(result: &mut RollResult, dice: &DiceRoll) ->
             }
             Modifier::SubtractDice(dice_to_subtract) => {
                 // Roll the additional dice only once and use that result consistently
-                let additional_result = roll_dice(dice_to_subtract.clone())?;
-                expression_parts.push("-".to_string());
-                expression_parts.push(format!("{}", additional_result.total));
+                let additional_result = roll_dice_with_rng(dice_to_subtract.clone(), rng)?;
+                push_operation(
+                    &mut expression_parts,
+                    "-",
+                    format!("{}", additional_result.total),
+                    &mut open_groups,
+                );
 
                 // Add dice to individual_rolls for display
                 result
@@ -493,9 +826,13 @@ fn apply_all_mathematical_modifiers(result: &mut RollResult, dice: &DiceRoll) ->
             }
             Modifier::MultiplyDice(dice_to_multiply) => {
                 // Handle dice multiplication
-                let additional_result = roll_dice(dice_to_multiply.clone())?;
-                expression_parts.push("*".to_string());
-                expression_parts.push(format!("{}", additional_result.total));
+                let additional_result = roll_dice_with_rng(dice_to_multiply.clone(), rng)?;
+                push_operation(
+                    &mut expression_parts,
+                    "*",
+                    format!("{}", additional_result.total),
+                    &mut open_groups,
+                );
 
                 // Add dice to individual_rolls for display
                 result
@@ -512,15 +849,19 @@ fn apply_all_mathematical_modifiers(result: &mut RollResult, dice: &DiceRoll) ->
             }
             Modifier::DivideDice(dice_to_divide) => {
                 //  Handle dice division
-                let additional_result = roll_dice(dice_to_divide.clone())?;
+                let additional_result = roll_dice_with_rng(dice_to_divide.clone(), rng)?;
 
                 // Check for division by zero
                 if additional_result.total == 0 {
-                    return Err(anyhow!("Cannot divide by zero (dice result was 0)"));
+                    return Err(DiceError::DivisionByZero { by_dice: true });
                 }
 
-                expression_parts.push("/".to_string());
-                expression_parts.push(format!("{}", additional_result.total));
+                push_operation(
+                    &mut expression_parts,
+                    "/",
+                    format!("{}", additional_result.total),
+                    &mut open_groups,
+                );
 
                 // Add dice to individual_rolls for display
                 result
@@ -536,37 +877,82 @@ fn apply_all_mathematical_modifiers(result: &mut RollResult, dice: &DiceRoll) ->
                 add_dice_group(result, dice_to_divide, &additional_result, "divide");
             }
             Modifier::Add(value) => {
-                expression_parts.push("+".to_string());
-                expression_parts.push(format!("{value}"));
+                push_operation(
+                    &mut expression_parts,
+                    "+",
+                    format!("{value}"),
+                    &mut open_groups,
+                );
             }
             Modifier::Subtract(value) => {
-                expression_parts.push("-".to_string());
-                expression_parts.push(format!("{value}"));
+                push_operation(
+                    &mut expression_parts,
+                    "-",
+                    format!("{value}"),
+                    &mut open_groups,
+                );
             }
-            Modifier::Multiply(value) if *value != 0 => {
+            Modifier::Multiply(value) if *value != 0 || dice.pemdas => {
                 // Skip the special marker (multiply by 0)
-                expression_parts.push("*".to_string());
-                expression_parts.push(format!("{value}"));
+                push_operation(
+                    &mut expression_parts,
+                    "*",
+                    format!("{value}"),
+                    &mut open_groups,
+                );
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
-                expression_parts.push("/".to_string());
-                expression_parts.push(format!("{value}"));
+                push_operation(
+                    &mut expression_parts,
+                    "/",
+                    format!("{value}"),
+                    &mut open_groups,
+                );
             }
+            Modifier::OpenGroup if leading_groups > 0 => leading_groups -= 1,
+            Modifier::OpenGroup => open_groups += 1,
+            Modifier::CloseGroup => expression_parts.push(")".to_string()),
             _ => {}
         }
     }
 
-    // Evaluate the expression with proper precedence
+    // Evaluate the expression, with operator precedence for `pemdas` rolls
     if expression_parts.len() > 1 {
-        result.total = evaluate_expression(&expression_parts)?;
+        result.total = evaluate_expression(&expression_parts, dice.pemdas)?;
     }
 
     Ok(())
 }
 
+// Whether a modifier is an operator with its operand, or a `)`: where the
+// parentheses around a `pemdas` roll's own total stop
+fn is_math_operation(modifier: &Modifier) -> bool {
+    matches!(
+        modifier,
+        Modifier::Add(_)
+            | Modifier::Subtract(_)
+            | Modifier::Multiply(_)
+            | Modifier::Divide(_)
+            | Modifier::AddDice(_)
+            | Modifier::SubtractDice(_)
+            | Modifier::MultiplyDice(_)
+            | Modifier::DivideDice(_)
+            | Modifier::CloseGroup
+    )
+}
+
+// Push `operator value`, opening any `pemdas` parentheses waiting for the
+// operand in between
+fn push_operation(parts: &mut Vec<String>, operator: &str, value: String, open_groups: &mut usize) {
+    parts.push(operator.to_string());
+    parts.extend((0..*open_groups).map(|_| "(".to_string()));
+    *open_groups = 0;
+    parts.push(value);
+}
+
 // Helper function to add dice groups, reducing duplication
 fn add_dice_group(
     result: &mut RollResult,
@@ -587,16 +973,28 @@ fn add_dice_group(
     result.dice_groups.push(dice_group);
 }
 
-// Simple expression evaluator with LEFT-TO-RIGHT evaluation (no PEMDAS)
-fn evaluate_expression(parts: &[String]) -> Result<i32> {
+// Sum dice in i64 so a big pool of d1000000s reports an error rather than
+// wrapping
+fn sum_rolls(rolls: &[i32]) -> Result<i32> {
+    let sum: i64 = rolls.iter().map(|&roll| i64::from(roll)).sum();
+    i32::try_from(sum).map_err(|_| DiceError::RollLimitExceeded("Result is too large".into()))
+}
+
+// Simple expression evaluator with LEFT-TO-RIGHT evaluation, or operator
+// precedence and parentheses for `pemdas` rolls.
+// Works in i64 so intermediate steps like `1d1000000 * 5000 / 100` don't
+// overflow; only the final result has to fit an i32.
+fn evaluate_expression(parts: &[String], pemdas: bool) -> Result<i32> {
     if parts.len() == 1 {
-        return Ok(parts[0].parse()?);
+        return parts[0]
+            .parse()
+            .map_err(|_| DiceError::ParseError(format!("Invalid token: {}", parts[0])));
     }
 
     // Convert to tokens
     let mut tokens = Vec::new();
     for part in parts {
-        if let Ok(num) = part.parse::<i32>() {
+        if let Ok(num) = part.parse::<i64>() {
             tokens.push(Token::Number(num));
         } else {
             match part.as_str() {
@@ -604,23 +1002,34 @@ fn evaluate_expression(parts: &[String]) -> Result<i32> {
                 "-" => tokens.push(Token::Minus),
                 "*" => tokens.push(Token::Multiply),
                 "/" => tokens.push(Token::Divide),
-                _ => return Err(anyhow!("Invalid token: {}", part)),
+                "(" => tokens.push(Token::OpenGroup),
+                ")" => tokens.push(Token::CloseGroup),
+                _ => return Err(DiceError::ParseError(format!("Invalid token: {}", part))),
             }
         }
     }
 
+    if pemdas {
+        let result = evaluate_with_precedence(&tokens)?;
+        return i32::try_from(result)
+            .map_err(|_| DiceError::RollLimitExceeded("Result is too large".into()));
+    }
+
     // Evaluate LEFT-TO-RIGHT (no precedence rules)
     apply_left_to_right_operations(&mut tokens)?;
 
     // Should have only one number left
     if tokens.len() == 1 {
         if let Token::Number(result) = tokens[0] {
-            Ok(result)
+            i32::try_from(result)
+                .map_err(|_| DiceError::RollLimitExceeded("Result is too large".into()))
         } else {
-            Err(anyhow!("Invalid expression result"))
+            Err(DiceError::ParseError("Invalid expression result".into()))
         }
     } else {
-        Err(anyhow!("Expression did not evaluate to a single value"))
+        Err(DiceError::ParseError(
+            "Expression did not evaluate to a single value".into(),
+        ))
     }
 }
 
@@ -643,49 +1052,111 @@ fn apply_left_to_right_operations(tokens: &mut Vec<Token>) -> Result<()> {
         if let Some(op_pos) = operator_pos {
             // We need at least one number before and after the operator
             if op_pos == 0 || op_pos >= tokens.len() - 1 {
-                return Err(anyhow!("Invalid expression structure"));
+                return Err(DiceError::ParseError("Invalid expression structure".into()));
             }
 
             // Get the left operand, operator, and right operand
             if let (Token::Number(left), op, Token::Number(right)) =
                 (&tokens[op_pos - 1], &tokens[op_pos], &tokens[op_pos + 1])
             {
-                let result = match op {
-                    Token::Plus => left + right,
-                    Token::Minus => left - right,
-                    Token::Multiply => left * right,
-                    Token::Divide => {
-                        if *right == 0 {
-                            return Err(anyhow!("Cannot divide by zero"));
-                        }
-                        left / right
-                    }
-                    _ => return Err(anyhow!("Unexpected token type")),
-                };
+                let result = apply_operator(*left, op, *right)?;
 
                 // Replace the three tokens (left operand, operator, right operand) with the result
                 tokens[op_pos - 1] = Token::Number(result);
                 tokens.remove(op_pos + 1); // Remove right operand
                 tokens.remove(op_pos); // Remove operator
             } else {
-                return Err(anyhow!("Invalid operands for operator"));
+                return Err(DiceError::ParseError(
+                    "Invalid operands for operator".into(),
+                ));
             }
         } else {
             // No more operators found but we still have multiple tokens
-            return Err(anyhow!("Expression contains non-operator tokens"));
+            return Err(DiceError::ParseError(
+                "Expression contains non-operator tokens".into(),
+            ));
         }
     }
 
     Ok(())
 }
 
+// Standard precedence for `pemdas` rolls: parentheses first, then * and /,
+// then + and -, each left to right.  Shunting-yard, so deeply nested
+// parentheses don't recurse.
+fn evaluate_with_precedence(tokens: &[Token]) -> Result<i64> {
+    let precedence = |token: &Token| match token {
+        Token::Multiply | Token::Divide => 2,
+        Token::Plus | Token::Minus => 1,
+        _ => 0,
+    };
+    let invalid = || DiceError::ParseError("Invalid expression structure".into());
+    let reduce = |values: &mut Vec<i64>, op: &Token| -> Result<()> {
+        let right = values.pop().ok_or_else(invalid)?;
+        let left = values.pop().ok_or_else(invalid)?;
+        values.push(apply_operator(left, op, right)?);
+        Ok(())
+    };
+
+    let mut values = Vec::new();
+    let mut operators: Vec<Token> = Vec::new();
+    for token in tokens {
+        match token {
+            Token::Number(value) => values.push(*value),
+            Token::OpenGroup => operators.push(Token::OpenGroup),
+            Token::CloseGroup => loop {
+                match operators.pop() {
+                    Some(Token::OpenGroup) => break,
+                    Some(op) => reduce(&mut values, &op)?,
+                    None => return Err(invalid()),
+                }
+            },
+            op => {
+                while let Some(top) = operators.pop_if(|top| precedence(top) >= precedence(op)) {
+                    reduce(&mut values, &top)?;
+                }
+                operators.push(op.clone());
+            }
+        }
+    }
+    while let Some(op) = operators.pop() {
+        if matches!(op, Token::OpenGroup) {
+            return Err(invalid());
+        }
+        reduce(&mut values, &op)?;
+    }
+
+    match values[..] {
+        [value] => Ok(value),
+        _ => Err(invalid()),
+    }
+}
+
+fn apply_operator(left: i64, op: &Token, right: i64) -> Result<i64> {
+    match op {
+        Token::Plus => left.checked_add(right),
+        Token::Minus => left.checked_sub(right),
+        Token::Multiply => left.checked_mul(right),
+        Token::Divide => {
+            if right == 0 {
+                return Err(DiceError::DivisionByZero { by_dice: false });
+            }
+            left.checked_div(right)
+        }
+        _ => return Err(DiceError::ParseError("Unexpected token type".into())),
+    }
+    .ok_or_else(|| DiceError::RollLimitExceeded("Result is too large".into()))
+}
+
 #[derive(Debug, Clone)]
 enum Token {
-    Number(i32),
+    Number(i64),
     Plus,
     Minus,
     Multiply,
     Divide,
+    OpenGroup,
+    CloseGroup,
 }
 
 // Special system modifiers applied after math
@@ -876,6 +1347,12 @@ fn apply_special_system_modifiers(
                 apply_plot_die_conversion(result)?;
                 has_special_system = true;
             }
+            Modifier::Matches => {
+                result.matches = Some(MatchResult::from_rolls(&result.kept_rolls));
+            }
+            Modifier::Dcc => {
+                apply_dcc_mechanics(result, dice.sides)?;
+            }
 
             // Skip mathematical modifiers here - they're handled by target processing or post-target processing
             Modifier::Add(_)
@@ -932,8 +1409,14 @@ fn apply_special_system_modifiers(
     Ok(())
 }
 
-// Helper function to sort result rolls
-fn sort_result_rolls(result: &mut RollResult) {
+/// Sort kept dice and each dice group high to low for display, unless the
+/// result has `preserve_order` set (the `ul` flag, or a system whose dice
+/// order carries meaning such as the Wrath & Glory wrath dice)
+pub fn sort_result_rolls(result: &mut RollResult) {
+    if result.preserve_order {
+        return;
+    }
+
     // Sort kept_rolls
     if !result.kept_rolls.is_empty() {
         result.kept_rolls.sort_by(|a, b| b.cmp(a)); // Sort descending by default
@@ -1081,6 +1564,8 @@ fn count_wrath_glory_successes(
 
         // Set Wrath & Glory specific fields
         if !wrath_dice_values.is_empty() {
+            // The wrath dice are the first N, so keep them there
+            result.preserve_order = true;
             result.wng_wrath_die = Some(wrath_dice_values[0]); // Keep for backwards compatibility
             result.wng_wrath_dice = Some(wrath_dice_values.clone()); // Store all wrath dice
         }
@@ -1210,6 +1695,52 @@ fn convert_to_godbound_damage(value: i32) -> i32 {
     }
 }
 
+// Most times `te#` rolls the pool again
+const MAX_TOTAL_EXPLOSIONS: u32 = 20;
+
+// `te#`: while the latest pool totals at least `threshold`, roll the whole
+// pool again and add it.  Unlike `e`, this looks at the sum, not each die.
+fn explode_total(
+    result: &mut RollResult,
+    rng: &mut impl Rng,
+    threshold: u32,
+    dice: &DiceRoll,
+) -> Result<()> {
+    // The lowest total is one per die, so anything up to that always explodes
+    if threshold <= dice.count {
+        return Err(DiceError::ValidationError(format!(
+            "te{} would always explode: it must be more than {}, the lowest total of {}d{}",
+            threshold, dice.count, dice.count, dice.sides
+        )));
+    }
+
+    let threshold = i64::from(threshold);
+    let mut pool_total: i64 = result.individual_rolls.iter().map(|&r| i64::from(r)).sum();
+    let mut explosions = 0;
+    while pool_total >= threshold && explosions < MAX_TOTAL_EXPLOSIONS {
+        pool_total = 0;
+        for _ in 0..dice.count {
+            let roll = rng.random_range(1..=dice.sides as i32);
+            result.individual_rolls.push(roll);
+            pool_total += i64::from(roll);
+        }
+        explosions += 1;
+    }
+
+    if explosions > 0 {
+        result.notes.push(format!(
+            "Total exploded {explosions} time{} (te{threshold})",
+            if explosions == 1 { "" } else { "s" }
+        ));
+    }
+    if explosions >= MAX_TOTAL_EXPLOSIONS && pool_total >= threshold {
+        result.notes.push(format!(
+            "Maximum total explosions reached ({MAX_TOTAL_EXPLOSIONS})"
+        ));
+    }
+    Ok(())
+}
+
 fn explode_dice(
     result: &mut RollResult,
     rng: &mut impl Rng,
@@ -1269,6 +1800,103 @@ fn explode_dice(
     Ok(())
 }
 
+// Most extra dice `ke`/`kp` roll for one pool, matching indefinite explosions
+const MAX_CHAINED_EXPLOSIONS: usize = 100;
+
+// The face `ke`/`kp` explode on, rejecting thresholds every roll would meet
+fn chained_explode_on(prefix: &str, threshold: Option<u32>, dice_sides: u32) -> Result<i32> {
+    let explode_on = threshold.unwrap_or(dice_sides);
+    if explode_on <= 1 {
+        return Err(DiceError::ValidationError(format!(
+            "{}{} would always explode on a d{}",
+            prefix,
+            threshold.map(|t| t.to_string()).unwrap_or_default(),
+            dice_sides
+        )));
+    }
+    Ok(explode_on as i32)
+}
+
+// Compounding explosions (`ke`): each extra roll is added to the die that
+// exploded, so the die is shown as one value
+fn compound_dice(
+    result: &mut RollResult,
+    rng: &mut impl Rng,
+    threshold: Option<u32>,
+    dice_sides: u32,
+) -> Result<()> {
+    let explode_on = chained_explode_on("ke", threshold, dice_sides)?;
+    let mut explosions = 0;
+    let mut compounded = Vec::new();
+
+    for die in result.individual_rolls.iter_mut() {
+        let mut last = *die;
+        let mut parts = vec![last];
+        while last >= explode_on && explosions < MAX_CHAINED_EXPLOSIONS {
+            last = rng.random_range(1..=dice_sides as i32);
+            parts.push(last);
+            *die = die.saturating_add(last);
+            explosions += 1;
+        }
+        if parts.len() > 1 {
+            let parts = parts.iter().map(i32::to_string).collect::<Vec<_>>();
+            compounded.push(format!("{} ({})", die, parts.join("+")));
+        }
+    }
+
+    if !compounded.is_empty() {
+        result
+            .notes
+            .push(format!("Compounded: {}", compounded.join(", ")));
+    }
+    if explosions >= MAX_CHAINED_EXPLOSIONS {
+        result.notes.push(format!(
+            "Maximum explosions reached ({MAX_CHAINED_EXPLOSIONS})"
+        ));
+    }
+    Ok(())
+}
+
+// Penetrating explosions (`kp`): every extra die is one lower than rolled, but
+// keeps exploding on its unmodified roll
+fn penetrate_dice(
+    result: &mut RollResult,
+    rng: &mut impl Rng,
+    threshold: Option<u32>,
+    dice_sides: u32,
+) -> Result<()> {
+    let explode_on = chained_explode_on("kp", threshold, dice_sides)?;
+    let original_dice_count = result.individual_rolls.len();
+    let mut explosions = 0;
+
+    for i in 0..original_dice_count {
+        let mut last = result.individual_rolls[i];
+        while last >= explode_on && explosions < MAX_CHAINED_EXPLOSIONS {
+            last = rng.random_range(1..=dice_sides as i32);
+            result.individual_rolls.push(last - 1);
+            explosions += 1;
+        }
+    }
+
+    if explosions > 0 {
+        result.notes.push(format!(
+            "{} penetrating {} (-1 each)",
+            explosions,
+            if explosions == 1 {
+                "explosion"
+            } else {
+                "explosions"
+            }
+        ));
+    }
+    if explosions >= MAX_CHAINED_EXPLOSIONS {
+        result.notes.push(format!(
+            "Maximum explosions reached ({MAX_CHAINED_EXPLOSIONS})"
+        ));
+    }
+    Ok(())
+}
+
 // Helper function for explosion notes
 fn add_explosion_notes(
     result: &mut RollResult,
@@ -1368,7 +1996,7 @@ fn keep_dice(result: &mut RollResult, count: usize, keep_low: bool) -> Result<()
 
     // Validate that count > 0
     if count == 0 {
-        return Err(anyhow!("Cannot keep 0 dice"));
+        return Err(DiceError::ValidationError("Cannot keep 0 dice".into()));
     }
 
     let mut indexed_rolls: Vec<(usize, i32)> = result
@@ -1401,21 +2029,179 @@ fn keep_dice(result: &mut RollResult, count: usize, keep_low: bool) -> Result<()
     Ok(())
 }
 
-fn reroll_dice(
-    result: &mut RollResult,
-    rng: &mut impl Rng,
-    threshold: u32,
-    dice_sides: u32,
-    indefinite: bool,
-) -> Result<()> {
-    let mut total_rerolls = 0;
-    let max_total_rerolls = 100;
+/// `maxs#`: limit the roll's successes to the cap and note any overflow,
+/// for systems that cap successes at a skill rating
+fn apply_success_cap(result: &mut RollResult, dice: &DiceRoll) -> Result<()> {
+    let Some(cap) = dice.modifiers.iter().find_map(|modifier| match modifier {
+        Modifier::MaxSuccesses(cap) => Some(*cap as i32),
+        _ => None,
+    }) else {
+        return Ok(());
+    };
 
-    for i in 0..result.individual_rolls.len() {
-        let mut rerolls_for_this_die = 0;
-        let max_rerolls_per_die = if indefinite { 100 } else { 1 };
+    let successes = result.successes.ok_or_else(|| {
+        DiceError::ValidationError("maxs needs a success target, e.g. `10d10 t7 maxs5`".into())
+    })?;
+    if successes > cap {
+        result.successes = Some(cap);
+        result.notes.push(format!(
+            "Successes capped at {cap} ({} overflow)",
+            successes - cap
+        ));
+    }
 
-        while result.individual_rolls[i] <= threshold as i32
+    Ok(())
+}
+
+/// `bands[a,b]`: classify the final total — or the successes, when counted —
+/// into outcome bands.  Each threshold is the highest value of its band, so
+/// `bands[6,9]` reads 6- as a failure, 7-9 as a partial success and 10+ as a
+/// success.  The outcome goes in `fitd_outcome`, so clocks tick from it like
+/// any FitD roll.
+fn apply_bands(result: &mut RollResult, dice: &DiceRoll) -> Result<()> {
+    let Some(thresholds) = dice.modifiers.iter().find_map(|modifier| match modifier {
+        Modifier::Bands(thresholds) => Some(thresholds),
+        _ => None,
+    }) else {
+        return Ok(());
+    };
+
+    if result.fitd_outcome.is_some() {
+        return Err(DiceError::ValidationError(
+            "bands can't be combined with a system that has its own outcomes".into(),
+        ));
+    }
+
+    let outcomes: &[&str] = match thresholds.len() {
+        1 => &["FAILURE", "SUCCESS"],
+        2 => &["FAILURE", "PARTIAL SUCCESS", "SUCCESS"],
+        _ => &["FAILURE", "PARTIAL SUCCESS", "SUCCESS", "CRITICAL SUCCESS"],
+    };
+    let value = result.successes.unwrap_or(result.total);
+    let band = thresholds
+        .iter()
+        .take_while(|&&threshold| value > threshold)
+        .count();
+    let outcome = outcomes.get(band).ok_or_else(|| {
+        DiceError::ValidationError(format!(
+            "bands takes at most {} thresholds",
+            outcomes.len() - 1
+        ))
+    })?;
+    result.fitd_outcome = Some(outcome.to_string());
+
+    Ok(())
+}
+
+/// `t[90,70,50]`: the highest tier the final total reaches.  Tiers are named
+/// from the top, so the best is always a critical and the lowest a plain
+/// success; below it is a failure.  Like bands, the tier goes in
+/// `fitd_outcome`, and a note lists the ladder.
+fn apply_target_ladder(result: &mut RollResult, dice: &DiceRoll) -> Result<()> {
+    let Some(thresholds) = dice.modifiers.iter().find_map(|modifier| match modifier {
+        Modifier::TargetLadder(thresholds) => Some(thresholds),
+        _ => None,
+    }) else {
+        return Ok(());
+    };
+
+    if result.fitd_outcome.is_some() || result.successes.is_some() {
+        return Err(DiceError::ValidationError(
+            "Target ladders can't be combined with bands, success counting or a system that has its own outcomes".into(),
+        ));
+    }
+
+    let names: &[&str] = match thresholds.len() {
+        2 => &["CRITICAL SUCCESS", "SUCCESS"],
+        3 => &["CRITICAL SUCCESS", "SPECIAL SUCCESS", "SUCCESS"],
+        _ => &[
+            "CRITICAL SUCCESS",
+            "EXTREME SUCCESS",
+            "SPECIAL SUCCESS",
+            "SUCCESS",
+        ],
+    };
+    let tier = thresholds
+        .iter()
+        .position(|&threshold| i64::from(result.total) >= i64::from(threshold));
+    result.fitd_outcome = Some(
+        tier.and_then(|tier| names.get(tier))
+            .unwrap_or(&"FAILURE")
+            .to_string(),
+    );
+    result.notes.push(format!(
+        "Ladder: {}",
+        thresholds
+            .iter()
+            .zip(names)
+            .map(|(threshold, name)| format!("{name} {threshold}+"))
+            .collect::<Vec<_>>()
+            .join(" · ")
+    ));
+
+    Ok(())
+}
+
+/// `rfail`: reroll every die that missed the roll's success target, once.
+/// The target comes from the roll's `t#` / `tl#` modifier, so successes are
+/// counted on the new dice; the note lists each failed die and its reroll.
+fn reroll_failed_dice(result: &mut RollResult, rng: &mut impl Rng, dice: &DiceRoll) -> Result<()> {
+    let failed = dice
+        .modifiers
+        .iter()
+        .find_map(|modifier| match modifier {
+            Modifier::Target(target) | Modifier::TargetWithDoubleSuccess(target, _) => {
+                let target = *target as i32;
+                Some(Box::new(move |roll: i32| roll < target) as Box<dyn Fn(i32) -> bool>)
+            }
+            Modifier::TargetLower(target) | Modifier::TargetLowerWithDoubleSuccess(target, _) => {
+                let target = *target as i32;
+                Some(Box::new(move |roll: i32| roll > target) as Box<dyn Fn(i32) -> bool>)
+            }
+            _ => None,
+        })
+        .ok_or_else(|| {
+            DiceError::ValidationError("rfail needs a success target, e.g. `8d10 t8 rfail`".into())
+        })?;
+
+    let mut original = Vec::new();
+    let mut rerolled = Vec::new();
+    for roll in result.individual_rolls.iter_mut() {
+        if failed(*roll) {
+            original.push(roll.to_string());
+            *roll = rng.random_range(1..=dice.sides as i32);
+            rerolled.push(roll.to_string());
+        }
+    }
+
+    if !original.is_empty() {
+        let dice_word = if original.len() == 1 { "die" } else { "dice" };
+        result.notes.push(format!(
+            "Rerolled {} failed {dice_word}: [{}] → [{}]",
+            original.len(),
+            original.join(", "),
+            rerolled.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+fn reroll_dice(
+    result: &mut RollResult,
+    rng: &mut impl Rng,
+    threshold: u32,
+    dice_sides: u32,
+    indefinite: bool,
+) -> Result<()> {
+    let mut total_rerolls = 0;
+    let max_total_rerolls = 100;
+
+    for i in 0..result.individual_rolls.len() {
+        let mut rerolls_for_this_die = 0;
+        let max_rerolls_per_die = if indefinite { 100 } else { 1 };
+
+        while result.individual_rolls[i] <= threshold as i32
             && rerolls_for_this_die < max_rerolls_per_die
             && total_rerolls < max_total_rerolls
         {
@@ -1527,7 +2313,12 @@ fn apply_fudge_conversion(result: &mut RollResult) -> Result<()> {
             1 => ("-", -1), // Minus
             2 => (" ", 0),  // Blank
             3 => ("+", 1),  // Plus
-            _ => return Err(anyhow!("Invalid Fudge die value: {}", roll)),
+            _ => {
+                return Err(DiceError::ParseError(format!(
+                    "Invalid Fudge die value: {}",
+                    roll
+                )));
+            }
         };
         symbols.push(symbol.to_string());
         fudge_total += value;
@@ -1569,7 +2360,7 @@ fn apply_mathematical_modifiers_to_successes_from_slice(
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 if let Some(ref mut successes) = result.successes {
                     *successes /= value;
@@ -1598,6 +2389,11 @@ fn handle_savage_worlds_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollR
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -1612,6 +2408,17 @@ fn handle_savage_worlds_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollR
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
     };
 
     // Find the Savage Worlds modifier
@@ -1625,7 +2432,7 @@ fn handle_savage_worlds_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollR
                 None
             }
         })
-        .ok_or_else(|| anyhow!("Expected Savage Worlds modifier"))?;
+        .ok_or_else(|| DiceError::ParseError("Expected Savage Worlds modifier".into()))?;
 
     // Roll trait die (exploding on max)
     let mut trait_rolls = vec![rng.random_range(1..=trait_sides as i32)];
@@ -1689,7 +2496,7 @@ fn handle_savage_worlds_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollR
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 result.total /= value;
             }
@@ -1788,6 +2595,11 @@ fn handle_d6_system_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResul
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -1802,6 +2614,17 @@ fn handle_d6_system_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResul
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
     };
 
     // Find the D6 System modifier
@@ -1815,7 +2638,7 @@ fn handle_d6_system_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResul
                 None
             }
         })
-        .ok_or_else(|| anyhow!("Expected D6 System modifier"))?;
+        .ok_or_else(|| DiceError::ParseError("Expected D6 System modifier".into()))?;
 
     // Roll base dice (non-exploding)
     let mut base_rolls = Vec::new();
@@ -1879,7 +2702,7 @@ fn handle_d6_system_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResul
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 result.total /= value;
             }
@@ -1956,6 +2779,11 @@ fn handle_marvel_multiverse_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<R
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -1970,6 +2798,17 @@ fn handle_marvel_multiverse_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<R
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
     };
 
     // Find the Marvel Multiverse modifier
@@ -2163,7 +3002,7 @@ fn handle_marvel_multiverse_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<R
             Modifier::Multiply(value) => result.total *= value,
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 result.total /= value;
             }
@@ -2182,7 +3021,9 @@ fn handle_marvel_multiverse_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<R
 fn apply_cyberpunk_red_mechanics(result: &mut RollResult, rng: &mut impl Rng) -> Result<()> {
     // CPR only works with exactly 1d10
     if result.individual_rolls.len() != 1 {
-        return Err(anyhow!("Cyberpunk Red mechanics only work with 1d10"));
+        return Err(DiceError::ValidationError(
+            "Cyberpunk Red mechanics only work with 1d10".into(),
+        ));
     }
 
     let original_roll = result.individual_rolls[0];
@@ -2276,7 +3117,7 @@ fn apply_mathematical_modifiers_to_cpr_total(
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 result.total /= value;
             }
@@ -2295,7 +3136,9 @@ fn apply_mathematical_modifiers_to_cpr_total(
 fn apply_witcher_mechanics(result: &mut RollResult, rng: &mut impl Rng) -> Result<()> {
     // Witcher only works with exactly 1d10
     if result.individual_rolls.len() != 1 {
-        return Err(anyhow!("Witcher mechanics only work with 1d10"));
+        return Err(DiceError::ValidationError(
+            "Witcher mechanics only work with 1d10".into(),
+        ));
     }
 
     let original_roll = result.individual_rolls[0];
@@ -2413,9 +3256,42 @@ fn apply_witcher_mechanics(result: &mut RollResult, rng: &mut impl Rng) -> Resul
     Ok(())
 }
 
+// Dungeon Crawl Classics: the die must be on the dice chain, and a natural 1
+// is a fumble on an attack or a failed spell check
+fn apply_dcc_mechanics(result: &mut RollResult, sides: u32) -> Result<()> {
+    if !DCC_DICE_CHAIN.contains(&sides) {
+        return Err(DiceError::ValidationError(format!(
+            "d{sides} isn't on the DCC dice chain (d3, d4, d5, d6, d7, d8, d10, d12, d14, d16, d20, d24, d30)"
+        )));
+    }
+
+    let ones = result
+        .individual_rolls
+        .iter()
+        .filter(|&&roll| roll == 1)
+        .count();
+    if ones > 0 {
+        result.notes.push(format!(
+            "💀 **FUMBLE** (Natural 1{}): on an attack, roll on the fumble table",
+            if ones > 1 {
+                format!(" x{ones}")
+            } else {
+                String::new()
+            }
+        ));
+        result.notes.push(
+            "🔥 On a spell check the spell fails and may misfire or corrupt; any spellburn is lost"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
 fn apply_cypher_system_mechanics(result: &mut RollResult, level: u32) -> Result<()> {
     if result.individual_rolls.is_empty() {
-        return Err(anyhow!("No dice rolled for Cypher System"));
+        return Err(DiceError::ValidationError(
+            "No dice rolled for Cypher System".into(),
+        ));
     }
 
     let roll = result.individual_rolls[0];
@@ -2480,6 +3356,11 @@ pub fn handle_brave_new_world_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -2494,6 +3375,17 @@ pub fn handle_brave_new_world_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
     };
 
     let pool_size = dice.count;
@@ -2502,7 +3394,7 @@ pub fn handle_brave_new_world_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result
     dice.modifiers
         .iter()
         .find(|m| matches!(m, Modifier::BraveNewWorld(_)))
-        .ok_or_else(|| anyhow!("Expected Brave New World modifier"))?;
+        .ok_or_else(|| DiceError::ParseError("Expected Brave New World modifier".into()))?;
 
     // Roll the initial dice pool
     let mut all_results = Vec::new();
@@ -2582,7 +3474,7 @@ pub fn handle_brave_new_world_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 result.total /= value;
             }
@@ -2598,7 +3490,17 @@ pub fn handle_brave_new_world_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result
     Ok(result)
 }
 
-fn handle_conan_skill_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResult> {
+// Most d20s in one 2d20 check: two, plus up to three bought with momentum
+const MAX_2D20_DICE: u32 = 5;
+
+// 2d20 system check (Conan, Star Trek Adventures, Fallout, Dune, Infinity).
+// With a target number each d20 at or under it is a success and each at or
+// under the focus (a natural 1 without one) scores two; a difficulty turns
+// the successes beyond it into momentum.  Faces in the complication range (a
+// natural 20 by default) are complications.  Conan skill rolls without a
+// target number keep their old reading: one success per d20, plus any combat
+// dice, and no complication note.
+fn handle_2d20_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResult> {
     let mut result = RollResult {
         individual_rolls: Vec::new(),
         kept_rolls: Vec::new(),
@@ -2615,6 +3517,11 @@ fn handle_conan_skill_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollRes
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -2629,26 +3536,83 @@ fn handle_conan_skill_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollRes
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
     };
 
-    // Find the ConanSkill modifier to get dice count
-    let dice_count = dice
-        .modifiers
-        .iter()
-        .find_map(|m| {
-            if let Modifier::ConanSkill(count) = m {
-                Some(*count)
-            } else {
-                None
-            }
-        })
-        .ok_or_else(|| anyhow!("Expected ConanSkill modifier"))?;
+    let conan_dice = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::ConanSkill(count) => Some(*count),
+        _ => None,
+    });
+    let target = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::TargetNumber(target) => Some(*target),
+        _ => None,
+    });
+    let focus = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::Focus(focus) => Some(*focus),
+        _ => None,
+    });
+    let difficulty = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::Difficulty(difficulty) => Some(*difficulty),
+        _ => None,
+    });
+    let complication_range = dice.modifiers.iter().find_map(|m| match m {
+        Modifier::Complication(range) => Some(*range),
+        _ => None,
+    });
+
+    if target.is_none()
+        && (conan_dice.is_none()
+            || focus.is_some()
+            || difficulty.is_some()
+            || complication_range.is_some())
+    {
+        return Err(DiceError::ValidationError(
+            "2d20 checks need a target number, e.g. `2d20 tn12 focus3`".into(),
+        ));
+    }
+    if let (Some(target), Some(focus)) = (target, focus)
+        && focus > target
+    {
+        return Err(DiceError::ValidationError(format!(
+            "A 2d20 focus ({focus}) can't be above the target number ({target})"
+        )));
+    }
+    let dice_count = match conan_dice {
+        Some(count) => count,
+        None if dice.sides == 20 && (1..=MAX_2D20_DICE).contains(&dice.count) => dice.count,
+        None => {
+            return Err(DiceError::ValidationError(format!(
+                "2d20 checks roll 1-{MAX_2D20_DICE} d20s, e.g. `3d20 tn14`"
+            )));
+        }
+    };
 
     // 1. Roll the base skill dice (d20s)
     for _ in 0..dice_count {
         result.individual_rolls.push(rng.random_range(1..=20));
     }
 
+    if let Some(target) = target {
+        return finish_2d20_check(
+            result,
+            &dice,
+            target,
+            focus.unwrap_or(1),
+            difficulty,
+            complication_range.unwrap_or(1),
+        );
+    }
+
     // Count successes for skill dice (simple approach: count = successes)
     let skill_successes = result.individual_rolls.len() as i32;
     result.successes = Some(skill_successes);
@@ -2671,7 +3635,7 @@ fn handle_conan_skill_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollRes
     for modifier in &dice.modifiers {
         if let Modifier::AddDice(additional_dice) = modifier {
             // Roll the additional dice
-            let additional_result = roll_dice(additional_dice.clone())?;
+            let additional_result = roll_dice_with_rng(additional_dice.clone(), rng)?;
 
             // Check if these are d6 dice that should use Conan combat interpretation
             if additional_dice.sides == 6 {
@@ -2744,7 +3708,7 @@ fn handle_conan_skill_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollRes
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 result.total /= value;
             }
@@ -2770,6 +3734,172 @@ fn handle_conan_skill_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollRes
     Ok(result)
 }
 
+// Count a 2d20 check's successes against its target number and focus, and
+// compare them with its difficulty, noting any dice in the complication
+// range.  Only `+N` / `-N` bonus successes can be combined with the check.
+fn finish_2d20_check(
+    mut result: RollResult,
+    dice: &DiceRoll,
+    target: u32,
+    focus: u32,
+    difficulty: Option<u32>,
+    complication_range: u32,
+) -> Result<RollResult> {
+    let mut successes: i32 = result
+        .individual_rolls
+        .iter()
+        .map(|&roll| match roll as u32 {
+            roll if roll <= focus => 2,
+            roll if roll <= target => 1,
+            _ => 0,
+        })
+        .sum();
+
+    for modifier in &dice.modifiers {
+        match modifier {
+            Modifier::ConanSkill(_)
+            | Modifier::TargetNumber(_)
+            | Modifier::Focus(_)
+            | Modifier::Difficulty(_)
+            | Modifier::Complication(_) => {}
+            Modifier::Add(value) => successes = successes.saturating_add(*value),
+            Modifier::Subtract(value) => successes = successes.saturating_sub(*value).max(0),
+            Modifier::Multiply(_)
+            | Modifier::Divide(_)
+            | Modifier::Explode(_)
+            | Modifier::ExplodeIndefinite(_)
+            | Modifier::ExplodeTotal(_)
+            | Modifier::Compound(_)
+            | Modifier::Penetrate(_)
+            | Modifier::Drop(_)
+            | Modifier::KeepHigh(_)
+            | Modifier::KeepLow(_)
+            | Modifier::KeepMiddle(_)
+            | Modifier::Reroll(_)
+            | Modifier::RerollIndefinite(_)
+            | Modifier::RerollFailed
+            | Modifier::MaxSuccesses(_)
+            | Modifier::Target(_)
+            | Modifier::RerollGreater(_)
+            | Modifier::RerollGreaterIndefinite(_)
+            | Modifier::TargetLower(_)
+            | Modifier::TargetWithDoubleSuccess(_, _)
+            | Modifier::TargetLowerWithDoubleSuccess(_, _)
+            | Modifier::Failure(_)
+            | Modifier::Botch(_)
+            | Modifier::Cancel
+            | Modifier::AddDice(_)
+            | Modifier::SubtractDice(_)
+            | Modifier::MultiplyDice(_)
+            | Modifier::DivideDice(_)
+            | Modifier::OpenGroup
+            | Modifier::CloseGroup
+            | Modifier::WrathGlory(_, _, _)
+            | Modifier::Godbound(_)
+            | Modifier::HeroSystem(_)
+            | Modifier::Fudge
+            | Modifier::DarkHeresy
+            | Modifier::SavageWorlds(_)
+            | Modifier::D6System(_, _)
+            | Modifier::Shadowrun(_)
+            | Modifier::MarvelMultiverse(_, _)
+            | Modifier::CyberpunkRed
+            | Modifier::Witcher
+            | Modifier::CypherSystem(_)
+            | Modifier::BraveNewWorld(_)
+            | Modifier::ConanCombat(_)
+            | Modifier::Silhouette(_)
+            | Modifier::VampireMasquerade5(_, _)
+            | Modifier::LaserFeelings(_, _, _)
+            | Modifier::Alien
+            | Modifier::AlienStress(_)
+            | Modifier::ForgedDark
+            | Modifier::ForgedDarkZero
+            | Modifier::Daggerheart
+            | Modifier::WildWorlds(_)
+            | Modifier::Mothership(_, _)
+            | Modifier::MothershipPanic(_)
+            | Modifier::MutantsMasterminds
+            | Modifier::PlotDie
+            | Modifier::Genesys(_)
+            | Modifier::L5r(_)
+            | Modifier::Ironsworn
+            | Modifier::Oracle(_)
+            | Modifier::CoC(_, _)
+            | Modifier::Wfrp(_)
+            | Modifier::Dragonbane(_, _, _)
+            | Modifier::Gurps(_)
+            | Modifier::YearZero(_)
+            | Modifier::BurningWheel(_)
+            | Modifier::Matches
+            | Modifier::Cortex
+            | Modifier::Bands(_)
+            | Modifier::TargetLadder(_)
+            | Modifier::Dcc => {
+                return Err(DiceError::ValidationError(
+                    "2d20 checks with a target number can only add or subtract successes".into(),
+                ));
+            }
+        }
+    }
+    let complication_floor = 21 - complication_range as i32;
+    let complications = result
+        .individual_rolls
+        .iter()
+        .filter(|&&roll| roll >= complication_floor)
+        .count();
+
+    result.kept_rolls = result.individual_rolls.clone();
+    result.dice_groups.push(DiceGroup {
+        _description: format!("{}d20", result.individual_rolls.len()),
+        rolls: result.individual_rolls.clone(),
+        dropped_rolls: Vec::new(),
+        modifier_type: "base".to_string(),
+    });
+    result.successes = Some(successes);
+    result.total = successes;
+
+    if let Some(difficulty) = difficulty.map(|difficulty| difficulty as i32) {
+        if successes >= difficulty {
+            result.notes.push(format!(
+                "✅ **SUCCESS** at difficulty {difficulty}, **{}** momentum",
+                successes - difficulty
+            ));
+        } else {
+            result.notes.push(format!(
+                "❌ **FAILURE** at difficulty {difficulty}, {} more success{} needed",
+                difficulty - successes,
+                if difficulty - successes == 1 {
+                    ""
+                } else {
+                    "es"
+                }
+            ));
+        }
+    }
+    result
+        .notes
+        .extend(complication_note(complications, complication_range));
+
+    Ok(result)
+}
+
+// "⚠️ 2 complications (rolled 19-20)" for dice in a 2d20 complication range
+fn complication_note(complications: usize, range: u32) -> Option<String> {
+    if complications == 0 {
+        return None;
+    }
+    let faces = if range == 1 {
+        "20".to_string()
+    } else {
+        format!("{}-20", 21 - range)
+    };
+    Some(format!(
+        "⚠️ {complications} complication{} (rolled {faces})",
+        if complications == 1 { "" } else { "s" }
+    ))
+}
+
 // Helper function to apply Conan combat dice interpretation
 fn apply_conan_combat_interpretation(rolls: &[i32]) -> i32 {
     let mut damage = 0;
@@ -2807,6 +3937,11 @@ fn handle_conan_combat_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollRe
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -2821,6 +3956,17 @@ fn handle_conan_combat_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollRe
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
     };
 
     // Find the ConanCombat modifier to get dice count
@@ -2834,7 +3980,7 @@ fn handle_conan_combat_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollRe
                 None
             }
         })
-        .ok_or_else(|| anyhow!("Expected ConanCombat modifier"))?;
+        .ok_or_else(|| DiceError::ParseError("Expected ConanCombat modifier".into()))?;
 
     // Roll the combat dice (d6s)
     for _ in 0..dice_count {
@@ -2884,7 +4030,7 @@ fn handle_conan_combat_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollRe
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 result.total /= value;
             }
@@ -2913,7 +4059,7 @@ fn handle_silhouette_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResu
                 None
             }
         })
-        .ok_or_else(|| anyhow!("Expected Silhouette modifier"))?;
+        .ok_or_else(|| DiceError::ParseError("Expected Silhouette modifier".into()))?;
 
     // Initialize complete RollResult structure
     let mut result = RollResult {
@@ -2932,6 +4078,11 @@ fn handle_silhouette_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResu
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -2946,6 +4097,17 @@ fn handle_silhouette_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResu
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
     };
 
     // Roll the dice pool
@@ -2997,7 +4159,7 @@ fn apply_mathematical_modifiers_to_silhouette(
             Modifier::Multiply(value) => result.total *= value,
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 result.total /= value;
             }
@@ -3173,7 +4335,7 @@ fn apply_pre_target_mathematical_modifiers(
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 for die_value in &mut result.kept_rolls {
                     *die_value /= value;
@@ -3324,6 +4486,11 @@ fn handle_vtm5_roll(
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -3338,6 +4505,17 @@ fn handle_vtm5_roll(
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: dice.unsorted,
     };
 
     let regular_dice = pool_size - hunger_dice;
@@ -3455,7 +4633,7 @@ fn apply_mathematical_modifiers_to_vtm5_successes(
             }
             Modifier::Divide(value) => {
                 if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
+                    return Err(DiceError::DivisionByZero { by_dice: false });
                 }
                 if let Some(ref mut successes) = result.successes {
                     *successes /= value;
@@ -3475,7 +4653,9 @@ fn apply_laser_feelings_mechanics(
     dice_count: u32,
 ) -> Result<()> {
     if result.individual_rolls.is_empty() {
-        return Err(anyhow!("No dice rolled for Lasers & Feelings"));
+        return Err(DiceError::ValidationError(
+            "No dice rolled for Lasers & Feelings".into(),
+        ));
     }
 
     // Validate that we're using d6s
@@ -3484,7 +4664,9 @@ fn apply_laser_feelings_mechanics(
         .iter()
         .all(|&roll| (1..=6).contains(&roll));
     if !all_d6 {
-        return Err(anyhow!("Lasers & Feelings requires d6 dice"));
+        return Err(DiceError::ValidationError(
+            "Lasers & Feelings requires d6 dice".into(),
+        ));
     }
 
     let target_i32 = target as i32;
@@ -3609,7 +4791,9 @@ fn interpret_panic_roll(panic_total: i32) -> String {
 /// - Classify result: 1-3=failure, 4-5=partial success, 6=success, multiple 6s=critical
 fn apply_forged_dark_mechanics(result: &mut RollResult) -> Result<()> {
     if result.kept_rolls.is_empty() {
-        return Err(anyhow!("No dice to apply FitD mechanics to"));
+        return Err(DiceError::ValidationError(
+            "No dice to apply FitD mechanics to".into(),
+        ));
     }
 
     // Find the highest die
@@ -3651,7 +4835,9 @@ fn apply_forged_dark_mechanics(result: &mut RollResult) -> Result<()> {
 /// - Same classification as standard FitD
 fn apply_forged_dark_zero_mechanics(result: &mut RollResult) -> Result<()> {
     if result.kept_rolls.len() != 2 {
-        return Err(anyhow!("FitD zero dice requires exactly 2 dice"));
+        return Err(DiceError::ValidationError(
+            "FitD zero dice requires exactly 2 dice".into(),
+        ));
     }
 
     // Find the lowest die (opposite of standard FitD)
@@ -3699,13 +4885,15 @@ fn finalize_success_failure_calculation(result: &mut RollResult) -> Result<()> {
 /// - If Hope == Fear: show "Critical Success!"
 fn apply_daggerheart_mechanics(result: &mut RollResult) -> Result<()> {
     if result.dice_groups.is_empty() || result.dice_groups[0].rolls.is_empty() {
-        return Err(anyhow!("No dice to apply Daggerheart mechanics to"));
+        return Err(DiceError::ValidationError(
+            "No dice to apply Daggerheart mechanics to".into(),
+        ));
     }
 
     let dice_group = &result.dice_groups[0];
     if dice_group.rolls.len() != 2 {
-        return Err(anyhow!(
-            "Daggerheart requires exactly 2 dice (Hope and Fear)"
+        return Err(DiceError::ValidationError(
+            "Daggerheart requires exactly 2 dice (Hope and Fear)".into(),
         ));
     }
 
@@ -3739,7 +4927,9 @@ fn apply_daggerheart_mechanics(result: &mut RollResult) -> Result<()> {
 /// Apply Wild Worlds RPG mechanics (The Wildsea RPG system)
 fn apply_wild_worlds_mechanics(result: &mut RollResult, cut_count: Option<u32>) -> Result<()> {
     if result.individual_rolls.is_empty() {
-        return Err(anyhow!("No dice to apply Wild Worlds mechanics to"));
+        return Err(DiceError::ValidationError(
+            "No dice to apply Wild Worlds mechanics to".into(),
+        ));
     }
 
     // Start with all rolled dice
@@ -3749,11 +4939,11 @@ fn apply_wild_worlds_mechanics(result: &mut RollResult, cut_count: Option<u32>)
     if let Some(cut) = cut_count {
         let cut_amount = cut as usize;
         if cut_amount >= working_dice.len() {
-            return Err(anyhow!(
+            return Err(DiceError::ValidationError(format!(
                 "Cannot cut {} dice from {} rolled",
                 cut,
                 working_dice.len()
-            ));
+            )));
         }
 
         // Sort dice in descending order and remove the highest ones
@@ -3765,7 +4955,9 @@ fn apply_wild_worlds_mechanics(result: &mut RollResult, cut_count: Option<u32>)
     }
 
     if working_dice.is_empty() {
-        return Err(anyhow!("No dice remaining after cutting"));
+        return Err(DiceError::ValidationError(
+            "No dice remaining after cutting".into(),
+        ));
     }
 
     // Find the highest die value (this determines the result in Wild Worlds)
@@ -3805,60 +4997,93 @@ fn apply_wild_worlds_mechanics(result: &mut RollResult, cut_count: Option<u32>)
     Ok(())
 }
 
-fn apply_plot_die_conversion(result: &mut RollResult) -> Result<()> {
-    let mut symbols = Vec::new();
-    let mut plot_total = 0;
-
-    for &roll in &result.kept_rolls {
-        let (symbol, value) = match roll {
-            1 => ("C+2", 2),
-            2 => ("C+4", 4),
-            3 | 4 => ("_", 0),
-            5 | 6 => ("Opp", 0),
-            _ => return Err(anyhow!("Invalid Plot die value: {}", roll)),
-        };
-        symbols.push(symbol.to_string());
-        plot_total += value;
+// Faces of the Genesys / Star Wars FFG dice: S success, A advantage, F failure,
+// T threat, and `!` triumph / `X` despair (which also count as a success /
+// failure).  An empty face is blank.
+const BOOST_FACES: [&str; 6] = ["", "", "S", "SA", "AA", "A"];
+const SETBACK_FACES: [&str; 6] = ["", "", "F", "F", "T", "T"];
+const ABILITY_FACES: [&str; 8] = ["", "S", "S", "SS", "A", "A", "SA", "AA"];
+const DIFFICULTY_FACES: [&str; 8] = ["", "F", "FF", "T", "T", "T", "TT", "FT"];
+const PROFICIENCY_FACES: [&str; 12] = [
+    "", "S", "S", "SS", "SS", "A", "SA", "SA", "SA", "AA", "AA", "S!",
+];
+const CHALLENGE_FACES: [&str; 12] = [
+    "", "F", "F", "FF", "FF", "T", "T", "FT", "FT", "TT", "TT", "FX",
+];
+
+fn handle_genesys_roll(
+    dice: DiceRoll,
+    pool: NarrativePool,
+    rng: &mut impl Rng,
+) -> Result<RollResult> {
+    if dice
+        .modifiers
+        .iter()
+        .any(|m| !matches!(m, Modifier::Genesys(_)))
+    {
+        return Err(DiceError::ValidationError(
+            "Genesys dice can't be combined with other modifiers".into(),
+        ));
     }
 
-    result.plot_symbols = Some(symbols);
-
-    let original_dice_total: i32 = result.kept_rolls.iter().sum();
-    let plot_adjustment = plot_total - original_dice_total;
-    result.total += plot_adjustment;
-
-    Ok(())
-}
-
-/// Helper function to detect matching dice values (for twist detection)
-fn has_matching_dice(dice: &[i32]) -> bool {
-    for i in 1..=6 {
-        let count = dice.iter().filter(|&&x| x == i).count();
-        if count >= 2 {
-            return true; // Found doubles (or triples, etc.)
+    let dice_types: [(&str, u32, &[&str]); 6] = [
+        ("🟦", pool.boost, &BOOST_FACES),
+        ("🟩", pool.ability, &ABILITY_FACES),
+        ("🟨", pool.proficiency, &PROFICIENCY_FACES),
+        ("⬛", pool.setback, &SETBACK_FACES),
+        ("🟪", pool.difficulty, &DIFFICULTY_FACES),
+        ("🟥", pool.challenge, &CHALLENGE_FACES),
+    ];
+
+    let mut narrative = NarrativeResult::default();
+    let mut individual_rolls = Vec::with_capacity(pool.dice_count() as usize);
+    for (colour, count, faces) in dice_types {
+        for _ in 0..count {
+            let roll = rng.random_range(1..=faces.len());
+            individual_rolls.push(roll as i32);
+            let face = faces[roll - 1];
+            for symbol in face.chars() {
+                match symbol {
+                    'S' => narrative.successes += 1,
+                    'A' => narrative.advantages += 1,
+                    'F' => narrative.successes -= 1,
+                    'T' => narrative.advantages -= 1,
+                    '!' => narrative.triumphs += 1,
+                    'X' => narrative.despairs += 1,
+                    _ => {
+                        return Err(DiceError::ParseError(format!(
+                            "Unknown Genesys symbol '{}'",
+                            symbol
+                        )));
+                    }
+                }
+            }
+            let face = if face.is_empty() { "-" } else { face };
+            narrative.faces.push(format!("{colour}`{face}`"));
         }
     }
-    false
-}
 
-pub fn handle_mutants_masterminds_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResult> {
-    // Initialize complete RollResult with all required fields
-    let mut result = RollResult {
-        individual_rolls: Vec::new(),
-        kept_rolls: Vec::new(),
+    Ok(RollResult {
+        individual_rolls: individual_rolls.clone(),
+        kept_rolls: individual_rolls,
         dropped_rolls: Vec::new(),
-        total: 0,
-        successes: None,
+        total: narrative.successes,
+        successes: Some(narrative.successes),
         failures: None,
         botches: None,
-        comment: dice.comment.clone(),
-        label: dice.label.clone(),
+        comment: dice.comment,
+        label: dice.label,
         notes: Vec::new(),
         dice_groups: Vec::new(),
-        original_expression: dice.original_expression.clone(),
+        original_expression: dice.original_expression,
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -3873,54 +5098,1373 @@ pub fn handle_mutants_masterminds_roll(dice: DiceRoll, rng: &mut impl Rng) -> Re
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
-    };
+        narrative: Some(narrative),
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
 
-    // Roll the dice
-    for _ in 0..dice.count {
-        let roll = rng.random_range(1..=dice.sides as i32);
-        result.individual_rolls.push(roll);
-        result.kept_rolls.push(roll);
-    }
+// Faces of the L5R 5e dice: S success, E explosive success (which is also a
+// success), O opportunity and `!` strife.  An empty face is blank.
+const RING_FACES: [&str; 6] = ["", "O!", "O", "S!", "S", "E!"];
+const SKILL_FACES: [&str; 12] = ["", "", "O", "O", "O", "S!", "S!", "S", "S", "SO", "E!", "E"];
 
-    // Create dice group for display
-    result.dice_groups.push(DiceGroup {
-        _description: format!("{}d{}", dice.count, dice.sides),
-        rolls: result.individual_rolls.clone(),
-        dropped_rolls: Vec::new(),
-        modifier_type: "base".to_string(),
-    });
+// Most extra dice explosive successes can add to one check
+const MAX_L5R_EXPLOSIONS: u32 = 100;
 
-    // Calculate base total
-    result.total = result.kept_rolls.iter().sum();
+struct L5rDie {
+    kind: &'static str, // ⚫ ring or ⚪ skill
+    faces: &'static [&'static str],
+    roll: i32,
+    face: &'static str,
+}
 
-    // Apply mathematical modifiers (add, subtract, multiply, divide)
-    for modifier in &dice.modifiers {
-        match modifier {
-            Modifier::Add(value) => {
-                result.total += value;
-            }
-            Modifier::Subtract(value) => {
-                result.total -= value;
-            }
-            Modifier::Multiply(value) => {
-                result.total *= value;
-            }
-            Modifier::Divide(value) => {
-                if *value == 0 {
-                    return Err(anyhow!("Cannot divide by zero"));
-                }
-                result.total /= value;
-            }
-            _ => {} // Only handle math modifiers, MutantsMasterminds handled separately
+impl L5rDie {
+    fn roll(kind: &'static str, faces: &'static [&'static str], rng: &mut impl Rng) -> Self {
+        let roll = rng.random_range(1..=faces.len());
+        L5rDie {
+            kind,
+            faces,
+            roll: roll as i32,
+            face: faces[roll - 1],
         }
     }
 
-    // Calculate degrees of success/failure against DC 10
-    let dc = 10;
-    let total_vs_dc = result.total - dc;
+    // How much a die is worth keeping: successes, then explosive successes,
+    // then opportunities, then the least strife
+    fn rank(&self) -> (usize, usize, usize, Reverse<usize>) {
+        let count = |symbols: &[char]| self.face.chars().filter(|c| symbols.contains(c)).count();
+        (
+            count(&['S', 'E']),
+            count(&['E']),
+            count(&['O']),
+            Reverse(count(&['!'])),
+        )
+    }
 
-    if total_vs_dc >= 0 {
-        // Success: every 5 points above DC is one degree of success
+    fn display(&self) -> String {
+        let face = if self.face.is_empty() { "-" } else { self.face };
+        format!("{}`{face}`", self.kind)
+    }
+}
+
+// Legend of the Five Rings 5e: roll ring and skill dice and keep the best
+// `ring` of them.  Each kept explosive success rolls another die of its type,
+// kept unless blank and free of the keep limit, which can explode in turn.
+fn handle_l5r_roll(dice: DiceRoll, pool: L5rPool, rng: &mut impl Rng) -> Result<RollResult> {
+    if dice
+        .modifiers
+        .iter()
+        .any(|m| !matches!(m, Modifier::L5r(_)))
+    {
+        return Err(DiceError::ValidationError(
+            "L5R dice can't be combined with other modifiers".into(),
+        ));
+    }
+
+    let dice_types: [(&str, u32, &'static [&'static str]); 2] = [
+        ("⚫", pool.ring, &RING_FACES),
+        ("⚪", pool.skill, &SKILL_FACES),
+    ];
+    let mut kept = Vec::with_capacity((pool.ring + pool.skill) as usize);
+    for (kind, count, faces) in dice_types {
+        for _ in 0..count {
+            kept.push(L5rDie::roll(kind, faces, rng));
+        }
+    }
+    kept.sort_by_key(|die| Reverse(die.rank()));
+    let mut dropped = kept.split_off(pool.ring as usize);
+
+    let mut explosions = 0;
+    let mut capped = false;
+    let mut index = 0;
+    while index < kept.len() {
+        if kept[index].face.contains('E') {
+            if explosions == MAX_L5R_EXPLOSIONS {
+                capped = true;
+                break;
+            }
+            explosions += 1;
+            let extra = L5rDie::roll(kept[index].kind, kept[index].faces, rng);
+            if extra.face.is_empty() {
+                dropped.push(extra);
+            } else {
+                kept.push(extra);
+            }
+        }
+        index += 1;
+    }
+
+    let mut l5r = L5rResult {
+        kept: kept.iter().map(L5rDie::display).collect(),
+        dropped: dropped.iter().map(L5rDie::display).collect(),
+        explosions,
+        ..Default::default()
+    };
+    for symbol in kept.iter().flat_map(|die| die.face.chars()) {
+        match symbol {
+            'S' | 'E' => l5r.successes += 1,
+            'O' => l5r.opportunities += 1,
+            '!' => l5r.strife += 1,
+            _ => {
+                return Err(DiceError::ParseError(format!(
+                    "Unknown L5R symbol '{}'",
+                    symbol
+                )));
+            }
+        }
+    }
+
+    let mut notes = Vec::new();
+    if explosions > 0 {
+        notes.push(format!(
+            "{explosions} extra {} rolled for explosive successes",
+            if explosions == 1 { "die" } else { "dice" }
+        ));
+    }
+    if capped {
+        notes.push(format!("Maximum explosions reached ({MAX_L5R_EXPLOSIONS})"));
+    }
+
+    let kept_rolls: Vec<i32> = kept.iter().map(|die| die.roll).collect();
+    let successes = l5r.successes as i32;
+    Ok(RollResult {
+        individual_rolls: kept_rolls.clone(),
+        kept_rolls,
+        dropped_rolls: Vec::new(),
+        total: successes,
+        successes: Some(successes),
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes,
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: Some(l5r),
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
+
+// Forbidden Lands (Year Zero): base, skill and gear d6s succeed on a 6,
+// artifact dice from 6 up with more successes on higher faces.  1s on base
+// and gear dice (artifact dice count as gear) are banes, which only hurt a
+// pushed roll.  A push rerolls every die without a success or a bane, once.
+fn handle_year_zero_roll(
+    dice: DiceRoll,
+    pool: YearZeroPool,
+    rng: &mut impl Rng,
+) -> Result<RollResult> {
+    if dice
+        .modifiers
+        .iter()
+        .any(|m| !matches!(m, Modifier::YearZero(_)))
+    {
+        return Err(DiceError::ValidationError(
+            "Forbidden Lands dice can't be combined with other modifiers".into(),
+        ));
+    }
+
+    let mut d6s =
+        |count: u32| -> Vec<i32> { (0..count).map(|_| rng.random_range(1..=6)).collect() };
+    let mut year_zero = YearZeroResult {
+        base: d6s(pool.base),
+        skill: d6s(pool.skill),
+        gear: d6s(pool.gear),
+        ..Default::default()
+    };
+    year_zero.artifacts = pool
+        .artifacts
+        .iter()
+        .map(|&sides| (sides, rng.random_range(1..=sides as i32)))
+        .collect();
+    year_zero.tally();
+
+    let mut notes = Vec::new();
+    if pool.push {
+        let first = year_zero.successes;
+        // Successes and banes stand; skill dice have no banes
+        let stands = |roll: i32, banes: bool| roll >= 6 || (banes && roll == 1);
+        let mut rerolled = 0;
+        for (dice, banes) in [
+            (&mut year_zero.base, true),
+            (&mut year_zero.skill, false),
+            (&mut year_zero.gear, true),
+        ] {
+            for roll in dice.iter_mut().filter(|roll| !stands(**roll, banes)) {
+                *roll = rng.random_range(1..=6);
+                rerolled += 1;
+            }
+        }
+        for (sides, roll) in &mut year_zero.artifacts {
+            if !stands(*roll, true) {
+                *roll = rng.random_range(1..=*sides as i32);
+                rerolled += 1;
+            }
+        }
+        year_zero.pushed = true;
+        year_zero.tally();
+
+        notes.push(format!(
+            "Pushed: rerolled {rerolled} {} after {first} {} on the first roll",
+            if rerolled == 1 { "die" } else { "dice" },
+            if first == 1 { "success" } else { "successes" }
+        ));
+        if year_zero.base_banes > 0 || year_zero.gear_banes > 0 {
+            notes.push(
+                "Each base bane deals 1 damage to the attribute and gives 1 willpower point; \
+                 each gear bane lowers the gear bonus by 1"
+                    .to_string(),
+            );
+        }
+    }
+
+    let rolls: Vec<i32> = year_zero
+        .base
+        .iter()
+        .chain(&year_zero.skill)
+        .chain(&year_zero.gear)
+        .copied()
+        .chain(year_zero.artifacts.iter().map(|&(_, roll)| roll))
+        .collect();
+    let successes = year_zero.successes as i32;
+    Ok(RollResult {
+        individual_rolls: rolls.clone(),
+        kept_rolls: rolls,
+        dropped_rolls: Vec::new(),
+        total: successes,
+        successes: Some(successes),
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes,
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: Some(year_zero),
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
+
+// Burning Wheel (also Mouse Guard and Torchbearer): the exponent and helping
+// d6s succeed on 4+, and the test passes with at least as many successes as
+// the obstacle.  Open-ended sixes each roll another die, which can be open
+// too.  Luck (spending a Fate point) makes the sixes open-ended, or on a
+// roll that already was, rerolls one traitor (a die under 4).
+fn handle_burning_wheel_roll(
+    dice: DiceRoll,
+    pool: BurningWheelPool,
+    rng: &mut impl Rng,
+) -> Result<RollResult> {
+    if dice.modifiers.len() > 1 {
+        return Err(DiceError::ValidationError(
+            "Burning Wheel tests can't be combined with other modifiers".into(),
+        ));
+    }
+
+    let mut d6s =
+        |count: u32| -> Vec<i32> { (0..count).map(|_| rng.random_range(1..=6)).collect() };
+    let mut burning_wheel = BurningWheelResult {
+        exponent: d6s(pool.exponent),
+        helping: d6s(pool.helping),
+        obstacle: pool.obstacle,
+        ..Default::default()
+    };
+
+    let mut notes = Vec::new();
+    if pool.open && pool.luck {
+        let traitor = burning_wheel
+            .exponent
+            .iter_mut()
+            .chain(&mut burning_wheel.helping)
+            .find(|roll| **roll < 4);
+        if let Some(traitor) = traitor {
+            let before = *traitor;
+            *traitor = rng.random_range(1..=6);
+            notes.push(format!("Luck: rerolled a traitor {before} → {traitor}"));
+        }
+    } else if pool.luck {
+        notes.push("Luck: sixes are open-ended".to_string());
+    }
+
+    if pool.open || pool.luck {
+        let mut sixes = burning_wheel
+            .exponent
+            .iter()
+            .chain(&burning_wheel.helping)
+            .filter(|&&roll| roll == 6)
+            .count();
+        while sixes > 0 && burning_wheel.open_ended.len() < MAX_CHAINED_EXPLOSIONS {
+            let roll = rng.random_range(1..=6);
+            burning_wheel.open_ended.push(roll);
+            sixes -= 1;
+            if roll == 6 {
+                sixes += 1;
+            }
+        }
+    }
+    burning_wheel.tally();
+
+    let rolls: Vec<i32> = burning_wheel
+        .exponent
+        .iter()
+        .chain(&burning_wheel.helping)
+        .chain(&burning_wheel.open_ended)
+        .copied()
+        .collect();
+    let successes = burning_wheel.successes as i32;
+    Ok(RollResult {
+        individual_rolls: rolls.clone(),
+        kept_rolls: rolls,
+        dropped_rolls: Vec::new(),
+        total: successes,
+        successes: Some(successes),
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes,
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: Some(burning_wheel),
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
+
+// Most dice in one Cortex Prime pool
+const MAX_CORTEX_DICE: usize = 20;
+
+// Die sizes a Cortex Prime pool can hold
+const CORTEX_DIE_SIZES: [u32; 5] = [4, 6, 8, 10, 12];
+
+// Cortex Prime: roll each die of the pool, then total the best two and keep
+// the largest die left over as the effect die (see `CortexResult`)
+fn handle_cortex_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResult> {
+    if dice.modifiers.len() > 1 {
+        return Err(DiceError::ValidationError(
+            "Cortex pools can't be combined with other modifiers".into(),
+        ));
+    }
+    let sizes = die_sizes(&dice);
+    if let Some(size) = sizes.iter().find(|size| !CORTEX_DIE_SIZES.contains(size)) {
+        return Err(DiceError::ValidationError(format!(
+            "Cortex dice are d4, d6, d8, d10 or d12, not d{}",
+            size
+        )));
+    }
+    if sizes.len() > MAX_CORTEX_DICE {
+        return Err(DiceError::RollLimitExceeded(format!(
+            "Maximum {} Cortex dice allowed",
+            MAX_CORTEX_DICE
+        )));
+    }
+
+    let rolled: Vec<CortexDie> = sizes
+        .into_iter()
+        .map(|sides| CortexDie {
+            sides,
+            roll: rng.random_range(1..=sides as i32),
+        })
+        .collect();
+    let individual_rolls: Vec<i32> = rolled.iter().map(|die| die.roll).collect();
+    let cortex = CortexResult::from_dice(rolled);
+    let kept_rolls: Vec<i32> = cortex.dice[..cortex.total_dice]
+        .iter()
+        .map(|die| die.roll)
+        .collect();
+    let dropped_rolls: Vec<i32> = cortex.dice[cortex.total_dice..]
+        .iter()
+        .map(|die| die.roll)
+        .collect();
+
+    Ok(RollResult {
+        individual_rolls,
+        kept_rolls,
+        dropped_rolls,
+        total: cortex.total(),
+        successes: None,
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes: Vec::new(),
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: Some(cortex),
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
+
+// Call of Cthulhu 7e: one units die and 1 + |bonus| tens dice, keeping the
+// lowest result for bonus dice and the highest for penalty dice.  00 + 0
+// reads as 100.
+fn handle_coc_roll(
+    dice: DiceRoll,
+    skill: Option<u32>,
+    bonus: i32,
+    rng: &mut impl Rng,
+) -> Result<RollResult> {
+    if dice.modifiers.len() > 1 {
+        return Err(DiceError::ValidationError(
+            "Call of Cthulhu rolls can't be combined with other modifiers".into(),
+        ));
+    }
+
+    let units = rng.random_range(0..=9);
+    let tens: Vec<i32> = (0..=bonus.unsigned_abs())
+        .map(|_| rng.random_range(0..=9) * 10)
+        .collect();
+    let mut candidates: Vec<i32> = tens
+        .iter()
+        .map(|ten| match ten + units {
+            0 => 100,
+            roll => roll,
+        })
+        .collect();
+    let kept = if bonus >= 0 {
+        candidates.iter().min()
+    } else {
+        candidates.iter().max()
+    }
+    .copied()
+    .ok_or_else(|| DiceError::ValidationError("Call of Cthulhu roll needs a tens die".into()))?;
+    if let Some(pos) = candidates.iter().position(|&roll| roll == kept) {
+        candidates.remove(pos);
+    }
+
+    let mut notes = Vec::new();
+    if bonus != 0 {
+        let (kind, choice) = if bonus > 0 {
+            ("Bonus", "lowest")
+        } else {
+            ("Penalty", "highest")
+        };
+        let tens_list = tens
+            .iter()
+            .map(|ten| format!("{ten:02}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        notes.push(format!(
+            "{kind} dice: tens {tens_list} with units {units}, kept the {choice}"
+        ));
+    }
+
+    // Below skill 50 anything from 96 fumbles; otherwise only 100
+    let fumble = match skill {
+        Some(skill) if skill < 50 => kept >= 96,
+        _ => kept == 100,
+    };
+    let outcome = match skill {
+        _ if kept == 1 => Some("**CRITICAL SUCCESS**"),
+        _ if fumble => Some("**FUMBLE**"),
+        Some(skill) if kept <= (skill / 5) as i32 => Some("**EXTREME SUCCESS**"),
+        Some(skill) if kept <= (skill / 2) as i32 => Some("**HARD SUCCESS**"),
+        Some(skill) if kept <= skill as i32 => Some("**REGULAR SUCCESS**"),
+        Some(_) => Some("**FAILURE**"),
+        None => None,
+    };
+    match (outcome, skill) {
+        (Some(outcome), Some(skill)) => notes.push(format!(
+            "{outcome} (rolled {kept} vs skill {skill}, hard {}, extreme {})",
+            skill / 2,
+            skill / 5
+        )),
+        (Some(outcome), None) => notes.push(outcome.to_string()),
+        (None, _) => {}
+    }
+
+    Ok(RollResult {
+        individual_rolls: vec![kept],
+        kept_rolls: vec![kept],
+        dropped_rolls: candidates,
+        total: kept,
+        successes: None,
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes,
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
+
+// WFRP 4e test of a d100 roll (1-100, 100 reading as 00) against a skill:
+// 01-05 always succeed and 96-00 always fail, and Success Levels are the
+// skill's tens digit minus the roll's
+fn wfrp_test(roll: i32, skill: u32) -> WfrpResult {
+    let success = match roll {
+        1..=5 => true,
+        96.. => false,
+        _ => roll <= skill as i32,
+    };
+    let sl = (skill / 10) as i32 - roll / 10;
+    WfrpResult {
+        skill,
+        success,
+        // An automatic success or failure never has Success Levels of the
+        // other sign
+        success_levels: if success { sl.max(0) } else { sl.min(0) },
+        double: roll % 11 == 0 || roll == 100,
+    }
+}
+
+// Warhammer Fantasy Roleplay 4e: a d100 test against a skill.  Doubles are
+// criticals on a success and fumbles on a failure, and the note says when
+// swapping the dice (a talent or Fortune) would give a better result.
+fn handle_wfrp_roll(dice: DiceRoll, skill: u32, rng: &mut impl Rng) -> Result<RollResult> {
+    if dice.modifiers.len() > 1 {
+        return Err(DiceError::ValidationError(
+            "Warhammer Fantasy Roleplay tests can't be combined with other modifiers".into(),
+        ));
+    }
+
+    let roll = rng.random_range(1..=100);
+    let test = wfrp_test(roll, skill);
+
+    let outcome = match (test.success, test.double) {
+        (true, true) => "**CRITICAL SUCCESS**",
+        (true, false) => "**SUCCESS**",
+        (false, true) => "**FUMBLE**",
+        (false, false) => "**FAILURE**",
+    };
+    let mut notes = vec![format!(
+        "{outcome} (rolled {roll:02} vs skill {skill}, SL {})",
+        test.sl_text()
+    )];
+
+    // 100 reads as 00, so 07 reverses to 70 and 00 stays 00
+    let reversed = match roll % 100 {
+        0 => 100,
+        r => (r % 10) * 10 + r / 10,
+    };
+    let reversed_test = wfrp_test(reversed, skill);
+    let better =
+        (reversed_test.success, reversed_test.success_levels) > (test.success, test.success_levels);
+    if reversed != roll && better {
+        let kind = if reversed_test.success {
+            "success"
+        } else {
+            "failure"
+        };
+        notes.push(format!(
+            "Can be reversed to {:02}: {kind} with SL {}",
+            reversed % 100,
+            reversed_test.sl_text()
+        ));
+    }
+
+    Ok(RollResult {
+        individual_rolls: vec![roll],
+        kept_rolls: vec![roll],
+        dropped_rolls: Vec::new(),
+        total: roll,
+        successes: None,
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes,
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: Some(test),
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
+
+// GURPS success roll: 3d6 at or under the effective skill succeeds, by the
+// margin between them.  3-4 always succeed critically (5 at skill 15+, 6 at
+// 16+); 18 always fails critically, and so does 17 at skill 15 or less and
+// any roll 10 or more over the skill.  17 always fails.
+fn handle_gurps_roll(dice: DiceRoll, skill: u32, rng: &mut impl Rng) -> Result<RollResult> {
+    if dice.modifiers.len() > 1 {
+        return Err(DiceError::ValidationError(
+            "GURPS success rolls can't be combined with other modifiers".into(),
+        ));
+    }
+    if dice.count != 3 || dice.sides != 6 {
+        return Err(DiceError::ValidationError(
+            "GURPS success rolls use 3d6".into(),
+        ));
+    }
+
+    let rolls: Vec<i32> = (0..3).map(|_| rng.random_range(1..=6)).collect();
+    let total: i32 = rolls.iter().sum();
+    let skill = skill as i32;
+
+    let critical_success = total <= 4 || (total == 5 && skill >= 15) || (total == 6 && skill >= 16);
+    let critical_failure = total == 18 || (total == 17 && skill <= 15) || total >= skill + 10;
+    let success = critical_success || (total <= skill && total < 17);
+    let outcome = match (success, critical_success, critical_failure) {
+        (true, true, _) => "**CRITICAL SUCCESS**",
+        (true, false, _) => "**SUCCESS**",
+        (false, _, true) => "**CRITICAL FAILURE**",
+        (false, _, false) => "**FAILURE**",
+    };
+    let margin = skill - total;
+    let by = if margin >= 0 {
+        format!("made it by {margin}")
+    } else {
+        format!("missed by {}", -margin)
+    };
+
+    Ok(RollResult {
+        individual_rolls: rolls.clone(),
+        kept_rolls: rolls,
+        dropped_rolls: Vec::new(),
+        total,
+        successes: None,
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes: vec![format!("{outcome} (rolled {total} vs skill {skill}, {by})")],
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: false,
+    })
+}
+
+// Dragonbane: a d20 rolled under a skill.  A boon rolls a second d20 and
+// keeps the lowest, a bane keeps the highest.  A 1 is a Dragon and a 20 a
+// Demon; a failed roll that isn't a Demon can be pushed once, rolling again
+// at the cost of a condition.
+fn handle_dragonbane_roll(
+    dice: DiceRoll,
+    skill: Option<u32>,
+    boon: i32,
+    pushed: bool,
+    rng: &mut impl Rng,
+) -> Result<RollResult> {
+    if dice.modifiers.len() > 1 {
+        return Err(DiceError::ValidationError(
+            "Dragonbane rolls can't be combined with other modifiers".into(),
+        ));
+    }
+    if dice.count != 1 || dice.sides != 20 {
+        return Err(DiceError::ValidationError(
+            "Dragonbane rolls use one d20".into(),
+        ));
+    }
+
+    let mut rolls: Vec<i32> = (0..=boon.unsigned_abs())
+        .map(|_| rng.random_range(1..=20))
+        .collect();
+    let kept = if boon >= 0 {
+        rolls.iter().min()
+    } else {
+        rolls.iter().max()
+    }
+    .copied()
+    .ok_or_else(|| DiceError::ValidationError("Dragonbane roll needs a d20".into()))?;
+
+    let mut notes = Vec::new();
+    if boon != 0 {
+        let (kind, choice) = if boon > 0 {
+            ("Boon", "lowest")
+        } else {
+            ("Bane", "highest")
+        };
+        notes.push(format!(
+            "{kind}: rolled {} and {}, kept the {choice}",
+            rolls[0], rolls[1]
+        ));
+    }
+    if let Some(pos) = rolls.iter().position(|&roll| roll == kept) {
+        rolls.remove(pos);
+    }
+
+    let outcome = match skill {
+        _ if kept == 1 => Some("**DRAGON**"),
+        _ if kept == 20 => Some("**DEMON**"),
+        Some(skill) if kept <= skill as i32 => Some("**SUCCESS**"),
+        Some(_) => Some("**FAILURE**"),
+        None => None,
+    };
+    match (outcome, skill) {
+        (Some(outcome), Some(skill)) => {
+            notes.push(format!("{outcome} (rolled {kept} vs skill {skill})"))
+        }
+        (Some(outcome), None) => notes.push(outcome.to_string()),
+        (None, _) => {}
+    }
+
+    // A pushed roll stands; a failure can be pushed unless it's a Demon
+    let failed = kept == 20 || skill.is_some_and(|skill| kept > skill as i32);
+    if pushed {
+        notes.push("Pushed roll: take a condition. It can't be pushed again".to_string());
+    } else if kept == 20 {
+        notes.push("A Demon can't be pushed".to_string());
+    } else if failed && let Some(skill) = skill {
+        let edge = match boon {
+            1 => " boon",
+            -1 => " bane",
+            _ => "",
+        };
+        notes.push(format!(
+            "Push it with `db {skill}{edge} push` and take a condition"
+        ));
+    }
+
+    Ok(RollResult {
+        individual_rolls: vec![kept],
+        kept_rolls: vec![kept],
+        dropped_rolls: rolls,
+        total: kept,
+        successes: None,
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes,
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
+
+// Highest action score in Ironsworn / Starforged
+const MAX_ACTION_SCORE: i32 = 10;
+
+// Ironsworn / Starforged action roll: the action die plus adds (capped at 10)
+// against two d10 challenge dice.  Beating both is a strong hit, one a weak
+// hit and neither a miss; challenge dice showing the same number are a match.
+fn handle_ironsworn_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResult> {
+    if dice.count != 1 || dice.sides != 6 {
+        return Err(DiceError::ValidationError(
+            "Ironsworn action rolls use one d6 action die".into(),
+        ));
+    }
+
+    let mut adds = 0i32;
+    for modifier in &dice.modifiers {
+        match modifier {
+            Modifier::Ironsworn => {}
+            Modifier::Add(value) => adds = adds.saturating_add(*value),
+            Modifier::Subtract(value) => adds = adds.saturating_sub(*value),
+            _ => {
+                return Err(DiceError::ValidationError(
+                    "Ironsworn action rolls can only add to or subtract from the action die".into(),
+                ));
+            }
+        }
+    }
+
+    let action_die: i32 = rng.random_range(1..=6);
+    let uncapped = action_die.saturating_add(adds);
+    let action_score = uncapped.min(MAX_ACTION_SCORE);
+    let challenge = vec![rng.random_range(1..=10), rng.random_range(1..=10)];
+
+    let outcome = match challenge.iter().filter(|&&die| action_score > die).count() {
+        2 => "STRONG HIT",
+        1 => "WEAK HIT",
+        _ => "MISS",
+    };
+
+    let mut notes = Vec::new();
+    if uncapped > MAX_ACTION_SCORE {
+        notes.push(format!(
+            "Action score {uncapped} capped at {MAX_ACTION_SCORE}"
+        ));
+    }
+    if challenge[0] == challenge[1] {
+        let meaning = if outcome == "STRONG HIT" {
+            "an unexpected advantage"
+        } else {
+            "a dramatic complication"
+        };
+        notes.push(format!(
+            "🎯 **MATCH**: both challenge dice show {} - {meaning}",
+            challenge[0]
+        ));
+    }
+
+    Ok(RollResult {
+        individual_rolls: vec![action_die],
+        kept_rolls: vec![action_die],
+        dropped_rolls: Vec::new(),
+        total: action_score,
+        successes: None,
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes,
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: Some(outcome.to_string()),
+        ironsworn_challenge: Some(challenge),
+        preserve_order: true,
+    })
+}
+
+// Ironsworn oracle: a d100, answering yes on `odds` or higher when asked with
+// odds.  Doubles (11, 22, … 99, and 100) are a match: an extreme result or a
+// twist.
+fn handle_oracle_roll(dice: DiceRoll, odds: Option<u32>, rng: &mut impl Rng) -> Result<RollResult> {
+    if dice.count != 1 || dice.sides != 100 || dice.modifiers.len() > 1 {
+        return Err(DiceError::ValidationError(
+            "Oracle rolls are a single d100 and can't be combined with other modifiers".into(),
+        ));
+    }
+
+    let roll = rng.random_range(1..=100);
+    let mut notes = Vec::new();
+    if roll == 100 || roll % 11 == 0 {
+        notes.push(format!(
+            "🎯 **MATCH**: {roll} - an extreme result or a twist"
+        ));
+    }
+    let outcome = odds.map(|odds| {
+        if roll >= odds as i32 {
+            "YES".to_string()
+        } else {
+            "NO".to_string()
+        }
+    });
+
+    Ok(RollResult {
+        individual_rolls: vec![roll],
+        kept_rolls: vec![roll],
+        dropped_rolls: Vec::new(),
+        total: roll,
+        successes: None,
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes,
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: outcome,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
+
+// Labeled dice: each die lands on one of `faces`.  The rolls are face
+// numbers (1 for the first face), kept in roll order; there is no total.
+fn handle_face_roll(dice: DiceRoll, faces: Vec<String>, rng: &mut impl Rng) -> Result<RollResult> {
+    if !dice.modifiers.is_empty() {
+        return Err(DiceError::ValidationError(
+            "Labeled dice can't be combined with modifiers".into(),
+        ));
+    }
+
+    let rolls: Vec<i32> = (0..dice.count)
+        .map(|_| rng.random_range(1..=faces.len() as i32))
+        .collect();
+    let labels = rolls
+        .iter()
+        .map(|&roll| faces[roll as usize - 1].clone())
+        .collect();
+
+    Ok(RollResult {
+        individual_rolls: rolls.clone(),
+        kept_rolls: rolls,
+        dropped_rolls: Vec::new(),
+        total: 0,
+        successes: None,
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes: Vec::new(),
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: Some(labels),
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
+
+fn apply_plot_die_conversion(result: &mut RollResult) -> Result<()> {
+    let mut symbols = Vec::new();
+    let mut plot_total = 0;
+
+    for &roll in &result.kept_rolls {
+        let (symbol, value) = match roll {
+            1 => ("C+2", 2),
+            2 => ("C+4", 4),
+            3 | 4 => ("_", 0),
+            5 | 6 => ("Opp", 0),
+            _ => {
+                return Err(DiceError::ParseError(format!(
+                    "Invalid Plot die value: {}",
+                    roll
+                )));
+            }
+        };
+        symbols.push(symbol.to_string());
+        plot_total += value;
+    }
+
+    result.plot_symbols = Some(symbols);
+
+    let original_dice_total: i32 = result.kept_rolls.iter().sum();
+    let plot_adjustment = plot_total - original_dice_total;
+    result.total += plot_adjustment;
+
+    Ok(())
+}
+
+/// Helper function to detect matching dice values (for twist detection)
+fn has_matching_dice(dice: &[i32]) -> bool {
+    for i in 1..=6 {
+        let count = dice.iter().filter(|&&x| x == i).count();
+        if count >= 2 {
+            return true; // Found doubles (or triples, etc.)
+        }
+    }
+    false
+}
+
+pub fn handle_mutants_masterminds_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResult> {
+    // Initialize complete RollResult with all required fields
+    let mut result = RollResult {
+        individual_rolls: Vec::new(),
+        kept_rolls: Vec::new(),
+        dropped_rolls: Vec::new(),
+        total: 0,
+        successes: None,
+        failures: None,
+        botches: None,
+        comment: dice.comment.clone(),
+        label: dice.label.clone(),
+        notes: Vec::new(),
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression.clone(),
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: None,
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    };
+
+    // Roll the dice
+    for _ in 0..dice.count {
+        let roll = rng.random_range(1..=dice.sides as i32);
+        result.individual_rolls.push(roll);
+        result.kept_rolls.push(roll);
+    }
+
+    // Create dice group for display
+    result.dice_groups.push(DiceGroup {
+        _description: format!("{}d{}", dice.count, dice.sides),
+        rolls: result.individual_rolls.clone(),
+        dropped_rolls: Vec::new(),
+        modifier_type: "base".to_string(),
+    });
+
+    // Calculate base total
+    result.total = result.kept_rolls.iter().sum();
+
+    // Apply mathematical modifiers (add, subtract, multiply, divide)
+    for modifier in &dice.modifiers {
+        match modifier {
+            Modifier::Add(value) => {
+                result.total += value;
+            }
+            Modifier::Subtract(value) => {
+                result.total -= value;
+            }
+            Modifier::Multiply(value) => {
+                result.total *= value;
+            }
+            Modifier::Divide(value) => {
+                if *value == 0 {
+                    return Err(DiceError::DivisionByZero { by_dice: false });
+                }
+                result.total /= value;
+            }
+            _ => {} // Only handle math modifiers, MutantsMasterminds handled separately
+        }
+    }
+
+    // Calculate degrees of success/failure against DC 10
+    let dc = 10;
+    let total_vs_dc = result.total - dc;
+
+    if total_vs_dc >= 0 {
+        // Success: every 5 points above DC is one degree of success
         let degrees = (total_vs_dc / 5) + 1;
         result.successes = Some(degrees);
 
@@ -3956,6 +6500,110 @@ pub fn handle_mutants_masterminds_roll(dice: DiceRoll, rng: &mut impl Rng) -> Re
     Ok(result)
 }
 
+// Mothership panic effects, by the d20 rolled on a failed panic check
+const MOTHERSHIP_PANIC_TABLE: [&str; 20] = [
+    "ADRENALINE RUSH: advantage on all rolls for the next 2d10 minutes; lose 1d5 stress",
+    "NERVOUS TWITCH: gain 1 stress, and so does the nearest crewmember",
+    "WHAT WAS THAT?: disadvantage on all rolls for the next 1d10 minutes",
+    "COWARD: new condition: make a Fear save to engage in violence, or flee",
+    "HALLUCINATIONS: new condition: you sometimes see things that aren't there",
+    "PHOBIA: new condition: gain a phobia; confronting it is a Fear save at disadvantage",
+    "NIGHT TERRORS: new condition: sleep is a Fear save; fail and gain 1 stress",
+    "LOSS OF CONFIDENCE: new condition: pick a skill; you can't use it",
+    "DEFLATED: new condition: when a crewmember fails a save, gain 1 stress",
+    "DOOMED: new condition: you feel cursed; critical successes count as failures",
+    "SUSPICIOUS: new condition: gain 1 stress whenever someone joins the crew, for a week",
+    "HAUNTED: new condition: something follows you at night",
+    "DEATH WISH: new condition: make a Sanity save to avoid danger",
+    "PROPHETIC VISION: new condition: you saw your death; disadvantage when it seems near",
+    "CATATONIC: unresponsive for 2d10 minutes; lose 1d10 stress",
+    "RAGE: attack the nearest creature, friend or foe, until you pass a Sanity save",
+    "SPIRALING: new condition: stress gains are doubled",
+    "COMPOUNDING PROBLEMS: roll twice on this table",
+    "HEART ATTACK: lose 1d10 health, and make a Body save or stop breathing",
+    "RETIRE: roll up a new character to play; this one leaves at the first chance",
+];
+
+// Mothership panic check: a d20 over the current stress holds it together;
+// anything else panics, and the roll picks the effect from the panic table
+fn handle_mothership_panic_roll(
+    dice: DiceRoll,
+    stress: u32,
+    rng: &mut impl Rng,
+) -> Result<RollResult> {
+    if dice.modifiers.len() > 1 {
+        return Err(DiceError::ValidationError(
+            "Mothership panic checks can't be combined with other modifiers".into(),
+        ));
+    }
+    if dice.count != 1 || dice.sides != 20 {
+        return Err(DiceError::ValidationError(
+            "Mothership panic checks use one d20".into(),
+        ));
+    }
+
+    let roll = rng.random_range(1..=20);
+    let panic = roll <= stress as i32;
+    let mut notes = vec![format!(
+        "Mothership panic check (stress {stress}): rolled {roll}, {} to hold it together",
+        if panic { "needed more" } else { "enough" }
+    )];
+    if panic {
+        notes.push(format!(
+            "**{}**",
+            MOTHERSHIP_PANIC_TABLE[(roll - 1) as usize]
+        ));
+    }
+
+    Ok(RollResult {
+        individual_rolls: vec![roll],
+        kept_rolls: vec![roll],
+        dropped_rolls: Vec::new(),
+        total: roll,
+        successes: None,
+        failures: None,
+        botches: None,
+        comment: dice.comment,
+        label: dice.label,
+        notes,
+        dice_groups: Vec::new(),
+        original_expression: dice.original_expression,
+        simple: dice.simple,
+        no_results: dice.no_results,
+        private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
+        godbound_damage: None,
+        fudge_symbols: None,
+        wng_wrath_die: None,
+        wng_icons: None,
+        wng_exalted_icons: None,
+        wng_wrath_dice: None,
+        suppress_comment: false,
+        alien_stress_level: None,
+        alien_panic_roll: None,
+        alien_stress_ones: None,
+        fitd_outcome: Some(if panic { "PANIC" } else { "NO PANIC" }.to_string()),
+        fitd_result: None,
+        fitd_highest_die: None,
+        plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
+    })
+}
+
 fn handle_mothership_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResult> {
     // Extract Mothership modifier
     let (stat_target, is_advantage_or_disadvantage) = dice
@@ -3968,14 +6616,17 @@ fn handle_mothership_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResu
                 None
             }
         })
-        .ok_or_else(|| anyhow!("Expected Mothership modifier"))?;
+        .ok_or_else(|| DiceError::ParseError("Expected Mothership modifier".into()))?;
 
     // Determine actual stat value (default to 50 if not specified)
     let stat = stat_target.unwrap_or(50);
 
     // Validate stat range
     if !(1..=99).contains(&stat) {
-        return Err(anyhow!("Mothership stat must be 1-99, got {}", stat));
+        return Err(DiceError::ValidationError(format!(
+            "Mothership stat must be 1-99, got {}",
+            stat
+        )));
     }
 
     // Roll the dice (either 1d100 or 2d100)
@@ -4074,6 +6725,11 @@ fn handle_mothership_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResu
         simple: dice.simple,
         no_results: dice.no_results,
         private: dice.private,
+        drama: dice.drama,
+        embed: dice.embed,
+        full: dice.full,
+        gm: dice.gm,
+        language: dice.language,
         godbound_damage: None,
         fudge_symbols: None,
         wng_wrath_die: None,
@@ -4088,6 +6744,17 @@ fn handle_mothership_roll(dice: DiceRoll, rng: &mut impl Rng) -> Result<RollResu
         fitd_result: None,
         fitd_highest_die: None,
         plot_symbols: None,
+        narrative: None,
+        l5r: None,
+        wfrp: None,
+        year_zero: None,
+        burning_wheel: None,
+        matches: None,
+        cortex: None,
+        face_labels: None,
+        ironsworn_outcome: None,
+        ironsworn_challenge: None,
+        preserve_order: true,
     };
 
     // Add descriptive notes
@@ -0,0 +1,170 @@
+//! Character trackers: sheet values that feed system rolls and change with them.
+//!
+//! Trackers are ordinary `/sheet` stats with a known name and range.  When a
+//! roll uses the matching system without stating the value, it is filled in
+//! from the sheet, and the roll can change it afterwards:
+//!
+//! | Tracker    | Range | Roll                 | Becomes                 | After the roll        |
+//! |------------|-------|----------------------|-------------------------|-----------------------|
+//! | `hunger`   | 0–5   | `vtm7`               | `vtm7h<hunger>`         | —                     |
+//! | `stress`   | 0–10  | `alien5`             | `alien5s<stress>`       | —                     |
+//! | `stress`   | 0–10  | `alien5p` (push)     | `alien5s<stress>p`      | stress +1             |
+//! | `momentum` | 0–6   | `conan3` … `conan5`  | unchanged               | 1 / 3 / 6 momentum spent |
+//!
+//! Rolls that already state the value (`vtm7h2`, `alien5s2`) are never
+//! touched.  Stress and momentum are only read once the player has set them
+//! with `/sheet`, so `alien5` and `conan4` behave as before for everyone else;
+//! a push (`alien5p`, new syntax) starts an unset stress tracker at 0.
+//!
+//! [`apply_trackers`] is pure; the caller loads the sheet and saves the
+//! returned [`TrackerUpdate`]s once the roll has succeeded.
+
+use super::error::{DiceError, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// A sheet stat with system-specific meaning
+#[derive(Debug)]
+pub struct Tracker {
+    pub name: &'static str,
+    pub system: &'static str,
+    pub max: i64,
+}
+
+pub const TRACKERS: [Tracker; 3] = [
+    Tracker {
+        name: "hunger",
+        system: "Vampire: The Masquerade 5e",
+        max: 5,
+    },
+    Tracker {
+        name: "stress",
+        system: "Alien RPG",
+        max: 10,
+    },
+    Tracker {
+        name: "momentum",
+        system: "2d20 (Conan)",
+        max: 6,
+    },
+];
+
+/// A tracker change caused by a roll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackerUpdate {
+    pub name: &'static str,
+    pub from: i64,
+    pub to: i64,
+}
+
+/// A roll with tracker values filled in, and the changes to save if it succeeds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrackedRoll {
+    pub expression: String,
+    pub updates: Vec<TrackerUpdate>,
+}
+
+// `[flags] [(label)] <system roll> [! comment]`
+static TRACKED_ROLL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)^((?:(?:p|s|nr|ul|drama)\s+)*(?:\([^)]*\)\s*)?)(vtm(\d+)|alien(\d+)(p?)|conan([345])(cd\d+)?)\s*$",
+    )
+    .expect("Failed to compile TRACKED_ROLL_REGEX")
+});
+
+/// Look up a tracker by stat name
+pub fn find_tracker(name: &str) -> Option<&'static Tracker> {
+    TRACKERS.iter().find(|tracker| tracker.name == name)
+}
+
+/// Whether `input` is a roll that trackers may apply to
+pub fn uses_trackers(input: &str) -> bool {
+    let expression = input.split('!').next().unwrap_or_default();
+    TRACKED_ROLL_REGEX.is_match(expression.trim())
+}
+
+/// Fill tracker values into a roll and work out how they change.
+///
+/// `lookup` returns a sheet stat by name.  Returns `None` when no tracker
+/// applies, and an error when the sheet can't pay for the roll (not enough
+/// momentum).
+pub fn apply_trackers<F>(input: &str, lookup: F) -> Option<Result<TrackedRoll>>
+where
+    F: Fn(&str) -> Option<i64>,
+{
+    let (expression, comment) = input.split_at(input.find('!').unwrap_or(input.len()));
+    let captures = TRACKED_ROLL_REGEX.captures(expression.trim())?;
+    let prefix = captures.get(1).map_or("", |m| m.as_str());
+    let tracked = |name: &str| {
+        let max = find_tracker(name).map_or(0, |tracker| tracker.max);
+        lookup(name).map(|value| value.clamp(0, max))
+    };
+
+    let (roll, updates) = if let Some(pool) = captures.get(3) {
+        let pool = pool.as_str().parse::<i64>().ok()?;
+        let hunger = tracked("hunger").unwrap_or(0).min(pool);
+        (format!("vtm{pool}h{hunger}"), Vec::new())
+    } else if let Some(pool) = captures.get(4) {
+        let pool = pool.as_str();
+        let push = captures.get(5).is_some_and(|p| !p.as_str().is_empty());
+        match (tracked("stress"), push) {
+            // Pushing adds a stress die to this roll, so the sheet gains it too
+            (stress, true) => {
+                let stress = stress.unwrap_or(0);
+                let max = find_tracker("stress")?.max;
+                if stress >= max {
+                    return Some(Err(DiceError::ValidationError(format!(
+                        "Stress is already at {max}, you can't push this roll"
+                    ))));
+                }
+                let update = TrackerUpdate {
+                    name: "stress",
+                    from: stress,
+                    to: stress + 1,
+                };
+                (format!("alien{pool}s{stress}p"), vec![update])
+            }
+            (Some(stress), false) if stress > 0 => (format!("alien{pool}s{stress}"), Vec::new()),
+            (_, false) => return None,
+        }
+    } else {
+        let dice = captures.get(6)?.as_str().parse::<i64>().ok()?;
+        let momentum = tracked("momentum")?;
+        // Each extra d20 costs one more momentum than the last: 1, 2, 3
+        let cost = match dice {
+            3 => 1,
+            4 => 3,
+            _ => 6,
+        };
+        if momentum < cost {
+            return Some(Err(DiceError::ValidationError(format!(
+                "Not enough momentum for {dice}d20 (need {cost}, have {momentum})"
+            ))));
+        }
+        let combat = captures
+            .get(7)
+            .map_or(String::new(), |m| m.as_str().to_lowercase());
+        let update = TrackerUpdate {
+            name: "momentum",
+            from: momentum,
+            to: momentum - cost,
+        };
+        (format!("conan{dice}{combat}"), vec![update])
+    };
+
+    let comment = comment.trim();
+    let expression = format!("{prefix}{roll} {comment}");
+    Some(Ok(TrackedRoll {
+        expression: expression.trim_end().to_string(),
+        updates,
+    }))
+}
+
+/// One-line summary of tracker changes, e.g. `` `stress` 2 → 3 ``
+pub fn format_updates(updates: &[TrackerUpdate]) -> String {
+    updates
+        .iter()
+        .map(|update| format!("`{}` {} → {}", update.name, update.from, update.to))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
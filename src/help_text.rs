@@ -2,6 +2,9 @@
 //! Kept in a separate module so the text can be shared without duplication
 //! if additional consumers are added (e.g. a web dashboard).
 
+use crate::dice::modifier_docs::{self, MODIFIER_DOCS};
+use crate::i18n::Language;
+
 pub fn generate_basic_help() -> String {
     r#"🎲 **Dice Maiden** 🎲
 
@@ -29,6 +32,8 @@ pub fn generate_basic_help() -> String {
 • `t4ds6` - Count successes (≥ 4) and double success on 6 (defaults to target)
 • `tl6` - Count successes (≤ 6)
 • `tl6ds4` - Count successes (≤ 6) and double success on 4 (defaults to target)
+• `t8 rfail` - Reroll dice that missed the target once
+• `t7 maxs5` - Count at most 5 successes
 • `f1` - Count failures (≤ 1)
 • `b1` - Count botches (≤ 1)
 
@@ -37,16 +42,332 @@ pub fn generate_basic_help() -> String {
 • `s` - Simple output (no dice breakdown)
 • `nr` - No results shown (just total)
 • `ul` - Unsorted dice results
+• `drama` - Suspenseful reveal, one dice group at a time
+• `pemdas` - Math with operator precedence and parentheses: `pemdas (2d6 + 3) * 2`
+• `e` - Show the result as an embed (green for crits, red for fumbles)
+• `gm` - Secret roll: only the server's GMs get the result, by DM
+• `full` - Never shorten long results; send them over several messages
+• `fr`/`de`/`es`/`it`/`pt` - Result keywords in that language
+• `tally:<name>` - Add the result to a `/tally` in this channel
+• `clock:<name>` - Tick a `/clock` from the roll's outcome
 
 **Examples:**
 • `/roll 10d6 e6 k8 +4` - Roll 10d6, explode 6s, keep 8 highest, add 4
 • `/roll 6 4d6` - Roll 6 sets of 4d6
 • `/roll 4d100 ; 3d10 k2` - Multiple separate rolls
-
-Type `/roll help alias` for game system shortcuts!"#
+• `/roll I hit [[1d20+5]] for [[2d6]]` - Rolls inside a sentence
+• `/roll split 8d6 t4 into 3/5` - Split one pool into two
+• `/roll 4*7+2` or `/calc 4*7+2` - Math without dice
+• `/roll 2d{yes,no,maybe}` or `/roll coin` - Dice with labeled faces
+• `/odds 4d6 k3 target:15` - The chances of a roll, without rolling it
+• `/roll last +2` or `/again +2` - Your last roll again, plus 2
+
+Type `/roll help alias` for game system shortcuts, or `/help modifier:ie` to look up one modifier!"#
         .to_string()
 }
 
+/// The basic help in `language`, for `/help` from a translated Discord client
+pub fn generate_basic_help_in(language: Language) -> String {
+    match language {
+        Language::English => generate_basic_help(),
+        Language::French => BASIC_HELP_FRENCH.to_string(),
+        Language::German => BASIC_HELP_GERMAN.to_string(),
+        Language::Spanish => BASIC_HELP_SPANISH.to_string(),
+        Language::Italian => BASIC_HELP_ITALIAN.to_string(),
+        Language::Portuguese => BASIC_HELP_PORTUGUESE.to_string(),
+    }
+}
+
+const BASIC_HELP_FRENCH: &str = r#"🎲 **Dice Maiden** 🎲
+
+**Remarque :**
+• Plus d'aide est disponible sur GitHub `https://github.com/Humblemonk/dicemaiden-rs`
+• Si vous rencontrez un bug, signalez-le sur GitHub !
+
+**Utilisation de base :**
+`/roll 2d6 + 3d10` - Lance deux dés à six faces et trois dés à dix faces
+`/roll 3d6 + 5` - Lance trois dés à six faces et ajoute cinq
+`/roll 4d6 k3` - Lance quatre dés à six faces et garde les 3 meilleurs
+
+**Modificateurs :**
+• `e6` ou `e` - Explose sur les 6 (ou la valeur max)
+• `ie6` - Explose indéfiniment sur les 6
+• `d2` - Retire les 2 plus bas
+• `k3` - Garde les 3 plus hauts
+• `kl2` - Garde les 2 plus bas
+• `km2` - Garde les 2 du milieu
+• `rg3` - Relance les dés ≥ 3
+• `irg3` - Relance les ≥ 3 indéfiniment
+• `r2` - Relance une fois les dés ≤ 2
+• `ir2` - Relance indéfiniment les dés ≤ 2
+• `t7` - Compte les succès (≥ 7)
+• `t4ds6` - Compte les succès (≥ 4), double succès sur 6 (par défaut, la cible)
+• `tl6` - Compte les succès (≤ 6)
+• `tl6ds4` - Compte les succès (≤ 6), double succès sur 4 (par défaut, la cible)
+• `t8 rfail` - Relance une fois les dés qui ont raté la cible
+• `t7 maxs5` - Compte au plus 5 succès
+• `f1` - Compte les échecs (≤ 1)
+• `b1` - Compte les échecs critiques (≤ 1)
+
+**Options spéciales :**
+• `p` - Jet privé (vous seul voyez le résultat)
+• `s` - Affichage simple (sans le détail des dés)
+• `nr` - Pas de détail (seulement le total)
+• `ul` - Dés non triés
+• `drama` - Révélation à suspense, un groupe de dés à la fois
+• `pemdas` - Priorité des opérations et parenthèses : `pemdas (2d6 + 3) * 2`
+• `e` - Résultat en embed (vert pour les critiques, rouge pour les échecs critiques)
+• `gm` - Jet secret : seuls les MJ du serveur reçoivent le résultat, en MP
+• `full` - Ne jamais raccourcir un long résultat : l'envoyer en plusieurs messages
+• `fr`/`de`/`es`/`it`/`pt` - Mots du résultat dans cette langue
+• `tally:<nom>` - Ajoute le résultat à un `/tally` de ce salon
+• `clock:<nom>` - Avance une `/clock` selon l'issue du jet
+
+**Exemples :**
+• `/roll 10d6 e6 k8 +4` - Lance 10d6, explose les 6, garde les 8 meilleurs, ajoute 4
+• `/roll 6 4d6` - Lance 6 fois 4d6
+• `/roll 4d100 ; 3d10 k2` - Plusieurs jets séparés
+• `/roll Je touche [[1d20+5]] pour [[2d6]]` - Des jets dans une phrase
+• `/roll split 8d6 t4 into 3/5` - Partage une réserve en deux
+• `/roll 4*7+2` ou `/calc 4*7+2` - Calcul sans dés
+• `/roll 2d{oui,non,peut-être}` ou `/roll coin` - Dés à faces nommées
+• `/odds 4d6 k3 target:15` - Les chances d'un jet, sans le lancer
+• `/roll last +2` ou `/again +2` - Votre dernier jet, plus 2
+
+Tapez `/roll help alias` pour les raccourcis des systèmes de jeu, ou `/help modifier:ie` pour un modificateur précis !"#;
+
+const BASIC_HELP_GERMAN: &str = r#"🎲 **Dice Maiden** 🎲
+
+**Hinweis:**
+• Weitere Hilfe gibt es auf GitHub `https://github.com/Humblemonk/dicemaiden-rs`
+• Wenn du einen Fehler findest, melde ihn bitte auf GitHub!
+
+**Grundlagen:**
+`/roll 2d6 + 3d10` - Zwei sechsseitige und drei zehnseitige Würfel werfen
+`/roll 3d6 + 5` - Drei sechsseitige Würfel werfen und fünf addieren
+`/roll 4d6 k3` - Vier sechsseitige Würfel werfen und die höchsten 3 behalten
+
+**Modifikatoren:**
+• `e6` oder `e` - Bei 6 (oder dem Höchstwert) explodieren
+• `ie6` - Bei 6 unbegrenzt explodieren
+• `d2` - Die niedrigsten 2 Würfel streichen
+• `k3` - Die höchsten 3 Würfel behalten
+• `kl2` - Die niedrigsten 2 Würfel behalten
+• `km2` - Die mittleren 2 Würfel behalten
+• `rg3` - Würfel ≥ 3 neu werfen
+• `irg3` - Würfel ≥ 3 unbegrenzt neu werfen
+• `r2` - Würfel ≤ 2 einmal neu werfen
+• `ir2` - Würfel ≤ 2 unbegrenzt neu werfen
+• `t7` - Erfolge zählen (≥ 7)
+• `t4ds6` - Erfolge zählen (≥ 4), doppelter Erfolg bei 6 (Standard: das Ziel)
+• `tl6` - Erfolge zählen (≤ 6)
+• `tl6ds4` - Erfolge zählen (≤ 6), doppelter Erfolg bei 4 (Standard: das Ziel)
+• `t8 rfail` - Würfel, die das Ziel verfehlt haben, einmal neu werfen
+• `t7 maxs5` - Höchstens 5 Erfolge zählen
+• `f1` - Fehlschläge zählen (≤ 1)
+• `b1` - Patzer zählen (≤ 1)
+
+**Besondere Optionen:**
+• `p` - Privater Wurf (nur du siehst das Ergebnis)
+• `s` - Einfache Ausgabe (ohne Aufschlüsselung der Würfel)
+• `nr` - Keine Einzelergebnisse (nur die Summe)
+• `ul` - Unsortierte Würfel
+• `drama` - Spannende Enthüllung, eine Würfelgruppe nach der anderen
+• `pemdas` - Punkt vor Strich und Klammern: `pemdas (2d6 + 3) * 2`
+• `e` - Ergebnis als Embed (grün bei kritischen Erfolgen, rot bei Patzern)
+• `gm` - Geheimer Wurf: nur die SL des Servers bekommen das Ergebnis per DM
+• `full` - Lange Ergebnisse nie kürzen, sondern auf mehrere Nachrichten verteilen
+• `fr`/`de`/`es`/`it`/`pt` - Ergebniswörter in dieser Sprache
+• `tally:<name>` - Das Ergebnis zu einem `/tally` in diesem Kanal addieren
+• `clock:<name>` - Eine `/clock` je nach Ausgang des Wurfs weiterdrehen
+
+**Beispiele:**
+• `/roll 10d6 e6 k8 +4` - 10d6 werfen, 6er explodieren, die höchsten 8 behalten, 4 addieren
+• `/roll 6 4d6` - 6 Sätze 4d6 werfen
+• `/roll 4d100 ; 3d10 k2` - Mehrere getrennte Würfe
+• `/roll Ich treffe [[1d20+5]] für [[2d6]]` - Würfe in einem Satz
+• `/roll split 8d6 t4 into 3/5` - Einen Pool in zwei teilen
+• `/roll 4*7+2` oder `/calc 4*7+2` - Rechnen ohne Würfel
+• `/roll 2d{ja,nein,vielleicht}` oder `/roll coin` - Würfel mit beschrifteten Seiten
+• `/odds 4d6 k3 target:15` - Die Chancen eines Wurfs, ohne zu würfeln
+• `/roll last +2` oder `/again +2` - Dein letzter Wurf noch einmal, plus 2
+
+Gib `/roll help alias` für Kürzel der Spielsysteme ein, oder `/help modifier:ie` für einen einzelnen Modifikator!"#;
+
+const BASIC_HELP_SPANISH: &str = r#"🎲 **Dice Maiden** 🎲
+
+**Nota:**
+• Hay más ayuda en GitHub `https://github.com/Humblemonk/dicemaiden-rs`
+• Si encuentras un error, ¡infórmalo en GitHub!
+
+**Uso básico:**
+`/roll 2d6 + 3d10` - Tira dos dados de seis caras y tres de diez caras
+`/roll 3d6 + 5` - Tira tres dados de seis caras y suma cinco
+`/roll 4d6 k3` - Tira cuatro dados de seis caras y conserva los 3 más altos
+
+**Modificadores:**
+• `e6` o `e` - Explota con 6 (o el valor máximo)
+• `ie6` - Explota indefinidamente con 6
+• `d2` - Descarta los 2 dados más bajos
+• `k3` - Conserva los 3 dados más altos
+• `kl2` - Conserva los 2 dados más bajos
+• `km2` - Conserva los 2 dados del medio
+• `rg3` - Repite los dados ≥ 3
+• `irg3` - Repite los ≥ 3 indefinidamente
+• `r2` - Repite una vez los dados ≤ 2
+• `ir2` - Repite indefinidamente los dados ≤ 2
+• `t7` - Cuenta éxitos (≥ 7)
+• `t4ds6` - Cuenta éxitos (≥ 4), éxito doble con 6 (por defecto, el objetivo)
+• `tl6` - Cuenta éxitos (≤ 6)
+• `tl6ds4` - Cuenta éxitos (≤ 6), éxito doble con 4 (por defecto, el objetivo)
+• `t8 rfail` - Repite una vez los dados que no alcanzaron el objetivo
+• `t7 maxs5` - Cuenta como máximo 5 éxitos
+• `f1` - Cuenta fallos (≤ 1)
+• `b1` - Cuenta pifias (≤ 1)
+
+**Opciones especiales:**
+• `p` - Tirada privada (solo tú ves el resultado)
+• `s` - Salida simple (sin desglose de dados)
+• `nr` - Sin desglose (solo el total)
+• `ul` - Dados sin ordenar
+• `drama` - Revelación con suspense, un grupo de dados cada vez
+• `pemdas` - Precedencia de operadores y paréntesis: `pemdas (2d6 + 3) * 2`
+• `e` - Resultado como embed (verde para críticos, rojo para pifias)
+• `gm` - Tirada secreta: solo los DJ del servidor reciben el resultado, por MD
+• `full` - No acortar nunca un resultado largo: enviarlo en varios mensajes
+• `fr`/`de`/`es`/`it`/`pt` - Palabras del resultado en ese idioma
+• `tally:<nombre>` - Suma el resultado a un `/tally` de este canal
+• `clock:<nombre>` - Avanza un `/clock` según el resultado de la tirada
+
+**Ejemplos:**
+• `/roll 10d6 e6 k8 +4` - Tira 10d6, explota los 6, conserva los 8 más altos, suma 4
+• `/roll 6 4d6` - Tira 6 veces 4d6
+• `/roll 4d100 ; 3d10 k2` - Varias tiradas separadas
+• `/roll Golpeo [[1d20+5]] por [[2d6]]` - Tiradas dentro de una frase
+• `/roll split 8d6 t4 into 3/5` - Divide una reserva en dos
+• `/roll 4*7+2` o `/calc 4*7+2` - Cálculos sin dados
+• `/roll 2d{sí,no,quizás}` o `/roll coin` - Dados con caras con nombre
+• `/odds 4d6 k3 target:15` - Las probabilidades de una tirada, sin tirarla
+• `/roll last +2` o `/again +2` - Tu última tirada otra vez, más 2
+
+¡Escribe `/roll help alias` para los atajos de sistemas de juego, o `/help modifier:ie` para consultar un modificador!"#;
+
+const BASIC_HELP_ITALIAN: &str = r#"🎲 **Dice Maiden** 🎲
+
+**Nota:**
+• Altro aiuto è disponibile su GitHub `https://github.com/Humblemonk/dicemaiden-rs`
+• Se trovi un bug, segnalalo su GitHub!
+
+**Uso di base:**
+`/roll 2d6 + 3d10` - Tira due dadi a sei facce e tre dadi a dieci facce
+`/roll 3d6 + 5` - Tira tre dadi a sei facce e aggiungi cinque
+`/roll 4d6 k3` - Tira quattro dadi a sei facce e tieni i 3 più alti
+
+**Modificatori:**
+• `e6` o `e` - Esplode sui 6 (o sul valore massimo)
+• `ie6` - Esplode all'infinito sui 6
+• `d2` - Scarta i 2 dadi più bassi
+• `k3` - Tiene i 3 dadi più alti
+• `kl2` - Tiene i 2 dadi più bassi
+• `km2` - Tiene i 2 dadi centrali
+• `rg3` - Ritira i dadi ≥ 3
+• `irg3` - Ritira i ≥ 3 all'infinito
+• `r2` - Ritira una volta i dadi ≤ 2
+• `ir2` - Ritira all'infinito i dadi ≤ 2
+• `t7` - Conta i successi (≥ 7)
+• `t4ds6` - Conta i successi (≥ 4), successo doppio sul 6 (predefinito: l'obiettivo)
+• `tl6` - Conta i successi (≤ 6)
+• `tl6ds4` - Conta i successi (≤ 6), successo doppio sul 4 (predefinito: l'obiettivo)
+• `t8 rfail` - Ritira una volta i dadi che hanno mancato l'obiettivo
+• `t7 maxs5` - Conta al massimo 5 successi
+• `f1` - Conta i fallimenti (≤ 1)
+• `b1` - Conta i fallimenti critici (≤ 1)
+
+**Opzioni speciali:**
+• `p` - Tiro privato (solo tu vedi il risultato)
+• `s` - Output semplice (senza il dettaglio dei dadi)
+• `nr` - Nessun dettaglio (solo il totale)
+• `ul` - Dadi non ordinati
+• `drama` - Rivelazione con suspense, un gruppo di dadi alla volta
+• `pemdas` - Precedenza degli operatori e parentesi: `pemdas (2d6 + 3) * 2`
+• `e` - Risultato come embed (verde per i critici, rosso per i fallimenti critici)
+• `gm` - Tiro segreto: solo i master del server ricevono il risultato, in DM
+• `full` - Non accorciare mai un risultato lungo: inviarlo in più messaggi
+• `fr`/`de`/`es`/`it`/`pt` - Parole del risultato in quella lingua
+• `tally:<nome>` - Aggiunge il risultato a un `/tally` di questo canale
+• `clock:<nome>` - Fa avanzare un `/clock` in base all'esito del tiro
+
+**Esempi:**
+• `/roll 10d6 e6 k8 +4` - Tira 10d6, esplode sui 6, tiene gli 8 più alti, aggiunge 4
+• `/roll 6 4d6` - Tira 6 volte 4d6
+• `/roll 4d100 ; 3d10 k2` - Più tiri separati
+• `/roll Colpisco [[1d20+5]] per [[2d6]]` - Tiri dentro una frase
+• `/roll split 8d6 t4 into 3/5` - Divide una riserva in due
+• `/roll 4*7+2` o `/calc 4*7+2` - Calcoli senza dadi
+• `/roll 2d{sì,no,forse}` o `/roll coin` - Dadi con facce etichettate
+• `/odds 4d6 k3 target:15` - Le probabilità di un tiro, senza tirarlo
+• `/roll last +2` o `/again +2` - Il tuo ultimo tiro di nuovo, più 2
+
+Scrivi `/roll help alias` per le scorciatoie dei sistemi di gioco, o `/help modifier:ie` per un singolo modificatore!"#;
+
+const BASIC_HELP_PORTUGUESE: &str = r#"🎲 **Dice Maiden** 🎲
+
+**Observação:**
+• Mais ajuda está disponível no GitHub `https://github.com/Humblemonk/dicemaiden-rs`
+• Se encontrar um bug, relate no GitHub!
+
+**Uso básico:**
+`/roll 2d6 + 3d10` - Rola dois dados de seis lados e três de dez lados
+`/roll 3d6 + 5` - Rola três dados de seis lados e soma cinco
+`/roll 4d6 k3` - Rola quatro dados de seis lados e mantém os 3 maiores
+
+**Modificadores:**
+• `e6` ou `e` - Explode nos 6 (ou no valor máximo)
+• `ie6` - Explode indefinidamente nos 6
+• `d2` - Descarta os 2 dados menores
+• `k3` - Mantém os 3 dados maiores
+• `kl2` - Mantém os 2 dados menores
+• `km2` - Mantém os 2 dados do meio
+• `rg3` - Rerrola os dados ≥ 3
+• `irg3` - Rerrola os ≥ 3 indefinidamente
+• `r2` - Rerrola uma vez os dados ≤ 2
+• `ir2` - Rerrola indefinidamente os dados ≤ 2
+• `t7` - Conta sucessos (≥ 7)
+• `t4ds6` - Conta sucessos (≥ 4), sucesso duplo no 6 (por padrão, o alvo)
+• `tl6` - Conta sucessos (≤ 6)
+• `tl6ds4` - Conta sucessos (≤ 6), sucesso duplo no 4 (por padrão, o alvo)
+• `t8 rfail` - Rerrola uma vez os dados que erraram o alvo
+• `t7 maxs5` - Conta no máximo 5 sucessos
+• `f1` - Conta falhas (≤ 1)
+• `b1` - Conta falhas críticas (≤ 1)
+
+**Opções especiais:**
+• `p` - Rolagem privada (só você vê o resultado)
+• `s` - Saída simples (sem o detalhe dos dados)
+• `nr` - Sem detalhe (só o total)
+• `ul` - Dados sem ordenar
+• `drama` - Revelação com suspense, um grupo de dados por vez
+• `pemdas` - Precedência de operadores e parênteses: `pemdas (2d6 + 3) * 2`
+• `e` - Resultado como embed (verde para críticos, vermelho para falhas críticas)
+• `gm` - Rolagem secreta: só os mestres do servidor recebem o resultado, por DM
+• `full` - Nunca encurtar um resultado longo: enviá-lo em várias mensagens
+• `fr`/`de`/`es`/`it`/`pt` - Palavras do resultado nesse idioma
+• `tally:<nome>` - Soma o resultado a um `/tally` deste canal
+• `clock:<nome>` - Avança um `/clock` conforme o resultado da rolagem
+
+**Exemplos:**
+• `/roll 10d6 e6 k8 +4` - Rola 10d6, explode os 6, mantém os 8 maiores, soma 4
+• `/roll 6 4d6` - Rola 6 vezes 4d6
+• `/roll 4d100 ; 3d10 k2` - Várias rolagens separadas
+• `/roll Eu acerto [[1d20+5]] causando [[2d6]]` - Rolagens dentro de uma frase
+• `/roll split 8d6 t4 into 3/5` - Divide uma parada em duas
+• `/roll 4*7+2` ou `/calc 4*7+2` - Contas sem dados
+• `/roll 2d{sim,não,talvez}` ou `/roll coin` - Dados com faces nomeadas
+• `/odds 4d6 k3 target:15` - As chances de uma rolagem, sem rolá-la
+• `/roll last +2` ou `/again +2` - Sua última rolagem de novo, mais 2
+
+Digite `/roll help alias` para os atalhos de sistemas de jogo, ou `/help modifier:ie` para consultar um modificador!"#;
+
 pub fn generate_alias_help() -> String {
     r#"🎲 **Game System Aliases** 🎲
 
@@ -95,7 +416,7 @@ pub fn generate_alias_help() -> String {
 • `6yz` → 6d6 t6 (Year Zero)
 • `age` → 2d6 + 1d6 (AGE system)
 • `dd34` → 1d3*10 + 1d4 (double-digit d66 style)
-• `ed15` → Earthdawn step 15
+• `ed15` → Earthdawn step 15 (`ed15 k8` adds a d8 karma die)
 • `cs 3` → Cypher System 1d20 cs3 (Level 3 task, target 9+)
 • `cpr` → Cyberpunk Red 1d10 cpr (critical success on 10, critical failure on 1)
 • `conan3` → 3d20 conan (3d20 skill roll)
@@ -307,3 +628,90 @@ When rolling with disadvantage, the worse roll is selected by priority:
 Use `/help` for basic syntax and `/help alias` for more game systems!"#
         .to_string()
 }
+
+pub fn generate_about_help() -> String {
+    let version = crate::version::engine_version();
+    format!(
+        r#"🎲 **About Dice Maiden** 🎲
+
+**Dice engine:** `{version}`
+• Bot version: {}
+• Grammar revision: {}
+• Memory allocator: {}
+
+The grammar revision changes whenever the bot reads some roll differently, so quote the whole engine version when reporting a roll that came out wrong.
+• `/settings version` - Show the engine version under every roll in this server (administrators only)
+• Developers can read it from the HTTP roll API at `GET /version`
+
+Dice Maiden is open source: https://github.com/Humblemonk/dicemaiden-rs"#,
+        version.crate_version,
+        version.grammar_revision,
+        crate::allocator::name()
+    )
+}
+
+pub fn generate_privacy_help() -> String {
+    r#"🎲 **Dice Maiden Privacy & Data** 🎲
+
+**What is stored:**
+• Server tallies, progress clocks, initiative orders and card decks created with `/tally`, `/clock`, `/init` and `/draw`
+• Server settings chosen with `/setup`, macro packs enabled with `/macropack`, `/alias` shorthand, `/keyword` rules and `/followup` buttons
+• Character sheet stats you save with `/sheet` and macros you save with `/macro` (until you remove them)
+• Running `/session`s: the channel, the seed and who started it (until the session ends)
+• With `/settings fair`: the server's fair-roll seeds, their hashes and roll counts
+• How many of your first rolls in each server got a tip, and whether you turned tips off with `/tips`
+• Your roll preferences from `/prefs` (until you `/prefs reset`)
+• Your public rolls' count, natural 20s and 1s and total in each server for `/stats` (turn it off and delete them with `/stats tracking enabled:false`)
+• Your recent rolls (kept for 30 days), at the level chosen below, with whether each check succeeded for streaks and the `/init` round it was made in (GMs can list them with `/history channel`; download yours with `/history export`). If the server set a `/config log-drain`, the same is also sent to its administrators' endpoint
+• Your last roll in each server, in memory only, for `/again` (until the bot restarts)
+• Which roller may use each roll's follow-up buttons, in memory only (until the bot restarts)
+• Anonymous bot statistics (server counts, memory usage, how often each game system alias is rolled)
+
+**Roll history:**
+• `/privacy history:<off|totals|full>` - Record nothing, only totals, or full rolls (default: full)
+• `/config history level:<off|totals|full>` - Set the level for this server (administrators only)
+The stricter of your level and the server's applies, and private (`p`) and secret `gm` rolls are never recorded.
+• `/mystats export` - Download your recorded rolls' statistics as CSV files
+
+**Deleting your data:**
+• `/forgetme` - Delete everything Dice Maiden stores about you, in every server
+• `/config erase-data` - Delete everything Dice Maiden stores for this server (administrators only)
+
+Both commands ask you to confirm with a button within 2 minutes before anything is deleted, and deletions cannot be undone. Each deletion is recorded in an audit log containing only the IDs involved and the number of records removed.
+
+See the privacy policy on GitHub `https://github.com/Humblemonk/dicemaiden-rs` for details."#
+        .to_string()
+}
+
+/// Help for one modifier, e.g. `ie6`, from the modifier registry
+pub fn generate_modifier_help(query: &str) -> String {
+    let Some(doc) = modifier_docs::lookup(query) else {
+        let known = MODIFIER_DOCS
+            .iter()
+            .map(|doc| format!("`{}`", doc.token))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return format!(
+            "❌ No modifier matches `{}`. Try one of: {known}",
+            query.trim()
+        );
+    };
+
+    let mut output = format!(
+        "🎲 **{}** (`{}`) 🎲\n\n{}\n\n**Examples:**",
+        doc.name, doc.syntax, doc.summary
+    );
+    for (expression, description) in doc.examples {
+        output.push_str(&format!("\n• `/roll {expression}` - {description}"));
+    }
+    if !doc.see_also.is_empty() {
+        let related = doc
+            .see_also
+            .iter()
+            .map(|token| format!("`{token}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        output.push_str(&format!("\n\n**See also:** {related}"));
+    }
+    output
+}
@@ -0,0 +1,150 @@
+//! Translations of the keywords in roll output.
+//!
+//! A roll that starts with a language code is shown with that language's
+//! keywords, e.g. `/roll fr 4d6 t5 ! Attaque` gives
+//! "Jet: `[6, 5, 2, 1]` = **2** succès Raison: `Attaque`".  Only the fixed
+//! words around the result are translated; dice, numbers, labels, comments
+//! and game-system outcomes (such as Forged in the Dark's `CRITICAL SUCCESS`)
+//! are shown as they are.
+//!
+//! | Code | Language   |
+//! |------|------------|
+//! | `en` | English    |
+//! | `fr` | French     |
+//! | `de` | German     |
+//! | `es` | Spanish    |
+//! | `it` | Italian    |
+//! | `pt` | Portuguese |
+//!
+//! The language is a per-roll flag like `p` or `s` (see `parser::parse_flags`)
+//! and is carried on [`DiceRoll`](crate::DiceRoll) and
+//! [`RollResult`](crate::RollResult), where the formatters look it up.
+
+/// Language of a roll's output keywords
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+    German,
+    Spanish,
+    Italian,
+    Portuguese,
+}
+
+/// The fixed words of roll output in one language
+#[derive(Debug, PartialEq, Eq)]
+pub struct Keywords {
+    pub roll: &'static str,
+    pub reason: &'static str,
+    pub request: &'static str,
+    pub successes: &'static str,
+    pub failures: &'static str,
+    pub botches: &'static str,
+    pub total: &'static str,
+}
+
+/// Language flag codes, in the order they're listed in help
+pub const LANGUAGE_CODES: [(&str, Language); 6] = [
+    ("en", Language::English),
+    ("fr", Language::French),
+    ("de", Language::German),
+    ("es", Language::Spanish),
+    ("it", Language::Italian),
+    ("pt", Language::Portuguese),
+];
+
+const ENGLISH: Keywords = Keywords {
+    roll: "Roll",
+    reason: "Reason",
+    request: "Request",
+    successes: "successes",
+    failures: "failures",
+    botches: "botches",
+    total: "Total",
+};
+
+const FRENCH: Keywords = Keywords {
+    roll: "Jet",
+    reason: "Raison",
+    request: "Demande",
+    successes: "succès",
+    failures: "échecs",
+    botches: "échecs critiques",
+    total: "Total",
+};
+
+const GERMAN: Keywords = Keywords {
+    roll: "Wurf",
+    reason: "Grund",
+    request: "Anfrage",
+    successes: "Erfolge",
+    failures: "Fehlschläge",
+    botches: "Patzer",
+    total: "Summe",
+};
+
+const SPANISH: Keywords = Keywords {
+    roll: "Tirada",
+    reason: "Motivo",
+    request: "Petición",
+    successes: "éxitos",
+    failures: "fallos",
+    botches: "pifias",
+    total: "Total",
+};
+
+const ITALIAN: Keywords = Keywords {
+    roll: "Tiro",
+    reason: "Motivo",
+    request: "Richiesta",
+    successes: "successi",
+    failures: "fallimenti",
+    botches: "fallimenti critici",
+    total: "Totale",
+};
+
+const PORTUGUESE: Keywords = Keywords {
+    roll: "Rolagem",
+    reason: "Motivo",
+    request: "Pedido",
+    successes: "sucessos",
+    failures: "falhas",
+    botches: "falhas críticas",
+    total: "Total",
+};
+
+impl Language {
+    /// The language for a flag code such as `fr`
+    pub fn from_code(code: &str) -> Option<Self> {
+        LANGUAGE_CODES
+            .iter()
+            .find(|(known, _)| *known == code)
+            .map(|(_, language)| *language)
+    }
+
+    pub fn code(self) -> &'static str {
+        LANGUAGE_CODES
+            .iter()
+            .find(|(_, language)| *language == self)
+            .map_or("en", |(code, _)| code)
+    }
+
+    pub fn keywords(self) -> &'static Keywords {
+        match self {
+            Language::English => &ENGLISH,
+            Language::French => &FRENCH,
+            Language::German => &GERMAN,
+            Language::Spanish => &SPANISH,
+            Language::Italian => &ITALIAN,
+            Language::Portuguese => &PORTUGUESE,
+        }
+    }
+}
+
+// Serialized as its flag code, e.g. `"fr"`
+impl serde::Serialize for Language {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
@@ -9,35 +9,84 @@
 //!
 //! ```text
 //! dicemaiden_rs
-//! ├── commands/        Discord slash-command handlers (roll, help, purge)
-//! ├── database         SQLite statistics persistence
+//! ├── allocator      Global allocator picked by the `mimalloc` / `jemalloc` features
+//! ├── api            HTTP `POST /roll` server (`api` feature)
+//! ├── commands/        Discord slash-command handlers (roll, again, calc, odds, help,
+//! │                    purge, confirm, tally, clock, config, forgetme, privacy, prefs, mystats,
+//! │                    stats, usage, admin, debugparse, setup, macropack, alias, macro_cmd,
+//! │                    keyword, followup, settings, sheet, char_cmd, session, verify, table,
+//! │                    init, history, tips, draw, reroll_select, text_prefix, token_store)
+//! ├── database         SQLite/PostgreSQL statistics, channel state and roll history persistence
+//! ├── deck.rs          Card decks and initiative draws for `/draw`
 //! ├── dice/            Core dice engine
 //! │   ├── mod.rs       Types: DiceRoll, RollResult, Modifier, DiceGroup
 //! │   ├── aliases.rs   Game-system alias expansion
+//! │   ├── cache.rs     Parsed-expression cache for repeated rolls
+//! │   ├── error.rs     DiceError, returned by the parser and roller
+//! │   ├── modifier_docs.rs  Modifier reference for `/help modifier`
 //! │   ├── parser.rs    Text → Vec<DiceRoll>
+//! │   ├── probability.rs  Exact and simulated distributions for `/odds`
 //! │   ├── roller.rs    Vec<DiceRoll> → Vec<RollResult>
 //! │   ├── roll.rs      RollResult → Discord message string
+//! │   ├── trackers.rs  Hunger / stress / momentum sheet trackers
 //! │   └── rng.rs       Enhanced RNG seeding
-//! └── help_text.rs     Static help message generators
+//! ├── help_text.rs     Static help message generators
+//! ├── i18n.rs          Translated roll output keywords (`fr 2d6`)
+//! ├── locale.rs        Translated command descriptions, basic help and errors by Discord locale
+//! ├── log_drain.rs     Signed roll batches posted to a server's HTTPS endpoint
+//! ├── metrics.rs       Roll and command timing histograms, write counters
+//! ├── middleware.rs    Interaction pipeline (dedupe, rate limit, permissions, metrics)
+//! ├── outbound.rs      Discord send retries and background send queue
+//! ├── presence.rs      Rotating bot status messages
+//! ├── selftest.rs      Golden-output engine checks for `/admin selftest`
+//! ├── stats_export.rs  CSV roll statistics for `/mystats export` and `/admin stats export`
+//! ├── sync.rs          Poison-tolerant `lock` for shared in-memory state
+//! ├── tables.rs        Random tables rolled with `/table`
+//! ├── templates.rs     Roll templates and shareable export codes
+//! ├── testkit.rs       Builders and assertions for tests (`testkit` feature)
+//! ├── version.rs       Dice engine version for `/help about`, the API and roll footers
+//! └── write_queue.rs   Background database writes with retries
 //! ```
 //!
 //! # Re-exports
 //!
 //! The most-used types (`DiceRoll`, `RollResult`, `Modifier`, `DiceGroup`,
-//! `parse_and_roll`, `format_multiple_results`) are re-exported from the crate
+//! `DiceError`, `parse_and_roll`, `format_multiple_results`) are re-exported from the crate
 //! root for convenience in tests and external consumers.
 //!
-//! [`ShardManagerContainer`] and [`DatabaseContainer`] are Serenity
-//! [`TypeMapKey`] wrappers that allow the shard manager and database handles to
-//! be stored in, and retrieved from, the Serenity shared data map.
+//! [`ShardManagerContainer`], [`DatabaseContainer`], [`OutboundQueueContainer`],
+//! [`WriteQueueContainer`], [`LogDrainContainer`], [`MetricsContainer`], [`LastRollContainer`], [`QuickActionContainer`],
+//! [`SelectedRerollContainer`], [`PresenceContainer`] and [`OwnersContainer`] are Serenity [`TypeMapKey`]
+//! wrappers that allow the shared handles to be stored in, and retrieved from,
+//! the Serenity data map.
 
+pub mod allocator;
+#[cfg(feature = "api")]
+pub mod api;
 pub mod commands;
 pub mod database;
+pub mod deck;
 pub mod dice;
 pub mod help_text;
+pub mod i18n;
+pub mod locale;
+pub mod log_drain;
+pub mod metrics;
+pub mod middleware;
+pub mod outbound;
+pub mod presence;
+pub mod selftest;
+pub mod stats_export;
+pub(crate) mod sync;
+pub mod tables;
+pub mod templates;
+#[cfg(any(test, feature = "testkit"))]
+pub mod testkit;
+pub mod version;
+pub mod write_queue;
 
-use serenity::prelude::*;
-use std::sync::Arc;
+use serenity::{model::id::UserId, prelude::*};
+use std::{collections::HashSet, sync::Arc};
 
 // Move these type map keys from main.rs to lib.rs so they can be shared
 pub struct ShardManagerContainer;
@@ -52,10 +101,72 @@ impl TypeMapKey for DatabaseContainer {
     type Value = Arc<database::Database>;
 }
 
+pub struct OutboundQueueContainer;
+
+impl TypeMapKey for OutboundQueueContainer {
+    type Value = outbound::OutboundQueue;
+}
+
+// Roll history, usage counters and audit rows waiting to be written
+pub struct WriteQueueContainer;
+
+impl TypeMapKey for WriteQueueContainer {
+    type Value = write_queue::WriteQueue;
+}
+
+// Per-server roll log drains, fed by `commands::privacy::record_roll`
+pub struct LogDrainContainer;
+
+impl TypeMapKey for LogDrainContainer {
+    type Value = log_drain::LogDrains;
+}
+
+// Roll and command timing histograms, reported by `/usage`
+pub struct MetricsContainer;
+
+impl TypeMapKey for MetricsContainer {
+    type Value = Arc<metrics::Metrics>;
+}
+
+// Each user's last roll per server, for `/again`
+pub struct LastRollContainer;
+
+impl TypeMapKey for LastRollContainer {
+    type Value = Arc<commands::again::LastRolls>;
+}
+
+// Quick-action buttons of recent rolls, for `/followup`
+pub struct QuickActionContainer;
+
+impl TypeMapKey for QuickActionContainer {
+    type Value = Arc<commands::followup::QuickActions>;
+}
+
+// Rolls behind recent Reroll selected menus
+pub struct SelectedRerollContainer;
+
+impl TypeMapKey for SelectedRerollContainer {
+    type Value = Arc<commands::reroll_select::SelectedRerolls>;
+}
+
+// Current status rotation message, shown by shards as they connect
+pub struct PresenceContainer;
+
+impl TypeMapKey for PresenceContainer {
+    type Value = Arc<presence::Presence>;
+}
+
+// Application owner (or team members), for owner-only commands
+pub struct OwnersContainer;
+
+impl TypeMapKey for OwnersContainer {
+    type Value = Arc<HashSet<UserId>>;
+}
+
 // Re-export commonly used items for easier testing
 pub use dice::{
-    DiceGroup, DiceRoll, HeroSystemType, Modifier, RollResult, format_multiple_results,
-    format_multiple_results_with_limit, parse_and_roll,
+    DiceError, DiceGroup, DiceRoll, HeroSystemType, Modifier, RollResult, format_multiple_results,
+    format_multiple_results_with_limit, format_reveal_frames, parse_and_roll,
 };
 
 // Re-export dice submodules for testing
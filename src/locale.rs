@@ -0,0 +1,503 @@
+//! Translations of the bot's own text: command descriptions, the basic
+//! `/help` page and common error messages.
+//!
+//! Discord reports the locale of the user's client with every interaction
+//! (and a community server's locale, if it has one).  [`language_for`]
+//! picks the first of those that has a translation, so a French client gets
+//! French help and errors in any server; otherwise the text is English.
+//! Slash command descriptions are registered with a translation per Discord
+//! locale ([`localize_command`]), and Discord shows the one matching each
+//! user's client.
+//!
+//! | Language   | Discord locales     |
+//! |------------|---------------------|
+//! | French     | `fr`                |
+//! | German     | `de`                |
+//! | Spanish    | `es-ES`, `es-419`   |
+//! | Italian    | `it`                |
+//! | Portuguese | `pt-BR`             |
+//!
+//! Roll output is not affected: its keywords follow the roll's language flag
+//! (`/roll fr 2d6`), see [`i18n`](crate::i18n).  The help text itself lives
+//! in `help_text.rs`.
+
+use crate::i18n::Language;
+use serenity::builder::CreateCommand;
+
+/// Discord locales of each translated language
+pub const DISCORD_LOCALES: [(&str, Language); 6] = [
+    ("fr", Language::French),
+    ("de", Language::German),
+    ("es-ES", Language::Spanish),
+    ("es-419", Language::Spanish),
+    ("it", Language::Italian),
+    ("pt-BR", Language::Portuguese),
+];
+
+/// Most characters Discord allows in a command description
+pub const MAX_DESCRIPTION_LENGTH: usize = 100;
+
+/// Common error messages in one language
+#[derive(Debug, PartialEq, Eq)]
+pub struct Messages {
+    pub rate_limited: &'static str,
+    pub servers_only: &'static str,
+    pub administrator_only: &'static str,
+    pub command_failed: &'static str,
+    pub unknown_command: &'static str,
+}
+
+const ENGLISH: Messages = Messages {
+    rate_limited: "⏳ You're sending commands too quickly. Try again in a few seconds.",
+    servers_only: "❌ This command can only be used in servers.",
+    administrator_only: "❌ You need the 'Administrator' permission to use this command.",
+    command_failed: "An error occurred while executing the command.",
+    unknown_command: "Unknown command",
+};
+
+const FRENCH: Messages = Messages {
+    rate_limited: "⏳ Vous envoyez des commandes trop vite. Réessayez dans quelques secondes.",
+    servers_only: "❌ Cette commande ne peut être utilisée que sur un serveur.",
+    administrator_only: "❌ Vous avez besoin de la permission « Administrateur » pour utiliser cette commande.",
+    command_failed: "Une erreur s'est produite lors de l'exécution de la commande.",
+    unknown_command: "Commande inconnue",
+};
+
+const GERMAN: Messages = Messages {
+    rate_limited: "⏳ Du sendest Befehle zu schnell. Versuche es in ein paar Sekunden erneut.",
+    servers_only: "❌ Dieser Befehl kann nur auf Servern verwendet werden.",
+    administrator_only: "❌ Du brauchst die Berechtigung „Administrator“, um diesen Befehl zu verwenden.",
+    command_failed: "Beim Ausführen des Befehls ist ein Fehler aufgetreten.",
+    unknown_command: "Unbekannter Befehl",
+};
+
+const SPANISH: Messages = Messages {
+    rate_limited: "⏳ Estás enviando comandos demasiado rápido. Inténtalo de nuevo en unos segundos.",
+    servers_only: "❌ Este comando solo se puede usar en servidores.",
+    administrator_only: "❌ Necesitas el permiso «Administrador» para usar este comando.",
+    command_failed: "Se produjo un error al ejecutar el comando.",
+    unknown_command: "Comando desconocido",
+};
+
+const ITALIAN: Messages = Messages {
+    rate_limited: "⏳ Stai inviando comandi troppo velocemente. Riprova tra qualche secondo.",
+    servers_only: "❌ Questo comando può essere usato solo nei server.",
+    administrator_only: "❌ Ti serve il permesso «Amministratore» per usare questo comando.",
+    command_failed: "Si è verificato un errore durante l'esecuzione del comando.",
+    unknown_command: "Comando sconosciuto",
+};
+
+const PORTUGUESE: Messages = Messages {
+    rate_limited: "⏳ Você está enviando comandos rápido demais. Tente de novo em alguns segundos.",
+    servers_only: "❌ Este comando só pode ser usado em servidores.",
+    administrator_only: "❌ Você precisa da permissão \"Administrador\" para usar este comando.",
+    command_failed: "Ocorreu um erro ao executar o comando.",
+    unknown_command: "Comando desconhecido",
+};
+
+/// Translated descriptions of each command: French, German, Spanish,
+/// Italian and Portuguese.  The English description is the one its
+/// `register` function sets.
+pub const COMMAND_DESCRIPTIONS: [(&str, [&str; 5]); 34] = [
+    (
+        "roll",
+        [
+            "Demander à Dice Maiden de lancer des dés !",
+            "Lass Dice Maiden Würfel werfen!",
+            "¡Pide a Dice Maiden que tire dados!",
+            "Chiedi a Dice Maiden di tirare i dadi!",
+            "Peça à Dice Maiden para rolar dados!",
+        ],
+    ),
+    (
+        "r",
+        [
+            "Lancer des dés (raccourci)",
+            "Würfeln (Kurzform)",
+            "Tirar dados (atajo)",
+            "Tira i dadi (scorciatoia)",
+            "Rolar dados (atalho)",
+        ],
+    ),
+    (
+        "again",
+        [
+            "Relancer votre dernier jet sur ce serveur",
+            "Deinen letzten Wurf auf diesem Server wiederholen",
+            "Repetir tu última tirada en este servidor",
+            "Ripeti il tuo ultimo tiro in questo server",
+            "Repetir sua última rolagem neste servidor",
+        ],
+    ),
+    (
+        "calc",
+        [
+            "Faire un calcul rapide sans lancer de dés",
+            "Schnell etwas ausrechnen, ohne zu würfeln",
+            "Hacer un cálculo rápido sin tirar dados",
+            "Fai un calcolo veloce senza tirare dadi",
+            "Fazer uma conta rápida sem rolar dados",
+        ],
+    ),
+    (
+        "odds",
+        [
+            "Afficher les probabilités d'un jet sans le lancer",
+            "Die Chancen eines Wurfs zeigen, ohne zu würfeln",
+            "Mostrar las probabilidades de una tirada sin tirarla",
+            "Mostra le probabilità di un tiro senza tirarlo",
+            "Mostrar as chances de uma rolagem sem rolá-la",
+        ],
+    ),
+    (
+        "help",
+        [
+            "Afficher l'aide de Dice Maiden",
+            "Hilfe zu Dice Maiden anzeigen",
+            "Mostrar la ayuda de Dice Maiden",
+            "Mostra l'aiuto di Dice Maiden",
+            "Mostrar a ajuda da Dice Maiden",
+        ],
+    ),
+    (
+        "purge",
+        [
+            "Supprimer les messages récents (permission Gérer les messages requise)",
+            "Neueste Nachrichten löschen (erfordert die Berechtigung Nachrichten verwalten)",
+            "Borrar mensajes recientes (requiere el permiso Gestionar mensajes)",
+            "Elimina i messaggi recenti (richiede il permesso Gestire i messaggi)",
+            "Apagar mensagens recentes (requer a permissão Gerenciar mensagens)",
+        ],
+    ),
+    (
+        "tally",
+        [
+            "Tenir un total cumulé des jets dans ce salon",
+            "Eine laufende Summe der Würfe in diesem Kanal führen",
+            "Llevar un total acumulado de tiradas en este canal",
+            "Tieni un totale progressivo dei tiri in questo canale",
+            "Manter um total acumulado de rolagens neste canal",
+        ],
+    ),
+    (
+        "clock",
+        [
+            "Suivre des horloges de progression dans ce salon",
+            "Fortschrittsuhren in diesem Kanal verfolgen",
+            "Seguir relojes de progreso en este canal",
+            "Segui gli orologi di avanzamento in questo canale",
+            "Acompanhar relógios de progresso neste canal",
+        ],
+    ),
+    (
+        "config",
+        [
+            "Configurer Dice Maiden pour ce serveur (administrateurs uniquement)",
+            "Dice Maiden für diesen Server konfigurieren (nur Administratoren)",
+            "Configurar Dice Maiden para este servidor (solo administradores)",
+            "Configura Dice Maiden per questo server (solo amministratori)",
+            "Configurar a Dice Maiden neste servidor (somente administradores)",
+        ],
+    ),
+    (
+        "forgetme",
+        [
+            "Supprimer définitivement toutes vos données stockées par Dice Maiden",
+            "Alle Daten, die Dice Maiden über dich speichert, dauerhaft löschen",
+            "Borrar para siempre todos los datos que Dice Maiden guarda sobre ti",
+            "Elimina per sempre tutti i dati che Dice Maiden conserva su di te",
+            "Apagar para sempre todos os dados que a Dice Maiden guarda sobre você",
+        ],
+    ),
+    (
+        "privacy",
+        [
+            "Voir ou changer la part de votre historique de jets conservée par Dice Maiden",
+            "Ansehen oder ändern, wie viel deines Wurfverlaufs Dice Maiden behält",
+            "Ver o cambiar cuánto de tu historial de tiradas guarda Dice Maiden",
+            "Vedi o cambia quanta parte della tua cronologia dei tiri conserva Dice Maiden",
+            "Ver ou mudar quanto do seu histórico de rolagens a Dice Maiden guarda",
+        ],
+    ),
+    (
+        "prefs",
+        [
+            "Régler vos propres options de jet par défaut, sur tous les serveurs",
+            "Deine eigenen Standardwerte für Würfe festlegen, auf allen Servern",
+            "Ajustar tus propias opciones de tirada, en todos los servidores",
+            "Imposta le tue opzioni di tiro predefinite, in tutti i server",
+            "Definir seus próprios padrões de rolagem, em todos os servidores",
+        ],
+    ),
+    (
+        "mystats",
+        [
+            "Vos statistiques de jets",
+            "Deine Wurfstatistiken",
+            "Tus estadísticas de tiradas",
+            "Le tue statistiche dei tiri",
+            "Suas estatísticas de rolagens",
+        ],
+    ),
+    (
+        "stats",
+        [
+            "Nombre de jets, 20 et 1 naturels sur ce serveur",
+            "Anzahl der Würfe, natürliche 20en und 1en auf diesem Server",
+            "Número de tiradas, 20 y 1 naturales en este servidor",
+            "Numero di tiri, 20 e 1 naturali in questo server",
+            "Contagem de rolagens, 20 e 1 naturais neste servidor",
+        ],
+    ),
+    (
+        "usage",
+        [
+            "Afficher l'usage des alias et la durée des jets (propriétaire du bot uniquement)",
+            "Alias-Nutzung und Wurfzeiten anzeigen (nur Bot-Besitzer)",
+            "Mostrar el uso de alias y los tiempos de tirada (solo el dueño del bot)",
+            "Mostra l'uso degli alias e i tempi dei tiri (solo proprietario del bot)",
+            "Mostrar o uso de aliases e os tempos de rolagem (somente o dono do bot)",
+        ],
+    ),
+    (
+        "admin",
+        [
+            "Maintenance du bot (propriétaire du bot uniquement)",
+            "Bot-Wartung (nur Bot-Besitzer)",
+            "Mantenimiento del bot (solo el dueño del bot)",
+            "Manutenzione del bot (solo proprietario del bot)",
+            "Manutenção do bot (somente o dono do bot)",
+        ],
+    ),
+    (
+        "debugparse",
+        [
+            "Montrer comment une expression de dés est lue (propriétaire du bot uniquement)",
+            "Zeigen, wie ein Würfelausdruck gelesen wird (nur Bot-Besitzer)",
+            "Mostrar cómo se interpreta una expresión de dados (solo el dueño del bot)",
+            "Mostra come viene letta un'espressione di dadi (solo proprietario del bot)",
+            "Mostrar como uma expressão de dados é lida (somente o dono do bot)",
+        ],
+    ),
+    (
+        "setup",
+        [
+            "Installer Dice Maiden sur ce serveur (administrateurs uniquement)",
+            "Dice Maiden für diesen Server einrichten (nur Administratoren)",
+            "Preparar Dice Maiden para este servidor (solo administradores)",
+            "Prepara Dice Maiden per questo server (solo amministratori)",
+            "Preparar a Dice Maiden neste servidor (somente administradores)",
+        ],
+    ),
+    (
+        "macropack",
+        [
+            "Activer les packs de macros intégrés sur ce serveur (administrateurs uniquement)",
+            "Eingebaute Makropakete für diesen Server aktivieren (nur Administratoren)",
+            "Activar paquetes de macros incluidos en este servidor (solo administradores)",
+            "Attiva i pacchetti di macro inclusi per questo server (solo amministratori)",
+            "Ativar pacotes de macros embutidos neste servidor (somente administradores)",
+        ],
+    ),
+    (
+        "alias",
+        [
+            "Définir des raccourcis de jets pour ce serveur (administrateurs uniquement)",
+            "Kurzbefehle für Würfe auf diesem Server festlegen (nur Administratoren)",
+            "Definir atajos de tiradas para este servidor (solo administradores)",
+            "Definisci scorciatoie di tiro per questo server (solo amministratori)",
+            "Definir atalhos de rolagem para este servidor (somente administradores)",
+        ],
+    ),
+    (
+        "macro",
+        [
+            "Enregistrer vos propres expressions de dés nommées",
+            "Eigene benannte Würfelausdrücke speichern",
+            "Guardar tus propias expresiones de dados con nombre",
+            "Salva le tue espressioni di dadi con un nome",
+            "Salvar suas próprias expressões de dados com nome",
+        ],
+    ),
+    (
+        "keyword",
+        [
+            "Marquer les jets selon les mots de leurs commentaires (administrateurs uniquement)",
+            "Würfe nach Wörtern in ihren Kommentaren markieren (nur Administratoren)",
+            "Marcar tiradas según palabras de sus comentarios (solo administradores)",
+            "Contrassegna i tiri in base alle parole dei commenti (solo amministratori)",
+            "Marcar rolagens por palavras nos comentários (somente administradores)",
+        ],
+    ),
+    (
+        "followup",
+        [
+            "Ajouter des boutons d'action aux jets selon leurs commentaires (administrateurs uniquement)",
+            "Aktionsbuttons zu Würfen nach Wörtern im Kommentar hinzufügen (nur Administratoren)",
+            "Añadir botones de acción a tiradas según su comentario (solo administradores)",
+            "Aggiungi pulsanti d'azione ai tiri in base ai commenti (solo amministratori)",
+            "Adicionar botões de ação às rolagens pelos comentários (somente administradores)",
+        ],
+    ),
+    (
+        "settings",
+        [
+            "Régler les options de jet par défaut de ce serveur (administrateurs uniquement)",
+            "Standardwerte für Würfe auf diesem Server festlegen (nur Administratoren)",
+            "Ajustar las opciones de tirada de este servidor (solo administradores)",
+            "Imposta le opzioni di tiro predefinite del server (solo amministratori)",
+            "Definir os padrões de rolagem deste servidor (somente administradores)",
+        ],
+    ),
+    (
+        "sheet",
+        [
+            "Gérer les valeurs de votre fiche de personnage pour les jets de réserve",
+            "Werte deines Charakterbogens für Pool-Würfe verwalten",
+            "Gestionar los atributos de tu hoja de personaje para tiradas de reserva",
+            "Gestisci i valori della scheda del personaggio per i tiri a riserva",
+            "Gerenciar os atributos da sua ficha de personagem para rolagens de parada",
+        ],
+    ),
+    (
+        "char",
+        [
+            "Enregistrer des modificateurs et les lancer, ou utiliser @nom dans /roll",
+            "Wertmodifikatoren speichern und würfeln, oder @name in /roll verwenden",
+            "Guardar modificadores y tirarlos, o usar @nombre en /roll",
+            "Salva i modificatori e tirali, o usa @nome in /roll",
+            "Salvar modificadores e rolá-los, ou usar @nome no /roll",
+        ],
+    ),
+    (
+        "session",
+        [
+            "Mener une session de jeu aux jets reproductibles dans ce salon",
+            "Eine Spielsitzung mit nachvollziehbaren Würfen in diesem Kanal leiten",
+            "Dirigir una sesión de juego con tiradas reproducibles en este canal",
+            "Gestisci una sessione di gioco con tiri riproducibili in questo canale",
+            "Conduzir uma sessão de jogo com rolagens reproduzíveis neste canal",
+        ],
+    ),
+    (
+        "verify",
+        [
+            "Révéler la graine des jets équitables et recalculer un jet fait avec",
+            "Den Seed fairer Würfe aufdecken und einen Wurf damit nachrechnen",
+            "Revelar la semilla de tiradas justas y recalcular una tirada hecha con ella",
+            "Rivela il seme dei tiri equi e ricalcola un tiro fatto con esso",
+            "Revelar a semente de rolagens justas e recalcular uma rolagem feita com ela",
+        ],
+    ),
+    (
+        "table",
+        [
+            "Lancer sur les tables aléatoires de ce serveur",
+            "Auf den Zufallstabellen dieses Servers würfeln",
+            "Tirar en las tablas aleatorias de este servidor",
+            "Tira sulle tabelle casuali di questo server",
+            "Rolar nas tabelas aleatórias deste servidor",
+        ],
+    ),
+    (
+        "init",
+        [
+            "Suivre l'ordre d'initiative dans ce salon",
+            "Initiativereihenfolge in diesem Kanal verfolgen",
+            "Seguir el orden de iniciativa en este canal",
+            "Segui l'ordine di iniziativa in questo canale",
+            "Acompanhar a ordem de iniciativa neste canal",
+        ],
+    ),
+    (
+        "history",
+        [
+            "Afficher les jets récents de ce salon, ou télécharger les vôtres",
+            "Die letzten Würfe dieses Kanals zeigen oder deine eigenen herunterladen",
+            "Mostrar las tiradas recientes de este canal, o descargar las tuyas",
+            "Mostra i tiri recenti di questo canale, o scarica i tuoi",
+            "Mostrar as rolagens recentes deste canal, ou baixar as suas",
+        ],
+    ),
+    (
+        "tips",
+        [
+            "Activer ou désactiver les astuces sous vos premiers jets sur un serveur",
+            "Tipps unter deinen ersten Würfen auf einem Server ein- oder ausschalten",
+            "Activar o desactivar los consejos bajo tus primeras tiradas en un servidor",
+            "Attiva o disattiva i consigli sotto i tuoi primi tiri in un server",
+            "Ligar ou desligar as dicas sob suas primeiras rolagens em um servidor",
+        ],
+    ),
+    (
+        "draw",
+        [
+            "Piocher des cartes dans les paquets de ce salon",
+            "Karten aus den Decks dieses Kanals ziehen",
+            "Robar cartas de los mazos de este canal",
+            "Pesca carte dai mazzi di questo canale",
+            "Comprar cartas dos baralhos deste canal",
+        ],
+    ),
+];
+
+/// The translated language for a Discord locale such as `pt-BR`
+pub fn language_for_locale(locale: &str) -> Option<Language> {
+    DISCORD_LOCALES
+        .iter()
+        .find(|(known, _)| *known == locale)
+        .map(|(_, language)| *language)
+}
+
+/// The language to answer an interaction in: the user's client locale if
+/// it's translated, then the server's, then English
+pub fn language_for(locale: &str, guild_locale: Option<&str>) -> Language {
+    language_for_locale(locale)
+        .or_else(|| guild_locale.and_then(language_for_locale))
+        .unwrap_or_default()
+}
+
+/// The common error messages in `language`
+pub fn messages(language: Language) -> &'static Messages {
+    match language {
+        Language::English => &ENGLISH,
+        Language::French => &FRENCH,
+        Language::German => &GERMAN,
+        Language::Spanish => &SPANISH,
+        Language::Italian => &ITALIAN,
+        Language::Portuguese => &PORTUGUESE,
+    }
+}
+
+/// The description of command `name` in `language`, if it's translated
+pub fn command_description(name: &str, language: Language) -> Option<&'static str> {
+    let index = match language {
+        Language::English => return None,
+        Language::French => 0,
+        Language::German => 1,
+        Language::Spanish => 2,
+        Language::Italian => 3,
+        Language::Portuguese => 4,
+    };
+    COMMAND_DESCRIPTIONS
+        .iter()
+        .find(|(command, _)| *command == name)
+        .map(|(_, descriptions)| descriptions[index])
+}
+
+/// `command` with its description translated for every Discord locale in
+/// [`DISCORD_LOCALES`]
+pub fn localize_command(command: CreateCommand) -> CreateCommand {
+    // The builder doesn't expose the name it was created with
+    let name = serde_json::to_value(&command)
+        .ok()
+        .and_then(|json| json["name"].as_str().map(str::to_string))
+        .unwrap_or_default();
+    DISCORD_LOCALES.iter().fold(
+        command,
+        |command, &(locale, language)| match command_description(&name, language) {
+            Some(description) => command.description_localized(locale, description),
+            None => command,
+        },
+    )
+}
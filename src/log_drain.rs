@@ -0,0 +1,477 @@
+//! Log drains: a JSON copy of every roll in a server, posted to the HTTPS
+//! endpoint its administrators set with `/config log-drain`, for campaign
+//! wikis and custom archives.
+//!
+//! Rolls are copied as roll history records them (see `commands::privacy`):
+//! nothing for members or servers with history off or for private and `gm`
+//! rolls, and only totals at the totals level.  Rolls in DMs are never drained.
+//!
+//! # Delivery
+//!
+//! [`LogDrains`] keeps a buffer of rolls per server, posted by a background
+//! task:
+//!
+//! * every [`FLUSH_INTERVAL`], or as soon as [`BATCH_SIZE`] rolls are
+//!   waiting, up to [`BATCH_SIZE`] rolls go out in one [`DrainBatch`];
+//! * a failed post (connection error, no answer within [`REQUEST_TIMEOUT`],
+//!   or a status other than 2xx) keeps the rolls, and the server's endpoint
+//!   is left alone for [`backoff_delay`]: 30 seconds, doubling up to an hour;
+//! * a buffer holds at most [`MAX_PENDING`] rolls; new rolls past that are
+//!   dropped and counted, as `/config log-drain` shows.
+//!
+//! Each server's batch is posted on its own task, so a slow or failing
+//! endpoint only delays its own rolls, never other servers' or the roll
+//! itself.  Buffers are kept in memory: rolls still waiting when the bot
+//! restarts are lost.
+//!
+//! # Request format
+//!
+//! ```text
+//! POST <url>
+//! Content-Type: application/json
+//! X-DiceMaiden-Timestamp: 1767225600
+//! X-DiceMaiden-Signature: sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">
+//!
+//! {"guild_id":"…","engine":"1.5.2+g1","rolls":[{"timestamp":"2026-01-01T00:00:00Z",
+//!  "channel_id":"…","user_id":"…","expression":"2d6+3","result":"…","total":10,"outcome":null}]}
+//! ```
+//!
+//! The HMAC key is the secret shown when the drain is set.  IDs are strings
+//! so JavaScript receivers keep every digit; receivers should check the
+//! signature and refuse old timestamps.  [`validate_url`] only accepts HTTPS
+//! URLs whose host isn't a local or private address, and redirects are never
+//! followed.
+
+use crate::LogDrainContainer;
+use crate::database::{Database, LogDrainConfig, RollHistoryEntry};
+use crate::dice::rng;
+use crate::sync::lock;
+use crate::version;
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, SecondsFormat, Utc};
+use hmac::{Hmac, Mac};
+use reqwest::{Url, header::CONTENT_TYPE};
+use serde::Serialize;
+use serenity::prelude::Context;
+use sha2::Sha256;
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tracing::{debug, warn};
+
+/// Most rolls posted in one request
+pub const BATCH_SIZE: usize = 50;
+
+/// Rolls a server's buffer holds before new ones are dropped
+pub const MAX_PENDING: usize = 1000;
+
+/// How often waiting rolls are posted
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long an endpoint has to answer
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest URL accepted for a drain
+pub const MAX_URL_LENGTH: usize = 512;
+
+pub const TIMESTAMP_HEADER: &str = "X-DiceMaiden-Timestamp";
+pub const SIGNATURE_HEADER: &str = "X-DiceMaiden-Signature";
+
+const BASE_BACKOFF: Duration = Duration::from_secs(30);
+const MAX_BACKOFF: Duration = Duration::from_secs(3600);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// One roll as a drain receives it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DrainedRoll {
+    /// When the roll was made, RFC 3339 in UTC
+    pub timestamp: String,
+    pub channel_id: String,
+    pub user_id: String,
+    /// `None` when only totals are recorded
+    pub expression: Option<String>,
+    /// The roll as posted in Discord; `None` when only totals are recorded
+    pub result: Option<String>,
+    pub total: i64,
+    /// `nat20`, `success`, `neutral`, `failure` or `nat1` for checks
+    pub outcome: Option<&'static str>,
+    /// The channel's `/init` round and turn, left out outside combat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub turn: Option<String>,
+}
+
+impl DrainedRoll {
+    /// The drained copy of a roll history entry
+    pub fn new(entry: &RollHistoryEntry, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            timestamp: timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+            channel_id: entry.channel_id.to_string(),
+            user_id: entry.user_id.to_string(),
+            expression: entry.expression.clone(),
+            result: entry.result.clone(),
+            total: entry.total,
+            outcome: entry.outcome.map(|outcome| outcome.as_str()),
+            round: entry.round,
+            turn: entry.turn.clone(),
+        }
+    }
+}
+
+/// The body of one request
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DrainBatch {
+    pub guild_id: String,
+    /// Dice engine version that rolled them (see `version.rs`)
+    pub engine: String,
+    pub rolls: Vec<DrainedRoll>,
+}
+
+impl DrainBatch {
+    pub fn new(guild_id: i64, rolls: Vec<DrainedRoll>) -> Self {
+        Self {
+            guild_id: guild_id.to_string(),
+            engine: version::engine_version().to_string(),
+            rolls,
+        }
+    }
+}
+
+/// Wait after `failures` failed posts in a row: none, then 30s, 1m, 2m, …
+/// capped at an hour
+pub fn backoff_delay(failures: u32) -> Duration {
+    if failures == 0 {
+        return Duration::ZERO;
+    }
+    BASE_BACKOFF
+        .saturating_mul(1 << (failures - 1).min(16))
+        .min(MAX_BACKOFF)
+}
+
+/// The `X-DiceMaiden-Signature` of a request body sent at `timestamp`
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// A new random signing secret: 32 bytes as hex
+pub fn generate_secret() -> Result<String> {
+    let bytes = rng::secret_bytes().map_err(|e| anyhow!("Couldn't generate a secret: {e}"))?;
+    Ok(hex::encode(bytes))
+}
+
+/// Check a drain URL, returning it normalized.  It must be HTTPS, without
+/// credentials, and its host can't be `localhost`, a `.local` or
+/// `.internal` name, or a loopback, private or link-local address.
+pub fn validate_url(url: &str) -> Result<String> {
+    let url = url.trim();
+    if url.len() > MAX_URL_LENGTH {
+        return Err(anyhow!("URL is longer than {MAX_URL_LENGTH} characters"));
+    }
+    let parsed = Url::parse(url).map_err(|e| anyhow!("`{url}` isn't a valid URL ({e})"))?;
+    if parsed.scheme() != "https" {
+        return Err(anyhow!("Log drains must use `https://`"));
+    }
+    if !parsed.username().is_empty() || parsed.password().is_some() {
+        return Err(anyhow!(
+            "Put credentials in your endpoint, not the URL; batches are signed instead"
+        ));
+    }
+
+    let local = match parsed.host_str() {
+        None => true,
+        Some(host) => match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+            Ok(ip) => is_local_ip(ip),
+            Err(_) => {
+                let domain = host.trim_end_matches('.').to_ascii_lowercase();
+                domain == "localhost"
+                    || [".localhost", ".local", ".internal"]
+                        .iter()
+                        .any(|suffix| domain.ends_with(suffix))
+            }
+        },
+    };
+    if local {
+        return Err(anyhow!(
+            "Log drains must point to a public host, not a local or private address"
+        ));
+    }
+    Ok(parsed.to_string())
+}
+
+fn is_local_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                // Carrier-grade NAT, 100.64.0.0/10
+                || (ip.octets()[0] == 100 && (ip.octets()[1] & 0xc0) == 64)
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            ip.is_loopback()
+                || ip.is_unspecified()
+                // Unique local fc00::/7 and link-local fe80::/10
+                || (segments[0] & 0xfe00) == 0xfc00
+                || (segments[0] & 0xffc0) == 0xfe80
+                || ip
+                    .to_ipv4_mapped()
+                    .is_some_and(|ip| is_local_ip(IpAddr::V4(ip)))
+        }
+    }
+}
+
+/// A server's waiting rolls and delivery state
+#[derive(Debug, Default)]
+pub struct DrainBuffer {
+    pending: VecDeque<DrainedRoll>,
+    // Rolls at the front of `pending` being posted right now
+    in_flight: usize,
+    failures: u32,
+    retry_at: Option<Instant>,
+    dropped: u64,
+}
+
+/// What `/config log-drain` shows about a drain's delivery
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrainStatus {
+    pub pending: usize,
+    /// Failed posts in a row
+    pub failures: u32,
+    /// Time left before the next try after failures
+    pub retry_in: Option<Duration>,
+    /// Rolls dropped because the buffer was full
+    pub dropped: u64,
+}
+
+impl DrainBuffer {
+    /// Add a roll, returning whether a full batch is now waiting.  Rolls past
+    /// [`MAX_PENDING`] are dropped.
+    pub fn push(&mut self, roll: DrainedRoll) -> bool {
+        if self.pending.len() >= MAX_PENDING {
+            self.dropped += 1;
+            return false;
+        }
+        self.pending.push_back(roll);
+        self.pending.len() - self.in_flight >= BATCH_SIZE
+    }
+
+    /// Whether a batch should be posted now
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.in_flight == 0
+            && !self.pending.is_empty()
+            && self.retry_at.is_none_or(|retry_at| now >= retry_at)
+    }
+
+    /// The oldest rolls, up to [`BATCH_SIZE`], marked as being posted
+    pub fn take_batch(&mut self) -> Vec<DrainedRoll> {
+        self.in_flight = self.pending.len().min(BATCH_SIZE);
+        self.pending.iter().take(self.in_flight).cloned().collect()
+    }
+
+    /// Record how posting the batch from [`take_batch`](Self::take_batch)
+    /// went: delivered rolls are removed, failed ones wait for the backoff
+    pub fn finish(&mut self, delivered: bool, now: Instant) {
+        if delivered {
+            self.pending.drain(..self.in_flight);
+            self.failures = 0;
+            self.retry_at = None;
+        } else {
+            self.failures += 1;
+            self.retry_at = Some(now + backoff_delay(self.failures));
+        }
+        self.in_flight = 0;
+    }
+
+    pub fn status(&self, now: Instant) -> DrainStatus {
+        DrainStatus {
+            pending: self.pending.len(),
+            failures: self.failures,
+            retry_in: self
+                .retry_at
+                .filter(|retry_at| *retry_at > now)
+                .map(|retry_at| retry_at - now),
+            dropped: self.dropped,
+        }
+    }
+}
+
+/// The bot's log drains: each server's configuration (cached from the
+/// `log_drains` table) and buffer, posted by one background task
+#[derive(Clone)]
+pub struct LogDrains {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    db: Arc<Database>,
+    client: reqwest::Client,
+    configs: Mutex<HashMap<i64, Option<LogDrainConfig>>>,
+    buffers: Mutex<HashMap<i64, DrainBuffer>>,
+    wake: Notify,
+}
+
+impl LogDrains {
+    /// Create the drains and spawn their worker on the current Tokio runtime
+    pub fn start(db: Arc<Database>) -> Self {
+        let drains = Self {
+            inner: Arc::new(Inner {
+                db,
+                client: http_client(),
+                configs: Mutex::new(HashMap::new()),
+                buffers: Mutex::new(HashMap::new()),
+                wake: Notify::new(),
+            }),
+        };
+
+        let worker = drains.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = worker.inner.wake.notified() => {}
+                }
+                worker.flush_due();
+            }
+        });
+
+        drains
+    }
+
+    /// Queue a roll for its server's drain, if it has one
+    pub async fn record(&self, guild_id: i64, roll: DrainedRoll) {
+        if self.config(guild_id).await.is_none() {
+            return;
+        }
+        if lock(&self.inner.buffers)
+            .entry(guild_id)
+            .or_default()
+            .push(roll)
+        {
+            self.inner.wake.notify_one();
+        }
+    }
+
+    /// Replace a server's cached drain after `/config log-drain` changes it.
+    /// Removing the drain discards its waiting rolls.
+    pub fn update_config(&self, guild_id: i64, config: Option<LogDrainConfig>) {
+        if config.is_none() {
+            lock(&self.inner.buffers).remove(&guild_id);
+        }
+        lock(&self.inner.configs).insert(guild_id, config);
+    }
+
+    pub fn status(&self, guild_id: i64) -> Option<DrainStatus> {
+        lock(&self.inner.buffers)
+            .get(&guild_id)
+            .map(|buffer| buffer.status(Instant::now()))
+    }
+
+    async fn config(&self, guild_id: i64) -> Option<LogDrainConfig> {
+        if let Some(config) = lock(&self.inner.configs).get(&guild_id) {
+            return config.clone();
+        }
+        match self.inner.db.get_log_drain(guild_id).await {
+            Ok(config) => {
+                lock(&self.inner.configs).insert(guild_id, config.clone());
+                config
+            }
+            Err(e) => {
+                // Not cached, so the next roll tries again
+                warn!("Failed to load log drain for guild {}: {}", guild_id, e);
+                None
+            }
+        }
+    }
+
+    // Post every server's due batch, each on its own task
+    fn flush_due(&self) {
+        let now = Instant::now();
+        let configs = lock(&self.inner.configs).clone();
+        let mut buffers = lock(&self.inner.buffers);
+        for (&guild_id, buffer) in buffers.iter_mut() {
+            if !buffer.is_due(now) {
+                continue;
+            }
+            let Some(Some(config)) = configs.get(&guild_id).cloned() else {
+                continue;
+            };
+            let batch = DrainBatch::new(guild_id, buffer.take_batch());
+            let drains = self.clone();
+            tokio::spawn(async move {
+                let rolls = batch.rolls.len();
+                let delivered = match deliver(&drains.inner.client, &config, &batch).await {
+                    Ok(()) => {
+                        debug!("Drained {} rolls for guild {}", rolls, guild_id);
+                        true
+                    }
+                    Err(e) => {
+                        warn!("Log drain for guild {} failed: {}", guild_id, e);
+                        false
+                    }
+                };
+                if let Some(buffer) = lock(&drains.inner.buffers).get_mut(&guild_id) {
+                    buffer.finish(delivered, Instant::now());
+                }
+            });
+        }
+    }
+}
+
+/// The HTTP client drains post with: [`REQUEST_TIMEOUT`], no redirects
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .redirect(reqwest::redirect::Policy::none())
+        .user_agent(format!("DiceMaiden/{}", version::CRATE_VERSION))
+        .build()
+        .expect("Failed to build the log drain HTTP client")
+}
+
+/// Post one signed batch, failing on any status other than 2xx
+pub async fn deliver(
+    client: &reqwest::Client,
+    config: &LogDrainConfig,
+    batch: &DrainBatch,
+) -> Result<()> {
+    let body = serde_json::to_string(batch)?;
+    let timestamp = Utc::now().timestamp();
+    client
+        .post(&config.url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign(&config.secret, timestamp, &body))
+        .body(body)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+/// Copy a recorded roll to its server's drain.  Rolls outside servers, or
+/// without the drains registered (e.g. in tools that skip `main`), are
+/// skipped.
+pub async fn forward(ctx: &Context, entry: &RollHistoryEntry) {
+    let Some(guild_id) = entry.guild_id else {
+        return;
+    };
+    let Some(drains) = ctx.data.read().await.get::<LogDrainContainer>().cloned() else {
+        return;
+    };
+    drains
+        .record(guild_id, DrainedRoll::new(entry, Utc::now()))
+        .await;
+}
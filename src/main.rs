@@ -1,6 +1,6 @@
 //! Bot entry point and Discord gateway management.
 //!
-//! Initialises the SQLite database, resolves the shard strategy from environment
+//! Initialises the database, resolves the shard strategy from environment
 //! variables, builds the Serenity [`Client`], and starts the Discord gateway.
 //!
 //! # Shard strategies
@@ -13,7 +13,7 @@
 //! | unset              | unset         | —                                | single            |
 //!
 //! In **multi-process** mode several bot processes each own a contiguous shard
-//! range (`SHARD_START..SHARD_START+SHARD_COUNT`), sharing a single SQLite DB
+//! range (`SHARD_START..SHARD_START+SHARD_COUNT`), sharing a single database
 //! via the `process_stats` table.  Single-process mode uses `shard_stats`.
 //!
 //! # Environment variables
@@ -21,34 +21,67 @@
 //! | Variable            | Required | Description                                      |
 //! |---------------------|----------|--------------------------------------------------|
 //! | `DISCORD_TOKEN`     | ✓        | Bot token from the Discord developer portal      |
-//! | `DATABASE_URL`      |          | SQLite URL; defaults to `./main.db`              |
+//! | `DATABASE_URL`      |          | SQLite/PostgreSQL URL; defaults to `./main.db`   |
 //! | `GUILD_ID`          |          | Register commands to one guild (dev/testing)     |
 //! | `SHARD_COUNT`       |          | Number of shards for this process                |
 //! | `SHARD_START`       |          | First shard ID in multi-process deployments      |
 //! | `TOTAL_SHARDS`      |          | Total shards across all processes                |
 //! | `USE_AUTOSHARDING`  |          | Let Discord decide the shard count (`true`)      |
 //! | `MAX_CONCURRENCY`   |          | Hint; overridden by Discord's reported value     |
+//! | `SUSPENSE_MODE`     |          | Post "Rolling…" before every result (`true`)     |
+//! | `SUSPENSE_DELAY_MS` |          | Pause between suspense edits (1000–2000 ms)      |
+//! | `PRESENCE_MESSAGES` |          | `\|`-separated status rotation (see `presence`)  |
+//! | `PRESENCE_INTERVAL_SECS` |     | Seconds per status message (default 300, min 60) |
+//! | `MESSAGE_COMMANDS`  |          | Read `!roll` chat messages (`true`; privileged)  |
 //!
 //! # Event handler
 //!
 //! [`Handler::ready`] fires once per shard connection.  Only shard 0 registers
-//! slash commands (`/roll`, `/r`, `/help`, `/purge`) to avoid duplicate
-//! registrations when running many shards.
+//! slash commands (`/roll`, `/r`, `/again`, `/calc`, `/odds`, `/help`, `/purge`, `/tally`, `/clock`, `/config`,
+//! `/forgetme`, `/privacy`, `/mystats`, `/stats`, `/usage`, `/admin`, `/debugparse`, `/setup`, `/macropack`, `/alias`, `/macro`, `/keyword`, `/followup`, `/settings`, `/sheet`, `/char`, `/session`, `/verify`, `/table`, `/init`, `/history`, `/tips`, `/draw`; see `commands::all_commands`) to avoid duplicate registrations when running many shards.
 //!
-//! [`Handler::interaction_create`] dispatches incoming slash-command interactions
-//! to `commands::roll`, `commands::help`, or `commands::purge`.
+//! [`Handler::interaction_create`] runs every interaction through the
+//! `middleware::Pipeline` (dedupe, rate limit, permissions, metrics) and
+//! dispatches slash commands to the matching `commands::*` handler (roll, again, calc, odds, help, purge, tally, clock, config,
+//! forgetme, privacy, mystats, stats, usage, admin, debugparse, setup, macropack, alias, macro, keyword, followup, settings, sheet, char, session, verify, table, init, history, tips, draw).  Button clicks and select menus are routed
+//! by their custom-ID prefix; a roll's Reroll button (`reroll:`) posts a new roll
+//! rather than replacing its message.  Autocomplete requests for the `/roll`
+//! and `/r` expression are answered directly by `commands::roll::autocomplete`.
+//! With `MESSAGE_COMMANDS=true`, [`Handler::message`] answers `!roll` and `!r`
+//! chat messages in servers that turned them on (`commands::text_prefix`).
+//! A command still running after 2 seconds is deferred ("is thinking…") and
+//! its result edited in; a `/roll` large enough to be slow
+//! (`commands::roll::is_heavy_command`) is deferred before it starts.  A
+//! result that misses its interaction token is posted as a channel message
+//! mentioning the requester (see `outbound`).
 //!
 //! A background task collects per-shard guild counts and process memory usage
-//! every 15 minutes and writes them to the database.  The task listens for
+//! every 15 minutes and writes them to the database, logging the memory
+//! allocator the build uses (`dicemaiden_rs::allocator`) alongside.  The task listens for
 //! SIGTERM/SIGINT/Ctrl-C and shuts down cleanly via a `broadcast` channel.
+//!
+//! Built with the `api` feature and given `API_ADDR`, the process also serves
+//! the HTTP roll API (`dicemaiden_rs::api`) until the same shutdown signal.
 
 use anyhow::Result;
-use dicemaiden_rs::{DatabaseContainer, ShardManagerContainer, commands, database};
+use dicemaiden_rs::{
+    DatabaseContainer, LastRollContainer, LogDrainContainer, MetricsContainer,
+    OutboundQueueContainer, OwnersContainer, PresenceContainer, QuickActionContainer,
+    SelectedRerollContainer, ShardManagerContainer, WriteQueueContainer, allocator, commands,
+    database, locale, log_drain, metrics,
+    middleware::{Pipeline, Request, RequestKind},
+    outbound, presence, write_queue,
+};
 use serenity::{
     all::*, async_trait, cache::Settings as CacheSettings, gateway::ShardManager, http::Http,
     model::gateway::Ready, prelude::*,
 };
-use std::{collections::HashSet, env, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    env,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use sysinfo::{Pid, ProcessesToUpdate, System};
 use tokio::{
     select,
@@ -60,11 +93,18 @@ use tracing::{error, info, warn};
 
 struct Handler {
     shard_count: u32,
+    pipeline: Pipeline,
+    /// Whether `!roll` text commands are read (`MESSAGE_COMMANDS`)
+    text_commands: bool,
 }
 
 impl Handler {
-    fn new(shard_count: u32) -> Self {
-        Self { shard_count }
+    fn new(shard_count: u32, pipeline: Pipeline, text_commands: bool) -> Self {
+        Self {
+            shard_count,
+            pipeline,
+            text_commands,
+        }
     }
 }
 
@@ -89,13 +129,17 @@ impl EventHandler for Handler {
             );
         }
 
-        // Set the bot's activity status to "Listening to /roll"
-        let activity = ActivityData::listening("/roll");
+        // Show the status rotation's current message ("Listening to /roll"
+        // until the rotation starts)
+        let presence = ctx.data.read().await.get::<PresenceContainer>().cloned();
+        let activity = match presence {
+            Some(presence) => presence.activity(ctx.shard_id.0),
+            None => ActivityData::listening("/roll"),
+        };
         ctx.set_activity(Some(activity));
 
         // Only do initial setup from shard 0 globally (not per-process)
         if ctx.shard_id.0 == 0 {
-            info!("Bot activity set to 'Listening to /roll'");
             info!("Starting command registration...");
         }
 
@@ -109,24 +153,11 @@ impl EventHandler for Handler {
             // Register slash commands globally (or to a specific guild for testing)
             let commands = if let Some(guild_id) = guild_id {
                 // Guild-specific commands for testing
-                let commands = vec![
-                    commands::roll::register(),
-                    commands::roll::register_r_alias(),
-                    commands::help::register(),
-                    commands::purge::register(),
-                ];
-
-                guild_id.set_commands(&ctx.http, commands).await
+                guild_id
+                    .set_commands(&ctx.http, commands::all_commands())
+                    .await
             } else {
-                // Global commands
-                let commands = vec![
-                    commands::roll::register(),
-                    commands::roll::register_r_alias(),
-                    commands::help::register(),
-                    commands::purge::register(),
-                ];
-
-                Command::set_global_commands(&ctx.http, commands).await
+                Command::set_global_commands(&ctx.http, commands::all_commands()).await
             };
 
             match commands {
@@ -148,50 +179,698 @@ impl EventHandler for Handler {
         }
     }
 
+    async fn message(&self, ctx: Context, msg: Message) {
+        if !self.text_commands || msg.author.bot {
+            return;
+        }
+        let Some(guild_id) = msg.guild_id else {
+            return;
+        };
+        let Some(expression) = commands::text_prefix::parse(&msg.content) else {
+            return;
+        };
+        if !commands::text_prefix::enabled_in(&ctx, guild_id).await {
+            return;
+        }
+
+        let member = msg.member(&ctx).await.ok();
+        let guild_locale = ctx
+            .cache
+            .guild(guild_id)
+            .map(|guild| guild.preferred_locale.clone());
+        let language = locale::language_for("", guild_locale.as_deref());
+        let request = Request {
+            interaction_id: msg.id.get(),
+            kind: RequestKind::Message,
+            name: "roll".to_string(),
+            user_id: msg.author.id.get(),
+            guild_id: Some(guild_id.get()),
+            permissions: None,
+            language,
+        };
+        let origin = commands::RollOrigin {
+            user: &msg.author,
+            member: member.as_ref(),
+            guild_id: Some(guild_id),
+            channel_id: msg.channel_id,
+            app_permissions: None,
+        };
+        let handler = commands::text_prefix::run(&ctx, &origin, expression, language);
+        if let Some(response) = self.pipeline.run(&request, handler).await {
+            send_text_command_response(&ctx, &msg, response).await;
+        }
+    }
+
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
-        if let Interaction::Command(command) = interaction {
-            let response = match command.data.name.as_str() {
-                "roll" => commands::roll::run(&ctx, &command).await,
-                "r" => commands::roll::run(&ctx, &command).await,
-                "help" => commands::help::run(&ctx, &command).await,
-                "purge" => match commands::purge::run(&ctx, &command).await {
-                    Ok(content) => Ok(commands::CommandResponse::public(content)),
-                    Err(e) => Err(e),
-                },
-                _ => Ok(commands::CommandResponse::public(
-                    "Unknown command".to_string(),
-                )),
-            };
+        match interaction {
+            Interaction::Command(command) => {
+                let deadline = outbound::InteractionDeadline::new(Instant::now());
+                let request = Request {
+                    interaction_id: command.id.get(),
+                    kind: RequestKind::Command,
+                    name: command.data.name.clone(),
+                    user_id: command.user.id.get(),
+                    guild_id: command.guild_id.map(|id| id.get()),
+                    permissions: command.member.as_deref().and_then(|m| m.permissions),
+                    language: locale::language_for(
+                        &command.locale,
+                        command.guild_locale.as_deref(),
+                    ),
+                };
+                // A roll big enough to be slow is deferred before it starts,
+                // since rolling holds up the task until it's done
+                if commands::roll::is_heavy_command(&command) {
+                    let deferred = defer_command(&ctx, &command).await;
+                    let handler = self.pipeline.run(&request, handle_command(&ctx, &command));
+                    if let Some(response) = handler.await {
+                        send_command_response(&ctx, &command, response, deadline, deferred).await;
+                    }
+                    return;
+                }
+                let handler = self.pipeline.run(&request, handle_command(&ctx, &command));
+                tokio::pin!(handler);
+
+                // A command still running near Discord's 3 second limit is
+                // deferred, and its result edited in when it's ready
+                let deferred = select! {
+                    response = &mut handler => {
+                        if let Some(response) = response {
+                            send_command_response(&ctx, &command, response, deadline, false).await;
+                        }
+                        return;
+                    }
+                    _ = tokio::time::sleep(outbound::DEFER_AFTER) => defer_command(&ctx, &command).await,
+                };
+                if let Some(response) = handler.await {
+                    send_command_response(&ctx, &command, response, deadline, deferred).await;
+                }
+            }
+            Interaction::Component(component) => {
+                let deadline = outbound::InteractionDeadline::new(Instant::now());
+                let custom_id = component.data.custom_id.as_str();
+                let request = Request {
+                    interaction_id: component.id.get(),
+                    kind: RequestKind::Component,
+                    name: custom_id.split(':').next().unwrap_or(custom_id).to_string(),
+                    user_id: component.user.id.get(),
+                    guild_id: component.guild_id.map(|id| id.get()),
+                    permissions: component.member.as_ref().and_then(|m| m.permissions),
+                    language: locale::language_for(
+                        &component.locale,
+                        component.guild_locale.as_deref(),
+                    ),
+                };
+                let handler = handle_component(&ctx, &component);
+                if let Some(response) = self.pipeline.run(&request, handler).await {
+                    send_component_response(&ctx, &component, response, deadline).await;
+                }
+            }
+            Interaction::Autocomplete(command) => {
+                if matches!(command.data.name.as_str(), "roll" | "r")
+                    && let Err(e) = commands::roll::autocomplete(&ctx, &command).await
+                {
+                    warn!(
+                        "Failed to answer autocomplete for /{}: {}",
+                        command.data.name, e
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
 
-            let (response_content, ephemeral) = match response {
-                Ok(cmd_response) => (cmd_response.content, cmd_response.ephemeral),
-                Err(e) => {
-                    error!("Error executing command: {}", e);
-                    (
-                        "An error occurred while executing the command.".to_string(),
-                        false,
-                    )
+/// Dispatch a slash command to its handler.  Cross-cutting checks live in
+/// the `middleware` pipeline that wraps this, not here.
+async fn handle_command(ctx: &Context, command: &CommandInteraction) -> commands::CommandResponse {
+    let messages = locale::messages(locale::language_for(
+        &command.locale,
+        command.guild_locale.as_deref(),
+    ));
+    let response = match command.data.name.as_str() {
+        "roll" => commands::roll::run(ctx, command).await,
+        "r" => commands::roll::run(ctx, command).await,
+        "again" => commands::again::run(ctx, command).await,
+        "calc" => commands::calc::run(ctx, command).await,
+        "odds" => commands::odds::run(ctx, command).await,
+        "help" => commands::help::run(ctx, command).await,
+        "tally" => commands::tally::run(ctx, command).await,
+        "clock" => commands::clock::run(ctx, command).await,
+        "config" => commands::config::run(ctx, command).await,
+        "forgetme" => commands::forgetme::run(ctx, command).await,
+        "privacy" => commands::privacy::run(ctx, command).await,
+        "prefs" => commands::prefs::run(ctx, command).await,
+        "mystats" => commands::mystats::run(ctx, command).await,
+        "usage" => commands::usage::run(ctx, command).await,
+        "admin" => commands::admin::run(ctx, command).await,
+        "char" => commands::char_cmd::run(ctx, command).await,
+        "stats" => commands::stats::run(ctx, command).await,
+        "debugparse" => commands::debugparse::run(ctx, command).await,
+        "setup" => commands::setup::run(ctx, command).await,
+        "macropack" => commands::macropack::run(ctx, command).await,
+        "alias" => commands::alias::run(ctx, command).await,
+        "macro" => commands::macro_cmd::run(ctx, command).await,
+        "keyword" => commands::keyword::run(ctx, command).await,
+        "followup" => commands::followup::run(ctx, command).await,
+        "settings" => commands::settings::run(ctx, command).await,
+        "sheet" => commands::sheet::run(ctx, command).await,
+        "session" => commands::session::run(ctx, command).await,
+        "history" => commands::history::run(ctx, command).await,
+        "tips" => commands::tips::run(ctx, command).await,
+        "verify" => commands::verify::run(ctx, command).await,
+        "table" => commands::table::run(ctx, command).await,
+        "draw" => commands::draw::run(ctx, command).await,
+        "init" => commands::init::run(ctx, command).await,
+        "purge" => commands::purge::run(ctx, command).await,
+        _ => Ok(commands::CommandResponse::public(
+            messages.unknown_command.to_string(),
+        )),
+    };
+
+    response.unwrap_or_else(|e| {
+        error!("Error executing command: {}", e);
+        commands::CommandResponse::public(messages.command_failed.to_string())
+    })
+}
+
+/// Acknowledge a slow command (Discord shows "is thinking…"), so its result
+/// can be edited in for as long as the token lasts.  False if that failed.
+async fn defer_command(ctx: &Context, command: &CommandInteraction) -> bool {
+    match outbound::send_with_retry("interaction defer", || command.defer(&ctx.http)).await {
+        Ok(()) => true,
+        Err(why) => {
+            warn!("Cannot defer slash command: {}", why);
+            false
+        }
+    }
+}
+
+async fn send_command_response(
+    ctx: &Context,
+    command: &CommandInteraction,
+    mut response: commands::CommandResponse,
+    deadline: outbound::InteractionDeadline,
+    deferred: bool,
+) {
+    // Emojis the channel can't show are sent as `:name:` text instead
+    let access = outbound::ChannelAccess::from_permissions(command.app_permissions);
+    let content = access.adapt_content(&response.content);
+
+    // A deferred private result can't be revealed: its edits would be public
+    if let Some(frames) = response.reveal.take()
+        && !(deferred && response.ephemeral)
+    {
+        let frames = frames
+            .iter()
+            .map(|frame| access.adapt_content(frame))
+            .collect();
+        send_suspense_response(ctx, command, content, frames, response, deadline, deferred).await;
+        return;
+    }
+
+    let now = Instant::now();
+    let in_time = if deferred {
+        deadline.can_follow_up(now)
+    } else {
+        deadline.can_respond(now)
+    };
+    if !in_time {
+        post_late_command_result(ctx, command, &content, &response).await;
+        return;
+    }
+    match send_first_message(ctx, command, &content, &response, deferred).await {
+        Ok(()) => {}
+        Err(why) if outbound::is_expired_interaction_error(&why) => {
+            post_late_command_result(ctx, command, &content, &response).await;
+            return;
+        }
+        Err(why) => {
+            error!("Cannot respond to slash command: {}", why);
+            return;
+        }
+    }
+
+    if let Some(thread) = &response.thread {
+        let message = command.get_response(&ctx.http).await;
+        post_response_thread(ctx, command.channel_id, message, thread).await;
+    }
+    for followup in &response.followups {
+        let followup = CreateInteractionResponseFollowup::new()
+            .content(access.adapt_content(followup))
+            .ephemeral(response.ephemeral);
+        if let Err(why) = outbound::send_with_retry("interaction follow-up", || {
+            command.create_followup(&ctx.http, followup.clone())
+        })
+        .await
+        {
+            error!("Cannot send follow-up message: {}", why);
+            break;
+        }
+    }
+
+    if let Some(mut progress) = response.progress.take() {
+        while let Some(update) = progress.recv().await {
+            let update = access.adapt_content(&update);
+            let edit = EditInteractionResponse::new().content(update.as_str());
+            let expired = !deadline.can_follow_up(Instant::now())
+                || match outbound::send_with_retry("interaction edit", || {
+                    command.edit_response(&ctx.http, edit.clone())
+                })
+                .await
+                {
+                    Ok(_) => false,
+                    Err(why) => {
+                        error!("Cannot edit slash command response: {}", why);
+                        outbound::is_expired_interaction_error(&why)
+                    }
+                };
+
+            // Once the token is gone only the last update is worth posting
+            if expired {
+                let mut last = update;
+                while let Some(update) = progress.recv().await {
+                    last = access.adapt_content(&update);
                 }
-            };
+                let late = commands::CommandResponse::new(last.clone(), response.ephemeral);
+                post_late_command_result(ctx, command, &last, &late).await;
+                return;
+            }
+        }
+    }
+}
+
+/// Send a command's result as its interaction response, or, once the command
+/// was deferred, in place of the "is thinking…" placeholder.  The placeholder
+/// is public, so a private result replaces it with a private follow-up.
+async fn send_first_message(
+    ctx: &Context,
+    command: &CommandInteraction,
+    content: &str,
+    response: &commands::CommandResponse,
+    deferred: bool,
+) -> serenity::Result<()> {
+    if !deferred {
+        let mut message = CreateInteractionResponseMessage::new().content(content);
+        if response.ephemeral {
+            message = message.ephemeral(true);
+        }
+        if !response.components.is_empty() {
+            message = message.components(response.components.clone());
+        }
+        if !response.attachments.is_empty() {
+            message = message.files(response.attachments.clone());
+        }
+        if !response.embeds.is_empty() {
+            message = message.embeds(response.embeds.clone());
+        }
+        return outbound::send_with_retry("interaction response", || {
+            command.create_response(
+                &ctx.http,
+                CreateInteractionResponse::Message(message.clone()),
+            )
+        })
+        .await;
+    }
+
+    if response.ephemeral {
+        let followup = CreateInteractionResponseFollowup::new()
+            .content(content)
+            .ephemeral(true)
+            .components(response.components.clone())
+            .embeds(response.embeds.clone())
+            .files(response.attachments.clone());
+        outbound::send_with_retry("interaction follow-up", || {
+            command.create_followup(&ctx.http, followup.clone())
+        })
+        .await?;
+        if let Err(why) = command.delete_response(&ctx.http).await {
+            warn!("Cannot remove deferred placeholder: {}", why);
+        }
+        return Ok(());
+    }
+
+    let mut edit = EditInteractionResponse::new()
+        .content(content)
+        .components(response.components.clone())
+        .embeds(response.embeds.clone());
+    for attachment in &response.attachments {
+        edit = edit.new_attachment(attachment.clone());
+    }
+    outbound::send_with_retry("interaction edit", || {
+        command.edit_response(&ctx.http, edit.clone())
+    })
+    .await
+    .map(|_| ())
+}
+
+/// Post a result whose interaction token ran out in the command's channel
+/// (or by DM if private); see `outbound::post_late_result`
+async fn post_late_command_result(
+    ctx: &Context,
+    command: &CommandInteraction,
+    content: &str,
+    response: &commands::CommandResponse,
+) {
+    warn!(
+        "/{} missed its interaction token, sending the result as a message",
+        command.data.name
+    );
+    let access = outbound::ChannelAccess::from_permissions(command.app_permissions);
+    let message = CreateMessage::new()
+        .components(response.components.clone())
+        .embeds(response.embeds.clone())
+        .files(response.attachments.clone());
+    if !outbound::post_late_result(
+        ctx,
+        command.channel_id,
+        command.user.id,
+        access,
+        response.ephemeral,
+        content,
+        message,
+    )
+    .await
+    {
+        return;
+    }
+    for followup in &response.followups {
+        outbound::post_late_result(
+            ctx,
+            command.channel_id,
+            command.user.id,
+            access,
+            response.ephemeral,
+            &access.adapt_content(followup),
+            CreateMessage::new(),
+        )
+        .await;
+    }
+}
+
+/// Reply to a `!roll` text command.  Private results are sent by DM; there
+/// is no interaction to edit, so suspense frames and progress updates are
+/// skipped and only the final result is sent.
+async fn send_text_command_response(
+    ctx: &Context,
+    msg: &Message,
+    response: commands::CommandResponse,
+) {
+    let mut message = CreateMessage::new()
+        .content(response.content.as_str())
+        .components(response.components.clone())
+        .embeds(response.embeds.clone())
+        .files(response.attachments.clone());
+    let sent = if response.ephemeral {
+        outbound::send_with_retry("text command DM", || {
+            msg.author.id.direct_message(&ctx.http, message.clone())
+        })
+        .await
+    } else {
+        message = message
+            .reference_message(msg)
+            .allowed_mentions(CreateAllowedMentions::new().replied_user(false));
+        outbound::send_with_retry("text command reply", || {
+            msg.channel_id.send_message(&ctx.http, message.clone())
+        })
+        .await
+    };
+    let sent = match sent {
+        Ok(sent) => sent,
+        Err(why) => {
+            error!("Cannot reply to text command: {}", why);
+            return;
+        }
+    };
+    if let Some(thread) = &response.thread {
+        post_response_thread(ctx, msg.channel_id, Ok(sent), thread).await;
+    }
+    for followup in &response.followups {
+        let message = CreateMessage::new().content(followup.as_str());
+        let sent = if response.ephemeral {
+            outbound::send_with_retry("text command DM", || {
+                msg.author.id.direct_message(&ctx.http, message.clone())
+            })
+            .await
+        } else {
+            outbound::send_with_retry("text command follow-up", || {
+                msg.channel_id.send_message(&ctx.http, message.clone())
+            })
+            .await
+        };
+        if let Err(why) = sent {
+            error!("Cannot send text command follow-up: {}", why);
+            break;
+        }
+    }
+}
+
+/// Start the thread holding a result too long for one message
+/// (`/settings threads`) from the message the summary was posted as
+async fn post_response_thread(
+    ctx: &Context,
+    channel_id: ChannelId,
+    message: serenity::Result<Message>,
+    thread: &commands::roll::ThreadedOutput,
+) {
+    match message {
+        Ok(message) => {
+            outbound::post_thread(ctx, channel_id, message.id, &thread.name, &thread.messages)
+                .await;
+        }
+        Err(why) => warn!("Cannot find the message to start a results thread: {}", why),
+    }
+}
+
+/// Handle button clicks. Each module owns the custom IDs it prefixes with
+/// its own name; the clicked message is replaced with the handler's result.
+async fn handle_component(
+    ctx: &Context,
+    component: &ComponentInteraction,
+) -> commands::CommandResponse {
+    let custom_id = component.data.custom_id.as_str();
+    let response = if custom_id.starts_with("config:") {
+        commands::config::handle_component(ctx, component)
+            .await
+            .map(commands::CommandResponse::private)
+    } else if custom_id.starts_with("forgetme:") {
+        commands::forgetme::handle_component(ctx, component)
+            .await
+            .map(commands::CommandResponse::private)
+    } else if custom_id.starts_with("purge:") {
+        commands::purge::handle_component(ctx, component)
+            .await
+            .map(commands::CommandResponse::private)
+    } else if custom_id.starts_with("setup:") {
+        commands::setup::handle_component(ctx, component).await
+    } else if custom_id.starts_with(commands::roll::REROLL_PREFIX) {
+        commands::roll::handle_reroll(ctx, component).await
+    } else if custom_id.starts_with(commands::followup::ACTION_PREFIX) {
+        commands::followup::handle_action(ctx, component).await
+    } else if custom_id.starts_with(commands::reroll_select::PICK_PREFIX) {
+        commands::reroll_select::handle_select(ctx, component).await
+    } else {
+        Ok(commands::CommandResponse::private(
+            "This button is no longer supported.".to_string(),
+        ))
+    };
 
-            let mut response_message = serenity::builder::CreateInteractionResponseMessage::new()
-                .content(response_content);
+    response.unwrap_or_else(|e| {
+        error!("Error handling component {}: {}", custom_id, e);
+        commands::CommandResponse::private(
+            "An error occurred while handling that button.".to_string(),
+        )
+    })
+}
+
+async fn send_component_response(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    response: commands::CommandResponse,
+    deadline: outbound::InteractionDeadline,
+) {
+    // Rerolls and quick actions are posted as new messages (without suspense)
+    // under the roll they follow.  Other buttons replace their message in
+    // place; handlers that end a flow return no components, which removes the
+    // old buttons.  A Reroll selected pick edits its roll, but its private
+    // refusals are new messages so they don't replace someone else's roll.
+    let access = outbound::ChannelAccess::from_permissions(component.app_permissions);
+    let content = access.adapt_content(&response.content);
+    let mut message = CreateInteractionResponseMessage::new()
+        .content(content.as_str())
+        .components(response.components.clone());
+    if !response.embeds.is_empty() {
+        message = message.embeds(response.embeds.clone());
+    }
+    let custom_id = component.data.custom_id.as_str();
+    let new_message = custom_id.starts_with(commands::roll::REROLL_PREFIX)
+        || custom_id.starts_with(commands::followup::ACTION_PREFIX)
+        || (custom_id.starts_with(commands::reroll_select::PICK_PREFIX) && response.ephemeral);
+    let reply = if new_message {
+        CreateInteractionResponse::Message(message.ephemeral(response.ephemeral))
+    } else {
+        CreateInteractionResponse::UpdateMessage(message)
+    };
 
-            if ephemeral {
-                response_message = response_message.ephemeral(true);
+    if deadline.can_respond(Instant::now()) {
+        match outbound::send_with_retry("component response", || {
+            component.create_response(&ctx.http, reply.clone())
+        })
+        .await
+        {
+            Ok(()) => {
+                if !new_message {
+                    return;
+                }
+                if let Some(thread) = &response.thread {
+                    let message = component.get_response(&ctx.http).await;
+                    post_response_thread(ctx, component.channel_id, message, thread).await;
+                }
+                for followup in &response.followups {
+                    let followup = CreateInteractionResponseFollowup::new()
+                        .content(access.adapt_content(followup))
+                        .ephemeral(response.ephemeral);
+                    if let Err(why) = outbound::send_with_retry("component follow-up", || {
+                        component.create_followup(&ctx.http, followup.clone())
+                    })
+                    .await
+                    {
+                        error!("Cannot send follow-up message: {}", why);
+                        break;
+                    }
+                }
+                return;
+            }
+            Err(why) if outbound::is_expired_interaction_error(&why) => {}
+            Err(why) => {
+                error!("Cannot respond to component interaction: {}", why);
+                return;
             }
+        }
+    }
 
-            if let Err(why) = command
-                .create_response(
-                    &ctx.http,
-                    serenity::builder::CreateInteractionResponse::Message(response_message),
-                )
-                .await
-            {
+    // Rerolls and quick actions are posted late like commands; a button that
+    // updates its own message in place has nothing new to post
+    if !new_message {
+        warn!("Button {} missed its interaction token", custom_id);
+        return;
+    }
+    warn!(
+        "Button {} missed its interaction token, sending the result as a message",
+        custom_id
+    );
+    let message = CreateMessage::new()
+        .components(response.components)
+        .embeds(response.embeds);
+    if !outbound::post_late_result(
+        ctx,
+        component.channel_id,
+        component.user.id,
+        access,
+        response.ephemeral,
+        &content,
+        message,
+    )
+    .await
+    {
+        return;
+    }
+    for followup in &response.followups {
+        outbound::post_late_result(
+            ctx,
+            component.channel_id,
+            component.user.id,
+            access,
+            response.ephemeral,
+            &access.adapt_content(followup),
+            CreateMessage::new(),
+        )
+        .await;
+    }
+}
+
+/// Post "Rolling…", then edit in each reveal frame followed by the final
+/// result (with its buttons and embeds), pausing between edits for suspense.
+/// A deferred command's "is thinking…" placeholder stands in for "Rolling…".
+/// If the token runs out first, the result is posted as a late message.
+async fn send_suspense_response(
+    ctx: &Context,
+    command: &CommandInteraction,
+    content: String,
+    frames: Vec<String>,
+    response: commands::CommandResponse,
+    deadline: outbound::InteractionDeadline,
+    deferred: bool,
+) {
+    if !deferred {
+        if !deadline.can_respond(Instant::now()) {
+            post_late_command_result(ctx, command, &content, &response).await;
+            return;
+        }
+
+        let mut opening = CreateInteractionResponseMessage::new().content("🎲 Rolling…");
+        if response.ephemeral {
+            opening = opening.ephemeral(true);
+        }
+        if let Err(why) = outbound::send_with_retry("interaction response", || {
+            command.create_response(
+                &ctx.http,
+                CreateInteractionResponse::Message(opening.clone()),
+            )
+        })
+        .await
+        {
+            if outbound::is_expired_interaction_error(&why) {
+                post_late_command_result(ctx, command, &content, &response).await;
+            } else {
                 error!("Cannot respond to slash command: {}", why);
             }
+            return;
         }
     }
+
+    let delay = suspense_delay();
+    let last = frames.len();
+    for (index, frame) in frames
+        .into_iter()
+        .chain(std::iter::once(content.clone()))
+        .enumerate()
+    {
+        tokio::time::sleep(delay).await;
+        if !deadline.can_follow_up(Instant::now()) {
+            post_late_command_result(ctx, command, &content, &response).await;
+            return;
+        }
+
+        let mut edit = EditInteractionResponse::new().content(frame.as_str());
+        if index == last && !response.components.is_empty() {
+            edit = edit.components(response.components.clone());
+        }
+        if index == last && !response.embeds.is_empty() {
+            edit = edit.embeds(response.embeds.clone());
+        }
+        if let Err(why) = outbound::send_with_retry("interaction edit", || {
+            command.edit_response(&ctx.http, edit.clone())
+        })
+        .await
+        {
+            if outbound::is_expired_interaction_error(&why) {
+                post_late_command_result(ctx, command, &content, &response).await;
+            } else {
+                error!("Cannot edit slash command response: {}", why);
+            }
+            return;
+        }
+    }
+}
+
+/// Pause between suspense edits, read from `SUSPENSE_DELAY_MS` and clamped to 1–2 seconds
+fn suspense_delay() -> Duration {
+    let millis = env::var("SUSPENSE_DELAY_MS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(1500)
+        .clamp(1000, 2000);
+    Duration::from_millis(millis)
 }
 
 #[tokio::main]
@@ -204,6 +883,7 @@ async fn main() -> Result<()> {
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
         )
         .init();
+    info!("Memory allocator: {}", allocator::name());
 
     // Initialize database
     let db = Arc::new(database::Database::new().await?);
@@ -380,7 +1060,7 @@ async fn main() -> Result<()> {
     // Use Discord's actual max_concurrency instead of environment variable
     let max_concurrency = actual_max_concurrency;
 
-    let (_owners, _bot_id) = match http.get_current_application_info().await {
+    let (owners, _bot_id) = match http.get_current_application_info().await {
         Ok(info) => {
             let mut owners = HashSet::new();
             if let Some(owner) = &info.owner {
@@ -417,7 +1097,13 @@ async fn main() -> Result<()> {
         );
     }
 
-    let intents = GatewayIntents::GUILDS;
+    // `!roll` text commands need to read messages, a privileged intent
+    let text_commands = commands::text_prefix::intent_enabled();
+    let mut intents = GatewayIntents::GUILDS;
+    if text_commands {
+        intents |= GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
+        info!("MESSAGE_COMMANDS enabled: servers can turn on !roll text commands");
+    }
 
     // Configure minimal cache settings to dramatically reduce memory usage
     let mut cache_settings = CacheSettings::default();
@@ -435,18 +1121,42 @@ async fn main() -> Result<()> {
 
     info!("Configured minimal cache settings for reduced memory usage");
 
+    // Timings and write counters are shared between the middleware pipeline,
+    // the write queue and the /usage report
+    let metrics = Arc::new(metrics::Metrics::new());
+    let pipeline = Pipeline::standard(Arc::clone(&metrics));
+
     // Create client with explicit shard configuration and optimized cache
     let client = Client::builder(&token, intents)
-        .event_handler(Handler::new(shard_count))
+        .event_handler(Handler::new(shard_count, pipeline, text_commands))
         .cache_settings(cache_settings) // Apply optimized cache settings
         .await
         .expect("Error creating client");
 
+    let presence = presence::Presence::new(total_shards);
+
     // Configure sharding after client creation
     {
         let mut data = client.data.write().await;
         data.insert::<ShardManagerContainer>(Arc::clone(&client.shard_manager));
         data.insert::<DatabaseContainer>(Arc::clone(&db));
+        data.insert::<OutboundQueueContainer>(outbound::OutboundQueue::start(
+            outbound::QUEUE_CAPACITY,
+        ));
+        data.insert::<WriteQueueContainer>(write_queue::WriteQueue::start(
+            Arc::clone(&db),
+            Arc::clone(&metrics),
+            write_queue::WRITE_QUEUE_CAPACITY,
+        ));
+        data.insert::<LogDrainContainer>(log_drain::LogDrains::start(Arc::clone(&db)));
+        data.insert::<OwnersContainer>(Arc::new(owners));
+        data.insert::<PresenceContainer>(Arc::clone(&presence));
+        data.insert::<MetricsContainer>(Arc::clone(&metrics));
+        data.insert::<LastRollContainer>(Arc::new(commands::again::LastRolls::new()));
+        data.insert::<QuickActionContainer>(Arc::new(commands::followup::QuickActions::new()));
+        data.insert::<SelectedRerollContainer>(Arc::new(
+            commands::reroll_select::SelectedRerolls::new(),
+        ));
     }
 
     // Create shutdown broadcast channel
@@ -472,6 +1182,25 @@ async fn main() -> Result<()> {
         info!("Statistics collection task stopped");
     });
 
+    // Rotate the bot's status across this process's shards
+    tokio::spawn(presence.run(
+        Arc::clone(&db),
+        Arc::clone(&client.cache),
+        Arc::clone(&client.shard_manager),
+        shutdown_tx.subscribe(),
+    ));
+
+    // Serve the HTTP roll API next to the bot when it's built in and configured
+    #[cfg(feature = "api")]
+    if let Ok(addr) = env::var("API_ADDR") {
+        let api_shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            if let Err(e) = dicemaiden_rs::api::serve(&addr, api_shutdown_rx).await {
+                error!("Roll API error: {}", e);
+            }
+        });
+    }
+
     // Setup signal handlers for graceful shutdown
     let shutdown_signal = setup_signal_handlers(shutdown_tx.clone());
 
@@ -729,6 +1458,11 @@ async fn collect_shard_stats_with_shutdown(
         // Get total guild count more efficiently
         let total_guilds = cache.guilds().len() as i32;
 
+        // Roll history is kept for 30 days regardless of deployment mode
+        if let Err(e) = db.cleanup_old_roll_history().await {
+            warn!("Failed to cleanup old roll history: {}", e);
+        }
+
         if is_multi_process {
             // Multi-process mode: Use process_stats table
             let shard_start = env::var("SHARD_START")
@@ -769,12 +1503,13 @@ async fn collect_shard_stats_with_shutdown(
 
             // Log summary for this process
             info!(
-                "Process stats: {} shards ({}-{}), {} servers, {:.2} MB memory",
+                "Process stats: {} shards ({}-{}), {} servers, {:.2} MB memory ({})",
                 shard_count + 1,
                 shard_start,
                 shard_start + shard_count,
                 total_guilds,
-                memory_usage
+                memory_usage,
+                allocator::name()
             );
         } else {
             // Single-process mode: Use existing shard_stats table
@@ -819,8 +1554,11 @@ async fn collect_shard_stats_with_shutdown(
 
             // Log summary every 15 minutes (same as stats collection interval)
             info!(
-                "Stats summary: {} shards, {} servers, {:.2} MB memory",
-                total_shards_in_process, total_guilds, memory_usage
+                "Stats summary: {} shards, {} servers, {:.2} MB memory ({})",
+                total_shards_in_process,
+                total_guilds,
+                memory_usage,
+                allocator::name()
             );
         }
     }
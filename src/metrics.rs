@@ -0,0 +1,188 @@
+//! In-process timing metrics.
+//!
+//! | Histograms         | Keyed by                 | Recorded by                  |
+//! |--------------------|--------------------------|------------------------------|
+//! | [`Metrics::rolls`]    | Game system (`cod`, `dice`, …) | `usage::record_roll`   |
+//! | [`Metrics::commands`] | Command name (`roll`, `sheet`, …) | the `middleware` pipeline |
+//!
+//! Every successful roll records how long parsing and rolling took under each
+//! game system it used (the same keys as the alias usage counters, see
+//! `parser::usage_keys`), and every command how long it took end to end.
+//! Timings go into fixed-bucket histograms, so recording is a map lookup and
+//! an atomic increment and memory stays constant however many rolls are made.
+//! The owner-only `/usage report:timings` shows each system's p50 / p95 / max,
+//! which makes a newly added system that is slow to roll stand out.
+//!
+//! [`Metrics::writes`] counts what happened to the background database
+//! writes of `write_queue` (written, retried, dropped), shown with `/usage
+//! report:writes`.
+//!
+//! Histograms and counters live in memory for the life of the process and
+//! are not persisted; with multi-process sharding each process reports its own.
+
+use crate::sync::lock;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds (in microseconds) of the histogram buckets; the last bucket
+/// catches everything slower
+pub const BUCKET_BOUNDS_US: [u64; 14] = [
+    10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000,
+];
+
+#[derive(Debug, Default)]
+struct Histogram {
+    buckets: [AtomicU64; BUCKET_BOUNDS_US.len() + 1],
+    count: AtomicU64,
+    max_us: AtomicU64,
+}
+
+impl Histogram {
+    fn record(&self, micros: u64) {
+        let bucket = BUCKET_BOUNDS_US
+            .iter()
+            .position(|bound| micros <= *bound)
+            .unwrap_or(BUCKET_BOUNDS_US.len());
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.max_us.fetch_max(micros, Ordering::Relaxed);
+    }
+
+    // Upper bound of the bucket holding the given percentile; the overflow
+    // bucket reports the slowest roll seen
+    fn percentile_us(&self, percentile: u64) -> u64 {
+        let count = self.count.load(Ordering::Relaxed);
+        let target = (count * percentile).div_ceil(100).max(1);
+        let mut seen = 0;
+        for (bucket, bound) in self.buckets.iter().zip(BUCKET_BOUNDS_US) {
+            seen += bucket.load(Ordering::Relaxed);
+            if seen >= target {
+                return bound;
+            }
+        }
+        self.max_us.load(Ordering::Relaxed)
+    }
+}
+
+/// Timing summary for one system (or command)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemTiming {
+    pub system: String,
+    pub count: u64,
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub max_us: u64,
+}
+
+/// Timing histograms for the bot, shared through `MetricsContainer`
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Parse-and-roll time per game system
+    pub rolls: Timings,
+    /// End-to-end handling time per command
+    pub commands: Timings,
+    /// Outcomes of queued database writes
+    pub writes: WriteCounters,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Timing histograms keyed by name (a game system or a command)
+#[derive(Debug, Default)]
+pub struct Timings {
+    systems: Mutex<HashMap<String, Histogram>>,
+}
+
+impl Timings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one duration under each of the given keys
+    pub fn record(&self, systems: &[String], elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+        let mut histograms = lock(&self.systems);
+        for system in systems {
+            // Only a system's first roll allocates its key
+            match histograms.get(system) {
+                Some(histogram) => histogram.record(micros),
+                None => histograms.entry(system.clone()).or_default().record(micros),
+            }
+        }
+    }
+
+    /// Summaries for every key seen, slowest p95 first
+    pub fn snapshot(&self) -> Vec<SystemTiming> {
+        let histograms = lock(&self.systems);
+        let mut timings: Vec<SystemTiming> = histograms
+            .iter()
+            .map(|(system, histogram)| SystemTiming {
+                system: system.clone(),
+                count: histogram.count.load(Ordering::Relaxed),
+                p50_us: histogram.percentile_us(50),
+                p95_us: histogram.percentile_us(95),
+                max_us: histogram.max_us.load(Ordering::Relaxed),
+            })
+            .collect();
+        timings.sort_by(|a, b| {
+            b.p95_us
+                .cmp(&a.p95_us)
+                .then_with(|| a.system.cmp(&b.system))
+        });
+        timings
+    }
+}
+
+/// Counts of queued database writes by outcome (see `write_queue`)
+#[derive(Debug, Default)]
+pub struct WriteCounters {
+    written: AtomicU64,
+    retried: AtomicU64,
+    dropped_full: AtomicU64,
+    dropped_failed: AtomicU64,
+}
+
+/// A point-in-time copy of [`WriteCounters`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteStats {
+    pub written: u64,
+    /// Attempts repeated after a transient failure
+    pub retried: u64,
+    /// Writes refused because the queue was full
+    pub dropped_full: u64,
+    /// Writes given up on after failing
+    pub dropped_failed: u64,
+}
+
+impl WriteCounters {
+    pub fn record_written(&self) {
+        self.written.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_retry(&self) {
+        self.retried.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_dropped_full(&self) {
+        self.dropped_full.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_dropped_failed(&self) {
+        self.dropped_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> WriteStats {
+        WriteStats {
+            written: self.written.load(Ordering::Relaxed),
+            retried: self.retried.load(Ordering::Relaxed),
+            dropped_full: self.dropped_full.load(Ordering::Relaxed),
+            dropped_failed: self.dropped_failed.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,279 @@
+//! Interaction pipeline: cross-cutting checks that wrap every handler.
+//!
+//! `main::interaction_create` describes each interaction as a [`Request`] and
+//! runs its handler through a [`Pipeline`] of [`Middleware`] stages instead of
+//! repeating the same checks in every command:
+//!
+//! ```text
+//! Dedupe → RateLimit → Permissions → CommandMetrics → handler
+//!                                                        │
+//!        after-hooks run in reverse order  ◄─────────────┘
+//! ```
+//!
+//! | Stage            | Before the handler                               | After the handler          |
+//! |------------------|--------------------------------------------------|----------------------------|
+//! | [`Dedupe`]       | Drop interactions Discord delivered twice        | —                          |
+//! | [`RateLimit`]    | Refuse users sending commands too quickly        | —                          |
+//! | [`Permissions`]  | Guild-only and administrator-only commands       | —                          |
+//! | [`CommandMetrics`] | —                                              | Record the command's time  |
+//!
+//! A stage's `before` hook can let the request continue, answer it itself
+//! (the handler is skipped) or drop it without any response.  `after` hooks
+//! see the final response and run for every stage whose `before` hook ran, so
+//! response post-processing (audit logs, history, webhooks) belongs there.
+//! New cross-cutting features should be a new stage rather than another
+//! branch in the dispatch `match`.
+
+use crate::commands::CommandResponse;
+use crate::i18n::Language;
+use crate::locale::messages;
+use crate::metrics::Metrics;
+use crate::sync::lock;
+use serenity::model::prelude::Permissions as DiscordPermissions;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Recent interaction IDs remembered by [`Dedupe`]
+pub const DEDUPE_CAPACITY: usize = 4096;
+
+/// Commands a user may send in a burst before [`RateLimit`] applies
+pub const RATE_LIMIT_BURST: f64 = 10.0;
+
+/// Commands per second a user regains after a burst
+pub const RATE_LIMIT_PER_SECOND: f64 = 1.0;
+
+// Users tracked by RateLimit before idle ones are pruned
+const RATE_LIMIT_TRACKED_USERS: usize = 4096;
+
+/// Commands only usable in servers, by administrators
+pub const ADMIN_COMMANDS: [&str; 7] = [
+    "config",
+    "setup",
+    "macropack",
+    "alias",
+    "keyword",
+    "followup",
+    "settings",
+];
+
+/// What kind of interaction a [`Request`] describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+    Command,
+    Component,
+    /// A text command in a chat message (`!roll 2d6`)
+    Message,
+}
+
+/// The parts of an interaction the middleware needs, independent of Serenity
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub interaction_id: u64,
+    pub kind: RequestKind,
+    /// Command name, or the custom-ID prefix for components
+    pub name: String,
+    pub user_id: u64,
+    pub guild_id: Option<u64>,
+    /// The member's permissions in the channel (None in DMs)
+    pub permissions: Option<DiscordPermissions>,
+    /// Language of the user's Discord client, for the stages' own replies
+    pub language: Language,
+}
+
+/// Outcome of a stage's `before` hook
+#[derive(Debug)]
+pub enum Flow {
+    /// Pass the request on to the next stage
+    Continue,
+    /// Answer with this response instead of running the handler
+    Respond(Box<CommandResponse>),
+    /// Send nothing at all
+    Ignore,
+}
+
+pub trait Middleware: Send + Sync {
+    fn before(&self, _request: &Request) -> Flow {
+        Flow::Continue
+    }
+
+    fn after(&self, _request: &Request, _response: &mut CommandResponse, _elapsed: Duration) {}
+}
+
+/// An ordered chain of middleware stages
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Box<dyn Middleware>>,
+}
+
+impl Pipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The bot's standard pipeline
+    pub fn standard(metrics: Arc<Metrics>) -> Self {
+        Self::new()
+            .with(Dedupe::default())
+            .with(RateLimit::default())
+            .with(Permissions)
+            .with(CommandMetrics::new(metrics))
+    }
+
+    pub fn with(mut self, stage: impl Middleware + 'static) -> Self {
+        self.stages.push(Box::new(stage));
+        self
+    }
+
+    /// Run `handler` through the pipeline.  Returns `None` when a stage
+    /// decided the interaction should get no response.
+    pub async fn run<F>(&self, request: &Request, handler: F) -> Option<CommandResponse>
+    where
+        F: Future<Output = CommandResponse>,
+    {
+        let started = Instant::now();
+        let mut ran = 0;
+        let mut answered = None;
+
+        for stage in &self.stages {
+            ran += 1;
+            match stage.before(request) {
+                Flow::Continue => {}
+                Flow::Respond(response) => {
+                    answered = Some(*response);
+                    break;
+                }
+                Flow::Ignore => return None,
+            }
+        }
+
+        let mut response = match answered {
+            Some(response) => response,
+            None => handler.await,
+        };
+
+        let elapsed = started.elapsed();
+        for stage in self.stages[..ran].iter().rev() {
+            stage.after(request, &mut response, elapsed);
+        }
+        Some(response)
+    }
+}
+
+/// Drops interactions whose ID was already seen, which Discord can redeliver
+/// after a gateway resume.  Answering twice would fail anyway.
+#[derive(Debug, Default)]
+pub struct Dedupe {
+    seen: Mutex<(HashSet<u64>, VecDeque<u64>)>,
+}
+
+impl Middleware for Dedupe {
+    fn before(&self, request: &Request) -> Flow {
+        let mut guard = lock(&self.seen);
+        let (seen, order) = &mut *guard;
+        if !seen.insert(request.interaction_id) {
+            return Flow::Ignore;
+        }
+        order.push_back(request.interaction_id);
+        if order.len() > DEDUPE_CAPACITY
+            && let Some(oldest) = order.pop_front()
+        {
+            seen.remove(&oldest);
+        }
+        Flow::Continue
+    }
+}
+
+/// Per-user token bucket: a burst of [`RATE_LIMIT_BURST`] commands, then
+/// [`RATE_LIMIT_PER_SECOND`]
+#[derive(Debug, Default)]
+pub struct RateLimit {
+    buckets: Mutex<HashMap<u64, (f64, Instant)>>,
+}
+
+impl RateLimit {
+    /// Take a token for `user_id` at `now`; false if the bucket is empty
+    pub fn allow_at(&self, user_id: u64, now: Instant) -> bool {
+        let mut buckets = lock(&self.buckets);
+
+        // Idle users have a full bucket again, so forget them
+        if buckets.len() > RATE_LIMIT_TRACKED_USERS {
+            let refill_time = Duration::from_secs_f64(RATE_LIMIT_BURST / RATE_LIMIT_PER_SECOND);
+            buckets.retain(|_, (_, last)| now.saturating_duration_since(*last) < refill_time);
+        }
+
+        let (tokens, last) = buckets.entry(user_id).or_insert((RATE_LIMIT_BURST, now));
+        let refilled = now.saturating_duration_since(*last).as_secs_f64() * RATE_LIMIT_PER_SECOND;
+        *tokens = (*tokens + refilled).min(RATE_LIMIT_BURST);
+        *last = now;
+
+        if *tokens < 1.0 {
+            return false;
+        }
+        *tokens -= 1.0;
+        true
+    }
+}
+
+impl Middleware for RateLimit {
+    fn before(&self, request: &Request) -> Flow {
+        if self.allow_at(request.user_id, Instant::now()) {
+            Flow::Continue
+        } else {
+            Flow::Respond(Box::new(CommandResponse::private(
+                messages(request.language).rate_limited.to_string(),
+            )))
+        }
+    }
+}
+
+/// Enforces [`ADMIN_COMMANDS`]: servers only, and the Administrator
+/// permission.  Discord hides these commands from other members via
+/// `default_member_permissions`, but server owners can override that.
+#[derive(Debug)]
+pub struct Permissions;
+
+impl Middleware for Permissions {
+    fn before(&self, request: &Request) -> Flow {
+        if request.kind != RequestKind::Command || !ADMIN_COMMANDS.contains(&request.name.as_str())
+        {
+            return Flow::Continue;
+        }
+        if request.guild_id.is_none() {
+            return Flow::Respond(Box::new(CommandResponse::private(
+                messages(request.language).servers_only.to_string(),
+            )));
+        }
+        if !request
+            .permissions
+            .is_some_and(|permissions| permissions.administrator())
+        {
+            return Flow::Respond(Box::new(CommandResponse::private(
+                messages(request.language).administrator_only.to_string(),
+            )));
+        }
+        Flow::Continue
+    }
+}
+
+/// Records how long each command took, in [`Metrics::commands`]
+pub struct CommandMetrics {
+    metrics: Arc<Metrics>,
+}
+
+impl CommandMetrics {
+    pub fn new(metrics: Arc<Metrics>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl Middleware for CommandMetrics {
+    fn after(&self, request: &Request, _response: &mut CommandResponse, elapsed: Duration) {
+        if request.kind == RequestKind::Command {
+            self.metrics
+                .commands
+                .record(std::slice::from_ref(&request.name), elapsed);
+        }
+    }
+}
@@ -0,0 +1,473 @@
+//! Outbound Discord messaging: retries and a background queue.
+//!
+//! Every message the bot sends or edits goes through this module so that a
+//! burst of rolls in a busy server degrades gracefully instead of cascading
+//! into failed interactions.
+//!
+//! * [`send_with_retry`] — run a Discord request, retrying rate-limited
+//!   (`429`), server-side (`5xx`) and connection failures with exponential
+//!   backoff.  Serenity's own ratelimiter already waits out the
+//!   `retry-after` header before a 429 reaches us, so the backoff here only
+//!   covers what it gives up on.  A request that may have reached Discord
+//!   (a timeout or a dropped response) is not retried, since sending a
+//!   message twice would post it twice.
+//! * [`OutboundQueue`] — a bounded queue drained by a single background task
+//!   for non-critical sends (tally message updates and similar).  When the
+//!   queue is full the send is dropped and logged rather than blocking the
+//!   roll that triggered it.
+//!
+//! Interaction responses are critical (Discord invalidates the interaction
+//! after 3 seconds) and are sent directly with [`send_with_retry`]; anything
+//! that can lag behind the roll result should be queued.
+//!
+//! # Restricted channels
+//!
+//! [`ChannelAccess`] reads the bot's permissions in the interaction's channel
+//! (Discord sends them with every interaction, so no lookup is needed) and
+//! lets senders adapt instead of failing:
+//!
+//! | Missing                      | Fallback                                            |
+//! |------------------------------|-----------------------------------------------------|
+//! | Use External Emojis          | Custom emojis are sent as `:name:` text             |
+//! | View Channel / Send Messages | Channel messages (tally pins) are skipped with a notice |
+//! | Manage Messages              | Tally messages are posted but not pinned            |
+//! | Create Public Threads        | Long results are cut to fit one message (`/settings threads`) |
+//!
+//! Channel messages are also subject to slowmode unless the bot can manage
+//! messages or the channel.  A send refused by slowmode is not retried with
+//! backoff; [`slowmode_delay`] tells the sender how long to wait instead.
+//!
+//! # Interaction deadlines
+//!
+//! Discord drops an interaction that isn't answered within 3 seconds, and its
+//! token stops accepting edits and follow-ups 15 minutes after it was created.
+//! [`InteractionDeadline`] tracks both from when the interaction arrived.  A
+//! command still running after [`DEFER_AFTER`] is deferred (Discord shows
+//! "is thinking…") so its result can be edited in later.  A result that
+//! misses its token anyway, or finds it expired
+//! ([`is_expired_interaction_error`]), is sent by [`post_late_result`] as a
+//! channel message mentioning the requester, or by DM if it was private, so
+//! it is never lost silently.  Nothing is posted in a channel the bot can't
+//! send messages to.
+
+use crate::OutboundQueueContainer;
+use crate::dice::DISCORD_MESSAGE_LIMIT;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serenity::{
+    all::{
+        AutoArchiveDuration, Channel, ChannelId, CreateAllowedMentions, CreateMessage,
+        CreateThread, MessageId, Permissions, UserId,
+    },
+    http::{Http, HttpError},
+    prelude::Context,
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+/// Maximum attempts for a single Discord request, including the first
+pub const MAX_ATTEMPTS: u32 = 3;
+
+/// Pending sends allowed in the queue before new ones are dropped
+pub const QUEUE_CAPACITY: usize = 256;
+
+/// Discord's JSON error code for a message refused by slowmode
+pub const SLOWMODE_ERROR_CODE: isize = 20016;
+
+/// Longest slowmode Discord allows (6 hours)
+pub const MAX_SLOWMODE: Duration = Duration::from_secs(21_600);
+
+/// Discord's window for the first response to an interaction
+pub const INITIAL_RESPONSE_WINDOW: Duration = Duration::from_secs(3);
+
+/// How long an interaction token can edit its response and send follow-ups
+pub const INTERACTION_TOKEN_LIFETIME: Duration = Duration::from_secs(15 * 60);
+
+/// A command still running this long after its interaction arrived is deferred
+pub const DEFER_AFTER: Duration = Duration::from_secs(2);
+
+// Requests this close to a deadline count as late, allowing for the time the
+// request takes to reach Discord
+const DEADLINE_MARGIN: Duration = Duration::from_millis(500);
+
+// Discord's JSON error codes for an expired interaction or token: Unknown
+// Webhook, Unknown Interaction and Invalid Webhook Token
+const EXPIRED_INTERACTION_ERROR_CODES: [isize; 3] = [10015, 10062, 50027];
+
+static CUSTOM_EMOJI_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"<a?:(\w{2,32}):\d+>").expect("Failed to compile CUSTOM_EMOJI_REGEX"));
+
+const BASE_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 8000;
+
+type Job = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Delay before retry number `attempt` (0-based): 500ms, 1s, 2s, … capped at 8s
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let millis = BASE_BACKOFF_MS.saturating_mul(1 << attempt.min(16));
+    Duration::from_millis(millis.min(MAX_BACKOFF_MS))
+}
+
+/// Whether a failed request is worth retrying.  Slowmode refusals are not:
+/// the wait is usually far longer than the backoff.  Of the transport
+/// errors only failed connections are, as nothing was sent; after a timeout
+/// Discord may already have posted the message.
+pub fn is_retryable(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response)) => {
+            let status = response.status_code.as_u16();
+            (status == 429 || status >= 500) && response.error.code != SLOWMODE_ERROR_CODE
+        }
+        serenity::Error::Http(HttpError::Request(e)) => e.is_connect(),
+        _ => false,
+    }
+}
+
+/// Whether a send was refused because of the channel's slowmode
+pub fn is_slowmode_error(error: &serenity::Error) -> bool {
+    matches!(
+        error,
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response))
+            if response.error.code == SLOWMODE_ERROR_CODE
+    )
+}
+
+/// How long to wait before posting again in a channel with slowmode, looked
+/// up from the channel (at least a second, at most [`MAX_SLOWMODE`])
+pub async fn slowmode_delay(http: &Http, channel_id: ChannelId) -> Duration {
+    let seconds = match channel_id.to_channel(http).await {
+        Ok(Channel::Guild(channel)) => channel.rate_limit_per_user.unwrap_or(0),
+        Ok(_) => 0,
+        Err(e) => {
+            warn!("Failed to look up slowmode for {}: {}", channel_id, e);
+            0
+        }
+    };
+    Duration::from_secs(u64::from(seconds).max(1)).min(MAX_SLOWMODE)
+}
+
+/// What the bot may do in the channel an interaction came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelAccess {
+    /// Post new messages (View Channel and Send Messages)
+    pub send_messages: bool,
+    /// Pin messages (Manage Messages)
+    pub pin_messages: bool,
+    /// Show custom emojis from other servers
+    pub external_emojis: bool,
+    /// Start threads from messages and post in them (Create Public Threads
+    /// and Send Messages in Threads)
+    pub create_threads: bool,
+}
+
+impl Default for ChannelAccess {
+    fn default() -> Self {
+        Self::from_permissions(None)
+    }
+}
+
+impl ChannelAccess {
+    /// From an interaction's `app_permissions`; unknown permissions allow
+    /// everything, as the bot behaved before checking
+    pub fn from_permissions(permissions: Option<Permissions>) -> Self {
+        let has = |required: Permissions| permissions.is_none_or(|p| p.contains(required));
+        Self {
+            send_messages: has(Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES),
+            pin_messages: has(Permissions::MANAGE_MESSAGES),
+            external_emojis: has(Permissions::USE_EXTERNAL_EMOJIS),
+            create_threads: has(
+                Permissions::CREATE_PUBLIC_THREADS | Permissions::SEND_MESSAGES_IN_THREADS
+            ),
+        }
+    }
+
+    /// Adapt message content to the channel: without the external emoji
+    /// permission, custom emojis (which may come from roll comments and
+    /// labels) are sent as their `:name:` text
+    pub fn adapt_content(&self, content: &str) -> String {
+        if self.external_emojis {
+            content.to_string()
+        } else {
+            CUSTOM_EMOJI_REGEX.replace_all(content, ":$1:").into_owned()
+        }
+    }
+}
+
+/// Discord's deadlines for answering one interaction
+#[derive(Debug, Clone, Copy)]
+pub struct InteractionDeadline {
+    received: Instant,
+}
+
+impl InteractionDeadline {
+    /// Deadlines of an interaction that arrived at `received`
+    pub fn new(received: Instant) -> Self {
+        Self { received }
+    }
+
+    /// Whether the first response can still be sent
+    pub fn can_respond(&self, now: Instant) -> bool {
+        self.within(INITIAL_RESPONSE_WINDOW, now)
+    }
+
+    /// Whether the token can still edit the response or send follow-ups
+    pub fn can_follow_up(&self, now: Instant) -> bool {
+        self.within(INTERACTION_TOKEN_LIFETIME, now)
+    }
+
+    fn within(&self, window: Duration, now: Instant) -> bool {
+        now.saturating_duration_since(self.received) + DEADLINE_MARGIN < window
+    }
+}
+
+/// Whether a Discord JSON error code means the interaction or its token expired
+pub fn is_expired_interaction_code(code: isize) -> bool {
+    EXPIRED_INTERACTION_ERROR_CODES.contains(&code)
+}
+
+/// Whether a request failed because the interaction or its token expired
+pub fn is_expired_interaction_error(error: &serenity::Error) -> bool {
+    matches!(
+        error,
+        serenity::Error::Http(HttpError::UnsuccessfulRequest(response))
+            if is_expired_interaction_code(response.error.code)
+    )
+}
+
+/// Content of a late result: `mention` (if any) and a note, then the result,
+/// shortened to fit a message
+pub fn late_result_content(mention: Option<UserId>, content: &str) -> String {
+    let note = match mention {
+        Some(user_id) => format!("<@{user_id}>, this took too long to show as a reply:\n"),
+        None => "This took too long to show as a reply:\n".to_string(),
+    };
+    let room = DISCORD_MESSAGE_LIMIT.saturating_sub(note.chars().count());
+    if content.chars().count() <= room {
+        return format!("{note}{content}");
+    }
+    let shortened: String = content.chars().take(room.saturating_sub(1)).collect();
+    format!("{note}{shortened}…")
+}
+
+/// Send a result whose interaction token ran out.  `message` carries
+/// everything but the content.  Public results are posted in `channel_id`
+/// mentioning the requester, if the bot may send messages there (waiting out
+/// slowmode once); private results are sent to the requester by DM.  Returns
+/// false if the result couldn't be sent.
+pub async fn post_late_result(
+    ctx: &Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+    access: ChannelAccess,
+    ephemeral: bool,
+    content: &str,
+    message: CreateMessage,
+) -> bool {
+    // A private result never goes to the channel
+    if ephemeral {
+        let message = message.content(late_result_content(None, content));
+        return match send_with_retry("late result DM", || {
+            user_id.direct_message(&ctx.http, message.clone())
+        })
+        .await
+        {
+            Ok(_) => true,
+            Err(e) => {
+                warn!("Failed to DM late result to {}: {}", user_id, e);
+                false
+            }
+        };
+    }
+
+    if !access.send_messages {
+        warn!(
+            "Dropped late result in {}: no permission to send messages",
+            channel_id
+        );
+        return false;
+    }
+    let message = message
+        .content(late_result_content(Some(user_id), content))
+        .allowed_mentions(CreateAllowedMentions::new().users([user_id]));
+    match send_with_retry("late result", || {
+        channel_id.send_message(&ctx.http, message.clone())
+    })
+    .await
+    {
+        Ok(_) => true,
+        Err(e) if is_slowmode_error(&e) => {
+            let delay = slowmode_delay(&ctx.http, channel_id).await;
+            warn!(
+                "Late result in {} held by slowmode, posting in {}s",
+                channel_id,
+                delay.as_secs()
+            );
+            let http = ctx.http.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                if let Err(e) = channel_id.send_message(&http, message).await {
+                    warn!("Failed to post late result in {}: {}", channel_id, e);
+                }
+            });
+            true
+        }
+        Err(e) => {
+            warn!("Failed to post late result in {}: {}", channel_id, e);
+            false
+        }
+    }
+}
+
+/// Start a thread named `name` from a posted message and send `messages` in
+/// it one after another, for results too long for one message
+/// (`/settings threads`).  False if the thread couldn't be started; a
+/// message that fails to send is logged and the rest are still tried.
+pub async fn post_thread(
+    ctx: &Context,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    name: &str,
+    messages: &[String],
+) -> bool {
+    let builder = CreateThread::new(name).auto_archive_duration(AutoArchiveDuration::OneDay);
+    let thread = match send_with_retry("thread start", || {
+        channel_id.create_thread_from_message(&ctx.http, message_id, builder.clone())
+    })
+    .await
+    {
+        Ok(thread) => thread,
+        Err(e) => {
+            warn!("Failed to start results thread in {}: {}", channel_id, e);
+            return false;
+        }
+    };
+    for content in messages {
+        let message = CreateMessage::new()
+            .content(content.as_str())
+            .allowed_mentions(CreateAllowedMentions::new());
+        if let Err(e) = send_with_retry("thread message", || {
+            thread.id.send_message(&ctx.http, message.clone())
+        })
+        .await
+        {
+            warn!("Failed to post results in thread {}: {}", thread.id, e);
+        }
+    }
+    true
+}
+
+/// Run a Discord request, retrying transient failures with backoff.
+///
+/// `label` identifies the request in logs.
+pub async fn send_with_retry<T, F, Fut>(label: &str, mut request: F) -> serenity::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = serenity::Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match request().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt + 1 < MAX_ATTEMPTS && is_retryable(&e) => {
+                let delay = backoff_delay(attempt);
+                warn!(
+                    "{} failed ({}), retrying in {}ms",
+                    label,
+                    e,
+                    delay.as_millis()
+                );
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Bounded queue for non-critical sends, drained by one background task
+#[derive(Clone)]
+pub struct OutboundQueue {
+    sender: mpsc::Sender<(String, Job)>,
+}
+
+impl OutboundQueue {
+    /// Create the queue and spawn its worker on the current Tokio runtime
+    pub fn start(capacity: usize) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<(String, Job)>(capacity);
+
+        tokio::spawn(async move {
+            while let Some((label, job)) = receiver.recv().await {
+                job.await;
+                tracing::debug!("Outbound send completed: {}", label);
+            }
+        });
+
+        Self { sender }
+    }
+
+    /// Queue a send; it runs with [`send_with_retry`] semantics in the background.
+    ///
+    /// Returns false (and logs) if the queue is full or closed and the send was dropped.
+    pub fn enqueue<T, F, Fut>(&self, label: impl Into<String>, request: F) -> bool
+    where
+        T: Send + 'static,
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = serenity::Result<T>> + Send + 'static,
+    {
+        let label = label.into();
+        let job_label = label.clone();
+        self.enqueue_job(label, async move {
+            if let Err(e) = send_with_retry(&job_label, request).await {
+                error!("Dropped outbound send {}: {}", job_label, e);
+            }
+        })
+    }
+
+    /// Queue a multi-step job (e.g. edit, falling back to a new message).
+    ///
+    /// The job is responsible for its own retries via [`send_with_retry`].
+    pub fn enqueue_job(
+        &self,
+        label: impl Into<String>,
+        job: impl Future<Output = ()> + Send + 'static,
+    ) -> bool {
+        match self.sender.try_send((label.into(), Box::pin(job))) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full((label, _))) => {
+                warn!("Outbound queue full, dropping send: {}", label);
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed((label, _))) => {
+                error!("Outbound queue closed, dropping send: {}", label);
+                false
+            }
+        }
+    }
+}
+
+/// Queue a job on the bot's shared [`OutboundQueue`], running it directly if
+/// no queue has been registered (e.g. in tools that skip `main`)
+pub async fn enqueue_job(
+    ctx: &Context,
+    label: impl Into<String>,
+    job: impl Future<Output = ()> + Send + 'static,
+) -> bool {
+    let queue = ctx
+        .data
+        .read()
+        .await
+        .get::<OutboundQueueContainer>()
+        .cloned();
+    match queue {
+        Some(queue) => queue.enqueue_job(label, job),
+        None => {
+            tokio::spawn(job);
+            true
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! Rotating bot presence.
+//!
+//! The bot's status cycles through a list of messages, moving to the next
+//! one every [`rotation_interval`].  The list is the first non-empty one of:
+//!
+//! 1. the `presence_messages` table, edited with `/admin presence`;
+//! 2. the `PRESENCE_MESSAGES` environment variable, messages separated by `|`;
+//! 3. [`DEFAULT_MESSAGES`].
+//!
+//! Each message is `[<season>] <kind>: <text>`:
+//!
+//! | Part   | Meaning                                                            |
+//! |--------|--------------------------------------------------------------------|
+//! | season | Optional `[MM-DD..MM-DD]`: only shown between these dates (UTC), wrapping past New Year |
+//! | kind   | `playing`, `listening`, `watching`, `competing` or `custom`; `listening` if left out |
+//! | text   | Shown with the placeholders below filled in, cut to 128 characters |
+//!
+//! | Placeholder | Value                                                         |
+//! |-------------|---------------------------------------------------------------|
+//! | `{rolls}`   | Rolls served, from the alias usage counters (`/usage`)        |
+//! | `{servers}` | Servers the bot is in                                         |
+//! | `{shard}`   | Number of the shard showing the message, counting from 1      |
+//! | `{shards}`  | Total shards                                                  |
+//! | `{version}` | Dice engine version (see `version.rs`)                        |
+//!
+//! [`Presence::run`] sets the message on every shard of the process each
+//! tick, so `{shard}` differs per shard; the `ready` handler shows the
+//! current message when a shard connects.  With multi-process sharding each
+//! process rotates its own shards, and `{servers}` adds up the servers every
+//! process last reported in `process_stats`.
+//!
+//! | Variable                 | Default | Description                          |
+//! |--------------------------|---------|--------------------------------------|
+//! | `PRESENCE_MESSAGES`      |         | `\|`-separated messages              |
+//! | `PRESENCE_INTERVAL_SECS` | 300     | Seconds per message (at least 60)    |
+
+use crate::database::Database;
+use crate::version;
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, NaiveDate, Utc};
+use serenity::{cache::Cache, gateway::ActivityData, gateway::ShardManager};
+use std::env;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::{select, sync::broadcast, time::interval};
+use tracing::{info, warn};
+
+/// Messages used when neither the database nor `PRESENCE_MESSAGES` has any
+pub const DEFAULT_MESSAGES: [&str; 6] = [
+    "listening: /roll",
+    "playing: {rolls} rolls served",
+    "watching: {servers} servers",
+    "[10-24..10-31] playing: 🎃 Rolling for candy",
+    "[12-18..12-31] playing: 🎄 Rolling for presents",
+    "[01-01..01-03] playing: 🎆 Rolling into the new year",
+];
+
+/// Time per message without `PRESENCE_INTERVAL_SECS`
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Shortest time per message, well inside Discord's presence update limit
+pub const MIN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Messages `/admin presence` keeps
+pub const MAX_MESSAGES: usize = 25;
+
+/// Longest activity text Discord shows
+pub const MAX_ACTIVITY_LENGTH: usize = 128;
+
+/// How the status is worded in Discord
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityKind {
+    Playing,
+    Listening,
+    Watching,
+    Competing,
+    /// The text on its own
+    Custom,
+}
+
+impl ActivityKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "playing" => Some(Self::Playing),
+            "listening" => Some(Self::Listening),
+            "watching" => Some(Self::Watching),
+            "competing" => Some(Self::Competing),
+            "custom" => Some(Self::Custom),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Playing => "playing",
+            Self::Listening => "listening",
+            Self::Watching => "watching",
+            Self::Competing => "competing",
+            Self::Custom => "custom",
+        }
+    }
+}
+
+/// Dates a seasonal message is shown between, inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Season {
+    /// (month, day)
+    pub start: (u32, u32),
+    pub end: (u32, u32),
+}
+
+impl Season {
+    /// Read `MM-DD..MM-DD`
+    pub fn parse(value: &str) -> Option<Self> {
+        let (start, end) = value.trim().split_once("..")?;
+        let day = |value: &str| {
+            let (month, day) = value.trim().split_once('-')?;
+            let (month, day) = (month.parse().ok()?, day.parse().ok()?);
+            // Any leap year accepts every real date, 02-29 included
+            NaiveDate::from_ymd_opt(2024, month, day)?;
+            Some((month, day))
+        };
+        Some(Self {
+            start: day(start)?,
+            end: day(end)?,
+        })
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        let day = (date.month(), date.day());
+        if self.start <= self.end {
+            self.start <= day && day <= self.end
+        } else {
+            // Wraps past New Year, e.g. 12-28..01-03
+            day >= self.start || day <= self.end
+        }
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}-{:02}..{:02}-{:02}",
+            self.start.0, self.start.1, self.end.0, self.end.1
+        )
+    }
+}
+
+/// One status in the rotation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresenceMessage {
+    pub kind: ActivityKind,
+    pub text: String,
+    pub season: Option<Season>,
+}
+
+impl PresenceMessage {
+    /// Read `[<season>] <kind>: <text>` (see the module docs)
+    pub fn parse(line: &str) -> Result<Self> {
+        let mut rest = line.trim();
+        let mut season = None;
+        if let Some(dated) = rest.strip_prefix('[') {
+            let (dates, text) = dated
+                .split_once(']')
+                .ok_or_else(|| anyhow!("Missing `]` after the season in `{line}`"))?;
+            season = Some(Season::parse(dates).ok_or_else(|| {
+                anyhow!("Invalid season `{dates}`: expected dates like `12-18..12-31`")
+            })?);
+            rest = text.trim();
+        }
+
+        let (kind, text) = match rest.split_once(':') {
+            Some((kind, text)) => match ActivityKind::parse(kind) {
+                Some(kind) => (kind, text.trim()),
+                None => (ActivityKind::Listening, rest),
+            },
+            None => (ActivityKind::Listening, rest),
+        };
+        if text.is_empty() {
+            return Err(anyhow!("Presence messages need some text"));
+        }
+        if text.chars().count() > MAX_ACTIVITY_LENGTH {
+            return Err(anyhow!(
+                "Presence messages can be at most {MAX_ACTIVITY_LENGTH} characters"
+            ));
+        }
+
+        Ok(Self {
+            kind,
+            text: text.to_string(),
+            season,
+        })
+    }
+
+    /// Whether the message is shown on `date`
+    pub fn in_season(&self, date: NaiveDate) -> bool {
+        self.season.is_none_or(|season| season.contains(date))
+    }
+
+    /// The text with its placeholders filled in for shard `shard` (from 0)
+    pub fn render(&self, stats: &PresenceStats, shard: u32) -> String {
+        let text = self
+            .text
+            .replace("{rolls}", &group_digits(stats.rolls.max(0) as u64))
+            .replace("{servers}", &group_digits(stats.servers))
+            .replace("{shard}", &(shard + 1).to_string())
+            .replace("{shards}", &stats.shards.to_string())
+            .replace("{version}", &version::engine_version().to_string());
+        text.chars().take(MAX_ACTIVITY_LENGTH).collect()
+    }
+
+    pub fn activity(&self, stats: &PresenceStats, shard: u32) -> ActivityData {
+        let text = self.render(stats, shard);
+        match self.kind {
+            ActivityKind::Playing => ActivityData::playing(text),
+            ActivityKind::Listening => ActivityData::listening(text),
+            ActivityKind::Watching => ActivityData::watching(text),
+            ActivityKind::Competing => ActivityData::competing(text),
+            ActivityKind::Custom => ActivityData::custom(text),
+        }
+    }
+}
+
+impl fmt::Display for PresenceMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(season) = self.season {
+            write!(f, "[{season}] ")?;
+        }
+        write!(f, "{}: {}", self.kind.as_str(), self.text)
+    }
+}
+
+/// Numbers the placeholders are filled in from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PresenceStats {
+    pub rolls: i64,
+    pub servers: u64,
+    pub shards: u32,
+}
+
+/// The message shown at rotation step `tick` on `date`: the messages in
+/// season take turns
+pub fn pick(messages: &[PresenceMessage], date: NaiveDate, tick: u64) -> Option<&PresenceMessage> {
+    let in_season: Vec<_> = messages
+        .iter()
+        .filter(|message| message.in_season(date))
+        .collect();
+    if in_season.is_empty() {
+        return None;
+    }
+    Some(in_season[(tick % in_season.len() as u64) as usize])
+}
+
+/// Read a `|`-separated list, skipping (and logging) invalid messages
+pub fn parse_messages(list: &str) -> Vec<PresenceMessage> {
+    list.split('|')
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| {
+            PresenceMessage::parse(line)
+                .inspect_err(|e| warn!("Ignoring presence message: {}", e))
+                .ok()
+        })
+        .collect()
+}
+
+/// `PRESENCE_INTERVAL_SECS`, at least [`MIN_INTERVAL`]
+pub fn rotation_interval() -> Duration {
+    env::var("PRESENCE_INTERVAL_SECS")
+        .ok()
+        .and_then(|secs| secs.trim().parse().ok())
+        .map_or(DEFAULT_INTERVAL, Duration::from_secs)
+        .max(MIN_INTERVAL)
+}
+
+/// `1234567` as `1,234,567`
+pub fn group_digits(value: u64) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// The message list in use: the database's, `PRESENCE_MESSAGES` or the defaults
+pub async fn load_messages(db: &Database) -> Vec<PresenceMessage> {
+    match db.get_presence_messages().await {
+        Ok(stored) if !stored.is_empty() => {
+            let messages: Vec<_> = stored
+                .iter()
+                .filter_map(|(_, line)| PresenceMessage::parse(line).ok())
+                .collect();
+            if !messages.is_empty() {
+                return messages;
+            }
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to load presence messages: {}", e),
+    }
+
+    let configured = env::var("PRESENCE_MESSAGES")
+        .map(|list| parse_messages(&list))
+        .unwrap_or_default();
+    if !configured.is_empty() {
+        return configured;
+    }
+    DEFAULT_MESSAGES
+        .iter()
+        .filter_map(|line| PresenceMessage::parse(line).ok())
+        .collect()
+}
+
+/// The rotation's current message, shared with the `ready` handler
+#[derive(Debug)]
+pub struct Presence {
+    total_shards: u32,
+    current: Mutex<Option<(PresenceMessage, PresenceStats)>>,
+}
+
+impl Presence {
+    pub fn new(total_shards: u32) -> Arc<Self> {
+        Arc::new(Self {
+            total_shards,
+            current: Mutex::new(None),
+        })
+    }
+
+    /// What shard `shard` should show now; `Listening to /roll` until the
+    /// rotation has started
+    pub fn activity(&self, shard: u32) -> ActivityData {
+        match &*self.current.lock().unwrap_or_else(|e| e.into_inner()) {
+            Some((message, stats)) => message.activity(stats, shard),
+            None => ActivityData::listening("/roll"),
+        }
+    }
+
+    /// Rotate the presence of the process's shards until shutdown
+    pub async fn run(
+        self: Arc<Self>,
+        db: Arc<Database>,
+        cache: Arc<Cache>,
+        shard_manager: Arc<ShardManager>,
+        mut shutdown_rx: broadcast::Receiver<()>,
+    ) {
+        let every = rotation_interval();
+        info!("Rotating bot presence every {}s", every.as_secs());
+        let mut ticker = interval(every);
+        let mut tick = 0u64;
+
+        loop {
+            select! {
+                _ = ticker.tick() => {}
+                _ = shutdown_rx.recv() => break,
+            }
+
+            let messages = load_messages(&db).await;
+            let Some(message) = pick(&messages, Utc::now().date_naive(), tick).cloned() else {
+                continue;
+            };
+            tick += 1;
+            let stats = self.gather_stats(&db, &cache).await;
+
+            let runners = shard_manager.runners.lock().await;
+            for (shard_id, runner) in runners.iter() {
+                runner
+                    .runner_tx
+                    .set_activity(Some(message.activity(&stats, shard_id.0)));
+            }
+            drop(runners);
+            *self.current.lock().unwrap_or_else(|e| e.into_inner()) = Some((message, stats));
+        }
+        info!("Presence rotation stopped");
+    }
+
+    async fn gather_stats(&self, db: &Database, cache: &Cache) -> PresenceStats {
+        let rolls = db.total_alias_uses().await.unwrap_or_else(|e| {
+            warn!("Failed to count rolls for presence: {}", e);
+            0
+        });
+        let local_servers = cache.guilds().len() as u64;
+        let reported_servers = match db.get_all_process_stats().await {
+            Ok(stats) => stats
+                .iter()
+                .map(|process| process.server_count.max(0) as u64)
+                .sum(),
+            Err(e) => {
+                warn!("Failed to read process stats for presence: {}", e);
+                0
+            }
+        };
+
+        PresenceStats {
+            rolls,
+            servers: local_servers.max(reported_servers),
+            shards: self.total_shards,
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Golden-output checks of the dice engine, run by `/admin selftest`.
+//!
+//! Each check rolls a canned expression with a fixed seed (check `n` draws
+//! from `rng::session_rng(SELFTEST_SEED, n)`) and compares the formatted
+//! output with the one recorded here.  A mismatch after an upgrade means a
+//! roll's result or its display changed: a parser, roller or formatter
+//! regression, or a new `rand` version that no longer produces the same
+//! stream from a seed (which would also break `/session replay`).
+//!
+//! When a change to the output is intended, update the expected string; the
+//! integration tests run the same checks, so they fail until it is.
+
+use crate::dice::{self, rng};
+
+/// Seed for every check's RNG stream
+pub const SELFTEST_SEED: [u8; 32] = *b"dicemaiden-rs engine selftest #1";
+
+// Failures shown in the report, to keep it within a Discord message
+const MAX_LISTED_FAILURES: usize = 5;
+
+/// Expressions and their expected output with [`SELFTEST_SEED`]
+pub const CHECKS: &[(&str, &str)] = &[
+    ("4d6", "Roll: `[4, 4, 3, 1]` = **12**"),
+    ("4d6 k3", "Roll: `[5, 3, 2, 1]` ~~[1]~~ = **10**"),
+    ("4d6 d1 + 2", "Roll: `[4, 4, 3, 1]` ~~[1]~~ = **13**"),
+    ("2d20 kl1", "Roll: `[19, 12]` ~~[19]~~ = **12**"),
+    ("1d100", "Roll: `[83]` = **83**"),
+    (
+        "10d10 t8",
+        "Roll: `[6, 5, 5, 4, 3, 3, 3, 3, 2, 1]` = **0** successes",
+    ),
+    (
+        "6d10 t8 f1",
+        "Roll: `[9, 9, 7, 7, 5, 1]` = **1** successes (1 failures)",
+    ),
+    (
+        "5d6 e6",
+        "Roll: `[6, 5, 3, 2, 2, 1]` = **19**\n*Note: 1 die exploded*",
+    ),
+    (
+        "4d6 ie6",
+        "Roll: `[6, 4, 4, 2, 2]` = **18**\n*Note: 1 die exploded*",
+    ),
+    (
+        "4d6 r2",
+        "Roll: `[6, 4, 3, 3]` = **16**\n*Note: 1 die rerolled*",
+    ),
+    ("3d6 * 2 - 1", "Roll: `[4, 3, 1]` = **15**"),
+    ("1d20+5 ! Attack", "Roll: `[14]` = **19** Reason: `Attack`"),
+    (
+        "(Fireball) 8d6",
+        "**Fireball**: Roll: `[6, 6, 6, 6, 5, 4, 2, 1]` = **36**",
+    ),
+    (
+        "3 1d20+2",
+        "**Set 1**: Roll: `[6]` = **8**\n**Set 2**: Roll: `[6]` = **8**\n**Set 3**: Roll: `[1]` = **3**\n**Total: 19**",
+    ),
+    ("4*7+2", "= **30**"),
+    ("fr 6d10 t7", "Jet: `[10, 9, 7, 6, 5, 3]` = **3** succès"),
+    (
+        "4df",
+        "Roll: `[ ,  , +, -]` = **0**\n*Note: Fudge dice: 1=(-), 2=( ), 3=(+)*",
+    ),
+    (
+        "sw8",
+        "Roll: `[3]` `[4]` = **4**\n*Note: Wild die (d6) kept: 4 beats Trait die (d8): 3*\n*Note: Savage Worlds: Trait die + Wild die, keep highest*",
+    ),
+    (
+        "wng 4d6",
+        "Roll: `[1, 3, 4, 6]` = Wrath: `1` | TOTAL - Icons: `1` Exalted Icons: `1` (Value:2)\n*Note: Wrath die rolled 1 - Complication!*",
+    ),
+    ("dd66", "Roll: `[6]` + `[5]` = **65**"),
+];
+
+/// A check whose output differed from the recorded one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckFailure {
+    pub expression: &'static str,
+    pub expected: &'static str,
+    pub actual: String,
+}
+
+/// Run one check: `number` is its 1-based position, which picks its RNG stream
+pub fn run_check(number: u64, expression: &str) -> String {
+    match dice::parse_and_roll_with_rng(expression, &mut rng::session_rng(&SELFTEST_SEED, number)) {
+        Ok(results) => dice::format_multiple_results_with_limit(&results),
+        Err(e) => format!("error: {e}"),
+    }
+}
+
+/// Run every check, returning the ones that failed
+pub fn run_checks() -> Vec<CheckFailure> {
+    CHECKS
+        .iter()
+        .zip(1..)
+        .filter_map(|(&(expression, expected), number)| {
+            let actual = run_check(number, expression);
+            (actual != expected).then_some(CheckFailure {
+                expression,
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
+
+/// The `/admin selftest` report
+pub fn format_report(failures: &[CheckFailure]) -> String {
+    let passed = CHECKS.len() - failures.len();
+    if failures.is_empty() {
+        return format!(
+            "✅ Self-test passed: {passed}/{} engine checks match their recorded output.",
+            CHECKS.len()
+        );
+    }
+
+    let mut output = format!(
+        "❌ Self-test failed: {passed}/{} engine checks passed.",
+        CHECKS.len()
+    );
+    for failure in failures.iter().take(MAX_LISTED_FAILURES) {
+        output.push_str(&format!(
+            "\n\n`{}`\nExpected: {}\nActual: {}",
+            failure.expression, failure.expected, failure.actual
+        ));
+    }
+    if failures.len() > MAX_LISTED_FAILURES {
+        output.push_str(&format!(
+            "\n\n…and {} more.",
+            failures.len() - MAX_LISTED_FAILURES
+        ));
+    }
+    output
+}
@@ -0,0 +1,272 @@
+//! CSV exports of roll statistics, attached by `/mystats export` and
+//! `/admin stats export`.
+//!
+//! | File                   | Columns                                                  | Source                     |
+//! |------------------------|----------------------------------------------------------|----------------------------|
+//! | `<name>-daily.csv`     | `day,rolls,average_total,lowest_total,highest_total`     | `roll_history`             |
+//! | `mystats-systems.csv`  | `system,rolls,average_total`                             | `roll_history` expressions |
+//! | `botstats-systems.csv` | `system,uses,first_used,last_used`                       | `alias_usage`              |
+//!
+//! `/mystats` covers the user's own rolls in every server; `/admin` covers
+//! every recorded roll plus the anonymous alias counters.  Days are UTC and
+//! history only goes back 30 days.  A user's systems are read from the
+//! expressions of rolls recorded in full, so rolls recorded as totals only
+//! count as `unrecorded`.
+//!
+//! Rows are written into the file as the database returns them, and a file
+//! stops growing at [`MAX_EXPORT_BYTES`], ending the query early; the export
+//! notes which files were cut short.
+
+use crate::database::{AliasUsage, DailyRollStats, Database};
+use crate::dice::parser;
+use anyhow::Result;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// Largest CSV file attached, well under Discord's upload limit
+pub const MAX_EXPORT_BYTES: usize = 1024 * 1024;
+
+/// System of user rolls whose expression wasn't recorded
+pub const UNRECORDED_SYSTEM: &str = "unrecorded";
+
+/// A CSV file built row by row, refusing rows past its size limit
+#[derive(Debug, Clone)]
+pub struct CsvWriter {
+    name: String,
+    contents: String,
+    limit: usize,
+    rows: usize,
+    truncated: bool,
+}
+
+impl CsvWriter {
+    /// A file holding only its header line
+    pub fn new(name: &str, header: &[&str], limit: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            contents: csv_line(header),
+            limit,
+            rows: 0,
+            truncated: false,
+        }
+    }
+
+    /// Append a row, or mark the file truncated and return false when it
+    /// would pass the limit
+    pub fn write_row<S: AsRef<str>>(&mut self, fields: &[S]) -> bool {
+        let line = csv_line(fields);
+        if self.truncated || self.contents.len() + line.len() > self.limit {
+            self.truncated = true;
+            return false;
+        }
+        self.contents.push_str(&line);
+        self.rows += 1;
+        true
+    }
+
+    pub fn finish(self) -> CsvFile {
+        CsvFile {
+            name: self.name,
+            contents: self.contents,
+            rows: self.rows,
+            truncated: self.truncated,
+        }
+    }
+}
+
+/// A finished CSV attachment
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvFile {
+    pub name: String,
+    pub contents: String,
+    /// Rows written, not counting the header
+    pub rows: usize,
+    /// Rows were left out to keep the file under its limit
+    pub truncated: bool,
+}
+
+/// The files of an export and the totals its message shows
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsExport {
+    pub files: Vec<CsvFile>,
+    pub rolls: i64,
+    pub days: usize,
+    /// Average roll total across every exported day, if any rolls
+    pub average_total: Option<f64>,
+}
+
+/// Rolls and summed totals per system, accumulated one roll at a time
+#[derive(Debug, Clone, Default)]
+pub struct SystemTally {
+    systems: BTreeMap<String, (i64, i64)>,
+}
+
+impl SystemTally {
+    /// Count a roll under each system its expression uses, or under
+    /// [`UNRECORDED_SYSTEM`] without one
+    pub fn add(&mut self, expression: Option<&str>, total: i64) {
+        let keys = match expression {
+            Some(expression) => parser::usage_keys(expression),
+            None => vec![UNRECORDED_SYSTEM.to_string()],
+        };
+        for key in keys {
+            let (rolls, sum) = self.systems.entry(key).or_default();
+            *rolls += 1;
+            *sum += total;
+        }
+    }
+
+    /// Systems with their rolls and average total, most rolled first
+    pub fn rows(&self) -> Vec<(&str, i64, f64)> {
+        let mut rows: Vec<(&str, i64, f64)> = self
+            .systems
+            .iter()
+            .map(|(system, &(rolls, sum))| (system.as_str(), rolls, sum as f64 / rolls as f64))
+            .collect();
+        rows.sort_by_key(|&(_, rolls, _)| std::cmp::Reverse(rolls));
+        rows
+    }
+}
+
+/// Export a user's own roll statistics
+pub async fn export_user_stats(db: &Database, user_id: i64) -> Result<StatsExport> {
+    let mut summary = DailySummary::new("mystats-daily.csv", MAX_EXPORT_BYTES);
+    db.stream_daily_roll_stats(Some(user_id), |day| summary.add(&day))
+        .await?;
+
+    let mut tally = SystemTally::default();
+    db.stream_user_rolls(user_id, |expression, total| {
+        tally.add(expression.as_deref(), total);
+        true
+    })
+    .await?;
+    let mut systems = CsvWriter::new(
+        "mystats-systems.csv",
+        &["system", "rolls", "average_total"],
+        MAX_EXPORT_BYTES,
+    );
+    for (system, rolls, average) in tally.rows() {
+        if !systems.write_row(&[
+            system.to_string(),
+            rolls.to_string(),
+            format_average(average),
+        ]) {
+            break;
+        }
+    }
+
+    Ok(summary.finish(vec![systems.finish()]))
+}
+
+/// Export the bot's roll statistics across every user
+pub async fn export_bot_stats(db: &Database) -> Result<StatsExport> {
+    let mut summary = DailySummary::new("botstats-daily.csv", MAX_EXPORT_BYTES);
+    db.stream_daily_roll_stats(None, |day| summary.add(&day))
+        .await?;
+
+    let systems = alias_usage_csv(&db.get_alias_usage().await?, MAX_EXPORT_BYTES);
+    Ok(summary.finish(vec![systems]))
+}
+
+/// The `botstats-systems.csv` file of the alias usage counters
+pub fn alias_usage_csv(usage: &[AliasUsage], limit: usize) -> CsvFile {
+    let mut systems = CsvWriter::new(
+        "botstats-systems.csv",
+        &["system", "uses", "first_used", "last_used"],
+        limit,
+    );
+    for alias in usage {
+        let row = [
+            alias.alias.clone(),
+            alias.uses.to_string(),
+            alias.first_used.clone(),
+            alias.last_used.clone(),
+        ];
+        if !systems.write_row(&row) {
+            break;
+        }
+    }
+    systems.finish()
+}
+
+/// The daily file, written as days are read, and the totals across them
+#[derive(Debug, Clone)]
+pub struct DailySummary {
+    writer: CsvWriter,
+    rolls: i64,
+    summed_totals: f64,
+    days: usize,
+}
+
+impl DailySummary {
+    pub fn new(name: &str, limit: usize) -> Self {
+        Self {
+            writer: CsvWriter::new(
+                name,
+                &[
+                    "day",
+                    "rolls",
+                    "average_total",
+                    "lowest_total",
+                    "highest_total",
+                ],
+                limit,
+            ),
+            rolls: 0,
+            summed_totals: 0.0,
+            days: 0,
+        }
+    }
+
+    /// Write a day, returning false once the file is full.  Days left out
+    /// still count towards the totals read so far.
+    pub fn add(&mut self, day: &DailyRollStats) -> bool {
+        self.rolls += day.rolls;
+        self.summed_totals += day.average_total * day.rolls as f64;
+        self.days += 1;
+        self.writer.write_row(&[
+            day.day.clone(),
+            day.rolls.to_string(),
+            format_average(day.average_total),
+            day.lowest_total.to_string(),
+            day.highest_total.to_string(),
+        ])
+    }
+
+    /// The export: the daily file first, then `others`
+    pub fn finish(self, others: Vec<CsvFile>) -> StatsExport {
+        let average_total = (self.rolls > 0).then(|| self.summed_totals / self.rolls as f64);
+        let mut files = vec![self.writer.finish()];
+        files.extend(others);
+        StatsExport {
+            files,
+            rolls: self.rolls,
+            days: self.days,
+            average_total,
+        }
+    }
+}
+
+/// `12.35` for an average
+pub fn format_average(average: f64) -> String {
+    format!("{average:.2}")
+}
+
+// One CSV line with its fields quoted where needed (RFC 4180)
+fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|field| escape_field(field.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
+
+fn escape_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
@@ -0,0 +1,12 @@
+//! Lock helpers shared by the bot's in-memory state.
+
+use std::sync::{Mutex, MutexGuard};
+
+/// Lock `mutex`, recovering from poisoning.  A poisoned lock only means
+/// another task panicked mid-update; the state inside is still usable, and
+/// none of it is held across an await.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
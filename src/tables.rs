@@ -0,0 +1,222 @@
+//! Random tables: weighted lists of results rolled with `/table roll`.
+//!
+//! A table is written as entries separated by `;` or new lines, each with the
+//! die results it covers:
+//!
+//! ```text
+//! 1-50: Glancing blow; 51-90: Bleeding, [1d4] damage a turn; 91-100: Severed limb
+//! ```
+//!
+//! | Entry form     | Covers                                           |
+//! |----------------|--------------------------------------------------|
+//! | `51-90: text`  | Results 51 to 90                                 |
+//! | `7: text`      | Result 7                                         |
+//! | `text`         | The result after the previous entry              |
+//!
+//! Entries must cover `1..=N` without gaps or overlaps, and the table is
+//! rolled with a dN — so wider ranges are more likely.  `[dice]` inside an
+//! entry is rolled with [`crate::dice::parse_and_roll_with_rng`] each time the
+//! entry comes up.  Tables belong to a server and are stored in their
+//! normalized form (see [`RandomTable::definition`]) in the `guild_tables`
+//! table.
+
+use crate::dice::{self, parser};
+use anyhow::{Result, anyhow};
+use once_cell::sync::Lazy;
+use rand::RngExt;
+use regex::Regex;
+
+/// Most entries in one table
+pub const MAX_TABLE_ENTRIES: usize = 100;
+
+/// Largest die a table may be rolled with
+pub const MAX_TABLE_DIE: u32 = 1000;
+
+/// Most characters of entry text in one table
+pub const MAX_TABLE_TEXT_LENGTH: usize = 2000;
+
+/// Most tables a server may keep
+pub const MAX_GUILD_TABLES: usize = 50;
+
+// `51-90: text` or `7: text`
+static TABLE_ENTRY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d+)(?:\s*-\s*(\d+))?\s*:\s*(.*)$").expect("Failed to compile TABLE_ENTRY_REGEX")
+});
+
+// Inline dice such as `[1d4]` or `[2d6 + 1]`
+static INLINE_DICE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[([^\[\]]+)\]").expect("Failed to compile INLINE_DICE_REGEX"));
+
+/// A server's random table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RandomTable {
+    pub name: String,
+    pub entries: Vec<TableEntry>,
+}
+
+/// One result of a table and the die results that select it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableEntry {
+    pub low: u32,
+    pub high: u32,
+    pub text: String,
+}
+
+/// The outcome of rolling on a table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRoll {
+    /// Die result that picked the entry
+    pub roll: u32,
+    /// Entry text with inline dice replaced by their totals
+    pub text: String,
+}
+
+impl RandomTable {
+    /// Parse a table definition, validating ranges and inline dice
+    pub fn parse(name: &str, definition: &str) -> Result<Self> {
+        let mut entries: Vec<TableEntry> = Vec::new();
+        for part in definition.split([';', '\n']).map(str::trim) {
+            if part.is_empty() {
+                continue;
+            }
+            let next = entries.last().map_or(1, |entry| entry.high + 1);
+            let entry = parse_entry(part, next)?;
+            if entry.low != next {
+                return Err(anyhow!(
+                    "Entry `{}` should start at {} so the table has no gaps or overlaps",
+                    part,
+                    next
+                ));
+            }
+            if entry.high > MAX_TABLE_DIE {
+                return Err(anyhow!(
+                    "Tables can be rolled with at most a d{MAX_TABLE_DIE} (`{}` needs a d{})",
+                    part,
+                    entry.high
+                ));
+            }
+            entries.push(entry);
+            if entries.len() > MAX_TABLE_ENTRIES {
+                return Err(anyhow!(
+                    "Tables can have at most {MAX_TABLE_ENTRIES} entries"
+                ));
+            }
+        }
+
+        // Counted on the entry text so the normalized form re-parses
+        if entries.iter().map(|entry| entry.text.len()).sum::<usize>() > MAX_TABLE_TEXT_LENGTH {
+            return Err(anyhow!(
+                "Table is too long (max {MAX_TABLE_TEXT_LENGTH} characters of entries)"
+            ));
+        }
+        if entries.is_empty() {
+            return Err(anyhow!("Table has no entries"));
+        }
+
+        Ok(Self {
+            name: name.trim().to_lowercase(),
+            entries,
+        })
+    }
+
+    /// Size of the die the table is rolled with
+    pub fn die(&self) -> u32 {
+        self.entries.last().map_or(0, |entry| entry.high)
+    }
+
+    /// The entry a die result selects
+    pub fn entry_for(&self, roll: u32) -> Option<&TableEntry> {
+        self.entries
+            .iter()
+            .find(|entry| (entry.low..=entry.high).contains(&roll))
+    }
+
+    /// Normalized definition, e.g. `1-50: Glancing blow; 51-100: Bleeding`
+    pub fn definition(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| {
+                if entry.low == entry.high {
+                    format!("{}: {}", entry.low, entry.text)
+                } else {
+                    format!("{}-{}: {}", entry.low, entry.high, entry.text)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Roll on the table, resolving the chosen entry's inline dice
+    pub fn roll(&self, rng: &mut impl rand::Rng) -> Result<TableRoll> {
+        let die = self.die();
+        if die == 0 {
+            return Err(anyhow!("Table has no entries"));
+        }
+        let roll = rng.random_range(1..=die);
+        let entry = self
+            .entry_for(roll)
+            .ok_or_else(|| anyhow!("No entry for {} on table {}", roll, self.name))?;
+
+        let mut text = String::with_capacity(entry.text.len());
+        let mut last = 0;
+        for captures in INLINE_DICE_REGEX.captures_iter(&entry.text) {
+            let (Some(whole), Some(expression)) = (captures.get(0), captures.get(1)) else {
+                continue;
+            };
+            let totals = dice::parse_and_roll_with_rng(expression.as_str(), rng)?
+                .iter()
+                .map(|result| result.total.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            text.push_str(&entry.text[last..whole.start()]);
+            text.push_str(&format!("**{totals}**"));
+            last = whole.end();
+        }
+        text.push_str(&entry.text[last..]);
+
+        Ok(TableRoll { roll, text })
+    }
+}
+
+// Parse one entry; entries without a range cover the single result `next`
+fn parse_entry(part: &str, next: u32) -> Result<TableEntry> {
+    let (low, high, text) = match TABLE_ENTRY_REGEX.captures(part) {
+        Some(captures) => {
+            let number = |index: usize| {
+                captures
+                    .get(index)
+                    .map(|m| {
+                        m.as_str()
+                            .parse::<u32>()
+                            .map_err(|_| anyhow!("Range in `{}` is too large", part))
+                    })
+                    .transpose()
+            };
+            let low = number(1)?.unwrap_or(next);
+            let high = number(2)?.unwrap_or(low);
+            let text = captures.get(3).map_or("", |m| m.as_str().trim());
+            (low, high, text)
+        }
+        None => (next, next, part),
+    };
+
+    if high < low {
+        return Err(anyhow!("Range in `{}` runs backwards", part));
+    }
+    if text.is_empty() {
+        return Err(anyhow!("Entry `{}` has no text", part));
+    }
+    for captures in INLINE_DICE_REGEX.captures_iter(text) {
+        if let Some(expression) = captures.get(1)
+            && let Err(e) = parser::parse_dice_string(expression.as_str())
+        {
+            return Err(anyhow!("Invalid dice `[{}]`: {}", expression.as_str(), e));
+        }
+    }
+
+    Ok(TableEntry {
+        low,
+        high,
+        text: text.to_string(),
+    })
+}
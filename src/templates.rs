@@ -0,0 +1,244 @@
+//! Roll templates and their shareable export strings.
+//!
+//! A template is a named dice expression, optionally with `{placeholder}`
+//! slots (e.g. `1d20 + {mod} ! {weapon} attack`).  [`encode`] packs a template
+//! into a compact share code that can be pasted into any server and turned
+//! back into the same template with [`decode`].  The code *is* the template,
+//! so sharing needs no central storage and nothing is looked up on import.
+//!
+//! # Share code format
+//!
+//! ```text
+//! dm1:<base64url(name \0 expression)>
+//! ```
+//!
+//! The `dm1:` prefix versions the format; codes are URL-safe base64 without
+//! padding so they survive being pasted into chat.  Decoding validates the
+//! name and length limits, since codes come from untrusted users.
+//!
+//! # Macro packs
+//!
+//! [`MACRO_PACKS`] are curated sets of templates for popular systems.  Server
+//! administrators enable them with `/macropack enable <pack>`, which registers
+//! every macro in the pack as a guild alias (e.g. `/roll stealth + 5`).
+//!
+//! | Pack    | Macros                                                        |
+//! |---------|---------------------------------------------------------------|
+//! | `dnd5e` | Checks, saves, initiative, death saves, stats and the 18 skills |
+//! | `cod`   | Chance die and initiative                                     |
+//! | `swade` | Unskilled rolls and maneuver / situational modifiers          |
+//!
+//! SWADE modifiers expand to a signed number, so they chain after a trait roll:
+//! `/roll sw8 wildattack dim` rolls `sw8 + 2 - 2`.
+
+use crate::dice::parser::{self, MAX_INPUT_LENGTH};
+use anyhow::{Result, anyhow};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Prefix identifying (and versioning) a share code
+pub const SHARE_CODE_PREFIX: &str = "dm1:";
+
+/// Maximum length of a template name
+pub const MAX_TEMPLATE_NAME_LENGTH: usize = 32;
+
+// Words `/roll` reads before expanding macros and aliases
+const RESERVED_COMMAND_WORDS: [&str; 4] = ["help", "donate", "last", "split"];
+
+/// Whether a macro or alias can't be named `name` because `/roll` already
+/// reads it: a command word (`help`, `last`) or roll syntax
+/// ([`parser::is_roll_word`])
+pub fn is_reserved_roll_word(name: &str) -> bool {
+    RESERVED_COMMAND_WORDS.contains(&name) || parser::is_roll_word(name)
+}
+
+static TEMPLATE_NAME_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[a-z][a-z0-9_-]*$").expect("Failed to compile TEMPLATE_NAME_REGEX"));
+
+static PLACEHOLDER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\{([a-z_][a-z0-9_]*)\}").expect("Failed to compile PLACEHOLDER_REGEX")
+});
+
+/// A named roll expression that can be shared between servers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollTemplate {
+    pub name: String,
+    pub expression: String,
+}
+
+impl RollTemplate {
+    /// Build a template, normalizing the name to lowercase and validating both parts
+    pub fn new(name: &str, expression: &str) -> Result<Self> {
+        let template = Self {
+            name: name.trim().to_lowercase(),
+            expression: expression.trim().to_string(),
+        };
+        template.validate()?;
+        Ok(template)
+    }
+
+    /// Distinct `{placeholder}` names in the expression, in order of first use
+    pub fn placeholders(&self) -> Vec<&str> {
+        placeholders(&self.expression)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.name.len() > MAX_TEMPLATE_NAME_LENGTH || !TEMPLATE_NAME_REGEX.is_match(&self.name) {
+            return Err(anyhow!(
+                "Invalid template name `{}`: use up to {} lowercase letters, digits, `_` or `-`, starting with a letter",
+                self.name,
+                MAX_TEMPLATE_NAME_LENGTH
+            ));
+        }
+        if self.expression.is_empty() {
+            return Err(anyhow!("Template expression cannot be empty"));
+        }
+        if self.expression.len() > MAX_INPUT_LENGTH {
+            return Err(anyhow!(
+                "Template expression too long (max {} characters)",
+                MAX_INPUT_LENGTH
+            ));
+        }
+        if self.expression.chars().any(char::is_control) {
+            return Err(anyhow!("Template expression contains control characters"));
+        }
+        Ok(())
+    }
+}
+
+/// Distinct `{placeholder}` names in an expression, in order of first use
+pub fn placeholders(expression: &str) -> Vec<&str> {
+    let mut names: Vec<&str> = Vec::new();
+    for captures in PLACEHOLDER_REGEX.captures_iter(expression) {
+        if let Some(name) = captures.get(1).map(|m| m.as_str())
+            && !names.contains(&name)
+        {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Replace every `{placeholder}` with its value; unknown names are left as-is
+pub fn fill_placeholders(expression: &str, values: &[(&str, &str)]) -> String {
+    PLACEHOLDER_REGEX
+        .replace_all(expression, |captures: &regex::Captures| {
+            let name = &captures[1];
+            values
+                .iter()
+                .find(|(placeholder, _)| *placeholder == name)
+                .map_or_else(|| captures[0].to_string(), |(_, value)| value.to_string())
+        })
+        .into_owned()
+}
+
+/// Encode a template as a share code
+pub fn encode(template: &RollTemplate) -> String {
+    let payload = format!("{}\0{}", template.name, template.expression);
+    format!("{}{}", SHARE_CODE_PREFIX, URL_SAFE_NO_PAD.encode(payload))
+}
+
+/// Decode and validate a share code produced by [`encode`]
+pub fn decode(code: &str) -> Result<RollTemplate> {
+    let code = code.trim();
+    let Some(encoded) = code.strip_prefix(SHARE_CODE_PREFIX) else {
+        return Err(anyhow!(
+            "Not a Dice Maiden share code (codes start with `{}`)",
+            SHARE_CODE_PREFIX
+        ));
+    };
+
+    // Longest possible payload: name, separator and expression
+    let max_encoded = (MAX_TEMPLATE_NAME_LENGTH + 1 + MAX_INPUT_LENGTH).div_ceil(3) * 4;
+    if encoded.len() > max_encoded {
+        return Err(anyhow!("Share code is too long"));
+    }
+
+    let bytes = URL_SAFE_NO_PAD
+        .decode(encoded)
+        .map_err(|_| anyhow!("Share code is damaged or incomplete"))?;
+    let payload =
+        String::from_utf8(bytes).map_err(|_| anyhow!("Share code is damaged or incomplete"))?;
+    let Some((name, expression)) = payload.split_once('\0') else {
+        return Err(anyhow!("Share code is damaged or incomplete"));
+    };
+
+    RollTemplate::new(name, expression)
+}
+
+/// A curated set of macros that can be enabled for a server
+#[derive(Debug)]
+pub struct MacroPack {
+    /// Key used with `/macropack enable`
+    pub key: &'static str,
+    pub name: &'static str,
+    /// `(name, expression)` pairs registered as guild aliases
+    pub macros: &'static [(&'static str, &'static str)],
+}
+
+/// Built-in macro packs.  Macro names must not clash with built-in aliases,
+/// dice syntax or roll flags.
+pub const MACRO_PACKS: [MacroPack; 3] = [
+    MacroPack {
+        key: "dnd5e",
+        name: "D&D 5e basic checks",
+        macros: &[
+            ("check", "1d20"),
+            ("savingthrow", "1d20"),
+            ("initiative", "1d20"),
+            ("deathsave", "1d20 t10"),
+            ("adv", "+d20"),
+            ("dis", "-d20"),
+            ("stats", "6 4d6 k3"),
+            ("acrobatics", "1d20"),
+            ("animal_handling", "1d20"),
+            ("arcana", "1d20"),
+            ("athletics", "1d20"),
+            ("deception", "1d20"),
+            ("history", "1d20"),
+            ("insight", "1d20"),
+            ("intimidation", "1d20"),
+            ("investigation", "1d20"),
+            ("medicine", "1d20"),
+            ("nature", "1d20"),
+            ("perception", "1d20"),
+            ("performance", "1d20"),
+            ("persuasion", "1d20"),
+            ("religion", "1d20"),
+            ("sleight_of_hand", "1d20"),
+            ("stealth", "1d20"),
+            ("survival", "1d20"),
+        ],
+    },
+    MacroPack {
+        key: "cod",
+        name: "Chronicles of Darkness",
+        macros: &[("chance", "1d10 t10 f1"), ("initiative", "1d10")],
+    },
+    MacroPack {
+        key: "swade",
+        name: "Savage Worlds maneuvers",
+        macros: &[
+            ("unskilled", "sw4 - 2"),
+            ("wildattack", "+ 2"),
+            ("aim", "+ 2"),
+            ("calledlimb", "- 2"),
+            ("calledhead", "- 4"),
+            ("multi2", "- 2"),
+            ("multi3", "- 4"),
+            ("unstable", "- 2"),
+            ("dim", "- 2"),
+            ("dark", "- 4"),
+            ("pitchdark", "- 6"),
+            ("lightcover", "- 2"),
+            ("mediumcover", "- 4"),
+            ("heavycover", "- 6"),
+        ],
+    },
+];
+
+/// Look up a built-in macro pack by key
+pub fn find_macro_pack(key: &str) -> Option<&'static MacroPack> {
+    MACRO_PACKS.iter().find(|pack| pack.key == key)
+}
@@ -0,0 +1,333 @@
+//! Test fixtures for the dice engine.
+//!
+//! Only compiled for tests: the crate's own unit tests, and the integration
+//! suites through the `testkit` feature (enabled by the self dev-dependency
+//! in `Cargo.toml`).  It is not part of the bot's public API.
+//!
+//! | Helper                     | Use                                                     |
+//! |----------------------------|---------------------------------------------------------|
+//! | [`DiceRollBuilder`]        | A parsed expression without going through the parser    |
+//! | [`RollResultBuilder`]      | A result with fixed dice, to test formatting directly   |
+//! | [`roll`]                   | `parse_and_roll` that panics with the input on failure  |
+//! | [`assert_valid`] / [`assert_invalid`] | Whether an expression parses and rolls       |
+//! | [`assert_formatted`] / [`assert_not_formatted`] | What a result's message contains   |
+//! | [`assert_output`]          | Roll an expression and check its whole message          |
+//!
+//! Random rolls can only be checked for shape, so a new system's formatting
+//! is easiest to test by building the `RollResult` it would produce:
+//!
+//! ```ignore
+//! let result = RollResultBuilder::new()
+//!     .rolls(&[6, 4])
+//!     .dropped(&[1])
+//!     .build();
+//! assert_formatted(&result, &["`[6, 4, 1]`", "~~[1]~~", "**10**"]);
+//! ```
+
+use crate::dice::{
+    DiceGroup, DiceRoll, Modifier, RollResult, format_multiple_results_with_limit, parse_and_roll,
+};
+use crate::i18n::Language;
+
+/// Builds a [`DiceRoll`] as the parser would produce it
+#[derive(Debug, Clone)]
+pub struct DiceRollBuilder {
+    roll: DiceRoll,
+}
+
+impl DiceRollBuilder {
+    /// `count`d`sides` with no modifiers or flags
+    pub fn new(count: u32, sides: u32) -> Self {
+        Self {
+            roll: DiceRoll {
+                count,
+                sides,
+                modifiers: Vec::new(),
+                comment: None,
+                label: None,
+                private: false,
+                simple: false,
+                no_results: false,
+                unsorted: false,
+                drama: false,
+                pemdas: false,
+                embed: false,
+                full: false,
+                gm: false,
+                language: Language::English,
+                faces: None,
+                pool: None,
+                original_expression: None,
+            },
+        }
+    }
+
+    pub fn modifier(mut self, modifier: Modifier) -> Self {
+        self.roll.modifiers.push(modifier);
+        self
+    }
+
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.roll.comment = Some(comment.to_string());
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.roll.label = Some(label.to_string());
+        self
+    }
+
+    pub fn expression(mut self, expression: &str) -> Self {
+        self.roll.original_expression = Some(expression.to_string());
+        self
+    }
+
+    pub fn private(mut self) -> Self {
+        self.roll.private = true;
+        self
+    }
+
+    pub fn simple(mut self) -> Self {
+        self.roll.simple = true;
+        self
+    }
+
+    pub fn no_results(mut self) -> Self {
+        self.roll.no_results = true;
+        self
+    }
+
+    pub fn unsorted(mut self) -> Self {
+        self.roll.unsorted = true;
+        self
+    }
+
+    pub fn build(self) -> DiceRoll {
+        self.roll
+    }
+}
+
+/// Builds a [`RollResult`] with fixed dice.  Every optional field starts
+/// unset, so a result only shows what the test gives it.
+#[derive(Debug, Clone)]
+pub struct RollResultBuilder {
+    result: RollResult,
+}
+
+impl Default for RollResultBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RollResultBuilder {
+    pub fn new() -> Self {
+        Self {
+            result: RollResult {
+                individual_rolls: Vec::new(),
+                kept_rolls: Vec::new(),
+                dropped_rolls: Vec::new(),
+                total: 0,
+                successes: None,
+                failures: None,
+                botches: None,
+                comment: None,
+                label: None,
+                notes: Vec::new(),
+                dice_groups: Vec::new(),
+                original_expression: None,
+                simple: false,
+                no_results: false,
+                private: false,
+                drama: false,
+                embed: false,
+                full: false,
+                gm: false,
+                language: Language::English,
+                godbound_damage: None,
+                fudge_symbols: None,
+                wng_wrath_die: None,
+                wng_icons: None,
+                wng_exalted_icons: None,
+                wng_wrath_dice: None,
+                suppress_comment: false,
+                alien_stress_level: None,
+                alien_panic_roll: None,
+                alien_stress_ones: None,
+                fitd_outcome: None,
+                fitd_result: None,
+                fitd_highest_die: None,
+                plot_symbols: None,
+                narrative: None,
+                l5r: None,
+                wfrp: None,
+                year_zero: None,
+                burning_wheel: None,
+                matches: None,
+                cortex: None,
+                face_labels: None,
+                ironsworn_outcome: None,
+                ironsworn_challenge: None,
+                preserve_order: false,
+            },
+        }
+    }
+
+    /// The kept dice; the total becomes their sum
+    pub fn rolls(mut self, rolls: &[i32]) -> Self {
+        self.result.individual_rolls = rolls.to_vec();
+        self.result.kept_rolls = rolls.to_vec();
+        self.result.total = rolls.iter().sum();
+        self
+    }
+
+    /// Dice removed by keep/drop, shown struck through
+    pub fn dropped(mut self, dropped: &[i32]) -> Self {
+        self.result.dropped_rolls = dropped.to_vec();
+        self
+    }
+
+    /// One group of the dice breakdown; `modifier_type` is `"base"` for the
+    /// first group, then `"add"`, `"subtract"`, …
+    pub fn group(mut self, modifier_type: &str, rolls: &[i32], dropped: &[i32]) -> Self {
+        self.result.dice_groups.push(DiceGroup {
+            _description: String::new(),
+            rolls: rolls.to_vec(),
+            dropped_rolls: dropped.to_vec(),
+            modifier_type: modifier_type.to_string(),
+        });
+        self
+    }
+
+    pub fn total(mut self, total: i32) -> Self {
+        self.result.total = total;
+        self
+    }
+
+    pub fn successes(mut self, successes: i32) -> Self {
+        self.result.successes = Some(successes);
+        self
+    }
+
+    pub fn failures(mut self, failures: i32) -> Self {
+        self.result.failures = Some(failures);
+        self
+    }
+
+    pub fn botches(mut self, botches: i32) -> Self {
+        self.result.botches = Some(botches);
+        self
+    }
+
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.result.comment = Some(comment.to_string());
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.result.label = Some(label.to_string());
+        self
+    }
+
+    pub fn note(mut self, note: &str) -> Self {
+        self.result.notes.push(note.to_string());
+        self
+    }
+
+    pub fn expression(mut self, expression: &str) -> Self {
+        self.result.original_expression = Some(expression.to_string());
+        self
+    }
+
+    pub fn simple(mut self) -> Self {
+        self.result.simple = true;
+        self
+    }
+
+    pub fn no_results(mut self) -> Self {
+        self.result.no_results = true;
+        self
+    }
+
+    pub fn private(mut self) -> Self {
+        self.result.private = true;
+        self
+    }
+
+    pub fn preserve_order(mut self) -> Self {
+        self.result.preserve_order = true;
+        self
+    }
+
+    pub fn language(mut self, language: Language) -> Self {
+        self.result.language = language;
+        self
+    }
+
+    /// Finish with direct access to the system-specific fields
+    /// (`wng_*`, `alien_*`, `fitd_*`, …) that have no builder method
+    pub fn with(mut self, set: impl FnOnce(&mut RollResult)) -> Self {
+        set(&mut self.result);
+        self
+    }
+
+    pub fn build(self) -> RollResult {
+        self.result
+    }
+}
+
+/// Roll `input`, panicking with the input and error if it fails
+pub fn roll(input: &str) -> Vec<RollResult> {
+    match parse_and_roll(input) {
+        Ok(results) => results,
+        Err(e) => panic!("Failed to parse: '{input}' - Error: {e}"),
+    }
+}
+
+/// Assert that `input` parses and produces at least one result
+pub fn assert_valid(input: &str) {
+    assert!(!roll(input).is_empty(), "No results for: '{input}'");
+}
+
+/// Assert that `input` is rejected
+pub fn assert_invalid(input: &str) {
+    assert!(
+        parse_and_roll(input).is_err(),
+        "Expected error for: '{input}'"
+    );
+}
+
+/// Assert that a result's message contains every one of `expected`
+pub fn assert_formatted(result: &RollResult, expected: &[&str]) {
+    let formatted = result.to_string();
+    for needle in expected {
+        assert!(
+            formatted.contains(needle),
+            "Expected {needle:?} in: {formatted}"
+        );
+    }
+}
+
+/// Assert that a result's message contains none of `unexpected`
+pub fn assert_not_formatted(result: &RollResult, unexpected: &[&str]) {
+    let formatted = result.to_string();
+    for needle in unexpected {
+        assert!(
+            !formatted.contains(needle),
+            "Did not expect {needle:?} in: {formatted}"
+        );
+    }
+}
+
+/// Roll `input` and assert its full Discord message contains every one of
+/// `expected`.  Returns the message for further checks.
+pub fn assert_output(input: &str, expected: &[&str]) -> String {
+    let formatted = format_multiple_results_with_limit(&roll(input));
+    for needle in expected {
+        assert!(
+            formatted.contains(needle),
+            "Expected {needle:?} in output of '{input}': {formatted}"
+        );
+    }
+    formatted
+}
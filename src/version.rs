@@ -0,0 +1,50 @@
+//! Dice engine version, so a reported roll can be matched to the parser
+//! behaviour that produced it.
+//!
+//! The engine version is the crate version plus a grammar revision, written
+//! `1.5.2+g1`.  The crate version changes with every release; the grammar
+//! revision only when the engine reads some expression differently (a new
+//! alias or modifier, a changed default or precedence), so two builds with
+//! the same revision roll every expression the same way.
+//!
+//! | Where                                  | Shows                              |
+//! |----------------------------------------|------------------------------------|
+//! | `/help about`                          | The engine version, explained      |
+//! | API `GET /version`, `POST /roll`       | [`EngineVersion`], `engine` field  |
+//! | Rolls, with `/settings version` on     | A small `Dice engine 1.5.2+g1` footer |
+
+use serde::Serialize;
+use std::fmt;
+
+/// The bot's crate version
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Bumped whenever an expression parses or rolls differently than before;
+/// see "Adding a new game system" in `dice/mod.rs`
+pub const GRAMMAR_REVISION: u32 = 20;
+
+/// The running dice engine's version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EngineVersion {
+    pub crate_version: &'static str,
+    pub grammar_revision: u32,
+}
+
+impl fmt::Display for EngineVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}+g{}", self.crate_version, self.grammar_revision)
+    }
+}
+
+/// This build's engine version
+pub fn engine_version() -> EngineVersion {
+    EngineVersion {
+        crate_version: CRATE_VERSION,
+        grammar_revision: GRAMMAR_REVISION,
+    }
+}
+
+/// The line appended to rolls in servers that turned on `/settings version`
+pub fn footer() -> String {
+    format!("\n-# Dice engine {}", engine_version())
+}
@@ -0,0 +1,209 @@
+//! Background database writes.
+//!
+//! Writes that only record what happened — roll history, alias usage and
+//! `/stats` counters, and the data-erasure audit log — are handed to
+//! [`WriteQueue`] instead of being awaited by the command, so a locked or
+//! briefly unavailable database never delays or fails a roll.  Writes
+//! whose result the command shows (settings, tallies, macros, …) are still
+//! made directly.
+//!
+//! One background task applies the queued writes in order.  A write refused
+//! because the database is busy, locked or can't be reached is tried again
+//! after `outbound::backoff_delay`, up to [`MAX_WRITE_ATTEMPTS`] times,
+//! holding back the writes behind it; any other failure (such as a
+//! constraint violation) is logged and dropped at once.  The queue holds at
+//! most [`WRITE_QUEUE_CAPACITY`] writes, and new writes are dropped while it
+//! is full rather than waited for.
+//!
+//! Every outcome is counted in `Metrics::writes` and shown with `/usage
+//! report:writes`, so dropped history is visible instead of silent.
+
+use crate::database::{Database, RollHistoryEntry, RollStatsEntry};
+use crate::metrics::Metrics;
+use crate::outbound::backoff_delay;
+use crate::{DatabaseContainer, WriteQueueContainer};
+use serenity::prelude::Context;
+use sqlx::postgres::PgDatabaseError;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+
+/// Pending writes allowed in the queue before new ones are dropped
+pub const WRITE_QUEUE_CAPACITY: usize = 1024;
+
+/// Attempts at a single write, including the first
+pub const MAX_WRITE_ATTEMPTS: u32 = 4;
+
+// SQLite primary result codes worth waiting out: BUSY, LOCKED, IOERR, CANTOPEN
+const TRANSIENT_SQLITE_CODES: [i64; 4] = [5, 6, 10, 14];
+
+// PostgreSQL SQLSTATEs worth waiting out: lost or refused connections,
+// serialization failures, deadlocks, too many connections and a server
+// still starting up
+const TRANSIENT_POSTGRES_STATES: [&str; 7] = [
+    "08000", "08003", "08006", "40001", "40P01", "53300", "57P03",
+];
+
+/// A write that can wait
+#[derive(Debug, Clone, PartialEq)]
+pub enum DbWrite {
+    RollHistory(RollHistoryEntry),
+    /// Alias keys of one roll (`parser::usage_keys`)
+    AliasUsage(Vec<String>),
+    RollStats(RollStatsEntry),
+    DataErasure {
+        scope: &'static str,
+        target_id: i64,
+        requested_by: i64,
+        rows_deleted: u64,
+    },
+}
+
+impl DbWrite {
+    /// What the write records, for logs
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::RollHistory(_) => "roll history",
+            Self::AliasUsage(_) => "alias usage",
+            Self::RollStats(_) => "roll stats",
+            Self::DataErasure { .. } => "erasure log",
+        }
+    }
+
+    /// Make the write once
+    pub async fn apply(&self, db: &Database) -> anyhow::Result<()> {
+        match self {
+            Self::RollHistory(entry) => db.record_roll_history(entry).await,
+            Self::AliasUsage(keys) => db.record_alias_usage(keys).await,
+            Self::RollStats(entry) => db.record_roll_stats(entry).await,
+            Self::DataErasure {
+                scope,
+                target_id,
+                requested_by,
+                rows_deleted,
+            } => {
+                db.log_data_erasure(scope, *target_id, *requested_by, *rows_deleted)
+                    .await
+            }
+        }
+    }
+}
+
+/// Whether a failed write may succeed if tried again: the database was
+/// busy, locked or unreachable, a PostgreSQL transaction lost a conflict,
+/// or no connection was free in time
+pub fn is_retryable(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(e)) if e.try_downcast_ref::<PgDatabaseError>().is_some() => e
+            .code()
+            .is_some_and(|state| TRANSIENT_POSTGRES_STATES.contains(&state.as_ref())),
+        Some(sqlx::Error::Database(e)) => e
+            .code()
+            .and_then(|code| code.parse::<i64>().ok())
+            // Extended result codes keep the primary code in the low byte
+            .is_some_and(|code| TRANSIENT_SQLITE_CODES.contains(&(code & 0xff))),
+        Some(sqlx::Error::PoolTimedOut | sqlx::Error::Io(_)) => true,
+        _ => false,
+    }
+}
+
+/// Make a write, retrying transient failures with backoff.  Returns whether
+/// it was written; outcomes are counted in `metrics`.
+pub async fn apply_with_retry(db: &Database, write: &DbWrite, metrics: &Metrics) -> bool {
+    let mut attempt = 0;
+    loop {
+        match write.apply(db).await {
+            Ok(()) => {
+                metrics.writes.record_written();
+                return true;
+            }
+            Err(e) if attempt + 1 < MAX_WRITE_ATTEMPTS && is_retryable(&e) => {
+                let delay = backoff_delay(attempt);
+                warn!(
+                    "Writing {} failed ({}), retrying in {}ms",
+                    write.label(),
+                    e,
+                    delay.as_millis()
+                );
+                metrics.writes.record_retry();
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                error!("Dropped {} write: {}", write.label(), e);
+                metrics.writes.record_dropped_failed();
+                return false;
+            }
+        }
+    }
+}
+
+/// Bounded queue of database writes, applied in order by one background task
+#[derive(Clone)]
+pub struct WriteQueue {
+    sender: mpsc::Sender<DbWrite>,
+    metrics: Arc<Metrics>,
+}
+
+impl WriteQueue {
+    /// Create the queue and spawn its worker on the current Tokio runtime
+    pub fn start(db: Arc<Database>, metrics: Arc<Metrics>, capacity: usize) -> Self {
+        let (sender, mut receiver) = mpsc::channel::<DbWrite>(capacity);
+
+        let worker_metrics = Arc::clone(&metrics);
+        tokio::spawn(async move {
+            while let Some(write) = receiver.recv().await {
+                apply_with_retry(&db, &write, &worker_metrics).await;
+            }
+        });
+
+        Self { sender, metrics }
+    }
+
+    /// Queue a write.  Returns false (and logs and counts it) if the queue is
+    /// full or closed and the write was dropped.
+    pub fn enqueue(&self, write: DbWrite) -> bool {
+        match self.sender.try_send(write) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(write)) => {
+                warn!("Write queue full, dropping {} write", write.label());
+                self.metrics.writes.record_dropped_full();
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed(write)) => {
+                error!("Write queue closed, dropping {} write", write.label());
+                self.metrics.writes.record_dropped_failed();
+                false
+            }
+        }
+    }
+
+    /// Writes waiting to be applied
+    pub fn pending(&self) -> usize {
+        self.sender.max_capacity() - self.sender.capacity()
+    }
+}
+
+/// Queue a write on the bot's shared [`WriteQueue`], making it in a spawned
+/// task if no queue has been registered (e.g. in tools that skip `main`)
+pub async fn enqueue(ctx: &Context, write: DbWrite) -> bool {
+    let (queue, db) = {
+        let data = ctx.data.read().await;
+        (
+            data.get::<WriteQueueContainer>().cloned(),
+            data.get::<DatabaseContainer>().cloned(),
+        )
+    };
+    match (queue, db) {
+        (Some(queue), _) => queue.enqueue(write),
+        (None, Some(db)) => {
+            tokio::spawn(async move {
+                if let Err(e) = write.apply(&db).await {
+                    warn!("Failed to write {}: {}", write.label(), e);
+                }
+            });
+            true
+        }
+        (None, None) => false,
+    }
+}
@@ -6,21 +6,24 @@
 // - Cross-system compatibility
 // - Game system modifiers and edge cases
 
-use dicemaiden_rs::{dice::aliases, parse_and_roll};
-
-// ============================================================================
-// HELPER FUNCTIONS
-// ============================================================================
-
-fn assert_valid(input: &str) {
-    let result = parse_and_roll(input);
-    assert!(result.is_ok(), "Failed to parse: '{}'", input);
-}
-
-fn assert_invalid(input: &str) {
-    let result = parse_and_roll(input);
-    assert!(result.is_err(), "Expected error for: '{}'", input);
-}
+// Older tests predate these lints and are kept as written
+#![allow(
+    clippy::collapsible_if,
+    clippy::identity_op,
+    clippy::len_zero,
+    clippy::manual_contains,
+    clippy::manual_range_contains,
+    clippy::nonminimal_bool,
+    clippy::redundant_pattern_matching,
+    clippy::unnecessary_cast,
+    clippy::unnecessary_unwrap
+)]
+
+use dicemaiden_rs::{
+    dice::{aliases, parse_and_roll_with_rng, parser, rng, roller},
+    format_multiple_results, parse_and_roll,
+    testkit::{RollResultBuilder, assert_invalid, assert_valid, roll},
+};
 
 // ============================================================================
 // COMPREHENSIVE GAME SYSTEM TESTS
@@ -216,6 +219,14 @@ fn test_game_systems_comprehensive() {
             let results = result.unwrap();
             assert!(!results.is_empty(), "No results for: '{}'", system);
 
+            // Every die comes from the injected RNG, so a seed replays the roll
+            let seeded = || {
+                format_multiple_results(
+                    &parse_and_roll_with_rng(system, &mut rng::seeded_rng(7)).unwrap(),
+                )
+            };
+            assert_eq!(seeded(), seeded(), "'{}' ignored the seeded RNG", system);
+
             // Check expected features
             if let Some(feature) = expected_feature {
                 match feature {
@@ -380,6 +391,90 @@ fn test_alias_expansions() {
     }
 }
 
+#[test]
+fn test_alias_usage_keys() {
+    // Alias parameters are dropped so each system is counted under one key
+    let key_tests = vec![
+        ("4cod", "cod"),
+        ("6wod7c + 3", "wod"),
+        ("+d20", "+d"),
+        ("-d%", "-d%"),
+        ("fitd3", "fitd"),
+        ("snm5", "snm"),
+        ("bnw4", "bnw"),
+        ("dh 4d10", "dh"),
+        ("2.5hsn", "hsn"),
+        ("d6s4 + 2", "d6s"),
+        ("8d6l", "d6l"),
+        ("a5e +5 ex1", "a5e"),
+        ("ww6c1", "ww"),
+        ("l5r 5k3", "l5r"),
+        ("iron +2", "iron"),
+        ("oracle likely", "oracle"),
+        ("sta 3d20 tn15 f2", "sta"),
+        ("2d6 + 3", "dice"),
+        ("1d20", "dice"),
+    ];
+
+    for (input, expected) in key_tests {
+        assert_eq!(
+            aliases::alias_usage_key(input),
+            expected,
+            "Wrong usage key for '{}'",
+            input
+        );
+    }
+
+    // Flags, labels, comments and roll-set counts are ignored; each system is counted once
+    let expression_tests = vec![
+        ("p (Stealth) 4cod ! sneaking", vec!["cod"]),
+        ("6 4d6 k3", vec!["dice"]),
+        ("4cod; 1d20; 5cod", vec!["cod", "dice"]),
+        ("s snm5; +d20 + 5", vec!["snm", "+d"]),
+    ];
+
+    for (input, expected) in expression_tests {
+        assert_eq!(
+            parser::usage_keys(input),
+            expected,
+            "Wrong usage keys for '{}'",
+            input
+        );
+    }
+}
+
+#[test]
+fn test_default_system_expansion() {
+    // Every selectable default system must turn a bare number into a valid roll
+    for (system, _, _) in aliases::DEFAULT_SYSTEMS {
+        let expanded = aliases::expand_default_system(system, "3")
+            .unwrap_or_else(|| panic!("Default system '{}' did not expand", system));
+        assert_valid(&expanded);
+    }
+
+    let expansion_tests = vec![
+        ("cod", "5", Some("5cod")),
+        ("d20", "7", Some("1d20 + 7")),
+        ("fitd", " 2 ", Some("fitd2")),
+        // Anything but a bare number is left alone
+        ("cod", "2d6", None),
+        ("cod", "-1", None),
+        ("cod", "1000", None),
+        ("cod", "", None),
+        ("unknown", "5", None),
+    ];
+
+    for (system, input, expected) in expansion_tests {
+        assert_eq!(
+            aliases::expand_default_system(system, input).as_deref(),
+            expected,
+            "Wrong expansion of '{}' for system '{}'",
+            input,
+            system
+        );
+    }
+}
+
 // ============================================================================
 // SPECIFIC SYSTEM MECHANICS
 // ============================================================================
@@ -408,6 +503,88 @@ fn test_savage_worlds_mechanics() {
     }
 }
 
+#[test]
+fn test_result_order_preservation() {
+    // Systems whose dice order carries meaning are never re-sorted for display
+    let test_cases = vec![
+        // (expression, preserve_order, description)
+        ("4d6", false, "Plain dice are sorted"),
+        ("ul 4d6", true, "ul flag keeps roll order"),
+        ("wng 4d6", true, "Wrath die stays first"),
+        ("wng w2 5d6", true, "All wrath dice stay first"),
+        (
+            "wng 4d6 !soak",
+            true,
+            "Wrath die stays first in total rolls",
+        ),
+        ("sw8", true, "Trait die group before wild die group"),
+        (
+            "fitd4",
+            false,
+            "FitD key die is the highest, sorting is safe",
+        ),
+    ];
+
+    for (expression, preserve_order, description) in test_cases {
+        for _ in 0..20 {
+            let result = &roll(expression)[0];
+            assert_eq!(
+                result.preserve_order, preserve_order,
+                "{}: '{}'",
+                description, expression
+            );
+            if !preserve_order {
+                assert!(
+                    result.kept_rolls.windows(2).all(|pair| pair[0] >= pair[1]),
+                    "{}: '{}' should be sorted: {:?}",
+                    description,
+                    expression,
+                    result.kept_rolls
+                );
+            }
+        }
+    }
+
+    for _ in 0..20 {
+        let result = &roll("wng w2 5d6")[0];
+        assert_eq!(
+            result.wng_wrath_dice.as_deref(),
+            Some(&result.kept_rolls[..2]),
+            "Wrath dice should be the first kept dice: {:?}",
+            result.kept_rolls
+        );
+
+        let result = &roll("sw8")[0];
+        let groups: Vec<&str> = result
+            .dice_groups
+            .iter()
+            .map(|group| group.modifier_type.as_str())
+            .collect();
+        assert_eq!(groups, vec!["trait", "wild"]);
+
+        let result = &roll("fitd4")[0];
+        assert_eq!(result.fitd_highest_die, result.kept_rolls.first().copied());
+    }
+
+    // sort_result_rolls itself honours the attribute
+    let mut result = RollResultBuilder::new()
+        .rolls(&[1, 6, 3])
+        .group("base", &[1, 6, 3], &[])
+        .build();
+    roller::sort_result_rolls(&mut result);
+    assert_eq!(result.kept_rolls, vec![6, 3, 1]);
+    assert_eq!(result.dice_groups[0].rolls, vec![6, 3, 1]);
+
+    let mut result = RollResultBuilder::new()
+        .rolls(&[1, 6, 3])
+        .group("base", &[1, 6, 3], &[])
+        .preserve_order()
+        .build();
+    roller::sort_result_rolls(&mut result);
+    assert_eq!(result.kept_rolls, vec![1, 6, 3]);
+    assert_eq!(result.dice_groups[0].rolls, vec![1, 6, 3]);
+}
+
 #[test]
 fn test_cyberpunk_red_mechanics() {
     // Test CPR critical success/failure mechanics
@@ -925,6 +1102,114 @@ fn test_conan_system_variants() {
     }
 }
 
+#[test]
+fn test_2d20_checks() {
+    // Table-driven: (alias, expansion)
+    let alias_cases = vec![
+        ("sta 3d20 tn15 f2", "3d20 tn15 focus2"),
+        (
+            "sta 2d20 tn9 focus1 diff2 comp2",
+            "2d20 tn9 focus1 diff2 comp2",
+        ),
+        ("dune 2d20 tn12 diff2", "2d20 tn12 diff2"),
+        ("fallout 4d20 tn10 comp3", "4d20 tn10 comp3"),
+        ("conan3 tn12 f2", "3d20 conan3 tn12 focus2"),
+        ("conan tn10 diff1", "2d20 conan2 tn10 diff1"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // Dice at or under the focus (or a natural 1) score two successes, at or
+    // under the target number one; difficulty turns the rest into momentum
+    for _ in 0..100 {
+        let result = &roll("sta 3d20 tn14 f3 diff2 comp2")[0];
+        assert_eq!(result.individual_rolls.len(), 3);
+        let expected: i32 = result
+            .individual_rolls
+            .iter()
+            .map(|&die| match die {
+                1..=3 => 2,
+                4..=14 => 1,
+                _ => 0,
+            })
+            .sum();
+        assert_eq!(result.successes, Some(expected));
+        let note = if expected >= 2 {
+            format!("**SUCCESS** at difficulty 2, **{}** momentum", expected - 2)
+        } else {
+            "**FAILURE** at difficulty 2".to_string()
+        };
+        assert!(
+            result.notes.iter().any(|n| n.contains(&note)),
+            "{:?}",
+            result.notes
+        );
+        let complications = result
+            .individual_rolls
+            .iter()
+            .filter(|&&die| die >= 19)
+            .count();
+        assert_eq!(
+            complications > 0,
+            result.notes.iter().any(|n| n.contains("(rolled 19-20)")),
+            "{:?}",
+            result.notes
+        );
+    }
+
+    // Bonus successes; without a focus only natural 1s count twice
+    for _ in 0..50 {
+        let result = &roll("2d20 tn10 + 1")[0];
+        let expected: i32 = result
+            .individual_rolls
+            .iter()
+            .map(|&die| match die {
+                1 => 2,
+                2..=10 => 1,
+                _ => 0,
+            })
+            .sum::<i32>()
+            + 1;
+        assert_eq!(result.successes, Some(expected));
+    }
+
+    // Conan skill rolls without a target number keep their old reading, with
+    // no complication note on a natural 20
+    assert_eq!(roll("conan")[0].successes, Some(2));
+    for seed in 0..100 {
+        let result = &parse_and_roll_with_rng("conan", &mut rng::seeded_rng(seed)).unwrap()[0];
+        assert!(
+            !result.notes.iter().any(|n| n.contains("complication")),
+            "{:?}",
+            result.notes
+        );
+    }
+    assert_eq!(roll("conan4 tn12")[0].individual_rolls.len(), 4);
+
+    for invalid in [
+        "2d20 focus3",
+        "2d20 comp2",
+        "conan focus2",
+        "conan comp2",
+        "6d20 tn12",
+        "2d6 tn4",
+        "2d20 tn21",
+        "2d20 tn12 focus13",
+        "2d20 tn12 diff6",
+        "2d20 tn12 comp6",
+        "2d20 tn12 k1",
+        "sta 2d20",
+    ] {
+        assert_invalid(invalid);
+    }
+}
+
 #[test]
 fn test_missing_game_systems() {
     // Test the remaining game systems mentioned in roll_syntax.md but not yet tested
@@ -1084,7 +1369,7 @@ fn test_system_edge_cases_and_boundaries() {
         ("ed1", "Earthdawn minimum step"),
         ("ed50", "Earthdawn maximum step (1e)"),
         ("ed4e1", "Earthdawn 4e minimum step"),
-        ("ed4e100", "Earthdawn 4e maximum step"),
+        ("ed4e250", "Earthdawn 4e maximum step"),
         // Hero System edge cases
         ("0.5hsk", "Hero System half die (if supported)"),
         ("10hsk", "Hero System large dice count"),
@@ -5657,3 +5942,1480 @@ fn test_plotweaver_doesnt_break_existing() {
         assert_eq!(result.is_ok(), should_work, "{desc}: {input}");
     }
 }
+
+#[test]
+fn test_genesys_narrative_dice() {
+    // Table-driven: (alias, expansion)
+    let alias_cases = vec![
+        ("gen 2p 1a 2d", "1d1 gen1a2p2d"),
+        ("gen 2p1a2d", "1d1 gen1a2p2d"),
+        ("gen 1b 1s 1c", "1d1 gen1b1s1c"),
+        ("gen 1a 2a", "1d1 gen3a"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // Every die shows one face, and the net symbols match the faces
+    for _ in 0..50 {
+        let result = &parse_and_roll("gen 2b 2a 2p 2s 2d 2c ! Pilot").unwrap()[0];
+        let narrative = result.narrative.as_ref().expect("narrative result");
+        assert_eq!(narrative.faces.len(), 12);
+        let faces: String = narrative.faces.concat();
+        let count = |symbol: char| faces.matches(symbol).count() as i32;
+        assert_eq!(narrative.successes, count('S') - count('F'));
+        assert_eq!(narrative.advantages, count('A') - count('T'));
+        assert_eq!(narrative.triumphs as i32, count('!'));
+        assert_eq!(narrative.despairs as i32, count('X'));
+        assert_eq!(result.successes, Some(narrative.successes));
+        assert!(result.to_string().contains("Reason: `Pilot`"));
+    }
+
+    let result = RollResultBuilder::new()
+        .with(|r| {
+            r.narrative = Some(dicemaiden_rs::dice::NarrativeResult {
+                faces: vec!["🟨`S!`".to_string(), "🟪`FT`".to_string()],
+                successes: 1,
+                advantages: -1,
+                triumphs: 1,
+                despairs: 0,
+            })
+        })
+        .build();
+    assert_eq!(
+        result.to_string(),
+        "Roll: 🟨`S!` 🟪`FT` = **Success**: 1 success, 1 threat, 1 triumph"
+    );
+
+    assert_invalid("gen 51a");
+    assert_invalid("gen 2x");
+    assert_invalid("1d1 gen2a + 1");
+}
+
+#[test]
+fn test_call_of_cthulhu_rolls() {
+    // Table-driven: (alias, expansion)
+    let alias_cases = vec![
+        ("coc", "1d100 coc"),
+        ("coc 65", "1d100 coc65"),
+        ("coc b", "1d100 cocb1"),
+        ("coc pp", "1d100 cocp2"),
+        ("coc 65 b", "1d100 coc65b1"),
+        ("coc 40 pp", "1d100 coc40p2"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // Bonus dice keep the lowest result, penalty dice the highest, and the
+    // outcome follows the skill's hard and extreme thresholds
+    for (expression, dice) in [("coc 65 bb", 3), ("coc 45 pp", 3), ("coc 65", 1)] {
+        for _ in 0..50 {
+            let result = &parse_and_roll(expression).unwrap()[0];
+            let roll = result.total;
+            assert!((1..=100).contains(&roll));
+            assert_eq!(result.dropped_rolls.len() + 1, dice);
+            if expression.contains('b') {
+                assert!(result.dropped_rolls.iter().all(|&other| other >= roll));
+            } else {
+                assert!(result.dropped_rolls.iter().all(|&other| other <= roll));
+            }
+            // Every tens die shares the one units die
+            assert!(
+                result
+                    .dropped_rolls
+                    .iter()
+                    .all(|&other| other % 10 == roll % 10)
+            );
+
+            let skill = if expression.contains("45") { 45 } else { 65 };
+            let expected = match roll {
+                1 => "CRITICAL SUCCESS",
+                96..=100 if skill < 50 => "FUMBLE",
+                100 => "FUMBLE",
+                r if r <= skill / 5 => "EXTREME SUCCESS",
+                r if r <= skill / 2 => "HARD SUCCESS",
+                r if r <= skill => "REGULAR SUCCESS",
+                _ => "FAILURE",
+            };
+            let outcome = result.notes.last().unwrap();
+            assert!(
+                outcome.starts_with(&format!("**{expected}**")),
+                "{expression} rolled {roll}: {outcome}"
+            );
+        }
+    }
+
+    assert_valid("coc ; coc 50 p");
+    assert_invalid("coc 100");
+    assert_invalid("coc 65 bbb");
+    assert_invalid("1d100 coc65 + 5");
+}
+
+#[test]
+fn test_wfrp_tests() {
+    assert_eq!(
+        aliases::expand_alias("wfrp 45"),
+        Some("1d100 wfrp45".to_string())
+    );
+    assert_eq!(
+        aliases::expand_alias("wfrp45"),
+        Some("1d100 wfrp45".to_string())
+    );
+
+    // Success Levels are the tens digits' difference, doubles are criticals
+    // or fumbles
+    for _ in 0..200 {
+        let result = &parse_and_roll("wfrp 45").unwrap()[0];
+        let roll = result.total;
+        assert!((1..=100).contains(&roll));
+        let test = result.wfrp.unwrap();
+        let success = roll <= 45;
+        assert_eq!(test.success, success, "rolled {roll}");
+        let sl = 4 - roll / 10;
+        let expected_sl = if success { sl.max(0) } else { sl.min(0) };
+        assert_eq!(test.success_levels, expected_sl, "rolled {roll}");
+
+        let double = roll == 100 || roll % 11 == 0;
+        let expected = match (success, double) {
+            (true, true) => "CRITICAL SUCCESS",
+            (true, false) => "SUCCESS",
+            (false, true) => "FUMBLE",
+            (false, false) => "FAILURE",
+        };
+        assert!(
+            result.notes[0].starts_with(&format!("**{expected}**")),
+            "rolled {roll}: {}",
+            result.notes[0]
+        );
+
+        // Swapping the dice is offered only when it does better
+        let reversed = match roll % 100 {
+            0 => 100,
+            r => (r % 10) * 10 + r / 10,
+        };
+        let reversed_success = reversed <= 45;
+        let reversed_sl = 4 - reversed / 10;
+        let better = (reversed_success, reversed_sl) > (success, expected_sl);
+        assert_eq!(
+            result.notes.iter().any(|note| note.contains("reversed")),
+            better,
+            "rolled {roll}: {:?}",
+            result.notes
+        );
+    }
+
+    let shown = |success, success_levels| {
+        dicemaiden_rs::dice::WfrpResult {
+            skill: 45,
+            success,
+            success_levels,
+            double: false,
+        }
+        .sl_text()
+    };
+    assert_eq!(shown(true, 2), "+2");
+    assert_eq!(shown(true, 0), "+0");
+    assert_eq!(shown(false, 0), "-0");
+    assert_eq!(shown(false, -3), "-3");
+
+    assert_valid("wfrp 45 ; wfrp 110");
+    assert_invalid("wfrp 0");
+    assert_invalid("wfrp 201");
+    assert_invalid("1d100 wfrp45 + 5");
+}
+
+#[test]
+fn test_dragonbane_rolls() {
+    let alias_cases = vec![
+        ("db", "1d20 db"),
+        ("db 12", "1d20 db12"),
+        ("db12 boon", "1d20 db12boon"),
+        ("db 7 bane push", "1d20 db7banepush"),
+        ("db boon", "1d20 dbboon"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // A 1 is a Dragon and a 20 a Demon; otherwise the roll is tested against
+    // the skill, and failures that aren't Demons offer a push
+    for _ in 0..200 {
+        let result = &parse_and_roll("db 12").unwrap()[0];
+        let roll = result.total;
+        assert!((1..=20).contains(&roll));
+        let expected = match roll {
+            1 => "DRAGON",
+            20 => "DEMON",
+            r if r <= 12 => "SUCCESS",
+            _ => "FAILURE",
+        };
+        assert!(
+            result.notes[0].starts_with(&format!("**{expected}**")),
+            "rolled {roll}: {:?}",
+            result.notes
+        );
+        assert_eq!(
+            result
+                .notes
+                .iter()
+                .any(|note| note.contains("`db 12 push`")),
+            (13..20).contains(&roll),
+            "rolled {roll}: {:?}",
+            result.notes
+        );
+    }
+
+    // Boons keep the lower of two d20s, banes the higher
+    for (alias, boon) in [("db 12 boon", true), ("db 12 bane", false)] {
+        for _ in 0..100 {
+            let result = &parse_and_roll(alias).unwrap()[0];
+            let other = result.dropped_rolls[0];
+            assert_eq!(result.dropped_rolls.len(), 1);
+            if boon {
+                assert!(result.total <= other, "{alias}: {:?}", result);
+            } else {
+                assert!(result.total >= other, "{alias}: {:?}", result);
+            }
+        }
+    }
+
+    // A pushed roll says so and is never offered another push
+    for _ in 0..50 {
+        let result = &parse_and_roll("db 12 bane push").unwrap()[0];
+        assert!(
+            result
+                .notes
+                .iter()
+                .any(|note| note.starts_with("Pushed roll"))
+        );
+        assert!(!result.notes.iter().any(|note| note.contains("Push it")));
+    }
+
+    // Without a skill only Dragons and Demons are called out
+    for _ in 0..50 {
+        let result = &parse_and_roll("db").unwrap()[0];
+        match result.total {
+            1 => assert_eq!(result.notes, vec!["**DRAGON**"]),
+            20 => assert_eq!(result.notes[0], "**DEMON**"),
+            _ => assert!(result.notes.is_empty(), "{:?}", result.notes),
+        }
+    }
+
+    assert_valid("db 1 ; db 20 boon");
+    assert_invalid("db 0");
+    assert_invalid("db 21");
+    assert_invalid("db 12 boon bane");
+    assert_invalid("2d20 db12");
+    assert_invalid("1d20 db12 + 2");
+}
+
+#[test]
+fn test_forbidden_lands_pools() {
+    let alias_cases = vec![
+        ("fbl b5 s3 g2 a10", "1d1 fblb5s3g2a10"),
+        ("fbl b4 push", "1d1 fblb4push"),
+        ("fblb2a8a12", "1d1 fblb2a8a12"),
+        ("fbl s2 g1", "1d1 fbls2g1"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // Artifact dice count 1 success on 6-7, 2 on 8-9, 3 on 10-11, 4 on 12
+    let successes: Vec<u32> = (1..=12)
+        .map(dicemaiden_rs::dice::YearZeroResult::die_successes)
+        .collect();
+    assert_eq!(successes, vec![0, 0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4]);
+
+    for seed in 0..100 {
+        let first =
+            &parse_and_roll_with_rng("fbl b5 s3 g2 a10", &mut rng::seeded_rng(seed)).unwrap()[0];
+        let pushed = &parse_and_roll_with_rng("fbl b5 s3 g2 a10 push", &mut rng::seeded_rng(seed))
+            .unwrap()[0];
+        let (first, pushed) = (
+            first.year_zero.as_ref().unwrap(),
+            pushed.year_zero.as_ref().unwrap(),
+        );
+        assert!(!first.pushed);
+        assert!(pushed.pushed);
+
+        // Base and gear banes are counted apart; the artifact die is gear
+        let ones = |dice: &[i32]| dice.iter().filter(|&&roll| roll == 1).count() as u32;
+        assert_eq!(first.base_banes, ones(&first.base));
+        assert_eq!(
+            first.gear_banes,
+            ones(&first.gear) + u32::from(first.artifacts[0].1 == 1)
+        );
+
+        // A push keeps successes and banes (skill dice have none) and
+        // rerolls the rest
+        let stands = |roll: i32, banes: bool| roll >= 6 || (banes && roll == 1);
+        for (before, after, banes) in [
+            (&first.base, &pushed.base, true),
+            (&first.skill, &pushed.skill, false),
+            (&first.gear, &pushed.gear, true),
+        ] {
+            for (&before, &after) in before.iter().zip(after.iter()) {
+                if stands(before, banes) {
+                    assert_eq!(before, after, "seed {seed}");
+                }
+            }
+        }
+        if stands(first.artifacts[0].1, true) {
+            assert_eq!(first.artifacts[0], pushed.artifacts[0], "seed {seed}");
+        }
+        assert!(pushed.successes >= first.successes, "seed {seed}");
+        assert!(pushed.base_banes >= first.base_banes, "seed {seed}");
+    }
+
+    let result = RollResultBuilder::new()
+        .successes(3)
+        .with(|result| {
+            result.year_zero = Some(dicemaiden_rs::dice::YearZeroResult {
+                base: vec![6, 3, 1],
+                skill: vec![4],
+                gear: vec![1, 2],
+                artifacts: vec![(10, 8)],
+                successes: 3,
+                base_banes: 1,
+                gear_banes: 1,
+                pushed: false,
+            })
+        })
+        .build();
+    assert_eq!(
+        result.to_string(),
+        "Roll: Base `[6, 3, 1]` Skill `[4]` Gear `[1, 2]` d10 `[8]` = **3** successes, 1 base bane, 1 gear bane"
+    );
+
+    let pushed = &roll("fbl b2 push")[0];
+    assert!(pushed.notes[0].starts_with("Pushed: rerolled"));
+
+    assert_valid("fbl b5 s3 g2 a8 a12 ; fbl s1");
+    assert_invalid("fbl b0");
+    assert_invalid("fbl b5 a6");
+    assert_invalid("fbl b5 b2");
+    assert_invalid("fbl b20 s11");
+    assert_invalid("1d1 fblb5 + 2");
+}
+
+#[test]
+fn test_mothership_stat_and_panic_checks() {
+    let alias_cases = vec![
+        ("msh 45", "1d100 ms45"),
+        ("msh45", "1d100 ms45"),
+        ("msh 45 adv", "2d100 ms45a"),
+        ("msh 45 dis", "2d100 ms45d"),
+        ("+msh 30", "2d100 ms30a"),
+        ("-msh 30", "2d100 ms30d"),
+        ("msh panic 5", "1d20 mspanic5"),
+        ("msh panic0", "1d20 mspanic0"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // Stat checks are the ms rolls: doubles are criticals
+    for _ in 0..100 {
+        let result = &parse_and_roll("msh 45").unwrap()[0];
+        let roll = result.total.max(result.kept_rolls[0]);
+        let double = roll == 100 || roll % 11 == 0;
+        let critical = result.notes.iter().any(|note| note.contains("CRITICAL"));
+        assert_eq!(critical, double, "rolled {roll}: {:?}", result.notes);
+    }
+
+    // A panic check panics on a d20 at or under the stress, with the table
+    // entry for the roll
+    for _ in 0..200 {
+        let result = &parse_and_roll("msh panic 10").unwrap()[0];
+        let roll = result.total;
+        assert!((1..=20).contains(&roll));
+        let panic = roll <= 10;
+        assert_eq!(
+            result.fitd_outcome.as_deref(),
+            Some(if panic { "PANIC" } else { "NO PANIC" })
+        );
+        assert_eq!(
+            result.notes.len(),
+            if panic { 2 } else { 1 },
+            "{:?}",
+            result.notes
+        );
+        assert!(result.notes[0].contains(&format!("rolled {roll}")));
+    }
+    let panicked = &parse_and_roll_with_rng("msh panic 20", &mut rng::seeded_rng(1)).unwrap()[0];
+    assert!(panicked.notes[1].starts_with("**"), "{:?}", panicked.notes);
+    assert!(
+        parse_and_roll("msh panic 0").unwrap()[0].notes.len() == 1,
+        "stress 0 never panics"
+    );
+
+    assert_valid("msh 1 ; msh 99 adv ; msh panic 20");
+    assert_invalid("msh panic 21");
+    assert_invalid("msh 100");
+    assert_invalid("1d20 mspanic5 + 2");
+    assert_invalid("2d20 mspanic5");
+}
+
+#[test]
+fn test_burning_wheel_tests() {
+    let alias_cases = vec![
+        ("bw 5 ob3", "1d1 bw5ob3"),
+        ("bw4", "1d1 bw4"),
+        ("bw 4 ob 2 h2", "1d1 bw4ob2h2"),
+        ("bw 6 ob4 open luck", "1d1 bw6ob4openluck"),
+        ("bw 3 luck", "1d1 bw3luck"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    for seed in 0..100 {
+        let closed =
+            &parse_and_roll_with_rng("bw 5 ob3 h2", &mut rng::seeded_rng(seed)).unwrap()[0];
+        let test = closed.burning_wheel.as_ref().unwrap();
+        assert_eq!((test.exponent.len(), test.helping.len()), (5, 2));
+        assert!(test.open_ended.is_empty());
+
+        // 4+ is a success; the margin is measured against the obstacle
+        let successes = test
+            .exponent
+            .iter()
+            .chain(&test.helping)
+            .filter(|&&roll| roll >= 4)
+            .count() as u32;
+        assert_eq!(test.successes, successes, "seed {seed}");
+        assert_eq!(closed.successes, Some(successes as i32));
+        assert_eq!(test.margin, Some(successes as i32 - 3));
+        assert_eq!(test.passed, Some(successes >= 3));
+
+        // Each six rolls another die, and so does each six among those
+        let open =
+            &parse_and_roll_with_rng("bw 5 ob3 h2 open", &mut rng::seeded_rng(seed)).unwrap()[0];
+        let open = open.burning_wheel.as_ref().unwrap();
+        let sixes = |dice: &[i32]| dice.iter().filter(|&&roll| roll == 6).count();
+        assert_eq!(
+            open.open_ended.len(),
+            sixes(&open.exponent) + sixes(&open.helping) + sixes(&open.open_ended),
+            "seed {seed}"
+        );
+
+        // Luck makes a closed roll open-ended
+        let lucky =
+            &parse_and_roll_with_rng("bw 5 ob3 h2 luck", &mut rng::seeded_rng(seed)).unwrap()[0];
+        let lucky = lucky.burning_wheel.as_ref().unwrap();
+        assert_eq!(lucky.exponent, test.exponent);
+        assert_eq!(
+            lucky.open_ended.len(),
+            sixes(&test.exponent) + sixes(&test.helping) + sixes(&lucky.open_ended),
+            "seed {seed}"
+        );
+
+        // On an open-ended roll it rerolls the first traitor instead
+        let rerolled =
+            &parse_and_roll_with_rng("bw 5 ob3 h2 open luck", &mut rng::seeded_rng(seed)).unwrap()
+                [0];
+        let before: Vec<i32> = test.exponent.iter().chain(&test.helping).copied().collect();
+        let after: Vec<i32> = {
+            let test = rerolled.burning_wheel.as_ref().unwrap();
+            test.exponent.iter().chain(&test.helping).copied().collect()
+        };
+        match before.iter().position(|&roll| roll < 4) {
+            Some(traitor) => {
+                assert!(rerolled.notes[0].starts_with("Luck: rerolled a traitor"));
+                for (i, (before, after)) in before.iter().zip(&after).enumerate() {
+                    if i != traitor {
+                        assert_eq!(before, after, "seed {seed}");
+                    }
+                }
+            }
+            None => {
+                assert_eq!(before, after);
+                assert!(rerolled.notes.is_empty());
+            }
+        }
+    }
+
+    // Without an obstacle only the successes are counted
+    let test = &roll("bw 4")[0];
+    let test = test.burning_wheel.as_ref().unwrap();
+    assert_eq!(
+        (test.obstacle, test.margin, test.passed),
+        (None, None, None)
+    );
+
+    let result = RollResultBuilder::new()
+        .successes(3)
+        .with(|result| {
+            result.burning_wheel = Some(dicemaiden_rs::dice::BurningWheelResult {
+                exponent: vec![6, 4, 2],
+                helping: vec![1],
+                open_ended: vec![5],
+                successes: 3,
+                obstacle: Some(4),
+                margin: Some(-1),
+                passed: Some(false),
+            })
+        })
+        .build();
+    assert_eq!(
+        result.to_string(),
+        "Roll: `[6, 4, 2]` Help `[1]` Open `[5]` = **3** successes vs Ob 4 — **FAILED** by 1"
+    );
+
+    assert_valid("bw 5 ob3 ; bw 2 ob1 h1 open");
+    assert_invalid("bw 0 ob2");
+    assert_invalid("bw 4 ob0");
+    assert_invalid("bw 25 ob3 h6");
+    assert_invalid("1d1 bw5ob3 + 2");
+}
+
+#[test]
+fn test_one_roll_engine_sets() {
+    for (alias, expected) in [("ore 7", "7d10 m"), ("ore10", "10d10 m")] {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // Sets are matching dice, widest first; everything else is loose
+    let matched = dicemaiden_rs::dice::MatchResult::from_rolls(&[7, 4, 9, 7, 1, 4, 7]);
+    let set = |width, height| dicemaiden_rs::dice::MatchSet { width, height };
+    assert_eq!(matched.sets, [set(3, 7), set(2, 4)]);
+    assert_eq!(matched.loose, [9, 1]);
+    let matched = dicemaiden_rs::dice::MatchResult::from_rolls(&[2, 2, 9, 9, 5]);
+    assert_eq!(matched.sets, [set(2, 9), set(2, 2)]);
+
+    for seed in 0..100 {
+        let result = &parse_and_roll_with_rng("ore 7", &mut rng::seeded_rng(seed)).unwrap()[0];
+        let matched = result.matches.as_ref().unwrap();
+        let widths: u32 = matched.sets.iter().map(|set| set.width).sum();
+        assert_eq!(widths as usize + matched.loose.len(), 7, "seed {seed}");
+        assert!(matched.sets.iter().all(|set| set.width >= 2));
+        assert!(matched.sets.windows(2).all(|w| w[0].width >= w[1].width));
+    }
+
+    // `m` works on any pool, after keeping
+    let result = &roll("6d1 k4 m")[0];
+    assert_eq!(result.matches.as_ref().unwrap().sets, [set(4, 1)]);
+
+    let result = RollResultBuilder::new()
+        .rolls(&[9, 7, 7, 7, 4, 4, 1])
+        .with(|result| {
+            result.matches = Some(dicemaiden_rs::dice::MatchResult::from_rolls(
+                &result.kept_rolls,
+            ))
+        })
+        .build();
+    assert_eq!(
+        result.to_string(),
+        "Roll: `[9, 7, 7, 7, 4, 4, 1]` = **3x7**, **2x4** | Loose: `[9, 1]`"
+    );
+    let result = RollResultBuilder::new()
+        .rolls(&[3, 2])
+        .with(|result| {
+            result.matches = Some(dicemaiden_rs::dice::MatchResult::from_rolls(
+                &result.kept_rolls,
+            ))
+        })
+        .build();
+    assert_eq!(
+        result.to_string(),
+        "Roll: `[3, 2]` = **No matches** | Loose: `[3, 2]`"
+    );
+
+    assert_valid("ore 5 ; 4d10 m ! Attack");
+    assert_invalid("ore 0");
+}
+
+#[test]
+fn test_cortex_prime_pools() {
+    for (alias, expected) in [
+        ("cortex d8 d8 d6 d10", "{d8+d8+d6+d10} cortex"),
+        ("cortex 2d8 d6", "{2d8+d6} cortex"),
+    ] {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // Best two make the total, the largest die left is the effect, 1s are hitches
+    let die = |sides, roll| dicemaiden_rs::dice::CortexDie { sides, roll };
+    let cortex = dicemaiden_rs::dice::CortexResult::from_dice(vec![
+        die(8, 7),
+        die(6, 1),
+        die(10, 9),
+        die(8, 3),
+        die(12, 2),
+    ]);
+    assert_eq!(cortex.total(), 16);
+    assert_eq!(cortex.effect, 12);
+    assert_eq!(cortex.hitches, 1);
+    // Equal rolls leave the larger die for the effect
+    let cortex =
+        dicemaiden_rs::dice::CortexResult::from_dice(vec![die(10, 4), die(6, 4), die(4, 4)]);
+    assert_eq!((cortex.total(), cortex.effect), (8, 10));
+    // Nothing left over: the effect die is a d4
+    let cortex = dicemaiden_rs::dice::CortexResult::from_dice(vec![die(8, 5), die(6, 1)]);
+    assert_eq!((cortex.total(), cortex.effect), (5, 4));
+    let cortex = dicemaiden_rs::dice::CortexResult::from_dice(vec![die(8, 1), die(6, 1)]);
+    assert!(cortex.botched());
+
+    for seed in 0..100 {
+        let result =
+            &parse_and_roll_with_rng("cortex d8 d8 d6 d10", &mut rng::seeded_rng(seed)).unwrap()[0];
+        let cortex = result.cortex.as_ref().unwrap();
+        assert_eq!(cortex.dice.len(), 4, "seed {seed}");
+        assert!(
+            cortex
+                .dice
+                .iter()
+                .all(|die| die.roll >= 1 && die.roll <= die.sides as i32)
+        );
+        assert_eq!(result.total, cortex.total(), "seed {seed}");
+        assert!(cortex.total_dice <= 2);
+    }
+
+    let result = RollResultBuilder::new()
+        .rolls(&[9, 7, 3, 1])
+        .with(|result| {
+            result.cortex = Some(dicemaiden_rs::dice::CortexResult::from_dice(vec![
+                die(10, 9),
+                die(8, 7),
+                die(8, 3),
+                die(6, 1),
+            ]))
+        })
+        .build();
+    assert_eq!(
+        result.to_string(),
+        "Roll: `[d10:9, d8:7]` `[d8:3]` Hitches `[d6:1]` = **16** | Effect: **d8** | Hitches: 1"
+    );
+    let result = RollResultBuilder::new()
+        .rolls(&[1, 1])
+        .with(|result| {
+            result.cortex = Some(dicemaiden_rs::dice::CortexResult::from_dice(vec![
+                die(8, 1),
+                die(6, 1),
+            ]))
+        })
+        .build();
+    assert_eq!(
+        result.to_string(),
+        "Roll: Hitches `[d6:1, d8:1]` = **BOTCH** | Hitches: 2"
+    );
+
+    assert_valid("cortex d4 d12 ! Trait roll");
+    // A pool of one size works without the alias
+    let result = &roll("3d8 cortex")[0];
+    assert!(
+        result
+            .cortex
+            .as_ref()
+            .unwrap()
+            .dice
+            .iter()
+            .all(|die| die.sides == 8)
+    );
+    assert_invalid("{d8+d7} cortex");
+    assert_invalid("2d20 cortex");
+    assert_invalid("{d8+d6} cortex + 2");
+    assert_invalid("21d6 cortex");
+}
+
+#[test]
+fn test_earthdawn_karma_and_extended_steps() {
+    let alias_cases = vec![
+        ("ed15 k8", "1d12 ie + 2d6 ie + 1d8 ie"),
+        ("ed15 k", "1d12 ie + 2d6 ie + 1d6 ie"),
+        ("ed1 k4", "1d4 ie - 2 + 1d4 ie"),
+        ("ed4e15 k6", "1d12 ie + 2d6 ie + 1d6 ie"),
+        // 4e steps keep cycling past the old table: one more d20 every 11 steps
+        ("ed4e100", "8d20 ie + 2d10 ie"),
+        ("ed4e111", "9d20 ie + 2d10 ie"),
+        ("ed4e250", "22d20 ie + 2d6 ie"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // The karma die explodes like the step dice
+    for seed in 0..50 {
+        let result = &parse_and_roll_with_rng("ed15 k8", &mut rng::seeded_rng(seed)).unwrap()[0];
+        assert!(result.total >= 4, "seed {seed}");
+    }
+
+    let error_cases = vec![
+        ("ed51", "Earthdawn steps go from 1 to 50, got 51"),
+        ("ed0", "Earthdawn steps go from 1 to 50, got 0"),
+        ("ed4e251", "Earthdawn 4e steps go from 1 to 250, got 251"),
+        ("ed15 k7", "Karma dice are d4, d6, d8, d10 or d12, got d7"),
+    ];
+    for (input, message) in error_cases {
+        let error = parse_and_roll(input).unwrap_err().to_string();
+        assert!(error.contains(message), "'{input}': {error}");
+    }
+
+    assert_valid("ed4e20 k10 ; ed10 k12");
+    assert_invalid("ed15 k20");
+}
+
+#[test]
+fn test_l5r_roll_and_keep() {
+    // Table-driven: (alias, expansion)
+    let alias_cases = vec![
+        ("l5r 5k3", "1d1 l5r5k3"),
+        ("l5r 3k3", "1d1 l5r3k3"),
+        ("l5r10k5", "1d1 l5r10k5"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // The ring's worth of dice is kept, plus one die per explosive success
+    // that didn't come up blank, and the totals match the kept faces
+    for _ in 0..50 {
+        let result = &parse_and_roll("l5r 6k3 ! Sneak").unwrap()[0];
+        let l5r = result.l5r.as_ref().expect("l5r result");
+        assert_eq!(
+            l5r.kept.len() + l5r.dropped.len(),
+            6 + l5r.explosions as usize
+        );
+        assert!(l5r.kept.len() >= 3);
+        let kept: String = l5r.kept.concat();
+        let count = |symbols: &[char]| kept.chars().filter(|c| symbols.contains(c)).count() as u32;
+        assert_eq!(l5r.successes, count(&['S', 'E']));
+        assert_eq!(l5r.opportunities, count(&['O']));
+        assert_eq!(l5r.strife, count(&['!']));
+        assert_eq!(l5r.explosions, count(&['E']));
+        assert_eq!(result.successes, Some(l5r.successes as i32));
+        assert!(result.to_string().contains("Reason: `Sneak`"));
+    }
+
+    let result = RollResultBuilder::new()
+        .with(|r| {
+            r.l5r = Some(dicemaiden_rs::dice::L5rResult {
+                kept: vec![
+                    "⚪`E`".to_string(),
+                    "⚫`S!`".to_string(),
+                    "⚪`O`".to_string(),
+                ],
+                dropped: vec!["⚫`-`".to_string()],
+                successes: 2,
+                opportunities: 1,
+                strife: 1,
+                explosions: 1,
+            })
+        })
+        .build();
+    assert_eq!(
+        result.to_string(),
+        "Roll: ⚪`E` ⚫`S!` ⚪`O` ~~⚫`-`~~ = **2** successes, 1 opportunity, 1 strife"
+    );
+
+    assert_valid("l5r 2k2 ; l5r 4k2");
+    assert_invalid("l5r 3k4");
+    assert_invalid("l5r 3k0");
+    assert_invalid("l5r 21k5");
+    assert_invalid("1d1 l5r5k3 + 1");
+}
+
+#[test]
+fn test_ironsworn_action_and_oracle() {
+    // Table-driven: (alias, expansion)
+    let alias_cases = vec![
+        ("iron", "1d6 iron"),
+        ("iron +2", "1d6+2 iron"),
+        ("iron+3 +1", "1d6+3+1 iron"),
+        ("iron -1", "1d6-1 iron"),
+        ("oracle", "1d100 oracle"),
+        ("oracle likely", "1d100 oracle26"),
+        ("oracle 50/50", "1d100 oracle51"),
+        ("oracle small chance", "1d100 oracle91"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // The action score (capped at 10) is compared with both challenge dice
+    for _ in 0..100 {
+        let result = &roll("iron +8 ! Face Danger")[0];
+        let action_die = result.kept_rolls[0];
+        assert_eq!(result.total, (action_die + 8).min(10));
+        let challenge = result.ironsworn_challenge.as_ref().expect("challenge dice");
+        assert!(challenge.iter().all(|die| (1..=10).contains(die)));
+        let beaten = challenge.iter().filter(|&&die| result.total > die).count();
+        let expected = ["MISS", "WEAK HIT", "STRONG HIT"][beaten];
+        assert_eq!(result.ironsworn_outcome.as_deref(), Some(expected));
+        assert_eq!(
+            challenge[0] == challenge[1],
+            result.notes.iter().any(|note| note.contains("MATCH"))
+        );
+        assert!(result.to_string().contains("Reason: `Face Danger`"));
+    }
+
+    for _ in 0..100 {
+        let result = &roll("oracle likely")[0];
+        let expected = if result.total >= 26 { "YES" } else { "NO" };
+        assert_eq!(result.ironsworn_outcome.as_deref(), Some(expected));
+    }
+    assert_eq!(roll("oracle")[0].ironsworn_outcome, None);
+
+    let result = RollResultBuilder::new()
+        .rolls(&[4])
+        .total(6)
+        .with(|r| {
+            r.ironsworn_outcome = Some("WEAK HIT".to_string());
+            r.ironsworn_challenge = Some(vec![3, 9]);
+        })
+        .build();
+    assert_eq!(
+        result.to_string(),
+        "Roll: `[4]` = **6** vs `[3, 9]` — **WEAK HIT**"
+    );
+
+    assert_invalid("1d6 iron k1");
+    assert_invalid("2d6 iron");
+    assert_invalid("1d100 oracle101");
+    assert_invalid("1d100 oracle + 5");
+}
+
+#[test]
+fn test_alias_typo_corrections() {
+    // Table-driven: (typed, close aliases)
+    let similar_cases = vec![
+        ("4cof", vec!["4cod"]),
+        ("wtm5h2", vec!["vtm5h2"]),
+        ("4cd", vec!["4cod"]),
+        ("cco 65", vec!["coc 65"]),
+        ("hsm", vec!["hsh", "hsk", "hsn"]),
+        ("4cod", vec![]), // Already an alias
+        ("xyzzy", vec![]),
+    ];
+    for (typed, expected) in similar_cases {
+        assert_eq!(aliases::similar_aliases(typed), expected, "'{typed}'");
+    }
+
+    // Flags, labels, comments, roll-set counts and other rolls are kept
+    let correction_cases = vec![
+        ("4cof", "4cof", "4cod", "4cod"),
+        (
+            "p (Sneak) 4cof ! Hide",
+            "4cof",
+            "4cod",
+            "p (Sneak) 4cod ! Hide",
+        ),
+        ("6 4cof", "4cof", "4cod", "6 4cod"),
+        ("1d20 + 2; sr6x", "sr6x", "sr6", "1d20 + 2; sr6"),
+    ];
+    for (input, typed, alias, expression) in correction_cases {
+        let corrections = parser::alias_corrections(input);
+        assert_eq!(corrections.len(), 1, "'{input}': {corrections:?}");
+        assert_eq!(corrections[0].typed, typed);
+        assert_eq!(corrections[0].alias, alias);
+        assert_eq!(corrections[0].expression, expression);
+        assert_valid(expression);
+    }
+
+    // Rolls that parse, and typos that aren't near an alias, get nothing
+    for input in ["4cod", "2d6 + x", "1d20 kk3", "4cof; 2cof"] {
+        assert!(parser::alias_corrections(input).is_empty(), "'{input}'");
+    }
+}
+
+#[test]
+fn test_alias_autocomplete_examples() {
+    // Every example rolls, and the list stays sorted for the prefix search
+    for (example, description) in aliases::ALIAS_EXAMPLES {
+        assert!(aliases::expand_alias(example).is_some(), "'{example}'");
+        assert!(!description.is_empty(), "'{example}'");
+        assert_valid(example);
+    }
+    assert!(
+        aliases::ALIAS_EXAMPLES
+            .windows(2)
+            .all(|pair| pair[0].0 < pair[1].0)
+    );
+
+    let prefix_cases = vec![
+        ("co", vec!["coc 50", "coin", "cortex d8 d8 d6"]),
+        ("CO", vec!["coc 50", "coin", "cortex d8 d8 d6"]),
+        ("alien3", vec!["alien3", "alien3s2"]),
+        ("+d", vec!["+d%", "+d20"]),
+        ("gurps 1", vec!["gurps 12"]),
+        ("zz", vec![]),
+    ];
+    for (prefix, expected) in prefix_cases {
+        let examples: Vec<&str> = aliases::alias_examples_with_prefix(prefix)
+            .iter()
+            .map(|(example, _)| *example)
+            .collect();
+        assert_eq!(examples, expected, "'{prefix}'");
+    }
+    assert_eq!(
+        aliases::alias_examples_with_prefix("").len(),
+        aliases::ALIAS_EXAMPLES.len()
+    );
+}
+
+#[test]
+fn test_user_alias_expansion_limits() {
+    use std::collections::HashMap;
+
+    let definitions: HashMap<&str, &str> = [
+        ("atk", "1d20 + str"),
+        ("str", "5"),
+        ("a", "b + 1"),
+        ("b", "c + 1"),
+        ("c", "a + 1"),
+        ("me", "me + 1"),
+        ("double", "twice twice"),
+        ("twice", "double"),
+        ("big", "huge huge huge huge"),
+        ("huge", "giant giant giant giant"),
+        ("giant", "giant_roll giant_roll giant_roll giant_roll"),
+        ("giant_roll", "100d100 + 100d100 + 100d100 + 100d100"),
+    ]
+    .into_iter()
+    .collect();
+    let lookup = |name: &str| definitions.get(name).map(|d| d.to_string());
+
+    // Nested definitions expand; unknown words, labels and comments are left alone
+    let expansion_tests = vec![
+        ("atk", "1d20 + 5"),
+        ("ATK + 2", "1d20 + 5 + 2"),
+        ("2d6 k1 + str", "2d6 k1 + 5"),
+        ("(atk) atk ! str check", "(atk) 1d20 + 5 ! str check"),
+        ("4d6 k3", "4d6 k3"),
+        ("2atk", "2atk"),
+    ];
+    for (input, expected) in expansion_tests {
+        let result = aliases::expand_user_aliases(input, lookup);
+        assert_eq!(
+            result.as_deref().ok(),
+            Some(expected),
+            "Wrong expansion for '{}': {:?}",
+            input,
+            result
+        );
+    }
+
+    // Cycles report the loop path
+    let loop_tests = vec![
+        ("a", "alias loop: a → b → c → a"),
+        ("me", "alias loop: me → me"),
+        ("1d6 + twice", "alias loop: twice → double → twice"),
+    ];
+    for (input, expected) in loop_tests {
+        let error = aliases::expand_user_aliases(input, lookup)
+            .expect_err(&format!("'{}' should be rejected", input));
+        assert_eq!(error.to_string(), expected);
+    }
+
+    // Exponential definitions hit the length limit instead of exhausting memory
+    let error = aliases::expand_user_aliases("big", lookup).expect_err("'big' should be rejected");
+    assert!(
+        error.to_string().contains("too long"),
+        "Unexpected error: {}",
+        error
+    );
+
+    // Chains deeper than the limit are rejected
+    let chain: HashMap<String, String> = (0..=aliases::MAX_USER_ALIAS_DEPTH)
+        .map(|i| (format!("n{i}"), format!("n{} + 1", i + 1)))
+        .collect();
+    let error = aliases::expand_user_aliases("n0", |name| chain.get(name).cloned())
+        .expect_err("deep chain should be rejected");
+    assert!(
+        error.to_string().starts_with("alias nesting too deep"),
+        "Unexpected error: {}",
+        error
+    );
+}
+
+#[test]
+fn test_user_alias_arguments() {
+    use std::collections::HashMap;
+
+    let definitions: HashMap<&str, &str> = [
+        ("atk", "1d20+{x} + 1d8+{x}"),
+        ("dmg", "{n}d{sides} + {bonus}"),
+        ("smite", "atk({x}) + 2d8"),
+        ("str", "3"),
+    ]
+    .into_iter()
+    .collect();
+    let lookup = |name: &str| definitions.get(name).map(|d| d.to_string());
+
+    // Arguments fill placeholders in order of first use, and may be dice
+    let expansion_tests = vec![
+        ("atk(5)", "1d20+5 + 1d8+5"),
+        ("ATK( 2 ) + str", "1d20+2 + 1d8+2 + 3"),
+        ("dmg(2, 6, d4)", "2d6 + d4"),
+        ("smite(1)", "1d20+1 + 1d8+1 + 2d8"),
+        ("(Hit) atk(3) ! atk(9)", "(Hit) 1d20+3 + 1d8+3 ! atk(9)"),
+    ];
+    for (input, expected) in expansion_tests {
+        let result = aliases::expand_user_aliases(input, lookup);
+        assert_eq!(
+            result.as_deref().ok(),
+            Some(expected),
+            "Wrong expansion for '{}': {:?}",
+            input,
+            result
+        );
+        assert!(
+            parse_and_roll(expected).is_ok(),
+            "'{}' should roll",
+            expected
+        );
+    }
+
+    let error_tests = vec![
+        ("atk", "`atk` needs arguments: `atk(x)`"),
+        ("atk + 5", "`atk` needs arguments: `atk(x)`"),
+        ("atk(1, 2)", "`atk(x)` takes 1 argument, got 2"),
+        ("dmg(2)", "`dmg(n, sides, bonus)` takes 3 arguments, got 1"),
+        (
+            "atk(str)",
+            "Invalid argument `str` for `atk(x)`: use a number or dice such as `2` or `1d6`",
+        ),
+        (
+            "atk(-1)",
+            "Invalid argument `-1` for `atk(x)`: use a number or dice such as `2` or `1d6`",
+        ),
+    ];
+    for (input, expected) in error_tests {
+        let error = aliases::expand_user_aliases(input, lookup)
+            .expect_err(&format!("'{}' should be rejected", input));
+        assert_eq!(error.to_string(), expected);
+    }
+}
+
+#[test]
+fn test_stat_pool_expansion() {
+    use std::collections::HashMap;
+
+    let stats: HashMap<&str, i64> = [
+        ("str", 3),
+        ("brawl", 4),
+        ("dex", 2),
+        ("hunger", 2),
+        ("wits", 1),
+        ("big", 60),
+    ]
+    .into_iter()
+    .collect();
+    let lookup = |name: &str| stats.get(name).copied();
+
+    let expansion_tests = vec![
+        ("vtm str+brawl", "(str + brawl) vtm7h2"),
+        ("VTM Str + Brawl", "(str + brawl) vtm7h2"),
+        ("vtm wits", "(wits) vtm1h1"), // Hunger never exceeds the pool
+        ("cod dex + brawl - 1", "(dex + brawl - 1) 5cod"),
+        ("cod wits - 2", "(wits - 2, chance die) 1d10 t10 f1"),
+        ("sr str + 2", "(str + 2) sr5"),
+        ("ex dex+brawl", "(dex + brawl) ex6"),
+        ("p vtm str + brawl", "p (str + brawl) vtm7h2"),
+        (
+            "(Punch) vtm str+brawl ! bar fight",
+            "(Punch) vtm7h2 ! bar fight",
+        ),
+    ];
+    for (input, expected) in expansion_tests {
+        assert!(aliases::is_stat_pool(input), "'{}' is a stat pool", input);
+        let expanded = aliases::expand_stat_pool(input, lookup)
+            .expect("stat pool")
+            .unwrap_or_else(|e| panic!("'{}' should expand: {}", input, e));
+        assert_eq!(expanded, expected, "Wrong expansion for '{}'", input);
+        assert!(
+            parse_and_roll(&expanded).is_ok(),
+            "'{}' should roll",
+            expanded
+        );
+    }
+
+    // Expressions that already roll, or name no stats, are left alone
+    for input in ["5cod", "vtm7h2", "sr6", "ex5", "cod 5", "vtm", "2d6 + str"] {
+        assert!(
+            !aliases::is_stat_pool(input),
+            "'{}' is not a stat pool",
+            input
+        );
+        assert!(aliases::expand_stat_pool(input, lookup).is_none());
+    }
+
+    let error_tests = vec![
+        ("vtm str + missing", "Unknown stat `missing`"),
+        ("vtm wits - 1", "empty"),
+        ("sr str - 5", "empty"),
+        ("vtm big + big", "too large"),
+    ];
+    for (input, expected) in error_tests {
+        let error = aliases::expand_stat_pool(input, lookup)
+            .expect("stat pool")
+            .expect_err(&format!("'{}' should be rejected", input));
+        assert!(
+            error.to_string().contains(expected),
+            "Unexpected error for '{}': {}",
+            input,
+            error
+        );
+    }
+}
+
+#[test]
+fn test_sheet_trackers() {
+    use dicemaiden_rs::dice::trackers::{self, TrackerUpdate};
+    use std::collections::HashMap;
+
+    let stats: HashMap<&str, i64> = [("hunger", 3), ("stress", 2), ("momentum", 4)]
+        .into_iter()
+        .collect();
+    let lookup = |name: &str| stats.get(name).copied();
+
+    let injection_tests = vec![
+        ("vtm7", "vtm7h3", vec![]),
+        ("vtm2", "vtm2h2", vec![]), // Hunger never exceeds the pool
+        ("(Feed) vtm5 ! hunt", "(Feed) vtm5h3 ! hunt", vec![]),
+        ("p vtm6", "p vtm6h3", vec![]),
+        ("alien5", "alien5s2", vec![]),
+        (
+            "alien5p",
+            "alien5s2p",
+            vec![TrackerUpdate {
+                name: "stress",
+                from: 2,
+                to: 3,
+            }],
+        ),
+        (
+            "conan3",
+            "conan3",
+            vec![TrackerUpdate {
+                name: "momentum",
+                from: 4,
+                to: 3,
+            }],
+        ),
+        (
+            "conan4cd2",
+            "conan4cd2",
+            vec![TrackerUpdate {
+                name: "momentum",
+                from: 4,
+                to: 1,
+            }],
+        ),
+    ];
+    for (input, expected, updates) in injection_tests {
+        assert!(trackers::uses_trackers(input), "'{}' uses trackers", input);
+        let tracked = trackers::apply_trackers(input, lookup)
+            .expect("tracked roll")
+            .unwrap_or_else(|e| panic!("'{}' should apply: {}", input, e));
+        assert_eq!(tracked.expression, expected, "Wrong roll for '{}'", input);
+        assert_eq!(tracked.updates, updates, "Wrong updates for '{}'", input);
+        assert!(
+            parse_and_roll(&tracked.expression).is_ok(),
+            "'{}' should roll",
+            tracked.expression
+        );
+    }
+
+    // Rolls that state their own value are never touched
+    for input in [
+        "vtm7h2",
+        "alien5s1",
+        "alien5s1p",
+        "2d20 conan",
+        "conan2cd3",
+        "1d20",
+    ] {
+        assert!(
+            trackers::apply_trackers(input, lookup).is_none(),
+            "'{}'",
+            input
+        );
+    }
+
+    // Without a sheet, existing rolls behave as before and VtM assumes no hunger
+    let empty = |_: &str| None;
+    assert!(trackers::apply_trackers("alien5", empty).is_none());
+    assert!(trackers::apply_trackers("conan4", empty).is_none());
+    let fresh = trackers::apply_trackers("vtm4", empty)
+        .expect("tracked roll")
+        .expect("vtm4 applies");
+    assert_eq!(fresh.expression, "vtm4h0");
+    let push = trackers::apply_trackers("alien3p", empty)
+        .expect("tracked roll")
+        .expect("push applies");
+    assert_eq!(push.expression, "alien3s0p");
+    assert!(parse_and_roll(&push.expression).is_ok());
+
+    let error_tests: Vec<(&str, i64, &str)> = vec![
+        ("conan5", 4, "Not enough momentum"),
+        ("alien4p", 10, "already at 10"),
+    ];
+    for (input, value, expected) in error_tests {
+        let error = trackers::apply_trackers(input, |_| Some(value))
+            .expect("tracked roll")
+            .expect_err(&format!("'{}' should be rejected", input));
+        assert!(
+            error.to_string().contains(expected),
+            "Unexpected error for '{}': {}",
+            input,
+            error
+        );
+    }
+}
+
+#[test]
+fn test_labeled_face_dice() {
+    // Table-driven: (alias, expansion)
+    let alias_cases = vec![
+        ("coin", "1d{Heads,Tails}"),
+        ("3coin", "3d{Heads,Tails}"),
+        ("3coins", "3d{Heads,Tails}"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // Faces keep their case and spacing is trimmed
+    let dice = &parser::parse_dice_string("2d{Yes, No ,Maybe}").unwrap()[0];
+    assert_eq!(dice.count, 2);
+    assert_eq!(dice.sides, 3);
+    assert_eq!(
+        dice.faces.as_deref(),
+        Some(&["Yes".to_string(), "No".to_string(), "Maybe".to_string()][..])
+    );
+
+    // Each rolled face number names the face shown
+    let faces = ["yes", "no", "maybe"];
+    for _ in 0..50 {
+        let result = &parse_and_roll("5d{yes,no,maybe} ! Omens").unwrap()[0];
+        let labels = result.face_labels.as_ref().expect("face labels");
+        assert_eq!(labels.len(), 5);
+        for (&roll, label) in result.individual_rolls.iter().zip(labels) {
+            assert_eq!(faces[roll as usize - 1], label);
+        }
+        assert!(result.to_string().contains("Reason: `Omens`"));
+    }
+
+    let coin = &parse_and_roll("coin").unwrap()[0];
+    let shown = coin.to_string();
+    assert!(
+        shown == "Roll: `[Heads]` = **Heads**" || shown == "Roll: `[Tails]` = **Tails**",
+        "{shown}"
+    );
+
+    // Several dice are counted per face, in face order
+    let result = RollResultBuilder::new()
+        .with(|r| {
+            r.individual_rolls = vec![2, 1, 2];
+            r.kept_rolls = vec![2, 1, 2];
+            r.face_labels = Some(vec![
+                "Tails".to_string(),
+                "Heads".to_string(),
+                "Tails".to_string(),
+            ]);
+        })
+        .build();
+    assert_eq!(
+        result.to_string(),
+        "Roll: `[Tails, Heads, Tails]` = **Heads** ×1, **Tails** ×2"
+    );
+
+    // Roll sets show each flip without a numeric total
+    let results = roll("3 coin");
+    assert_eq!(results.len(), 3);
+    assert!(!format_multiple_results(&results).contains("Total"));
+
+    assert_valid("p (Door) d{open,locked}");
+    assert_valid("coin ; 2d{hit,miss}");
+    assert_invalid("0d{a,b}");
+    assert_invalid("101d{a,b}");
+    assert_invalid("d{only}");
+    assert_invalid("d{a,,b}");
+    assert_invalid("2d{a,b} + 3");
+    assert_invalid(&format!("d{{a,{}}}", "x".repeat(51)));
+}
+
+#[test]
+fn test_gurps_success_rolls() {
+    let alias_cases = vec![
+        ("gurps 12", "3d6 gurps12"),
+        ("gurps12", "3d6 gurps12"),
+        ("gurps 14 -2", "3d6 gurps12"),
+        ("gurps 10+1", "3d6 gurps11"),
+        ("gurps 2 -5", "3d6 gurps-3"),
+    ];
+    for (alias, expected) in alias_cases {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // 3-4 always crit (5 at 15+, 6 at 16+); 18 always fumbles, 17 at 15 or
+    // less, and so does any roll 10 or more over the skill
+    for skill in [3, 8, 12, 15, 16, 18] {
+        for _ in 0..200 {
+            let result = &parse_and_roll(&format!("gurps {skill}")).unwrap()[0];
+            let roll = result.total;
+            assert_eq!(result.kept_rolls.len(), 3);
+            assert!((3..=18).contains(&roll));
+            let crit_success =
+                roll <= 4 || (roll == 5 && skill >= 15) || (roll == 6 && skill >= 16);
+            let crit_failure = roll == 18 || (roll == 17 && skill <= 15) || roll >= skill + 10;
+            let expected = if crit_success {
+                "CRITICAL SUCCESS"
+            } else if roll <= skill && roll < 17 {
+                "SUCCESS"
+            } else if crit_failure {
+                "CRITICAL FAILURE"
+            } else {
+                "FAILURE"
+            };
+            let margin = if roll <= skill {
+                format!("made it by {}", skill - roll)
+            } else {
+                format!("missed by {}", roll - skill)
+            };
+            assert_eq!(
+                result.notes,
+                vec![format!(
+                    "**{expected}** (rolled {roll} vs skill {skill}, {margin})"
+                )],
+                "skill {skill}"
+            );
+        }
+    }
+
+    assert_invalid("gurps 2 -5");
+    assert_invalid("gurps 31");
+    assert_invalid("2d6 gurps12");
+    assert_invalid("3d6 gurps12 e6");
+}
+
+#[test]
+fn test_dcc_dice_chain() {
+    for (alias, expected) in [
+        ("dcc", "1d20 dcc"),
+        ("dcc +1", "1d24 dcc"),
+        ("dcc +2", "1d30 dcc"),
+        ("dcc -1d", "1d16 dcc"),
+        ("dcc d16", "1d16 dcc"),
+        ("dcc d7 +1", "1d8 dcc"),
+        ("dcc d8 -2", "1d6 dcc"),
+        ("dcc +1 +3", "1d24 dcc +3"),
+        ("dcc +0 -2", "1d20 dcc -2"),
+        ("dcc 2d14", "2d14 dcc"),
+    ] {
+        assert_eq!(
+            aliases::expand_alias(alias),
+            Some(expected.to_string()),
+            "'{}'",
+            alias
+        );
+    }
+
+    // Off the chain, or a die that isn't on it
+    for alias in ["dcc +3", "dcc d3 -1", "dcc d9", "dcc d100 +1", "1d9 dcc"] {
+        assert_invalid(alias);
+    }
+
+    // Funky dice roll in range
+    for sides in [3, 5, 7, 14, 16, 24, 30] {
+        for seed in 0..50 {
+            let result =
+                &parse_and_roll_with_rng(&format!("1d{sides} dcc"), &mut rng::seeded_rng(seed))
+                    .unwrap()[0];
+            assert!(
+                (1..=sides).contains(&result.total),
+                "d{sides}: {}",
+                result.total
+            );
+        }
+    }
+
+    // A natural 1 is a fumble, or a failed spell check
+    let fumbles = (0..200)
+        .map(|seed| parse_and_roll_with_rng("dcc d3", &mut rng::seeded_rng(seed)).unwrap())
+        .filter(|results| results[0].individual_rolls == [1])
+        .inspect(|results| {
+            assert!(results[0].notes.iter().any(|note| note.contains("FUMBLE")));
+            assert!(
+                results[0]
+                    .notes
+                    .iter()
+                    .any(|note| note.contains("spellburn"))
+            );
+        })
+        .count();
+    assert!(fumbles > 0);
+    let result = &parse_and_roll_with_rng("dcc +1 +5", &mut rng::seeded_rng(1)).unwrap()[0];
+    assert_eq!(result.total, result.individual_rolls[0] + 5);
+    if result.individual_rolls[0] != 1 {
+        assert!(result.notes.is_empty());
+    }
+}
@@ -6,30 +6,24 @@
 // - Core dice modifier behavior (exploding, keep/drop, rerolls)
 // - Error handling and input validation
 
-use dicemaiden_rs::dice::{Modifier, parse_and_roll, parser};
-
-// ============================================================================
-// HELPER FUNCTIONS
-// ============================================================================
-
-/// Test helper: Assert that a dice expression parses and rolls successfully
-fn assert_valid(input: &str) {
-    let result = parse_and_roll(input);
-    assert!(
-        result.is_ok(),
-        "Failed to parse: '{}' - Error: {:?}",
-        input,
-        result.err()
-    );
-    let results = result.unwrap();
-    assert!(!results.is_empty(), "No results for: '{}'", input);
-}
-
-/// Test helper: Assert that a dice expression fails to parse
-fn assert_invalid(input: &str) {
-    let result = parse_and_roll(input);
-    assert!(result.is_err(), "Expected error for: '{}'", input);
-}
+// Older tests predate these lints and are kept as written
+#![allow(
+    clippy::len_zero,
+    clippy::manual_range_contains,
+    clippy::unnecessary_cast,
+    clippy::unnecessary_unwrap
+)]
+
+use dicemaiden_rs::commands::{calc, odds};
+use dicemaiden_rs::dice::{
+    DiceError, Modifier, aliases, format_reveal_frames, parse_and_roll, parse_and_roll_with_rng,
+    parser, probability, rng,
+};
+use dicemaiden_rs::i18n::Language;
+use dicemaiden_rs::testkit::{
+    DiceRollBuilder, RollResultBuilder, assert_formatted, assert_invalid, assert_not_formatted,
+    assert_valid,
+};
 
 // ============================================================================
 // BASIC DICE TESTS
@@ -48,15 +42,16 @@ fn test_basic_dice_parsing() {
         ("1d%", true, 1, 100), // Percentile
         ("d%", true, 1, 100),
         // Boundary conditions
-        ("500d1000", true, 500, 1000), // Max allowed
-        ("1d1", true, 1, 1),           // Min sides
+        ("500d1000", true, 500, 1000),     // Max allowed
+        ("1d1", true, 1, 1),               // Min sides
+        ("1d1000000", true, 1, 1_000_000), // Max sides (lottery-style)
         // Invalid cases
-        ("501d6", false, 0, 0),  // Too many dice
-        ("1d1001", false, 0, 0), // Too many sides
-        ("0d6", false, 0, 0),    // Zero dice
-        ("1d0", false, 0, 0),    // Zero sides
-        ("-1d6", false, 0, 0),   // Negative dice count
-        ("1d-6", false, 0, 0),   // Negative sides
+        ("501d6", false, 0, 0),     // Too many dice
+        ("1d1000001", false, 0, 0), // Too many sides
+        ("0d6", false, 0, 0),       // Zero dice
+        ("1d0", false, 0, 0),       // Zero sides
+        ("-1d6", false, 0, 0),      // Negative dice count
+        ("1d-6", false, 0, 0),      // Negative sides
     ];
 
     for (input, should_succeed, expected_count, expected_sides) in test_cases {
@@ -87,6 +82,38 @@ fn test_basic_dice_parsing() {
     }
 }
 
+#[test]
+fn test_large_dice() {
+    // Rolls of a d1000000 spread over the whole range
+    let mut rng = rng::session_rng(&[42; 32], 1);
+    let rolls: Vec<i32> = (0..2000)
+        .map(|_| parse_and_roll_with_rng("1d1000000", &mut rng).unwrap()[0].total)
+        .collect();
+    assert!(rolls.iter().all(|roll| (1..=1_000_000).contains(roll)));
+    let mean = rolls.iter().map(|&roll| f64::from(roll)).sum::<f64>() / rolls.len() as f64;
+    assert!((450_000.0..550_000.0).contains(&mean), "Mean was {mean}");
+    assert!(rolls.iter().any(|&roll| roll < 100_000));
+    assert!(rolls.iter().any(|&roll| roll > 900_000));
+
+    // (expression, should_succeed, description)
+    let test_cases = vec![
+        ("500d1000000", true, "Largest pool fits an i32"),
+        ("1d1000000 * 5000 / 100", true, "Big steps within the math"),
+        ("1d6 + 1d1000000", true, "Added dice may be large too"),
+        ("1d1000000 * 1000000", false, "Result too large"),
+        ("1d6 + 1d1000001", false, "Added dice over the limit"),
+        ("1d6 + 1d0", false, "Added dice with no sides"),
+        ("1d99999999999", false, "Sides beyond u32"),
+    ];
+    for (expression, should_succeed, description) in test_cases {
+        assert_eq!(
+            parse_and_roll(expression).is_ok(),
+            should_succeed,
+            "{description}: '{expression}'"
+        );
+    }
+}
+
 #[test]
 fn test_whitespace_handling() {
     // Test various whitespace patterns
@@ -452,6 +479,174 @@ fn test_exploding_dice() {
     }
 }
 
+#[test]
+fn test_exploding_total() {
+    let result = parser::parse_dice_string("2d6te8 + 3").unwrap();
+    assert!(matches!(result[0].modifiers[0], Modifier::ExplodeTotal(8)));
+
+    // Each extra round of 2 dice follows a round that totalled at least 8,
+    // and the last round is below it (ul keeps the dice in rolled order)
+    for _ in 0..100 {
+        let result = &parse_and_roll("ul 2d6 te8").unwrap()[0];
+        let rounds: Vec<i32> = result
+            .individual_rolls
+            .chunks(2)
+            .map(|round| round.iter().sum())
+            .collect();
+        let (last, earlier) = rounds.split_last().unwrap();
+        assert!(earlier.iter().all(|&total| total >= 8), "{rounds:?}");
+        assert!(*last < 8, "{rounds:?}");
+        assert_eq!(result.total, rounds.iter().sum::<i32>());
+        assert_eq!(
+            result.notes.iter().any(|n| n.starts_with("Total exploded")),
+            rounds.len() > 1
+        );
+    }
+
+    // 1d6 te2 explodes on anything but a 1, so it is capped
+    let capped = (0..200)
+        .map(|_| parse_and_roll("1d6 te2").unwrap().remove(0))
+        .map(|result| result.individual_rolls.len())
+        .max()
+        .unwrap();
+    assert!(capped <= 21);
+
+    assert_invalid("2d6 te2"); // Always explodes
+    assert_invalid("2d6 te0");
+}
+
+#[test]
+fn test_compound_and_penetrating_explosions() {
+    let result = parser::parse_dice_string("3d6 ke5 kp").unwrap();
+    assert!(matches!(
+        result[0].modifiers[0],
+        Modifier::Compound(Some(5))
+    ));
+    assert!(matches!(result[0].modifiers[1], Modifier::Penetrate(None)));
+
+    for _ in 0..100 {
+        // Compounded dice stay one value each: a run of 6s plus a non-6
+        let result = &parse_and_roll("ul 3d6 ke").unwrap()[0];
+        assert_eq!(result.individual_rolls.len(), 3);
+        assert!(result.individual_rolls.iter().all(|&die| die % 6 != 0));
+        assert_eq!(
+            result.notes.iter().any(|n| n.starts_with("Compounded")),
+            result.individual_rolls.iter().any(|&die| die > 6)
+        );
+
+        // Penetrating dice are added after the originals, each one lower
+        let result = &parse_and_roll("ul 2d6 kp").unwrap()[0];
+        let (originals, extras) = result.individual_rolls.split_at(2);
+        assert!(extras.iter().all(|&die| (0..=5).contains(&die)));
+        assert!(extras.len() >= originals.iter().filter(|&&die| die == 6).count());
+        assert_eq!(result.total, result.individual_rolls.iter().sum::<i32>());
+    }
+
+    assert_invalid("3d6 ke1"); // Always explodes
+    assert_invalid("3d6 kp0");
+    assert_invalid("1d1 ke");
+}
+
+#[test]
+fn test_outcome_bands() {
+    let result = parser::parse_dice_string("2d6+1 bands[6,9]").unwrap();
+    assert!(
+        matches!(&result[0].modifiers[..], [Modifier::Add(1), Modifier::Bands(b)] if b == &[6, 9])
+    );
+
+    // d1 dice make the totals fixed; a bare `bands` is PbtA's 6-/7-9/10+
+    let test_cases = vec![
+        ("6d1 bands", "FAILURE"),
+        ("7d1 bands", "PARTIAL SUCCESS"),
+        ("9d1 bands", "PARTIAL SUCCESS"),
+        ("5d1 + 5 bands", "SUCCESS"),
+        ("5d1 bands[5,9]", "FAILURE"),
+        ("3d1 bands[2]", "SUCCESS"),
+        ("1d1 - 3 bands[0,1]", "FAILURE"),
+        ("20d1 bands[5,10,15]", "CRITICAL SUCCESS"),
+        ("4d1 t1 bands[1,3]", "SUCCESS"), // Successes are classified when counted
+    ];
+    for (expression, expected) in test_cases {
+        let result = &parse_and_roll(expression).unwrap()[0];
+        assert_eq!(
+            result.fitd_outcome.as_deref(),
+            Some(expected),
+            "'{expression}'"
+        );
+    }
+    assert_formatted(
+        &parse_and_roll("7d1 bands").unwrap()[0],
+        &["**7** — **PARTIAL SUCCESS**"],
+    );
+
+    assert_invalid("2d6 bands[9,6]");
+    assert_invalid("2d6 bands[1,2,3,4]");
+    assert_invalid("2d6 bands[]");
+    assert_invalid("3d6 fitd bands");
+    assert_valid("2d6 b1"); // Botch is unaffected
+}
+
+#[test]
+fn test_target_ladders() {
+    // Tiers can be written in any order and are kept highest first, with or
+    // without a space before them
+    for expression in ["1d100 t[90,70,50]", "1d100t[50,90,70]"] {
+        let result = parser::parse_dice_string(expression).unwrap();
+        assert!(
+            matches!(&result[0].modifiers[..], [Modifier::TargetLadder(t)] if t == &[90, 70, 50]),
+            "'{expression}'"
+        );
+    }
+
+    // d1 dice make the totals fixed; the ladder reads the total after math
+    let test_cases = vec![
+        ("49d1 t[90,70,50]", "FAILURE"),
+        ("50d1 t[90,70,50]", "SUCCESS"),
+        ("70d1 t[90,70,50]", "SPECIAL SUCCESS"),
+        ("40d1 * 2 + 10 t[90,70,50]", "CRITICAL SUCCESS"),
+        ("100d1 / 2 t[90,70,50]", "SUCCESS"),
+        ("15d1 t[20,15]", "SUCCESS"),
+        ("30d1 t[30,20,10,5]", "CRITICAL SUCCESS"),
+        ("25d1 t[30,20,10,5]", "EXTREME SUCCESS"),
+    ];
+    for (expression, expected) in test_cases {
+        let result = &parse_and_roll(expression).unwrap()[0];
+        assert_eq!(
+            result.fitd_outcome.as_deref(),
+            Some(expected),
+            "'{expression}'"
+        );
+    }
+    let result = &parse_and_roll("75d1 t[90,70,50]").unwrap()[0];
+    assert_formatted(result, &["**75** — **SPECIAL SUCCESS**"]);
+    assert_eq!(
+        result.notes,
+        vec!["Ladder: CRITICAL SUCCESS 90+ · SPECIAL SUCCESS 70+ · SUCCESS 50+"]
+    );
+
+    assert_valid("2d6 / 2"); // Division still divides
+    // A target followed by `/` still counts successes and then divides
+    for (expression, target, divisor) in [("1d20 t7/2", 7, 2), ("10d10t50/2", 50, 2)] {
+        let result = parser::parse_dice_string(expression).unwrap();
+        assert!(
+            matches!(
+                &result[0].modifiers[..],
+                [Modifier::Target(t), Modifier::Divide(d)] if *t == target && *d == divisor
+            ),
+            "'{expression}': {:?}",
+            result[0].modifiers
+        );
+    }
+    assert_valid("100d6 t90/70/50");
+    assert_invalid("1d100 t[90,70,70]");
+    assert_invalid("1d100 t[90,0]");
+    assert_invalid("1d100 t[90]");
+    assert_invalid("1d100 t[]");
+    assert_invalid("1d100 t[90,70,50,30,10]");
+    assert_invalid("8d10 t8 t[90,50]");
+    assert_invalid("2d6 bands t[10,8]");
+}
+
 #[test]
 fn test_keep_drop_modifiers() {
     let keep_drop_patterns = vec![
@@ -488,6 +683,371 @@ fn test_reroll_modifiers() {
     }
 }
 
+#[test]
+fn test_reroll_failed_modifier() {
+    // Table-driven: rfail parses spaced or combined, before or after the target
+    let test_cases = vec![
+        ("8d10 t8 rfail", true, "Spaced after target"),
+        ("8d10 t8rfail", true, "Combined with target"),
+        ("8d10 rfail t8", true, "Before target"),
+        ("6d10 tl3 rfail", true, "Roll-under target"),
+        ("4d10 t8ds10 rfail", true, "Double successes"),
+        ("8d10 t8 ie10 rfail f1", true, "With explode and failures"),
+        ("8d10 t8 rfail ! willpower", true, "With comment"),
+        ("3 6d10 t7 rfail", true, "Roll sets"),
+        ("4d6 rfail", false, "No target to fail"),
+        ("8d10 t8 rfail2", false, "No value allowed"),
+    ];
+
+    for (expression, valid, description) in test_cases {
+        assert_eq!(
+            parse_and_roll(expression).is_ok(),
+            valid,
+            "{}: '{}'",
+            description,
+            expression
+        );
+    }
+
+    let parsed = parser::parse_dice_string("8d10 t8rfail").unwrap();
+    assert!(
+        parsed[0]
+            .modifiers
+            .iter()
+            .any(|m| matches!(m, Modifier::RerollFailed))
+    );
+
+    // Every die fails and is rerolled exactly once, showing old and new dice
+    let result = &parse_and_roll("4d1 t2 rfail").unwrap()[0];
+    assert_eq!(result.successes, Some(0));
+    assert_eq!(
+        result.notes,
+        vec!["Rerolled 4 failed dice: [1, 1, 1, 1] → [1, 1, 1, 1]"]
+    );
+
+    // Nothing to reroll when every die succeeds
+    let result = &parse_and_roll("4d1 t1 rfail").unwrap()[0];
+    assert_eq!(result.successes, Some(4));
+    assert!(result.notes.is_empty());
+
+    // Successes are counted on the rerolled dice
+    for _ in 0..50 {
+        let result = &parse_and_roll("10d10 t8 rfail").unwrap()[0];
+        let successes = result.kept_rolls.iter().filter(|&&roll| roll >= 8).count() as i32;
+        assert_eq!(result.successes, Some(successes));
+    }
+}
+
+#[test]
+fn test_success_cap_modifier() {
+    // Table-driven: (expression, successes, note) with d1s so every die succeeds
+    let test_cases = vec![
+        (
+            "10d1 t1 maxs3",
+            3,
+            Some("Successes capped at 3 (7 overflow)"),
+        ),
+        ("10d1t1maxs3", 3, Some("Successes capped at 3 (7 overflow)")),
+        (
+            "10d1 maxs3 t1",
+            3,
+            Some("Successes capped at 3 (7 overflow)"),
+        ),
+        (
+            "10d1 t1ds1 maxs5",
+            5,
+            Some("Successes capped at 5 (15 overflow)"),
+        ),
+        (
+            "10d1 tl1 maxs4 ! soak",
+            4,
+            Some("Successes capped at 4 (6 overflow)"),
+        ),
+        ("3d1 t1 maxs5", 3, None),     // Under the cap
+        ("10d1 t1 f1 maxs3", 0, None), // Failures cancel first
+        ("3d1 t1 maxs3", 3, None),     // Exactly at the cap
+    ];
+
+    for (expression, successes, note) in test_cases {
+        let result = &parse_and_roll(expression).unwrap()[0];
+        assert_eq!(result.successes, Some(successes), "'{}'", expression);
+        let cap_note = result
+            .notes
+            .iter()
+            .find(|n| n.starts_with("Successes capped"));
+        assert_eq!(cap_note.map(String::as_str), note, "'{}'", expression);
+    }
+
+    // Roll sets cap each set
+    let results = parse_and_roll("3 10d1 t1 maxs2").unwrap();
+    assert!(results.iter().all(|r| r.successes == Some(2)));
+
+    assert_valid("4cod maxs2");
+    assert_invalid("4d6 maxs2"); // Nothing to cap
+    assert_invalid("10d10 t7 maxs0");
+    assert_invalid("10d10 t7 maxs");
+}
+
+#[test]
+fn test_math_only_expressions() {
+    // Table-driven: (expression, total), evaluated left to right like dice math
+    let test_cases = vec![
+        ("4*7+2", 30),
+        ("4 * 7 + 2", 30),
+        ("5", 5),
+        ("5 - 8", -3),
+        ("2+3*4", 20),
+        ("10/3", 3),
+        ("(Gold) 120/4 ! split four ways", 30),
+    ];
+
+    for (expression, total) in test_cases {
+        let results = parse_and_roll(expression).unwrap();
+        assert_eq!(results[0].total, total, "'{}'", expression);
+        assert!(results[0].individual_rolls.is_empty(), "'{}'", expression);
+        assert_not_formatted(&results[0], &["Roll:", "`[]`"]);
+        assert_formatted(&results[0], &[&format!("**{total}**")]);
+    }
+    assert!(parser::is_math_only("4*7+2"));
+    assert!(!parser::is_math_only("4*7+1d2"));
+
+    // Dice expressions that start with a number are unchanged
+    let results = parse_and_roll("4 + 1d1").unwrap();
+    assert_eq!(
+        (results[0].total, results[0].individual_rolls.len()),
+        (5, 1)
+    );
+
+    assert_invalid("10/0");
+    assert_invalid("99999*99999");
+    assert_invalid("99999999999");
+    assert_invalid("4*");
+
+    // /calc refuses dice
+    assert_eq!(calc::calculate("4*7+2").unwrap().total, 30);
+    assert!(calc::calculate("2d6 + 1").is_err());
+    assert!(calc::calculate("1 ; 2").is_err());
+}
+
+#[test]
+fn test_odds_probability() {
+    // Table-driven exact distributions: (expression, mean, min, max, target, P(>= target))
+    let test_cases = vec![
+        ("3d6", 10.5, 3, 18, 15, 20.0 / 216.0),
+        ("1d20+5", 15.5, 6, 25, 20, 6.0 / 20.0),
+        ("4d6 k3", 12.2446, 3, 18, 18, 21.0 / 1296.0),
+        ("2d20 kl1", 7.175, 1, 20, 20, 1.0 / 400.0),
+        ("4d6 d1", 12.2446, 3, 18, 3, 1.0),
+        ("2d6*2", 14.0, 4, 24, 24, 1.0 / 36.0),
+        ("1d6+1d4", 6.0, 2, 10, 10, 1.0 / 24.0),
+        ("5d10 t7", 2.0, 0, 5, 5, 0.4f64.powi(5)),
+        ("3d6 tl2", 1.0, 0, 3, 1, 1.0 - (2.0f64 / 3.0).powi(3)),
+    ];
+
+    for (expression, mean, min, max, target, chance) in test_cases {
+        let distribution = probability::distribution(expression).unwrap();
+        assert!(distribution.is_exact(), "'{}'", expression);
+        assert!(
+            (distribution.mean() - mean).abs() < 0.001,
+            "'{}'",
+            expression
+        );
+        assert_eq!(
+            (distribution.min(), distribution.max()),
+            (min, max),
+            "'{}'",
+            expression
+        );
+        let total: f64 = distribution.outcomes.values().sum();
+        assert!((total - 1.0).abs() < 1e-9, "'{}'", expression);
+        assert!(
+            (distribution.chance_at_least(target) - chance).abs() < 1e-9,
+            "'{}'",
+            expression
+        );
+    }
+    let successes = probability::distribution("5d10 t7").unwrap();
+    assert!(successes.successes);
+    assert!((successes.chance_at_most(0) - 0.6f64.powi(5)).abs() < 1e-9);
+
+    // Exploding dice and game systems are simulated with the real roller
+    let mut rng = rng::session_rng(&[42; 32], 1);
+    let dice = probability::parse_single_roll("3d6 e6").unwrap();
+    assert!(probability::exact_distribution(&dice).is_none());
+    let simulated = probability::simulate(&dice, 5_000, &mut rng).unwrap();
+    assert_eq!(simulated.samples, Some(5_000));
+    assert!(
+        (simulated.mean() - 12.6).abs() < 0.5,
+        "{}",
+        simulated.mean()
+    );
+    assert!(simulated.min() >= 3);
+    let cod = probability::distribution("4cod").unwrap();
+    assert!(!cod.is_exact() && cod.successes);
+
+    // Labeled faces aren't numbered 1..=N, so they are simulated too
+    for expression in ["2d{yes,no,maybe}", "3coin"] {
+        let dice = probability::parse_single_roll(expression).unwrap();
+        assert!(
+            probability::exact_distribution(&dice).is_none(),
+            "'{expression}'"
+        );
+    }
+
+    // Huge pools fall back to simulation rather than enumerating
+    let dice = probability::parse_single_roll("100d100 k50").unwrap();
+    assert!(probability::exact_distribution(&dice).is_none());
+
+    // One roll with dice at a time
+    assert!(probability::distribution("4*7+2").is_err());
+    assert!(probability::distribution("6 4d6").is_err());
+    assert!(probability::distribution("1d6 ; 1d8").is_err());
+    assert!(probability::distribution("2d6 +").is_err());
+
+    // The histogram has a line per value, or per range of values
+    let histogram = probability::format_histogram(&probability::distribution("2d6").unwrap(), 20);
+    assert_eq!(histogram.lines().count(), 11);
+    assert!(histogram.starts_with(" 2 │█"), "{histogram}");
+    assert!(
+        histogram.contains(" 7 │████████████████████ 16.67%"),
+        "{histogram}"
+    );
+    let histogram = probability::format_histogram(&probability::distribution("10d6").unwrap(), 20);
+    assert!(histogram.lines().count() <= 20, "{histogram}");
+    assert!(histogram.starts_with("10-12 │"), "{histogram}");
+    assert!(histogram.contains("<0.01%"), "{histogram}");
+
+    let output = odds::format_odds(&probability::distribution("3d6").unwrap(), Some(15));
+    assert!(output.starts_with("(exact)\nMean **10.50** · Min **3** · Max **18**"));
+    assert!(
+        output.contains("15+: **9.26%** · 15 or less: **95.37%**"),
+        "{output}"
+    );
+    assert!(output.contains("```\n 3 │"), "{output}");
+    assert!(
+        output.contains("Percentiles: 10th **7** · 50th **10** · 90th **14**"),
+        "{output}"
+    );
+
+    // The chart has a column per value, with the percentiles marked below
+    let chart = probability::format_chart(&probability::distribution("2d6").unwrap(), 40);
+    let lines: Vec<&str> = chart.lines().collect();
+    assert_eq!(lines[0], "▁▃▄▅▇█▇▅▄▃▁", "{chart}");
+    assert_eq!(lines[1], "  ^  ^  ^", "{chart}");
+    assert_eq!(lines[2], "2        12", "{chart}");
+    let chart = probability::format_chart(&probability::distribution("10d6").unwrap(), 40);
+    assert!(
+        chart.lines().next().unwrap().chars().count() <= 40,
+        "{chart}"
+    );
+
+    // Long replies merge more values per line until they fit
+    let distribution = probability::distribution("20d20").unwrap();
+    let full = odds::format_odds(&distribution, None);
+    let short = odds::format_odds_within(&distribution, None, 600);
+    assert!(full.chars().count() > 600);
+    assert!(short.chars().count() <= 600, "{short}");
+    assert!(short.lines().count() < full.lines().count());
+    let output = odds::format_odds(&simulated, None);
+    assert!(
+        output.starts_with("(simulated from 5000 rolls)"),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_language_flag() {
+    // Table-driven: (expression, expected language)
+    let test_cases = vec![
+        ("2d6", Language::English),
+        ("en 2d6", Language::English),
+        ("fr 2d6", Language::French),
+        ("p de 4d6 t5", Language::German),
+        ("es p 2d6 ! Ataque", Language::Spanish),
+        ("it 3 4d6", Language::Italian),
+        ("pt 4cod", Language::Portuguese),
+        ("fr 4*7", Language::French),
+    ];
+    for (expression, language) in test_cases {
+        let results = parse_and_roll(expression).unwrap();
+        assert!(
+            results.iter().all(|r| r.language == language),
+            "'{}'",
+            expression
+        );
+    }
+
+    // Each roll of a multi-roll has its own language
+    let results = parse_and_roll("fr 2d6 ; de 1d8").unwrap();
+    assert_eq!(results[0].language, Language::French);
+    assert_eq!(results[1].language, Language::German);
+
+    let result = RollResultBuilder::new()
+        .rolls(&[6, 5, 2])
+        .successes(2)
+        .failures(1)
+        .comment("Attaque")
+        .language(Language::French)
+        .build();
+    assert_formatted(
+        &result,
+        &["Jet: ", "**2** succès", "(1 échecs)", "Raison: `Attaque`"],
+    );
+    assert_not_formatted(&result, &["Roll:", "successes", "Reason:"]);
+
+    // Codes are only flags when followed by a space
+    assert_invalid("fr2d6");
+    assert_invalid("xx 2d6");
+    assert_eq!(Language::from_code("de"), Some(Language::German));
+    assert_eq!(Language::French.code(), "fr");
+}
+
+#[test]
+fn test_split_pools() {
+    // Table-driven: (expression, expected pool labels)
+    let test_cases = vec![
+        (
+            "split 8d6 t4 into 3/5",
+            vec!["Pool 1 (3d6)", "Pool 2 (5d6)"],
+        ),
+        (
+            "split 8d6t4 into 3 / 5",
+            vec!["Pool 1 (3d6)", "Pool 2 (5d6)"],
+        ),
+        (
+            "split 9d10 t8 into 3/3/3",
+            vec!["Pool 1 (3d10)", "Pool 2 (3d10)", "Pool 3 (3d10)"],
+        ),
+        ("split 4d6 into 2/2", vec!["Pool 1 (2d6)", "Pool 2 (2d6)"]),
+        (
+            "(Attack) split 6d6 t5 into 1/5",
+            vec!["Attack Pool 1 (1d6)", "Attack Pool 2 (5d6)"],
+        ),
+    ];
+
+    for (expression, labels) in test_cases {
+        let results = parse_and_roll(expression).unwrap();
+        let actual: Vec<&str> = results.iter().filter_map(|r| r.label.as_deref()).collect();
+        assert_eq!(actual, labels, "'{}'", expression);
+    }
+
+    // Each pool counts its own successes
+    let results = parse_and_roll("split 8d1 t1 into 3/5").unwrap();
+    let successes: Vec<Option<i32>> = results.iter().map(|r| r.successes).collect();
+    assert_eq!(successes, vec![Some(3), Some(5)]);
+
+    // Flags apply to every pool; the comment is shown once, on the last
+    let results = parse_and_roll("p split 5d6 t4 into 2/3 ! two swings").unwrap();
+    assert!(results.iter().all(|r| r.private));
+    assert_eq!(results[0].comment, None);
+    assert_eq!(results[1].comment.as_deref(), Some("two swings"));
+
+    assert_invalid("split 8d6 t4 into 3/4"); // Doesn't add up
+    assert_invalid("split 8d6 t4 into 0/8");
+    assert_invalid("split 8d6 t4 into 8");
+    assert_invalid("split 10d6 into 2/2/2/2/2");
+}
+
 #[test]
 fn test_target_system_modifiers() {
     let target_patterns = vec!["6d10t7", "4d6f1", "6d10b", "6d10b1", "6d10tl7", "6d10c"];
@@ -602,6 +1162,42 @@ fn test_input_length_limits() {
     assert!(error_msg.contains("Input too long"));
 }
 
+#[test]
+fn test_dice_error_variants() {
+    let error = |input: &str| parse_and_roll(input).unwrap_err();
+
+    assert!(matches!(error("2d6 xyz"), DiceError::ParseError(_)));
+    assert!(matches!(error("0d6"), DiceError::ValidationError(_)));
+    assert!(matches!(error("1d6 k0"), DiceError::ValidationError(_)));
+    assert!(matches!(error("501d6"), DiceError::RollLimitExceeded(_)));
+    assert!(matches!(
+        error(&("1d6+".repeat(250) + "1")),
+        DiceError::InputTooLong { max: 1000, .. }
+    ));
+
+    // Messages are unchanged by the variants
+    assert_eq!(error("1d6 / 0").to_string(), "Cannot divide by zero");
+    assert_eq!(
+        error("1d6 / 0"),
+        DiceError::DivisionByZero { by_dice: false }
+    );
+    assert_eq!(error("501d6").to_string(), "Maximum 500 dice allowed");
+    assert_eq!(
+        DiceError::DivisionByZero { by_dice: true }.to_string(),
+        "Cannot divide by zero (dice result was 0)"
+    );
+
+    let looped = aliases::expand_user_aliases("a", |name| match name {
+        "a" => Some("b".to_string()),
+        "b" => Some("a".to_string()),
+        _ => None,
+    })
+    .unwrap_err();
+    assert!(matches!(looped, DiceError::AliasError(_)));
+    assert_eq!(looped.kind(), "alias_error");
+    assert_eq!(error("2d6 xyz").kind(), "parse_error");
+}
+
 #[test]
 fn test_comments_and_labels_parsing() {
     // Test comment parsing functionality
@@ -834,6 +1430,43 @@ fn test_special_flags_comprehensive() {
     }
 }
 
+#[test]
+fn test_drama_flag_reveal_frames() {
+    // Test 'drama' flag is parsed and carried through to the result
+    let drama_tests = vec!["drama 2d6", "drama p 1d20", "p drama 4d6 k3", "drama 6 4d6"];
+
+    for test in drama_tests {
+        let result = parse_and_roll(test);
+        assert!(result.is_ok(), "drama flag test '{}' should parse", test);
+        let results = result.unwrap();
+        assert!(
+            results.iter().all(|r| r.drama),
+            "Should have drama flag set for '{}'",
+            test
+        );
+    }
+
+    assert!(!parse_and_roll("2d6").unwrap()[0].drama);
+
+    // A single roll reveals one dice group per frame, holding back the total
+    let results = parse_and_roll("drama 2d6 + 1d4 + 1d8").unwrap();
+    let frames = format_reveal_frames(&results);
+    assert_eq!(frames.len(), 3, "Expected one frame per dice group");
+    for frame in &frames {
+        assert!(frame.ends_with("= …"), "Frame should hide total: {}", frame);
+        assert!(!frame.contains("Reason"), "Frame should hide comment");
+    }
+
+    // Roll sets reveal one set per frame, the final output is not a frame
+    let results = parse_and_roll("drama 3 4d6 ! stats").unwrap();
+    let frames = format_reveal_frames(&results);
+    assert_eq!(frames.len(), 2);
+    assert!(frames[1].contains("Set 2") && !frames[1].contains("Set 3"));
+
+    // Simple output has no dice to reveal
+    assert!(format_reveal_frames(&parse_and_roll("drama s 2d6").unwrap()).is_empty());
+}
+
 #[test]
 fn test_percentile_dice_variants() {
     // Test standalone percentile dice
@@ -1735,6 +2368,76 @@ fn test_complete_display_format_with_keep() {
     println!("Complete format test result: {}", formatted);
 }
 
+#[test]
+fn test_fixed_result_formatting() {
+    // Table-driven: results built with fixed dice, so the exact output is known
+    let test_cases = vec![
+        (
+            RollResultBuilder::new().rolls(&[3, 5]).build(),
+            vec!["Roll: `[3, 5]` = **8**"],
+            vec!["~~"],
+            "Plain roll",
+        ),
+        (
+            RollResultBuilder::new()
+                .rolls(&[6, 4])
+                .dropped(&[1])
+                .build(),
+            vec!["`[6, 4, 1]`", "~~[1]~~", "**10**"],
+            vec![],
+            "Dropped dice are shown and struck through",
+        ),
+        (
+            RollResultBuilder::new()
+                .rolls(&[4, 2])
+                .label("Attack")
+                .comment("sneak")
+                .note("Advantage")
+                .build(),
+            vec!["**Attack**: ", " Reason: `sneak`", "\n*Note: Advantage*"],
+            vec![],
+            "Label, comment and notes",
+        ),
+        (
+            RollResultBuilder::new().rolls(&[4, 2]).simple().build(),
+            vec!["= **6**"],
+            vec!["Roll:"],
+            "Simple output hides the dice",
+        ),
+        (
+            RollResultBuilder::new().rolls(&[4, 2]).no_results().build(),
+            vec!["Roll: `[4, 2]`"],
+            vec!["**6**"],
+            "No-results output hides the total",
+        ),
+        (
+            RollResultBuilder::new()
+                .group("base", &[2, 3], &[])
+                .group("add", &[6], &[1])
+                .total(11)
+                .build(),
+            vec!["`[2, 3]` + `[6]`", "**11**"],
+            vec![],
+            "Dice groups",
+        ),
+    ];
+
+    for (result, expected, unexpected, description) in test_cases {
+        assert_formatted(&result, &expected);
+        assert_not_formatted(&result, &unexpected);
+        println!("{}: {}", description, result);
+    }
+
+    // A built DiceRoll rolls like a parsed one
+    let dice = DiceRollBuilder::new(4, 1)
+        .modifier(Modifier::KeepHigh(2))
+        .label("Built")
+        .build();
+    let result = dicemaiden_rs::roller::roll_dice(dice).unwrap();
+    assert_eq!(result.total, 2);
+    assert_formatted(&result, &["**Built**: ", "~~[1, 1]~~", "**2**"]);
+}
+
 // ============================================================================
 // DOUBLE SUCCESS TESTS
 // ============================================================================
@@ -2551,3 +3254,143 @@ fn test_indefinite_vs_non_indefinite_explosions() {
     // Note: We can't easily test indefinite explosions deterministically due to randomness,
     // but the existing performance tests already cover explosion limits
 }
+
+#[test]
+fn test_reroll_selected_dice() {
+    use dicemaiden_rs::roller;
+
+    let roll = |expression: &str| {
+        parser::parse_dice_string(expression)
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap()
+    };
+
+    // Dice left alone keep their values; the rest are rolled again
+    let mut rng = rng::session_rng(&[42; 32], 1);
+    let result = roller::reroll_selected(roll("4d6+5"), &[6, 5, 2, 1], &[2, 3], &mut rng).unwrap();
+    let dice = roller::selectable_dice(&result);
+    assert!(dice.contains(&6) && dice.contains(&5), "{dice:?}");
+    assert_eq!(result.total, dice.iter().sum::<i32>() + 5);
+    assert_eq!(result.notes.len(), 1);
+    assert!(result.notes[0].starts_with("Rerolled ~~2~~ → "));
+    assert!(result.notes[0].contains(", ~~1~~ → "));
+
+    // Keep and success counting run again over the new dice
+    let result = roller::reroll_selected(roll("3d1 k2 t1"), &[1, 1, 1], &[0], &mut rng).unwrap();
+    assert_eq!(result.successes, Some(2));
+    assert_eq!(roller::selectable_dice(&result), [1, 1, 1]);
+
+    // Only rolls whose sole random draws are their main dice qualify
+    for expression in ["2d6+5", "4d6 k3", "6d10 t7 f1", "1d20 bands[5,10]"] {
+        assert!(
+            roller::supports_selected_reroll(&roll(expression)),
+            "{expression}"
+        );
+    }
+    for expression in ["2d6 e6", "2d6 r1", "1d20+1d4", "4df", "2d{yes,no}", "sw8"] {
+        let dice = parser::parse_dice_string(expression).unwrap();
+        assert!(
+            dice.iter().all(|d| !roller::supports_selected_reroll(d)),
+            "{expression}"
+        );
+    }
+
+    // Dice that aren't the roll's are refused
+    assert!(roller::reroll_selected(roll("2d6"), &[1, 2], &[2], &mut rng).is_err());
+    assert!(roller::reroll_selected(roll("2d6"), &[1, 2, 3], &[0], &mut rng).is_err());
+    assert!(roller::reroll_selected(roll("2d6 e6"), &[1, 2], &[0], &mut rng).is_err());
+}
+
+#[test]
+fn test_mixed_die_pools() {
+    use dicemaiden_rs::roller;
+
+    // One pool of mixed sizes, with the largest die as its `sides`
+    let dice = &parser::parse_dice_string("{d8+d6+d4} k2 + 1").unwrap()[0];
+    assert_eq!((dice.count, dice.sides), (3, 8));
+    assert_eq!(dice.pool.as_deref(), Some(&[8, 6, 4][..]));
+    assert_eq!(roller::die_sizes(dice), [8, 6, 4]);
+    assert!(matches!(dice.modifiers[0], Modifier::KeepHigh(2)));
+    let dice = &parser::parse_dice_string("{ 2d10 + d4 }").unwrap()[0];
+    assert_eq!(dice.pool.as_deref(), Some(&[10, 10, 4][..]));
+    // A pool of one size is plain NdS
+    let dice = &parser::parse_dice_string("{d6+2d6} e6").unwrap()[0];
+    assert_eq!((dice.count, dice.sides, dice.pool.as_ref()), (3, 6, None));
+
+    for seed in 0..200 {
+        let result =
+            &parse_and_roll_with_rng("{d8+d6+d4} k2 + 1", &mut rng::seeded_rng(seed)).unwrap()[0];
+        assert_eq!(result.kept_rolls.len(), 2, "seed {seed}");
+        assert_eq!(result.dropped_rolls.len(), 1, "seed {seed}");
+        assert_eq!(result.total, result.kept_rolls.iter().sum::<i32>() + 1);
+        assert!(
+            result
+                .kept_rolls
+                .iter()
+                .all(|&roll| (1..=8).contains(&roll))
+        );
+
+        // Each die stays within its own size
+        let result = &parse_and_roll_with_rng("{d1+d2}", &mut rng::seeded_rng(seed)).unwrap()[0];
+        assert!(result.individual_rolls.contains(&1));
+        assert!((2..=3).contains(&result.total), "seed {seed}");
+    }
+    let result = &parse_and_roll("{d1+3d2} t2").unwrap()[0];
+    assert!(result.successes.unwrap() <= 3);
+
+    // Modifiers that need each die's size are refused
+    assert_valid("{d12+d10+d8} kl1 t6 ! Pool");
+    assert_valid("p {d8+d6} + 1d4");
+    assert_invalid("{d8+d6} e");
+    assert_invalid("{d8+d6} r1");
+    assert_invalid("{d8+d6} wng");
+    assert_invalid("{d8+0d6}");
+    assert_invalid("{300d6+300d8}");
+
+    // Odds of mixed pools are simulated
+    let dice = probability::parse_single_roll("{d8+d4} k1").unwrap();
+    assert!(probability::exact_distribution(&dice).is_none());
+    assert!(!roller::supports_selected_reroll(&dice));
+}
+
+#[test]
+fn test_parse_cache() {
+    use dicemaiden_rs::dice::cache::ParseCache;
+
+    let cache = ParseCache::new(2);
+    let fresh = parser::parse_dice_string("1d20+5").unwrap();
+    let cached = cache.parse(" 1d20+5 ").unwrap();
+    assert_eq!(format!("{fresh:?}"), format!("{cached:?}"));
+    assert!(cache.contains("1d20+5"));
+    // A hit returns the same parse
+    assert_eq!(
+        format!("{:?}", cache.parse("1d20+5").unwrap()),
+        format!("{fresh:?}")
+    );
+
+    // Failed parses aren't kept
+    assert!(cache.parse("not dice").is_err());
+    assert_eq!(cache.len(), 1);
+
+    // The least recently used expression is evicted
+    cache.parse("4cod").unwrap();
+    cache.parse("1d20+5").unwrap();
+    cache.parse("3 4d6 k3").unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains("1d20+5"));
+    assert!(cache.contains("3 4d6 k3"));
+    assert!(!cache.contains("4cod"));
+    cache.parse("3 4d6 k3").unwrap();
+    cache.parse("2d6").unwrap();
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains("3 4d6 k3"));
+    assert!(!cache.contains("1d20+5"));
+
+    // Cached rolls still roll fresh dice
+    let totals: std::collections::HashSet<i32> = (0..50)
+        .map(|_| parse_and_roll("10d100").unwrap()[0].total)
+        .collect();
+    assert!(totals.len() > 1);
+}